@@ -13,7 +13,7 @@ use casper_node::{
     rpcs::{
         account::{PutDeploy, PutDeployParams},
         chain::{
-            BlockIdentifier, GetBlock, GetBlockParams, GetBlockTransfers, GetBlockTransfersParams,
+            GetBlock, GetBlockParams, GetBlockTransfers, GetBlockTransfersParams,
             GetEraInfoBySwitchBlock, GetEraInfoParams, GetStateRootHash, GetStateRootHashParams,
         },
         docs::ListRpcs,
@@ -21,7 +21,7 @@ use casper_node::{
         state::{GetAuctionInfo, GetBalance, GetBalanceParams, GetItem, GetItemParams},
         RpcWithOptionalParams, RpcWithParams, RpcWithoutParams, RPC_API_PATH,
     },
-    types::{BlockHash, Deploy, DeployHash},
+    types::{BlockHash, BlockIdentifier, Deploy, DeployHash},
 };
 use casper_types::{AsymmetricType, Key, PublicKey, URef, U512};
 
@@ -267,11 +267,20 @@ impl RpcCall {
             .await
             .map_err(Error::FailedToGetResponse)?;
 
+        // The node responds to a failed request with a non-2xx status code, but still carries the
+        // JSON-RPC error in the body, so only treat the status as a sending failure if the body
+        // isn't a JSON-RPC error.
         if let Err(error) = response.error_for_status_ref() {
             if self.verbosity_level > 0 {
                 println!("Failed Sending {}", error);
             }
-            return Err(Error::FailedSending(rpc_req));
+            return match response.json::<JsonRpc>().await {
+                Ok(rpc_response) => match rpc_response.get_error() {
+                    Some(rpc_error) => Err(Error::ResponseIsError(rpc_error.clone())),
+                    None => Err(Error::FailedSending(rpc_req)),
+                },
+                Err(_) => Err(Error::FailedSending(rpc_req)),
+            };
         }
 
         let rpc_response = response.json().await.map_err(Error::FailedToParseResponse);
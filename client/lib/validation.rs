@@ -9,8 +9,8 @@ use casper_execution_engine::{
 };
 use casper_node::{
     crypto::hash::Digest,
-    rpcs::chain::{BlockIdentifier, EraSummary, GetEraInfoResult},
-    types::{json_compatibility, Block, BlockValidationError, JsonBlock},
+    rpcs::chain::{EraSummary, GetEraInfoResult},
+    types::{json_compatibility, Block, BlockIdentifier, BlockValidationError, JsonBlock},
 };
 use casper_types::{bytesrepr, Key, U512};
 
@@ -250,7 +250,7 @@ pub(crate) fn validate_get_block_response(
         // More is necessary here to mitigate a MITM attack. In this case we would want to validate
         // `block.proofs()` to make sure that 1/3 of the validator weight signed the block, and we
         // would have to know the latest validators through some trustworthy means
-        None => (),
+        Some(BlockIdentifier::Latest) | None => (),
     }
     Ok(())
 }
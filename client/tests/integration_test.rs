@@ -1,7 +1,7 @@
 use std::{convert::Infallible, net::SocketAddr, sync::Arc, time::Duration};
 
 use futures::{channel::oneshot, future};
-use hyper::{Body, Response, Server};
+use hyper::{Body, Response, Server, StatusCode};
 use serde::Deserialize;
 use tempfile::TempDir;
 use tokio::{sync::Mutex, task, task::JoinHandle};
@@ -18,7 +18,7 @@ use casper_node::rpcs::{
     chain::{GetStateRootHash, GetStateRootHashParams},
     info::{GetDeploy, GetDeployParams},
     state::{GetBalance, GetBalanceParams},
-    RpcWithOptionalParams, RpcWithParams,
+    ErrorCode, RpcWithOptionalParams, RpcWithParams,
 };
 
 const VALID_PURSE_UREF: &str =
@@ -49,6 +49,22 @@ fn test_filter_without_params(
         .map(|builder: Builder| builder.success(()).unwrap())
 }
 
+/// Responds to JSON-RPC requests for `method` with a "no such deploy" error carried in a
+/// `404 Not Found` response, as the node does.
+fn test_error_filter(
+    method: &'static str,
+) -> impl Filter<Extract = (Response<Body>,), Error = Rejection> + Copy {
+    warp_json_rpc::filters::json_rpc()
+        .and(warp_json_rpc::filters::method(method))
+        .map(|builder: Builder| {
+            let error =
+                warp_json_rpc::Error::custom(ErrorCode::NoSuchDeploy as i64, "deploy not known");
+            let mut response = builder.error(error).unwrap();
+            *response.status_mut() = StatusCode::NOT_FOUND;
+            response
+        })
+}
+
 type ServerJoiner = Option<Arc<Mutex<JoinHandle<Result<(), hyper::Error>>>>>;
 
 struct MockServerHandle {
@@ -505,6 +521,25 @@ mod get_deploy {
         );
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn should_return_rpc_error_from_non_success_response() {
+        let server_handle = MockServerHandle::spawn_with_filter(
+            test_error_filter(GetDeploy::METHOD),
+            DEFAULT_RATE_LIMIT,
+            DEFAULT_RATE_PER,
+        );
+        let expected_error = jsonrpc_lite::Error {
+            code: ErrorCode::NoSuchDeploy as i64,
+            message: "deploy not known".to_string(),
+            data: None,
+        };
+        assert_eq!(
+            server_handle
+                .get_deploy("09dcee4b212cfd53642ab323fbef07dafafc6f945a80a00147f62910a915c4e6"),
+            Err(Error::ResponseIsError(expected_error).into())
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn should_fail_with_invalid_hash() {
         let server_handle = MockServerHandle::spawn::<GetDeployParams>(GetDeploy::METHOD);
@@ -0,0 +1,149 @@
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use lmdb::DatabaseFlags;
+use tempfile::TempDir;
+
+use casper_execution_engine::{
+    shared::{
+        account::Account,
+        additive_map::AdditiveMap,
+        newtypes::{Blake2bHash, CorrelationId},
+        stored_value::StoredValue,
+        transform::Transform,
+    },
+    storage::{
+        error,
+        global_state::{
+            commit_in_parallel, commit_serially, lmdb::LmdbGlobalState, CommitResult, StateProvider,
+        },
+        protocol_data_store::lmdb::LmdbProtocolDataStore,
+        transaction_source::lmdb::LmdbEnvironment,
+        trie_store::lmdb::LmdbTrieStore,
+    },
+};
+use casper_types::{
+    account::AccountHash, contracts::NamedKeys, AccessRights, CLValue, Key, URef, U512,
+};
+
+const GENESIS_ACCOUNTS: u32 = 10_000;
+const MAP_SIZE: usize = 4 * 1024 * 1024 * 1024;
+const MAX_READERS: u32 = 512;
+
+/// Returns effects resembling those of a genesis with `GENESIS_ACCOUNTS` accounts, each with a
+/// funded main purse.
+fn genesis_effects() -> AdditiveMap<Key, Transform> {
+    let mut effects = AdditiveMap::new();
+    for i in 0..GENESIS_ACCOUNTS {
+        let mut account_bytes = [0; 32];
+        account_bytes[..4].copy_from_slice(&i.to_le_bytes());
+        let mut purse_bytes = [1; 32];
+        purse_bytes[..4].copy_from_slice(&i.to_le_bytes());
+
+        let account_hash = AccountHash::new(account_bytes);
+        let main_purse = URef::new(purse_bytes, AccessRights::READ_ADD_WRITE);
+        let account = Account::create(account_hash, NamedKeys::new(), main_purse);
+
+        effects.insert(
+            Key::Account(account_hash),
+            Transform::Write(StoredValue::Account(account)),
+        );
+        effects.insert(
+            Key::URef(main_purse),
+            Transform::Write(StoredValue::CLValue(CLValue::unit())),
+        );
+        effects.insert(
+            Key::Balance(main_purse.addr()),
+            Transform::Write(StoredValue::CLValue(
+                CLValue::from_t(U512::from(1_000_000_000_u64)).unwrap(),
+            )),
+        );
+    }
+    effects
+}
+
+fn empty_global_state() -> (TempDir, LmdbGlobalState) {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let environment =
+        Arc::new(LmdbEnvironment::new(temp_dir.path(), MAP_SIZE, MAX_READERS).unwrap());
+    let trie_store =
+        Arc::new(LmdbTrieStore::new(&environment, None, DatabaseFlags::empty()).unwrap());
+    let protocol_data_store =
+        Arc::new(LmdbProtocolDataStore::new(&environment, None, DatabaseFlags::empty()).unwrap());
+    let global_state =
+        LmdbGlobalState::empty(environment, trie_store, protocol_data_store).unwrap();
+    (temp_dir, global_state)
+}
+
+fn state_root(commit_result: Result<CommitResult, error::Error>) -> Blake2bHash {
+    match commit_result.unwrap() {
+        CommitResult::Success { state_root } => state_root,
+        other => panic!("commit failed: {}", other),
+    }
+}
+
+fn commit_genesis_serially(
+    global_state: &LmdbGlobalState,
+    effects: AdditiveMap<Key, Transform>,
+) -> Blake2bHash {
+    state_root(commit_serially::<_, _, _, error::Error>(
+        &*global_state.environment,
+        &*global_state.trie_store,
+        CorrelationId::new(),
+        global_state.empty_root(),
+        effects,
+    ))
+}
+
+fn commit_genesis_in_parallel(
+    global_state: &LmdbGlobalState,
+    effects: AdditiveMap<Key, Transform>,
+) -> Blake2bHash {
+    state_root(commit_in_parallel::<_, _, _, error::Error>(
+        &*global_state.environment,
+        &*global_state.trie_store,
+        CorrelationId::new(),
+        global_state.empty_root(),
+        effects,
+    ))
+}
+
+fn commit_bench(c: &mut Criterion) {
+    let effects = genesis_effects();
+
+    // Both ways of committing must arrive at the same state root.
+    let (_serial_dir, serial_state) = empty_global_state();
+    let (_parallel_dir, parallel_state) = empty_global_state();
+    assert_eq!(
+        commit_genesis_serially(&serial_state, effects.clone()),
+        commit_genesis_in_parallel(&parallel_state, effects.clone())
+    );
+
+    let mut group = c.benchmark_group("commit_genesis_10k_accounts");
+    group.sample_size(10);
+    group.bench_function("serially", |b| {
+        b.iter_batched(
+            || (empty_global_state(), effects.clone()),
+            // Return the state so that it is dropped outside of the measurement.
+            |((temp_dir, global_state), effects)| {
+                let state_root = commit_genesis_serially(&global_state, effects);
+                (temp_dir, global_state, state_root)
+            },
+            BatchSize::PerIteration,
+        )
+    });
+    group.bench_function("in_parallel", |b| {
+        b.iter_batched(
+            || (empty_global_state(), effects.clone()),
+            |((temp_dir, global_state), effects)| {
+                let state_root = commit_genesis_in_parallel(&global_state, effects);
+                (temp_dir, global_state, state_root)
+            },
+            BatchSize::PerIteration,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, commit_bench);
+criterion_main!(benches);
@@ -15,6 +15,8 @@ pub struct DeployItem {
     pub gas_price: GasPrice,
     pub authorization_keys: BTreeSet<AccountHash>,
     pub deploy_hash: DeployHash,
+    /// The name of the chain the deploy was created for.
+    pub chain_name: String,
 }
 
 impl DeployItem {
@@ -26,6 +28,7 @@ impl DeployItem {
         gas_price: GasPrice,
         authorization_keys: BTreeSet<AccountHash>,
         deploy_hash: DeployHash,
+        chain_name: String,
     ) -> Self {
         DeployItem {
             address,
@@ -34,6 +37,7 @@ impl DeployItem {
             gas_price,
             authorization_keys,
             deploy_hash,
+            chain_name,
         }
     }
 }
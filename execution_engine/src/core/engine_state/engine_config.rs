@@ -1,22 +1,76 @@
+use num_rational::Ratio;
+
 const DEFAULT_MAX_QUERY_DEPTH: u64 = 5;
 
 /// The runtime configuration of the execution engine
 #[derive(Debug, Copy, Clone)]
 pub struct EngineConfig {
     pub(crate) max_query_depth: u64,
+    /// The fraction of the unspent payment amount refunded to the payer.
+    refund_ratio: Ratio<u64>,
+    /// Whether deploys may be executed as, or authorized by, the system account.
+    allow_system_account_deploys: bool,
+    /// The maximum number of times gas may be charged while executing the payment or session code
+    /// of a deploy.
+    max_execution_steps: Option<u64>,
 }
 
 impl Default for EngineConfig {
     fn default() -> Self {
         EngineConfig {
             max_query_depth: DEFAULT_MAX_QUERY_DEPTH,
+            refund_ratio: Ratio::new_raw(0, 1),
+            allow_system_account_deploys: false,
+            max_execution_steps: None,
         }
     }
 }
 
 impl EngineConfig {
     /// Creates a new engine configuration with provided parameters.
-    pub fn new(max_query_depth: u64) -> EngineConfig {
-        EngineConfig { max_query_depth }
+    pub fn new(max_query_depth: u64, refund_ratio: Ratio<u64>) -> EngineConfig {
+        EngineConfig {
+            max_query_depth,
+            refund_ratio,
+            allow_system_account_deploys: false,
+            max_execution_steps: None,
+        }
+    }
+
+    /// Returns a copy of this configuration under which deploys may be executed as, or authorized
+    /// by, the system account.
+    ///
+    /// This is only meant for test harnesses calling system contract entry points directly.  A node
+    /// must never let user deploys act as the system account.
+    pub fn with_system_account_deploys_allowed(mut self) -> EngineConfig {
+        self.allow_system_account_deploys = true;
+        self
+    }
+
+    /// Returns a copy of this configuration under which the execution of a deploy's payment or
+    /// session code is aborted once gas has been charged the given number of times.
+    ///
+    /// Gas is charged for every executed basic block of wasm and every host function call, so this
+    /// bounds the running time of wasm which is cheap in gas but slow to interpret.  Unlike a
+    /// wall-clock limit, the outcome doesn't depend on the speed of the executing machine.
+    pub fn with_max_execution_steps(mut self, max_execution_steps: Option<u64>) -> EngineConfig {
+        self.max_execution_steps = max_execution_steps;
+        self
+    }
+
+    /// Returns the maximum number of times gas may be charged while executing the payment or
+    /// session code of a deploy.
+    pub fn max_execution_steps(&self) -> Option<u64> {
+        self.max_execution_steps
+    }
+
+    /// Returns whether deploys may be executed as, or authorized by, the system account.
+    pub fn allow_system_account_deploys(&self) -> bool {
+        self.allow_system_account_deploys
+    }
+
+    /// Returns the fraction of the unspent payment amount refunded to the payer.
+    pub fn refund_ratio(&self) -> Ratio<u64> {
+        self.refund_ratio
     }
 }
@@ -5,7 +5,9 @@ use casper_types::{bytesrepr, system::mint, ProtocolVersion};
 
 use crate::{
     core::{
-        engine_state::{genesis::GenesisError, upgrade::ProtocolUpgradeError},
+        engine_state::{
+            genesis::GenesisError, state_snapshot::SnapshotError, upgrade::ProtocolUpgradeError,
+        },
         execution,
     },
     shared::{newtypes::Blake2bHash, wasm_prep},
@@ -34,6 +36,10 @@ pub enum Error {
     Storage(#[from] storage::error::Error),
     #[error("Authorization failure: not authorized.")]
     Authorization,
+    #[error("Deploys may not be executed as or authorized by the system account")]
+    SystemAccountNotAllowed,
+    #[error("Invalid chain name: expected {expected}, got {got}")]
+    InvalidChainName { expected: String, got: String },
     #[error("Insufficient payment")]
     InsufficientPayment,
     #[error("Gas conversion overflow")]
@@ -54,6 +60,15 @@ pub enum Error {
     ProtocolUpgrade(ProtocolUpgradeError),
     #[error("Unsupported deploy item variant: {0}")]
     InvalidDeployItemVariant(String),
+    #[error("Missing trie node: {0}")]
+    MissingTrieNode(Blake2bHash),
+    #[error("Genesis snapshot root hash mismatch: expected {expected}, actual {actual}")]
+    GenesisSnapshotRootMismatch {
+        expected: Blake2bHash,
+        actual: Blake2bHash,
+    },
+    #[error("Genesis snapshot error: {0}")]
+    Snapshot(String),
 }
 
 impl From<execution::Error> for Error {
@@ -85,6 +100,12 @@ impl From<GenesisError> for Error {
     }
 }
 
+impl From<SnapshotError> for Error {
+    fn from(error: SnapshotError) -> Self {
+        Error::Snapshot(format!("{}", error))
+    }
+}
+
 impl DataSize for Error {
     const IS_DYNAMIC: bool = true;
 
@@ -140,6 +140,15 @@ impl ExecutableDeployItem {
         matches!(self, ExecutableDeployItem::Transfer { .. })
     }
 
+    /// Returns `true` if this is empty module bytes, i.e. a request to use the standard payment
+    /// code.
+    pub fn is_standard_payment(&self) -> bool {
+        match self {
+            ExecutableDeployItem::ModuleBytes { module_bytes, .. } => module_bytes.is_empty(),
+            _ => false,
+        }
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn get_deploy_metadata<R>(
         &self,
@@ -12,6 +12,8 @@ pub struct ExecuteRequest {
     pub deploys: Vec<DeployItem>,
     pub protocol_version: ProtocolVersion,
     pub proposer: PublicKey,
+    /// The name of the chain the deploys must have been created for, if it is to be enforced.
+    pub chain_name: Option<String>,
 }
 
 impl ExecuteRequest {
@@ -21,6 +23,7 @@ impl ExecuteRequest {
         deploys: Vec<DeployItem>,
         protocol_version: ProtocolVersion,
         proposer: PublicKey,
+        chain_name: Option<String>,
     ) -> Self {
         Self {
             parent_state_hash,
@@ -28,6 +31,7 @@ impl ExecuteRequest {
             deploys,
             protocol_version,
             proposer,
+            chain_name,
         }
     }
 
@@ -51,6 +55,7 @@ impl Default for ExecuteRequest {
             deploys: vec![],
             protocol_version: Default::default(),
             proposer,
+            chain_name: None,
         }
     }
 }
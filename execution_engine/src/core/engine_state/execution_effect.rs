@@ -1,34 +1,97 @@
-use casper_types::Key;
+use serde::{Deserialize, Serialize};
+
+use casper_types::{bytesrepr::Bytes, ContractHash, Key};
 
 use super::op::Op;
 use crate::shared::{additive_map::AdditiveMap, transform::Transform};
 
+/// An event emitted by a stored contract via the `casper_emit_event` host function.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractEvent {
+    /// The hash of the contract which emitted the event.
+    pub contract_hash: ContractHash,
+    /// The topic of the event.
+    pub topic: String,
+    /// The event's payload.
+    pub data: Bytes,
+}
+
+/// A single read, write or addition performed on global state.
+///
+/// Only the type of the value is recorded, not the value itself: the net effect on each key is
+/// already held in the `transforms` of the `ExecutionEffect`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JournalEntry {
+    /// The normalized key operated on.
+    pub key: Key,
+    /// The kind of operation.
+    pub op: Op,
+    /// The type name of the value under the key after the operation.
+    pub value_type: String,
+}
+
+impl From<&JournalEntry> for casper_types::JournalEntry {
+    fn from(entry: &JournalEntry) -> Self {
+        casper_types::JournalEntry {
+            key: entry.key.to_formatted_string(),
+            kind: (&entry.op).into(),
+            value_type: entry.value_type.clone(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct ExecutionEffect {
     pub ops: AdditiveMap<Key, Op>,
     pub transforms: AdditiveMap<Key, Transform>,
+    /// Events emitted by contracts, in emission order.
+    pub events: Vec<ContractEvent>,
+    /// All operations, in execution order.  Unlike `ops` and `transforms`, these are neither
+    /// merged per key nor reduced.
+    pub journal: Vec<JournalEntry>,
 }
 
 impl ExecutionEffect {
     pub fn new(ops: AdditiveMap<Key, Op>, transforms: AdditiveMap<Key, Transform>) -> Self {
-        ExecutionEffect { ops, transforms }
+        ExecutionEffect {
+            ops,
+            transforms,
+            events: Vec::new(),
+            journal: Vec::new(),
+        }
+    }
+
+    /// Attaches the given contract events to the effect.
+    pub fn with_events(mut self, events: Vec<ContractEvent>) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Attaches the given journal of operations to the effect.
+    pub fn with_journal(mut self, journal: Vec<JournalEntry>) -> Self {
+        self.journal = journal;
+        self
     }
 }
 
 impl From<&ExecutionEffect> for casper_types::ExecutionEffect {
     fn from(effect: &ExecutionEffect) -> Self {
+        // The effects are held in hash maps, so they are sorted by key to make the serialized
+        // execution result independent of the order in which they were recorded.
+        let mut ops: Vec<_> = effect.ops.iter().collect();
+        ops.sort_by(|(key1, _), (key2, _)| key1.cmp(key2));
+        let mut transforms: Vec<_> = effect.transforms.iter().collect();
+        transforms.sort_by(|(key1, _), (key2, _)| key1.cmp(key2));
         casper_types::ExecutionEffect {
-            operations: effect
-                .ops
-                .iter()
+            operations: ops
+                .into_iter()
                 .map(|(key, op)| casper_types::Operation {
                     key: key.to_formatted_string(),
                     kind: op.into(),
                 })
                 .collect(),
-            transforms: effect
-                .transforms
-                .iter()
+            transforms: transforms
+                .into_iter()
                 .map(|(key, transform)| casper_types::TransformEntry {
                     key: key.to_formatted_string(),
                     transform: transform.into(),
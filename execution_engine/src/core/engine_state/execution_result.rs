@@ -2,7 +2,11 @@ use std::collections::VecDeque;
 
 use casper_types::{bytesrepr::FromBytes, CLTyped, CLValue, CLValueError, Key, TransferAddr};
 
-use super::{error, execution_effect::ExecutionEffect, op::Op};
+use super::{
+    error,
+    execution_effect::{ContractEvent, ExecutionEffect, JournalEntry},
+    op::Op,
+};
 use crate::{
     shared::{
         additive_map::AdditiveMap, gas::Gas, motes::Motes, newtypes::CorrelationId,
@@ -390,6 +394,8 @@ impl ExecutionResultBuilder {
         let cost = self.total_cost();
         let mut ops = AdditiveMap::new();
         let mut transforms = AdditiveMap::new();
+        let mut events = Vec::new();
+        let mut journal = Vec::new();
 
         let mut ret: ExecutionResult = ExecutionResult::Success {
             effect: Default::default(),
@@ -402,7 +408,13 @@ impl ExecutionResultBuilder {
                 if result.is_failure() {
                     return Ok(result);
                 } else {
-                    Self::add_effects(&mut ops, &mut transforms, result.effect());
+                    Self::add_effects(
+                        &mut ops,
+                        &mut transforms,
+                        &mut events,
+                        &mut journal,
+                        result.effect(),
+                    );
                 }
             }
             None => return Err(ExecutionResultBuilderError::MissingPaymentExecutionResult),
@@ -415,7 +427,13 @@ impl ExecutionResultBuilder {
                 if result.is_failure() {
                     ret = result.with_cost(cost);
                 } else {
-                    Self::add_effects(&mut ops, &mut transforms, result.effect());
+                    Self::add_effects(
+                        &mut ops,
+                        &mut transforms,
+                        &mut events,
+                        &mut journal,
+                        result.effect(),
+                    );
                 }
             }
             None => return Err(ExecutionResultBuilderError::MissingSessionExecutionResult),
@@ -429,7 +447,13 @@ impl ExecutionResultBuilder {
                         error::Error::Finalization,
                     ));
                 } else {
-                    Self::add_effects(&mut ops, &mut transforms, result.effect());
+                    Self::add_effects(
+                        &mut ops,
+                        &mut transforms,
+                        &mut events,
+                        &mut journal,
+                        result.effect(),
+                    );
                 }
             }
             None => return Err(ExecutionResultBuilderError::MissingFinalizeExecutionResult),
@@ -438,12 +462,14 @@ impl ExecutionResultBuilder {
         // Remove redundant writes to allow more opportunity to commute
         let reduced_effect = Self::reduce_identity_writes(ops, transforms, reader, correlation_id);
 
-        Ok(ret.with_effect(reduced_effect))
+        Ok(ret.with_effect(reduced_effect.with_events(events).with_journal(journal)))
     }
 
     fn add_effects(
         ops: &mut AdditiveMap<Key, Op>,
         transforms: &mut AdditiveMap<Key, Transform>,
+        events: &mut Vec<ContractEvent>,
+        journal: &mut Vec<JournalEntry>,
         effect: &ExecutionEffect,
     ) {
         for (k, op) in effect.ops.iter() {
@@ -452,6 +478,8 @@ impl ExecutionResultBuilder {
         for (k, t) in effect.transforms.iter() {
             transforms.insert_add(*k, t.clone())
         }
+        events.extend(effect.events.iter().cloned());
+        journal.extend(effect.journal.iter().cloned());
     }
 
     /// In the case we are writing the same value as was there originally,
@@ -25,9 +25,10 @@ use casper_types::{
             ARG_VALIDATOR_PUBLIC_KEY, AUCTION_DELAY_KEY, DELEGATION_RATE_DENOMINATOR,
             ERA_END_TIMESTAMP_MILLIS_KEY, ERA_ID_KEY, INITIAL_ERA_END_TIMESTAMP_MILLIS,
             INITIAL_ERA_ID, LOCKED_FUNDS_PERIOD_KEY, METHOD_ACTIVATE_BID, METHOD_ADD_BID,
-            METHOD_DELEGATE, METHOD_DISTRIBUTE, METHOD_GET_ERA_VALIDATORS, METHOD_READ_ERA_ID,
-            METHOD_RUN_AUCTION, METHOD_SLASH, METHOD_UNDELEGATE, METHOD_WITHDRAW_BID,
-            SEIGNIORAGE_RECIPIENTS_SNAPSHOT_KEY, UNBONDING_DELAY_KEY, VALIDATOR_SLOTS_KEY,
+            METHOD_DELEGATE, METHOD_DISTRIBUTE, METHOD_GET_ERA_VALIDATORS, METHOD_READ_BID,
+            METHOD_READ_ERA_ID, METHOD_RUN_AUCTION, METHOD_SLASH, METHOD_UNDELEGATE,
+            METHOD_WITHDRAW_BID, SEIGNIORAGE_RECIPIENTS_SNAPSHOT_KEY, UNBONDING_DELAY_KEY,
+            VALIDATOR_SLOTS_KEY,
         },
         handle_payment::{
             self, ARG_ACCOUNT, METHOD_FINALIZE_PAYMENT, METHOD_GET_PAYMENT_PURSE,
@@ -40,6 +41,7 @@ use casper_types::{
             TOTAL_SUPPLY_KEY,
         },
         standard_payment::METHOD_PAY,
+        SystemContractRegistry,
     },
     AccessRights, CLType, CLTyped, CLValue, Contract, ContractHash, ContractPackage,
     ContractPackageHash, ContractWasm, ContractWasmHash, DeployHash, EntryPoint, EntryPointAccess,
@@ -780,6 +782,19 @@ where
         }
     }
 
+    /// Writes the registry of system contract hashes under `Key::SystemContractRegistry`.
+    pub(crate) fn store_system_contract_registry(
+        &self,
+        registry: SystemContractRegistry,
+    ) -> Result<(), GenesisError> {
+        let cl_value = CLValue::from_t(registry)
+            .map_err(|_| GenesisError::CLValue("system contract registry".to_string()))?;
+        self.tracking_copy
+            .borrow_mut()
+            .write(Key::SystemContractRegistry, StoredValue::CLValue(cl_value));
+        Ok(())
+    }
+
     pub(crate) fn finalize(self) -> ExecutionEffect {
         self.tracking_copy.borrow_mut().effect()
     }
@@ -1515,6 +1530,15 @@ where
         );
         entry_points.add_entry_point(entry_point);
 
+        let entry_point = EntryPoint::new(
+            METHOD_READ_BID,
+            vec![Parameter::new(ARG_VALIDATOR_PUBLIC_KEY, CLType::PublicKey)],
+            CLType::Option(Box::new(CLType::Any)),
+            EntryPointAccess::Public,
+            EntryPointType::Contract,
+        );
+        entry_points.add_entry_point(entry_point);
+
         entry_points
     }
 
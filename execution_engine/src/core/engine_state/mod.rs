@@ -11,6 +11,8 @@ pub mod genesis;
 pub mod op;
 pub mod query;
 pub mod run_genesis_request;
+pub mod state_diff;
+pub mod state_snapshot;
 pub mod step;
 pub mod system_contract_cache;
 mod transfer;
@@ -20,6 +22,7 @@ use std::{
     cell::RefCell,
     collections::{BTreeMap, BTreeSet},
     convert::TryFrom,
+    io::{Read, Write},
     iter::FromIterator,
     rc::Rc,
 };
@@ -34,12 +37,15 @@ use casper_types::{
     contracts::NamedKeys,
     system::{
         auction::{
-            EraValidators, ARG_ERA_END_TIMESTAMP_MILLIS, ARG_EVICTED_VALIDATORS,
+            EraValidators, SeigniorageRecipient, SeigniorageRecipients,
+            SeigniorageRecipientsSnapshot, ARG_ERA_END_TIMESTAMP_MILLIS, ARG_EVICTED_VALIDATORS,
             ARG_REWARD_FACTORS, ARG_VALIDATOR_PUBLIC_KEYS, AUCTION_DELAY_KEY,
-            LOCKED_FUNDS_PERIOD_KEY, UNBONDING_DELAY_KEY, VALIDATOR_SLOTS_KEY,
+            LOCKED_FUNDS_PERIOD_KEY, SEIGNIORAGE_RECIPIENTS_SNAPSHOT_KEY, UNBONDING_DELAY_KEY,
+            VALIDATOR_SLOTS_KEY,
         },
         handle_payment,
         mint::{self, ROUND_SEIGNIORAGE_RATE_KEY},
+        SystemContractRegistry, AUCTION, HANDLE_PAYMENT, MINT, STANDARD_PAYMENT,
     },
     AccessRights, ApiError, BlockTime, CLValue, Contract, DeployHash, DeployInfo, Key, KeyTag,
     Phase, ProtocolVersion, PublicKey, RuntimeArgs, URef, U512,
@@ -54,19 +60,23 @@ pub use self::{
     executable_deploy_item::ExecutableDeployItem,
     execute_request::ExecuteRequest,
     execution::Error as ExecError,
+    execution_effect::{ContractEvent, JournalEntry},
     execution_result::{ExecutionResult, ExecutionResults, ForcedTransferResult},
     genesis::{ExecConfig, GenesisAccount, GenesisResult},
     query::{GetBidsRequest, GetBidsResult, QueryRequest, QueryResult},
+    state_diff::{StateDiff, StateDiffRequest, StateDiffResult},
+    state_snapshot::{ExportStateResult, SnapshotError, SnapshotReader, SnapshotWriter},
     step::{RewardItem, SlashItem, StepRequest, StepResult},
     system_contract_cache::SystemContractCache,
     transfer::{TransferArgs, TransferRuntimeArgsBuilder, TransferTargetMode},
-    upgrade::{UpgradeConfig, UpgradeResult},
+    upgrade::{ProtocolUpgradeError, UpgradeConfig, UpgradeResult},
 };
 use crate::{
     core::{
         engine_state::{
-            executable_deploy_item::DeployMetadata, execution_result::ExecutionResultBuilder,
-            genesis::GenesisInstaller, upgrade::SystemUpgrader,
+            executable_deploy_item::DeployMetadata, execution_effect::ExecutionEffect,
+            execution_result::ExecutionResultBuilder, genesis::GenesisInstaller,
+            upgrade::SystemUpgrader,
         },
         execution::{self, DirectSystemContractCall, Executor},
         tracking_copy::{TrackingCopy, TrackingCopyExt},
@@ -79,10 +89,12 @@ use crate::{
         newtypes::{Blake2bHash, CorrelationId},
         stored_value::StoredValue,
         transform::Transform,
+        wasm_cache::WasmCache,
+        wasm_config::WasmConfig,
         wasm_prep::Preprocessor,
     },
     storage::{
-        global_state::{CommitResult, StateProvider},
+        global_state::{in_memory::InMemoryGlobalState, CommitResult, StateProvider},
         protocol_data::ProtocolData,
         trie::Trie,
     },
@@ -95,10 +107,14 @@ pub static MAX_PAYMENT: Lazy<U512> = Lazy::new(|| U512::from(MAX_PAYMENT_AMOUNT)
 /// pay.
 pub const WASMLESS_TRANSFER_FIXED_GAS_PRICE: u64 = 1;
 
+/// The number of genesis snapshot entries committed to the global state at once.
+pub const GENESIS_SNAPSHOT_CHUNK_SIZE: usize = 10_000;
+
 #[derive(Debug)]
 pub struct EngineState<S> {
     config: EngineConfig,
     system_contract_cache: SystemContractCache,
+    wasm_cache: WasmCache,
     state: S,
 }
 
@@ -112,6 +128,7 @@ where
         EngineState {
             config,
             system_contract_cache,
+            wasm_cache: WasmCache::default(),
             state,
         }
     }
@@ -120,6 +137,16 @@ where
         &self.config
     }
 
+    /// Returns the cache of preprocessed wasm modules.
+    pub fn wasm_cache(&self) -> &WasmCache {
+        &self.wasm_cache
+    }
+
+    /// Returns a preprocessor for `wasm_config` backed by the engine's wasm cache.
+    fn preprocessor(&self, wasm_config: &WasmConfig) -> Preprocessor {
+        Preprocessor::with_cache(*wasm_config, self.wasm_cache.clone())
+    }
+
     pub fn get_protocol_data(
         &self,
         protocol_version: ProtocolVersion,
@@ -151,7 +178,7 @@ where
         };
 
         let wasm_config = ee_config.wasm_config();
-        let preprocessor = Preprocessor::new(*wasm_config);
+        let preprocessor = self.preprocessor(wasm_config);
 
         let system_module = tracking_copy
             .borrow_mut()
@@ -196,6 +223,9 @@ where
             self.state
                 .put_protocol_data(protocol_version, &protocol_data)
                 .map_err(Into::into)?;
+
+            genesis_installer
+                .store_system_contract_registry(protocol_data.system_contract_registry())?;
         }
 
         // Commit the transforms.
@@ -216,6 +246,112 @@ where
         Ok(genesis_result)
     }
 
+    /// Runs genesis against a scratch, in-memory global state and returns its result.
+    ///
+    /// The global state of this engine is left untouched, allowing the genesis post-state hash of
+    /// a genesis config to be compared against a known hash before committing to it.
+    pub fn validate_genesis(
+        &self,
+        correlation_id: CorrelationId,
+        genesis_config_hash: Blake2bHash,
+        protocol_version: ProtocolVersion,
+        ee_config: &ExecConfig,
+    ) -> Result<GenesisResult, Error> {
+        let scratch_state = InMemoryGlobalState::empty().map_err(execution::Error::from)?;
+        EngineState::new(scratch_state, self.config).commit_genesis(
+            correlation_id,
+            genesis_config_hash,
+            protocol_version,
+            ee_config,
+        )
+    }
+
+    /// Initializes the global state from a previously exported snapshot instead of running the
+    /// genesis installer.
+    ///
+    /// The entries are read from `snapshot` and written to the empty global state in chunks of
+    /// `GENESIS_SNAPSHOT_CHUNK_SIZE`, so that the snapshot never needs to be held in memory as a
+    /// whole.  The resulting root hash must match `expected_state_root_hash`.  The protocol data is
+    /// assembled from the system contract registry contained in the snapshot and the given config.
+    ///
+    /// Since the written values are not kept, the returned effect is empty.
+    pub fn commit_genesis_snapshot<R: Read>(
+        &self,
+        correlation_id: CorrelationId,
+        protocol_version: ProtocolVersion,
+        ee_config: &ExecConfig,
+        snapshot: R,
+        expected_state_root_hash: Blake2bHash,
+    ) -> Result<GenesisResult, Error> {
+        let mut entries = SnapshotReader::new(snapshot);
+        let mut post_state_hash = self.state.empty_root();
+        loop {
+            let mut transforms = AdditiveMap::new();
+            for entry in entries.by_ref().take(GENESIS_SNAPSHOT_CHUNK_SIZE) {
+                let (key, value) = entry?;
+                transforms.insert(key, Transform::Write(value));
+            }
+            if transforms.is_empty() {
+                break;
+            }
+            post_state_hash = match self
+                .state
+                .commit(correlation_id, post_state_hash, transforms)
+                .map_err(Into::into)?
+            {
+                CommitResult::Success { state_root } => state_root,
+                commit_result => {
+                    return Ok(GenesisResult::from_commit_result(
+                        commit_result,
+                        ExecutionEffect::default(),
+                    ))
+                }
+            };
+        }
+        if post_state_hash != expected_state_root_hash {
+            return Err(Error::GenesisSnapshotRootMismatch {
+                expected: expected_state_root_hash,
+                actual: post_state_hash,
+            });
+        }
+
+        let mut tracking_copy = match self.tracking_copy(post_state_hash)? {
+            Some(tracking_copy) => tracking_copy,
+            None => return Ok(GenesisResult::RootNotFound),
+        };
+        let registry: SystemContractRegistry = match tracking_copy
+            .read(correlation_id, &Key::SystemContractRegistry)
+            .map_err(|error| Error::Exec(error.into()))?
+        {
+            Some(StoredValue::CLValue(cl_value)) => cl_value
+                .into_t()
+                .map_err(|_| Error::Bytesrepr("system_contract_registry".to_string()))?,
+            _ => return Ok(GenesisResult::KeyNotFound(Key::SystemContractRegistry)),
+        };
+        let system_contract = |name: &str| {
+            registry
+                .get(name)
+                .copied()
+                .ok_or_else(|| Error::MissingSystemContract(name.to_string()))
+        };
+        let protocol_data = ProtocolData::new(
+            *ee_config.wasm_config(),
+            *ee_config.system_config(),
+            system_contract(MINT)?,
+            system_contract(HANDLE_PAYMENT)?,
+            system_contract(STANDARD_PAYMENT)?,
+            system_contract(AUCTION)?,
+        );
+        self.state
+            .put_protocol_data(protocol_version, &protocol_data)
+            .map_err(Into::into)?;
+
+        Ok(GenesisResult::Success {
+            post_state_hash,
+            effect: ExecutionEffect::default(),
+        })
+    }
+
     pub fn commit_upgrade(
         &self,
         correlation_id: CorrelationId,
@@ -293,6 +429,19 @@ where
             .put_protocol_data(new_protocol_version, &new_protocol_data)
             .map_err(Into::into)?;
 
+        // Modules preprocessed under the old wasm config are no longer useful.
+        self.wasm_cache.clear();
+
+        // Store the registry of system contracts, so that it exists in global state also if the
+        // chain was started before it was introduced.
+        let registry_value = StoredValue::CLValue(
+            CLValue::from_t(new_protocol_data.system_contract_registry())
+                .map_err(|_| Error::Bytesrepr("system_contract_registry".to_string()))?,
+        );
+        tracking_copy
+            .borrow_mut()
+            .write(Key::SystemContractRegistry, registry_value);
+
         // 3.1.1.1.1.7 new total validator slots is optional
         if let Some(new_validator_slots) = upgrade_config.new_validator_slots() {
             // 3.1.2.4 if new total validator slots is provided, update auction contract state
@@ -369,6 +518,54 @@ where
                 .write(locked_funds_period_key, value);
         }
 
+        // In an emergency restart the validator set may be replaced: the seigniorage recipients
+        // snapshot is overwritten so that the new validators are used from the activation point
+        // until the auction delay has passed, after which the auction selects them from the bids
+        // again.
+        if let Some(new_validators) = upgrade_config.new_validators() {
+            let activation_point =
+                upgrade_config
+                    .activation_point()
+                    .ok_or(Error::ProtocolUpgrade(
+                        ProtocolUpgradeError::InvalidUpgradeConfig,
+                    ))?;
+
+            let auction_contract = tracking_copy
+                .borrow_mut()
+                .get_contract(correlation_id, new_protocol_data.auction())?;
+
+            let auction_delay_key = auction_contract.named_keys()[AUCTION_DELAY_KEY];
+            let auction_delay: u64 = match tracking_copy
+                .borrow_mut()
+                .read(correlation_id, &auction_delay_key)
+                .map_err(|error| Error::Exec(error.into()))?
+            {
+                Some(StoredValue::CLValue(cl_value)) => cl_value
+                    .into_t()
+                    .map_err(|_| Error::Bytesrepr(AUCTION_DELAY_KEY.to_string()))?,
+                _ => return Ok(UpgradeResult::KeyNotFound(auction_delay_key)),
+            };
+
+            let seigniorage_recipients: SeigniorageRecipients = new_validators
+                .iter()
+                .map(|(public_key, weight)| {
+                    let recipient = SeigniorageRecipient::new(*weight, 0, BTreeMap::new());
+                    (public_key.clone(), recipient)
+                })
+                .collect();
+            let snapshot: SeigniorageRecipientsSnapshot = activation_point
+                .iter_inclusive(auction_delay)
+                .map(|era_id| (era_id, seigniorage_recipients.clone()))
+                .collect();
+
+            let snapshot_key = auction_contract.named_keys()[SEIGNIORAGE_RECIPIENTS_SNAPSHOT_KEY];
+            let value = StoredValue::CLValue(
+                CLValue::from_t(snapshot)
+                    .map_err(|_| Error::Bytesrepr("new_validators".to_string()))?,
+            );
+            tracking_copy.borrow_mut().write(snapshot_key, value);
+        }
+
         // apply the arbitrary modifications
         for (key, value) in upgrade_config.global_state_update() {
             tracking_copy.borrow_mut().write(*key, value.clone());
@@ -390,6 +587,12 @@ where
         Ok(UpgradeResult::from_commit_result(commit_result, effects))
     }
 
+    /// Returns `true` if the global state contains the given state root.
+    pub fn has_state_root(&self, state_root_hash: Blake2bHash) -> Result<bool, Error> {
+        let maybe_reader = self.state.checkout(state_root_hash).map_err(Into::into)?;
+        Ok(maybe_reader.is_some())
+    }
+
     pub fn tracking_copy(
         &self,
         hash: Blake2bHash,
@@ -434,6 +637,18 @@ where
         let mut results = ExecutionResults::with_capacity(deploys.len());
 
         for deploy_item in deploys {
+            // Deploys created for another network must never be executed, to prevent them from
+            // being replayed across networks.
+            if let Some(chain_name) = &exec_request.chain_name {
+                if deploy_item.chain_name != *chain_name {
+                    let error = Error::InvalidChainName {
+                        expected: chain_name.clone(),
+                        got: deploy_item.chain_name,
+                    };
+                    results.push_back(ExecutionResult::precondition_failure(error));
+                    continue;
+                }
+            }
             let result = match deploy_item.session {
                 ExecutableDeployItem::Transfer { .. } => self.transfer(
                     correlation_id,
@@ -472,6 +687,17 @@ where
         authorization_keys: &BTreeSet<AccountHash>,
         tracking_copy: Rc<RefCell<TrackingCopy<<S as StateProvider>::Reader>>>,
     ) -> Result<Account, Error> {
+        // Deploys only ever run in the payment and session phases.  Work done as the system account
+        // in `Phase::System` (genesis, upgrades and steps) uses a virtual system account instead,
+        // so a deploy must never be able to act as the real one.
+        let system_account_hash = PublicKey::System.to_account_hash();
+        if !self.config.allow_system_account_deploys()
+            && (account_hash == system_account_hash
+                || authorization_keys.contains(&system_account_hash))
+        {
+            return Err(Error::SystemAccountNotAllowed);
+        }
+
         let account: Account = match tracking_copy
             .borrow_mut()
             .get_account(correlation_id, account_hash)
@@ -546,7 +772,7 @@ where
 
         let preprocessor = {
             let wasm_config = protocol_data.wasm_config();
-            Preprocessor::new(*wasm_config)
+            self.preprocessor(wasm_config)
         };
 
         let system_module = {
@@ -1078,7 +1304,7 @@ where
 
         let preprocessor = {
             let wasm_config = protocol_data.wasm_config();
-            Preprocessor::new(*wasm_config)
+            self.preprocessor(wasm_config)
         };
 
         // Create tracking copy (which functions as a deploy context)
@@ -1676,6 +1902,76 @@ where
             .map_err(Error::from)
     }
 
+    /// Returns the differences between the values stored under the two requested state roots,
+    /// restricted to keys starting with the requested prefix.
+    pub fn diff_state(
+        &self,
+        correlation_id: CorrelationId,
+        state_diff_request: StateDiffRequest,
+    ) -> Result<StateDiffResult, Error>
+    where
+        Error: From<S::Error>,
+    {
+        for state_hash in [
+            state_diff_request.pre_state_hash(),
+            state_diff_request.post_state_hash(),
+        ]
+        .iter()
+        {
+            if self.state.read_trie(correlation_id, state_hash)?.is_none() {
+                return Ok(StateDiffResult::RootNotFound(*state_hash));
+            }
+        }
+
+        let state_diff = state_diff::diff_tries(
+            |trie_key| {
+                self.state
+                    .read_trie(correlation_id, trie_key)
+                    .map_err(Error::from)
+            },
+            state_diff_request.key_prefix(),
+            state_diff_request.pre_state_hash(),
+            state_diff_request.post_state_hash(),
+        )?;
+        Ok(StateDiffResult::Success(state_diff))
+    }
+
+    /// Writes all values stored under the requested state root to `writer` as a snapshot, from
+    /// which a new network can be initialized via `commit_genesis_snapshot`.
+    ///
+    /// The trie is traversed and written one entry at a time, so the state is never held in
+    /// memory as a whole.
+    pub fn export_state<W: Write>(
+        &self,
+        correlation_id: CorrelationId,
+        state_root_hash: Blake2bHash,
+        writer: W,
+    ) -> Result<ExportStateResult, Error>
+    where
+        Error: From<S::Error>,
+    {
+        if self
+            .state
+            .read_trie(correlation_id, &state_root_hash)?
+            .is_none()
+        {
+            return Ok(ExportStateResult::RootNotFound(state_root_hash));
+        }
+
+        let mut snapshot_writer = SnapshotWriter::new(writer);
+        state_snapshot::for_each_leaf(
+            |trie_key| {
+                self.state
+                    .read_trie(correlation_id, trie_key)
+                    .map_err(Error::from)
+            },
+            state_root_hash,
+            |key, value| Ok(snapshot_writer.write_entry(&key, &value)?),
+        )?;
+        let entry_count = snapshot_writer.finish()?;
+        Ok(ExportStateResult::Success { entry_count })
+    }
+
     pub fn put_trie_and_find_missing_descendant_trie_keys(
         &self,
         correlation_id: CorrelationId,
@@ -1724,7 +2020,7 @@ where
 
         let wasm_config = protocol_data.wasm_config();
 
-        let preprocessor = Preprocessor::new(*wasm_config);
+        let preprocessor = self.preprocessor(wasm_config);
 
         let auction_contract: Contract = tracking_copy
             .borrow_mut()
@@ -1821,6 +2117,11 @@ where
         Ok(GetBidsResult::Success { bids })
     }
 
+    /// Runs the era-end processing of a switch block: distributes rewards, slashes equivocators
+    /// and, if requested, runs the auction, all on a single tracking copy.
+    ///
+    /// The resulting effects are committed at once, so the era end produces exactly one post-state
+    /// hash, which is returned together with the validator weights of the next era.
     pub fn commit_step(
         &self,
         correlation_id: CorrelationId,
@@ -1844,7 +2145,7 @@ where
 
         let preprocessor = {
             let wasm_config = protocol_data.wasm_config();
-            Preprocessor::new(*wasm_config)
+            self.preprocessor(wasm_config)
         };
 
         let auction_hash = protocol_data.auction();
@@ -0,0 +1,253 @@
+//! Computing the differences between the global states under two state root hashes.
+
+use std::collections::BTreeMap;
+
+use casper_types::{bytesrepr::ToBytes, Key};
+
+use super::Error;
+use crate::{
+    shared::{newtypes::Blake2bHash, stored_value::StoredValue},
+    storage::trie::Trie,
+};
+
+/// A request to compute the differences between the global states under two state root hashes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateDiffRequest {
+    pre_state_hash: Blake2bHash,
+    post_state_hash: Blake2bHash,
+    key_prefix: Vec<u8>,
+}
+
+impl StateDiffRequest {
+    /// Creates a new request.  Only keys whose serialized form starts with `key_prefix` are
+    /// compared; pass an empty prefix to compare all keys.
+    pub fn new(
+        pre_state_hash: Blake2bHash,
+        post_state_hash: Blake2bHash,
+        key_prefix: Vec<u8>,
+    ) -> Self {
+        StateDiffRequest {
+            pre_state_hash,
+            post_state_hash,
+            key_prefix,
+        }
+    }
+
+    pub fn pre_state_hash(&self) -> Blake2bHash {
+        self.pre_state_hash
+    }
+
+    pub fn post_state_hash(&self) -> Blake2bHash {
+        self.post_state_hash
+    }
+
+    pub fn key_prefix(&self) -> &[u8] {
+        &self.key_prefix
+    }
+}
+
+#[derive(Debug)]
+pub enum StateDiffResult {
+    RootNotFound(Blake2bHash),
+    Success(StateDiff),
+}
+
+/// The differences between two global states.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StateDiff {
+    /// Values only present in the post state.
+    pub added: BTreeMap<Key, StoredValue>,
+    /// Values only present in the pre state.
+    pub removed: BTreeMap<Key, StoredValue>,
+    /// Values present in both states, but differing, as `(pre value, post value)`.
+    pub changed: BTreeMap<Key, (StoredValue, StoredValue)>,
+}
+
+impl StateDiff {
+    /// Returns `true` if the two states are identical.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Walks two tries in lockstep, skipping all subtries with identical hashes, and returns the
+/// differences between their leaves.
+///
+/// `read_trie` should return `Ok(None)` if the trie node is missing from the store.
+pub(super) fn diff_tries<F>(
+    read_trie: F,
+    key_prefix: &[u8],
+    pre_state_hash: Blake2bHash,
+    post_state_hash: Blake2bHash,
+) -> Result<StateDiff, Error>
+where
+    F: Fn(&Blake2bHash) -> Result<Option<Trie<Key, StoredValue>>, Error>,
+{
+    let mut differ = TrieDiffer {
+        read_trie,
+        key_prefix,
+        pre_values: BTreeMap::new(),
+        post_values: BTreeMap::new(),
+    };
+    differ.diff(&[], pre_state_hash, post_state_hash)?;
+
+    let TrieDiffer {
+        pre_values,
+        mut post_values,
+        ..
+    } = differ;
+    let mut state_diff = StateDiff::default();
+    for (key, pre_value) in pre_values {
+        match post_values.remove(&key) {
+            // The value may have only moved within the trie due to other keys changing.
+            Some(post_value) if post_value == pre_value => {}
+            Some(post_value) => {
+                let _ = state_diff.changed.insert(key, (pre_value, post_value));
+            }
+            None => {
+                let _ = state_diff.removed.insert(key, pre_value);
+            }
+        }
+    }
+    state_diff.added = post_values;
+    Ok(state_diff)
+}
+
+struct TrieDiffer<'a, F> {
+    read_trie: F,
+    key_prefix: &'a [u8],
+    /// The leaves of differing pre state subtries.
+    pre_values: BTreeMap<Key, StoredValue>,
+    /// The leaves of differing post state subtries.
+    post_values: BTreeMap<Key, StoredValue>,
+}
+
+impl<'a, F> TrieDiffer<'a, F>
+where
+    F: Fn(&Blake2bHash) -> Result<Option<Trie<Key, StoredValue>>, Error>,
+{
+    /// Compares the subtries with the given hashes, both located at `path`.
+    fn diff(
+        &mut self,
+        path: &[u8],
+        pre_hash: Blake2bHash,
+        post_hash: Blake2bHash,
+    ) -> Result<(), Error> {
+        if pre_hash == post_hash || !self.is_relevant(path) {
+            return Ok(());
+        }
+
+        match (self.read(&pre_hash)?, self.read(&post_hash)?) {
+            (
+                Trie::Node {
+                    pointer_block: pre_pointer_block,
+                },
+                Trie::Node {
+                    pointer_block: post_pointer_block,
+                },
+            ) => {
+                for index in 0..=u8::MAX {
+                    let child_path = extend_path(path, &[index]);
+                    let position = usize::from(index);
+                    match (pre_pointer_block[position], post_pointer_block[position]) {
+                        (Some(pre_pointer), Some(post_pointer)) => self.diff(
+                            &child_path,
+                            pre_pointer.into_hash(),
+                            post_pointer.into_hash(),
+                        )?,
+                        (Some(pre_pointer), None) => {
+                            let pre_child = self.read(pre_pointer.hash())?;
+                            self.collect(&child_path, pre_child, true)?;
+                        }
+                        (None, Some(post_pointer)) => {
+                            let post_child = self.read(post_pointer.hash())?;
+                            self.collect(&child_path, post_child, false)?;
+                        }
+                        (None, None) => {}
+                    }
+                }
+            }
+            (
+                Trie::Extension {
+                    affix: pre_affix,
+                    pointer: pre_pointer,
+                },
+                Trie::Extension {
+                    affix: post_affix,
+                    pointer: post_pointer,
+                },
+            ) if pre_affix == post_affix => {
+                let child_path = extend_path(path, &pre_affix);
+                self.diff(
+                    &child_path,
+                    pre_pointer.into_hash(),
+                    post_pointer.into_hash(),
+                )?;
+            }
+            // The subtries are shaped differently, so compare all their leaves.
+            (pre_trie, post_trie) => {
+                self.collect(path, pre_trie, true)?;
+                self.collect(path, post_trie, false)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Collects all relevant leaves of the given trie located at `path`.
+    fn collect(
+        &mut self,
+        path: &[u8],
+        trie: Trie<Key, StoredValue>,
+        is_pre_state: bool,
+    ) -> Result<(), Error> {
+        if !self.is_relevant(path) {
+            return Ok(());
+        }
+
+        match trie {
+            Trie::Leaf { key, value } => {
+                if key.to_bytes()?.starts_with(self.key_prefix) {
+                    let values = if is_pre_state {
+                        &mut self.pre_values
+                    } else {
+                        &mut self.post_values
+                    };
+                    let _ = values.insert(key, value);
+                }
+            }
+            Trie::Node { pointer_block } => {
+                for (index, pointer) in pointer_block.to_indexed_pointers() {
+                    let child_path = extend_path(path, &[index]);
+                    if self.is_relevant(&child_path) {
+                        let child = self.read(pointer.hash())?;
+                        self.collect(&child_path, child, is_pre_state)?;
+                    }
+                }
+            }
+            Trie::Extension { affix, pointer } => {
+                let child_path = extend_path(path, &affix);
+                if self.is_relevant(&child_path) {
+                    let child = self.read(pointer.hash())?;
+                    self.collect(&child_path, child, is_pre_state)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if keys located at `path` may start with the key prefix.
+    fn is_relevant(&self, path: &[u8]) -> bool {
+        path.starts_with(self.key_prefix) || self.key_prefix.starts_with(path)
+    }
+
+    fn read(&self, trie_key: &Blake2bHash) -> Result<Trie<Key, StoredValue>, Error> {
+        (self.read_trie)(trie_key)?.ok_or(Error::MissingTrieNode(*trie_key))
+    }
+}
+
+fn extend_path(path: &[u8], suffix: &[u8]) -> Vec<u8> {
+    let mut extended_path = Vec::with_capacity(path.len() + suffix.len());
+    extended_path.extend_from_slice(path);
+    extended_path.extend_from_slice(suffix);
+    extended_path
+}
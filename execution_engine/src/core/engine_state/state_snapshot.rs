@@ -0,0 +1,167 @@
+//! Exporting the global state under a state root hash as a snapshot, and the snapshot stream
+//! format used to initialize a new network from it.
+//!
+//! A snapshot is a stream of entries, each encoded as the length of the serialized
+//! `(Key, StoredValue)` pair as a little-endian `u32`, followed by the serialized pair.  Since the
+//! trie only depends on its contents, importing all entries into an empty global state reproduces
+//! the exported state root hash.
+//!
+//! Snapshots of a live network's global state can be large, so they are written and read one entry
+//! at a time rather than being held in memory as a whole.
+
+use std::{
+    convert::TryFrom,
+    io::{self, Read, Write},
+};
+
+use thiserror::Error;
+
+use casper_types::{
+    bytesrepr::{self, ToBytes},
+    Key,
+};
+
+use super::Error;
+use crate::{
+    shared::{newtypes::Blake2bHash, stored_value::StoredValue},
+    storage::trie::Trie,
+};
+
+/// The result of exporting the global state under a state root hash.
+#[derive(Debug)]
+pub enum ExportStateResult {
+    RootNotFound(Blake2bHash),
+    /// The snapshot was written, containing the given number of entries.
+    Success {
+        entry_count: u64,
+    },
+}
+
+/// An error reading or writing a snapshot stream.
+#[derive(Debug, Error)]
+pub enum SnapshotError {
+    #[error("snapshot I/O error: {0}")]
+    Io(#[from] io::Error),
+    #[error("invalid snapshot entry: {0}")]
+    Bytesrepr(bytesrepr::Error),
+    #[error("snapshot entry too large: {0} bytes")]
+    EntryTooLarge(usize),
+}
+
+/// Writes entries to a snapshot stream.
+pub struct SnapshotWriter<W> {
+    writer: W,
+    entry_count: u64,
+}
+
+impl<W: Write> SnapshotWriter<W> {
+    pub fn new(writer: W) -> Self {
+        SnapshotWriter {
+            writer,
+            entry_count: 0,
+        }
+    }
+
+    /// Appends a single entry to the stream.
+    pub fn write_entry(&mut self, key: &Key, value: &StoredValue) -> Result<(), SnapshotError> {
+        let mut bytes = key.to_bytes().map_err(SnapshotError::Bytesrepr)?;
+        bytes.extend(value.to_bytes().map_err(SnapshotError::Bytesrepr)?);
+        let length =
+            u32::try_from(bytes.len()).map_err(|_| SnapshotError::EntryTooLarge(bytes.len()))?;
+        self.writer.write_all(&length.to_le_bytes())?;
+        self.writer.write_all(&bytes)?;
+        self.entry_count += 1;
+        Ok(())
+    }
+
+    /// Flushes the stream and returns the number of entries written.
+    pub fn finish(mut self) -> Result<u64, SnapshotError> {
+        self.writer.flush()?;
+        Ok(self.entry_count)
+    }
+}
+
+/// Reads the entries of a snapshot stream one at a time.
+pub struct SnapshotReader<R> {
+    reader: R,
+}
+
+impl<R: Read> SnapshotReader<R> {
+    pub fn new(reader: R) -> Self {
+        SnapshotReader { reader }
+    }
+
+    /// Reads the next entry, or returns `None` at the end of the stream.
+    fn read_entry(&mut self) -> Result<Option<(Key, StoredValue)>, SnapshotError> {
+        let mut length_bytes = [0; 4];
+        // The stream may only end between entries.
+        if self.reader.read(&mut length_bytes[..1])? == 0 {
+            return Ok(None);
+        }
+        self.reader.read_exact(&mut length_bytes[1..])?;
+        let mut bytes = vec![0; u32::from_le_bytes(length_bytes) as usize];
+        self.reader.read_exact(&mut bytes)?;
+        let entry = bytesrepr::deserialize(bytes).map_err(SnapshotError::Bytesrepr)?;
+        Ok(Some(entry))
+    }
+}
+
+impl<R: Read> Iterator for SnapshotReader<R> {
+    type Item = Result<(Key, StoredValue), SnapshotError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_entry().transpose()
+    }
+}
+
+/// Writes the entries to the given writer in the snapshot stream format.
+pub fn write_snapshot<W: Write>(
+    writer: W,
+    entries: &[(Key, StoredValue)],
+) -> Result<(), SnapshotError> {
+    let mut snapshot_writer = SnapshotWriter::new(writer);
+    for (key, value) in entries {
+        snapshot_writer.write_entry(key, value)?;
+    }
+    snapshot_writer.finish()?;
+    Ok(())
+}
+
+/// Reads all entries of a snapshot stream from the given reader.
+pub fn read_snapshot<R: Read>(reader: R) -> Result<Vec<(Key, StoredValue)>, SnapshotError> {
+    SnapshotReader::new(reader).collect()
+}
+
+/// Calls `visit` with every leaf of the trie under `root`, in the order of their serialized keys.
+///
+/// The trie is walked depth-first, so only the unvisited siblings along the current path are held
+/// in memory.
+/// `read_trie` should return `Ok(None)` if the trie node is missing from the store.
+pub(super) fn for_each_leaf<F, V>(
+    read_trie: F,
+    root: Blake2bHash,
+    mut visit: V,
+) -> Result<(), Error>
+where
+    F: Fn(&Blake2bHash) -> Result<Option<Trie<Key, StoredValue>>, Error>,
+    V: FnMut(Key, StoredValue) -> Result<(), Error>,
+{
+    let mut pending = vec![root];
+    while let Some(trie_key) = pending.pop() {
+        match read_trie(&trie_key)?.ok_or(Error::MissingTrieNode(trie_key))? {
+            Trie::Leaf { key, value } => visit(key, value)?,
+            Trie::Node { pointer_block } => {
+                let children: Vec<_> = pointer_block.to_indexed_pointers().collect();
+                // Push in reverse, so that the children are visited in order.
+                pending.extend(
+                    children
+                        .into_iter()
+                        .rev()
+                        .map(|(_, pointer)| pointer.into_hash()),
+                );
+            }
+            Trie::Extension { pointer, .. } => pending.push(pointer.into_hash()),
+        }
+    }
+    Ok(())
+}
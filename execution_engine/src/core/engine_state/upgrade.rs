@@ -6,7 +6,7 @@ use thiserror::Error;
 use casper_types::{
     bytesrepr,
     system::{AUCTION, HANDLE_PAYMENT, MINT, STANDARD_PAYMENT},
-    ContractHash, EraId, Key, ProtocolVersion,
+    ContractHash, EraId, Key, ProtocolVersion, PublicKey, U512,
 };
 
 use crate::{
@@ -86,6 +86,7 @@ pub struct UpgradeConfig {
     new_round_seigniorage_rate: Option<Ratio<u64>>,
     new_unbonding_delay: Option<u64>,
     global_state_update: BTreeMap<Key, StoredValue>,
+    new_validators: Option<BTreeMap<PublicKey, U512>>,
 }
 
 impl UpgradeConfig {
@@ -103,6 +104,7 @@ impl UpgradeConfig {
         new_round_seigniorage_rate: Option<Ratio<u64>>,
         new_unbonding_delay: Option<u64>,
         global_state_update: BTreeMap<Key, StoredValue>,
+        new_validators: Option<BTreeMap<PublicKey, U512>>,
     ) -> Self {
         UpgradeConfig {
             pre_state_hash,
@@ -117,6 +119,7 @@ impl UpgradeConfig {
             new_round_seigniorage_rate,
             new_unbonding_delay,
             global_state_update,
+            new_validators,
         }
     }
 
@@ -168,6 +171,11 @@ impl UpgradeConfig {
         &self.global_state_update
     }
 
+    /// Returns the validator set replacing the current one in an emergency restart, if any.
+    pub fn new_validators(&self) -> Option<&BTreeMap<PublicKey, U512>> {
+        self.new_validators.as_ref()
+    }
+
     pub fn with_pre_state_hash(&mut self, pre_state_hash: Blake2bHash) {
         self.pre_state_hash = pre_state_hash;
     }
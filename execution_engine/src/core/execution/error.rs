@@ -43,6 +43,8 @@ pub enum Error {
     WasmOptimizer,
     #[error("Out of gas error")]
     GasLimit,
+    #[error("Execution step limit exceeded")]
+    ExecutionStepLimit,
     #[error("Return")]
     Ret(Vec<URef>),
     #[error("{}", _0)]
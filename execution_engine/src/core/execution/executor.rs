@@ -1,4 +1,8 @@
-use std::{cell::RefCell, collections::BTreeSet, rc::Rc};
+use std::{
+    cell::{Cell, RefCell},
+    collections::BTreeSet,
+    rc::Rc,
+};
 
 use parity_wasm::elements::Module;
 use tracing::warn;
@@ -166,7 +170,18 @@ impl Executor {
             transfers,
         );
 
-        let mut runtime = Runtime::new(self.config, system_contract_cache, memory, module, context);
+        let remaining_steps = self
+            .config
+            .max_execution_steps()
+            .map(|max_execution_steps| Rc::new(Cell::new(max_execution_steps)));
+        let mut runtime = Runtime::new(
+            self.config,
+            system_contract_cache,
+            memory,
+            module,
+            context,
+            remaining_steps,
+        );
 
         let accounts_access_rights = {
             let keys: Vec<Key> = account.named_keys().values().cloned().collect();
@@ -639,6 +654,7 @@ impl Executor {
             memory,
             module,
             runtime_context,
+            None,
         );
 
         Ok((instance, runtime))
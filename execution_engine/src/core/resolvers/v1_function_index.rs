@@ -50,6 +50,8 @@ pub enum FunctionIndex {
     Blake2b,
     RecordTransfer,
     RecordEraInfo,
+    GetBalanceWithProofIndex,
+    EmitEventIndex,
 }
 
 impl From<FunctionIndex> for usize {
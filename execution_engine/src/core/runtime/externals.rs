@@ -480,6 +480,39 @@ where
                 Ok(Some(RuntimeValue::I32(api_error::i32_from(ret))))
             }
 
+            FunctionIndex::GetBalanceWithProofIndex => {
+                // args(0) = pointer to purse input
+                // args(1) = length of purse
+                // args(2) = pointer to output size (output)
+                let (ptr, ptr_size, output_size_ptr) = Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.get_balance_with_proof,
+                    [ptr, ptr_size, output_size_ptr],
+                )?;
+                let ret = self.get_balance_with_proof_host_buffer(
+                    ptr,
+                    ptr_size as usize,
+                    output_size_ptr,
+                )?;
+                Ok(Some(RuntimeValue::I32(api_error::i32_from(ret))))
+            }
+
+            FunctionIndex::EmitEventIndex => {
+                // args(0) = pointer to the UTF-8 bytes of the event's topic
+                // args(1) = length of the topic
+                // args(2) = pointer to the event's data
+                // args(3) = length of the data
+                let (topic_ptr, topic_size, data_ptr, data_size) = Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.emit_event,
+                    [topic_ptr, topic_size, data_ptr, data_size],
+                )?;
+                scoped_instrumenter.add_property("topic_size", topic_size.to_string());
+                scoped_instrumenter.add_property("data_size", data_size.to_string());
+                let ret = self.emit_event(topic_ptr, topic_size, data_ptr, data_size)?;
+                Ok(Some(RuntimeValue::I32(api_error::i32_from(ret))))
+            }
+
             FunctionIndex::GetPhaseIndex => {
                 // args(0) = pointer to Wasm memory where to write.
                 let dest_ptr = Args::parse(args)?;
@@ -1,3 +1,5 @@
+use num_rational::Ratio;
+
 use casper_types::{
     account::AccountHash,
     system::handle_payment::{Error, HandlePayment, MintProvider, RuntimeProvider},
@@ -95,6 +97,10 @@ where
     fn get_caller(&self) -> AccountHash {
         self.context.get_caller()
     }
+
+    fn refund_ratio(&self) -> Ratio<u64> {
+        self.config.refund_ratio()
+    }
 }
 
 impl<'a, R> HandlePayment for Runtime<'a, R>
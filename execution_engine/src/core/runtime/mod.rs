@@ -7,10 +7,12 @@ mod scoped_instrumenter;
 mod standard_payment_internal;
 
 use std::{
+    cell::Cell,
     cmp,
     collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     convert::TryFrom,
     iter::IntoIterator,
+    rc::Rc,
 };
 
 use itertools::Itertools;
@@ -30,7 +32,7 @@ use casper_types::{
         handle_payment::{self, HandlePayment},
         mint::{self, Mint},
         standard_payment::{self, StandardPayment},
-        SystemContractType,
+        SystemContractRegistry, SystemContractType,
     },
     AccessRights, ApiError, CLType, CLTyped, CLValue, ContractHash, ContractPackageHash,
     ContractVersionKey, ContractWasm, DeployHash, EntryPointType, EraId, Key, Phase,
@@ -40,7 +42,10 @@ use casper_types::{
 
 use crate::{
     core::{
-        engine_state::{system_contract_cache::SystemContractCache, EngineConfig},
+        engine_state::{
+            execution_effect::ContractEvent, system_contract_cache::SystemContractCache,
+            EngineConfig,
+        },
         execution::{self, Error},
         resolvers::{create_module_resolver, memory_resolver::MemoryResolver},
         runtime::scoped_instrumenter::ScopedInstrumenter,
@@ -64,6 +69,9 @@ pub struct Runtime<'a, R> {
     module: Module,
     host_buffer: Option<CLValue>,
     context: RuntimeContext<'a, R>,
+    /// The number of times gas may still be charged before execution is aborted, if limited.
+    /// Shared with the runtimes of nested contract calls.
+    remaining_steps: Option<Rc<Cell<u64>>>,
 }
 
 pub fn instance_and_memory(
@@ -99,6 +107,7 @@ pub fn key_to_tuple(key: Key) -> Option<([u8; 32], AccessRights)> {
         Key::Balance(_) => None,
         Key::Bid(_) => None,
         Key::Withdraw(_) => None,
+        Key::SystemContractRegistry => None,
     }
 }
 
@@ -972,6 +981,7 @@ where
         memory: MemoryRef,
         module: Module,
         context: RuntimeContext<'a, R>,
+        remaining_steps: Option<Rc<Cell<u64>>>,
     ) -> Self {
         Runtime {
             config,
@@ -980,6 +990,7 @@ where
             module,
             host_buffer: None,
             context,
+            remaining_steps,
         }
     }
 
@@ -1000,6 +1011,14 @@ where
     }
 
     fn gas(&mut self, amount: Gas) -> Result<(), Error> {
+        // Gas is charged at least once per basic block, so limiting the number of charges bounds
+        // how long wasm can run, independently of the speed of the executing machine.
+        if let Some(remaining_steps) = &self.remaining_steps {
+            match remaining_steps.get().checked_sub(1) {
+                Some(remaining) => remaining_steps.set(remaining),
+                None => return Err(Error::ExecutionStepLimit),
+            }
+        }
         self.context.charge_gas(amount)
     }
 
@@ -1354,6 +1373,7 @@ where
             self.memory.clone(),
             self.module.clone(),
             mint_context,
+            self.remaining_steps.clone(),
         );
 
         let system_config = protocol_data.system_config();
@@ -1499,6 +1519,7 @@ where
             self.memory.clone(),
             self.module.clone(),
             runtime_context,
+            self.remaining_steps.clone(),
         );
 
         let system_config = protocol_data.system_config();
@@ -1626,6 +1647,7 @@ where
             self.memory.clone(),
             self.module.clone(),
             runtime_context,
+            self.remaining_steps.clone(),
         );
 
         let system_config = protocol_data.system_config();
@@ -1740,9 +1762,22 @@ where
                 CLValue::from_t(result).map_err(Self::reverter)
             })(),
 
-            auction::METHOD_ACTIVATE_BID => (|| {
+            // Type: `fn read_bid(validator_public_key: PublicKey) -> Result<Option<Bid>, Error>`
+            auction::METHOD_READ_BID => (|| {
                 runtime.charge_system_contract_call(auction_costs.read_era_id)?;
 
+                let validator_public_key: PublicKey =
+                    Self::get_named_argument(runtime_args, auction::ARG_VALIDATOR_PUBLIC_KEY)?;
+
+                let result = runtime
+                    .read_validator_bid(validator_public_key)
+                    .map_err(Self::reverter)?;
+                CLValue::from_t(result).map_err(Self::reverter)
+            })(),
+
+            auction::METHOD_ACTIVATE_BID => (|| {
+                runtime.charge_system_contract_call(auction_costs.activate_bid)?;
+
                 let validator_public_key: PublicKey =
                     Self::get_named_argument(runtime_args, auction::ARG_VALIDATOR_PUBLIC_KEY)?;
 
@@ -2052,6 +2087,7 @@ where
             module,
             host_buffer,
             context,
+            remaining_steps: self.remaining_steps.clone(),
         };
 
         let result = instance.invoke_export(entry_point_name, &[], &mut runtime);
@@ -3019,20 +3055,108 @@ where
         Ok(Ok(()))
     }
 
+    fn get_balance_with_proof_host_buffer(
+        &mut self,
+        purse_ptr: u32,
+        purse_size: usize,
+        output_size_ptr: u32,
+    ) -> Result<Result<(), ApiError>, Error> {
+        if !self.can_write_to_host_buffer() {
+            // Exit early if the host buffer is already occupied
+            return Ok(Err(ApiError::HostBufferFull));
+        }
+
+        let purse: URef = {
+            let bytes = self.bytes_from_mem(purse_ptr, purse_size)?;
+            match bytesrepr::deserialize(bytes) {
+                Ok(purse) => purse,
+                Err(error) => return Ok(Err(error.into())),
+            }
+        };
+
+        let proof = match self
+            .context
+            .read_gs_with_proof(&Key::Balance(purse.addr()))?
+        {
+            Some(proof) => proof,
+            None => return Ok(Err(ApiError::InvalidPurse)),
+        };
+
+        let balance: U512 = match proof.value() {
+            StoredValue::CLValue(value) => value.clone().into_t()?,
+            _ => return Err(Error::UnexpectedStoredValueVariant),
+        };
+        let proof_bytes = proof.to_bytes()?;
+
+        let result_cl_value = match CLValue::from_t((balance, proof_bytes)) {
+            Ok(cl_value) => cl_value,
+            Err(error) => return Ok(Err(error.into())),
+        };
+
+        let result_size = result_cl_value.inner_bytes().len() as i32;
+        if let Err(error) = self.write_host_buffer(result_cl_value) {
+            return Ok(Err(error));
+        }
+
+        let result_size_bytes = result_size.to_le_bytes(); // Wasm is little-endian
+        if let Err(error) = self.memory.set(output_size_ptr, &result_size_bytes) {
+            return Err(Error::Interpreter(error.into()));
+        }
+
+        Ok(Ok(()))
+    }
+
+    /// Records an event with the given topic and payload on behalf of the currently executing
+    /// stored contract.
+    ///
+    /// Events can only be emitted from within a stored contract, as they are indexed by the hash
+    /// of the contract which emitted them.
+    fn emit_event(
+        &mut self,
+        topic_ptr: u32,
+        topic_size: u32,
+        data_ptr: u32,
+        data_size: u32,
+    ) -> Result<Result<(), ApiError>, Error> {
+        let contract_hash = match self.context.base_key() {
+            Key::Hash(contract_hash) => ContractHash::new(contract_hash),
+            _ => return Err(Error::InvalidContext),
+        };
+        let topic_bytes = self.bytes_from_mem(topic_ptr, topic_size as usize)?;
+        let topic = match String::from_utf8(topic_bytes) {
+            Ok(topic) => topic,
+            Err(_) => return Ok(Err(ApiError::InvalidArgument)),
+        };
+        let data = self.bytes_from_mem(data_ptr, data_size as usize)?;
+        self.context.emit_event(ContractEvent {
+            contract_hash,
+            topic,
+            data: data.into(),
+        });
+        Ok(Ok(()))
+    }
+
     fn get_system_contract(
         &mut self,
         system_contract_index: u32,
         dest_ptr: u32,
         _dest_size: u32,
     ) -> Result<Result<(), ApiError>, Trap> {
-        let contract_hash: ContractHash = match SystemContractType::try_from(system_contract_index)
-        {
-            Ok(SystemContractType::Mint) => self.get_mint_contract(),
-            Ok(SystemContractType::HandlePayment) => self.get_handle_payment_contract(),
-            Ok(SystemContractType::StandardPayment) => self.get_standard_payment_contract(),
-            Ok(SystemContractType::Auction) => self.get_auction_contract(),
+        let system_contract_type = match SystemContractType::try_from(system_contract_index) {
+            Ok(system_contract_type) => system_contract_type,
             Err(error) => return Ok(Err(error)),
         };
+        let contract_hash = match self.get_registered_system_contract(system_contract_type)? {
+            Some(contract_hash) => contract_hash,
+            // Global states created before the registry was introduced don't contain it until the
+            // next upgrade.
+            None => match system_contract_type {
+                SystemContractType::Mint => self.get_mint_contract(),
+                SystemContractType::HandlePayment => self.get_handle_payment_contract(),
+                SystemContractType::StandardPayment => self.get_standard_payment_contract(),
+                SystemContractType::Auction => self.get_auction_contract(),
+            },
+        };
 
         match self.memory.set(dest_ptr, contract_hash.as_ref()) {
             Ok(_) => Ok(Ok(())),
@@ -3040,6 +3164,27 @@ where
         }
     }
 
+    /// Looks up the hash of the given system contract in the registry stored in global state under
+    /// `Key::SystemContractRegistry`.
+    ///
+    /// Returns `None` if there is no registry in global state.
+    fn get_registered_system_contract(
+        &mut self,
+        system_contract_type: SystemContractType,
+    ) -> Result<Option<ContractHash>, Error> {
+        let cl_value = match self.context.read_gs(&Key::SystemContractRegistry)? {
+            Some(StoredValue::CLValue(cl_value)) => cl_value,
+            Some(_) => return Err(Error::UnexpectedStoredValueVariant),
+            None => return Ok(None),
+        };
+        let registry: SystemContractRegistry = cl_value.into_t()?;
+        registry
+            .get(&system_contract_type.to_string())
+            .copied()
+            .map(Some)
+            .ok_or(Error::KeyNotFound(Key::SystemContractRegistry))
+    }
+
     /// If host_buffer set, clears the host_buffer and returns value, else None
     pub fn take_host_buffer(&mut self) -> Option<CLValue> {
         self.host_buffer.take()
@@ -143,6 +143,8 @@ impl Drop for ScopedInstrumenter {
             FunctionIndex::Blake2b => "host_blake2b",
             FunctionIndex::RecordTransfer => "host_record_transfer",
             FunctionIndex::RecordEraInfo => "host_record_era_info",
+            FunctionIndex::GetBalanceWithProofIndex => "host_function_get_balance_with_proof",
+            FunctionIndex::EmitEventIndex => "host_function_emit_event",
         };
 
         let mut properties = mem::take(&mut self.properties);
@@ -22,13 +22,15 @@ use casper_types::{
 
 use crate::{
     core::{
-        engine_state::execution_effect::ExecutionEffect,
+        engine_state::execution_effect::{ContractEvent, ExecutionEffect},
         execution::{AddressGenerator, Error},
         tracking_copy::{AddResult, TrackingCopy},
         Address,
     },
     shared::{account::Account, gas::Gas, newtypes::CorrelationId, stored_value::StoredValue},
-    storage::{global_state::StateReader, protocol_data::ProtocolData},
+    storage::{
+        global_state::StateReader, protocol_data::ProtocolData, trie::merkle_proof::TrieMerkleProof,
+    },
 };
 
 #[cfg(test)]
@@ -260,6 +262,11 @@ where
                 self.named_keys.remove(name);
                 Ok(())
             }
+            Key::SystemContractRegistry => {
+                self.named_keys.remove(name);
+                // Users cannot remove the system contract registry from global state
+                Ok(())
+            }
         }
     }
 
@@ -408,6 +415,22 @@ where
             .map_err(Into::into)
     }
 
+    /// Reads the value under `key` together with a Merkle proof of its inclusion in global state.
+    ///
+    /// The proof is taken against the pre-state root hash of the current execution, so any writes
+    /// made earlier in this execution are not reflected in the returned value.
+    ///
+    /// DO NOT EXPOSE THIS VIA THE FFI
+    pub fn read_gs_with_proof(
+        &mut self,
+        key: &Key,
+    ) -> Result<Option<TrieMerkleProof<Key, StoredValue>>, Error> {
+        let tracking_copy = self.tracking_copy.borrow();
+        (&*tracking_copy)
+            .read_with_proof(self.correlation_id, key)
+            .map_err(Into::into)
+    }
+
     /// This method is a wrapper over `read_gs` in the sense that it extracts the type held by a
     /// `StoredValue` stored in the global state in a type safe manner.
     ///
@@ -518,6 +541,11 @@ where
         self.tracking_copy.borrow_mut().effect()
     }
 
+    /// Records an event emitted by the currently executing contract.
+    pub fn emit_event(&mut self, event: ContractEvent) {
+        self.tracking_copy.borrow_mut().emit_event(event)
+    }
+
     pub fn transfers(&self) -> &Vec<TransferAddr> {
         &self.transfers
     }
@@ -673,6 +701,7 @@ where
             Key::Balance(_) => false,
             Key::Bid(_) => true,
             Key::Withdraw(_) => true,
+            Key::SystemContractRegistry => true,
         }
     }
 
@@ -687,6 +716,7 @@ where
             Key::Balance(_) => false,
             Key::Bid(_) => false,
             Key::Withdraw(_) => false,
+            Key::SystemContractRegistry => false,
         }
     }
 
@@ -701,6 +731,7 @@ where
             Key::Balance(_) => false,
             Key::Bid(_) => false,
             Key::Withdraw(_) => false,
+            Key::SystemContractRegistry => false,
         }
     }
 
@@ -18,7 +18,10 @@ use casper_types::{bytesrepr, CLType, CLValue, CLValueError, Key, KeyTag, Tagged
 pub use self::ext::TrackingCopyExt;
 use self::meter::{heap_meter::HeapSize, Meter};
 use crate::{
-    core::engine_state::{execution_effect::ExecutionEffect, op::Op},
+    core::engine_state::{
+        execution_effect::{ContractEvent, ExecutionEffect, JournalEntry},
+        op::Op,
+    },
     shared::{
         additive_map::AdditiveMap,
         newtypes::{Blake2bHash, CorrelationId},
@@ -211,6 +214,8 @@ pub struct TrackingCopy<R> {
     cache: TrackingCopyCache<HeapSize>,
     ops: AdditiveMap<Key, Op>,
     fns: AdditiveMap<Key, Transform>,
+    events: Vec<ContractEvent>,
+    journal: Vec<JournalEntry>,
 }
 
 #[derive(Debug)]
@@ -244,6 +249,8 @@ impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
              * limit? */
             ops: AdditiveMap::new(),
             fns: AdditiveMap::new(),
+            events: Vec::new(),
+            journal: Vec::new(),
         }
     }
 
@@ -315,6 +322,7 @@ impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
         if let Some(value) = self.get(correlation_id, &normalized_key)? {
             self.ops.insert_add(normalized_key, Op::Read);
             self.fns.insert_add(normalized_key, Transform::Identity);
+            self.record(normalized_key, Op::Read, &value);
             Ok(Some(value))
         } else {
             Ok(None)
@@ -323,6 +331,7 @@ impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
 
     pub fn write(&mut self, key: Key, value: StoredValue) {
         let normalized_key = key.normalize();
+        self.record(normalized_key, Op::Write, &value);
         self.cache.insert_write(normalized_key, value.clone());
         self.ops.insert_add(normalized_key, Op::Write);
         self.fns.insert_add(normalized_key, Transform::Write(value));
@@ -393,6 +402,7 @@ impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
 
         match transform.clone().apply(current_value) {
             Ok(new_value) => {
+                self.record(normalized_key, Op::Add, &new_value);
                 self.cache.insert_write(normalized_key, new_value);
                 self.ops.insert_add(normalized_key, Op::Add);
                 self.fns.insert_add(normalized_key, transform);
@@ -405,8 +415,24 @@ impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
         }
     }
 
+    /// Records an event emitted by a contract.
+    pub fn emit_event(&mut self, event: ContractEvent) {
+        self.events.push(event);
+    }
+
+    /// Appends an operation to the journal, resulting in the given value under the key.
+    fn record(&mut self, key: Key, op: Op, value: &StoredValue) {
+        self.journal.push(JournalEntry {
+            key,
+            op,
+            value_type: value.type_name(),
+        });
+    }
+
     pub fn effect(&self) -> ExecutionEffect {
         ExecutionEffect::new(self.ops.clone(), self.fns.clone())
+            .with_events(self.events.clone())
+            .with_journal(self.journal.clone())
     }
 
     /// Calling `query()` avoids calling into `self.cache`, so this will not return any values
@@ -16,7 +16,7 @@ use super::{
 };
 use crate::{
     core::{
-        engine_state::{op::Op, EngineConfig},
+        engine_state::{execution_effect::JournalEntry, op::Op, EngineConfig},
         ValidationError,
     },
     shared::{
@@ -198,6 +198,42 @@ fn tracking_copy_add_i32() {
     assert_eq!(tc.ops.get(&k), Some(&Op::Add));
 }
 
+#[test]
+fn tracking_copy_journal_preserves_execution_order() {
+    let correlation_id = CorrelationId::new();
+    let counter = Rc::new(Cell::new(0));
+    let db = CountingDb::new(counter);
+    let mut tc = TrackingCopy::new(db);
+    let k1 = Key::Hash([1u8; 32]);
+    let k2 = Key::Hash([0u8; 32]);
+
+    let one = StoredValue::CLValue(CLValue::from_t(1_i32).unwrap());
+    let three = StoredValue::CLValue(CLValue::from_t(3_i32).unwrap());
+
+    tc.write(k1, one.clone());
+    let read = tc.read(correlation_id, &k1);
+    assert_matches!(read, Ok(Some(_)));
+    let add = tc.add(correlation_id, k1, three);
+    assert_matches!(add, Ok(_));
+    tc.write(k2, one);
+
+    // The journal holds every operation in order, while `ops` and `fns` are merged per key.
+    let entry = |key, op| JournalEntry {
+        key,
+        op,
+        value_type: "I32".to_string(),
+    };
+    let expected_journal = vec![
+        entry(k1, Op::Write),
+        entry(k1, Op::Read),
+        entry(k1, Op::Add),
+        entry(k2, Op::Write),
+    ];
+    assert_eq!(tc.effect().journal, expected_journal);
+    assert_eq!(tc.ops.len(), 2);
+    assert_eq!(tc.ops.get(&k1), Some(&Op::Write));
+}
+
 #[test]
 fn tracking_copy_add_named_key() {
     let zero_account_hash = AccountHash::new([0u8; ACCOUNT_HASH_LENGTH]);
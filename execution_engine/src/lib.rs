@@ -17,3 +17,5 @@ pub mod config;
 pub mod core;
 pub mod shared;
 pub mod storage;
+#[cfg(feature = "test-support")]
+pub mod testing;
@@ -18,6 +18,7 @@ pub mod transform;
 mod type_mismatch;
 pub mod utils;
 pub mod wasm;
+pub mod wasm_cache;
 pub mod wasm_config;
 pub mod wasm_prep;
 
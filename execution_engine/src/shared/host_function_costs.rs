@@ -24,7 +24,11 @@ const DEFAULT_CALL_CONTRACT_COST: u32 = 4_500;
 const DEFAULT_CALL_CONTRACT_ARGS_SIZE_WEIGHT: u32 = 420;
 
 const DEFAULT_CREATE_PURSE_COST: u32 = 170_000;
+const DEFAULT_EMIT_EVENT_COST: u32 = 12_000;
+const DEFAULT_EMIT_EVENT_DATA_SIZE_WEIGHT: u32 = 590;
+
 const DEFAULT_GET_BALANCE_COST: u32 = 3_800;
+const DEFAULT_GET_BALANCE_WITH_PROOF_COST: u32 = 25_000;
 const DEFAULT_GET_BLOCKTIME_COST: u32 = 330;
 const DEFAULT_GET_CALLER_COST: u32 = 380;
 const DEFAULT_GET_KEY_COST: u32 = 2_000;
@@ -210,6 +214,7 @@ pub struct HostFunctionCosts {
     pub transfer_from_purse_to_account: HostFunction<[Cost; 9]>,
     pub transfer_from_purse_to_purse: HostFunction<[Cost; 8]>,
     pub get_balance: HostFunction<[Cost; 3]>,
+    pub get_balance_with_proof: HostFunction<[Cost; 3]>,
     pub get_phase: HostFunction<[Cost; 1]>,
     pub get_system_contract: HostFunction<[Cost; 3]>,
     pub get_main_purse: HostFunction<[Cost; 1]>,
@@ -227,6 +232,7 @@ pub struct HostFunctionCosts {
     pub remove_contract_user_group_urefs: HostFunction<[Cost; 6]>,
     pub print: HostFunction<[Cost; 2]>,
     pub blake2b: HostFunction<[Cost; 4]>,
+    pub emit_event: HostFunction<[Cost; 4]>,
 }
 
 impl Default for HostFunctionCosts {
@@ -306,6 +312,7 @@ impl Default for HostFunctionCosts {
                 DEFAULT_TRANSFER_FROM_PURSE_TO_PURSE_COST,
             ),
             get_balance: HostFunction::fixed(DEFAULT_GET_BALANCE_COST),
+            get_balance_with_proof: HostFunction::fixed(DEFAULT_GET_BALANCE_WITH_PROOF_COST),
             get_phase: HostFunction::fixed(DEFAULT_GET_PHASE_COST),
             get_system_contract: HostFunction::fixed(DEFAULT_GET_SYSTEM_CONTRACT_COST),
             get_main_purse: HostFunction::fixed(DEFAULT_GET_MAIN_PURSE_COST),
@@ -344,56 +351,22 @@ impl Default for HostFunctionCosts {
                 [NOT_USED, DEFAULT_PRINT_TEXT_SIZE_WEIGHT],
             ),
             blake2b: HostFunction::default(),
+            emit_event: HostFunction::new(
+                DEFAULT_EMIT_EVENT_COST,
+                [
+                    NOT_USED,
+                    DEFAULT_EMIT_EVENT_DATA_SIZE_WEIGHT,
+                    NOT_USED,
+                    DEFAULT_EMIT_EVENT_DATA_SIZE_WEIGHT,
+                ],
+            ),
         }
     }
 }
 
 impl ToBytes for HostFunctionCosts {
     fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
-        let mut ret = bytesrepr::unchecked_allocate_buffer(self);
-        ret.append(&mut self.read_value.to_bytes()?);
-        ret.append(&mut self.read_value_local.to_bytes()?);
-        ret.append(&mut self.write.to_bytes()?);
-        ret.append(&mut self.write_local.to_bytes()?);
-        ret.append(&mut self.add.to_bytes()?);
-        ret.append(&mut self.new_uref.to_bytes()?);
-        ret.append(&mut self.load_named_keys.to_bytes()?);
-        ret.append(&mut self.ret.to_bytes()?);
-        ret.append(&mut self.get_key.to_bytes()?);
-        ret.append(&mut self.has_key.to_bytes()?);
-        ret.append(&mut self.put_key.to_bytes()?);
-        ret.append(&mut self.remove_key.to_bytes()?);
-        ret.append(&mut self.revert.to_bytes()?);
-        ret.append(&mut self.is_valid_uref.to_bytes()?);
-        ret.append(&mut self.add_associated_key.to_bytes()?);
-        ret.append(&mut self.remove_associated_key.to_bytes()?);
-        ret.append(&mut self.update_associated_key.to_bytes()?);
-        ret.append(&mut self.set_action_threshold.to_bytes()?);
-        ret.append(&mut self.get_caller.to_bytes()?);
-        ret.append(&mut self.get_blocktime.to_bytes()?);
-        ret.append(&mut self.create_purse.to_bytes()?);
-        ret.append(&mut self.transfer_to_account.to_bytes()?);
-        ret.append(&mut self.transfer_from_purse_to_account.to_bytes()?);
-        ret.append(&mut self.transfer_from_purse_to_purse.to_bytes()?);
-        ret.append(&mut self.get_balance.to_bytes()?);
-        ret.append(&mut self.get_phase.to_bytes()?);
-        ret.append(&mut self.get_system_contract.to_bytes()?);
-        ret.append(&mut self.get_main_purse.to_bytes()?);
-        ret.append(&mut self.read_host_buffer.to_bytes()?);
-        ret.append(&mut self.create_contract_package_at_hash.to_bytes()?);
-        ret.append(&mut self.create_contract_user_group.to_bytes()?);
-        ret.append(&mut self.add_contract_version.to_bytes()?);
-        ret.append(&mut self.disable_contract_version.to_bytes()?);
-        ret.append(&mut self.call_contract.to_bytes()?);
-        ret.append(&mut self.call_versioned_contract.to_bytes()?);
-        ret.append(&mut self.get_named_arg_size.to_bytes()?);
-        ret.append(&mut self.get_named_arg.to_bytes()?);
-        ret.append(&mut self.remove_contract_user_group.to_bytes()?);
-        ret.append(&mut self.provision_contract_user_group_uref.to_bytes()?);
-        ret.append(&mut self.remove_contract_user_group_urefs.to_bytes()?);
-        ret.append(&mut self.print.to_bytes()?);
-        ret.append(&mut self.blake2b.to_bytes()?);
-        Ok(ret)
+        self.to_bytes_with_layout(false)
     }
 
     fn serialized_length(&self) -> usize {
@@ -422,6 +395,7 @@ impl ToBytes for HostFunctionCosts {
             + self.transfer_from_purse_to_account.serialized_length()
             + self.transfer_from_purse_to_purse.serialized_length()
             + self.get_balance.serialized_length()
+            + self.get_balance_with_proof.serialized_length()
             + self.get_phase.serialized_length()
             + self.get_system_contract.serialized_length()
             + self.get_main_purse.serialized_length()
@@ -439,11 +413,90 @@ impl ToBytes for HostFunctionCosts {
             + self.remove_contract_user_group_urefs.serialized_length()
             + self.print.serialized_length()
             + self.blake2b.serialized_length()
+            + self.emit_event.serialized_length()
+    }
+}
+
+impl HostFunctionCosts {
+    /// Serializes the host function costs in the layout used before `get_balance_with_proof` and
+    /// `emit_event` were added.
+    #[cfg(test)]
+    pub(crate) fn to_legacy_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        self.to_bytes_with_layout(true)
+    }
+
+    fn to_bytes_with_layout(&self, legacy: bool) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut ret = bytesrepr::unchecked_allocate_buffer(self);
+        ret.append(&mut self.read_value.to_bytes()?);
+        ret.append(&mut self.read_value_local.to_bytes()?);
+        ret.append(&mut self.write.to_bytes()?);
+        ret.append(&mut self.write_local.to_bytes()?);
+        ret.append(&mut self.add.to_bytes()?);
+        ret.append(&mut self.new_uref.to_bytes()?);
+        ret.append(&mut self.load_named_keys.to_bytes()?);
+        ret.append(&mut self.ret.to_bytes()?);
+        ret.append(&mut self.get_key.to_bytes()?);
+        ret.append(&mut self.has_key.to_bytes()?);
+        ret.append(&mut self.put_key.to_bytes()?);
+        ret.append(&mut self.remove_key.to_bytes()?);
+        ret.append(&mut self.revert.to_bytes()?);
+        ret.append(&mut self.is_valid_uref.to_bytes()?);
+        ret.append(&mut self.add_associated_key.to_bytes()?);
+        ret.append(&mut self.remove_associated_key.to_bytes()?);
+        ret.append(&mut self.update_associated_key.to_bytes()?);
+        ret.append(&mut self.set_action_threshold.to_bytes()?);
+        ret.append(&mut self.get_caller.to_bytes()?);
+        ret.append(&mut self.get_blocktime.to_bytes()?);
+        ret.append(&mut self.create_purse.to_bytes()?);
+        ret.append(&mut self.transfer_to_account.to_bytes()?);
+        ret.append(&mut self.transfer_from_purse_to_account.to_bytes()?);
+        ret.append(&mut self.transfer_from_purse_to_purse.to_bytes()?);
+        ret.append(&mut self.get_balance.to_bytes()?);
+        if !legacy {
+            ret.append(&mut self.get_balance_with_proof.to_bytes()?);
+        }
+        ret.append(&mut self.get_phase.to_bytes()?);
+        ret.append(&mut self.get_system_contract.to_bytes()?);
+        ret.append(&mut self.get_main_purse.to_bytes()?);
+        ret.append(&mut self.read_host_buffer.to_bytes()?);
+        ret.append(&mut self.create_contract_package_at_hash.to_bytes()?);
+        ret.append(&mut self.create_contract_user_group.to_bytes()?);
+        ret.append(&mut self.add_contract_version.to_bytes()?);
+        ret.append(&mut self.disable_contract_version.to_bytes()?);
+        ret.append(&mut self.call_contract.to_bytes()?);
+        ret.append(&mut self.call_versioned_contract.to_bytes()?);
+        ret.append(&mut self.get_named_arg_size.to_bytes()?);
+        ret.append(&mut self.get_named_arg.to_bytes()?);
+        ret.append(&mut self.remove_contract_user_group.to_bytes()?);
+        ret.append(&mut self.provision_contract_user_group_uref.to_bytes()?);
+        ret.append(&mut self.remove_contract_user_group_urefs.to_bytes()?);
+        ret.append(&mut self.print.to_bytes()?);
+        ret.append(&mut self.blake2b.to_bytes()?);
+        if !legacy {
+            ret.append(&mut self.emit_event.to_bytes()?);
+        }
+        Ok(ret)
     }
 }
 
 impl FromBytes for HostFunctionCosts {
     fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        Self::from_bytes_with_layout(bytes, false)
+    }
+}
+
+impl HostFunctionCosts {
+    /// Deserializes host function costs written before `get_balance_with_proof` and `emit_event`
+    /// were added, giving both their default costs.
+    pub(crate) fn from_legacy_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        Self::from_bytes_with_layout(bytes, true)
+    }
+
+    fn from_bytes_with_layout(
+        bytes: &[u8],
+        legacy: bool,
+    ) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let defaults = HostFunctionCosts::default();
         let (read_value, rem) = FromBytes::from_bytes(bytes)?;
         let (read_value_local, rem) = FromBytes::from_bytes(rem)?;
         let (write, rem) = FromBytes::from_bytes(rem)?;
@@ -469,6 +522,11 @@ impl FromBytes for HostFunctionCosts {
         let (transfer_from_purse_to_account, rem) = FromBytes::from_bytes(rem)?;
         let (transfer_from_purse_to_purse, rem) = FromBytes::from_bytes(rem)?;
         let (get_balance, rem) = FromBytes::from_bytes(rem)?;
+        let (get_balance_with_proof, rem) = if legacy {
+            (defaults.get_balance_with_proof, rem)
+        } else {
+            FromBytes::from_bytes(rem)?
+        };
         let (get_phase, rem) = FromBytes::from_bytes(rem)?;
         let (get_system_contract, rem) = FromBytes::from_bytes(rem)?;
         let (get_main_purse, rem) = FromBytes::from_bytes(rem)?;
@@ -486,6 +544,11 @@ impl FromBytes for HostFunctionCosts {
         let (remove_contract_user_group_urefs, rem) = FromBytes::from_bytes(rem)?;
         let (print, rem) = FromBytes::from_bytes(rem)?;
         let (blake2b, rem) = FromBytes::from_bytes(rem)?;
+        let (emit_event, rem) = if legacy {
+            (defaults.emit_event, rem)
+        } else {
+            FromBytes::from_bytes(rem)?
+        };
         Ok((
             HostFunctionCosts {
                 read_value,
@@ -513,6 +576,7 @@ impl FromBytes for HostFunctionCosts {
                 transfer_from_purse_to_account,
                 transfer_from_purse_to_purse,
                 get_balance,
+                get_balance_with_proof,
                 get_phase,
                 get_system_contract,
                 get_main_purse,
@@ -530,6 +594,7 @@ impl FromBytes for HostFunctionCosts {
                 remove_contract_user_group_urefs,
                 print,
                 blake2b,
+                emit_event,
             },
             rem,
         ))
@@ -564,6 +629,7 @@ impl Distribution<HostFunctionCosts> for Standard {
             transfer_from_purse_to_account: rng.gen(),
             transfer_from_purse_to_purse: rng.gen(),
             get_balance: rng.gen(),
+            get_balance_with_proof: rng.gen(),
             get_phase: rng.gen(),
             get_system_contract: rng.gen(),
             get_main_purse: rng.gen(),
@@ -581,6 +647,7 @@ impl Distribution<HostFunctionCosts> for Standard {
             remove_contract_user_group_urefs: rng.gen(),
             print: rng.gen(),
             blake2b: rng.gen(),
+            emit_event: rng.gen(),
         }
     }
 }
@@ -622,6 +689,7 @@ pub mod gens {
             transfer_from_purse_to_account in host_function_cost_arb(),
             transfer_from_purse_to_purse in host_function_cost_arb(),
             get_balance in host_function_cost_arb(),
+            get_balance_with_proof in host_function_cost_arb(),
             get_phase in host_function_cost_arb(),
             get_system_contract in host_function_cost_arb(),
             get_main_purse in host_function_cost_arb(),
@@ -639,6 +707,7 @@ pub mod gens {
             remove_contract_user_group_urefs in host_function_cost_arb(),
             print in host_function_cost_arb(),
             blake2b in host_function_cost_arb(),
+            emit_event in host_function_cost_arb(),
         ) -> HostFunctionCosts {
             HostFunctionCosts {
                 read_value,
@@ -666,6 +735,7 @@ pub mod gens {
                 transfer_from_purse_to_account,
                 transfer_from_purse_to_purse,
                 get_balance,
+                get_balance_with_proof,
                 get_phase,
                 get_system_contract,
                 get_main_purse,
@@ -683,6 +753,7 @@ pub mod gens {
                 remove_contract_user_group_urefs,
                 print,
                 blake2b,
+                emit_event,
             }
         }
     }
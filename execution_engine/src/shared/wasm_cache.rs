@@ -0,0 +1,199 @@
+//! A bounded cache of preprocessed wasm modules.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use parity_wasm::elements::Module;
+
+use casper_types::bytesrepr::ToBytes;
+
+use super::{newtypes::Blake2bHash, wasm_config::WasmConfig};
+
+/// The default maximum number of preprocessed modules held by a [`WasmCache`].
+pub const DEFAULT_WASM_CACHE_CAPACITY: usize = 256;
+
+/// The key under which a preprocessed module is cached.
+///
+/// The instrumentation applied by the preprocessor depends on the wasm config, so the hash of the
+/// config is part of the key alongside the hash of the original module bytes.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+struct CacheKey {
+    module_hash: Blake2bHash,
+    wasm_config_hash: Blake2bHash,
+}
+
+impl CacheKey {
+    fn new(module_bytes: &[u8], wasm_config: &WasmConfig) -> Self {
+        let wasm_config_bytes = wasm_config
+            .to_bytes()
+            .expect("should serialize wasm config");
+        CacheKey {
+            module_hash: Blake2bHash::new(module_bytes),
+            wasm_config_hash: Blake2bHash::new(&wasm_config_bytes),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    modules: HashMap<CacheKey, Module>,
+    /// Keys in insertion order, used to evict the oldest entry once the capacity is reached.
+    insertion_order: VecDeque<CacheKey>,
+}
+
+/// A cache of preprocessed (deserialized and instrumented) wasm modules.
+///
+/// Cloning the cache is cheap; all clones share the same underlying storage and counters.
+#[derive(Clone, Debug)]
+pub struct WasmCache {
+    capacity: usize,
+    inner: Arc<Mutex<Inner>>,
+    hits: Arc<AtomicU64>,
+    misses: Arc<AtomicU64>,
+}
+
+impl Default for WasmCache {
+    fn default() -> Self {
+        WasmCache::new(DEFAULT_WASM_CACHE_CAPACITY)
+    }
+}
+
+impl WasmCache {
+    /// Creates a new cache holding at most `capacity` modules.
+    pub fn new(capacity: usize) -> Self {
+        WasmCache {
+            capacity,
+            inner: Default::default(),
+            hits: Default::default(),
+            misses: Default::default(),
+        }
+    }
+
+    /// Returns a clone of the module cached for `module_bytes` and `wasm_config`, if any.
+    ///
+    /// Updates the hit and miss counters accordingly.
+    pub fn get(&self, module_bytes: &[u8], wasm_config: &WasmConfig) -> Option<Module> {
+        let key = CacheKey::new(module_bytes, wasm_config);
+        let maybe_module = self.inner.lock().unwrap().modules.get(&key).cloned();
+        if maybe_module.is_some() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+        }
+        maybe_module
+    }
+
+    /// Inserts the preprocessed `module` for `module_bytes` and `wasm_config`, evicting the oldest
+    /// entry if the cache is full.
+    pub fn insert(&self, module_bytes: &[u8], wasm_config: &WasmConfig, module: Module) {
+        if self.capacity == 0 {
+            return;
+        }
+        let key = CacheKey::new(module_bytes, wasm_config);
+        let mut inner = self.inner.lock().unwrap();
+        if inner.modules.insert(key, module).is_some() {
+            return;
+        }
+        inner.insertion_order.push_back(key);
+        while inner.insertion_order.len() > self.capacity {
+            if let Some(oldest) = inner.insertion_order.pop_front() {
+                inner.modules.remove(&oldest);
+            }
+        }
+    }
+
+    /// Removes all cached modules. The hit and miss counters are left unchanged.
+    pub fn clear(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.modules = HashMap::new();
+        inner.insertion_order = VecDeque::new();
+    }
+
+    /// Returns the number of cached modules.
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().modules.len()
+    }
+
+    /// Returns `true` if no modules are cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the total number of cache hits.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Returns the total number of cache misses.
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parity_wasm::elements::{Module, ModuleNameSubsection, NameSection, Section};
+
+    use super::WasmCache;
+    use crate::shared::wasm_config::WasmConfig;
+
+    fn named_module(name: &str) -> Module {
+        let section = NameSection::new(Some(ModuleNameSubsection::new(name)), None, None);
+        Module::new(vec![Section::Name(section)])
+    }
+
+    #[test]
+    fn should_count_hits_and_misses() {
+        let cache = WasmCache::new(2);
+        let wasm_config = WasmConfig::default();
+
+        assert!(cache.get(b"a", &wasm_config).is_none());
+        cache.insert(b"a", &wasm_config, named_module("a"));
+        assert_eq!(cache.get(b"a", &wasm_config), Some(named_module("a")));
+
+        assert_eq!(cache.hits(), 1);
+        assert_eq!(cache.misses(), 1);
+    }
+
+    #[test]
+    fn should_key_by_wasm_config() {
+        let cache = WasmCache::new(2);
+        let wasm_config = WasmConfig::default();
+        let mut other_wasm_config = wasm_config;
+        other_wasm_config.max_stack_height += 1;
+
+        cache.insert(b"a", &wasm_config, named_module("a"));
+        assert!(cache.get(b"a", &other_wasm_config).is_none());
+    }
+
+    #[test]
+    fn should_evict_oldest_module() {
+        let cache = WasmCache::new(2);
+        let wasm_config = WasmConfig::default();
+
+        cache.insert(b"a", &wasm_config, named_module("a"));
+        cache.insert(b"b", &wasm_config, named_module("b"));
+        cache.insert(b"c", &wasm_config, named_module("c"));
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(b"a", &wasm_config).is_none());
+        assert!(cache.get(b"b", &wasm_config).is_some());
+        assert!(cache.get(b"c", &wasm_config).is_some());
+    }
+
+    #[test]
+    fn should_clear() {
+        let cache = WasmCache::new(2);
+        let wasm_config = WasmConfig::default();
+
+        cache.insert(b"a", &wasm_config, named_module("a"));
+        cache.clear();
+
+        assert!(cache.is_empty());
+    }
+}
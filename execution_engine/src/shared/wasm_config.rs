@@ -10,6 +10,7 @@ use super::{
 
 pub const DEFAULT_WASM_MAX_MEMORY: u32 = 64;
 pub const DEFAULT_MAX_STACK_HEIGHT: u32 = 64 * 1024;
+pub const DEFAULT_MAX_TABLE_SIZE: u32 = 4096;
 
 #[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug, DataSize)]
 pub struct WasmConfig {
@@ -17,6 +18,8 @@ pub struct WasmConfig {
     pub max_memory: u32,
     /// Max stack height (native WebAssembly stack limiter)
     pub max_stack_height: u32,
+    /// Maximum number of elements in a contract's function table.
+    pub max_table_size: u32,
     /// Wasm opcode costs table
     opcode_costs: OpcodeCosts,
     /// Storage costs
@@ -29,6 +32,7 @@ impl WasmConfig {
     pub const fn new(
         max_memory: u32,
         max_stack_height: u32,
+        max_table_size: u32,
         opcode_costs: OpcodeCosts,
         storage_costs: StorageCosts,
         host_function_costs: HostFunctionCosts,
@@ -36,6 +40,7 @@ impl WasmConfig {
         Self {
             max_memory,
             max_stack_height,
+            max_table_size,
             opcode_costs,
             storage_costs,
             host_function_costs,
@@ -60,6 +65,7 @@ impl Default for WasmConfig {
         Self {
             max_memory: DEFAULT_WASM_MAX_MEMORY,
             max_stack_height: DEFAULT_MAX_STACK_HEIGHT,
+            max_table_size: DEFAULT_MAX_TABLE_SIZE,
             opcode_costs: OpcodeCosts::default(),
             storage_costs: StorageCosts::default(),
             host_function_costs: HostFunctionCosts::default(),
@@ -73,6 +79,7 @@ impl ToBytes for WasmConfig {
 
         ret.append(&mut self.max_memory.to_bytes()?);
         ret.append(&mut self.max_stack_height.to_bytes()?);
+        ret.append(&mut self.max_table_size.to_bytes()?);
         ret.append(&mut self.opcode_costs.to_bytes()?);
         ret.append(&mut self.storage_costs.to_bytes()?);
         ret.append(&mut self.host_function_costs.to_bytes()?);
@@ -83,6 +90,7 @@ impl ToBytes for WasmConfig {
     fn serialized_length(&self) -> usize {
         self.max_memory.serialized_length()
             + self.max_stack_height.serialized_length()
+            + self.max_table_size.serialized_length()
             + self.opcode_costs.serialized_length()
             + self.storage_costs.serialized_length()
             + self.host_function_costs.serialized_length()
@@ -93,6 +101,7 @@ impl FromBytes for WasmConfig {
     fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
         let (max_memory, rem) = FromBytes::from_bytes(bytes)?;
         let (max_stack_height, rem) = FromBytes::from_bytes(rem)?;
+        let (max_table_size, rem) = FromBytes::from_bytes(rem)?;
         let (opcode_costs, rem) = FromBytes::from_bytes(rem)?;
         let (storage_costs, rem) = FromBytes::from_bytes(rem)?;
         let (host_function_costs, rem) = FromBytes::from_bytes(rem)?;
@@ -101,6 +110,44 @@ impl FromBytes for WasmConfig {
             WasmConfig {
                 max_memory,
                 max_stack_height,
+                max_table_size,
+                opcode_costs,
+                storage_costs,
+                host_function_costs,
+            },
+            rem,
+        ))
+    }
+}
+
+impl WasmConfig {
+    /// Serializes the wasm config in the layout used before `max_table_size` was added.
+    #[cfg(test)]
+    pub(crate) fn to_legacy_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut ret = Vec::new();
+        ret.append(&mut self.max_memory.to_bytes()?);
+        ret.append(&mut self.max_stack_height.to_bytes()?);
+        ret.append(&mut self.opcode_costs.to_bytes()?);
+        ret.append(&mut self.storage_costs.to_bytes()?);
+        ret.append(&mut self.host_function_costs.to_legacy_bytes()?);
+        Ok(ret)
+    }
+
+    /// Deserializes a wasm config written before `max_table_size` was added, using the default
+    /// maximum table size and the legacy host function costs layout.
+    pub(crate) fn from_legacy_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (max_memory, rem) = FromBytes::from_bytes(bytes)?;
+        let (max_stack_height, rem) = FromBytes::from_bytes(rem)?;
+        let max_table_size = DEFAULT_MAX_TABLE_SIZE;
+        let (opcode_costs, rem) = FromBytes::from_bytes(rem)?;
+        let (storage_costs, rem) = FromBytes::from_bytes(rem)?;
+        let (host_function_costs, rem) = HostFunctionCosts::from_legacy_bytes(rem)?;
+
+        Ok((
+            WasmConfig {
+                max_memory,
+                max_stack_height,
+                max_table_size,
                 opcode_costs,
                 storage_costs,
                 host_function_costs,
@@ -115,6 +162,7 @@ impl Distribution<WasmConfig> for Standard {
         WasmConfig {
             max_memory: rng.gen(),
             max_stack_height: rng.gen(),
+            max_table_size: rng.gen(),
             opcode_costs: rng.gen(),
             storage_costs: rng.gen(),
             host_function_costs: rng.gen(),
@@ -136,6 +184,7 @@ pub mod gens {
         pub fn wasm_config_arb() (
             max_memory in num::u32::ANY,
             max_stack_height in num::u32::ANY,
+            max_table_size in num::u32::ANY,
             opcode_costs in opcode_costs_arb(),
             storage_costs in storage_costs_arb(),
             host_function_costs in host_function_costs_arb(),
@@ -143,6 +192,7 @@ pub mod gens {
             WasmConfig {
                 max_memory,
                 max_stack_height,
+                max_table_size,
                 opcode_costs,
                 storage_costs,
                 host_function_costs,
@@ -1,10 +1,10 @@
 use std::fmt::{self, Display, Formatter};
 
-use parity_wasm::elements::{self, MemorySection, Module, Section};
+use parity_wasm::elements::{self, External, MemorySection, Module, Section, TableType};
 use pwasm_utils::{self, stack_height};
 use thiserror::Error;
 
-use super::wasm_config::WasmConfig;
+use super::{wasm_cache::WasmCache, wasm_config::WasmConfig};
 
 const DEFAULT_GAS_MODULE_NAME: &str = "env";
 
@@ -14,6 +14,7 @@ pub enum PreprocessingError {
     OperationForbiddenByGasRules,
     StackLimiter,
     MissingMemorySection,
+    TableSizeExceeded { max_table_size: u32, actual: u32 },
 }
 
 impl From<elements::Error> for PreprocessingError {
@@ -29,6 +30,7 @@ impl Display for PreprocessingError {
             PreprocessingError::OperationForbiddenByGasRules => write!(f, "Encountered operation forbidden by gas rules. Consult instruction -> metering config map"),
             PreprocessingError::StackLimiter => write!(f, "Stack limiter error"),
             PreprocessingError::MissingMemorySection => write!(f, "Memory section should exist"),
+            PreprocessingError::TableSizeExceeded { max_table_size, actual } => write!(f, "Table size of {} exceeds the maximum of {}", actual, max_table_size),
         }
     }
 }
@@ -43,16 +45,78 @@ fn memory_section(module: &Module) -> Option<&MemorySection> {
     None
 }
 
+/// Returns the types of all tables defined or imported by the given wasm module.
+fn table_types(module: &Module) -> impl Iterator<Item = &TableType> {
+    let defined = module
+        .table_section()
+        .into_iter()
+        .flat_map(|section| section.entries());
+    let imported = module
+        .import_section()
+        .into_iter()
+        .flat_map(|section| section.entries())
+        .filter_map(|entry| match entry.external() {
+            External::Table(table_type) => Some(table_type),
+            _ => None,
+        });
+    defined.chain(imported)
+}
+
+/// Checks that no table in the given wasm module can grow beyond `max_table_size` elements.
+fn ensure_table_size_limit(module: &Module, max_table_size: u32) -> Result<(), PreprocessingError> {
+    for table_type in table_types(module) {
+        // Tables can't be grown from within a module, so without a declared maximum the initial
+        // size is all that can ever be allocated.
+        let limits = table_type.limits();
+        let actual = limits
+            .maximum()
+            .map_or(limits.initial(), |maximum| maximum.max(limits.initial()));
+        if actual > max_table_size {
+            return Err(PreprocessingError::TableSizeExceeded {
+                max_table_size,
+                actual,
+            });
+        }
+    }
+    Ok(())
+}
+
 pub struct Preprocessor {
     wasm_config: WasmConfig,
+    cache: Option<WasmCache>,
 }
 
 impl Preprocessor {
     pub fn new(wasm_config: WasmConfig) -> Self {
-        Self { wasm_config }
+        Self {
+            wasm_config,
+            cache: None,
+        }
+    }
+
+    /// Returns a preprocessor which looks up and stores preprocessed modules in `cache`.
+    pub fn with_cache(wasm_config: WasmConfig, cache: WasmCache) -> Self {
+        Self {
+            wasm_config,
+            cache: Some(cache),
+        }
     }
 
     pub fn preprocess(&self, module_bytes: &[u8]) -> Result<Module, PreprocessingError> {
+        let cache = match &self.cache {
+            Some(cache) => cache,
+            None => return self.instrument(module_bytes),
+        };
+        if let Some(module) = cache.get(module_bytes, &self.wasm_config) {
+            return Ok(module);
+        }
+        let module = self.instrument(module_bytes)?;
+        cache.insert(module_bytes, &self.wasm_config, module.clone());
+        Ok(module)
+    }
+
+    /// Deserializes the module and injects the gas counter and stack height limiter.
+    fn instrument(&self, module_bytes: &[u8]) -> Result<Module, PreprocessingError> {
         let module = deserialize(module_bytes)?;
 
         if memory_section(&module).is_none() {
@@ -61,6 +125,8 @@ impl Preprocessor {
             return Err(PreprocessingError::MissingMemorySection);
         }
 
+        ensure_table_size_limit(&module, self.wasm_config.max_table_size)?;
+
         let module = pwasm_utils::externalize_mem(module, None, self.wasm_config.max_memory);
         let module = pwasm_utils::inject_gas_counter(
             module,
@@ -78,3 +144,108 @@ impl Preprocessor {
 pub fn deserialize(module_bytes: &[u8]) -> Result<Module, PreprocessingError> {
     parity_wasm::deserialize_buffer::<Module>(module_bytes).map_err(Into::into)
 }
+
+#[cfg(test)]
+mod tests {
+    use parity_wasm::{
+        builder,
+        elements::{Instruction, Instructions},
+    };
+
+    use super::*;
+    use crate::shared::{
+        host_function_costs::HostFunctionCosts,
+        opcode_costs::OpcodeCosts,
+        storage_costs::StorageCosts,
+        wasm_config::{DEFAULT_MAX_STACK_HEIGHT, DEFAULT_MAX_TABLE_SIZE, DEFAULT_WASM_MAX_MEMORY},
+    };
+
+    fn make_module_with_table(initial: u32, maximum: Option<u32>) -> Vec<u8> {
+        let module = builder::module()
+            .function()
+            .signature()
+            .build()
+            .body()
+            .build()
+            .build()
+            .memory()
+            .build()
+            .table()
+            .with_min(initial)
+            .with_max(maximum)
+            .build()
+            .build();
+        parity_wasm::serialize(module).expect("should serialize")
+    }
+
+    #[test]
+    fn should_accept_table_within_limit() {
+        let wasm_config = WasmConfig::default();
+        let module_bytes = make_module_with_table(1, Some(wasm_config.max_table_size));
+        assert!(Preprocessor::new(wasm_config)
+            .preprocess(&module_bytes)
+            .is_ok());
+    }
+
+    #[test]
+    fn should_reject_table_exceeding_limit() {
+        let wasm_config = WasmConfig::default();
+        let too_large = wasm_config.max_table_size + 1;
+        for module_bytes in &[
+            make_module_with_table(too_large, None),
+            make_module_with_table(1, Some(too_large)),
+        ] {
+            let error = Preprocessor::new(wasm_config)
+                .preprocess(module_bytes)
+                .expect_err("should reject table");
+            assert!(matches!(
+                error,
+                PreprocessingError::TableSizeExceeded { actual, .. } if actual == too_large
+            ));
+        }
+    }
+
+    #[test]
+    fn should_charge_configured_opcode_costs() {
+        const NOP_COST: u32 = 1_234;
+
+        let module = builder::module()
+            .function()
+            .signature()
+            .build()
+            .body()
+            .with_instructions(Instructions::new(vec![Instruction::Nop, Instruction::End]))
+            .build()
+            .build()
+            .memory()
+            .build()
+            .build();
+        let module_bytes = parity_wasm::serialize(module).expect("should serialize");
+
+        let opcode_costs = OpcodeCosts {
+            nop: NOP_COST,
+            control_flow: 0,
+            ..Default::default()
+        };
+        let wasm_config = WasmConfig::new(
+            DEFAULT_WASM_MAX_MEMORY,
+            DEFAULT_MAX_STACK_HEIGHT,
+            DEFAULT_MAX_TABLE_SIZE,
+            opcode_costs,
+            StorageCosts::default(),
+            HostFunctionCosts::default(),
+        );
+        let module = Preprocessor::new(wasm_config)
+            .preprocess(&module_bytes)
+            .expect("should preprocess");
+
+        let body = &module.code_section().expect("should have code").bodies()[0];
+        assert!(
+            body.code()
+                .elements()
+                .contains(&Instruction::I32Const(NOP_COST as i32)),
+            "the gas counter should charge the configured cost: {:?}",
+            body.code().elements()
+        );
+    }
+}
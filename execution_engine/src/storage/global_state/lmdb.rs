@@ -268,6 +268,7 @@ mod tests {
 
     use super::*;
     use crate::storage::{
+        global_state::{commit_in_parallel, commit_serially},
         trie_store::operations::{write, WriteResult},
         DEFAULT_TEST_MAX_DB_SIZE, DEFAULT_TEST_MAX_READERS,
     };
@@ -443,4 +444,62 @@ mod tests {
                 .unwrap()
         );
     }
+
+    #[test]
+    fn commit_in_parallel_matches_commit_serially() {
+        let correlation_id = CorrelationId::new();
+        let (state, root_hash) = create_test_state();
+
+        let mut effects: AdditiveMap<Key, Transform> = AdditiveMap::new();
+        for TestPair { key, value } in &create_test_pairs_updated() {
+            effects.insert(*key, Transform::Write(value.to_owned()));
+        }
+        for i in 0..2_000_u32 {
+            let mut bytes = [0; 32];
+            bytes[..4].copy_from_slice(&i.to_le_bytes());
+            let value = StoredValue::CLValue(CLValue::from_t(i).unwrap());
+            effects.insert(
+                Key::Account(AccountHash::new(bytes)),
+                Transform::Write(value.clone()),
+            );
+            effects.insert(Key::Hash(bytes), Transform::Write(value));
+        }
+
+        let serial_hash = match commit_serially::<_, _, _, error::Error>(
+            state.environment.deref(),
+            state.trie_store.deref(),
+            correlation_id,
+            root_hash,
+            effects.clone(),
+        )
+        .unwrap()
+        {
+            CommitResult::Success { state_root } => state_root,
+            _ => panic!("commit failed"),
+        };
+        let parallel_hash = match commit_in_parallel::<_, _, _, error::Error>(
+            state.environment.deref(),
+            state.trie_store.deref(),
+            correlation_id,
+            root_hash,
+            effects.clone(),
+        )
+        .unwrap()
+        {
+            CommitResult::Success { state_root } => state_root,
+            _ => panic!("commit failed"),
+        };
+        assert_eq!(serial_hash, parallel_hash);
+
+        let checkout = state.checkout(parallel_hash).unwrap().unwrap();
+        for (key, transform) in effects.iter() {
+            match transform {
+                Transform::Write(value) => assert_eq!(
+                    Some(value.to_owned()),
+                    checkout.read(correlation_id, key).unwrap()
+                ),
+                _ => unreachable!(),
+            }
+        }
+    }
 }
@@ -1,5 +1,6 @@
 pub mod in_memory;
 pub mod lmdb;
+mod parallel_commit;
 
 use std::{fmt, hash::BuildHasher};
 
@@ -14,7 +15,7 @@ use casper_types::{bytesrepr, Key, ProtocolVersion};
 
 use crate::storage::{
     protocol_data::ProtocolData,
-    transaction_source::{Transaction, TransactionSource},
+    transaction_source::{Readable, Transaction, TransactionSource, Writable},
     trie::{merkle_proof::TrieMerkleProof, Trie},
     trie_store::{
         operations::{read, write, ReadResult, WriteResult},
@@ -22,6 +23,8 @@ use crate::storage::{
     },
 };
 
+pub use self::parallel_commit::commit_in_parallel;
+
 /// A reader of state
 pub trait StateReader<K, V> {
     /// An error which occurs when reading state
@@ -132,6 +135,14 @@ pub trait StateProvider {
     ) -> Result<Vec<Blake2bHash>, Self::Error>;
 }
 
+/// The minimum number of effects for a commit to update disjoint subtries in parallel.
+const PARALLEL_COMMIT_THRESHOLD: usize = 1_000;
+
+/// Applies `effects` to the state at `prestate_hash` and returns the new state root.
+///
+/// Large sets of effects, such as those of genesis or of an era end, are applied in parallel via
+/// [`commit_in_parallel`], the rest one by one via [`commit_serially`].  Both yield the same state
+/// root.
 pub fn commit<'a, R, S, H, E>(
     environment: &'a R,
     store: &S,
@@ -139,6 +150,28 @@ pub fn commit<'a, R, S, H, E>(
     prestate_hash: Blake2bHash,
     effects: AdditiveMap<Key, Transform, H>,
 ) -> Result<CommitResult, E>
+where
+    R: TransactionSource<'a, Handle = S::Handle> + Sync,
+    S: TrieStore<Key, StoredValue> + Sync,
+    S::Error: From<R::Error>,
+    E: From<R::Error> + From<S::Error> + From<bytesrepr::Error> + Send,
+    H: BuildHasher,
+{
+    if effects.len() >= PARALLEL_COMMIT_THRESHOLD {
+        commit_in_parallel::<R, S, H, E>(environment, store, correlation_id, prestate_hash, effects)
+    } else {
+        commit_serially::<R, S, H, E>(environment, store, correlation_id, prestate_hash, effects)
+    }
+}
+
+/// Applies `effects` one by one to the state at `prestate_hash` and returns the new state root.
+pub fn commit_serially<'a, R, S, H, E>(
+    environment: &'a R,
+    store: &S,
+    correlation_id: CorrelationId,
+    prestate_hash: Blake2bHash,
+    effects: AdditiveMap<Key, Transform, H>,
+) -> Result<CommitResult, E>
 where
     R: TransactionSource<'a, Handle = S::Handle>,
     S: TrieStore<Key, StoredValue>,
@@ -156,33 +189,61 @@ where
     };
 
     for (key, transform) in effects.into_iter() {
-        let read_result = read::<_, _, _, _, E>(correlation_id, &txn, store, &state_root, &key)?;
-
-        let value = match (read_result, transform) {
-            (ReadResult::NotFound, Transform::Write(new_value)) => new_value,
-            (ReadResult::NotFound, _) => {
-                return Ok(CommitResult::KeyNotFound(key));
-            }
-            (ReadResult::Found(current_value), transform) => match transform.apply(current_value) {
-                Ok(updated_value) => updated_value,
-                Err(err) => return Ok(err.into()),
-            },
-            _x @ (ReadResult::RootNotFound, _) => panic!(stringify!(_x._1)),
+        state_root = match apply_transform::<_, _, E>(
+            correlation_id,
+            &mut txn,
+            store,
+            state_root,
+            &key,
+            transform,
+        )? {
+            Ok(state_root) => state_root,
+            Err(commit_result) => return Ok(commit_result),
         };
-
-        let write_result =
-            write::<_, _, _, _, E>(correlation_id, &mut txn, store, &state_root, &key, &value)?;
-
-        match write_result {
-            WriteResult::Written(root_hash) => {
-                state_root = root_hash;
-            }
-            WriteResult::AlreadyExists => (),
-            _x @ WriteResult::RootNotFound => panic!(stringify!(_x)),
-        }
     }
 
     txn.commit()?;
 
     Ok(CommitResult::Success { state_root })
 }
+
+/// Applies `transform` to the value under `key` in the trie at `state_root`.
+///
+/// Returns the new state root, or the reason the transform couldn't be applied.
+fn apply_transform<T, S, E>(
+    correlation_id: CorrelationId,
+    txn: &mut T,
+    store: &S,
+    state_root: Blake2bHash,
+    key: &Key,
+    transform: Transform,
+) -> Result<Result<Blake2bHash, CommitResult>, E>
+where
+    T: Readable<Handle = S::Handle> + Writable<Handle = S::Handle>,
+    S: TrieStore<Key, StoredValue>,
+    S::Error: From<T::Error>,
+    E: From<S::Error> + From<bytesrepr::Error>,
+{
+    let read_result = read::<_, _, _, _, E>(correlation_id, &*txn, store, &state_root, key)?;
+
+    let value = match (read_result, transform) {
+        (ReadResult::NotFound, Transform::Write(new_value)) => new_value,
+        (ReadResult::NotFound, _) => {
+            return Ok(Err(CommitResult::KeyNotFound(*key)));
+        }
+        (ReadResult::Found(current_value), transform) => match transform.apply(current_value) {
+            Ok(updated_value) => updated_value,
+            Err(err) => return Ok(Err(err.into())),
+        },
+        _x @ (ReadResult::RootNotFound, _) => panic!(stringify!(_x._1)),
+    };
+
+    let write_result =
+        write::<_, _, _, _, E>(correlation_id, txn, store, &state_root, key, &value)?;
+
+    match write_result {
+        WriteResult::Written(root_hash) => Ok(Ok(root_hash)),
+        WriteResult::AlreadyExists => Ok(Ok(state_root)),
+        _x @ WriteResult::RootNotFound => panic!(stringify!(_x)),
+    }
+}
@@ -0,0 +1,388 @@
+//! Commits large sets of effects by updating disjoint subtries of global state in parallel.
+//!
+//! The effects are split by the leading bytes of their keys among the children of the root node,
+//! and further among the children of nodes right below it, into subtries which don't share any
+//! tries.  Each subtrie is updated on its own thread against a read transaction, buffering the new
+//! tries in memory.  The nodes above the subtries are then rehashed, and all new tries are written
+//! in a single read-write transaction.
+//!
+//! As the shape of the trie doesn't depend on the order in which keys are written, the resulting
+//! state root hash is identical to the one produced by applying the effects one by one.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::BuildHasher,
+};
+
+use rayon::prelude::*;
+
+use casper_types::{
+    bytesrepr::{self, Bytes, ToBytes},
+    Key,
+};
+
+use crate::{
+    shared::{
+        additive_map::AdditiveMap,
+        newtypes::{Blake2bHash, CorrelationId},
+        stored_value::StoredValue,
+        transform::Transform,
+    },
+    storage::{
+        global_state::{apply_transform, commit_serially, CommitResult},
+        store::Store,
+        transaction_source::{Readable, Transaction, TransactionSource, Writable},
+        trie::{Pointer, PointerBlock, Trie},
+        trie_store::TrieStore,
+    },
+};
+
+/// The maximum depth down to which effects are split among the children of a node.
+const MAX_SPLIT_DEPTH: usize = 2;
+
+/// The minimum number of effects below a node for them to be split among its children.
+const MIN_SPLIT_EFFECTS: usize = 256;
+
+/// An effect along with the serialized key, i.e. the key's path in the trie.
+type PathedEffect = (Vec<u8>, Key, Transform);
+
+/// The effects whose paths start with `prefix`, to be applied to the subtrie below it.
+struct Subtrie {
+    prefix: Vec<u8>,
+    pointer: Option<Pointer>,
+    effects: Vec<PathedEffect>,
+}
+
+/// A subtrie with its effects applied.
+struct UpdatedSubtrie {
+    prefix: Vec<u8>,
+    pointer: Option<Pointer>,
+    new_tries: HashMap<Vec<u8>, Vec<u8>>,
+}
+
+/// A transaction which buffers writes in memory on top of a read transaction.
+///
+/// All writes are buffered together regardless of their handle, so it must only be used with a
+/// single store.
+struct ScratchTransaction<'t, T> {
+    txn: &'t T,
+    written: HashMap<Vec<u8>, Vec<u8>>,
+}
+
+impl<'t, T> ScratchTransaction<'t, T> {
+    fn new(txn: &'t T) -> Self {
+        ScratchTransaction {
+            txn,
+            written: HashMap::new(),
+        }
+    }
+
+    fn into_written(self) -> HashMap<Vec<u8>, Vec<u8>> {
+        self.written
+    }
+}
+
+impl<'t, T: Transaction> Transaction for ScratchTransaction<'t, T> {
+    type Error = T::Error;
+
+    type Handle = T::Handle;
+
+    fn commit(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<'t, T: Readable> Readable for ScratchTransaction<'t, T> {
+    fn read(&self, handle: Self::Handle, key: &[u8]) -> Result<Option<Bytes>, Self::Error> {
+        match self.written.get(key) {
+            Some(value) => Ok(Some(Bytes::from(value.clone()))),
+            None => self.txn.read(handle, key),
+        }
+    }
+}
+
+impl<'t, T: Readable> Writable for ScratchTransaction<'t, T> {
+    fn write(
+        &mut self,
+        _handle: Self::Handle,
+        key: &[u8],
+        value: &[u8],
+    ) -> Result<(), Self::Error> {
+        let _ = self.written.insert(key.to_vec(), value.to_vec());
+        Ok(())
+    }
+}
+
+/// Applies `effects` to the state at `prestate_hash`, updating disjoint subtries in parallel.
+///
+/// Falls back to [`commit_serially`] if the state's root is not a node.
+pub fn commit_in_parallel<'a, R, S, H, E>(
+    environment: &'a R,
+    store: &S,
+    correlation_id: CorrelationId,
+    prestate_hash: Blake2bHash,
+    effects: AdditiveMap<Key, Transform, H>,
+) -> Result<CommitResult, E>
+where
+    R: TransactionSource<'a, Handle = S::Handle> + Sync,
+    S: TrieStore<Key, StoredValue> + Sync,
+    S::Error: From<R::Error>,
+    E: From<R::Error> + From<S::Error> + From<bytesrepr::Error> + Send,
+    H: BuildHasher,
+{
+    let txn = environment.create_read_txn()?;
+    let root_pointer_block = match store.get(&txn, &prestate_hash)? {
+        None => return Ok(CommitResult::RootNotFound),
+        Some(Trie::Node { pointer_block }) => *pointer_block,
+        Some(_) => {
+            txn.commit()?;
+            return commit_serially::<R, S, H, E>(
+                environment,
+                store,
+                correlation_id,
+                prestate_hash,
+                effects,
+            );
+        }
+    };
+
+    let mut pathed_effects = Vec::with_capacity(effects.len());
+    for (key, transform) in effects.into_iter() {
+        pathed_effects.push((key.to_bytes()?, key, transform));
+    }
+    let mut nodes = Vec::new();
+    let mut subtries = Vec::new();
+    split::<_, _, E>(
+        &txn,
+        store,
+        Vec::new(),
+        root_pointer_block,
+        pathed_effects,
+        &mut nodes,
+        &mut subtries,
+    )?;
+    txn.commit()?;
+
+    let updated_subtries = subtries
+        .into_par_iter()
+        .map(|subtrie| -> Result<_, E> {
+            let txn = environment.create_read_txn()?;
+            let result = update_subtrie::<_, _, E>(correlation_id, &txn, store, subtrie)?;
+            txn.commit()?;
+            Ok(result)
+        })
+        .collect::<Result<Vec<_>, E>>()?;
+
+    let mut new_tries = HashMap::new();
+    let mut new_pointers = BTreeMap::new();
+    for result in updated_subtries {
+        let updated_subtrie = match result {
+            Ok(updated_subtrie) => updated_subtrie,
+            Err(commit_result) => return Ok(commit_result),
+        };
+        new_tries.extend(updated_subtrie.new_tries);
+        if let Some(pointer) = updated_subtrie.pointer {
+            let _ = new_pointers.insert(updated_subtrie.prefix, pointer);
+        }
+    }
+
+    // Rehash the nodes above the subtries, children before their parents.
+    let mut state_root = prestate_hash;
+    for (prefix, mut pointer_block) in nodes {
+        for (child_prefix, pointer) in new_pointers.range(prefix.clone()..) {
+            if !child_prefix.starts_with(&prefix) {
+                break;
+            }
+            if child_prefix.len() == prefix.len() + 1 {
+                pointer_block[usize::from(child_prefix[prefix.len()])] = Some(*pointer);
+            }
+        }
+        let node = Trie::<Key, StoredValue>::Node {
+            pointer_block: Box::new(pointer_block),
+        };
+        let node_bytes = node.to_bytes()?;
+        let node_hash = Blake2bHash::new(&node_bytes);
+        let _ = new_tries.insert(node_hash.to_bytes()?, node_bytes);
+        if prefix.is_empty() {
+            state_root = node_hash;
+        } else {
+            let _ = new_pointers.insert(prefix, Pointer::NodePointer(node_hash));
+        }
+    }
+
+    let mut txn = environment.create_read_write_txn()?;
+    for (key, value) in new_tries {
+        txn.write(store.handle(), &key, &value)?;
+    }
+    txn.commit()?;
+
+    Ok(CommitResult::Success { state_root })
+}
+
+/// Splits `effects`, whose paths all start with `prefix`, among the children of the node at
+/// `prefix`.
+///
+/// Nodes which effects are split below are pushed to `nodes` after their children, and the
+/// resulting subtries to `subtries`.
+fn split<T, S, E>(
+    txn: &T,
+    store: &S,
+    prefix: Vec<u8>,
+    pointer_block: PointerBlock,
+    effects: Vec<PathedEffect>,
+    nodes: &mut Vec<(Vec<u8>, PointerBlock)>,
+    subtries: &mut Vec<Subtrie>,
+) -> Result<(), E>
+where
+    T: Readable<Handle = S::Handle>,
+    S: TrieStore<Key, StoredValue>,
+    S::Error: From<T::Error>,
+    E: From<S::Error>,
+{
+    let depth = prefix.len();
+    let mut effects_by_index: BTreeMap<u8, Vec<PathedEffect>> = BTreeMap::new();
+    for effect in effects {
+        effects_by_index
+            .entry(effect.0[depth])
+            .or_default()
+            .push(effect);
+    }
+
+    for (index, effects) in effects_by_index {
+        let mut child_prefix = prefix.clone();
+        child_prefix.push(index);
+        let pointer = pointer_block[usize::from(index)];
+        match splittable_node::<T, S, E>(txn, store, child_prefix.len(), pointer, &effects)? {
+            Some(child_pointer_block) => split::<T, S, E>(
+                txn,
+                store,
+                child_prefix,
+                child_pointer_block,
+                effects,
+                nodes,
+                subtries,
+            )?,
+            None => subtries.push(Subtrie {
+                prefix: child_prefix,
+                pointer,
+                effects,
+            }),
+        }
+    }
+
+    nodes.push((prefix, pointer_block));
+    Ok(())
+}
+
+/// Returns the pointer block of the node at `depth` which `pointer` points to, if `effects`
+/// should be split among its children.
+///
+/// An empty subtrie is treated as an empty node if the paths of `effects` diverge at `depth`, as
+/// writing them creates a node there.
+fn splittable_node<T, S, E>(
+    txn: &T,
+    store: &S,
+    depth: usize,
+    pointer: Option<Pointer>,
+    effects: &[PathedEffect],
+) -> Result<Option<PointerBlock>, E>
+where
+    T: Readable<Handle = S::Handle>,
+    S: TrieStore<Key, StoredValue>,
+    S::Error: From<T::Error>,
+    E: From<S::Error>,
+{
+    if depth >= MAX_SPLIT_DEPTH
+        || effects.len() < MIN_SPLIT_EFFECTS
+        || effects.iter().any(|(path, ..)| path.len() <= depth)
+    {
+        return Ok(None);
+    }
+
+    match pointer {
+        Some(Pointer::NodePointer(hash)) => match store.get(txn, &hash)? {
+            Some(Trie::Node { pointer_block }) => Ok(Some(*pointer_block)),
+            _ => Ok(None),
+        },
+        Some(Pointer::LeafPointer(_)) => Ok(None),
+        None => {
+            let first_index = effects[0].0[depth];
+            if effects.iter().any(|(path, ..)| path[depth] != first_index) {
+                Ok(Some(PointerBlock::new()))
+            } else {
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// Applies the effects of `subtrie`, buffering the new tries in memory.
+///
+/// The effects are applied to a temporary trie whose nodes lead straight to the subtrie, so that
+/// the regular trie operations can be used.  Like the tries superseded while applying the effects,
+/// these temporary nodes are not reachable from any state root.
+fn update_subtrie<T, S, E>(
+    correlation_id: CorrelationId,
+    txn: &T,
+    store: &S,
+    subtrie: Subtrie,
+) -> Result<Result<UpdatedSubtrie, CommitResult>, E>
+where
+    T: Readable<Handle = S::Handle>,
+    S: TrieStore<Key, StoredValue>,
+    S::Error: From<T::Error>,
+    E: From<S::Error> + From<bytesrepr::Error>,
+{
+    let Subtrie {
+        prefix,
+        pointer,
+        effects,
+    } = subtrie;
+    let mut scratch = ScratchTransaction::new(txn);
+
+    let mut root_pointer = pointer;
+    for index in prefix.iter().rev() {
+        let indexed_pointers: Vec<(u8, Pointer)> = root_pointer
+            .into_iter()
+            .map(|pointer| (*index, pointer))
+            .collect();
+        let node = Trie::<Key, StoredValue>::node(&indexed_pointers);
+        let node_hash = Blake2bHash::new(&node.to_bytes()?);
+        store.put(&mut scratch, &node_hash, &node)?;
+        root_pointer = Some(Pointer::NodePointer(node_hash));
+    }
+    let mut state_root = root_pointer
+        .expect("subtries should have a non-empty prefix")
+        .into_hash();
+
+    for (_, key, transform) in effects {
+        state_root = match apply_transform::<_, _, E>(
+            correlation_id,
+            &mut scratch,
+            store,
+            state_root,
+            &key,
+            transform,
+        )? {
+            Ok(state_root) => state_root,
+            Err(commit_result) => return Ok(Err(commit_result)),
+        };
+    }
+
+    let mut pointer = Some(Pointer::NodePointer(state_root));
+    for index in &prefix {
+        pointer = match pointer {
+            Some(Pointer::NodePointer(hash)) => match store.get(&scratch, &hash)? {
+                Some(Trie::Node { pointer_block }) => pointer_block[usize::from(*index)],
+                _ => unreachable!("temporary nodes should be stored nodes"),
+            },
+            _ => unreachable!("temporary nodes should point to nodes"),
+        };
+    }
+
+    Ok(Ok(UpdatedSubtrie {
+        prefix,
+        pointer,
+        new_tries: scratch.into_written(),
+    }))
+}
@@ -2,6 +2,7 @@ use std::collections::BTreeMap;
 
 use casper_types::{
     bytesrepr::{self, FromBytes, ToBytes},
+    system::{SystemContractRegistry, AUCTION, HANDLE_PAYMENT, MINT, STANDARD_PAYMENT},
     ContractHash, HashAddr,
 };
 
@@ -111,6 +112,17 @@ impl ProtocolData {
         self.auction
     }
 
+    /// Returns the registry of system contract hashes by name, which is stored in global state
+    /// under `Key::SystemContractRegistry`.
+    pub fn system_contract_registry(&self) -> SystemContractRegistry {
+        let mut registry = SystemContractRegistry::new();
+        registry.insert(MINT.to_string(), self.mint);
+        registry.insert(HANDLE_PAYMENT.to_string(), self.handle_payment);
+        registry.insert(STANDARD_PAYMENT.to_string(), self.standard_payment);
+        registry.insert(AUCTION.to_string(), self.auction);
+        registry
+    }
+
     /// Retrieves all valid system contracts stored in protocol version
     pub fn system_contracts(&self) -> Vec<ContractHash> {
         let mut vec = Vec::with_capacity(4);
@@ -173,7 +185,36 @@ impl ToBytes for ProtocolData {
 
 impl FromBytes for ProtocolData {
     fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
-        let (wasm_config, rem) = WasmConfig::from_bytes(bytes)?;
+        match Self::from_bytes_with(bytes, WasmConfig::from_bytes) {
+            // Protocol data written before `max_table_size`, `get_balance_with_proof` and
+            // `emit_event` were added to the wasm config is too short for the current layout, so
+            // it is decoded using the legacy layout instead, giving the new fields their defaults.
+            Err(error) => Self::from_bytes_with(bytes, WasmConfig::from_legacy_bytes)
+                .map_err(|_legacy_error| error),
+            result => result,
+        }
+    }
+}
+
+impl ProtocolData {
+    /// Serializes the protocol data in the layout used before `max_table_size`,
+    /// `get_balance_with_proof` and `emit_event` were added to the wasm config.
+    #[cfg(test)]
+    fn to_legacy_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut ret = self.wasm_config.to_legacy_bytes()?;
+        ret.append(&mut self.system_config.to_bytes()?);
+        ret.append(&mut self.mint.to_bytes()?);
+        ret.append(&mut self.handle_payment.to_bytes()?);
+        ret.append(&mut self.standard_payment.to_bytes()?);
+        ret.append(&mut self.auction.to_bytes()?);
+        Ok(ret)
+    }
+
+    fn from_bytes_with<'a>(
+        bytes: &'a [u8],
+        wasm_config_from_bytes: fn(&[u8]) -> Result<(WasmConfig, &[u8]), bytesrepr::Error>,
+    ) -> Result<(Self, &'a [u8]), bytesrepr::Error> {
+        let (wasm_config, rem) = wasm_config_from_bytes(bytes)?;
         let (system_config, rem) = FromBytes::from_bytes(rem)?;
         let (mint, rem) = HashAddr::from_bytes(rem)?;
         let (handle_payment, rem) = HashAddr::from_bytes(rem)?;
@@ -231,7 +272,10 @@ mod tests {
     use proptest::proptest;
 
     use crate::shared::{system_config::SystemConfig, wasm_config::WasmConfig};
-    use casper_types::{bytesrepr, ContractHash};
+    use casper_types::{
+        bytesrepr::{self, ToBytes},
+        ContractHash,
+    };
 
     use super::{gens, ProtocolData};
 
@@ -301,6 +345,23 @@ mod tests {
         assert_eq!(actual[2], auction_reference);
     }
 
+    #[test]
+    fn should_deserialize_legacy_layout_with_defaults() {
+        let protocol_data = ProtocolData::new(
+            WasmConfig::default(),
+            SystemConfig::default(),
+            [1u8; 32].into(),
+            [2u8; 32].into(),
+            [3u8; 32].into(),
+            [4u8; 32].into(),
+        );
+        let legacy_bytes = protocol_data.to_legacy_bytes().unwrap();
+        assert!(legacy_bytes.len() < protocol_data.serialized_length());
+
+        let deserialized: ProtocolData = bytesrepr::deserialize(legacy_bytes).unwrap();
+        assert_eq!(deserialized, protocol_data);
+    }
+
     proptest! {
         #[test]
         fn should_serialize_and_deserialize_with_arbitrary_values(
@@ -308,5 +369,20 @@ mod tests {
         ) {
             bytesrepr::test_serialization_roundtrip(&protocol_data);
         }
+
+        #[test]
+        fn should_deserialize_arbitrary_legacy_layout(
+            protocol_data in gens::protocol_data_arb()
+        ) {
+            let legacy_bytes = protocol_data.to_legacy_bytes().unwrap();
+            let deserialized: ProtocolData = bytesrepr::deserialize(legacy_bytes).unwrap();
+            assert_eq!(deserialized.mint(), protocol_data.mint());
+            assert_eq!(deserialized.auction(), protocol_data.auction());
+            assert_eq!(deserialized.system_config(), protocol_data.system_config());
+            assert_eq!(
+                deserialized.wasm_config().opcode_costs(),
+                protocol_data.wasm_config().opcode_costs()
+            );
+        }
     }
 }
@@ -12,7 +12,8 @@ use crate::storage::{
 };
 
 /// Filename for the LMDB database created by the EE.
-const EE_DB_FILENAME: &str = "data.lmdb";
+/// The name of the file containing the global state, within the directory it is opened in.
+pub const EE_DB_FILENAME: &str = "data.lmdb";
 
 impl<'a> Transaction for RoTransaction<'a> {
     type Error = lmdb::Error;
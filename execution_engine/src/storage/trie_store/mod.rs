@@ -12,7 +12,8 @@ use crate::shared::newtypes::Blake2bHash;
 
 use crate::storage::{store::Store, trie::Trie};
 
-const NAME: &str = "TRIE_STORE";
+/// The name of the database in which tries are stored, unless a different name is given.
+pub const NAME: &str = "TRIE_STORE";
 
 /// An entity which persists [`Trie`] values at their hashes.
 pub trait TrieStore<K, V>: Store<Blake2bHash, Trie<K, V>> {}
@@ -0,0 +1,268 @@
+//! A lightweight, in-process harness for testing contracts against the execution engine.
+//!
+//! [`LocalTestHarness`] wraps an [`EngineState`] backed by in-memory global state, so contract
+//! developers can run genesis, execute session code built from Wasm bytes and runtime arguments,
+//! and inspect the resulting global state without a running node.
+//!
+//! ```ignore
+//! let mut harness = LocalTestHarness::new();
+//! harness
+//!     .run_genesis(genesis_config_hash, protocol_version, &exec_config)
+//!     .with_proposer(proposer)
+//!     .exec(account_hash, module_bytes, args, payment_amount)
+//!     .expect_success()
+//!     .commit();
+//! let balance = harness.get_main_purse_balance(account_hash);
+//! ```
+
+use std::{collections::BTreeSet, iter};
+
+use casper_types::{
+    account::AccountHash, bytesrepr::Bytes, runtime_args, system::standard_payment, DeployHash,
+    Key, ProtocolVersion, PublicKey, RuntimeArgs, URef, U512,
+};
+
+use crate::{
+    core::engine_state::{
+        BalanceResult, DeployItem, EngineConfig, EngineState, ExecConfig, ExecutableDeployItem,
+        ExecuteRequest, ExecutionResult, GenesisResult, QueryRequest, QueryResult,
+    },
+    shared::{
+        account::Account,
+        newtypes::{Blake2bHash, CorrelationId},
+        stored_value::StoredValue,
+    },
+    storage::global_state::{in_memory::InMemoryGlobalState, CommitResult},
+};
+
+/// The gas price used for deploys executed by the harness.
+const DEFAULT_GAS_PRICE: u64 = 1;
+
+/// An in-process test harness running an execution engine on top of in-memory global state.
+///
+/// All methods panic on unexpected engine failures, as they are meant to be used from tests.
+pub struct LocalTestHarness {
+    engine_state: EngineState<InMemoryGlobalState>,
+    protocol_version: ProtocolVersion,
+    post_state_hash: Option<Blake2bHash>,
+    proposer: Option<PublicKey>,
+    block_time: u64,
+    deploy_counter: u64,
+    last_exec_result: Option<ExecutionResult>,
+}
+
+impl Default for LocalTestHarness {
+    fn default() -> Self {
+        LocalTestHarness::new()
+    }
+}
+
+impl LocalTestHarness {
+    /// Creates a harness with empty in-memory global state and the default engine config.
+    pub fn new() -> Self {
+        LocalTestHarness::with_engine_config(EngineConfig::default())
+    }
+
+    /// Creates a harness with empty in-memory global state and the given engine config.
+    pub fn with_engine_config(engine_config: EngineConfig) -> Self {
+        let global_state = InMemoryGlobalState::empty().expect("should create global state");
+        LocalTestHarness {
+            engine_state: EngineState::new(global_state, engine_config),
+            protocol_version: ProtocolVersion::V1_0_0,
+            post_state_hash: None,
+            proposer: None,
+            block_time: 0,
+            deploy_counter: 0,
+            last_exec_result: None,
+        }
+    }
+
+    /// Runs genesis from the given `ExecConfig` and commits its effects.
+    pub fn run_genesis(
+        &mut self,
+        genesis_config_hash: Blake2bHash,
+        protocol_version: ProtocolVersion,
+        exec_config: &ExecConfig,
+    ) -> &mut Self {
+        let result = self
+            .engine_state
+            .commit_genesis(
+                CorrelationId::new(),
+                genesis_config_hash,
+                protocol_version,
+                exec_config,
+            )
+            .expect("should run genesis");
+        match result {
+            GenesisResult::Success {
+                post_state_hash, ..
+            } => {
+                self.protocol_version = protocol_version;
+                self.post_state_hash = Some(post_state_hash);
+            }
+            other => panic!("genesis failed: {}", other),
+        }
+        self
+    }
+
+    /// Sets the proposer credited with the fees of subsequently executed deploys.
+    ///
+    /// The proposer's account must exist in global state, e.g. by being a genesis account.
+    pub fn with_proposer(&mut self, proposer: PublicKey) -> &mut Self {
+        self.proposer = Some(proposer);
+        self
+    }
+
+    /// Sets the block time, in milliseconds, at which subsequent deploys are executed.
+    pub fn with_block_time(&mut self, block_time: u64) -> &mut Self {
+        self.block_time = block_time;
+        self
+    }
+
+    /// Executes the given Wasm `module_bytes` with `args` as session code of `account`, paying
+    /// `payment_amount` via the standard payment.
+    ///
+    /// The result is not committed; call [`commit`](Self::commit) to apply its effects.
+    pub fn exec(
+        &mut self,
+        account: AccountHash,
+        module_bytes: Vec<u8>,
+        args: RuntimeArgs,
+        payment_amount: U512,
+    ) -> &mut Self {
+        let session = ExecutableDeployItem::ModuleBytes {
+            module_bytes: Bytes::from(module_bytes),
+            args,
+        };
+        let payment = ExecutableDeployItem::ModuleBytes {
+            module_bytes: Bytes::new(),
+            args: runtime_args! { standard_payment::ARG_AMOUNT => payment_amount },
+        };
+        self.exec_deploy(account, session, payment)
+    }
+
+    /// Executes a deploy with the given session and payment items on behalf of `account`.
+    ///
+    /// The result is not committed; call [`commit`](Self::commit) to apply its effects.
+    pub fn exec_deploy(
+        &mut self,
+        account: AccountHash,
+        session: ExecutableDeployItem,
+        payment: ExecutableDeployItem,
+    ) -> &mut Self {
+        self.deploy_counter += 1;
+        let mut deploy_hash_bytes = [0; 32];
+        deploy_hash_bytes[..8].copy_from_slice(&self.deploy_counter.to_le_bytes());
+        let deploy_item = DeployItem::new(
+            account,
+            session,
+            payment,
+            DEFAULT_GAS_PRICE,
+            iter::once(account).collect::<BTreeSet<_>>(),
+            DeployHash::new(deploy_hash_bytes),
+            String::new(),
+        );
+        let proposer = self
+            .proposer
+            .clone()
+            .expect("a proposer must be set before executing deploys");
+        let exec_request = ExecuteRequest::new(
+            self.post_state_hash(),
+            self.block_time,
+            vec![deploy_item],
+            self.protocol_version,
+            proposer,
+            None,
+        );
+        let mut results = self
+            .engine_state
+            .run_execute(CorrelationId::new(), exec_request)
+            .expect("should execute deploy");
+        self.last_exec_result = Some(results.pop_front().expect("should have a result"));
+        self
+    }
+
+    /// Panics if the last executed deploy failed.
+    pub fn expect_success(&mut self) -> &mut Self {
+        let result = self.last_exec_result();
+        if let Some(error) = result.as_error() {
+            panic!("expected success, got error: {:?}", error);
+        }
+        self
+    }
+
+    /// Panics if the last executed deploy succeeded.
+    pub fn expect_failure(&mut self) -> &mut Self {
+        assert!(
+            self.last_exec_result().is_failure(),
+            "expected failure, got success"
+        );
+        self
+    }
+
+    /// Commits the effects of the last executed deploy to global state.
+    pub fn commit(&mut self) -> &mut Self {
+        let effects = self.last_exec_result().effect().transforms.clone();
+        let result = self
+            .engine_state
+            .apply_effect(CorrelationId::new(), self.post_state_hash(), effects)
+            .expect("should commit");
+        match result {
+            CommitResult::Success { state_root } => self.post_state_hash = Some(state_root),
+            other => panic!("commit failed: {}", other),
+        }
+        self
+    }
+
+    /// Returns the result of the last executed deploy.
+    pub fn last_exec_result(&self) -> &ExecutionResult {
+        self.last_exec_result
+            .as_ref()
+            .expect("no deploy has been executed")
+    }
+
+    /// Returns the current state root hash.
+    pub fn post_state_hash(&self) -> Blake2bHash {
+        self.post_state_hash.expect("genesis must be run first")
+    }
+
+    /// Queries the value stored under `key`, following the named keys in `path`.
+    pub fn query(&self, key: Key, path: &[&str]) -> Option<StoredValue> {
+        let path = path.iter().map(|name| name.to_string()).collect();
+        let query_request = QueryRequest::new(self.post_state_hash(), key, path);
+        match self
+            .engine_state
+            .run_query(CorrelationId::new(), query_request)
+            .expect("should query")
+        {
+            QueryResult::Success { value, .. } => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the account stored under `account_hash`, if any.
+    pub fn get_account(&self, account_hash: AccountHash) -> Option<Account> {
+        self.query(Key::Account(account_hash), &[])
+            .and_then(|value| value.as_account().cloned())
+    }
+
+    /// Returns the balance of the given purse.
+    pub fn get_balance(&self, purse: URef) -> U512 {
+        match self
+            .engine_state
+            .get_purse_balance(CorrelationId::new(), self.post_state_hash(), purse)
+            .expect("should get balance")
+        {
+            BalanceResult::Success { motes, .. } => motes,
+            BalanceResult::RootNotFound => panic!("state root not found"),
+        }
+    }
+
+    /// Returns the balance of the main purse of the account stored under `account_hash`.
+    pub fn get_main_purse_balance(&self, account_hash: AccountHash) -> U512 {
+        let account = self
+            .get_account(account_hash)
+            .expect("account should exist");
+        self.get_balance(account.main_purse())
+    }
+}
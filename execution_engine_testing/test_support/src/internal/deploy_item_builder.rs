@@ -18,6 +18,7 @@ struct DeployItemData {
     pub gas_price: u64,
     pub authorization_keys: BTreeSet<AccountHash>,
     pub deploy_hash: DeployHash,
+    pub chain_name: String,
 }
 
 pub struct DeployItemBuilder {
@@ -205,6 +206,11 @@ impl DeployItemBuilder {
         self
     }
 
+    pub fn with_chain_name<T: Into<String>>(mut self, chain_name: T) -> Self {
+        self.deploy_item.chain_name = chain_name.into();
+        self
+    }
+
     pub fn build(self) -> DeployItem {
         DeployItem {
             address: self
@@ -222,6 +228,7 @@ impl DeployItemBuilder {
             gas_price: self.deploy_item.gas_price,
             authorization_keys: self.deploy_item.authorization_keys,
             deploy_hash: self.deploy_item.deploy_hash,
+            chain_name: self.deploy_item.chain_name,
         }
     }
 }
@@ -54,6 +54,11 @@ impl ExecuteRequestBuilder {
         self
     }
 
+    pub fn with_chain_name<T: Into<String>>(mut self, chain_name: T) -> Self {
+        self.execute_request.chain_name = Some(chain_name.into());
+        self
+    }
+
     pub fn build(self) -> ExecuteRequest {
         self.execute_request
     }
@@ -9,7 +9,7 @@ use casper_execution_engine::{
         wasm_config::WasmConfig,
     },
 };
-use casper_types::{EraId, Key, ProtocolVersion};
+use casper_types::{EraId, Key, ProtocolVersion, PublicKey, U512};
 
 #[derive(Default)]
 pub struct UpgradeRequestBuilder {
@@ -25,6 +25,7 @@ pub struct UpgradeRequestBuilder {
     new_round_seigniorage_rate: Option<Ratio<u64>>,
     new_unbonding_delay: Option<u64>,
     global_state_update: BTreeMap<Key, StoredValue>,
+    new_validators: Option<BTreeMap<PublicKey, U512>>,
 }
 
 impl UpgradeRequestBuilder {
@@ -92,6 +93,11 @@ impl UpgradeRequestBuilder {
         self
     }
 
+    pub fn with_new_validators(mut self, new_validators: BTreeMap<PublicKey, U512>) -> Self {
+        self.new_validators = Some(new_validators);
+        self
+    }
+
     pub fn with_activation_point(mut self, activation_point: EraId) -> Self {
         self.activation_point = Some(activation_point);
         self
@@ -111,6 +117,7 @@ impl UpgradeRequestBuilder {
             self.new_round_seigniorage_rate,
             self.new_unbonding_delay,
             self.global_state_update,
+            self.new_validators,
         )
     }
 }
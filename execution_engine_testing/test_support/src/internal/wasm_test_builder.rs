@@ -119,26 +119,8 @@ impl<S> WasmTestBuilder<S> {
 
 impl Default for InMemoryWasmTestBuilder {
     fn default() -> Self {
-        Self::initialize_logging();
-        let engine_config = EngineConfig::default();
-
-        let global_state = InMemoryGlobalState::empty().expect("should create global state");
-        let engine_state = EngineState::new(global_state, engine_config);
-
-        WasmTestBuilder {
-            engine_state: Rc::new(engine_state),
-            exec_results: Vec::new(),
-            upgrade_results: Vec::new(),
-            genesis_hash: None,
-            post_state_hash: None,
-            transforms: Vec::new(),
-            genesis_account: None,
-            genesis_transforms: None,
-            mint_contract_hash: None,
-            handle_payment_contract_hash: None,
-            standard_payment_hash: None,
-            auction_contract_hash: None,
-        }
+        // Tests call system contract entry points as the system account through deploys.
+        Self::new_with_config(EngineConfig::default().with_system_account_deploys_allowed())
     }
 }
 
@@ -189,6 +171,28 @@ impl InMemoryWasmTestBuilder {
             ..Default::default()
         }
     }
+
+    pub fn new_with_config(engine_config: EngineConfig) -> Self {
+        Self::initialize_logging();
+
+        let global_state = InMemoryGlobalState::empty().expect("should create global state");
+        let engine_state = EngineState::new(global_state, engine_config);
+
+        WasmTestBuilder {
+            engine_state: Rc::new(engine_state),
+            exec_results: Vec::new(),
+            upgrade_results: Vec::new(),
+            genesis_hash: None,
+            post_state_hash: None,
+            transforms: Vec::new(),
+            genesis_account: None,
+            genesis_transforms: None,
+            mint_contract_hash: None,
+            handle_payment_contract_hash: None,
+            standard_payment_hash: None,
+            auction_contract_hash: None,
+        }
+    }
 }
 
 impl LmdbWasmTestBuilder {
@@ -235,7 +239,11 @@ impl LmdbWasmTestBuilder {
     }
 
     pub fn new<T: AsRef<OsStr> + ?Sized>(data_dir: &T) -> Self {
-        Self::new_with_config(data_dir, Default::default())
+        // Tests call system contract entry points as the system account through deploys.
+        Self::new_with_config(
+            data_dir,
+            EngineConfig::default().with_system_account_deploys_allowed(),
+        )
     }
 
     /// Creates new instance of builder and applies values only which allows the engine state to be
@@ -0,0 +1,93 @@
+use assert_matches::assert_matches;
+use casper_engine_test_support::{
+    internal::{ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_RUN_GENESIS_REQUEST},
+    DEFAULT_ACCOUNT_ADDR,
+};
+use casper_execution_engine::core::{
+    engine_state::{ContractEvent, Error, ExecuteRequest},
+    execution,
+};
+use casper_types::{bytesrepr::Bytes, runtime_args, ContractHash, RuntimeArgs};
+
+const EMIT_EVENT_WASM: &str = "emit_event.wasm";
+const HASH_KEY_NAME: &str = "emit_event_hash";
+const ENTRY_POINT_EMIT: &str = "emit";
+const ENTRY_POINT_EMIT_IN_SESSION: &str = "emit_in_session";
+const ARG_TOPIC: &str = "topic";
+const ARG_DATA: &str = "data";
+const TOPIC: &str = "transfer";
+
+fn setup() -> (InMemoryWasmTestBuilder, ContractHash) {
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST);
+
+    let install_request =
+        ExecuteRequestBuilder::standard(*DEFAULT_ACCOUNT_ADDR, EMIT_EVENT_WASM, RuntimeArgs::new())
+            .build();
+
+    builder.exec(install_request).expect_success().commit();
+
+    let contract_hash = builder
+        .get_expected_account(*DEFAULT_ACCOUNT_ADDR)
+        .named_keys()
+        .get(HASH_KEY_NAME)
+        .expect("should have contract hash")
+        .into_hash()
+        .expect("should be hash")
+        .into();
+
+    (builder, contract_hash)
+}
+
+fn emit_request(contract_hash: ContractHash, entry_point: &str, data: &[u8]) -> ExecuteRequest {
+    ExecuteRequestBuilder::contract_call_by_hash(
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        entry_point,
+        runtime_args! {
+            ARG_TOPIC => TOPIC.to_string(),
+            ARG_DATA => data.to_vec(),
+        },
+    )
+    .build()
+}
+
+#[ignore]
+#[test]
+fn should_record_event_emitted_by_stored_contract() {
+    let (mut builder, contract_hash) = setup();
+    let data = [1u8, 2, 3, 4];
+
+    builder
+        .exec(emit_request(contract_hash, ENTRY_POINT_EMIT, &data))
+        .expect_success()
+        .commit();
+
+    let exec_result = &builder.get_exec_result(1).expect("should have exec result")[0];
+    let expected_event = ContractEvent {
+        contract_hash,
+        topic: TOPIC.to_string(),
+        data: Bytes::from(data.to_vec()),
+    };
+    assert_eq!(exec_result.effect().events, vec![expected_event]);
+}
+
+#[ignore]
+#[test]
+fn should_not_emit_event_from_session_code() {
+    let (mut builder, contract_hash) = setup();
+
+    builder
+        .exec(emit_request(
+            contract_hash,
+            ENTRY_POINT_EMIT_IN_SESSION,
+            &[1u8, 2, 3],
+        ))
+        .commit();
+
+    let error = builder.get_error().expect("should fail");
+    assert_matches!(error, Error::Exec(execution::Error::InvalidContext));
+    let exec_result = &builder.get_exec_result(1).expect("should have exec result")[0];
+    assert!(exec_result.effect().events.is_empty());
+}
@@ -0,0 +1,79 @@
+use casper_engine_test_support::{
+    internal::{ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_RUN_GENESIS_REQUEST},
+    DEFAULT_ACCOUNT_ADDR,
+};
+use casper_execution_engine::{
+    shared::stored_value::StoredValue, storage::trie::merkle_proof::TrieMerkleProof,
+};
+use casper_types::{bytesrepr, CLValue, Key, RuntimeArgs, U512};
+
+const GET_BALANCE_WITH_PROOF_WASM: &str = "get_balance_with_proof.wasm";
+const BALANCE_RESULT: &str = "balance_result";
+const PROOF_RESULT: &str = "proof_result";
+
+fn get_named_value(builder: &InMemoryWasmTestBuilder, name: &str) -> CLValue {
+    let account = builder
+        .get_account(*DEFAULT_ACCOUNT_ADDR)
+        .expect("should have account");
+
+    let uref = account.named_keys().get(name).expect("should have value");
+
+    builder
+        .query(None, *uref, &[])
+        .expect("should query")
+        .as_cl_value()
+        .cloned()
+        .expect("should be CLValue")
+}
+
+#[ignore]
+#[test]
+fn should_get_balance_with_proof_against_pre_state() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST);
+
+    let pre_state_hash = builder.get_post_state_hash();
+    let main_purse = builder
+        .get_expected_account(*DEFAULT_ACCOUNT_ADDR)
+        .main_purse();
+    let pre_state_balance = builder.get_purse_balance(main_purse);
+
+    let exec_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        GET_BALANCE_WITH_PROOF_WASM,
+        RuntimeArgs::default(),
+    )
+    .build();
+
+    builder.exec(exec_request).commit().expect_success();
+
+    // The balance is read before the deploy paid for its execution.
+    let balance: U512 = get_named_value(&builder, BALANCE_RESULT)
+        .into_t()
+        .expect("should convert balance");
+    assert_eq!(balance, pre_state_balance);
+    assert!(builder.get_purse_balance(main_purse) < pre_state_balance);
+
+    let proof_bytes: Vec<u8> = get_named_value(&builder, PROOF_RESULT)
+        .into_t()
+        .expect("should convert proof");
+    let proof: TrieMerkleProof<Key, StoredValue> =
+        bytesrepr::deserialize(proof_bytes).expect("should deserialize proof");
+
+    assert_eq!(*proof.key(), Key::Balance(main_purse.addr()));
+    let proven_balance: U512 = proof
+        .value()
+        .as_cl_value()
+        .cloned()
+        .expect("should be CLValue")
+        .into_t()
+        .expect("should convert proven balance");
+    assert_eq!(proven_balance, pre_state_balance);
+    assert_eq!(
+        proof
+            .compute_state_hash()
+            .expect("should compute state hash"),
+        pre_state_hash
+    );
+}
@@ -1,7 +1,9 @@
 mod account;
 mod blake2b;
 mod create_purse;
+mod emit_event;
 mod get_arg;
+mod get_balance_with_proof;
 mod get_blocktime;
 mod get_caller;
 mod get_phase;
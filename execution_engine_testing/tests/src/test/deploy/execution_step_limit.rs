@@ -0,0 +1,65 @@
+use assert_matches::assert_matches;
+
+use casper_engine_test_support::{
+    internal::{
+        utils, DeployItemBuilder, ExecuteRequestBuilder, InMemoryWasmTestBuilder,
+        DEFAULT_ACCOUNT_KEY, DEFAULT_PAYMENT, DEFAULT_RUN_GENESIS_REQUEST,
+    },
+    DEFAULT_ACCOUNT_ADDR,
+};
+use casper_execution_engine::{
+    core::{
+        engine_state::{EngineConfig, Error},
+        execution,
+    },
+    shared::gas::Gas,
+};
+use casper_types::{runtime_args, RuntimeArgs};
+
+const ENDLESS_LOOP_WASM: &str = "endless_loop.wasm";
+const ARG_AMOUNT: &str = "amount";
+const MAX_EXECUTION_STEPS: u64 = 10_000;
+
+/// Executes the endless loop session code under the step limit, returning the error and cost.
+fn run_endless_loop() -> (Error, Gas) {
+    let exec_request = {
+        let deploy = DeployItemBuilder::new()
+            .with_address(*DEFAULT_ACCOUNT_ADDR)
+            .with_deploy_hash([1; 32])
+            .with_empty_payment_bytes(runtime_args! { ARG_AMOUNT => *DEFAULT_PAYMENT })
+            .with_session_code(ENDLESS_LOOP_WASM, RuntimeArgs::default())
+            .with_authorization_keys(&[*DEFAULT_ACCOUNT_KEY])
+            .build();
+
+        ExecuteRequestBuilder::new().push_deploy(deploy).build()
+    };
+
+    let engine_config = EngineConfig::default().with_max_execution_steps(Some(MAX_EXECUTION_STEPS));
+    let mut builder = InMemoryWasmTestBuilder::new_with_config(engine_config);
+    builder
+        .run_genesis(&DEFAULT_RUN_GENESIS_REQUEST)
+        .exec(exec_request)
+        .commit();
+
+    let response = builder
+        .get_exec_result(0)
+        .expect("there should be a response");
+    let execution_result = utils::get_success_result(response);
+    let error = execution_result.as_error().expect("should have error");
+    (error.clone(), execution_result.cost())
+}
+
+#[ignore]
+#[test]
+fn should_abort_session_exceeding_execution_step_limit() {
+    let (error, _) = run_endless_loop();
+    assert_matches!(error, Error::Exec(execution::Error::ExecutionStepLimit));
+}
+
+#[ignore]
+#[test]
+fn should_abort_session_at_the_same_step_on_every_run() {
+    let (_, first_cost) = run_endless_loop();
+    let (_, second_cost) = run_endless_loop();
+    assert_eq!(first_cost, second_cost);
+}
@@ -1,4 +1,5 @@
 mod context_association;
+mod execution_step_limit;
 mod non_standard_payment;
 mod preconditions;
 mod receipts;
@@ -2,12 +2,12 @@ use assert_matches::assert_matches;
 
 use casper_engine_test_support::{
     internal::{
-        utils, DeployItemBuilder, ExecuteRequestBuilder, InMemoryWasmTestBuilder,
-        DEFAULT_RUN_GENESIS_REQUEST,
+        utils, DeployItemBuilder, ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_PAYMENT,
+        DEFAULT_RUN_GENESIS_REQUEST, SYSTEM_ADDR,
     },
     DEFAULT_ACCOUNT_ADDR,
 };
-use casper_execution_engine::core::engine_state::Error;
+use casper_execution_engine::core::engine_state::{EngineConfig, Error};
 use casper_types::{account::AccountHash, runtime_args, RuntimeArgs, U512};
 
 const ACCOUNT_1_ADDR: AccountHash = AccountHash::new([42u8; 32]);
@@ -119,3 +119,97 @@ fn should_raise_precondition_authorization_failure_invalid_authorized_keys() {
     let precondition_failure = utils::get_precondition_failure(response);
     assert_matches!(precondition_failure, Error::Authorization);
 }
+
+#[ignore]
+#[test]
+fn should_raise_precondition_failure_for_deploy_from_other_chain() {
+    let exec_request = {
+        let deploy = DeployItemBuilder::new()
+            .with_address(*DEFAULT_ACCOUNT_ADDR)
+            .with_session_code("do_nothing.wasm", RuntimeArgs::default())
+            .with_empty_payment_bytes(runtime_args! { ARG_AMOUNT => *DEFAULT_PAYMENT })
+            .with_deploy_hash([1; 32])
+            .with_authorization_keys(&[*DEFAULT_ACCOUNT_ADDR])
+            .with_chain_name("casper-test")
+            .build();
+
+        ExecuteRequestBuilder::new()
+            .push_deploy(deploy)
+            .with_chain_name("casper")
+            .build()
+    };
+
+    let result = InMemoryWasmTestBuilder::default()
+        .run_genesis(&DEFAULT_RUN_GENESIS_REQUEST)
+        .exec(exec_request)
+        .finish();
+
+    let response = result
+        .builder()
+        .get_exec_result(0)
+        .expect("there should be a response");
+
+    let precondition_failure = utils::get_precondition_failure(response);
+    assert_matches!(
+        precondition_failure,
+        Error::InvalidChainName { expected, got } if expected == "casper" && got == "casper-test"
+    );
+}
+
+#[ignore]
+#[test]
+fn should_raise_precondition_system_account_not_allowed() {
+    let exec_request = {
+        let deploy = DeployItemBuilder::new()
+            .with_address(*SYSTEM_ADDR)
+            .with_session_code("do_nothing.wasm", RuntimeArgs::default())
+            .with_empty_payment_bytes(runtime_args! { ARG_AMOUNT => *DEFAULT_PAYMENT })
+            .with_deploy_hash([1; 32])
+            .with_authorization_keys(&[*SYSTEM_ADDR])
+            .build();
+
+        ExecuteRequestBuilder::new().push_deploy(deploy).build()
+    };
+
+    let result = InMemoryWasmTestBuilder::new_with_config(EngineConfig::default())
+        .run_genesis(&DEFAULT_RUN_GENESIS_REQUEST)
+        .exec(exec_request)
+        .finish();
+
+    let response = result
+        .builder()
+        .get_exec_result(0)
+        .expect("there should be a response");
+
+    let precondition_failure = utils::get_precondition_failure(response);
+    assert_matches!(precondition_failure, Error::SystemAccountNotAllowed);
+}
+
+#[ignore]
+#[test]
+fn should_raise_precondition_system_account_authorization_not_allowed() {
+    let exec_request = {
+        let deploy = DeployItemBuilder::new()
+            .with_address(*DEFAULT_ACCOUNT_ADDR)
+            .with_session_code("do_nothing.wasm", RuntimeArgs::default())
+            .with_empty_payment_bytes(runtime_args! { ARG_AMOUNT => *DEFAULT_PAYMENT })
+            .with_deploy_hash([1; 32])
+            .with_authorization_keys(&[*DEFAULT_ACCOUNT_ADDR, *SYSTEM_ADDR])
+            .build();
+
+        ExecuteRequestBuilder::new().push_deploy(deploy).build()
+    };
+
+    let result = InMemoryWasmTestBuilder::new_with_config(EngineConfig::default())
+        .run_genesis(&DEFAULT_RUN_GENESIS_REQUEST)
+        .exec(exec_request)
+        .finish();
+
+    let response = result
+        .builder()
+        .get_exec_result(0)
+        .expect("there should be a response");
+
+    let precondition_failure = utils::get_precondition_failure(response);
+    assert_matches!(precondition_failure, Error::SystemAccountNotAllowed);
+}
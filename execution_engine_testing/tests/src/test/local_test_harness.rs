@@ -0,0 +1,57 @@
+use casper_engine_test_support::{
+    internal::{
+        utils, DEFAULT_EXEC_CONFIG, DEFAULT_GENESIS_CONFIG_HASH, DEFAULT_PAYMENT,
+        DEFAULT_PROPOSER_PUBLIC_KEY, DEFAULT_PROTOCOL_VERSION,
+    },
+    DEFAULT_ACCOUNT_ADDR,
+};
+use casper_execution_engine::testing::LocalTestHarness;
+use casper_types::{account::AccountHash, runtime_args, Key, RuntimeArgs, U512};
+
+const CONTRACT_TRANSFER_TO_ACCOUNT: &str = "transfer_to_account_u512.wasm";
+const ARG_TARGET: &str = "target";
+const ARG_AMOUNT: &str = "amount";
+const TRANSFER_AMOUNT: u64 = 1_000_000;
+const ACCOUNT_1_ADDR: AccountHash = AccountHash::new([1u8; 32]);
+
+#[ignore]
+#[test]
+fn should_run_genesis_and_execute_session_code_in_local_harness() {
+    let mut harness = LocalTestHarness::new();
+    harness
+        .run_genesis(
+            *DEFAULT_GENESIS_CONFIG_HASH,
+            *DEFAULT_PROTOCOL_VERSION,
+            &*DEFAULT_EXEC_CONFIG,
+        )
+        .with_proposer(DEFAULT_PROPOSER_PUBLIC_KEY.clone());
+
+    assert!(harness.get_account(ACCOUNT_1_ADDR).is_none());
+
+    harness
+        .exec(
+            *DEFAULT_ACCOUNT_ADDR,
+            utils::read_wasm_file_bytes(CONTRACT_TRANSFER_TO_ACCOUNT),
+            runtime_args! {
+                ARG_TARGET => ACCOUNT_1_ADDR,
+                ARG_AMOUNT => U512::from(TRANSFER_AMOUNT),
+            },
+            *DEFAULT_PAYMENT,
+        )
+        .expect_success()
+        .commit();
+
+    assert_eq!(
+        harness.get_main_purse_balance(ACCOUNT_1_ADDR),
+        U512::from(TRANSFER_AMOUNT)
+    );
+    let stored_account = harness
+        .query(Key::Account(ACCOUNT_1_ADDR), &[])
+        .expect("should have stored account");
+    assert_eq!(
+        stored_account
+            .as_account()
+            .map(|account| account.account_hash()),
+        Some(ACCOUNT_1_ADDR)
+    );
+}
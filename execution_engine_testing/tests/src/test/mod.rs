@@ -5,11 +5,14 @@ mod counter;
 mod deploy;
 mod explorer;
 mod gas_counter;
+mod genesis_snapshot;
 mod get_balance;
 mod groups;
 mod host_function_costs;
+mod local_test_harness;
 mod manage_groups;
 mod regression;
+mod state_diff;
 mod step;
 mod storage_costs;
 mod system_contracts;
@@ -18,7 +18,9 @@ use casper_execution_engine::{
         host_function_costs::HostFunctionCosts,
         opcode_costs::OpcodeCosts,
         storage_costs::StorageCosts,
-        wasm_config::{WasmConfig, DEFAULT_MAX_STACK_HEIGHT, DEFAULT_WASM_MAX_MEMORY},
+        wasm_config::{
+            WasmConfig, DEFAULT_MAX_STACK_HEIGHT, DEFAULT_MAX_TABLE_SIZE, DEFAULT_WASM_MAX_MEMORY,
+        },
     },
 };
 use casper_types::{
@@ -34,6 +36,7 @@ static DOUBLED_WASM_MEMORY_LIMIT: Lazy<WasmConfig> = Lazy::new(|| {
     WasmConfig::new(
         DEFAULT_WASM_MAX_MEMORY * 2,
         DEFAULT_MAX_STACK_HEIGHT,
+        DEFAULT_MAX_TABLE_SIZE,
         OpcodeCosts::default(),
         StorageCosts::default(),
         HostFunctionCosts::default(),
@@ -0,0 +1,108 @@
+use once_cell::sync::Lazy;
+
+use casper_engine_test_support::{
+    internal::{ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_RUN_GENESIS_REQUEST},
+    DEFAULT_ACCOUNT_ADDR,
+};
+use casper_execution_engine::{
+    core::engine_state::{StateDiffRequest, StateDiffResult},
+    shared::newtypes::{Blake2bHash, CorrelationId},
+};
+use casper_types::{
+    account::AccountHash, runtime_args, Key, KeyTag, PublicKey, RuntimeArgs, SecretKey, U512,
+};
+
+const TRANSFER_ARG_TARGET: &str = "target";
+const TRANSFER_ARG_AMOUNT: &str = "amount";
+const TRANSFER_ARG_ID: &str = "id";
+
+static ALICE_KEY: Lazy<PublicKey> = Lazy::new(|| {
+    SecretKey::ed25519_from_bytes([3; SecretKey::ED25519_LENGTH])
+        .unwrap()
+        .into()
+});
+static ALICE_ADDR: Lazy<AccountHash> = Lazy::new(|| AccountHash::from(&*ALICE_KEY));
+
+static TRANSFER_AMOUNT: Lazy<U512> = Lazy::new(|| U512::from(100_000_000));
+
+fn diff_state(
+    builder: &InMemoryWasmTestBuilder,
+    pre_state_hash: Blake2bHash,
+    post_state_hash: Blake2bHash,
+    key_prefix: Vec<u8>,
+) -> StateDiffResult {
+    builder
+        .get_engine_state()
+        .diff_state(
+            CorrelationId::new(),
+            StateDiffRequest::new(pre_state_hash, post_state_hash, key_prefix),
+        )
+        .expect("should diff state")
+}
+
+#[ignore]
+#[test]
+fn should_diff_state_after_transfer() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST);
+    let genesis_hash = builder.get_post_state_hash();
+
+    let transfer_request = ExecuteRequestBuilder::transfer(
+        *DEFAULT_ACCOUNT_ADDR,
+        runtime_args! {
+            TRANSFER_ARG_TARGET => *ALICE_ADDR,
+            TRANSFER_ARG_AMOUNT => *TRANSFER_AMOUNT,
+            TRANSFER_ARG_ID => <Option<u64>>::None,
+        },
+    )
+    .build();
+    builder.exec(transfer_request).commit().expect_success();
+    let post_state_hash = builder.get_post_state_hash();
+
+    let default_account_purse = builder
+        .get_account(*DEFAULT_ACCOUNT_ADDR)
+        .expect("should have default account")
+        .main_purse();
+    let alice_purse = builder
+        .get_account(*ALICE_ADDR)
+        .expect("should have Alice's account")
+        .main_purse();
+
+    let state_diff = match diff_state(&builder, genesis_hash, post_state_hash, vec![]) {
+        StateDiffResult::Success(state_diff) => state_diff,
+        StateDiffResult::RootNotFound(root) => panic!("root {} not found", root),
+    };
+    assert!(state_diff.added.contains_key(&Key::Account(*ALICE_ADDR)));
+    assert!(state_diff
+        .added
+        .contains_key(&Key::Balance(alice_purse.addr())));
+    assert!(state_diff
+        .changed
+        .contains_key(&Key::Balance(default_account_purse.addr())));
+
+    // Only keys with the given prefix should be compared.
+    let account_prefix = vec![KeyTag::Account as u8];
+    let account_diff = match diff_state(&builder, genesis_hash, post_state_hash, account_prefix) {
+        StateDiffResult::Success(state_diff) => state_diff,
+        StateDiffResult::RootNotFound(root) => panic!("root {} not found", root),
+    };
+    assert!(account_diff.added.contains_key(&Key::Account(*ALICE_ADDR)));
+    assert!(account_diff
+        .added
+        .keys()
+        .chain(account_diff.removed.keys())
+        .chain(account_diff.changed.keys())
+        .all(|key| matches!(key, Key::Account(_))));
+
+    // Identical states have no differences.
+    match diff_state(&builder, post_state_hash, post_state_hash, vec![]) {
+        StateDiffResult::Success(state_diff) => assert!(state_diff.is_empty()),
+        StateDiffResult::RootNotFound(root) => panic!("root {} not found", root),
+    }
+
+    let missing_root = Blake2bHash::new(&[1; 32]);
+    assert!(matches!(
+        diff_state(&builder, genesis_hash, missing_root, vec![]),
+        StateDiffResult::RootNotFound(root) if root == missing_root
+    ));
+}
@@ -16,7 +16,9 @@ use casper_execution_engine::shared::{
     opcode_costs::OpcodeCosts,
     storage_costs::StorageCosts,
     stored_value::StoredValue,
-    wasm_config::{WasmConfig, DEFAULT_MAX_STACK_HEIGHT, DEFAULT_WASM_MAX_MEMORY},
+    wasm_config::{
+        WasmConfig, DEFAULT_MAX_STACK_HEIGHT, DEFAULT_MAX_TABLE_SIZE, DEFAULT_WASM_MAX_MEMORY,
+    },
 };
 use casper_types::{
     bytesrepr::{Bytes, ToBytes},
@@ -106,6 +108,7 @@ static NEW_HOST_FUNCTION_COSTS: Lazy<HostFunctionCosts> = Lazy::new(|| HostFunct
     transfer_from_purse_to_account: HostFunction::fixed(0),
     transfer_from_purse_to_purse: HostFunction::fixed(0),
     get_balance: HostFunction::fixed(0),
+    get_balance_with_proof: HostFunction::fixed(0),
     get_phase: HostFunction::fixed(0),
     get_system_contract: HostFunction::fixed(0),
     get_main_purse: HostFunction::fixed(0),
@@ -123,11 +126,13 @@ static NEW_HOST_FUNCTION_COSTS: Lazy<HostFunctionCosts> = Lazy::new(|| HostFunct
     remove_contract_user_group_urefs: HostFunction::fixed(0),
     print: HostFunction::fixed(0),
     blake2b: HostFunction::fixed(0),
+    emit_event: HostFunction::fixed(0),
 });
 static STORAGE_COSTS_ONLY: Lazy<WasmConfig> = Lazy::new(|| {
     WasmConfig::new(
         DEFAULT_WASM_MAX_MEMORY,
         DEFAULT_MAX_STACK_HEIGHT,
+        DEFAULT_MAX_TABLE_SIZE,
         NEW_OPCODE_COSTS,
         StorageCosts::default(),
         *NEW_HOST_FUNCTION_COSTS,
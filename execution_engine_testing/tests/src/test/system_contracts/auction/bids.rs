@@ -3024,3 +3024,48 @@ fn should_validate_genesis_delegators_bond_amount() {
 
     builder.run_genesis(&run_genesis_request);
 }
+
+#[ignore]
+#[test]
+fn should_read_single_validator_bid() {
+    let accounts = {
+        let mut tmp: Vec<GenesisAccount> = DEFAULT_ACCOUNTS.clone();
+        let account_1 = GenesisAccount::account(
+            ACCOUNT_1_PK.clone(),
+            Motes::new(ACCOUNT_1_BALANCE.into()),
+            Some(GenesisValidator::new(Motes::new(ACCOUNT_1_BOND.into()), 80)),
+        );
+        tmp.push(account_1);
+        tmp
+    };
+
+    let run_genesis_request = utils::create_run_genesis_request(accounts);
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&run_genesis_request);
+
+    let auction_hash = builder.get_auction_contract_hash();
+
+    let auction_contract = builder
+        .get_contract(auction_hash)
+        .expect("should have auction contract");
+    assert!(auction_contract
+        .entry_points()
+        .has_entry_point(auction::METHOD_READ_BID));
+
+    // Reading an existing and a missing bid both succeed without touching the full bids map.
+    for public_key in [&*ACCOUNT_1_PK, &*ACCOUNT_2_PK].iter() {
+        let read_bid_request = ExecuteRequestBuilder::contract_call_by_hash(
+            *DEFAULT_ACCOUNT_ADDR,
+            auction_hash,
+            auction::METHOD_READ_BID,
+            runtime_args! {
+                auction::ARG_VALIDATOR_PUBLIC_KEY => (*public_key).clone(),
+            },
+        )
+        .build();
+
+        builder.exec(read_bid_request).expect_success().commit();
+    }
+}
@@ -4,19 +4,26 @@ use once_cell::sync::Lazy;
 use casper_engine_test_support::{
     internal::{
         InMemoryWasmTestBuilder, DEFAULT_AUCTION_DELAY, DEFAULT_GENESIS_TIMESTAMP_MILLIS,
-        DEFAULT_LOCKED_FUNDS_PERIOD_MILLIS, DEFAULT_ROUND_SEIGNIORAGE_RATE, DEFAULT_SYSTEM_CONFIG,
-        DEFAULT_UNBONDING_DELAY, DEFAULT_VALIDATOR_SLOTS, DEFAULT_WASM_CONFIG,
+        DEFAULT_LOCKED_FUNDS_PERIOD_MILLIS, DEFAULT_ROUND_SEIGNIORAGE_RATE,
+        DEFAULT_RUN_GENESIS_REQUEST, DEFAULT_SYSTEM_CONFIG, DEFAULT_UNBONDING_DELAY,
+        DEFAULT_VALIDATOR_SLOTS, DEFAULT_WASM_CONFIG,
     },
     AccountHash,
 };
 use casper_execution_engine::{
     core::engine_state::{
-        genesis::{ExecConfig, GenesisAccount, GenesisValidator},
+        genesis::{ExecConfig, GenesisAccount, GenesisResult, GenesisValidator},
         run_genesis_request::RunGenesisRequest,
     },
-    shared::{motes::Motes, stored_value::StoredValue},
+    shared::{motes::Motes, newtypes::CorrelationId, stored_value::StoredValue},
+};
+use casper_types::{
+    system::{
+        auction::DelegationRate, SystemContractRegistry, AUCTION, HANDLE_PAYMENT, MINT,
+        STANDARD_PAYMENT,
+    },
+    Key, ProtocolVersion, PublicKey, SecretKey, U512,
 };
-use casper_types::{system::auction::DelegationRate, ProtocolVersion, PublicKey, SecretKey, U512};
 
 const GENESIS_CONFIG_HASH: [u8; 32] = [127; 32];
 const ACCOUNT_1_BONDED_AMOUNT: u64 = 1_000_000;
@@ -181,3 +188,87 @@ fn should_track_total_token_supply_in_mint() {
         "unexpected total supply"
     )
 }
+
+#[ignore]
+#[test]
+fn should_validate_genesis_without_committing() {
+    let exec_config = ExecConfig::new(
+        GENESIS_CUSTOM_ACCOUNTS.clone(),
+        *DEFAULT_WASM_CONFIG,
+        *DEFAULT_SYSTEM_CONFIG,
+        DEFAULT_VALIDATOR_SLOTS,
+        DEFAULT_AUCTION_DELAY,
+        DEFAULT_LOCKED_FUNDS_PERIOD_MILLIS,
+        DEFAULT_ROUND_SEIGNIORAGE_RATE,
+        DEFAULT_UNBONDING_DELAY,
+        DEFAULT_GENESIS_TIMESTAMP_MILLIS,
+    );
+    let run_genesis_request = RunGenesisRequest::new(
+        GENESIS_CONFIG_HASH.into(),
+        ProtocolVersion::V1_0_0,
+        exec_config,
+    );
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    let validated_hash = match builder
+        .get_engine_state()
+        .validate_genesis(
+            CorrelationId::new(),
+            run_genesis_request.genesis_config_hash(),
+            run_genesis_request.protocol_version(),
+            run_genesis_request.ee_config(),
+        )
+        .expect("should validate genesis")
+    {
+        GenesisResult::Success {
+            post_state_hash, ..
+        } => post_state_hash,
+        other => panic!("genesis validation failed: {}", other),
+    };
+
+    let engine_state = builder.get_engine_state();
+    assert!(!engine_state
+        .has_state_root(validated_hash)
+        .expect("should check state root"));
+
+    builder.run_genesis(&run_genesis_request);
+
+    assert_eq!(builder.get_genesis_hash(), validated_hash);
+    assert!(builder
+        .get_engine_state()
+        .has_state_root(validated_hash)
+        .expect("should check state root"));
+}
+
+#[ignore]
+#[test]
+fn should_store_system_contract_registry() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&*DEFAULT_RUN_GENESIS_REQUEST);
+
+    let registry: SystemContractRegistry = builder
+        .query(None, Key::SystemContractRegistry, &[])
+        .expect("should have system contract registry")
+        .as_cl_value()
+        .cloned()
+        .expect("should be a CLValue")
+        .into_t()
+        .expect("should convert to registry");
+
+    let expected_registry: SystemContractRegistry = vec![
+        (MINT.to_string(), builder.get_mint_contract_hash()),
+        (
+            HANDLE_PAYMENT.to_string(),
+            builder.get_handle_payment_contract_hash(),
+        ),
+        (
+            STANDARD_PAYMENT.to_string(),
+            builder.get_standard_payment_contract_hash(),
+        ),
+        (AUCTION.to_string(), builder.get_auction_contract_hash()),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(registry, expected_registry);
+}
@@ -2,12 +2,12 @@ use std::convert::TryInto;
 
 use casper_engine_test_support::{
     internal::{
-        DeployItemBuilder, ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_PAYMENT,
-        DEFAULT_RUN_GENESIS_REQUEST, SYSTEM_ADDR,
+        DeployItemBuilder, ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_ACCOUNT_KEY,
+        DEFAULT_PAYMENT, DEFAULT_RUN_GENESIS_REQUEST, SYSTEM_ADDR,
     },
     DEFAULT_ACCOUNT_ADDR, MINIMUM_ACCOUNT_CREATION_BALANCE,
 };
-use casper_execution_engine::shared::account::Account;
+use casper_execution_engine::shared::{account::Account, transform::Transform};
 use casper_types::{
     account::AccountHash, runtime_args, system::handle_payment, Key, RuntimeArgs, URef, U512,
 };
@@ -16,6 +16,8 @@ const CONTRACT_FINALIZE_PAYMENT: &str = "finalize_payment.wasm";
 const CONTRACT_TRANSFER_PURSE_TO_ACCOUNT: &str = "transfer_purse_to_account.wasm";
 const FINALIZE_PAYMENT: &str = "finalize_payment.wasm";
 const LOCAL_REFUND_PURSE: &str = "local_refund_purse";
+const DO_NOTHING_WASM: &str = "do_nothing.wasm";
+const REVERT_WASM: &str = "revert.wasm";
 
 const ACCOUNT_ADDR: AccountHash = AccountHash::new([1u8; 32]);
 pub const ARG_AMOUNT: &str = "amount";
@@ -162,8 +164,92 @@ fn finalize_payment_should_refund_to_specified_purse() {
     );
 }
 
+#[ignore]
+#[test]
+fn successful_deploy_should_not_write_payment_purse_balance() {
+    let exec_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        DO_NOTHING_WASM,
+        RuntimeArgs::default(),
+    )
+    .build();
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder
+        .run_genesis(&DEFAULT_RUN_GENESIS_REQUEST)
+        .exec(exec_request)
+        .expect_success()
+        .commit();
+
+    assert_payment_purse_balance_not_written(&builder);
+}
+
+#[ignore]
+#[test]
+fn failed_session_should_not_write_payment_purse_balance() {
+    let exec_request =
+        ExecuteRequestBuilder::standard(*DEFAULT_ACCOUNT_ADDR, REVERT_WASM, RuntimeArgs::default())
+            .build();
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder
+        .run_genesis(&DEFAULT_RUN_GENESIS_REQUEST)
+        .exec(exec_request)
+        .commit();
+    assert!(builder.is_error());
+
+    assert_payment_purse_balance_not_written(&builder);
+}
+
+#[ignore]
+#[test]
+fn failed_payment_should_not_write_payment_purse_balance() {
+    let exec_request = {
+        let deploy = DeployItemBuilder::new()
+            .with_address(*DEFAULT_ACCOUNT_ADDR)
+            .with_deploy_hash([1; 32])
+            .with_payment_code(REVERT_WASM, RuntimeArgs::default())
+            .with_session_code(DO_NOTHING_WASM, RuntimeArgs::default())
+            .with_authorization_keys(&[*DEFAULT_ACCOUNT_KEY])
+            .build();
+
+        ExecuteRequestBuilder::new().push_deploy(deploy).build()
+    };
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder
+        .run_genesis(&DEFAULT_RUN_GENESIS_REQUEST)
+        .exec(exec_request)
+        .commit();
+    assert!(builder.is_error());
+
+    assert_payment_purse_balance_not_written(&builder);
+}
+
 // ------------- utility functions -------------------- //
 
+/// Asserts that the latest deploy's effects don't write the payment purse's balance.
+///
+/// All deploys pay through the same payment purse, but as it is empty before and after each of
+/// them, its balance is at most read, so deploys executed against the same state don't conflict on
+/// it.
+fn assert_payment_purse_balance_not_written(builder: &InMemoryWasmTestBuilder) {
+    let payment_purse = get_payment_purse_by_name(builder, handle_payment::PAYMENT_PURSE_KEY)
+        .expect("should find handle payment payment purse");
+    assert!(builder.get_purse_balance(payment_purse).is_zero());
+
+    let transforms = builder.get_transforms();
+    let transform = transforms
+        .last()
+        .expect("should have transforms")
+        .get(&Key::Balance(payment_purse.addr()));
+    assert!(
+        matches!(transform, None | Some(Transform::Identity)),
+        "payment purse balance should not be written, got {:?}",
+        transform
+    );
+}
+
 fn get_handle_payment_payment_purse_balance(builder: &InMemoryWasmTestBuilder) -> U512 {
     let purse = get_payment_purse_by_name(builder, handle_payment::PAYMENT_PURSE_KEY)
         .expect("should find handle payment payment purse");
@@ -16,7 +16,9 @@ use casper_execution_engine::shared::{
     },
     storage_costs::StorageCosts,
     stored_value::StoredValue,
-    wasm_config::{WasmConfig, DEFAULT_MAX_STACK_HEIGHT, DEFAULT_WASM_MAX_MEMORY},
+    wasm_config::{
+        WasmConfig, DEFAULT_MAX_STACK_HEIGHT, DEFAULT_MAX_TABLE_SIZE, DEFAULT_WASM_MAX_MEMORY,
+    },
 };
 use casper_types::{
     system::{
@@ -25,7 +27,7 @@ use casper_types::{
         },
         mint::ROUND_SEIGNIORAGE_RATE_KEY,
     },
-    CLValue, EraId, ProtocolVersion, U512,
+    CLValue, EraId, ProtocolVersion, PublicKey, SecretKey, U512,
 };
 use num_rational::Ratio;
 
@@ -57,6 +59,7 @@ fn get_upgraded_wasm_config() -> WasmConfig {
     WasmConfig::new(
         DEFAULT_WASM_MAX_MEMORY,
         DEFAULT_MAX_STACK_HEIGHT * 2,
+        DEFAULT_MAX_TABLE_SIZE,
         opcode_cost,
         storage_costs,
         host_function_costs,
@@ -631,3 +634,52 @@ fn should_apply_global_state_upgrade() {
         "Should have modified locked funds period"
     );
 }
+
+#[ignore]
+#[test]
+fn should_replace_validators_in_emergency_restart() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST);
+
+    let sem_ver = PROTOCOL_VERSION.value();
+    let new_protocol_version =
+        ProtocolVersion::from_parts(sem_ver.major, sem_ver.minor, sem_ver.patch + 1);
+
+    let new_validator = PublicKey::from(
+        &SecretKey::ed25519_from_bytes([42; SecretKey::ED25519_LENGTH])
+            .expect("should create secret key"),
+    );
+    let mut new_validators = BTreeMap::new();
+    new_validators.insert(new_validator, U512::from(1_000));
+
+    let mut upgrade_request = {
+        UpgradeRequestBuilder::new()
+            .with_current_protocol_version(PROTOCOL_VERSION)
+            .with_new_protocol_version(new_protocol_version)
+            .with_activation_point(DEFAULT_ACTIVATION_POINT)
+            .with_new_validators(new_validators.clone())
+            .build()
+    };
+
+    builder
+        .upgrade_with_upgrade_request(&mut upgrade_request)
+        .expect_upgrade_success();
+
+    let auction_delay = builder.get_auction_delay();
+    let era_validators = builder.get_era_validators();
+    let expected_era_ids: Vec<EraId> = DEFAULT_ACTIVATION_POINT
+        .iter_inclusive(auction_delay)
+        .collect();
+    assert_eq!(
+        era_validators.keys().copied().collect::<Vec<_>>(),
+        expected_era_ids,
+        "should have validators from the activation point until the auction delay has passed"
+    );
+    for validator_weights in era_validators.values() {
+        assert_eq!(
+            *validator_weights, new_validators,
+            "should have replaced the validators"
+        );
+    }
+}
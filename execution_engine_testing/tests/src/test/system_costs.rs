@@ -34,7 +34,9 @@ use casper_execution_engine::{
             SystemConfig,
         },
         wasm,
-        wasm_config::{WasmConfig, DEFAULT_MAX_STACK_HEIGHT, DEFAULT_WASM_MAX_MEMORY},
+        wasm_config::{
+            WasmConfig, DEFAULT_MAX_STACK_HEIGHT, DEFAULT_MAX_TABLE_SIZE, DEFAULT_WASM_MAX_MEMORY,
+        },
     },
     storage::protocol_data::DEFAULT_WASMLESS_TRANSFER_COST,
 };
@@ -812,6 +814,7 @@ fn should_verify_wasm_add_bid_wasm_cost_is_not_recursive() {
         transfer_from_purse_to_account: HostFunction::fixed(0),
         transfer_from_purse_to_purse: HostFunction::fixed(0),
         get_balance: HostFunction::fixed(0),
+        get_balance_with_proof: HostFunction::fixed(0),
         get_phase: HostFunction::fixed(0),
         get_system_contract: HostFunction::fixed(0),
         get_main_purse: HostFunction::fixed(0),
@@ -829,11 +832,13 @@ fn should_verify_wasm_add_bid_wasm_cost_is_not_recursive() {
         remove_contract_user_group_urefs: HostFunction::fixed(0),
         print: HostFunction::fixed(0),
         blake2b: HostFunction::fixed(0),
+        emit_event: HostFunction::fixed(0),
     };
 
     let new_wasm_config = WasmConfig::new(
         DEFAULT_WASM_MAX_MEMORY,
         DEFAULT_MAX_STACK_HEIGHT,
+        DEFAULT_MAX_TABLE_SIZE,
         new_opcode_costs,
         new_storage_costs,
         new_host_function_costs,
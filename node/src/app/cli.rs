@@ -18,6 +18,7 @@ use tracing::{error, info, trace, warn};
 
 use crate::config;
 use casper_node::{
+    crypto::hash::Digest,
     logging,
     reactor::{initializer, joiner, validator, ReactorExit, Runner},
     setup_signal_hooks,
@@ -76,6 +77,48 @@ pub enum Cli {
         #[structopt(long)]
         new_config: PathBuf,
     },
+    /// Back up or restore the node's storage.
+    Storage(StorageCommand),
+    /// Export the global state under a state root hash as a genesis snapshot.
+    ///
+    /// The snapshot can be placed next to the chainspec of a new network as genesis_snapshot.bin.
+    /// The node must not be running while the state is exported.
+    ExportState {
+        /// Path to configuration file of the node.
+        #[structopt(long)]
+        config: PathBuf,
+        /// Hex-encoded state root hash of the global state to export.
+        #[structopt(long, parse(try_from_str = Digest::from_hex))]
+        state_root_hash: Digest,
+        /// Path of the snapshot file to create.
+        output: PathBuf,
+    },
+}
+
+/// Subcommands operating on the storage databases.
+#[derive(Debug, StructOpt)]
+pub enum StorageCommand {
+    /// Create a consistent backup of the storage databases and the global state, and verify its
+    /// integrity.
+    ///
+    /// The node may keep running while the backup is created.
+    Backup {
+        /// Path to configuration file of the node.
+        #[structopt(long)]
+        config: PathBuf,
+        /// Directory to create the backup in.
+        dir: PathBuf,
+    },
+    /// Verify the integrity of a backup and restore it into a fresh storage directory.
+    ///
+    /// The storage directory configured for the node must not contain a database yet.
+    Restore {
+        /// Path to configuration file of the node.
+        #[structopt(long)]
+        config: PathBuf,
+        /// Directory containing the backup.
+        dir: PathBuf,
+    },
 }
 
 #[derive(Debug)]
@@ -193,10 +236,14 @@ impl Cli {
                 // The metrics are shared across all reactors.
                 let registry = Registry::new();
 
-                let mut initializer_runner = Runner::<initializer::Reactor>::with_metrics(
+                // All reactors use the same scheduler configuration.
+                let scheduler_config = validator_config.value().scheduler.clone();
+
+                let mut initializer_runner = Runner::<initializer::Reactor>::with_scheduler_config(
                     (crashed, validator_config),
                     &mut rng,
                     &registry,
+                    &scheduler_config,
                 )
                 .await?;
 
@@ -218,10 +265,11 @@ impl Cli {
                     .parent()
                     .map(|path| path.to_owned())
                     .unwrap_or_else(|| "/".into());
-                let mut joiner_runner = Runner::<joiner::Reactor>::with_metrics(
+                let mut joiner_runner = Runner::<joiner::Reactor>::with_scheduler_config(
                     WithDir::new(root, initializer),
                     &mut rng,
                     &registry,
+                    &scheduler_config,
                 )
                 .await?;
                 match joiner_runner.run(&mut rng).await {
@@ -230,8 +278,13 @@ impl Cli {
                 }
 
                 let config = joiner_runner.into_inner().into_validator_config().await?;
-                let mut validator_runner =
-                    Runner::<validator::Reactor>::with_metrics(config, &mut rng, &registry).await?;
+                let mut validator_runner = Runner::<validator::Reactor>::with_scheduler_config(
+                    config,
+                    &mut rng,
+                    &registry,
+                    &scheduler_config,
+                )
+                .await?;
 
                 match validator_runner.run(&mut rng).await {
                     ReactorExit::ProcessShouldExit(exit_code) => Ok(exit_code as i32),
@@ -279,6 +332,54 @@ impl Cli {
                 casper_node::migrate_data(WithDir::new(old_root, old_config), new_config)?;
                 Ok(ExitCode::Success as i32)
             }
+            Cli::Storage(StorageCommand::Backup { config, dir }) => {
+                let validator_config = Self::init(&config, vec![])?;
+                let storage_config = validator_config.map_ref(|cfg| cfg.storage.clone());
+
+                info!(version = %env!("CARGO_PKG_VERSION"), "backing up storage");
+                let summary = casper_node::backup_storage(
+                    &storage_config,
+                    &validator_config.value().contract_runtime,
+                    &dir,
+                )?;
+                info!(%summary, backup_dir = %dir.display(), "storage backup complete");
+                Ok(ExitCode::Success as i32)
+            }
+            Cli::Storage(StorageCommand::Restore { config, dir }) => {
+                let validator_config = Self::init(&config, vec![])?;
+                let storage_config = validator_config.map_ref(|cfg| cfg.storage.clone());
+
+                info!(version = %env!("CARGO_PKG_VERSION"), "restoring storage");
+                let summary = casper_node::restore_storage(
+                    &dir,
+                    &storage_config,
+                    &validator_config.value().contract_runtime,
+                )?;
+                info!(%summary, backup_dir = %dir.display(), "storage restore complete");
+                Ok(ExitCode::Success as i32)
+            }
+            Cli::ExportState {
+                config,
+                state_root_hash,
+                output,
+            } => {
+                let validator_config = Self::init(&config, vec![])?;
+                let storage_config = validator_config.map_ref(|cfg| cfg.storage.clone());
+
+                info!(%state_root_hash, "exporting global state");
+                let entry_count = casper_node::export_global_state(
+                    &storage_config,
+                    &validator_config.value().contract_runtime,
+                    state_root_hash,
+                    &output,
+                )?;
+                info!(
+                    entry_count,
+                    output = %output.display(),
+                    "global state export complete"
+                );
+                Ok(ExitCode::Success as i32)
+            }
         }
     }
 
@@ -4,6 +4,7 @@
 //! Each component has a unified interface, expressed by the `Component` trait.
 pub(crate) mod block_proposer;
 pub(crate) mod block_validator;
+pub(crate) mod chain_synchronizer;
 pub(crate) mod chainspec_loader;
 #[cfg(test)]
 pub(crate) mod collector;
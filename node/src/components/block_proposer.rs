@@ -7,22 +7,26 @@ mod config;
 mod deploy_sets;
 mod event;
 mod metrics;
+mod parked_deploys;
+mod pending_deploy_set;
 
 #[cfg(test)]
 mod tests;
 
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{HashMap, HashSet, VecDeque},
     convert::Infallible,
     sync::Arc,
     time::Duration,
 };
 
-use casper_types::PublicKey;
+use casper_types::{bytesrepr::ToBytes, EraId, PublicKey};
 pub use config::Config;
 use datasize::DataSize;
-use itertools::Itertools;
 use prometheus::{self, Registry};
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use serde::{Deserialize, Serialize};
 use tracing::{debug, error, info, trace, warn};
 
 use crate::{
@@ -30,20 +34,107 @@ use crate::{
         consensus::{BlockContext, ClContext},
         Component,
     },
+    crypto::hash,
     effect::{
+        announcements::BlockProposerAnnouncement,
         requests::{BlockPayloadRequest, BlockProposerRequest, StateStoreRequest, StorageRequest},
-        EffectBuilder, EffectExt, Effects,
+        EffectBuilder, EffectExt, EffectResultExt, Effects, Multiple,
     },
     types::{
         appendable_block::{AddError, AppendableBlock},
         chainspec::DeployConfig,
-        BlockPayload, Chainspec, DeployHash, DeployHeader, Timestamp,
+        BlockPayload, Chainspec, DeployHash, DeployHeader, FinalizedBlock, TimeDiff, Timestamp,
     },
     NodeRng,
 };
 use deploy_sets::BlockProposerDeploySets;
 pub(crate) use event::{DeployType, Event};
-use metrics::BlockProposerMetrics;
+use metrics::{BlockProposerMetrics, RejectionReason};
+
+/// The reason why a pending deploy was not included in a proposed block.
+#[derive(Clone, Copy, DataSize, Debug, Eq, PartialEq, Serialize)]
+pub enum DeferralReason {
+    /// Some of the deploy's dependencies are neither in the block's ancestors nor finalized.
+    DependenciesUnresolved,
+    /// The deploy's gas price is below the block's gas price floor.
+    GasPriceBelowFloor,
+    /// The block is outside the deploy's validity window.
+    OutsideValidityWindow,
+    /// The deploy was received too recently.
+    DeployDelay,
+    /// The deploy is not valid in this block, e.g. because it has expired.
+    InvalidInBlock,
+    /// The block's count, gas or size limits don't leave room for the deploy.
+    BlockFull,
+}
+
+/// A deploy which was rejected because its dependencies lead back to itself through pending
+/// deploys.
+#[derive(Clone, DataSize, Debug, Eq, PartialEq, Serialize)]
+pub struct DependencyCycle {
+    /// The rejected deploy.
+    pub deploy_hash: DeployHash,
+    /// The chain of dependencies from the rejected deploy back to itself.
+    pub cycle: Vec<DeployHash>,
+}
+
+/// Filters and pagination for inspecting the pending deploys via the API.
+#[derive(Clone, DataSize, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PendingDeploysQuery {
+    /// Only deploys from this account are returned.
+    pub account: Option<PublicKey>,
+    /// Only deploys buffered at least this long ago are returned.
+    pub min_age: Option<TimeDiff>,
+    /// Only deploys buffered at most this long ago are returned.
+    pub max_age: Option<TimeDiff>,
+    /// Only deploys offering at least this gas price are returned.
+    pub min_gas_price: Option<u64>,
+    /// Only deploys offering at most this gas price are returned.
+    pub max_gas_price: Option<u64>,
+    /// The number of matching deploys to skip.
+    pub offset: usize,
+    /// The maximum number of deploys to return, capped at `MAX_PENDING_DEPLOYS_PAGE_SIZE`.
+    pub limit: Option<usize>,
+}
+
+impl PendingDeploysQuery {
+    /// Returns whether a pending deploy with the given header, buffered `age` ago, matches the
+    /// filters.
+    fn matches(&self, header: &DeployHeader, age: TimeDiff) -> bool {
+        self.account
+            .as_ref()
+            .map_or(true, |account| header.account() == account)
+            && self.min_age.map_or(true, |min_age| age >= min_age)
+            && self.max_age.map_or(true, |max_age| age <= max_age)
+            && self
+                .min_gas_price
+                .map_or(true, |min_gas_price| header.gas_price() >= min_gas_price)
+            && self
+                .max_gas_price
+                .map_or(true, |max_gas_price| header.gas_price() <= max_gas_price)
+    }
+}
+
+/// A deploy in the block proposer's pending set.
+#[derive(Clone, DataSize, Debug, Eq, PartialEq, Serialize)]
+pub struct PendingDeploy {
+    /// The deploy's hash.
+    pub deploy_hash: DeployHash,
+    /// The time when the deploy was buffered.
+    pub received: Timestamp,
+    /// The deploy's header.
+    pub header: DeployHeader,
+}
+
+/// A page of the pending deploys matching a `PendingDeploysQuery`.
+#[derive(Clone, DataSize, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct PendingDeploys {
+    /// The total number of pending deploys matching the filters.
+    pub total: usize,
+    /// The requested page of matching deploys, oldest first.
+    pub deploys: Vec<PendingDeploy>,
+}
 
 /// Block proposer component.
 #[derive(DataSize, Debug)]
@@ -59,17 +150,26 @@ pub(crate) struct BlockProposer {
 // TODO: Make configurable.
 const PRUNE_INTERVAL: Duration = Duration::from_secs(10);
 
+/// Delay before loading the finalized deploys from storage is retried after a failure.
+const LOAD_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
 /// Experimentally, deploys are in the range of 270-280 bytes, we use this to determine if we are
 /// within a threshold to break iteration of `pending` early.
 const DEPLOY_APPROX_MIN_SIZE: usize = 300;
 
+/// The number of most recently detected dependency cycles kept for reporting via the API.
+const MAX_REPORTED_DEPENDENCY_CYCLES: usize = 100;
+
+/// The maximum number of pending deploys returned in a single page via the API.
+const MAX_PENDING_DEPLOYS_PAGE_SIZE: usize = 100;
+
 /// The type of values expressing the block height in the chain.
 type BlockHeight = u64;
 
 /// A queue of contents of blocks that we know have been finalized, but we are still missing
-/// notifications about finalization of some of their ancestors. It maps block height to the
-/// deploys contained in the corresponding block.
-type FinalizationQueue = HashMap<BlockHeight, Vec<DeployHash>>;
+/// notifications about finalization of some of their ancestors. It maps the height of the parent
+/// to the corresponding block.
+type FinalizationQueue = HashMap<BlockHeight, Box<FinalizedBlock>>;
 
 /// A queue of requests we can't respond to yet, because we aren't up to date on finalized blocks.
 /// The key is the height of the next block we will expect to be finalized at the point when we can
@@ -108,10 +208,16 @@ impl BlockProposer {
         debug!(%next_finalized_block, "creating block proposer");
         let effects = effect_builder
             .get_finalized_deploys(chainspec.deploy_config.max_ttl)
-            .event(move |finalized_deploys| Event::Loaded {
-                finalized_deploys,
-                next_finalized_block,
-            });
+            .result(
+                move |finalized_deploys| Event::Loaded {
+                    finalized_deploys,
+                    next_finalized_block,
+                },
+                move |error| Event::LoadFailed {
+                    error: Box::new(error),
+                    next_finalized_block,
+                },
+            );
 
         let block_proposer = BlockProposer {
             state: BlockProposerState::Initializing {
@@ -128,7 +234,12 @@ impl BlockProposer {
 
 impl<REv> Component<REv> for BlockProposer
 where
-    REv: From<Event> + From<StorageRequest> + From<StateStoreRequest> + Send + 'static,
+    REv: From<Event>
+        + From<StorageRequest>
+        + From<StateStoreRequest>
+        + From<BlockProposerAnnouncement>
+        + Send
+        + 'static,
 {
     type Event = Event;
     type ConstructionError = Infallible;
@@ -165,6 +276,10 @@ where
                     deploy_config: *deploy_config,
                     request_queue: Default::default(),
                     local_config: local_config.clone(),
+                    deferred: Vec::new(),
+                    rejected: Vec::new(),
+                    inclusion_latencies: Vec::new(),
+                    dependency_cycles: VecDeque::new(),
                 };
 
                 // Replay postponed events onto new state.
@@ -181,6 +296,32 @@ where
                         .event(|_| Event::Prune),
                 );
             }
+            (
+                BlockProposerState::Initializing { deploy_config, .. },
+                Event::LoadFailed {
+                    error,
+                    next_finalized_block,
+                },
+            ) => {
+                warn!(%error, "failed to load finalized deploys, retrying");
+                let ttl = deploy_config.max_ttl;
+                effects.extend(
+                    async move {
+                        effect_builder.set_timeout(LOAD_RETRY_INTERVAL).await;
+                        effect_builder.get_finalized_deploys(ttl).await
+                    }
+                    .result(
+                        move |finalized_deploys| Event::Loaded {
+                            finalized_deploys,
+                            next_finalized_block,
+                        },
+                        move |error| Event::LoadFailed {
+                            error: Box::new(error),
+                            next_finalized_block,
+                        },
+                    ),
+                );
+            }
             (
                 BlockProposerState::Initializing {
                     ref mut pending, ..
@@ -192,12 +333,41 @@ where
             }
 
             (BlockProposerState::Ready(ref mut ready_state), event) => {
+                let is_new_deploy = matches!(
+                    event,
+                    Event::BufferDeploy { .. } | Event::OrphanedDeploysLoaded(_)
+                );
                 effects.extend(ready_state.handle_event(effect_builder, event));
 
+                if is_new_deploy {
+                    let evicted = ready_state.evict_excess_pending();
+                    if !evicted.is_empty() {
+                        warn!(count = %evicted.len(), "evicted pending deploys over capacity");
+                        self.metrics.evicted_deploys.inc_by(evicted.len() as u64);
+                        effects.extend(effect_builder.announce_deploys_evicted(evicted).ignore());
+                    }
+                }
+
                 // Update metrics after the effects have been applied.
                 self.metrics
                     .pending_deploys
                     .set(ready_state.sets.pending.len() as i64);
+                self.metrics
+                    .parked_deploys
+                    .set(ready_state.sets.parked.len() as i64);
+                self.metrics.request_queue_depth.set(
+                    ready_state
+                        .request_queue
+                        .values()
+                        .map(Vec::len)
+                        .sum::<usize>() as i64,
+                );
+                for reason in ready_state.rejected.drain(..) {
+                    self.metrics.record_rejection(reason);
+                }
+                for latency in ready_state.inclusion_latencies.drain(..) {
+                    self.metrics.record_inclusion_latency(latency);
+                }
             }
         };
 
@@ -220,6 +390,14 @@ struct BlockProposerReady {
     request_queue: RequestQueue,
     /// The block proposer configuration, containing local settings for selecting deploys.
     local_config: Config,
+    /// The pending deploys that were not included in the most recently proposed block, and why.
+    deferred: Vec<(DeployHash, DeferralReason)>,
+    /// Reasons for the deploy rejections not yet recorded in the metrics.
+    rejected: Vec<RejectionReason>,
+    /// Times from buffering to finalization of deploys, not yet recorded in the metrics.
+    inclusion_latencies: Vec<TimeDiff>,
+    /// The most recently rejected deploys whose dependencies formed a cycle, oldest first.
+    dependency_cycles: VecDeque<DependencyCycle>,
 }
 
 impl BlockProposerReady {
@@ -229,7 +407,7 @@ impl BlockProposerReady {
         event: Event,
     ) -> Effects<Event>
     where
-        REv: Send + From<StateStoreRequest>,
+        REv: Send + From<StateStoreRequest> + From<StorageRequest>,
     {
         match event {
             Event::Request(BlockProposerRequest::RequestBlockPayload(request)) => {
@@ -250,12 +428,26 @@ impl BlockProposerReady {
                         .respond(self.propose_block_payload(
                             self.deploy_config,
                             request.context,
+                            request.gas_price_floor,
+                            request.era_id,
+                            &request.proposer,
                             request.accusations,
                             request.random_bit,
                         ))
                         .ignore()
                 }
             }
+            Event::Request(BlockProposerRequest::GetDeferredDeploys { responder }) => {
+                responder.respond(self.deferred.clone()).ignore()
+            }
+            Event::Request(BlockProposerRequest::GetDependencyCycles { responder }) => responder
+                .respond(self.dependency_cycles.iter().cloned().collect())
+                .ignore(),
+            Event::Request(BlockProposerRequest::GetPendingDeploys { query, responder }) => {
+                responder
+                    .respond(self.pending_deploys(&query, Timestamp::now()))
+                    .ignore()
+            }
             Event::BufferDeploy { hash, deploy_type } => {
                 self.add_deploy_or_transfer(Timestamp::now(), hash, *deploy_type);
                 Effects::new()
@@ -269,13 +461,43 @@ impl BlockProposerReady {
                     .set_timeout(PRUNE_INTERVAL)
                     .event(|_| Event::Prune)
             }
-            Event::Loaded { .. } => {
+            Event::Loaded { .. } | Event::LoadFailed { .. } => {
                 // This should never happen, but we can just ignore the event and carry on.
-                error!("got loaded event for block proposer state during ready state");
+                error!("got loading event for block proposer state during ready state");
+                Effects::new()
+            }
+            Event::OrphanedProtoBlock(block_payload) => {
+                let deploy_hashes = self.orphaned_deploys_to_load(&block_payload);
+                if deploy_hashes.is_empty() {
+                    return Effects::new();
+                }
+                debug!(
+                    count = deploy_hashes.len(),
+                    "loading deploys of orphaned block"
+                );
+                effect_builder
+                    .get_deploys_from_storage(deploy_hashes)
+                    .event(|deploys| {
+                        Event::OrphanedDeploysLoaded(
+                            deploys
+                                .into_iter()
+                                .flatten()
+                                .filter_map(|deploy| {
+                                    let deploy_type = deploy.deploy_type().ok()?;
+                                    Some((*deploy.id(), deploy_type))
+                                })
+                                .collect(),
+                        )
+                    })
+            }
+            Event::OrphanedDeploysLoaded(deploys) => {
+                let now = Timestamp::now();
+                for (hash, deploy_type) in deploys {
+                    self.add_deploy_or_transfer(now, hash, deploy_type);
+                }
                 Effects::new()
             }
             Event::FinalizedBlock(block) => {
-                let deploys = block.deploys_and_transfers_iter().copied().collect_vec();
                 let mut height = block.height();
 
                 if height > self.sets.next_finalized {
@@ -285,19 +507,15 @@ impl BlockProposerReady {
                     );
                     // safe to subtract 1 - height will never be 0 in this branch, because
                     // next_finalized is at least 0, and height has to be greater
-                    self.sets.finalization_queue.insert(height - 1, deploys);
+                    self.sets.finalization_queue.insert(height - 1, block);
                     Effects::new()
                 } else {
                     debug!(%height, "handling finalized block");
-                    let mut effects = self.handle_finalized_block(effect_builder, height, deploys);
-                    while let Some(deploys) = self.sets.finalization_queue.remove(&height) {
+                    let mut effects = self.handle_finalized_block(effect_builder, &block);
+                    while let Some(block) = self.sets.finalization_queue.remove(&height) {
                         info!(%height, "removed finalization queue entry");
                         height += 1;
-                        effects.extend(self.handle_finalized_block(
-                            effect_builder,
-                            height,
-                            deploys,
-                        ));
+                        effects.extend(self.handle_finalized_block(effect_builder, &block));
                     }
                     effects
                 }
@@ -316,6 +534,7 @@ impl BlockProposerReady {
     ) {
         if deploy_or_transfer.header().expired(current_instant) {
             trace!(%hash, "expired deploy rejected from the buffer");
+            self.rejected.push(RejectionReason::Expired);
             return;
         }
         if self.unhandled_finalized.remove(&hash) {
@@ -330,22 +549,75 @@ impl BlockProposerReady {
         // only add the deploy if it isn't contained in a finalized block
         if self.sets.finalized_deploys.contains_key(&hash) {
             info!(%hash, "deploy rejected from the buffer");
+            self.rejected.push(RejectionReason::Duplicate);
+        } else if let Some(cycle) = self.dependency_cycle(&hash, deploy_or_transfer.header()) {
+            warn!(%hash, ?cycle, "deploy closing a dependency cycle rejected from the buffer");
+            self.rejected.push(RejectionReason::DependencyCycle);
+            if self.dependency_cycles.len() >= MAX_REPORTED_DEPENDENCY_CYCLES {
+                self.dependency_cycles.pop_front();
+            }
+            self.dependency_cycles.push_back(DependencyCycle {
+                deploy_hash: hash,
+                cycle,
+            });
         } else {
+            let missing_dependencies: HashSet<_> = deploy_or_transfer
+                .header()
+                .dependencies()
+                .iter()
+                .filter(|dep| !self.contains_finalized(dep))
+                .copied()
+                .collect();
+            if !missing_dependencies.is_empty() {
+                debug!(
+                    %hash,
+                    count = missing_dependencies.len(),
+                    "parked deploy with unfinalized dependencies"
+                );
+            }
+            self.sets.parked.park(hash, missing_dependencies);
             self.sets
                 .pending
-                .insert(hash, (deploy_or_transfer, current_instant));
+                .insert(hash, deploy_or_transfer, current_instant);
             info!(%hash, "added deploy to the buffer");
         }
     }
 
+    /// Evicts pending deploys until the configured capacity limits are met again, and returns the
+    /// hashes of the evicted deploys.
+    ///
+    /// Deploys offering the lowest gas price are evicted first, and among those the oldest ones.
+    fn evict_excess_pending(&mut self) -> Vec<DeployHash> {
+        let max_count = self.local_config.max_pending_deploys;
+        let max_size = self.local_config.max_pending_deploys_size;
+        let mut evicted = Vec::new();
+        while self.sets.pending.len() > max_count || self.sets.pending.total_size() > max_size {
+            let hash = match self.sets.pending.next_to_evict() {
+                Some(hash) => hash,
+                None => break,
+            };
+            self.sets.pending.remove(&hash);
+            self.sets.parked.remove(&hash);
+            evicted.push(hash);
+        }
+        evicted
+    }
+
     /// Notifies the block proposer that a block has been finalized.
     fn finalized_deploys<I>(&mut self, deploys: I)
     where
         I: IntoIterator<Item = DeployHash>,
     {
+        let now = Timestamp::now();
         for deploy_hash in deploys.into_iter() {
+            self.sets.parked.remove(&deploy_hash);
+            for unparked in self.sets.parked.dependency_finalized(&deploy_hash) {
+                trace!(%unparked, "all dependencies finalized; unparked deploy");
+            }
             match self.sets.pending.remove(&deploy_hash) {
-                Some((deploy_type, _)) => {
+                Some((deploy_type, received_time)) => {
+                    self.inclusion_latencies
+                        .push(now.saturating_diff(received_time));
                     self.sets
                         .finalized_deploys
                         .insert(deploy_hash, deploy_type.take_header());
@@ -359,16 +631,13 @@ impl BlockProposerReady {
     }
 
     /// Handles finalization of a block.
-    fn handle_finalized_block<I, REv>(
+    fn handle_finalized_block<REv>(
         &mut self,
         _effect_builder: EffectBuilder<REv>,
-        height: BlockHeight,
-        deploys: I,
-    ) -> Effects<Event>
-    where
-        I: IntoIterator<Item = DeployHash>,
-    {
-        self.finalized_deploys(deploys);
+        block: &FinalizedBlock,
+    ) -> Effects<Event> {
+        let height = block.height();
+        self.finalized_deploys(block.deploys_and_transfers_iter().copied());
         self.sets.next_finalized = self.sets.next_finalized.max(height + 1);
 
         if let Some(requests) = self.request_queue.remove(&self.sets.next_finalized) {
@@ -381,6 +650,9 @@ impl BlockProposerReady {
                         .respond(self.propose_block_payload(
                             self.deploy_config,
                             request.context,
+                            request.gas_price_floor,
+                            request.era_id,
+                            &request.proposer,
                             request.accusations,
                             request.random_bit,
                         ))
@@ -392,6 +664,89 @@ impl BlockProposerReady {
         }
     }
 
+    /// Returns the deploys of the orphaned block that need to be loaded from storage to propose
+    /// them again.
+    ///
+    /// Deploys that are still pending will be proposed again anyway, and finalized ones must not
+    /// be. The others were either evicted, or never buffered, e.g. if we only fetched them to
+    /// validate the block.
+    fn orphaned_deploys_to_load(&self, block_payload: &BlockPayload) -> Multiple<DeployHash> {
+        block_payload
+            .deploys_and_transfers_iter()
+            .filter(|hash| !self.sets.pending.contains_key(hash) && !self.contains_finalized(hash))
+            .copied()
+            .collect()
+    }
+
+    /// Returns the requested page of the pending deploys matching the query, ordered by the time
+    /// they were buffered.
+    fn pending_deploys(&self, query: &PendingDeploysQuery, now: Timestamp) -> PendingDeploys {
+        let mut matching = self
+            .sets
+            .pending
+            .iter()
+            .filter(|(_, (deploy_type, received_time))| {
+                query.matches(deploy_type.header(), now.saturating_diff(*received_time))
+            })
+            .collect::<Vec<_>>();
+        matching.sort_unstable_by_key(|(hash, (_, received_time))| (*received_time, **hash));
+        let limit = query.limit.map_or(MAX_PENDING_DEPLOYS_PAGE_SIZE, |limit| {
+            limit.min(MAX_PENDING_DEPLOYS_PAGE_SIZE)
+        });
+        let deploys = matching
+            .iter()
+            .skip(query.offset)
+            .take(limit)
+            .map(|(hash, (deploy_type, received_time))| PendingDeploy {
+                deploy_hash: **hash,
+                received: *received_time,
+                header: deploy_type.header().clone(),
+            })
+            .collect();
+        PendingDeploys {
+            total: matching.len(),
+            deploys,
+        }
+    }
+
+    /// Returns the chain of pending deploys through which the dependencies of the new deploy with
+    /// the given hash and header lead back to it, if any.
+    ///
+    /// The chain starts and ends with the new deploy.  None of the deploys in such a cycle could
+    /// ever be proposed, since each of them waits for the next one.
+    fn dependency_cycle(
+        &self,
+        hash: &DeployHash,
+        header: &DeployHeader,
+    ) -> Option<Vec<DeployHash>> {
+        // Depth-first search, with one iterator over the dependencies of each deploy on the path.
+        let mut path = vec![*hash];
+        let mut stack = vec![header.dependencies().iter()];
+        let mut visited = HashSet::new();
+        while let Some(dependencies) = stack.last_mut() {
+            match dependencies.next() {
+                Some(dependency) if dependency == hash => {
+                    path.push(*hash);
+                    return Some(path);
+                }
+                Some(dependency) => {
+                    if !visited.insert(dependency) {
+                        continue;
+                    }
+                    if let Some((deploy_type, _)) = self.sets.pending.get(dependency) {
+                        path.push(*dependency);
+                        stack.push(deploy_type.header().dependencies().iter());
+                    }
+                }
+                None => {
+                    stack.pop();
+                    path.pop();
+                }
+            }
+        }
+        None
+    }
+
     /// Checks if a deploy's dependencies are satisfied, so the deploy is eligible for inclusion.
     fn deps_resolved(&self, header: &DeployHeader, past_deploys: &HashSet<&DeployHash>) -> bool {
         header
@@ -400,11 +755,53 @@ impl BlockProposerReady {
             .all(|dep| past_deploys.contains(dep) || self.contains_finalized(dep))
     }
 
+    /// Returns the reason why the given pending deploy can't be included in the block, if any.
+    fn deferral_reason(
+        &self,
+        deploy_type: &DeployType,
+        received_time: Timestamp,
+        past_deploys: &HashSet<&DeployHash>,
+        block_timestamp: Timestamp,
+        era_id: EraId,
+    ) -> Option<DeferralReason> {
+        let header = deploy_type.header();
+        if !self.deps_resolved(header, past_deploys) {
+            Some(DeferralReason::DependenciesUnresolved)
+        } else if !header.is_within_validity_window(self.sets.next_finalized, era_id) {
+            Some(DeferralReason::OutsideValidityWindow)
+        } else if block_timestamp.saturating_diff(received_time) < self.local_config.deploy_delay {
+            Some(DeferralReason::DeployDelay)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the random number generator determining the order in which pending deploys are
+    /// considered for a block, seeded by the block's timestamp and its proposer.
+    ///
+    /// The same proposer therefore always selects the same deploys for the same block, which makes
+    /// proposals reproducible, while different proposers still favor different deploys.
+    fn selection_rng(block_timestamp: Timestamp, proposer: &PublicKey) -> ChaCha20Rng {
+        let mut seed_data = block_timestamp.millis().to_le_bytes().to_vec();
+        seed_data.extend(proposer.to_bytes().unwrap_or_default());
+        ChaCha20Rng::from_seed(hash::hash(&seed_data).to_array())
+    }
+
     /// Returns a list of candidates for inclusion into a block.
+    ///
+    /// Deploys are only included if the block is within their validity window. The height of the
+    /// next finalized block is used as a lower bound for the height of the proposed block.
+    ///
+    /// If there are more eligible deploys than fit, the selection is random, but deterministic for
+    /// the given block timestamp and proposer. The deploys that were not included are recorded
+    /// together with the reason, and can be retrieved via `GetDeferredDeploys`.
     fn propose_block_payload(
         &mut self,
         deploy_config: DeployConfig,
         context: BlockContext<ClContext>,
+        gas_price_floor: u64,
+        era_id: EraId,
+        proposer: &PublicKey,
         accusations: Vec<PublicKey>,
         random_bit: bool,
     ) -> Arc<BlockPayload> {
@@ -414,27 +811,69 @@ impl BlockProposerReady {
             .flat_map(|block_payload| block_payload.deploys_and_transfers_iter())
             .collect();
         let block_timestamp = context.timestamp();
-        let mut appendable_block = AppendableBlock::new(deploy_config, block_timestamp);
+        let mut appendable_block =
+            AppendableBlock::new(deploy_config, block_timestamp, gas_price_floor);
+        let mut deferred = Vec::new();
+
+        // Parked deploys are only candidates if the ancestors contain all their missing
+        // dependencies; the others are deferred without further evaluation.
+        let unparked = self.sets.parked.resolved_by(&past_deploys);
+        let mut candidates = Vec::new();
+        for (hash, pending) in self.sets.pending.iter() {
+            if past_deploys.contains(hash) || self.contains_finalized(hash) {
+                continue;
+            }
+            if self.sets.parked.is_parked(hash) && !unparked.contains(hash) {
+                deferred.push((*hash, DeferralReason::DependenciesUnresolved));
+            } else {
+                candidates.push((hash, pending));
+            }
+        }
+        // Sort the candidates first, so that the shuffled order doesn't depend on the order of
+        // the hash map.
+        candidates.sort_unstable_by_key(|(hash, _)| **hash);
+        candidates.shuffle(&mut Self::selection_rng(block_timestamp, proposer));
+        let (transfers, deploys): (Vec<_>, Vec<_>) = candidates
+            .into_iter()
+            .partition(|(_, (deploy_type, _))| deploy_type.is_transfer());
 
         // We prioritize transfers over deploys, so we try to include them first.
-        for (hash, (deploy_type, received_time)) in &self.sets.pending {
-            if !deploy_type.is_transfer()
-                || !self.deps_resolved(deploy_type.header(), &past_deploys)
-                || past_deploys.contains(hash)
-                || self.contains_finalized(hash)
-                || block_timestamp.saturating_diff(*received_time) < self.local_config.deploy_delay
-            {
+        for (index, (hash, (deploy_type, received_time))) in transfers.iter().enumerate() {
+            if let Some(reason) = self.deferral_reason(
+                deploy_type,
+                *received_time,
+                &past_deploys,
+                block_timestamp,
+                era_id,
+            ) {
+                deferred.push((**hash, reason));
                 continue;
             }
 
-            if let Err(err) = appendable_block.add(*hash, deploy_type) {
+            if let Err(err) = appendable_block.add(**hash, deploy_type) {
                 match err {
                     // We added the maximum number of transfers.
-                    AddError::TransferCount | AddError::GasLimit | AddError::BlockSize => break,
+                    AddError::TransferCount | AddError::GasLimit | AddError::BlockSize => {
+                        deferred.extend(
+                            transfers[index..]
+                                .iter()
+                                .map(|(hash, _)| (**hash, DeferralReason::BlockFull)),
+                        );
+                        break;
+                    }
                     // The deploy is not valid in this block, but might be valid in another.
-                    AddError::InvalidDeploy => (),
+                    AddError::InvalidDeploy => {
+                        deferred.push((**hash, DeferralReason::InvalidInBlock));
+                    }
+                    AddError::GasPriceBelowFloor => {
+                        deferred.push((**hash, DeferralReason::GasPriceBelowFloor));
+                    }
                     // These errors should never happen when adding a transfer.
-                    AddError::InvalidGasAmount | AddError::DeployCount | AddError::Duplicate => {
+                    AddError::InvalidGasAmount
+                    | AddError::DeployCount
+                    | AddError::SizeClassCount(_)
+                    | AddError::InsufficientPayment(_)
+                    | AddError::Duplicate => {
                         error!(?err, "unexpected error when adding transfer")
                     }
                 }
@@ -442,30 +881,60 @@ impl BlockProposerReady {
         }
 
         // Now we try to add other deploys to the block.
-        for (hash, (deploy_type, received_time)) in &self.sets.pending {
-            if deploy_type.is_transfer()
-                || !self.deps_resolved(deploy_type.header(), &past_deploys)
-                || past_deploys.contains(hash)
-                || self.contains_finalized(hash)
-                || block_timestamp.saturating_diff(*received_time) < self.local_config.deploy_delay
-            {
+        for (index, (hash, (deploy_type, received_time))) in deploys.iter().enumerate() {
+            if let Some(reason) = self.deferral_reason(
+                deploy_type,
+                *received_time,
+                &past_deploys,
+                block_timestamp,
+                era_id,
+            ) {
+                deferred.push((**hash, reason));
                 continue;
             }
 
-            if let Err(err) = appendable_block.add(*hash, deploy_type) {
+            if let Err(err) = appendable_block.add(**hash, deploy_type) {
                 match err {
                     // We added the maximum number of deploys.
-                    AddError::DeployCount => break,
+                    AddError::DeployCount => {
+                        deferred.extend(
+                            deploys[index..]
+                                .iter()
+                                .map(|(hash, _)| (**hash, DeferralReason::BlockFull)),
+                        );
+                        break;
+                    }
                     AddError::BlockSize => {
                         if appendable_block.total_size() + DEPLOY_APPROX_MIN_SIZE
                             > deploy_config.block_gas_limit as usize
                         {
-                            break; // Probably no deploy will fit in this block anymore.
+                            // Probably no deploy will fit in this block anymore.
+                            deferred.extend(
+                                deploys[index..]
+                                    .iter()
+                                    .map(|(hash, _)| (**hash, DeferralReason::BlockFull)),
+                            );
+                            break;
                         }
+                        deferred.push((**hash, DeferralReason::BlockFull));
+                    }
+                    // We added the maximum number of deploys of this size class, but smaller or
+                    // larger ones might still fit.
+                    AddError::SizeClassCount(_) => {
+                        deferred.push((**hash, DeferralReason::BlockFull));
                     }
                     // The deploy is not valid in this block, but might be valid in another.
+                    AddError::InvalidDeploy | AddError::InsufficientPayment(_) => {
+                        deferred.push((**hash, DeferralReason::InvalidInBlock));
+                    }
+                    AddError::GasPriceBelowFloor => {
+                        deferred.push((**hash, DeferralReason::GasPriceBelowFloor));
+                    }
                     // TODO: Do something similar to DEPLOY_APPROX_MIN_SIZE for gas.
-                    AddError::InvalidDeploy | AddError::GasLimit => (),
+                    AddError::GasLimit => {
+                        self.rejected.push(RejectionReason::OverGasLimit);
+                        deferred.push((**hash, DeferralReason::BlockFull));
+                    }
                     // These errors should never happen when adding a deploy.
                     AddError::TransferCount | AddError::Duplicate => {
                         error!(?err, "unexpected error when adding deploy")
@@ -477,6 +946,15 @@ impl BlockProposerReady {
             }
         }
 
+        if !deferred.is_empty() {
+            debug!(count = deferred.len(), "deferred pending deploys");
+        }
+        self.rejected
+            .extend(deferred.iter().filter_map(|(_, reason)| match reason {
+                DeferralReason::DependenciesUnresolved => Some(RejectionReason::MissingDependency),
+                _ => None,
+            }));
+        self.deferred = deferred;
         Arc::new(appendable_block.into_block_payload(accusations, random_bit))
     }
 
@@ -13,12 +13,23 @@ pub struct Config {
     /// other nodes, and don't have to be requested from the proposer afterwards.
     #[serde(default = "default_deploy_delay")]
     pub deploy_delay: TimeDiff,
+    /// The maximum number of pending deploys and transfers held by the block proposer.  When
+    /// exceeded, the deploys with the lowest gas price are evicted, oldest first.
+    #[serde(default = "default_max_pending_deploys")]
+    pub max_pending_deploys: usize,
+    /// The maximum total serialized size in bytes of all pending deploys and transfers held by
+    /// the block proposer.  Exceeding it triggers eviction in the same way as
+    /// `max_pending_deploys`.
+    #[serde(default = "default_max_pending_deploys_size")]
+    pub max_pending_deploys_size: usize,
 }
 
 impl Default for Config {
     fn default() -> Config {
         Config {
             deploy_delay: default_deploy_delay(),
+            max_pending_deploys: default_max_pending_deploys(),
+            max_pending_deploys_size: default_max_pending_deploys_size(),
         }
     }
 }
@@ -26,3 +37,11 @@ impl Default for Config {
 fn default_deploy_delay() -> TimeDiff {
     "1min".parse().unwrap()
 }
+
+fn default_max_pending_deploys() -> usize {
+    100_000
+}
+
+fn default_max_pending_deploys_size() -> usize {
+    256 * 1024 * 1024
+}
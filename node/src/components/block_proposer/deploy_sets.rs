@@ -5,7 +5,10 @@ use std::{
 
 use datasize::DataSize;
 
-use super::{event::DeployType, BlockHeight, FinalizationQueue};
+use super::{
+    parked_deploys::ParkedDeploys, pending_deploy_set::PendingDeploySet, BlockHeight,
+    FinalizationQueue,
+};
 use crate::types::{DeployHash, DeployHeader, Timestamp};
 
 /// Stores the internal state of the BlockProposer.
@@ -13,7 +16,9 @@ use crate::types::{DeployHash, DeployHeader, Timestamp};
 pub(super) struct BlockProposerDeploySets {
     /// The collection of deploys pending for inclusion in a block, with a timestamp of when we
     /// received them.
-    pub(super) pending: HashMap<DeployHash, (DeployType, Timestamp)>,
+    pub(super) pending: PendingDeploySet,
+    /// The pending deploys that are waiting for some of their dependencies to be finalized.
+    pub(super) parked: ParkedDeploys,
     /// The deploys that have already been included in a finalized block.
     pub(super) finalized_deploys: HashMap<DeployHash, DeployHeader>,
     /// The next block height we expect to be finalized.
@@ -28,12 +33,14 @@ pub(super) struct BlockProposerDeploySets {
 
 impl Default for BlockProposerDeploySets {
     fn default() -> Self {
-        let pending = HashMap::new();
+        let pending = Default::default();
+        let parked = Default::default();
         let finalized_deploys = Default::default();
         let next_finalized = Default::default();
         let finalization_queue = Default::default();
         BlockProposerDeploySets {
             pending,
+            parked,
             finalized_deploys,
             next_finalized,
             finalization_queue,
@@ -48,7 +55,8 @@ impl BlockProposerDeploySets {
         next_finalized_height: u64,
     ) -> BlockProposerDeploySets {
         BlockProposerDeploySets {
-            pending: HashMap::new(),
+            pending: Default::default(),
+            parked: Default::default(),
             finalized_deploys: finalized_deploys.into_iter().collect(),
             next_finalized: next_finalized_height,
             finalization_queue: Default::default(),
@@ -60,8 +68,9 @@ impl Display for BlockProposerDeploySets {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         write!(
             f,
-            "(pending:{}, finalized:{})",
+            "(pending:{}, parked:{}, finalized:{})",
             self.pending.len(),
+            self.parked.len(),
             self.finalized_deploys.len()
         )
     }
@@ -71,7 +80,11 @@ impl BlockProposerDeploySets {
     /// Prunes expired deploy information from the BlockProposerState, returns the total deploys
     /// pruned
     pub(crate) fn prune(&mut self, current_instant: Timestamp) -> usize {
-        let pending = prune_pending_deploys(&mut self.pending, current_instant);
+        let pending = self
+            .pending
+            .retain(|_hash, deploy_type| !deploy_type.header().expired(current_instant));
+        let remaining = &self.pending;
+        self.parked.retain(|hash| remaining.contains_key(hash));
         let finalized = prune_deploys(&mut self.finalized_deploys, current_instant);
         pending + finalized
     }
@@ -87,14 +100,3 @@ pub(super) fn prune_deploys(
     deploys.retain(|_hash, header| !header.expired(current_instant));
     initial_len - deploys.len()
 }
-
-/// Prunes expired deploy information from an individual pending deploy collection, returns the
-/// total deploys pruned
-pub(super) fn prune_pending_deploys(
-    deploys: &mut HashMap<DeployHash, (DeployType, Timestamp)>,
-    current_instant: Timestamp,
-) -> usize {
-    let initial_len = deploys.len();
-    deploys.retain(|_hash, (deploy_type, _)| !deploy_type.header().expired(current_instant));
-    initial_len - deploys.len()
-}
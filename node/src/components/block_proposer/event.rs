@@ -1,4 +1,7 @@
-use std::fmt::{self, Formatter};
+use std::{
+    fmt::{self, Formatter},
+    sync::Arc,
+};
 
 use datasize::DataSize;
 use derive_more::From;
@@ -7,8 +10,9 @@ use serde::{Deserialize, Serialize};
 
 use super::BlockHeight;
 use crate::{
+    components::storage,
     effect::requests::BlockProposerRequest,
-    types::{DeployHash, DeployHeader, FinalizedBlock},
+    types::{BlockPayload, DeployHash, DeployHeader, FinalizedBlock},
 };
 use casper_execution_engine::shared::motes::Motes;
 
@@ -86,6 +90,14 @@ pub enum Event {
         /// The height of the next expected finalized block.
         next_finalized_block: BlockHeight,
     },
+    /// Loading the previously finalized deploys from storage failed and will be retried.
+    LoadFailed {
+        /// The storage error.
+        #[data_size(skip)]
+        error: Box<storage::Error>,
+        /// The height of the next expected finalized block.
+        next_finalized_block: BlockHeight,
+    },
     /// A new deploy should be buffered.
     BufferDeploy {
         hash: DeployHash,
@@ -95,6 +107,12 @@ pub enum Event {
     Prune,
     /// A block has been finalized. We should never propose its deploys again.
     FinalizedBlock(Box<FinalizedBlock>),
+    /// A proposed block has been orphaned. Its deploys should be proposed again, unless they have
+    /// been finalized in another block.
+    OrphanedProtoBlock(Arc<BlockPayload>),
+    /// The deploys of an orphaned block that were not pending anymore have been loaded from
+    /// storage.
+    OrphanedDeploysLoaded(Vec<(DeployHash, DeployType)>),
 }
 
 impl Display for Event {
@@ -109,11 +127,28 @@ impl Display for Event {
                 "loaded block-proposer finalized deploys; expected next finalized block: {}",
                 next_finalized_block
             ),
+            Event::LoadFailed { error, .. } => {
+                write!(
+                    f,
+                    "failed to load block-proposer finalized deploys: {}",
+                    error
+                )
+            }
             Event::BufferDeploy { hash, .. } => write!(f, "block-proposer add {}", hash),
             Event::Prune => write!(f, "block-proposer prune"),
             Event::FinalizedBlock(block) => {
                 write!(f, "block-proposer finalized block {}", block)
             }
+            Event::OrphanedProtoBlock(block_payload) => {
+                write!(f, "block-proposer orphaned {}", block_payload)
+            }
+            Event::OrphanedDeploysLoaded(deploys) => {
+                write!(
+                    f,
+                    "block-proposer loaded {} orphaned deploys",
+                    deploys.len()
+                )
+            }
         }
     }
 }
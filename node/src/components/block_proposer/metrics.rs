@@ -1,14 +1,66 @@
 use datasize::DataSize;
-use prometheus::{self, IntGauge, Registry};
+use prometheus::{
+    self, Histogram, HistogramOpts, IntCounter, IntCounterVec, IntGauge, Opts, Registry,
+};
 
-use crate::unregister_metric;
+use crate::{types::TimeDiff, unregister_metric};
+
+/// Value of the upper bound of the first bucket of the inclusion latency histogram, in seconds.
+const INCLUSION_LATENCY_BUCKET_START: f64 = 1.0;
+/// Multiplier of the previous upper bound for the next bound.
+const INCLUSION_LATENCY_BUCKET_FACTOR: f64 = 2.0;
+/// Bucket count, with the last going to +Inf.
+const INCLUSION_LATENCY_BUCKET_COUNT: usize = 12;
+
+/// The reason why a deploy was rejected by the block proposer.
+#[derive(Clone, Copy, DataSize, Debug, Eq, PartialEq)]
+pub(super) enum RejectionReason {
+    /// The deploy had already expired when it was buffered.
+    Expired,
+    /// The deploy had already been included in a finalized block when it was buffered.
+    Duplicate,
+    /// The deploy was left out of a proposed block because of unresolved dependencies.
+    MissingDependency,
+    /// The deploy was left out of a proposed block because it exceeded the remaining gas.
+    OverGasLimit,
+    /// The deploy's dependencies led back to itself through pending deploys when it was buffered.
+    DependencyCycle,
+}
+
+impl RejectionReason {
+    /// Returns the value of the `reason` label of the `rejected_deploys` metric.
+    fn label(self) -> &'static str {
+        match self {
+            RejectionReason::Expired => "expired",
+            RejectionReason::Duplicate => "duplicate",
+            RejectionReason::MissingDependency => "missing_dependency",
+            RejectionReason::OverGasLimit => "over_gas_limit",
+            RejectionReason::DependencyCycle => "dependency_cycle",
+        }
+    }
+}
 
 /// Metrics for the block proposer.
-#[derive(DataSize, Debug, Clone)]
+#[derive(DataSize, Debug)]
 pub(super) struct BlockProposerMetrics {
     /// Amount of pending deploys
     #[data_size(skip)]
     pub(super) pending_deploys: IntGauge,
+    /// Number of pending deploys waiting for their dependencies to be finalized.
+    #[data_size(skip)]
+    pub(super) parked_deploys: IntGauge,
+    /// Total number of pending deploys evicted due to the configured capacity limits.
+    #[data_size(skip)]
+    pub(super) evicted_deploys: IntCounter,
+    /// Total number of rejected deploys, by reason.
+    #[data_size(skip)]
+    rejected_deploys: IntCounterVec,
+    /// Time from buffering a deploy to its inclusion in a finalized block, in seconds.
+    #[data_size(skip)]
+    deploy_inclusion_latency: Histogram,
+    /// Number of block payload requests queued until the preceding block is finalized.
+    #[data_size(skip)]
+    pub(super) request_queue_depth: IntGauge,
     /// Registry stored to allow deregistration later.
     #[data_size(skip)]
     registry: Registry,
@@ -18,16 +70,74 @@ impl BlockProposerMetrics {
     /// Creates a new instance of the block proposer metrics.
     pub fn new(registry: Registry) -> Result<Self, prometheus::Error> {
         let pending_deploys = IntGauge::new("pending_deploy", "amount of pending deploys")?;
+        let parked_deploys = IntGauge::new(
+            "parked_deploys",
+            "number of pending deploys waiting for their dependencies to be finalized",
+        )?;
+        let evicted_deploys = IntCounter::new(
+            "evicted_deploys",
+            "total number of pending deploys evicted due to capacity limits",
+        )?;
+        let rejected_deploys = IntCounterVec::new(
+            Opts::new(
+                "rejected_deploys",
+                "total number of deploys rejected by the block proposer, by reason",
+            ),
+            &["reason"],
+        )?;
+        let deploy_inclusion_latency = Histogram::with_opts(
+            HistogramOpts::new(
+                "deploy_inclusion_latency",
+                "time in seconds from buffering a deploy to its inclusion in a finalized block",
+            )
+            .buckets(prometheus::exponential_buckets(
+                INCLUSION_LATENCY_BUCKET_START,
+                INCLUSION_LATENCY_BUCKET_FACTOR,
+                INCLUSION_LATENCY_BUCKET_COUNT,
+            )?),
+        )?;
+        let request_queue_depth = IntGauge::new(
+            "block_proposer_request_queue_depth",
+            "number of block payload requests waiting for the preceding block to be finalized",
+        )?;
         registry.register(Box::new(pending_deploys.clone()))?;
+        registry.register(Box::new(parked_deploys.clone()))?;
+        registry.register(Box::new(evicted_deploys.clone()))?;
+        registry.register(Box::new(rejected_deploys.clone()))?;
+        registry.register(Box::new(deploy_inclusion_latency.clone()))?;
+        registry.register(Box::new(request_queue_depth.clone()))?;
         Ok(BlockProposerMetrics {
             pending_deploys,
+            parked_deploys,
+            evicted_deploys,
+            rejected_deploys,
+            deploy_inclusion_latency,
+            request_queue_depth,
             registry,
         })
     }
+
+    /// Counts a deploy rejected for the given reason.
+    pub(super) fn record_rejection(&self, reason: RejectionReason) {
+        self.rejected_deploys
+            .with_label_values(&[reason.label()])
+            .inc();
+    }
+
+    /// Records the time it took for a deploy to be included in a finalized block.
+    pub(super) fn record_inclusion_latency(&self, latency: TimeDiff) {
+        self.deploy_inclusion_latency
+            .observe(latency.millis() as f64 / 1000.0);
+    }
 }
 
 impl Drop for BlockProposerMetrics {
     fn drop(&mut self) {
         unregister_metric!(self.registry, self.pending_deploys);
+        unregister_metric!(self.registry, self.parked_deploys);
+        unregister_metric!(self.registry, self.evicted_deploys);
+        unregister_metric!(self.registry, self.rejected_deploys);
+        unregister_metric!(self.registry, self.deploy_inclusion_latency);
+        unregister_metric!(self.registry, self.request_queue_depth);
     }
 }
@@ -0,0 +1,111 @@
+use std::collections::{hash_map::Entry, HashMap, HashSet};
+
+use datasize::DataSize;
+
+use crate::types::DeployHash;
+
+/// The pending deploys whose dependencies are not finalized yet, indexed by those dependencies.
+///
+/// Parked deploys are only proposed in blocks whose ancestors contain all of their missing
+/// dependencies. They are re-activated when the specific dependencies they are waiting for get
+/// finalized, instead of being re-evaluated in every proposal.
+#[derive(Clone, DataSize, Debug, Default)]
+pub(super) struct ParkedDeploys {
+    /// The dependencies of each parked deploy that are not finalized yet.
+    missing_dependencies: HashMap<DeployHash, HashSet<DeployHash>>,
+    /// The parked deploys waiting for each dependency.
+    waiting_for: HashMap<DeployHash, HashSet<DeployHash>>,
+}
+
+impl ParkedDeploys {
+    /// Parks the deploy until all of the given dependencies are finalized.
+    pub(super) fn park(&mut self, hash: DeployHash, missing_dependencies: HashSet<DeployHash>) {
+        self.remove(&hash);
+        if missing_dependencies.is_empty() {
+            return;
+        }
+        for dependency in &missing_dependencies {
+            self.waiting_for
+                .entry(*dependency)
+                .or_default()
+                .insert(hash);
+        }
+        self.missing_dependencies.insert(hash, missing_dependencies);
+    }
+
+    /// Returns whether the deploy is waiting for any dependencies.
+    pub(super) fn is_parked(&self, hash: &DeployHash) -> bool {
+        self.missing_dependencies.contains_key(hash)
+    }
+
+    /// Removes the deploy, e.g. because it has been finalized or evicted.
+    pub(super) fn remove(&mut self, hash: &DeployHash) {
+        let missing_dependencies = match self.missing_dependencies.remove(hash) {
+            Some(missing_dependencies) => missing_dependencies,
+            None => return,
+        };
+        for dependency in missing_dependencies {
+            if let Entry::Occupied(mut entry) = self.waiting_for.entry(dependency) {
+                entry.get_mut().remove(hash);
+                if entry.get().is_empty() {
+                    entry.remove();
+                }
+            }
+        }
+    }
+
+    /// Removes all deploys for which the predicate returns `false`.
+    pub(super) fn retain<F>(&mut self, mut predicate: F)
+    where
+        F: FnMut(&DeployHash) -> bool,
+    {
+        let removed: Vec<_> = self
+            .missing_dependencies
+            .keys()
+            .filter(|hash| !predicate(hash))
+            .copied()
+            .collect();
+        for hash in removed {
+            self.remove(&hash);
+        }
+    }
+
+    /// Records that the given dependency has been finalized, and returns the deploys that are not
+    /// waiting for any other dependencies anymore.
+    pub(super) fn dependency_finalized(&mut self, dependency: &DeployHash) -> Vec<DeployHash> {
+        let waiting = self.waiting_for.remove(dependency).unwrap_or_default();
+        let mut unparked = Vec::new();
+        for hash in waiting {
+            if let Entry::Occupied(mut entry) = self.missing_dependencies.entry(hash) {
+                entry.get_mut().remove(dependency);
+                if entry.get().is_empty() {
+                    entry.remove();
+                    unparked.push(hash);
+                }
+            }
+        }
+        unparked
+    }
+
+    /// Returns the parked deploys whose missing dependencies are all among the given deploys.
+    pub(super) fn resolved_by(&self, deploys: &HashSet<&DeployHash>) -> HashSet<DeployHash> {
+        deploys
+            .iter()
+            .filter_map(|dependency| self.waiting_for.get(dependency))
+            .flatten()
+            .filter(|hash| {
+                self.missing_dependencies
+                    .get(hash)
+                    .map_or(false, |missing| {
+                        missing.iter().all(|dep| deploys.contains(dep))
+                    })
+            })
+            .copied()
+            .collect()
+    }
+
+    /// Returns the number of parked deploys.
+    pub(super) fn len(&self) -> usize {
+        self.missing_dependencies.len()
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::{BTreeSet, HashMap};
+
+use datasize::DataSize;
+
+use super::event::DeployType;
+use crate::types::{DeployHash, Timestamp};
+
+/// The deploys pending for inclusion in a block, with a timestamp of when we received them.
+///
+/// Keeps track of their total size and of the order in which they are evicted when the pending
+/// set exceeds its capacity, so that neither needs to be recomputed whenever a deploy is added.
+#[derive(Clone, DataSize, Debug, Default)]
+pub(super) struct PendingDeploySet {
+    /// The pending deploys, with the time they were received.
+    deploys: HashMap<DeployHash, (DeployType, Timestamp)>,
+    /// The pending deploys ordered by gas price and then by the time they were received, i.e. in
+    /// the order in which they are evicted.
+    eviction_order: BTreeSet<(u64, Timestamp, DeployHash)>,
+    /// The total size of all pending deploys.
+    total_size: usize,
+}
+
+impl PendingDeploySet {
+    /// Adds the deploy, replacing any previous entry with the same hash.
+    pub(super) fn insert(
+        &mut self,
+        hash: DeployHash,
+        deploy_type: DeployType,
+        received_time: Timestamp,
+    ) {
+        self.remove(&hash);
+        self.eviction_order
+            .insert((deploy_type.header().gas_price(), received_time, hash));
+        self.total_size = self.total_size.saturating_add(deploy_type.size());
+        self.deploys.insert(hash, (deploy_type, received_time));
+    }
+
+    /// Removes the deploy and returns it together with the time it was received, if present.
+    pub(super) fn remove(&mut self, hash: &DeployHash) -> Option<(DeployType, Timestamp)> {
+        let (deploy_type, received_time) = self.deploys.remove(hash)?;
+        self.eviction_order
+            .remove(&(deploy_type.header().gas_price(), received_time, *hash));
+        self.total_size = self.total_size.saturating_sub(deploy_type.size());
+        Some((deploy_type, received_time))
+    }
+
+    /// Removes all deploys for which the predicate returns `false`, and returns how many were
+    /// removed.
+    pub(super) fn retain<F>(&mut self, mut predicate: F) -> usize
+    where
+        F: FnMut(&DeployHash, &DeployType) -> bool,
+    {
+        let removed: Vec<_> = self
+            .deploys
+            .iter()
+            .filter(|(hash, (deploy_type, _))| !predicate(hash, deploy_type))
+            .map(|(hash, _)| *hash)
+            .collect();
+        for hash in &removed {
+            self.remove(hash);
+        }
+        removed.len()
+    }
+
+    /// Returns the deploy and the time it was received, if present.
+    pub(super) fn get(&self, hash: &DeployHash) -> Option<&(DeployType, Timestamp)> {
+        self.deploys.get(hash)
+    }
+
+    /// Returns whether the deploy is pending.
+    pub(super) fn contains_key(&self, hash: &DeployHash) -> bool {
+        self.deploys.contains_key(hash)
+    }
+
+    /// Returns an iterator over the pending deploys, in arbitrary order.
+    pub(super) fn iter(&self) -> impl Iterator<Item = (&DeployHash, &(DeployType, Timestamp))> {
+        self.deploys.iter()
+    }
+
+    /// Returns the number of pending deploys.
+    pub(super) fn len(&self) -> usize {
+        self.deploys.len()
+    }
+
+    /// Returns the total size of all pending deploys.
+    pub(super) fn total_size(&self) -> usize {
+        self.total_size
+    }
+
+    /// Returns the deploy to be evicted first: the oldest one among those offering the lowest gas
+    /// price.
+    pub(super) fn next_to_evict(&self) -> Option<DeployHash> {
+        self.eviction_order.iter().next().map(|(_, _, hash)| *hash)
+    }
+}
@@ -17,6 +17,7 @@ use crate::{
 };
 
 const DEFAULT_TEST_GAS_PRICE: u64 = 1;
+const TEST_GAS_PRICE_FLOOR: u64 = DEFAULT_TEST_GAS_PRICE;
 
 fn default_gas_payment() -> Gas {
     Gas::from(1u32)
@@ -91,13 +92,24 @@ fn generate_deploy(
     )
 }
 
+fn test_proposer() -> PublicKey {
+    PublicKey::from(&SecretKey::ed25519_from_bytes([1; SecretKey::ED25519_LENGTH]).unwrap())
+}
+
 fn create_test_proposer(deploy_delay: TimeDiff) -> BlockProposerReady {
     BlockProposerReady {
         sets: Default::default(),
         deploy_config: Default::default(),
         request_queue: Default::default(),
         unhandled_finalized: Default::default(),
-        local_config: Config { deploy_delay },
+        local_config: Config {
+            deploy_delay,
+            ..Default::default()
+        },
+        deferred: Vec::new(),
+        rejected: Vec::new(),
+        inclusion_latencies: Vec::new(),
+        dependency_cycles: VecDeque::new(),
     }
 }
 
@@ -161,6 +173,9 @@ fn should_add_and_take_deploys() {
     let block = proposer.propose_block_payload(
         DeployConfig::default(),
         BlockContext::new(block_time2, vec![]),
+        TEST_GAS_PRICE_FLOOR,
+        EraId::from(0),
+        &test_proposer(),
         vec![],
         true,
     );
@@ -176,6 +191,9 @@ fn should_add_and_take_deploys() {
     let block = proposer.propose_block_payload(
         DeployConfig::default(),
         BlockContext::new(block_time1, vec![]),
+        TEST_GAS_PRICE_FLOOR,
+        EraId::from(0),
+        &test_proposer(),
         vec![],
         true,
     );
@@ -187,6 +205,9 @@ fn should_add_and_take_deploys() {
     let block = proposer.propose_block_payload(
         DeployConfig::default(),
         BlockContext::new(block_time3, vec![]),
+        TEST_GAS_PRICE_FLOOR,
+        EraId::from(0),
+        &test_proposer(),
         vec![],
         true,
     );
@@ -197,6 +218,9 @@ fn should_add_and_take_deploys() {
     let block = proposer.propose_block_payload(
         DeployConfig::default(),
         BlockContext::new(block_time2, vec![]),
+        TEST_GAS_PRICE_FLOOR,
+        EraId::from(0),
+        &test_proposer(),
         vec![],
         true,
     );
@@ -209,6 +233,9 @@ fn should_add_and_take_deploys() {
     let block = proposer.propose_block_payload(
         DeployConfig::default(),
         BlockContext::new(block_time2, vec![]),
+        TEST_GAS_PRICE_FLOOR,
+        EraId::from(0),
+        &test_proposer(),
         vec![],
         true,
     );
@@ -220,6 +247,9 @@ fn should_add_and_take_deploys() {
     let block = proposer.propose_block_payload(
         DeployConfig::default(),
         BlockContext::new(block_time2, vec![block]),
+        TEST_GAS_PRICE_FLOOR,
+        EraId::from(0),
+        &test_proposer(),
         vec![],
         true,
     );
@@ -236,6 +266,9 @@ fn should_add_and_take_deploys() {
     let block = proposer.propose_block_payload(
         DeployConfig::default(),
         BlockContext::new(block_time2, vec![]),
+        TEST_GAS_PRICE_FLOOR,
+        EraId::from(0),
+        &test_proposer(),
         vec![],
         true,
     );
@@ -579,8 +612,15 @@ fn test_proposer_with(
         );
     }
 
-    let block =
-        proposer.propose_block_payload(config, BlockContext::new(test_time, vec![]), vec![], true);
+    let block = proposer.propose_block_payload(
+        config,
+        BlockContext::new(test_time, vec![]),
+        TEST_GAS_PRICE_FLOOR,
+        EraId::from(0),
+        &test_proposer(),
+        vec![],
+        true,
+    );
     let all_deploys = block.deploys_and_transfers_iter().collect_vec();
     proposer.finalized_deploys(all_deploys.iter().map(|hash| **hash));
     println!("proposed deploys {}", block.deploy_hashes().len());
@@ -636,6 +676,9 @@ fn should_return_deploy_dependencies() {
     let block = proposer.propose_block_payload(
         DeployConfig::default(),
         BlockContext::new(block_time, vec![]),
+        TEST_GAS_PRICE_FLOOR,
+        EraId::from(0),
+        &test_proposer(),
         vec![],
         true,
     );
@@ -648,6 +691,9 @@ fn should_return_deploy_dependencies() {
     let block = proposer.propose_block_payload(
         DeployConfig::default(),
         BlockContext::new(block_time, vec![]),
+        TEST_GAS_PRICE_FLOOR,
+        EraId::from(0),
+        &test_proposer(),
         vec![],
         true,
     );
@@ -662,6 +708,9 @@ fn should_return_deploy_dependencies() {
     let block = proposer.propose_block_payload(
         DeployConfig::default(),
         BlockContext::new(block_time, vec![]),
+        TEST_GAS_PRICE_FLOOR,
+        EraId::from(0),
+        &test_proposer(),
         vec![],
         true,
     );
@@ -671,6 +720,80 @@ fn should_return_deploy_dependencies() {
     assert!(deploys2.contains(deploy2.id()));
 }
 
+#[test]
+fn should_park_deploys_until_dependencies_finalized() {
+    let creation_time = Timestamp::from(100);
+    let ttl = TimeDiff::from(Duration::from_millis(100));
+    let block_time = Timestamp::from(120);
+
+    let mut rng = crate::new_rng();
+    let mut generate = |dependencies| {
+        generate_deploy(
+            &mut rng,
+            creation_time,
+            ttl,
+            dependencies,
+            default_gas_payment(),
+            DEFAULT_TEST_GAS_PRICE,
+        )
+    };
+    let deploy1 = generate(vec![]);
+    let deploy2 = generate(vec![]);
+    // let deploy3 depend on deploy1 and deploy2
+    let deploy3 = generate(vec![*deploy1.id(), *deploy2.id()]);
+
+    let mut proposer = create_test_proposer(0.into());
+    proposer.add_deploy_or_transfer(creation_time, *deploy3.id(), deploy3.deploy_type().unwrap());
+    assert!(proposer.sets.parked.is_parked(deploy3.id()));
+    assert_eq!(proposer.sets.pending.len(), 1);
+
+    // finalizing only one of the dependencies keeps deploy3 parked
+    proposer.finalized_deploys(vec![*deploy1.id()]);
+    assert!(proposer.sets.parked.is_parked(deploy3.id()));
+    let block = proposer.propose_block_payload(
+        DeployConfig::default(),
+        BlockContext::new(block_time, vec![]),
+        TEST_GAS_PRICE_FLOOR,
+        EraId::from(0),
+        &test_proposer(),
+        vec![],
+        true,
+    );
+    assert!(block.deploy_hashes().is_empty());
+    assert_eq!(
+        proposer.deferred,
+        vec![(*deploy3.id(), DeferralReason::DependenciesUnresolved)]
+    );
+
+    // a parked deploy can be proposed if the ancestors contain its missing dependencies
+    let ancestor = Arc::new(BlockPayload::new(vec![*deploy2.id()], vec![], vec![], true));
+    let block = proposer.propose_block_payload(
+        DeployConfig::default(),
+        BlockContext::new(block_time, vec![ancestor]),
+        TEST_GAS_PRICE_FLOOR,
+        EraId::from(0),
+        &test_proposer(),
+        vec![],
+        true,
+    );
+    assert_eq!(block.deploy_hashes(), &vec![*deploy3.id()]);
+
+    // finalizing the last dependency unparks deploy3
+    proposer.finalized_deploys(vec![*deploy2.id()]);
+    assert!(!proposer.sets.parked.is_parked(deploy3.id()));
+    assert_eq!(proposer.sets.parked.len(), 0);
+    let block = proposer.propose_block_payload(
+        DeployConfig::default(),
+        BlockContext::new(block_time, vec![]),
+        TEST_GAS_PRICE_FLOOR,
+        EraId::from(0),
+        &test_proposer(),
+        vec![],
+        true,
+    );
+    assert_eq!(block.deploy_hashes(), &vec![*deploy3.id()]);
+}
+
 #[test]
 fn should_respect_deploy_delay() {
     let mut rng = crate::new_rng();
@@ -692,6 +815,9 @@ fn should_respect_deploy_delay() {
     let block = proposer.propose_block_payload(
         deploy_config,
         BlockContext::new(109.into(), vec![]),
+        TEST_GAS_PRICE_FLOOR,
+        EraId::from(0),
+        &test_proposer(),
         vec![],
         true,
     );
@@ -699,8 +825,488 @@ fn should_respect_deploy_delay() {
     let block = proposer.propose_block_payload(
         deploy_config,
         BlockContext::new(110.into(), vec![]),
+        TEST_GAS_PRICE_FLOOR,
+        EraId::from(0),
+        &test_proposer(),
+        vec![],
+        true,
+    );
+    assert_eq!(&vec![*deploy.id()], block.deploy_hashes());
+}
+
+#[test]
+fn should_not_propose_deploy_below_gas_price_floor() {
+    let mut rng = crate::new_rng();
+    let creation_time = Timestamp::from(100);
+    let ttl = TimeDiff::from(Duration::from_millis(100));
+    let block_time = Timestamp::from(120);
+
+    let cheap_deploy = generate_deploy(
+        &mut rng,
+        creation_time,
+        ttl,
+        vec![],
+        default_gas_payment(),
+        DEFAULT_TEST_GAS_PRICE,
+    );
+    let deploy = generate_deploy(
+        &mut rng,
+        creation_time,
+        ttl,
+        vec![],
+        default_gas_payment(),
+        DEFAULT_TEST_GAS_PRICE + 1,
+    );
+
+    let mut proposer = create_test_proposer(0.into());
+    proposer.add_deploy_or_transfer(
+        creation_time,
+        *cheap_deploy.id(),
+        cheap_deploy.deploy_type().unwrap(),
+    );
+    proposer.add_deploy_or_transfer(creation_time, *deploy.id(), deploy.deploy_type().unwrap());
+
+    let block = proposer.propose_block_payload(
+        DeployConfig::default(),
+        BlockContext::new(block_time, vec![]),
+        DEFAULT_TEST_GAS_PRICE + 1,
+        EraId::from(0),
+        &test_proposer(),
         vec![],
         true,
     );
     assert_eq!(&vec![*deploy.id()], block.deploy_hashes());
+    assert_eq!(
+        proposer.deferred,
+        vec![(*cheap_deploy.id(), DeferralReason::GasPriceBelowFloor)]
+    );
+}
+
+#[test]
+fn should_respect_deploy_validity_window() {
+    let mut rng = crate::new_rng();
+    let creation_time = Timestamp::from(100);
+    let ttl = TimeDiff::from(Duration::from_millis(100));
+    let block_time = Timestamp::from(120);
+
+    let mut generate_windowed_deploy = |valid_from_height, valid_until_era| {
+        Deploy::new_with_validity_window(
+            creation_time,
+            ttl,
+            DEFAULT_TEST_GAS_PRICE,
+            vec![],
+            "chain".to_string(),
+            valid_from_height,
+            valid_until_era,
+            ExecutableDeployItem::ModuleBytes {
+                module_bytes: Bytes::new(),
+                args: runtime_args! { ARG_AMOUNT => default_gas_payment().value() },
+            },
+            ExecutableDeployItem::ModuleBytes {
+                module_bytes: Bytes::new(),
+                args: RuntimeArgs::new(),
+            },
+            &SecretKey::random(&mut rng),
+        )
+    };
+    let future_deploy = generate_windowed_deploy(Some(5), None);
+    let expiring_deploy = generate_windowed_deploy(None, Some(EraId::from(1)));
+
+    let mut proposer = create_test_proposer(0.into());
+    for deploy in &[&future_deploy, &expiring_deploy] {
+        proposer.add_deploy_or_transfer(creation_time, *deploy.id(), deploy.deploy_type().unwrap());
+    }
+
+    // Below the starting height only the deploy without a height restriction is proposed.
+    let block = proposer.propose_block_payload(
+        DeployConfig::default(),
+        BlockContext::new(block_time, vec![]),
+        TEST_GAS_PRICE_FLOOR,
+        EraId::from(1),
+        &test_proposer(),
+        vec![],
+        true,
+    );
+    assert_eq!(&vec![*expiring_deploy.id()], block.deploy_hashes());
+
+    // Once the height is reached, but the era has passed, only the other deploy is proposed.
+    proposer.sets.next_finalized = 5;
+    let block = proposer.propose_block_payload(
+        DeployConfig::default(),
+        BlockContext::new(block_time, vec![]),
+        TEST_GAS_PRICE_FLOOR,
+        EraId::from(2),
+        &test_proposer(),
+        vec![],
+        true,
+    );
+    assert_eq!(&vec![*future_deploy.id()], block.deploy_hashes());
+}
+
+#[test]
+fn should_select_deploys_deterministically_and_record_deferred_ones() {
+    let mut rng = crate::new_rng();
+    let creation_time = Timestamp::from(100);
+    let ttl = TimeDiff::from(Duration::from_millis(100));
+    let block_time = Timestamp::from(120);
+
+    let mut proposer = create_test_proposer(0.into());
+    let mut deploy_config = proposer.deploy_config;
+    deploy_config.block_max_deploy_count = 2;
+    let deploys = (0..5)
+        .map(|_| {
+            generate_deploy(
+                &mut rng,
+                creation_time,
+                ttl,
+                vec![],
+                default_gas_payment(),
+                DEFAULT_TEST_GAS_PRICE,
+            )
+        })
+        .collect::<Vec<_>>();
+    for deploy in &deploys {
+        proposer.add_deploy_or_transfer(creation_time, *deploy.id(), deploy.deploy_type().unwrap());
+    }
+
+    let propose = |proposer: &mut BlockProposerReady| {
+        proposer.propose_block_payload(
+            deploy_config,
+            BlockContext::new(block_time, vec![]),
+            TEST_GAS_PRICE_FLOOR,
+            EraId::from(0),
+            &test_proposer(),
+            vec![],
+            true,
+        )
+    };
+
+    // The same proposer selects the same deploys for the same block.
+    let block = propose(&mut proposer);
+    assert_eq!(2, block.deploy_hashes().len());
+    assert_eq!(
+        block.deploy_hashes(),
+        propose(&mut proposer).deploy_hashes()
+    );
+
+    // The remaining deploys are recorded as deferred because the block is full.
+    let deferred = proposer.deferred.clone();
+    assert_eq!(3, deferred.len());
+    for (deploy_hash, reason) in deferred {
+        assert!(!block.deploy_hashes().contains(&deploy_hash));
+        assert_eq!(DeferralReason::BlockFull, reason);
+    }
+}
+
+#[test]
+fn should_respect_size_class_limits() {
+    let mut rng = crate::new_rng();
+    let creation_time = Timestamp::from(100);
+    let ttl = TimeDiff::from(Duration::from_millis(100));
+    let block_time = Timestamp::from(120);
+
+    let mut proposer = create_test_proposer(0.into());
+    let deploys = (0..3)
+        .map(|_| {
+            generate_deploy(
+                &mut rng,
+                creation_time,
+                ttl,
+                vec![],
+                default_gas_payment(),
+                DEFAULT_TEST_GAS_PRICE,
+            )
+        })
+        .collect::<Vec<_>>();
+    for deploy in &deploys {
+        proposer.add_deploy_or_transfer(creation_time, *deploy.id(), deploy.deploy_type().unwrap());
+    }
+
+    let propose = |proposer: &mut BlockProposerReady, deploy_config| {
+        proposer.propose_block_payload(
+            deploy_config,
+            BlockContext::new(block_time, vec![]),
+            TEST_GAS_PRICE_FLOOR,
+            EraId::from(0),
+            &test_proposer(),
+            vec![],
+            true,
+        )
+    };
+
+    // Only two small deploys fit into a block; the third one is deferred.
+    let mut deploy_config = proposer.deploy_config;
+    deploy_config.size_classes.small.block_max_count = 2;
+    let block = propose(&mut proposer, deploy_config);
+    assert_eq!(2, block.deploy_hashes().len());
+    assert_eq!(1, proposer.deferred.len());
+    assert_eq!(DeferralReason::BlockFull, proposer.deferred[0].1);
+
+    // Small deploys paying less than the class minimum are not proposed at all.
+    let mut deploy_config = proposer.deploy_config;
+    deploy_config.size_classes.small.minimum_payment_motes = 2;
+    let block = propose(&mut proposer, deploy_config);
+    assert!(block.deploy_hashes().is_empty());
+    assert_eq!(3, proposer.deferred.len());
+    for (_, reason) in &proposer.deferred {
+        assert_eq!(DeferralReason::InvalidInBlock, *reason);
+    }
+}
+
+#[test]
+fn should_evict_cheapest_and_oldest_pending_deploys_over_capacity() {
+    let mut rng = crate::new_rng();
+    let ttl = TimeDiff::from(Duration::from_millis(1000));
+
+    let mut proposer = create_test_proposer(0.into());
+    proposer.local_config.max_pending_deploys = 2;
+
+    let mut add_deploy = |proposer: &mut BlockProposerReady, received: u64, gas_price: u64| {
+        let deploy = generate_deploy(
+            &mut rng,
+            Timestamp::from(100),
+            ttl,
+            vec![],
+            default_gas_payment(),
+            gas_price,
+        );
+        proposer.add_deploy_or_transfer(
+            Timestamp::from(received),
+            *deploy.id(),
+            deploy.deploy_type().unwrap(),
+        );
+        *deploy.id()
+    };
+
+    let old_cheap = add_deploy(&mut proposer, 100, 1);
+    let new_cheap = add_deploy(&mut proposer, 110, 1);
+    let old_expensive = add_deploy(&mut proposer, 100, 2);
+    assert_eq!(proposer.evict_excess_pending(), vec![old_cheap]);
+
+    let new_expensive = add_deploy(&mut proposer, 120, 2);
+    assert_eq!(proposer.evict_excess_pending(), vec![new_cheap]);
+    assert!(proposer.evict_excess_pending().is_empty());
+    assert!(proposer.sets.pending.contains_key(&old_expensive));
+    assert!(proposer.sets.pending.contains_key(&new_expensive));
+    let expected_size: usize = proposer
+        .sets
+        .pending
+        .iter()
+        .map(|(_, (deploy_type, _))| deploy_type.size())
+        .sum();
+    assert_eq!(proposer.sets.pending.total_size(), expected_size);
+
+    // The size limit is respected as well.
+    proposer.local_config.max_pending_deploys_size = 0;
+    assert_eq!(proposer.evict_excess_pending().len(), 2);
+    assert_eq!(proposer.sets.pending.len(), 0);
+    assert_eq!(proposer.sets.pending.total_size(), 0);
+}
+
+#[test]
+fn should_record_rejected_deploys_and_inclusion_latencies() {
+    let creation_time = Timestamp::from(100);
+    let ttl = TimeDiff::from(Duration::from_millis(100));
+    let block_time = Timestamp::from(120);
+
+    let mut rng = crate::new_rng();
+    let mut generate = |dependencies| {
+        generate_deploy(
+            &mut rng,
+            creation_time,
+            ttl,
+            dependencies,
+            default_gas_payment(),
+            DEFAULT_TEST_GAS_PRICE,
+        )
+    };
+    let deploy1 = generate(vec![]);
+    let deploy2 = generate(vec![*deploy1.id()]);
+    let expired = generate(vec![]);
+
+    let mut proposer = create_test_proposer(0.into());
+
+    // An expired deploy is rejected right away.
+    proposer.add_deploy_or_transfer(
+        Timestamp::from(1000),
+        *expired.id(),
+        expired.deploy_type().unwrap(),
+    );
+    // deploy2 is left out of the block, since deploy1 is not known yet.
+    proposer.add_deploy_or_transfer(creation_time, *deploy2.id(), deploy2.deploy_type().unwrap());
+    proposer.propose_block_payload(
+        DeployConfig::default(),
+        BlockContext::new(block_time, vec![]),
+        TEST_GAS_PRICE_FLOOR,
+        EraId::from(0),
+        &test_proposer(),
+        vec![],
+        true,
+    );
+    assert_eq!(
+        proposer.rejected,
+        vec![RejectionReason::Expired, RejectionReason::MissingDependency]
+    );
+    assert!(proposer.inclusion_latencies.is_empty());
+
+    // Finalizing a pending deploy records its latency, and buffering it again is a duplicate.
+    proposer.rejected.clear();
+    proposer.add_deploy_or_transfer(creation_time, *deploy1.id(), deploy1.deploy_type().unwrap());
+    proposer.finalized_deploys(vec![*deploy1.id()]);
+    assert_eq!(proposer.inclusion_latencies.len(), 1);
+    proposer.add_deploy_or_transfer(creation_time, *deploy1.id(), deploy1.deploy_type().unwrap());
+    assert_eq!(proposer.rejected, vec![RejectionReason::Duplicate]);
+}
+
+#[test]
+fn should_reject_deploy_closing_a_dependency_cycle() {
+    let creation_time = Timestamp::from(100);
+    let ttl = TimeDiff::from(Duration::from_millis(100));
+
+    let mut rng = crate::new_rng();
+    // Deploy hashes cover the dependencies, so the cycle needs made-up hashes.
+    let hash1 = DeployHash::random(&mut rng);
+    let hash2 = DeployHash::random(&mut rng);
+    let hash3 = DeployHash::random(&mut rng);
+    let mut generate = |dependencies| {
+        generate_deploy(
+            &mut rng,
+            creation_time,
+            ttl,
+            dependencies,
+            default_gas_payment(),
+            DEFAULT_TEST_GAS_PRICE,
+        )
+        .deploy_type()
+        .unwrap()
+    };
+    let deploy1 = generate(vec![hash3]);
+    let deploy2 = generate(vec![hash1]);
+    let deploy3 = generate(vec![hash2]);
+
+    let mut proposer = create_test_proposer(0.into());
+    proposer.add_deploy_or_transfer(creation_time, hash1, deploy1);
+    proposer.add_deploy_or_transfer(creation_time, hash2, deploy2);
+    assert!(proposer.rejected.is_empty());
+
+    // deploy3 depends on deploy2, which depends on deploy1, which depends on deploy3.
+    proposer.add_deploy_or_transfer(creation_time, hash3, deploy3);
+    assert_eq!(proposer.rejected, vec![RejectionReason::DependencyCycle]);
+    assert_eq!(proposer.sets.pending.len(), 2);
+    assert!(!proposer.sets.pending.contains_key(&hash3));
+    assert_eq!(
+        proposer.dependency_cycles.iter().collect::<Vec<_>>(),
+        vec![&DependencyCycle {
+            deploy_hash: hash3,
+            cycle: vec![hash3, hash2, hash1, hash3],
+        }]
+    );
+}
+
+#[test]
+fn should_only_reload_orphaned_deploys_that_are_neither_pending_nor_finalized() {
+    let creation_time = Timestamp::from(100);
+    let ttl = TimeDiff::from(Duration::from_millis(100));
+
+    let mut rng = crate::new_rng();
+    let mut generate = || {
+        generate_deploy(
+            &mut rng,
+            creation_time,
+            ttl,
+            vec![],
+            default_gas_payment(),
+            DEFAULT_TEST_GAS_PRICE,
+        )
+    };
+    let pending = generate();
+    let finalized = generate();
+    let evicted = generate();
+
+    let mut proposer = create_test_proposer(0.into());
+    proposer.add_deploy_or_transfer(creation_time, *pending.id(), pending.deploy_type().unwrap());
+    proposer.finalized_deploys(vec![*finalized.id()]);
+
+    let block_payload = BlockPayload::new(
+        vec![*pending.id(), *finalized.id(), *evicted.id()],
+        vec![],
+        vec![],
+        false,
+    );
+    assert_eq!(
+        proposer.orphaned_deploys_to_load(&block_payload).to_vec(),
+        vec![*evicted.id()]
+    );
+}
+
+#[test]
+fn should_filter_and_paginate_pending_deploys() {
+    let creation_time = Timestamp::from(100);
+    let ttl = TimeDiff::from(Duration::from_millis(1000));
+    let now = Timestamp::from(500);
+
+    let mut rng = crate::new_rng();
+    let mut proposer = create_test_proposer(0.into());
+    let mut hashes = Vec::new();
+    for (index, gas_price) in [1, 2, 3, 4].iter().enumerate() {
+        let deploy = generate_deploy(
+            &mut rng,
+            creation_time,
+            ttl,
+            vec![],
+            default_gas_payment(),
+            *gas_price,
+        );
+        let received_time = Timestamp::from(100 * (index as u64 + 1));
+        proposer.add_deploy_or_transfer(received_time, *deploy.id(), deploy.deploy_type().unwrap());
+        hashes.push(*deploy.id());
+    }
+    let deploy_hashes = |pending_deploys: PendingDeploys| {
+        pending_deploys
+            .deploys
+            .into_iter()
+            .map(|pending_deploy| pending_deploy.deploy_hash)
+            .collect::<Vec<_>>()
+    };
+
+    let all = proposer.pending_deploys(&PendingDeploysQuery::default(), now);
+    assert_eq!(all.total, 4);
+    assert_eq!(deploy_hashes(all), hashes);
+
+    let page = PendingDeploysQuery {
+        offset: 1,
+        limit: Some(2),
+        ..Default::default()
+    };
+    let pending_deploys = proposer.pending_deploys(&page, now);
+    assert_eq!(pending_deploys.total, 4);
+    assert_eq!(deploy_hashes(pending_deploys), hashes[1..3].to_vec());
+
+    // The deploys were received 400, 300, 200 and 100 ms ago, with gas prices 1 to 4.
+    let filtered = PendingDeploysQuery {
+        min_age: Some(TimeDiff::from(200)),
+        max_gas_price: Some(2),
+        ..Default::default()
+    };
+    let pending_deploys = proposer.pending_deploys(&filtered, now);
+    assert_eq!(pending_deploys.total, 2);
+    assert_eq!(deploy_hashes(pending_deploys), hashes[..2].to_vec());
+
+    let account = proposer
+        .sets
+        .pending
+        .get(&hashes[2])
+        .unwrap()
+        .0
+        .header()
+        .account()
+        .clone();
+    let by_account = PendingDeploysQuery {
+        account: Some(account),
+        ..Default::default()
+    };
+    assert_eq!(
+        deploy_hashes(proposer.pending_deploys(&by_account, now)),
+        vec![hashes[2]]
+    );
 }
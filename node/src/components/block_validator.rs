@@ -1,7 +1,9 @@
 //! Block validator
 //!
 //! The block validator checks whether all the deploys included in the block payload exist, either
-//! locally or on the network.
+//! locally or on the network, and whether they are valid in the block. For proposed blocks, this
+//! includes checking the deploys against the block's ancestry: They must not be included in any
+//! ancestor already, and their dependencies must be included in an ancestor or a stored block.
 //!
 //! When multiple requests are made to validate the same block payload, they will eagerly return
 //! true if valid, but only fail if all sources have been exhausted. This is only relevant when
@@ -35,7 +37,10 @@ use crate::{
         requests::{BlockValidationRequest, FetcherRequest, StorageRequest},
         EffectBuilder, EffectExt, EffectOptionExt, Effects, Responder,
     },
-    types::{appendable_block::AppendableBlock, Block, Chainspec, Deploy, DeployHash, Timestamp},
+    types::{
+        appendable_block::AppendableBlock, chainspec::DeployConfig, Block, Chainspec, Deploy,
+        DeployHash, Timestamp,
+    },
     NodeRng,
 };
 use keyed_counter::KeyedCounter;
@@ -67,10 +72,12 @@ impl DeployOrTransferHash {
 
 #[derive(DataSize, Debug, Display, Clone, Hash, Eq, PartialEq)]
 pub enum ValidatingBlock {
+    /// A block from the linear chain.
     #[display(fmt = "{}", _0.display())]
     Block(Box<Block>),
+    /// A block proposed in consensus, together with its gas price floor.
     #[display(fmt = "{}", _0.display())]
-    ProposedBlock(Box<ProposedBlock<ClContext>>),
+    ProposedBlock(Box<ProposedBlock<ClContext>>, u64),
 }
 
 impl From<Block> for ValidatingBlock {
@@ -79,31 +86,63 @@ impl From<Block> for ValidatingBlock {
     }
 }
 
-impl From<ProposedBlock<ClContext>> for ValidatingBlock {
-    fn from(proposed_block: ProposedBlock<ClContext>) -> ValidatingBlock {
-        ValidatingBlock::ProposedBlock(Box::new(proposed_block))
-    }
-}
-
 impl ValidatingBlock {
     fn timestamp(&self) -> Timestamp {
         match self {
             ValidatingBlock::Block(block) => block.timestamp(),
-            ValidatingBlock::ProposedBlock(pb) => pb.context().timestamp(),
+            ValidatingBlock::ProposedBlock(pb, _) => pb.context().timestamp(),
+        }
+    }
+
+    /// Returns the minimum gas price of the block's deploys.
+    ///
+    /// Blocks from the linear chain have already been accepted by the network, so their deploys'
+    /// gas prices aren't checked.
+    fn gas_price_floor(&self) -> u64 {
+        match self {
+            ValidatingBlock::Block(_) => 0,
+            ValidatingBlock::ProposedBlock(_, gas_price_floor) => *gas_price_floor,
+        }
+    }
+
+    /// Returns an empty `AppendableBlock` to validate this block's deploys against.
+    ///
+    /// Blocks from the linear chain may predate the size class limits, so these aren't enforced.
+    fn appendable_block(&self, deploy_config: DeployConfig) -> AppendableBlock {
+        let appendable_block =
+            AppendableBlock::new(deploy_config, self.timestamp(), self.gas_price_floor());
+        match self {
+            ValidatingBlock::Block(_) => appendable_block.without_size_class_limits(),
+            ValidatingBlock::ProposedBlock(..) => appendable_block,
         }
     }
 
     fn deploy_hashes(&self) -> &[DeployHash] {
         match self {
             ValidatingBlock::Block(block) => block.deploy_hashes(),
-            ValidatingBlock::ProposedBlock(pb) => pb.value().deploy_hashes(),
+            ValidatingBlock::ProposedBlock(pb, _) => pb.value().deploy_hashes(),
         }
     }
 
     fn transfer_hashes(&self) -> &[DeployHash] {
         match self {
             ValidatingBlock::Block(block) => block.transfer_hashes(),
-            ValidatingBlock::ProposedBlock(pb) => pb.value().transfer_hashes(),
+            ValidatingBlock::ProposedBlock(pb, _) => pb.value().transfer_hashes(),
+        }
+    }
+
+    /// Returns the deploys and transfers of the proposed block's ancestors in the current era.
+    /// Blocks from the linear chain have no known ancestors.
+    fn ancestor_deploys(&self) -> HashSet<DeployHash> {
+        match self {
+            ValidatingBlock::Block(_) => HashSet::new(),
+            ValidatingBlock::ProposedBlock(pb, _) => pb
+                .context()
+                .ancestor_values()
+                .iter()
+                .flat_map(|block_payload| block_payload.deploys_and_transfers_iter())
+                .copied()
+                .collect(),
         }
     }
 
@@ -141,6 +180,14 @@ pub enum Event<I> {
     /// Deploy was invalid. Unable to convert to a deploy type.
     #[display(fmt = "{} invalid", _0)]
     CannotConvertDeploy(DeployOrTransferHash),
+
+    /// A dependency of a deploy has been found in a stored block.
+    #[display(fmt = "dependency {} found", _0)]
+    DependencyFound(DeployHash),
+
+    /// A dependency of a deploy is not included in any stored block.
+    #[display(fmt = "dependency {} missing", _0)]
+    DependencyMissing(DeployHash),
 }
 
 /// State of the current process of block validation.
@@ -152,6 +199,11 @@ pub(crate) struct BlockValidationState<I> {
     appendable_block: AppendableBlock,
     /// The deploys that have not yet been "crossed off" the list of potential misses.
     missing_deploys: HashSet<DeployOrTransferHash>,
+    /// The deploys of the block's ancestors, which satisfy dependencies.
+    ancestor_deploys: HashSet<DeployHash>,
+    /// Dependencies of the block's deploys that are not included in an ancestor, and are still
+    /// being looked up in storage.
+    missing_dependencies: HashSet<DeployHash>,
     /// A list of responders that are awaiting an answer.
     responders: SmallVec<[Responder<bool>; 2]>,
     /// Peers that should have the data.
@@ -178,6 +230,25 @@ where
         self.sources.pop_front()
     }
 
+    /// Returns the validation result, or `None` if deploys or dependencies are still missing.
+    fn result(&self, block: &ValidatingBlock) -> Option<bool> {
+        if !self.missing_deploys.is_empty() || !self.missing_dependencies.is_empty() {
+            return None;
+        }
+        // Proposed blocks must list their deploys in canonical order. Blocks from the linear chain
+        // may predate that rule, so they aren't checked.
+        let is_valid = match block {
+            ValidatingBlock::Block(_) => true,
+            ValidatingBlock::ProposedBlock(..) => self
+                .appendable_block
+                .is_in_canonical_order(block.deploy_hashes(), block.transfer_hashes()),
+        };
+        if !is_valid {
+            info!(?block, "deploys not in canonical order. block is invalid");
+        }
+        Some(is_valid)
+    }
+
     fn respond<REv>(&mut self, value: bool) -> Effects<REv> {
         self.responders
             .drain(..)
@@ -265,11 +336,23 @@ where
                     self.log_block_with_replay(sender, &block);
                     return responder.respond(false).ignore();
                 }
+                // Deploys already included in an ancestor must not be replayed.
+                let ancestor_deploys = block.ancestor_deploys();
+                if let Some(dt_hash) = block_deploys
+                    .iter()
+                    .find(|dt_hash| ancestor_deploys.contains(&(**dt_hash).into()))
+                {
+                    info!(
+                        ?sender, %dt_hash,
+                        "received invalid block containing a deploy from an ancestor"
+                    );
+                    return responder.respond(false).ignore();
+                }
 
                 match self.validation_states.entry(block) {
                     Entry::Occupied(mut entry) => {
                         // The entry already exists.
-                        if entry.get().missing_deploys.is_empty() {
+                        if entry.get().result(entry.key()) == Some(true) {
                             // Block has already been validated successfully, early return to
                             // caller.
                             effects.extend(responder.respond(true).ignore());
@@ -292,11 +375,13 @@ where
                                 fetch_deploy(effect_builder, dt_hash, sender.clone())
                             },
                         ));
-                        let block_timestamp = entry.key().timestamp();
-                        let deploy_config = self.chainspec.deploy_config;
+                        let appendable_block =
+                            entry.key().appendable_block(self.chainspec.deploy_config);
                         entry.insert(BlockValidationState {
-                            appendable_block: AppendableBlock::new(deploy_config, block_timestamp),
+                            appendable_block,
                             missing_deploys: block_deploys,
+                            ancestor_deploys,
+                            missing_dependencies: HashSet::new(),
                             responders: smallvec![responder],
                             sources: VecDeque::new(), /* This is empty b/c we create the first
                                                        * request using `sender`. */
@@ -314,6 +399,9 @@ where
                 // If a deploy is received for a given block that makes that block invalid somehow,
                 // mark it for removal.
                 let mut invalid = Vec::new();
+                // Dependencies which are not included in the ancestors of a proposed block must be
+                // looked up in storage.
+                let mut lookups = HashSet::new();
 
                 // Our first pass updates all validation states, crossing off the found deploy.
                 for (key, state) in self.validation_states.iter_mut() {
@@ -328,6 +416,14 @@ where
                         {
                             info!(block = ?key, %dt_hash, ?deploy_type, ?err, "block invalid");
                             invalid.push(key.clone());
+                        } else if let ValidatingBlock::ProposedBlock(..) = key {
+                            for dependency in deploy_type.header().dependencies() {
+                                if !state.ancestor_deploys.contains(dependency)
+                                    && state.missing_dependencies.insert(*dependency)
+                                {
+                                    lookups.insert(*dependency);
+                                }
+                            }
                         }
                     }
                 }
@@ -338,13 +434,19 @@ where
                         effects.extend(state.respond(false));
                         return false;
                     }
-                    if state.missing_deploys.is_empty() {
-                        // This one is done and valid.
-                        effects.extend(state.respond(true));
+                    if let Some(is_valid) = state.result(key) {
+                        // This one is done.
+                        effects.extend(state.respond(is_valid));
                         return false;
                     }
                     true
                 });
+
+                effects.extend(
+                    lookups
+                        .into_iter()
+                        .flat_map(|dependency| look_up_dependency(effect_builder, dependency)),
+                );
             }
             Event::DeployMissing(dt_hash) => {
                 info!(%dt_hash, "request to download deploy timed out");
@@ -413,6 +515,35 @@ where
                     }
                 });
             }
+            Event::DependencyFound(dependency) => {
+                self.validation_states.retain(|key, state| {
+                    if !state.missing_dependencies.remove(&dependency) {
+                        return true;
+                    }
+                    match state.result(key) {
+                        Some(is_valid) => {
+                            effects.extend(state.respond(is_valid));
+                            false
+                        }
+                        None => true,
+                    }
+                });
+            }
+            Event::DependencyMissing(dependency) => {
+                self.validation_states.retain(|key, state| {
+                    if state.missing_dependencies.contains(&dependency) {
+                        // The dependency is neither included in an ancestor nor finalized.
+                        info!(
+                            block = ?key, %dependency,
+                            "deploy dependency unresolved. block is invalid"
+                        );
+                        effects.extend(state.respond(false));
+                        false
+                    } else {
+                        true
+                    }
+                });
+            }
         }
         effects
     }
@@ -447,3 +578,20 @@ where
         .fetch_deploy(dt_hash.into(), sender)
         .map_or_else(validate_deploy, move || Event::DeployMissing(dt_hash))
 }
+
+/// Returns effects that check whether the given dependency is included in a stored block.
+fn look_up_dependency<REv, I>(
+    effect_builder: EffectBuilder<REv>,
+    dependency: DeployHash,
+) -> Effects<Event<I>>
+where
+    REv: From<StorageRequest> + Send,
+    I: Send + 'static,
+{
+    effect_builder
+        .get_block_header_for_deploy_from_storage(dependency)
+        .event(move |maybe_header| match maybe_header {
+            Some(_) => Event::DependencyFound(dependency),
+            None => Event::DependencyMissing(dependency),
+        })
+}
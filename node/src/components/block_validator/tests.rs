@@ -2,18 +2,18 @@ use std::sync::Arc;
 
 use casper_execution_engine::core::engine_state::executable_deploy_item::ExecutableDeployItem;
 use casper_types::{
-    bytesrepr::Bytes, runtime_args, system::standard_payment::ARG_AMOUNT, RuntimeArgs, SecretKey,
-    U512,
+    bytesrepr::Bytes, runtime_args, system::standard_payment::ARG_AMOUNT, EraId, ProtocolVersion,
+    PublicKey, RuntimeArgs, SecretKey, U512,
 };
 use derive_more::From;
 use itertools::Itertools;
 
 use crate::{
     components::{consensus::BlockContext, fetcher::FetchResult},
-    crypto::AsymmetricKeyExt,
+    crypto::{hash::Digest, AsymmetricKeyExt},
     reactor::{EventQueueHandle, QueueKind, Scheduler},
     testing::TestRng,
-    types::{BlockPayload, TimeDiff},
+    types::{BlockHash, BlockPayload, FinalizedBlock, TimeDiff},
     utils::{self, Loadable},
 };
 
@@ -80,32 +80,93 @@ impl MockReactor {
             panic!("unexpected event: {:?}", reactor_event);
         }
     }
+
+    /// Expects a request for the block containing a deploy, and responds with a header if the
+    /// deploy is among the `stored` ones.
+    async fn expect_block_header_for_deploy(&self, rng: &mut TestRng, stored: &[DeployHash]) {
+        let (reactor_event, _) = self.scheduler.pop().await;
+        if let ReactorEvent::Storage(StorageRequest::GetBlockHeaderForDeploy {
+            deploy_hash,
+            responder,
+        }) = reactor_event
+        {
+            let maybe_header = stored
+                .contains(&deploy_hash)
+                .then(|| Block::random(rng).header().clone());
+            responder.respond(maybe_header).await;
+        } else {
+            panic!("unexpected event: {:?}", reactor_event);
+        }
+    }
 }
 
+/// The gas price floor of the proposed blocks, equal to the gas price of the test deploys.
+const GAS_PRICE_FLOOR: u64 = 1;
+
+/// The payment amount of the test deploys, equal to the local chainspec's minimum payment for
+/// small deploys.
+const PAYMENT_AMOUNT: u64 = 100_000_000;
+
 fn new_proposed_block(
     timestamp: Timestamp,
     deploy_hashes: Vec<DeployHash>,
     transfer_hashes: Vec<DeployHash>,
+    ancestors: Vec<Arc<BlockPayload>>,
 ) -> ProposedBlock<ClContext> {
-    // Accusations and ancestors are empty, and the random bit is always true:
+    // Accusations are empty, and the random bit is always true:
     // These values are not checked by the block validator.
-    let block_context = BlockContext::new(timestamp, vec![]);
+    let block_context = BlockContext::new(timestamp, ancestors);
     let block_payload = BlockPayload::new(deploy_hashes, transfer_hashes, vec![], true);
     ProposedBlock::new(Arc::new(block_payload), block_context)
 }
 
+/// Creates a block from the linear chain with the given deploys.
+fn new_block(rng: &mut TestRng, timestamp: Timestamp, deploy_hashes: Vec<DeployHash>) -> Block {
+    let block_payload = BlockPayload::new(deploy_hashes, vec![], vec![], true);
+    let proposer = PublicKey::from(&SecretKey::random(rng));
+    let finalized_block =
+        FinalizedBlock::new(block_payload, None, timestamp, EraId::from(0), 0, proposer);
+    Block::new(
+        BlockHash::new(Digest::random(rng)),
+        Digest::random(rng),
+        Digest::random(rng),
+        finalized_block,
+        None,
+        ProtocolVersion::V1_0_0,
+        GAS_PRICE_FLOOR,
+    )
+}
+
 fn new_deploy(rng: &mut TestRng, timestamp: Timestamp, ttl: TimeDiff) -> Deploy {
+    new_deploy_with_dependencies(rng, timestamp, ttl, vec![])
+}
+
+fn new_deploy_with_dependencies(
+    rng: &mut TestRng,
+    timestamp: Timestamp,
+    ttl: TimeDiff,
+    dependencies: Vec<DeployHash>,
+) -> Deploy {
+    new_deploy_with_payment(rng, timestamp, ttl, dependencies, PAYMENT_AMOUNT)
+}
+
+fn new_deploy_with_payment(
+    rng: &mut TestRng,
+    timestamp: Timestamp,
+    ttl: TimeDiff,
+    dependencies: Vec<DeployHash>,
+    payment_amount: u64,
+) -> Deploy {
     let secret_key = SecretKey::random(rng);
     let chain_name = "chain".to_string();
     let payment = ExecutableDeployItem::ModuleBytes {
         module_bytes: Bytes::new(),
-        args: runtime_args! { ARG_AMOUNT => U512::from(1) },
+        args: runtime_args! { ARG_AMOUNT => U512::from(payment_amount) },
     };
     let session = ExecutableDeployItem::ModuleBytes {
         module_bytes: Bytes::new(),
         args: RuntimeArgs::new(),
     };
-    let dependencies = vec![];
     let gas_price = 1;
 
     Deploy::new(
@@ -145,18 +206,69 @@ fn new_transfer(rng: &mut TestRng, timestamp: Timestamp, ttl: TimeDiff) -> Deplo
     )
 }
 
-/// Validates a block using a `BlockValidator` component, and returns the result.
+/// Validates a block with the given deploys and transfers sorted into canonical order, and returns
+/// the result.
 async fn validate_block(
+    rng: &mut TestRng,
+    timestamp: Timestamp,
+    mut deploys: Vec<Deploy>,
+    mut transfers: Vec<Deploy>,
+) -> bool {
+    deploys.sort_by_key(Deploy::order_key);
+    transfers.sort_by_key(Deploy::order_key);
+    validate_block_in_given_order(rng, timestamp, deploys, transfers).await
+}
+
+/// Validates a block with the given deploys and ancestors, and returns the result. Dependencies
+/// are found in storage if they are among the `stored` deploys.
+async fn validate_block_with_ancestors(
+    rng: &mut TestRng,
+    timestamp: Timestamp,
+    mut deploys: Vec<Deploy>,
+    ancestors: Vec<Arc<BlockPayload>>,
+    stored: &[DeployHash],
+) -> bool {
+    deploys.sort_by_key(Deploy::order_key);
+    let deploy_hashes = deploys.iter().map(|deploy| *deploy.id()).collect_vec();
+    let proposed_block = new_proposed_block(timestamp, deploy_hashes, vec![], ancestors);
+    validate_proposed_block(rng, proposed_block, GAS_PRICE_FLOOR, deploys, stored).await
+}
+
+/// Validates a block in the given order, without ancestors, and returns the result.
+async fn validate_block_in_given_order(
     rng: &mut TestRng,
     timestamp: Timestamp,
     deploys: Vec<Deploy>,
     transfers: Vec<Deploy>,
 ) -> bool {
-    // Assemble the block to be validated.
     let deploy_hashes = deploys.iter().map(|deploy| *deploy.id()).collect_vec();
     let transfer_hashes = transfers.iter().map(|deploy| *deploy.id()).collect_vec();
-    let proposed_block = new_proposed_block(timestamp, deploy_hashes, transfer_hashes);
+    let proposed_block = new_proposed_block(timestamp, deploy_hashes, transfer_hashes, vec![]);
+    let deploys = deploys.into_iter().chain(transfers).collect();
+    validate_proposed_block(rng, proposed_block, GAS_PRICE_FLOOR, deploys, &[]).await
+}
+
+/// Validates a proposed block with the given gas price floor, and returns the result.
+async fn validate_proposed_block(
+    rng: &mut TestRng,
+    proposed_block: ProposedBlock<ClContext>,
+    gas_price_floor: u64,
+    deploys: Vec<Deploy>,
+    stored: &[DeployHash],
+) -> bool {
+    let validating_block =
+        ValidatingBlock::ProposedBlock(Box::new(proposed_block), gas_price_floor);
+    validate(rng, validating_block, deploys, stored).await
+}
 
+/// Validates a block using a `BlockValidator` component, and returns the result. The `deploys`
+/// must contain the block's deploys and transfers, in the order in which they are fetched.
+async fn validate(
+    rng: &mut TestRng,
+    validating_block: ValidatingBlock,
+    deploys: Vec<Deploy>,
+    stored: &[DeployHash],
+) -> bool {
     // Create the reactor and component.
     let reactor = MockReactor::new();
     let effect_builder = EffectBuilder::new(EventQueueHandle::new(reactor.scheduler));
@@ -165,8 +277,7 @@ async fn validate_block(
 
     // Pass the block to the component. This future will eventually resolve to the result, i.e.
     // whether the block is valid or not.
-    let validation_result =
-        tokio::spawn(effect_builder.validate_block("Bob", proposed_block.clone()));
+    let validation_result = tokio::spawn(effect_builder.validate_block("Bob", validating_block));
     let event = reactor.expect_block_validator_event().await;
     let effects = block_validator.handle_event(effect_builder, rng, event);
 
@@ -183,7 +294,7 @@ async fn validate_block(
     let fetch_results: Vec<_> = effects.into_iter().map(tokio::spawn).collect();
 
     // We make our mock reactor answer with the expected deploys and transfers:
-    for deploy in deploys.into_iter().chain(transfers) {
+    for deploy in deploys {
         reactor.expect_fetch_deploy(deploy).await;
     }
 
@@ -198,6 +309,23 @@ async fn validate_block(
         }));
     }
 
+    // Dependencies that are not included in an ancestor are looked up in storage. Once they have
+    // all been found, or any of them is missing, the component will respond.
+    while !block_validator.validation_states.is_empty() {
+        let lookup_results: Vec<_> = effects.into_iter().map(tokio::spawn).collect();
+        for _ in 0..lookup_results.len() {
+            reactor.expect_block_header_for_deploy(rng, stored).await;
+        }
+        effects = Effects::new();
+        for lookup_result in lookup_results {
+            let events = lookup_result.await.unwrap();
+            assert_eq!(1, events.len());
+            effects.extend(events.into_iter().flat_map(|dependency_lookup| {
+                block_validator.handle_event(effect_builder, rng, dependency_lookup)
+            }));
+        }
+    }
+
     // We expect exactly one effect: the validation response. This will resolve the result.
     assert_eq!(1, effects.len());
     for effect in effects {
@@ -280,3 +408,123 @@ async fn transfer_deploy_mixup_and_replay() {
     let transfers = vec![transfer1.clone(), transfer2.clone(), transfer2.clone()];
     assert!(!validate_block(&mut rng, timestamp, deploys, transfers).await);
 }
+
+/// Verifies that a proposed block is invalid unless its deploys and transfers are each listed in
+/// canonical order.
+#[tokio::test]
+async fn canonical_order() {
+    let mut rng = TestRng::new();
+    let ttl = TimeDiff::from(200);
+    let timestamp = Timestamp::from(1000);
+    let mut deploys = vec![
+        new_deploy(&mut rng, timestamp, ttl),
+        new_deploy(&mut rng, timestamp, ttl),
+    ];
+    deploys.sort_by_key(Deploy::order_key);
+    let mut transfers = vec![
+        new_transfer(&mut rng, timestamp, ttl),
+        new_transfer(&mut rng, timestamp, ttl),
+    ];
+    transfers.sort_by_key(Deploy::order_key);
+
+    assert!(
+        validate_block_in_given_order(&mut rng, timestamp, deploys.clone(), transfers.clone())
+            .await
+    );
+
+    let reversed_deploys = deploys.iter().rev().cloned().collect();
+    assert!(
+        !validate_block_in_given_order(&mut rng, timestamp, reversed_deploys, transfers.clone())
+            .await
+    );
+    let reversed_transfers = transfers.into_iter().rev().collect();
+    assert!(!validate_block_in_given_order(&mut rng, timestamp, deploys, reversed_transfers).await);
+}
+
+/// Verifies that a proposed block is invalid if it contains a deploy from one of its ancestors, or
+/// a deploy with a dependency that is neither included in an ancestor nor in a stored block.
+#[tokio::test]
+async fn ancestry_and_dependencies() {
+    let mut rng = TestRng::new();
+    let ttl = TimeDiff::from(200);
+    let timestamp = Timestamp::from(1000);
+    let ancestor_deploy = new_deploy(&mut rng, timestamp, ttl);
+    let stored_deploy = new_deploy(&mut rng, timestamp, ttl);
+    let unknown_deploy = new_deploy(&mut rng, timestamp, ttl);
+    let ancestor_payload = BlockPayload::new(vec![*ancestor_deploy.id()], vec![], vec![], true);
+    let ancestors = vec![Arc::new(ancestor_payload)];
+    let stored = [*stored_deploy.id()];
+    let mut depending_on = |deploy: &Deploy| {
+        new_deploy_with_dependencies(&mut rng, timestamp, ttl, vec![*deploy.id()])
+    };
+    let depends_on_ancestor = depending_on(&ancestor_deploy);
+    let depends_on_stored = depending_on(&stored_deploy);
+    let depends_on_unknown = depending_on(&unknown_deploy);
+
+    // Dependencies may be included in an ancestor or in a stored block.
+    let deploys = vec![depends_on_ancestor.clone(), depends_on_stored];
+    assert!(
+        validate_block_with_ancestors(&mut rng, timestamp, deploys, ancestors.clone(), &stored)
+            .await
+    );
+
+    // Without the ancestor, the first dependency is unresolved.
+    let deploys = vec![depends_on_ancestor];
+    assert!(!validate_block_with_ancestors(&mut rng, timestamp, deploys, vec![], &stored).await);
+
+    // A dependency that is neither in an ancestor nor stored is unresolved, too.
+    let deploys = vec![depends_on_unknown];
+    assert!(
+        !validate_block_with_ancestors(&mut rng, timestamp, deploys, ancestors.clone(), &stored)
+            .await
+    );
+
+    // A deploy from an ancestor must not be included again.
+    let deploys = vec![ancestor_deploy];
+    assert!(!validate_block_with_ancestors(&mut rng, timestamp, deploys, ancestors, &stored).await);
+}
+
+/// Verifies that a proposed block is invalid if any of its deploys or transfers offers a gas price
+/// below the block's gas price floor.
+#[tokio::test]
+async fn gas_price_floor() {
+    let mut rng = TestRng::new();
+    let ttl = TimeDiff::from(200);
+    let timestamp = Timestamp::from(1000);
+    let deploy = new_deploy(&mut rng, timestamp, ttl);
+    let transfer = new_transfer(&mut rng, timestamp, ttl);
+
+    let deploy_block = || new_proposed_block(timestamp, vec![*deploy.id()], vec![], vec![]);
+    let transfer_block = || new_proposed_block(timestamp, vec![], vec![*transfer.id()], vec![]);
+
+    let floor = GAS_PRICE_FLOOR;
+    let deploys = vec![deploy.clone()];
+    assert!(validate_proposed_block(&mut rng, deploy_block(), floor, deploys, &[]).await);
+    let transfers = vec![transfer.clone()];
+    assert!(validate_proposed_block(&mut rng, transfer_block(), floor, transfers, &[]).await);
+
+    let floor = GAS_PRICE_FLOOR + 1;
+    let deploys = vec![deploy.clone()];
+    assert!(!validate_proposed_block(&mut rng, deploy_block(), floor, deploys, &[]).await);
+    let transfers = vec![transfer.clone()];
+    assert!(!validate_proposed_block(&mut rng, transfer_block(), floor, transfers, &[]).await);
+}
+
+/// Verifies that a proposed block is invalid if one of its deploys pays less than the minimum
+/// payment for its size class, but a block from the linear chain isn't subject to that limit.
+#[tokio::test]
+async fn size_class_limits() {
+    let mut rng = TestRng::new();
+    let ttl = TimeDiff::from(200);
+    let timestamp = Timestamp::from(1000);
+    let deploy = new_deploy_with_payment(&mut rng, timestamp, ttl, vec![], PAYMENT_AMOUNT - 1);
+
+    let proposed_block = new_proposed_block(timestamp, vec![*deploy.id()], vec![], vec![]);
+    let deploys = vec![deploy.clone()];
+    assert!(
+        !validate_proposed_block(&mut rng, proposed_block, GAS_PRICE_FLOOR, deploys, &[]).await
+    );
+
+    let block = new_block(&mut rng, timestamp, vec![*deploy.id()]);
+    assert!(validate(&mut rng, block.into(), vec![deploy], &[]).await);
+}
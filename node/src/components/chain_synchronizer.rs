@@ -0,0 +1,498 @@
+//! Chain synchronizer.
+//!
+//! Downloads the global state trie under a trusted state root hash from peers, so a joining node
+//! can start executing blocks from that state rather than replaying the whole linear chain.
+//!
+//! Starting from the root node, trie nodes are fetched in parallel from all connected peers in a
+//! round-robin fashion.  Each downloaded node is verified against the hash it was requested by and
+//! written into the local trie store, which reports back the descendants of the node which are
+//! still missing locally.  These are then queued for fetching, until no missing nodes remain.
+
+mod config;
+mod event;
+mod metrics;
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    convert::Infallible,
+    time::Instant,
+};
+
+use prometheus::Registry;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::{debug, error, info, warn};
+
+use casper_execution_engine::{
+    core::engine_state,
+    shared::{newtypes::Blake2bHash, stored_value::StoredValue},
+    storage::trie::Trie,
+};
+use casper_types::Key;
+
+use crate::{
+    components::{fetcher::FetchResult, Component},
+    crypto::hash::Digest,
+    effect::{
+        requests::{ChainSynchronizerRequest, ContractRuntimeRequest, FetcherRequest},
+        EffectBuilder, EffectExt, Effects, Responder,
+    },
+    types::{Item, NodeId},
+    NodeRng,
+};
+
+pub use config::Config;
+pub use event::Event;
+use metrics::ChainSynchronizerMetrics;
+
+/// A helper trait constraining `ChainSynchronizer` compatible reactor events.
+pub trait ReactorEventT:
+    From<Event>
+    + From<FetcherRequest<NodeId, Trie<Key, StoredValue>>>
+    + From<ContractRuntimeRequest>
+    + Send
+    + 'static
+{
+}
+
+impl<REv> ReactorEventT for REv where
+    REv: From<Event>
+        + From<FetcherRequest<NodeId, Trie<Key, StoredValue>>>
+        + From<ContractRuntimeRequest>
+        + Send
+        + 'static
+{
+}
+
+/// Error returned when a global state trie sync fails.
+#[derive(Clone, Debug, Error)]
+pub enum Error {
+    /// The chain synchronizer is not running in the current reactor.
+    #[error("chain synchronizer not running")]
+    NotRunning,
+    /// There are no connected peers to download trie nodes from.
+    #[error("no peers to download the trie from")]
+    NoPeers,
+    /// A sync of a different trie is already in progress.
+    #[error("already syncing the trie under state root {0}")]
+    AlreadySyncing(Digest),
+    /// A trie node could not be downloaded from any peer.
+    #[error("failed to fetch trie node {trie_key} after {attempts} attempts")]
+    FetchFailed {
+        /// The hash of the trie node.
+        trie_key: Blake2bHash,
+        /// The number of failed attempts.
+        attempts: usize,
+    },
+    /// A downloaded trie node could not be written into the local trie store.
+    #[error("failed to store trie node {trie_key}: {error}")]
+    PutTrie {
+        /// The hash of the trie node.
+        trie_key: Blake2bHash,
+        /// The underlying error.
+        error: engine_state::Error,
+    },
+}
+
+/// Progress of a global state trie sync.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TrieSyncProgress {
+    /// The state root hash of the trie being synced.
+    pub state_root_hash: Digest,
+    /// Whether the whole trie is stored locally.
+    pub complete: bool,
+    /// The number of trie nodes stored locally so far.
+    pub nodes_synced: u64,
+    /// The number of known trie nodes not stored locally yet.
+    pub nodes_pending: u64,
+    /// An estimate of the percentage of the trie stored locally so far.  The total size of the
+    /// trie is only discovered while downloading it, so the estimate is conservative.
+    pub percent_complete: f64,
+    /// The average number of trie nodes stored per second.
+    pub nodes_per_second: f64,
+}
+
+/// State of a single global state trie sync.
+#[derive(Debug)]
+struct TrieSync {
+    /// The root of the trie being synced.
+    state_root_hash: Blake2bHash,
+    /// Trie nodes queued for fetching.
+    queued: VecDeque<Blake2bHash>,
+    /// Trie nodes currently being fetched or stored.
+    in_flight: HashSet<Blake2bHash>,
+    /// Every trie node which has been queued so far, to avoid fetching shared subtries twice.
+    seen: HashSet<Blake2bHash>,
+    /// Failed fetch attempts per trie node.
+    attempts: HashMap<Blake2bHash, usize>,
+    /// Number of trie nodes stored locally.
+    synced: u64,
+    /// When the sync was started.
+    started: Instant,
+    /// Responders waiting for the sync to finish.
+    responders: Vec<Responder<Result<(), Error>>>,
+}
+
+impl TrieSync {
+    fn new(state_root_hash: Blake2bHash) -> Self {
+        let mut seen = HashSet::new();
+        seen.insert(state_root_hash);
+        TrieSync {
+            state_root_hash,
+            queued: VecDeque::from(vec![state_root_hash]),
+            in_flight: HashSet::new(),
+            seen,
+            attempts: HashMap::new(),
+            synced: 0,
+            started: Instant::now(),
+            responders: Vec::new(),
+        }
+    }
+
+    fn pending(&self) -> u64 {
+        (self.queued.len() + self.in_flight.len()) as u64
+    }
+
+    fn is_complete(&self) -> bool {
+        self.queued.is_empty() && self.in_flight.is_empty()
+    }
+
+    fn progress(&self) -> TrieSyncProgress {
+        let pending = self.pending();
+        let known = self.synced + pending;
+        let percent_complete = if known == 0 {
+            100.0
+        } else {
+            self.synced as f64 * 100.0 / known as f64
+        };
+        let elapsed = self.started.elapsed().as_secs_f64();
+        let nodes_per_second = if elapsed > 0.0 {
+            self.synced as f64 / elapsed
+        } else {
+            0.0
+        };
+        TrieSyncProgress {
+            state_root_hash: self.state_root_hash.into(),
+            complete: self.is_complete(),
+            nodes_synced: self.synced,
+            nodes_pending: pending,
+            percent_complete,
+            nodes_per_second,
+        }
+    }
+}
+
+/// The chain synchronizer component.
+#[derive(Debug)]
+pub(crate) struct ChainSynchronizer {
+    config: Config,
+    /// Connected peers trie nodes are fetched from.
+    peers: Vec<NodeId>,
+    /// Index of the peer to fetch the next trie node from.
+    next_peer: usize,
+    /// The current or most recent trie sync.
+    trie_sync: Option<TrieSync>,
+    metrics: ChainSynchronizerMetrics,
+}
+
+impl ChainSynchronizer {
+    pub(crate) fn new(config: Config, registry: &Registry) -> Result<Self, prometheus::Error> {
+        Ok(ChainSynchronizer {
+            config,
+            peers: Vec::new(),
+            next_peer: 0,
+            trie_sync: None,
+            metrics: ChainSynchronizerMetrics::new(registry)?,
+        })
+    }
+
+    /// Returns the peer to fetch the next trie node from, cycling through all connected peers.
+    fn next_peer(&mut self) -> Option<NodeId> {
+        if self.peers.is_empty() {
+            return None;
+        }
+        let peer = self.peers[self.next_peer % self.peers.len()];
+        self.next_peer = self.next_peer.wrapping_add(1);
+        Some(peer)
+    }
+
+    fn handle_sync_trie<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        state_root_hash: Digest,
+        responder: Responder<Result<(), Error>>,
+    ) -> Effects<Event> {
+        let state_root_hash = Blake2bHash::from(state_root_hash);
+        match self.trie_sync.as_mut() {
+            Some(trie_sync) if !trie_sync.is_complete() => {
+                if trie_sync.state_root_hash == state_root_hash {
+                    trie_sync.responders.push(responder);
+                    return Effects::new();
+                }
+                let error = Error::AlreadySyncing(trie_sync.state_root_hash.into());
+                return responder.respond(Err(error)).ignore();
+            }
+            _ => (),
+        }
+        if self.peers.is_empty() {
+            return responder.respond(Err(Error::NoPeers)).ignore();
+        }
+
+        info!(%state_root_hash, "starting global state trie sync");
+        let mut trie_sync = TrieSync::new(state_root_hash);
+        trie_sync.responders.push(responder);
+        self.trie_sync = Some(trie_sync);
+        self.fetch_queued(effect_builder)
+    }
+
+    /// Fetches queued trie nodes until the configured number of parallel fetches is reached.
+    fn fetch_queued<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+    ) -> Effects<Event> {
+        let mut effects = Effects::new();
+        loop {
+            let trie_sync = match self.trie_sync.as_ref() {
+                Some(trie_sync) => trie_sync,
+                None => break,
+            };
+            if trie_sync.in_flight.len() >= self.config.max_parallel_trie_fetches
+                || trie_sync.queued.is_empty()
+            {
+                break;
+            }
+            let peer = match self.next_peer() {
+                Some(peer) => peer,
+                None => break,
+            };
+            // Both unwraps are safe, as checked above.
+            let trie_sync = self.trie_sync.as_mut().unwrap();
+            let trie_key = trie_sync.queued.pop_front().unwrap();
+            trie_sync.in_flight.insert(trie_key);
+            effects.extend(
+                effect_builder
+                    .fetch_trie(trie_key, peer)
+                    .event(move |result| Event::FetchTrieResult {
+                        trie_key,
+                        peer,
+                        result,
+                    }),
+            );
+        }
+        self.update_metrics();
+        effects
+    }
+
+    fn handle_fetch_trie_result<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        trie_key: Blake2bHash,
+        peer: NodeId,
+        result: Option<FetchResult<Trie<Key, StoredValue>, NodeId>>,
+    ) -> Effects<Event> {
+        let trie = match result {
+            Some(FetchResult::FromStorage(trie)) => trie,
+            Some(FetchResult::FromPeer(trie, peer)) => {
+                if trie.id() == trie_key {
+                    trie
+                } else {
+                    warn!(%trie_key, %peer, "received trie node with mismatched hash");
+                    return self.retry_fetch(effect_builder, trie_key);
+                }
+            }
+            None => {
+                debug!(%trie_key, %peer, "failed to fetch trie node");
+                return self.retry_fetch(effect_builder, trie_key);
+            }
+        };
+        // Storing a node which is already present is harmless, and still yields its missing
+        // descendants.
+        effect_builder
+            .put_trie_and_find_missing_descendant_trie_keys(trie)
+            .event(move |result| Event::PutTrieResult { trie_key, result })
+    }
+
+    /// Queues a trie node for another fetch attempt, or aborts the sync if it failed too often.
+    fn retry_fetch<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        trie_key: Blake2bHash,
+    ) -> Effects<Event> {
+        let trie_sync = match self.trie_sync.as_mut() {
+            Some(trie_sync) => trie_sync,
+            None => return Effects::new(),
+        };
+        if !trie_sync.in_flight.remove(&trie_key) {
+            return Effects::new();
+        }
+        let attempts = {
+            let attempts = trie_sync.attempts.entry(trie_key).or_default();
+            *attempts += 1;
+            *attempts
+        };
+        if attempts >= self.config.max_trie_fetch_attempts {
+            return self.fail(Error::FetchFailed { trie_key, attempts });
+        }
+        trie_sync.queued.push_front(trie_key);
+        self.fetch_queued(effect_builder)
+    }
+
+    fn handle_put_trie_result<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        trie_key: Blake2bHash,
+        result: Result<Vec<Blake2bHash>, engine_state::Error>,
+    ) -> Effects<Event> {
+        let missing_descendants = match result {
+            Ok(missing_descendants) => missing_descendants,
+            Err(error) => return self.fail(Error::PutTrie { trie_key, error }),
+        };
+        let trie_sync = match self.trie_sync.as_mut() {
+            Some(trie_sync) => trie_sync,
+            None => return Effects::new(),
+        };
+        if !trie_sync.in_flight.remove(&trie_key) {
+            return Effects::new();
+        }
+        trie_sync.synced += 1;
+        for descendant in missing_descendants {
+            if trie_sync.seen.insert(descendant) {
+                trie_sync.queued.push_back(descendant);
+            }
+        }
+
+        if !trie_sync.is_complete() {
+            return self.fetch_queued(effect_builder);
+        }
+
+        let progress = trie_sync.progress();
+        info!(
+            state_root_hash = %trie_sync.state_root_hash,
+            nodes = progress.nodes_synced,
+            nodes_per_second = progress.nodes_per_second,
+            "finished global state trie sync"
+        );
+        let responders = std::mem::take(&mut trie_sync.responders);
+        self.update_metrics();
+        responders
+            .into_iter()
+            .flat_map(|responder| responder.respond(Ok(())).ignore())
+            .collect()
+    }
+
+    /// Aborts the current sync, reporting `error` to all waiting responders.
+    fn fail(&mut self, error: Error) -> Effects<Event> {
+        error!(%error, "global state trie sync failed");
+        let trie_sync = match self.trie_sync.take() {
+            Some(trie_sync) => trie_sync,
+            None => return Effects::new(),
+        };
+        self.update_metrics();
+        trie_sync
+            .responders
+            .into_iter()
+            .flat_map(|responder| responder.respond(Err(error.clone())).ignore())
+            .collect()
+    }
+
+    fn update_metrics(&self) {
+        let progress = match self.trie_sync.as_ref() {
+            Some(trie_sync) => trie_sync.progress(),
+            None => {
+                self.metrics.trie_nodes_synced.set(0);
+                self.metrics.trie_nodes_pending.set(0);
+                self.metrics.trie_sync_percent_complete.set(0.0);
+                self.metrics.trie_nodes_per_second.set(0.0);
+                return;
+            }
+        };
+        self.metrics
+            .trie_nodes_synced
+            .set(progress.nodes_synced as i64);
+        self.metrics
+            .trie_nodes_pending
+            .set(progress.nodes_pending as i64);
+        self.metrics
+            .trie_sync_percent_complete
+            .set(progress.percent_complete);
+        self.metrics
+            .trie_nodes_per_second
+            .set(progress.nodes_per_second);
+    }
+}
+
+impl<REv> Component<REv> for ChainSynchronizer
+where
+    REv: ReactorEventT,
+{
+    type Event = Event;
+    type ConstructionError = Infallible;
+
+    fn handle_event(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        _rng: &mut NodeRng,
+        event: Self::Event,
+    ) -> Effects<Self::Event> {
+        match event {
+            Event::Request(ChainSynchronizerRequest::SyncTrie {
+                state_root_hash,
+                responder,
+            }) => self.handle_sync_trie(effect_builder, state_root_hash, responder),
+            Event::Request(ChainSynchronizerRequest::Progress(responder)) => {
+                let progress = self.trie_sync.as_ref().map(TrieSync::progress);
+                responder.respond(progress).ignore()
+            }
+            Event::NewPeerConnected(peer) => {
+                if !self.peers.contains(&peer) {
+                    self.peers.push(peer);
+                }
+                // Resume fetching in case the sync stalled for a lack of peers.
+                self.fetch_queued(effect_builder)
+            }
+            Event::FetchTrieResult {
+                trie_key,
+                peer,
+                result,
+            } => self.handle_fetch_trie_result(effect_builder, trie_key, peer, result),
+            Event::PutTrieResult { trie_key, result } => {
+                self.handle_put_trie_result(effect_builder, trie_key, result)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_estimate_trie_sync_progress() {
+        let root = Blake2bHash::new(&[0]);
+        let mut trie_sync = TrieSync::new(root);
+        let progress = trie_sync.progress();
+        assert!(!progress.complete);
+        assert_eq!(progress.nodes_pending, 1);
+        assert_eq!(progress.percent_complete, 0.0);
+
+        // The root is stored and reports three missing descendants.
+        trie_sync.queued.pop_front();
+        trie_sync.synced += 1;
+        for byte in 1..=3 {
+            trie_sync.queued.push_back(Blake2bHash::new(&[byte]));
+        }
+        let progress = trie_sync.progress();
+        assert_eq!(progress.nodes_synced, 1);
+        assert_eq!(progress.nodes_pending, 3);
+        assert_eq!(progress.percent_complete, 25.0);
+
+        trie_sync.queued.clear();
+        trie_sync.synced += 3;
+        let progress = trie_sync.progress();
+        assert!(progress.complete);
+        assert_eq!(progress.percent_complete, 100.0);
+        assert_eq!(progress.state_root_hash, Digest::from(root));
+    }
+}
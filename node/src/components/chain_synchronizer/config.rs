@@ -0,0 +1,40 @@
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_MAX_PARALLEL_TRIE_FETCHES: usize = 64;
+const DEFAULT_MAX_TRIE_FETCH_ATTEMPTS: usize = 5;
+
+/// Chain synchronizer configuration.
+#[derive(Copy, Clone, DataSize, Debug, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Maximum number of trie nodes being fetched from peers at the same time.
+    #[serde(default = "default_max_parallel_trie_fetches")]
+    pub max_parallel_trie_fetches: usize,
+    /// Maximum number of attempts to fetch a single trie node before the sync is aborted.
+    #[serde(default = "default_max_trie_fetch_attempts")]
+    pub max_trie_fetch_attempts: usize,
+    /// Whether a joining node downloads the global state under the trusted block from its peers
+    /// instead of executing all blocks up to the trusted block.
+    #[serde(default)]
+    pub sync_global_state: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            max_parallel_trie_fetches: default_max_parallel_trie_fetches(),
+            max_trie_fetch_attempts: default_max_trie_fetch_attempts(),
+            sync_global_state: false,
+        }
+    }
+}
+
+fn default_max_parallel_trie_fetches() -> usize {
+    DEFAULT_MAX_PARALLEL_TRIE_FETCHES
+}
+
+fn default_max_trie_fetch_attempts() -> usize {
+    DEFAULT_MAX_TRIE_FETCH_ATTEMPTS
+}
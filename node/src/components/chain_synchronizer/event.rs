@@ -0,0 +1,71 @@
+use std::fmt::{self, Display, Formatter};
+
+use derive_more::From;
+use serde::Serialize;
+
+use casper_execution_engine::{
+    core::engine_state,
+    shared::{newtypes::Blake2bHash, stored_value::StoredValue},
+    storage::trie::Trie,
+};
+use casper_types::Key;
+
+use crate::{
+    components::fetcher::FetchResult, effect::requests::ChainSynchronizerRequest, types::NodeId,
+};
+
+/// Chain synchronizer events.
+#[derive(Debug, From, Serialize)]
+pub enum Event {
+    /// An incoming request.
+    #[from]
+    Request(ChainSynchronizerRequest),
+    /// A new peer connected and can be used to download trie nodes from.
+    NewPeerConnected(NodeId),
+    /// The result of fetching a trie node from a peer.
+    FetchTrieResult {
+        trie_key: Blake2bHash,
+        peer: NodeId,
+        #[serde(skip_serializing)]
+        result: Option<FetchResult<Trie<Key, StoredValue>, NodeId>>,
+    },
+    /// The result of writing a trie node into the local trie store.
+    PutTrieResult {
+        trie_key: Blake2bHash,
+        #[serde(skip_serializing)]
+        result: Result<Vec<Blake2bHash>, engine_state::Error>,
+    },
+}
+
+impl Display for Event {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::Request(request) => write!(f, "request: {}", request),
+            Event::NewPeerConnected(peer) => write!(f, "new peer connected: {}", peer),
+            Event::FetchTrieResult {
+                trie_key,
+                peer,
+                result,
+            } => write!(
+                f,
+                "fetch result for trie {} from {}: {}",
+                trie_key,
+                peer,
+                if result.is_some() {
+                    "found"
+                } else {
+                    "not found"
+                }
+            ),
+            Event::PutTrieResult { trie_key, result } => match result {
+                Ok(missing) => write!(
+                    f,
+                    "stored trie {}, {} missing descendants",
+                    trie_key,
+                    missing.len()
+                ),
+                Err(error) => write!(f, "failed to store trie {}: {}", trie_key, error),
+            },
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use prometheus::{Gauge, IntGauge, Registry};
+
+use crate::unregister_metric;
+
+#[derive(Debug)]
+pub(super) struct ChainSynchronizerMetrics {
+    /// Number of trie nodes of the current sync which are stored locally.
+    pub(super) trie_nodes_synced: IntGauge,
+    /// Number of known trie nodes of the current sync which are queued or being fetched.
+    pub(super) trie_nodes_pending: IntGauge,
+    /// Estimated percentage of the current sync which is complete.
+    pub(super) trie_sync_percent_complete: Gauge,
+    /// Average number of trie nodes stored per second during the current sync.
+    pub(super) trie_nodes_per_second: Gauge,
+    /// Reference to the registry for unregistering.
+    registry: Registry,
+}
+
+impl ChainSynchronizerMetrics {
+    pub(super) fn new(registry: &Registry) -> Result<Self, prometheus::Error> {
+        let trie_nodes_synced = IntGauge::new(
+            "chain_synchronizer_trie_nodes_synced",
+            "number of global state trie nodes of the current sync stored locally",
+        )?;
+        let trie_nodes_pending = IntGauge::new(
+            "chain_synchronizer_trie_nodes_pending",
+            "number of known global state trie nodes of the current sync not yet stored locally",
+        )?;
+        let trie_sync_percent_complete = Gauge::new(
+            "chain_synchronizer_trie_sync_percent_complete",
+            "estimated percentage of the current global state trie sync which is complete",
+        )?;
+        let trie_nodes_per_second = Gauge::new(
+            "chain_synchronizer_trie_nodes_per_second",
+            "average number of global state trie nodes stored per second during the current sync",
+        )?;
+        registry.register(Box::new(trie_nodes_synced.clone()))?;
+        registry.register(Box::new(trie_nodes_pending.clone()))?;
+        registry.register(Box::new(trie_sync_percent_complete.clone()))?;
+        registry.register(Box::new(trie_nodes_per_second.clone()))?;
+
+        Ok(ChainSynchronizerMetrics {
+            trie_nodes_synced,
+            trie_nodes_pending,
+            trie_sync_percent_complete,
+            trie_nodes_per_second,
+            registry: registry.clone(),
+        })
+    }
+}
+
+impl Drop for ChainSynchronizerMetrics {
+    fn drop(&mut self) {
+        unregister_metric!(self.registry, self.trie_nodes_synced);
+        unregister_metric!(self.registry, self.trie_nodes_pending);
+        unregister_metric!(self.registry, self.trie_sync_percent_complete);
+        unregister_metric!(self.registry, self.trie_nodes_per_second);
+    }
+}
@@ -11,6 +11,7 @@
 #![allow(clippy::field_reassign_with_default)]
 
 use std::{
+    collections::BTreeMap,
     fmt::{self, Display, Formatter},
     fs,
     path::{Path, PathBuf},
@@ -34,7 +35,7 @@ use casper_execution_engine::{
     },
     shared::stored_value::StoredValue,
 };
-use casper_types::{bytesrepr::FromBytes, EraId, ProtocolVersion};
+use casper_types::{bytesrepr::FromBytes, EraId, Key, ProtocolVersion};
 
 #[cfg(test)]
 use crate::utils::RESOURCES_PATH;
@@ -50,8 +51,9 @@ use crate::{
     },
     reactor::ReactorExit,
     types::{
-        chainspec::{Error, ProtocolConfig, CHAINSPEC_NAME},
+        chainspec::{Error, GenesisSnapshot, ProtocolConfig, CHAINSPEC_NAME},
         ActivationPoint, Block, BlockHash, BlockHeader, Chainspec, ChainspecInfo, ExitCode,
+        CHAINSPEC_KEY,
     },
     utils::{self, Loadable},
     NodeRng,
@@ -162,6 +164,10 @@ pub struct CurrentRunInfo {
     pub protocol_version: ProtocolVersion,
     pub initial_state_root_hash: Digest,
     pub last_emergency_restart: Option<EraId>,
+    /// The era in which the chain started: 0, unless it was initialized from a genesis snapshot.
+    pub genesis_era_id: EraId,
+    /// The height of the chain's first block.
+    pub genesis_height: u64,
 }
 
 #[derive(Clone, DataSize, Debug)]
@@ -170,6 +176,8 @@ pub struct ChainspecLoader {
     /// The path to the folder where all chainspec and upgrade_point files will be stored in
     /// subdirs corresponding to their versions.
     root_dir: PathBuf,
+    /// The folder containing the chainspec we're running, and the files it refers to.
+    chainspec_dir: PathBuf,
     /// If `Some`, we're finished loading and committing the chainspec.
     reactor_exit: Option<ReactorExit>,
     /// The initial state root hash for this session.
@@ -222,6 +230,7 @@ impl ChainspecLoader {
                 panic!("chainspec dir must have a parent");
             })
             .to_path_buf();
+        let chainspec_dir = chainspec_dir.as_ref().to_path_buf();
 
         let next_upgrade = next_upgrade(root_dir.clone(), chainspec.protocol_config.version);
 
@@ -262,6 +271,7 @@ impl ChainspecLoader {
         let chainspec_loader = ChainspecLoader {
             chainspec,
             root_dir,
+            chainspec_dir,
             reactor_exit,
             initial_state_root_hash: Digest::default(),
             next_upgrade,
@@ -325,7 +335,7 @@ impl ChainspecLoader {
         // it's not, we continue the era the highest block belongs to.
         self.initial_block_header()
             .map(BlockHeader::next_block_era_id)
-            .unwrap_or_else(|| EraId::from(0))
+            .unwrap_or_else(|| self.chainspec.protocol_config.activation_era_id())
     }
 
     /// Returns the era ID of where we should reset back to.  This means stored blocks in that and
@@ -355,6 +365,9 @@ impl ChainspecLoader {
                 if self.chainspec.is_genesis() {
                     // This is a valid initial run on a new network at genesis.
                     trace!("valid initial run at genesis");
+                    if self.chainspec.protocol_config.genesis_snapshot.is_some() {
+                        return self.commit_genesis_snapshot(effect_builder);
+                    }
                     return effect_builder
                         .commit_genesis(Arc::clone(&self.chainspec))
                         .event(Event::CommitGenesisResult);
@@ -444,13 +457,26 @@ impl ChainspecLoader {
         Effects::new()
     }
 
+    /// Initializes the global state from the genesis snapshot in the chainspec directory instead
+    /// of running the genesis process.
+    fn commit_genesis_snapshot<REv>(&mut self, effect_builder: EffectBuilder<REv>) -> Effects<Event>
+    where
+        REv: From<Event> + From<ContractRuntimeRequest> + Send,
+    {
+        let snapshot_path = GenesisSnapshot::path(&self.chainspec_dir);
+        info!(path = %snapshot_path.display(), "committing genesis snapshot");
+        effect_builder
+            .commit_genesis_snapshot(Arc::clone(&self.chainspec), snapshot_path)
+            .event(Event::CommitGenesisResult)
+    }
+
     fn new_upgrade_config(
         &self,
         block: &Block,
         previous_version: ProtocolVersion,
     ) -> Box<UpgradeConfig> {
         let new_version = self.chainspec.protocol_config.version;
-        let global_state_update = self
+        let mut global_state_update: BTreeMap<Key, StoredValue> = self
             .chainspec
             .protocol_config
             .global_state_update
@@ -473,6 +499,11 @@ impl ChainspecLoader {
                     .collect()
             })
             .unwrap_or_default();
+        // Record the chainspec the network is running from this upgrade onwards.
+        let chainspec_value = self.chainspec.to_stored_value().unwrap_or_else(|error| {
+            panic!("failed to convert chainspec to StoredValue: {}", error)
+        });
+        global_state_update.insert(*CHAINSPEC_KEY, chainspec_value);
         Box::new(UpgradeConfig::new(
             (*block.state_root_hash()).into(),
             previous_version,
@@ -486,6 +517,11 @@ impl ChainspecLoader {
             Some(self.chainspec.core_config.round_seigniorage_rate),
             Some(self.chainspec.core_config.unbonding_delay),
             global_state_update,
+            self.chainspec
+                .protocol_config
+                .validators_override
+                .as_ref()
+                .map(|validators_override| validators_override.0.clone()),
         ))
     }
 
@@ -558,6 +594,7 @@ impl ChainspecLoader {
             self.chainspec.network_config.name.clone(),
             self.initial_state_root_hash,
             self.next_upgrade.clone(),
+            self.chainspec.max_deploy_ttl(),
         )
     }
 
@@ -567,6 +604,8 @@ impl ChainspecLoader {
             protocol_version: self.chainspec.protocol_config.version,
             initial_state_root_hash: self.initial_state_root_hash,
             last_emergency_restart: self.chainspec.protocol_config.last_emergency_restart,
+            genesis_era_id: self.chainspec.protocol_config.genesis_era_id(),
+            genesis_height: self.chainspec.protocol_config.genesis_height(),
         }
     }
 
@@ -51,7 +51,7 @@ pub(crate) use cl_context::ClContext;
 pub use config::Config;
 pub(crate) use consensus_protocol::{BlockContext, EraReport, ProposedBlock};
 pub(crate) use era_supervisor::EraSupervisor;
-pub(crate) use protocols::highway::HighwayProtocol;
+pub(crate) use protocols::highway::{HighwayProtocol, HighwayStateSummary};
 use traits::NodeIdT;
 
 #[cfg(test)]
@@ -68,7 +68,7 @@ pub enum ConsensusMessage {
 
 /// An ID to distinguish different timers. What they are used for is specific to each consensus
 /// protocol implementation.
-#[derive(DataSize, Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(DataSize, Clone, Copy, Debug, Eq, PartialEq, Hash)]
 pub struct TimerId(pub u8);
 
 /// An ID to distinguish queued actions. What they are used for is specific to each consensus
@@ -125,6 +125,8 @@ pub enum Event<I> {
         switch_block_header: Box<BlockHeader>,
         /// `Ok(block_hash)` if the booking block was found, `Err(era_id)` if not
         booking_block_hash: Result<BlockHash, EraId>,
+        /// The current time, used to decide whether the new era is still ongoing.
+        now: Timestamp,
     },
     /// Event raised upon initialization, when a number of eras have to be instantiated at once.
     InitializeEras {
@@ -226,6 +228,7 @@ impl<I: Debug> Display for Event<I> {
             Event::CreateNewEra {
                 booking_block_hash,
                 switch_block_header,
+                ..
             } => write!(
                 f,
                 "New era should be created; booking block hash: {:?}, switch block: {:?}",
@@ -311,6 +314,7 @@ where
             Event::CreateNewEra {
                 switch_block_header,
                 booking_block_hash,
+                now,
             } => {
                 let booking_block_hash = match booking_block_hash {
                     Ok(hash) => hash,
@@ -327,7 +331,7 @@ where
                         .ignore();
                     }
                 };
-                handling_es.handle_create_new_era(*switch_block_header, booking_block_hash)
+                handling_es.handle_create_new_era(*switch_block_header, booking_block_hash, now)
             }
             Event::InitializeEras {
                 key_blocks,
@@ -340,6 +344,9 @@ where
             Event::ConsensusRequest(ConsensusRequest::Status(responder)) => {
                 handling_es.status(responder)
             }
+            Event::ConsensusRequest(ConsensusRequest::StateSummary { era_id, responder }) => {
+                handling_es.state_summary(era_id, responder)
+            }
         }
     }
 }
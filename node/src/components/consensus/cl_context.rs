@@ -74,4 +74,16 @@ impl Context for ClContext {
         }
         true
     }
+
+    fn verify_signatures(items: &[(&Digest, &PublicKey, &Signature)]) -> bool {
+        let items: Vec<_> = items
+            .iter()
+            .map(|(hash, public_key, signature)| (*hash, *signature, *public_key))
+            .collect();
+        if let Err(error) = crypto::verify_batch(&items) {
+            info!(%error, count = items.len(), "failed to validate signatures");
+            return false;
+        }
+        true
+    }
 }
@@ -1,12 +1,17 @@
+use std::collections::{BTreeMap, BTreeSet};
+
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
-use casper_types::{ProtocolVersion, SecretKey};
+use casper_types::{ProtocolVersion, PublicKey, SecretKey};
 
 use crate::{
     components::consensus::{protocols::highway::config::Config as HighwayConfig, EraId},
     crypto::hash::Digest,
-    types::{chainspec::HighwayConfig as HighwayProtocolConfig, Chainspec, TimeDiff, Timestamp},
+    types::{
+        chainspec::{DeployConfig, HighwayConfig as HighwayProtocolConfig},
+        Chainspec, TimeDiff, Timestamp,
+    },
     utils::External,
 };
 
@@ -17,6 +22,14 @@ use crate::{
 pub struct Config {
     /// Path to secret key file.
     pub secret_key_path: External<SecretKey>,
+    /// Whether to switch to observer mode once this node is no longer a validator in any of the
+    /// bonded eras.
+    #[serde(default)]
+    pub observer_mode_when_unbonded: bool,
+    /// The number of peers each consensus message is gossiped to, preferring validators of the
+    /// message's era. If 0, messages are broadcast to all connected peers.
+    #[serde(default)]
+    pub gossip_fanout: u32,
     /// Highway-specific node configuration.
     pub highway: HighwayConfig,
 }
@@ -25,6 +38,8 @@ impl Default for Config {
     fn default() -> Self {
         Config {
             secret_key_path: External::Missing,
+            observer_mode_when_unbonded: false,
+            gossip_fanout: 0,
             highway: HighwayConfig::default(),
         }
     }
@@ -34,6 +49,8 @@ impl Default for Config {
 #[derive(DataSize, Debug)]
 pub(crate) struct ProtocolConfig {
     pub(crate) highway_config: HighwayProtocolConfig,
+    /// The deploy configuration, determining the gas price floor of proposed blocks.
+    pub(crate) deploy_config: DeployConfig,
     pub(crate) era_duration: TimeDiff,
     pub(crate) minimum_era_height: u64,
     /// Number of eras before an auction actually defines the set of validators.
@@ -46,29 +63,59 @@ pub(crate) struct ProtocolConfig {
     pub(crate) protocol_version: ProtocolVersion,
     /// The first era ID after the last upgrade
     pub(crate) last_activation_point: EraId,
+    /// Whether the last upgrade was an emergency restart, which resets all evidence against
+    /// validators from before the activation point.
+    pub(crate) emergency_restart: bool,
+    /// Validators that are banned in particular eras, in addition to the slashed ones.
+    pub(crate) banned_validators: BTreeMap<EraId, BTreeSet<PublicKey>>,
     /// Name of the network.
     pub(crate) name: String,
     /// Genesis timestamp, if available.
     pub(crate) genesis_timestamp: Option<Timestamp>,
+    /// The height of the first block, if the chain started at the last activation point.
+    pub(crate) genesis_height: u64,
     /// The chainspec hash: All nodes in the network agree on it, and it's unique to this network.
     pub(crate) chainspec_hash: Digest,
 }
 
+impl ProtocolConfig {
+    /// Returns the validators that the chainspec bans in the given era.
+    pub(crate) fn banned_validators_in(&self, era_id: EraId) -> impl Iterator<Item = &PublicKey> {
+        self.banned_validators.get(&era_id).into_iter().flatten()
+    }
+
+    /// Returns whether the given era is the chain's first one, which has no key block.
+    pub(crate) fn is_genesis_era(&self, era_id: EraId) -> bool {
+        self.genesis_timestamp.is_some() && era_id == self.last_activation_point
+    }
+}
+
 impl From<&Chainspec> for ProtocolConfig {
     fn from(chainspec: &Chainspec) -> Self {
+        let mut banned_validators: BTreeMap<EraId, BTreeSet<PublicKey>> = BTreeMap::new();
+        for banned in &chainspec.protocol_config.banned_validators {
+            banned_validators
+                .entry(banned.era_id)
+                .or_default()
+                .extend(banned.public_keys.iter().cloned());
+        }
         ProtocolConfig {
             highway_config: chainspec.highway_config,
+            deploy_config: chainspec.deploy_config,
             era_duration: chainspec.core_config.era_duration,
             minimum_era_height: chainspec.core_config.minimum_era_height,
             auction_delay: chainspec.core_config.auction_delay,
             unbonding_delay: chainspec.core_config.unbonding_delay,
             protocol_version: chainspec.protocol_config.version,
-            last_activation_point: chainspec.protocol_config.activation_point.era_id(),
+            last_activation_point: chainspec.protocol_config.activation_era_id(),
+            emergency_restart: chainspec.protocol_config.is_emergency_restart(),
+            banned_validators,
             name: chainspec.network_config.name.clone(),
             genesis_timestamp: chainspec
                 .protocol_config
                 .activation_point
                 .genesis_timestamp(),
+            genesis_height: chainspec.protocol_config.genesis_height(),
             chainspec_hash: chainspec.hash(),
         }
     }
@@ -7,6 +7,7 @@ use std::{
 
 use anyhow::Error;
 use datasize::DataSize;
+use num_rational::Ratio;
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -151,6 +152,15 @@ pub(crate) enum ProtocolOutcome<I, C: Context> {
     CreateNewBlock(BlockContext<C>),
     /// A block was finalized.
     FinalizedBlock(FinalizedBlock<C>),
+    /// A proposed value was orphaned: it is not an ancestor of a newer block, so it will never be
+    /// finalized.
+    OrphanedValue(C::ConsensusValue),
+    /// The block at the given height in the era was finalized with respect to one of the
+    /// additional finality thresholds.
+    FinalityTierReached {
+        relative_height: u64,
+        finality_threshold_fraction: Ratio<u64>,
+    },
     /// Request validation of the consensus value, contained in a message received from the given
     /// node.
     ///
@@ -176,6 +186,12 @@ pub(crate) enum ProtocolOutcome<I, C: Context> {
     StandstillAlert,
     /// We want to disconnect from a sender of invalid data.
     Disconnect(I),
+    /// The peer sent more vertices than we keep in the queues per peer. Its vertices were dropped
+    /// and we want to disconnect from it.
+    PendingVertexQuotaExceeded(I),
+    /// The given number of pending vertices were evicted because the queues exceeded their byte
+    /// budget.
+    PendingVerticesEvicted(u64),
 }
 
 /// An API for a single instance of the consensus.
@@ -226,6 +242,12 @@ pub(crate) trait ConsensusProtocol<I, C: Context>: Send {
     /// Turns this instance into a passive observer, that does not create any new vertices.
     fn deactivate_validator(&mut self);
 
+    /// Returns the IDs of the timers that only drive this instance's participation as an active
+    /// validator. For these only the most recently scheduled timer matters, so it supersedes any
+    /// pending one with the same ID, and all of them are obsolete once the validator is
+    /// deactivated.
+    fn validator_timer_ids(&self) -> &'static [TimerId];
+
     /// Clears this instance and keeps only the information necessary to validate evidence.
     fn set_evidence_only(&mut self);
 
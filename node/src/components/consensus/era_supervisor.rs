@@ -5,13 +5,14 @@
 //! it assumes is the concept of era/epoch and that each era runs separate consensus instance.
 //! Most importantly, it doesn't care about what messages it's forwarding.
 
+mod early_messages;
 mod era;
 
 use std::{
-    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    collections::{hash_map::Entry, BTreeMap, BTreeSet, HashMap, HashSet},
     convert::TryInto,
     fmt::{self, Debug, Formatter},
-    fs, io,
+    fs, io, iter,
     path::PathBuf,
     sync::Arc,
     time::Duration,
@@ -32,22 +33,25 @@ use tracing::{debug, error, info, trace, warn};
 use casper_types::{AsymmetricType, EraId, PublicKey, SecretKey, U512};
 
 use crate::{
-    components::consensus::{
-        cl_context::{ClContext, Keypair},
-        config::ProtocolConfig,
-        consensus_protocol::{
-            ConsensusProtocol, EraReport, FinalizedBlock as CpFinalizedBlock, ProposedBlock,
-            ProtocolOutcome, ProtocolOutcomes,
+    components::{
+        block_validator::ValidatingBlock,
+        consensus::{
+            cl_context::{ClContext, Keypair},
+            config::ProtocolConfig,
+            consensus_protocol::{
+                BlockContext, ConsensusProtocol, EraReport, FinalizedBlock as CpFinalizedBlock,
+                ProposedBlock, ProtocolOutcome, ProtocolOutcomes,
+            },
+            metrics::ConsensusMetrics,
+            traits::NodeIdT,
+            ActionId, Config, ConsensusMessage, Event, HighwayProtocol, HighwayStateSummary,
+            NewBlockPayload, ReactorEventT, ResolveValidity, TimerId,
         },
-        metrics::ConsensusMetrics,
-        traits::NodeIdT,
-        ActionId, Config, ConsensusMessage, Event, NewBlockPayload, ReactorEventT, ResolveValidity,
-        TimerId,
     },
     crypto::hash::Digest,
     effect::{
         requests::{BlockValidationRequest, StorageRequest},
-        EffectBuilder, EffectExt, Effects, Responder,
+        EffectBuilder, EffectExt, EffectOptionExt, Effects, Responder,
     },
     fatal,
     types::{
@@ -58,6 +62,7 @@ use crate::{
     NodeRng,
 };
 
+use self::early_messages::EarlyMessages;
 pub use self::era::Era;
 
 /// The delay in milliseconds before we shutdown after the number of faulty validators exceeded the
@@ -118,6 +123,16 @@ pub struct EraSupervisor<I> {
     /// TODO: A temporary field. Shouldn't be needed once the Joiner doesn't have a consensus
     /// component.
     is_initialized: bool,
+    /// The evidence requests served recently, by requesting peer and accused validator, with the
+    /// time they were served. Repeated requests are ignored until the cooldown has passed.
+    evidence_requests: HashMap<(I, PublicKey), Timestamp>,
+    /// Messages received for the next era before it was created, to be handled once it is.
+    early_messages: EarlyMessages<I>,
+    /// Whether our key is a validator in any of the bonded eras.
+    bonded_validator: bool,
+    /// Set when we are no longer bonded and `observer_mode_when_unbonded` is enabled: the node
+    /// keeps following the chain, but stops doing work that only a validator needs.
+    observer_mode: bool,
 }
 
 impl<I> Debug for EraSupervisor<I> {
@@ -157,10 +172,15 @@ where
         info!(our_id = %public_signing_key, "EraSupervisor pubkey",);
         let metrics = ConsensusMetrics::new(registry)
             .expect("failure to setup and register ConsensusMetrics");
+        let early_messages = EarlyMessages::new(
+            config.highway.max_early_messages_per_era,
+            config.highway.max_early_messages_per_peer,
+        );
         let activation_era_id = protocol_config.last_activation_point;
         let auction_delay = protocol_config.auction_delay;
         #[allow(clippy::integer_arithmetic)] // Block height should never reach u64::MAX.
-        let next_height = maybe_latest_block_header.map_or(0, |hdr| hdr.height() + 1);
+        let next_height = maybe_latest_block_header
+            .map_or(protocol_config.genesis_height, |hdr| hdr.height() + 1);
 
         let era_supervisor = Self {
             active_eras: Default::default(),
@@ -177,19 +197,29 @@ where
             stop_for_upgrade: false,
             next_executed_height: next_height,
             is_initialized: false,
+            evidence_requests: Default::default(),
+            early_messages,
+            bonded_validator: false,
+            observer_mode: false,
         };
 
         let bonded_eras = era_supervisor.bonded_eras();
         let era_ids: Vec<EraId> = era_supervisor
             .iter_past(current_era, era_supervisor.bonded_eras().saturating_mul(3))
             .collect();
+        // The genesis era has no key block.
+        let key_block_era_ids: Vec<EraId> = era_ids
+            .iter()
+            .filter(|era_id| !era_supervisor.protocol_config.is_genesis_era(**era_id))
+            .cloned()
+            .collect();
 
         // Asynchronously collect the information needed to initialize all recent eras.
         let effects = async move {
             info!(?era_ids, "collecting key blocks and booking blocks");
 
             let key_blocks = effect_builder
-                .collect_key_block_headers(era_ids.iter().cloned())
+                .collect_key_block_headers(key_block_era_ids)
                 .await
                 .expect("should have all the key blocks in storage");
 
@@ -293,6 +323,7 @@ where
         seed: u64,
         start_time: Timestamp,
         start_height: u64,
+        start_gas_price_floor: u64,
     ) -> Vec<ProtocolOutcome<I, ClContext>> {
         if self.active_eras.contains_key(&era_id) {
             panic!("{} already exists", era_id);
@@ -331,6 +362,10 @@ where
             self.metrics.current_era.set(era_id.value() as i64);
         }
 
+        // Validators banned in the chainspec for this era are treated like slashed ones.
+        let mut slashed = slashed;
+        slashed.extend(self.protocol_config.banned_validators_in(era_id).cloned());
+
         let prev_era = era_id
             .checked_sub(1)
             .and_then(|last_era_id| self.active_eras.get(&last_era_id));
@@ -361,11 +396,13 @@ where
             consensus,
             start_time,
             start_height,
+            start_gas_price_floor,
             newly_slashed,
             slashed,
             validators,
         );
         let _ = self.active_eras.insert(era_id, era);
+        self.bonded_validator = self.our_key_is_bonded();
         let oldest_bonded_era_id = oldest_bonded_era(&self.protocol_config, era_id);
         // Clear the obsolete data from the era whose validators are unbonded now. We only retain
         // the information necessary to validate evidence that units in still-bonded eras may refer
@@ -373,15 +410,16 @@ where
         if let Some(evidence_only_era_id) = oldest_bonded_era_id.checked_sub(1) {
             trace!(era = evidence_only_era_id.value(), "clearing unbonded era");
             if let Some(era) = self.active_eras.get_mut(&evidence_only_era_id) {
-                era.consensus.set_evidence_only();
+                era.set_evidence_only();
             }
         }
         // Remove the era that has become obsolete now: The oldest bonded era could still receive
         // units that refer to evidence from any era that was bonded when it was the current one.
         let oldest_evidence_era_id = oldest_bonded_era(&self.protocol_config, oldest_bonded_era_id);
         if let Some(obsolete_era_id) = oldest_evidence_era_id.checked_sub(1) {
-            if let Some(era) = self.active_eras.remove(&obsolete_era_id) {
+            if let Some(mut era) = self.active_eras.remove(&obsolete_era_id) {
                 trace!(era = obsolete_era_id.value(), "removing obsolete era");
+                era.cancel_all_timers();
                 match fs::remove_file(self.unit_hash_file(era.consensus.instance_id())) {
                     Ok(_) => {}
                     Err(err) => match err.kind() {
@@ -401,6 +439,32 @@ where
             && era_id <= self.current_era
     }
 
+    /// Returns `true` if our key is a validator in any of the bonded eras.
+    fn our_key_is_bonded(&self) -> bool {
+        self.iter_past(self.current_era, self.bonded_eras())
+            .chain(iter::once(self.current_era))
+            .any(|era_id| self.is_validator_in(&self.public_signing_key, era_id))
+    }
+
+    /// Records an evidence request from `sender` for evidence against `pub_key`, and returns
+    /// whether it should be served, i.e. whether no identical request was served within the
+    /// cooldown period.
+    fn accept_evidence_request(&mut self, sender: &I, pub_key: &PublicKey, now: Timestamp) -> bool {
+        let cooldown = self.config.highway.evidence_request_cooldown;
+        self.evidence_requests
+            .retain(|_, served_at| *served_at + cooldown > now);
+        match self
+            .evidence_requests
+            .entry((sender.clone(), pub_key.clone()))
+        {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                let _ = entry.insert(now);
+                true
+            }
+        }
+    }
+
     /// Returns whether the validator with the given public key is bonded in that era.
     fn is_validator_in(&self, pub_key: &PublicKey, era_id: EraId) -> bool {
         let has_validator = |era: &Era<I>| era.validators().contains_key(pub_key);
@@ -417,6 +481,18 @@ where
         self.stop_for_upgrade
     }
 
+    /// Returns `true` if this node is no longer bonded and has switched to observer mode.
+    pub(crate) fn is_observer(&self) -> bool {
+        self.observer_mode
+    }
+
+    /// Returns the estimated heap memory usage of each active era, in bytes.
+    pub(crate) fn estimate_era_heap_sizes(&self) -> impl Iterator<Item = (EraId, usize)> + '_ {
+        self.active_eras
+            .iter()
+            .map(|(era_id, era)| (*era_id, era.estimate_heap_size()))
+    }
+
     /// Updates `next_executed_height` based on the given block header, and unpauses consensus if
     /// block execution has caught up with finalization.
     #[allow(clippy::integer_arithmetic)] // Block height should never reach u64::MAX.
@@ -453,6 +529,7 @@ where
             let newly_slashed;
             let validators;
             let start_height;
+            let start_gas_price_floor;
             let era_start_time;
             let seed;
 
@@ -461,20 +538,23 @@ where
                 .expect("should have booking block");
 
             #[allow(clippy::integer_arithmetic)] // Block height should never reach u64::MAX.
-            if era_id.is_genesis() {
+            if self.protocol_config.is_genesis_era(era_id) {
                 newly_slashed = vec![];
-                // The validator set was read from the global state: there's no key block for era 0.
+                // The validator set was read from the global state: there's no key block for the
+                // genesis era.
                 validators = activation_era_validators.clone();
-                start_height = 0;
+                start_height = self.protocol_config.genesis_height;
+                start_gas_price_floor = self.protocol_config.deploy_config.min_gas_price;
                 era_start_time = self
                     .protocol_config
                     .genesis_timestamp
-                    .expect("must have genesis start time if era ID is 0");
+                    .expect("must have genesis start time in the genesis era");
                 seed = 0;
             } else {
-                // If this is not era 0, there must be a key block for it.
+                // If this is not the genesis era, there must be a key block for it.
                 let key_block = key_blocks.get(&era_id).expect("missing key block");
                 start_height = key_block.height() + 1;
+                start_gas_price_floor = self.start_gas_price_floor(key_block);
                 era_start_time = key_block.timestamp();
                 seed = Self::era_seed(*booking_block_hash, key_block.accumulated_seed());
                 if era_id == self.protocol_config.last_activation_point {
@@ -498,8 +578,13 @@ where
                 }
             }
 
+            // The key block of the activation era was created before the upgrade. After an
+            // emergency restart, the equivocations reported in it are forgiven.
+            let last_activation_point = self.protocol_config.last_activation_point;
+            let emergency_restart = self.protocol_config.emergency_restart;
             let slashed = self
                 .iter_past(era_id, self.bonded_eras())
+                .filter(|old_id| !(emergency_restart && *old_id == last_activation_point))
                 .filter_map(|old_id| key_blocks.get(&old_id).and_then(|bhdr| bhdr.era_end()))
                 .flat_map(|era_end| era_end.equivocators.clone())
                 .collect();
@@ -513,6 +598,7 @@ where
                 seed,
                 era_start_time,
                 start_height,
+                start_gas_price_floor,
             );
             result_map.insert(era_id, results);
         }
@@ -528,6 +614,35 @@ where
         result_map
     }
 
+    /// Returns the gas price floor of the first block of the era following the given switch block.
+    ///
+    /// Switch blocks created before the gas price floor was introduced are followed by the minimum
+    /// gas price.
+    fn start_gas_price_floor(&self, switch_block_header: &BlockHeader) -> u64 {
+        switch_block_header
+            .next_gas_price_floor()
+            .unwrap_or(self.protocol_config.deploy_config.min_gas_price)
+    }
+
+    /// Returns the gas price floor of a block proposed in the given era, on top of the ancestors in
+    /// the given block context.
+    ///
+    /// Starting from the floor of the era's first block, it is adjusted by each ancestor's fullness
+    /// in the same way as the contract runtime does when executing the blocks.
+    fn gas_price_floor(&self, era_id: EraId, block_context: &BlockContext<ClContext>) -> u64 {
+        let deploy_config = &self.protocol_config.deploy_config;
+        block_context.ancestor_values().iter().rev().fold(
+            self.active_eras[&era_id].start_gas_price_floor,
+            |gas_price_floor, block_payload| {
+                deploy_config.next_gas_price_floor(
+                    gas_price_floor,
+                    block_payload.deploy_hashes().len(),
+                    block_payload.transfer_hashes().len(),
+                )
+            },
+        )
+    }
+
     /// The number of past eras whose validators are still bonded. After this many eras, a former
     /// validator is allowed to withdraw their stake, so their signature can't be trusted anymore.
     ///
@@ -673,6 +788,9 @@ where
         timestamp: Timestamp,
         timer_id: TimerId,
     ) -> Effects<Event<I>> {
+        if let Some(era) = self.era_supervisor.active_eras.get_mut(&era_id) {
+            era.remove_timer(timer_id, timestamp);
+        }
         self.delegate_to_era(era_id, move |consensus| {
             consensus.handle_timer(timestamp, timer_id)
         })
@@ -694,6 +812,21 @@ where
                 // If the era is already unbonded, only accept new evidence, because still-bonded
                 // eras could depend on that.
                 trace!(era = era_id.value(), "received a consensus message");
+                if !self.era_supervisor.active_eras.contains_key(&era_id)
+                    && era_id == self.era_supervisor.current_era.successor()
+                {
+                    if !self
+                        .era_supervisor
+                        .early_messages
+                        .push(era_id, sender, payload)
+                    {
+                        debug!(
+                            era = era_id.value(),
+                            "dropping message for next era; buffer full"
+                        );
+                    }
+                    return Effects::new();
+                }
                 self.delegate_to_era(era_id, move |consensus| {
                     consensus.handle_message(sender, payload, Timestamp::now())
                 })
@@ -703,6 +836,15 @@ where
                     trace!(era = era_id.value(), "not handling message; era too old");
                     return Effects::new();
                 }
+                // The response covers all bonded eras, so repeated requests would only amplify the
+                // traffic: serve each peer's request for a validator once per cooldown period.
+                if !self
+                    .era_supervisor
+                    .accept_evidence_request(&sender, &pub_key, Timestamp::now())
+                {
+                    trace!(%sender, %pub_key, "ignoring repeated evidence request");
+                    return Effects::new();
+                }
                 self.era_supervisor
                     .iter_past(era_id, self.era_supervisor.bonded_eras())
                     .flat_map(|e_id| {
@@ -768,6 +910,7 @@ where
             .event(move |booking_block_hash| Event::CreateNewEra {
                 switch_block_header: Box::new(block_header),
                 booking_block_hash: Ok(booking_block_hash),
+                now: Timestamp::now(),
             });
             effects.extend(effect);
         }
@@ -789,7 +932,7 @@ where
         let faulty_num = era.consensus.validators_with_evidence().len();
         if faulty_num == old_faulty_num {
             info!(era = era_id.value(), "stop voting in era");
-            era.consensus.deactivate_validator();
+            era.deactivate_validator();
             if self.should_upgrade_after(&era_id) {
                 // If the next era is at or after the upgrade activation point, stop the node.
                 info!(era = era_id.value(), "shutting down for upgrade");
@@ -816,10 +959,12 @@ where
             self.era_supervisor
                 .handle_initialize_eras(key_blocks, booking_blocks, validators);
 
-        let effects = result_map
+        let mut effects: Effects<Event<I>> = result_map
             .into_iter()
             .flat_map(|(era_id, results)| self.handle_consensus_outcomes(era_id, results))
             .collect();
+        effects.extend(self.announce_era_validators(self.era_supervisor.current_era));
+        effects.extend(self.handle_early_messages(self.era_supervisor.current_era));
 
         info!("finished initializing era supervisor");
         info!(?self.era_supervisor, "current eras");
@@ -827,11 +972,12 @@ where
         effects
     }
 
-    /// Creates a new era.
+    /// Creates a new era, deciding whether to activate it based on the given current time.
     pub(super) fn handle_create_new_era(
         &mut self,
         switch_block_header: BlockHeader,
         booking_block_hash: BlockHash,
+        now: Timestamp,
     ) -> Effects<Event<I>> {
         let (era_end, next_era_validators_weights) = match (
             switch_block_header.era_end(),
@@ -864,23 +1010,90 @@ where
             .chain(&newly_slashed)
             .cloned()
             .collect();
+        let was_bonded = self.era_supervisor.bonded_validator;
         #[allow(clippy::integer_arithmetic)] // Block height should never reach u64::MAX.
         let mut outcomes = self.era_supervisor.new_era(
             era_id,
-            Timestamp::now(), // TODO: This should be passed in.
+            now,
             next_era_validators_weights.clone(),
             newly_slashed,
             slashed,
             seed,
             switch_block_header.timestamp(),
             switch_block_header.height() + 1,
+            self.era_supervisor
+                .start_gas_price_floor(&switch_block_header),
         );
         outcomes.extend(
             self.era_supervisor.active_eras[&era_id]
                 .consensus
                 .handle_is_current(),
         );
-        self.handle_consensus_outcomes(era_id, outcomes)
+        let mut effects = self.handle_consensus_outcomes(era_id, outcomes);
+        effects.extend(self.announce_era_validators(era_id));
+        effects.extend(self.handle_bonded_status(era_id, was_bonded));
+        effects.extend(self.handle_early_messages(era_id));
+        effects
+    }
+
+    /// Stops voting in all eras if our key is no longer bonded in any of them, and announces it.
+    fn handle_bonded_status(&mut self, era_id: EraId, was_bonded: bool) -> Effects<Event<I>> {
+        let is_bonded = self.era_supervisor.bonded_validator;
+        if is_bonded {
+            if self.era_supervisor.observer_mode {
+                info!(era = era_id.value(), "bonded again; leaving observer mode");
+                self.era_supervisor.observer_mode = false;
+            }
+            return Effects::new();
+        }
+        if !was_bonded {
+            return Effects::new();
+        }
+        info!(
+            era = era_id.value(),
+            our_id = %self.era_supervisor.public_signing_key,
+            "no longer bonded in any active era; stop voting"
+        );
+        for era in self.era_supervisor.active_eras.values_mut() {
+            era.deactivate_validator();
+        }
+        if self.era_supervisor.config.observer_mode_when_unbonded {
+            info!(era = era_id.value(), "switching to observer mode");
+            self.era_supervisor.observer_mode = true;
+        }
+        self.effect_builder
+            .announce_no_longer_bonded(era_id, self.era_supervisor.public_signing_key.clone())
+            .ignore()
+    }
+
+    /// Handles the messages for the given era that were received before it was created.
+    fn handle_early_messages(&mut self, era_id: EraId) -> Effects<Event<I>> {
+        let messages = self.era_supervisor.early_messages.take(era_id);
+        if !messages.is_empty() {
+            debug!(
+                era = era_id.value(),
+                count = messages.len(),
+                "handling messages received before era was created"
+            );
+        }
+        let mut effects = Effects::new();
+        for (sender, payload) in messages {
+            effects.extend(self.delegate_to_era(era_id, move |consensus| {
+                consensus.handle_message(sender, payload, Timestamp::now())
+            }));
+        }
+        effects
+    }
+
+    /// Announces the validators of the given era, e.g. so the network can prioritize them.
+    fn announce_era_validators(&self, era_id: EraId) -> Effects<Event<I>> {
+        let validators = match self.era_supervisor.active_eras.get(&era_id) {
+            Some(era) => era.validators().clone(),
+            None => return Effects::new(),
+        };
+        self.effect_builder
+            .announce_era_validators_changed(era_id, validators)
+            .ignore()
     }
 
     pub(super) fn resolve_validity(
@@ -966,12 +1179,36 @@ where
                 );
                 self.disconnect(sender)
             }
+            ProtocolOutcome::PendingVertexQuotaExceeded(sender) => {
+                warn!(
+                    %sender,
+                    "disconnecting from a peer that exceeded the pending vertex quota"
+                );
+                self.era_supervisor
+                    .metrics
+                    .pending_vertex_quota_violations
+                    .inc();
+                self.disconnect(sender)
+            }
+            ProtocolOutcome::PendingVerticesEvicted(count) => {
+                self.era_supervisor
+                    .metrics
+                    .evicted_pending_vertices
+                    .inc_by(count);
+                Effects::new()
+            }
             ProtocolOutcome::CreatedGossipMessage(payload) => {
                 let message = ConsensusMessage::Protocol { era_id, payload };
-                // TODO: we'll want to gossip instead of broadcast here
-                self.effect_builder
-                    .broadcast_message(message.into())
-                    .ignore()
+                match self.era_supervisor.config.gossip_fanout {
+                    0 => self
+                        .effect_builder
+                        .broadcast_message(message.into())
+                        .ignore(),
+                    fanout => self
+                        .effect_builder
+                        .gossip_message_to_validators(message.into(), era_id, fanout as usize)
+                        .ignore(),
+                }
             }
             ProtocolOutcome::CreatedTargetedMessage(payload, to) => {
                 let message = ConsensusMessage::Protocol { era_id, payload };
@@ -980,14 +1217,19 @@ where
                     .ignore()
             }
             ProtocolOutcome::ScheduleTimer(timestamp, timer_id) => {
+                if self.era(era_id).has_timer(timer_id, timestamp) {
+                    // An identical timer is already pending; it will trigger the same event.
+                    return Effects::new();
+                }
                 let timediff = timestamp.saturating_diff(Timestamp::now());
-                self.effect_builder
-                    .set_timeout(timediff.into())
-                    .event(move |_| Event::Timer {
-                        era_id,
-                        timestamp,
-                        timer_id,
-                    })
+                let (handle, timeout) =
+                    self.effect_builder.set_cancellable_timeout(timediff.into());
+                self.era_mut(era_id).add_timer(timer_id, timestamp, handle);
+                timeout.map_some(move |_| Event::Timer {
+                    era_id,
+                    timestamp,
+                    timer_id,
+                })
             }
             ProtocolOutcome::QueueAction(action_id) => self
                 .effect_builder
@@ -1002,9 +1244,13 @@ where
                     .filter(|pub_key| !self.era(era_id).slashed.contains(pub_key))
                     .cloned()
                     .collect();
+                let gas_price_floor = self.era_supervisor.gas_price_floor(era_id, &block_context);
                 self.effect_builder
                     .request_block_payload(
                         block_context.clone(),
+                        gas_price_floor,
+                        era_id,
+                        self.era_supervisor.public_signing_key.clone(),
                         self.era_supervisor.next_block_height,
                         accusations,
                         self.rng.gen(),
@@ -1083,6 +1329,26 @@ where
                 self.era_supervisor.update_consensus_pause();
                 effects
             }
+            ProtocolOutcome::OrphanedValue(value) => {
+                info!(era = era_id.value(), %value, "proposed block payload was orphaned");
+                self.effect_builder
+                    .announce_orphaned_proto_block(value)
+                    .ignore()
+            }
+            ProtocolOutcome::FinalityTierReached {
+                relative_height,
+                finality_threshold_fraction,
+            } => match self.era_supervisor.active_eras.get(&era_id) {
+                Some(era) => self
+                    .effect_builder
+                    .announce_finality_tier_reached(
+                        era_id,
+                        era.start_height + relative_height,
+                        finality_threshold_fraction,
+                    )
+                    .ignore(),
+                None => Effects::new(),
+            },
             ProtocolOutcome::ValidateConsensusValue {
                 sender,
                 proposed_block,
@@ -1118,6 +1384,9 @@ where
                     );
                 }
                 let effect_builder = self.effect_builder;
+                let gas_price_floor = self
+                    .era_supervisor
+                    .gas_price_floor(era_id, proposed_block.context());
                 effects.extend(
                     async move {
                         check_deploys_for_replay_in_previous_eras_and_validate_block(
@@ -1125,6 +1394,7 @@ where
                             era_id,
                             sender,
                             proposed_block,
+                            gas_price_floor,
                         )
                         .await
                     }
@@ -1207,6 +1477,26 @@ where
         responder.respond(Some((public_key, round_length))).ignore()
     }
 
+    /// Responds with a summary of the given era's Highway protocol state, or `None` if the era is
+    /// not active.
+    pub(super) fn state_summary(
+        &self,
+        era_id: EraId,
+        responder: Responder<Option<HighwayStateSummary<ClContext>>>,
+    ) -> Effects<Event<I>> {
+        let summary = self
+            .era_supervisor
+            .active_eras
+            .get(&era_id)
+            .and_then(|era| {
+                era.consensus
+                    .as_any()
+                    .downcast_ref::<HighwayProtocol<I, ClContext>>()
+            })
+            .map(HighwayProtocol::state_summary);
+        responder.respond(summary).ignore()
+    }
+
     fn disconnect(&self, sender: I) -> Effects<Event<I>> {
         self.effect_builder
             .announce_disconnect_from_peer(sender)
@@ -1263,6 +1553,7 @@ async fn check_deploys_for_replay_in_previous_eras_and_validate_block<REv, I>(
     proposed_block_era_id: EraId,
     sender: I,
     proposed_block: ProposedBlock<ClContext>,
+    gas_price_floor: u64,
 ) -> Event<I>
 where
     REv: From<BlockValidationRequest<I>> + From<StorageRequest>,
@@ -1294,8 +1585,10 @@ where
     }
 
     let sender_for_validate_block: I = sender.clone();
+    let validating_block =
+        ValidatingBlock::ProposedBlock(Box::new(proposed_block.clone()), gas_price_floor);
     let valid = effect_builder
-        .validate_block(sender_for_validate_block, proposed_block.clone())
+        .validate_block(sender_for_validate_block, validating_block)
         .await;
 
     Event::ResolveValidity(ResolveValidity {
@@ -0,0 +1,88 @@
+use std::{collections::BTreeMap, mem};
+
+use datasize::DataSize;
+
+use casper_types::EraId;
+
+/// Consensus messages received for an era that has not been created yet.
+///
+/// After an era transition, faster validators may send the first units of the new era before a
+/// slower node has executed the switch block and created it. Instead of dropping those, they are
+/// kept here and handled once the era exists.
+#[derive(DataSize, Debug)]
+pub(super) struct EarlyMessages<I> {
+    /// The buffered senders and payloads by era, in order of arrival.
+    messages: BTreeMap<EraId, Vec<(I, Vec<u8>)>>,
+    /// The maximum number of messages buffered per era.
+    max_per_era: usize,
+    /// The maximum number of messages buffered per era from a single sender.
+    max_per_peer: usize,
+}
+
+impl<I: PartialEq> EarlyMessages<I> {
+    pub(super) fn new(max_per_era: usize, max_per_peer: usize) -> Self {
+        EarlyMessages {
+            messages: BTreeMap::new(),
+            max_per_era,
+            max_per_peer,
+        }
+    }
+
+    /// Buffers a message for `era_id`. Returns `false` if it was dropped because the era or the
+    /// sender has reached its limit.
+    pub(super) fn push(&mut self, era_id: EraId, sender: I, payload: Vec<u8>) -> bool {
+        let era_messages = self.messages.entry(era_id).or_default();
+        if era_messages.len() >= self.max_per_era {
+            return false;
+        }
+        let from_sender = era_messages
+            .iter()
+            .filter(|(peer, _)| *peer == sender)
+            .count();
+        if from_sender >= self.max_per_peer {
+            return false;
+        }
+        era_messages.push((sender, payload));
+        true
+    }
+
+    /// Removes and returns the messages buffered for `era_id`. Messages for earlier eras are
+    /// discarded, since those eras will not be created anymore.
+    pub(super) fn take(&mut self, era_id: EraId) -> Vec<(I, Vec<u8>)> {
+        let later_eras = self.messages.split_off(&era_id.successor());
+        let mut earlier_eras = mem::replace(&mut self.messages, later_eras);
+        earlier_eras.remove(&era_id).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_cap_messages_per_era_and_peer() {
+        let era_id = EraId::from(3);
+        let mut early_messages = EarlyMessages::new(3, 2);
+        assert!(early_messages.push(era_id, 1, vec![1]));
+        assert!(early_messages.push(era_id, 1, vec![2]));
+        assert!(!early_messages.push(era_id, 1, vec![3]));
+        assert!(early_messages.push(era_id, 2, vec![4]));
+        assert!(!early_messages.push(era_id, 3, vec![5]));
+        assert!(early_messages.push(era_id.successor(), 3, vec![6]));
+
+        let expected = vec![(1, vec![1]), (1, vec![2]), (2, vec![4])];
+        assert_eq!(early_messages.take(era_id), expected);
+        assert!(early_messages.take(era_id).is_empty());
+    }
+
+    #[test]
+    fn should_discard_messages_for_earlier_eras() {
+        let mut early_messages = EarlyMessages::new(10, 10);
+        assert!(early_messages.push(EraId::from(3), 1, vec![1]));
+        assert!(early_messages.push(EraId::from(5), 1, vec![2]));
+
+        assert!(early_messages.take(EraId::from(4)).is_empty());
+        assert!(early_messages.take(EraId::from(3)).is_empty());
+        assert_eq!(early_messages.take(EraId::from(5)), vec![(1, vec![2])]);
+    }
+}
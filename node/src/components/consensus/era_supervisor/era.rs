@@ -15,7 +15,9 @@ use crate::{
         cl_context::ClContext,
         consensus_protocol::{ConsensusProtocol, ProposedBlock},
         protocols::highway::HighwayProtocol,
+        TimerId,
     },
+    effect::TimerHandle,
     types::Timestamp,
 };
 
@@ -53,6 +55,8 @@ pub struct Era<I> {
     pub(crate) start_time: Timestamp,
     /// The height of this era's first block.
     pub(crate) start_height: u64,
+    /// The gas price floor of this era's first block.
+    pub(crate) start_gas_price_floor: u64,
     /// Pending blocks, waiting for validation and dependencies.
     validation_states: HashMap<ProposedBlock<ClContext>, ValidationState>,
     /// Validators banned in this and the next BONDED_ERAS eras, because they were slashed in the
@@ -65,6 +69,8 @@ pub struct Era<I> {
     accusations: HashSet<PublicKey>,
     /// The validator weights.
     validators: BTreeMap<PublicKey, U512>,
+    /// The pending timers, by ID and timestamp.
+    timers: HashMap<(TimerId, Timestamp), TimerHandle>,
 }
 
 impl<I> Era<I> {
@@ -72,6 +78,7 @@ impl<I> Era<I> {
         consensus: Box<dyn ConsensusProtocol<I, ClContext>>,
         start_time: Timestamp,
         start_height: u64,
+        start_gas_price_floor: u64,
         newly_slashed: Vec<PublicKey>,
         slashed: HashSet<PublicKey>,
         validators: BTreeMap<PublicKey, U512>,
@@ -80,11 +87,13 @@ impl<I> Era<I> {
             consensus,
             start_time,
             start_height,
+            start_gas_price_floor,
             validation_states: HashMap::new(),
             newly_slashed,
             slashed,
             accusations: HashSet::new(),
             validators,
+            timers: HashMap::new(),
         }
     }
 
@@ -157,10 +166,69 @@ impl<I> Era<I> {
         &self.validators
     }
 
+    /// Drops everything except the evidence and the validator set, once the era's validators are
+    /// unbonded. Only the evidence is still needed, to validate units in later eras that cite it.
+    pub(crate) fn set_evidence_only(&mut self) {
+        self.consensus.set_evidence_only();
+        self.validation_states = HashMap::new();
+        self.accusations = HashSet::new();
+    }
+
     /// Sets the pause status: While paused we don't create consensus messages other than pings.
     pub(crate) fn set_paused(&mut self, paused: bool) {
         self.consensus.set_paused(paused);
     }
+
+    /// Turns the consensus instance into a passive observer and cancels the timers that only drive
+    /// our participation as a validator.
+    pub(crate) fn deactivate_validator(&mut self) {
+        self.consensus.deactivate_validator();
+        let validator_timer_ids = self.consensus.validator_timer_ids();
+        self.cancel_timers(|timer_id| validator_timer_ids.contains(&timer_id));
+    }
+
+    /// Returns whether a timer with the given ID and timestamp is already pending.
+    pub(crate) fn has_timer(&self, timer_id: TimerId, timestamp: Timestamp) -> bool {
+        self.timers.contains_key(&(timer_id, timestamp))
+    }
+
+    /// Registers a newly scheduled timer, cancelling the pending timers it supersedes.
+    pub(crate) fn add_timer(
+        &mut self,
+        timer_id: TimerId,
+        timestamp: Timestamp,
+        handle: TimerHandle,
+    ) {
+        if self.consensus.validator_timer_ids().contains(&timer_id) {
+            self.cancel_timers(|id| id == timer_id);
+        }
+        self.timers.insert((timer_id, timestamp), handle);
+    }
+
+    /// Forgets a timer that has elapsed.
+    pub(crate) fn remove_timer(&mut self, timer_id: TimerId, timestamp: Timestamp) {
+        self.timers.remove(&(timer_id, timestamp));
+    }
+
+    /// Cancels all pending timers, e.g. because the era is being removed.
+    pub(crate) fn cancel_all_timers(&mut self) {
+        self.cancel_timers(|_| true);
+    }
+
+    /// Cancels the pending timers whose ID matches the predicate.
+    fn cancel_timers<F>(&mut self, predicate: F)
+    where
+        F: Fn(TimerId) -> bool,
+    {
+        self.timers.retain(|(timer_id, _), handle| {
+            if predicate(*timer_id) {
+                handle.cancel();
+                false
+            } else {
+                true
+            }
+        });
+    }
 }
 
 impl<I> DataSize for Era<I>
@@ -178,11 +246,13 @@ where
             consensus,
             start_time,
             start_height,
+            start_gas_price_floor,
             validation_states,
             newly_slashed,
             slashed,
             accusations,
             validators,
+            timers,
         } = self;
 
         // `DataSize` cannot be made object safe due its use of associated constants. We implement
@@ -214,10 +284,12 @@ where
         consensus_heap_size
             .saturating_add(start_time.estimate_heap_size())
             .saturating_add(start_height.estimate_heap_size())
+            .saturating_add(start_gas_price_floor.estimate_heap_size())
             .saturating_add(validation_states.estimate_heap_size())
             .saturating_add(newly_slashed.estimate_heap_size())
             .saturating_add(slashed.estimate_heap_size())
             .saturating_add(accusations.estimate_heap_size())
             .saturating_add(validators.estimate_heap_size())
+            .saturating_add(timers.estimate_heap_size())
     }
 }
@@ -18,7 +18,7 @@ use crate::{
             active_validator::{ActiveValidator, Effect},
             evidence::EvidenceError,
             state::{Fault, State, UnitError, Weight},
-            validators::{Validator, Validators},
+            validators::{Validator, ValidatorIndex, Validators},
         },
         traits::Context,
     },
@@ -238,12 +238,40 @@ impl<C: Context> Highway<C> {
         &self,
         vertex: Vertex<C>,
     ) -> Result<PreValidatedVertex<C>, (Vertex<C>, VertexError)> {
-        match self.do_pre_validate_vertex(&vertex) {
+        match self.do_pre_validate_vertex(&vertex, true) {
             Err(err) => Err((vertex, err)),
             Ok(()) => Ok(PreValidatedVertex(vertex)),
         }
     }
 
+    /// Does initial validation like `pre_validate_vertex`, but skips verifying the signature if
+    /// the vertex is a unit: The caller must already have done that, e.g. as part of a batch of
+    /// signatures returned by `unit_signature`.
+    pub(crate) fn pre_validate_vertex_with_verified_signature(
+        &self,
+        vertex: Vertex<C>,
+    ) -> Result<PreValidatedVertex<C>, (Vertex<C>, VertexError)> {
+        match self.do_pre_validate_vertex(&vertex, false) {
+            Err(err) => Err((vertex, err)),
+            Ok(()) => Ok(PreValidatedVertex(vertex)),
+        }
+    }
+
+    /// Returns the hash, the creator's ID and the signature, if the vertex is a unit created by a
+    /// known validator.
+    pub(crate) fn unit_signature<'a>(
+        &'a self,
+        vertex: &'a Vertex<C>,
+    ) -> Option<(C::Hash, &'a C::ValidatorId, &'a C::Signature)> {
+        match vertex {
+            Vertex::Unit(unit) => {
+                let v_id = self.validators.id(unit.wire_unit().creator)?;
+                Some((unit.hash(), v_id, &unit.signature))
+            }
+            Vertex::Evidence(_) | Vertex::Endorsements(_) | Vertex::Ping(_) => None,
+        }
+    }
+
     /// Returns the next missing dependency, or `None` if all dependencies of `pvv` are satisfied.
     ///
     /// If this returns `None`, `validate_vertex` can be called.
@@ -515,7 +543,13 @@ impl<C: Context> Highway<C> {
 
     /// Performs initial validation and returns an error if `vertex` is invalid. (See
     /// `PreValidatedVertex` and `validate_vertex`.)
-    fn do_pre_validate_vertex(&self, vertex: &Vertex<C>) -> Result<(), VertexError> {
+    ///
+    /// A unit's signature is only verified if `verify_unit_signature` is `true`.
+    fn do_pre_validate_vertex(
+        &self,
+        vertex: &Vertex<C>,
+        verify_unit_signature: bool,
+    ) -> Result<(), VertexError> {
         match vertex {
             Vertex::Unit(unit) => {
                 let creator = unit.wire_unit().creator;
@@ -523,7 +557,9 @@ impl<C: Context> Highway<C> {
                 if unit.wire_unit().instance_id != self.instance_id {
                     return Err(UnitError::InstanceId.into());
                 }
-                if !C::verify_signature(&unit.hash(), v_id, &unit.signature) {
+                if verify_unit_signature
+                    && !C::verify_signature(&unit.hash(), v_id, &unit.signature)
+                {
                     return Err(UnitError::Signature.into());
                 }
                 Ok(self.state.pre_validate_unit(unit)?)
@@ -664,8 +700,27 @@ impl<C: Context> Highway<C> {
             .map(|av| av.next_round_length())
     }
 
-    /// Logs a message if this is a block and any previous blocks were skipped.
-    fn log_if_missing_proposal(&self, unit_hash: &C::Hash) {
+    /// Returns the values of the proposals orphaned by the given unit, if it is a block: Those are
+    /// the proposals by honest leaders of the rounds between the block's parent and the block
+    /// itself. They are not among the block's ancestors and can therefore never be finalized.
+    pub(crate) fn orphaned_values(&self, unit_hash: &C::Hash) -> Vec<&C::ConsensusValue> {
+        self.skipped_rounds(unit_hash)
+            .into_iter()
+            .filter_map(|skipped_r_id| {
+                let leader_index = self.state.leader(skipped_r_id);
+                if self.state.is_faulty(leader_index) {
+                    return None;
+                }
+                let proposal_hash = self.orphaned_proposal(leader_index, skipped_r_id)?;
+                Some(&self.state.block(proposal_hash).value)
+            })
+            .collect()
+    }
+
+    /// Returns the IDs of the rounds since the given unit's parent block in which no block was
+    /// added to the chain, if the unit is a block by an honest validator. At most
+    /// `MAX_SKIPPED_PROPOSAL_LOGS` rounds are returned, most recent first.
+    fn skipped_rounds(&self, unit_hash: &C::Hash) -> Vec<Timestamp> {
         let state = &self.state;
         let unit = state.unit(unit_hash);
         let r_id = unit.round_id();
@@ -674,7 +729,7 @@ impl<C: Context> Highway<C> {
             || state.leader(r_id) != unit.creator
             || state.is_faulty(unit.creator)
         {
-            return; // Not a block by an honest validator. (Don't let faulty validators spam logs.)
+            return vec![]; // Not a block by an honest validator.
         }
 
         // Iterate over all rounds since the parent — or since the start time, if there is none.
@@ -683,11 +738,31 @@ impl<C: Context> Highway<C> {
         } else {
             state.params().start_timestamp()
         };
-        for skipped_r_id in (1..=MAX_SKIPPED_PROPOSAL_LOGS)
+        (1..=MAX_SKIPPED_PROPOSAL_LOGS)
             .map(|i| r_id.saturating_sub(state.params().min_round_length() * i))
             .take_while(|skipped_r_id| *skipped_r_id > parent_timestamp)
-        {
-            let leader_index = state.leader(skipped_r_id);
+            .collect()
+    }
+
+    /// Returns the hash of the given leader's proposal in the skipped round, if we have it.
+    fn orphaned_proposal(
+        &self,
+        leader_index: ValidatorIndex,
+        skipped_r_id: Timestamp,
+    ) -> Option<&C::Hash> {
+        let state = &self.state;
+        let leader_hash = state.panorama()[leader_index].correct()?;
+        state
+            .swimlane(leader_hash)
+            .find(|(_, unit)| unit.timestamp <= skipped_r_id)
+            .filter(|(hash, unit)| unit.timestamp == skipped_r_id && unit.block == **hash)
+            .map(|(hash, _)| hash)
+    }
+
+    /// Logs a message if this is a block and any previous blocks were skipped.
+    fn log_if_missing_proposal(&self, unit_hash: &C::Hash) {
+        for skipped_r_id in self.skipped_rounds(unit_hash) {
+            let leader_index = self.state.leader(skipped_r_id);
             let leader_id = match self.validators.id(leader_index) {
                 None => {
                     error!(?leader_index, "missing leader validator ID");
@@ -695,20 +770,14 @@ impl<C: Context> Highway<C> {
                 }
                 Some(leader_id) => leader_id,
             };
-            if state.is_faulty(leader_index) {
+            if self.state.is_faulty(leader_index) {
                 trace!(
                     ?leader_index, %leader_id, round_id = %skipped_r_id,
                     "missing proposal: faulty leader was skipped",
                 );
             } else {
-                let reason = state.panorama()[leader_index]
-                    .correct()
-                    .and_then(|leader_hash| {
-                        state
-                            .swimlane(leader_hash)
-                            .find(|(_, unit)| unit.timestamp <= skipped_r_id)
-                            .filter(|(_, unit)| unit.timestamp == skipped_r_id)
-                    })
+                let reason = self
+                    .orphaned_proposal(leader_index, skipped_r_id)
                     .map_or("the leader missed their turn", |_| {
                         "the leader's proposal got orphaned"
                     });
@@ -871,6 +871,15 @@ impl<C: Context> State<C> {
         self.units.len()
     }
 
+    /// Returns the number of units received from each validator.
+    pub(crate) fn unit_counts(&self) -> ValidatorMap<u64> {
+        let mut counts: ValidatorMap<u64> = self.weights.iter().map(|_| 0).collect();
+        for unit in self.units.values() {
+            counts[unit.creator] = counts[unit.creator].saturating_add(1);
+        }
+        counts
+    }
+
     /// Returns the set of units (by hash) that are endorsed and seen from the panorama.
     pub(crate) fn seen_endorsed(&self, pan: &Panorama<C>) -> BTreeSet<C::Hash> {
         // First we collect all units that were already seen as endorsed by earlier units.
@@ -889,16 +898,21 @@ impl<C: Context> State<C> {
     }
 
     /// Drops all state other than evidence.
+    ///
+    /// The collections are replaced rather than cleared, so that the memory they occupied is
+    /// actually released: `clear` would keep the full capacity of the unit DAG allocated for the
+    /// rest of the era's lifetime.
     pub(crate) fn retain_evidence_only(&mut self) {
-        self.units.clear();
-        self.blocks.clear();
+        self.units = HashMap::new();
+        self.blocks = HashMap::new();
         for obs in self.panorama.iter_mut() {
             if obs.is_correct() {
                 *obs = Observation::None;
             }
         }
-        self.endorsements.clear();
-        self.incomplete_endorsements.clear();
+        self.endorsements = HashMap::new();
+        self.incomplete_endorsements = HashMap::new();
+        self.faults.shrink_to_fit();
     }
 
     /// Validates whether a unit with the given panorama and `endorsed` set satisfies the
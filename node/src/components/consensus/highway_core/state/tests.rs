@@ -300,6 +300,18 @@ fn find_in_swimlane() -> Result<(), AddUnitError<TestContext>> {
     Ok(())
 }
 
+#[test]
+fn unit_counts() -> Result<(), AddUnitError<TestContext>> {
+    let mut state = State::new_test(WEIGHTS, 0);
+    let a0 = add_unit!(state, ALICE, 0xA; N, N, N)?;
+    let b0 = add_unit!(state, BOB, 48, 4u8, 0xB; N, N, N)?;
+    let _a1 = add_unit!(state, ALICE, None; a0, b0, N)?;
+
+    let counts: Vec<u64> = state.unit_counts().into_iter().collect();
+    assert_eq!(vec![2, 1, 0], counts);
+    Ok(())
+}
+
 #[test]
 fn fork_choice() -> Result<(), AddUnitError<TestContext>> {
     let mut state = State::new_test(WEIGHTS, 0);
@@ -826,10 +838,14 @@ fn retain_evidence_only() -> Result<(), AddUnitError<TestContext>> {
     let b0 = add_unit!(state, BOB, 0xB; a0, N, N)?;
     let _a0_prime = add_unit!(state, ALICE, 0xA2; N, N, N)?;
     assert_eq!(&panorama!(F, b0, N), state.panorama());
+    let heap_size_before = state.estimate_heap_size();
     state.retain_evidence_only();
     assert_eq!(&panorama!(F, N, N), state.panorama());
     assert!(!state.has_unit(&a0));
     assert!(state.has_evidence(ALICE));
+    // The memory used by the dropped units must actually be released.
+    assert_eq!(0, state.units.capacity());
+    assert!(state.estimate_heap_size() < heap_size_before);
     Ok(())
 }
 
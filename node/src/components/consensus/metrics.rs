@@ -1,4 +1,4 @@
-use prometheus::{Gauge, IntGauge, Registry};
+use prometheus::{Gauge, IntCounter, IntGauge, Registry};
 
 use crate::{
     types::{FinalizedBlock, Timestamp},
@@ -18,6 +18,12 @@ pub(super) struct ConsensusMetrics {
     time_of_last_finalized_block: IntGauge,
     /// The Current era.
     pub(super) current_era: IntGauge,
+    /// Number of times a peer was disconnected for exceeding the pending vertex quota.
+    pub(super) pending_vertex_quota_violations: IntCounter,
+    /// Number of pending vertices evicted because the synchronizer exceeded its byte budget.
+    pub(super) evicted_pending_vertices: IntCounter,
+    /// Number of validators reported as inactive, and thus evicted, in the latest switch block.
+    inactive_validators: IntGauge,
     /// registry component.
     registry: Registry,
 }
@@ -39,17 +45,35 @@ impl ConsensusMetrics {
             "timestamp of the most recently finalized block",
         )?;
         let current_era = IntGauge::new("current_era", "The current era")?;
+        let pending_vertex_quota_violations = IntCounter::new(
+            "pending_vertex_quota_violations",
+            "number of peers disconnected for exceeding the pending vertex quota",
+        )?;
+        let evicted_pending_vertices = IntCounter::new(
+            "evicted_pending_vertices",
+            "number of pending vertices evicted because the synchronizer exceeded its byte budget",
+        )?;
+        let inactive_validators = IntGauge::new(
+            "inactive_validators",
+            "number of validators reported as inactive in the most recent switch block",
+        )?;
         registry.register(Box::new(finalization_time.clone()))?;
         registry.register(Box::new(finalized_block_count.clone()))?;
         registry.register(Box::new(current_era.clone()))?;
         registry.register(Box::new(time_of_last_proposed_block.clone()))?;
         registry.register(Box::new(time_of_last_finalized_block.clone()))?;
+        registry.register(Box::new(pending_vertex_quota_violations.clone()))?;
+        registry.register(Box::new(evicted_pending_vertices.clone()))?;
+        registry.register(Box::new(inactive_validators.clone()))?;
         Ok(ConsensusMetrics {
             finalization_time,
             finalized_block_count,
             time_of_last_proposed_block,
             time_of_last_finalized_block,
             current_era,
+            pending_vertex_quota_violations,
+            evicted_pending_vertices,
+            inactive_validators,
             registry: registry.clone(),
         })
     }
@@ -62,6 +86,10 @@ impl ConsensusMetrics {
             .set(finalized_block.timestamp().millis() as i64);
         self.finalized_block_count
             .set(finalized_block.height() as i64);
+        if let Some(era_report) = finalized_block.era_report() {
+            self.inactive_validators
+                .set(era_report.inactive_validators.len() as i64);
+        }
     }
 
     /// Updates the metrics and records a newly proposed block.
@@ -78,5 +106,8 @@ impl Drop for ConsensusMetrics {
         unregister_metric!(self.registry, self.current_era);
         unregister_metric!(self.registry, self.time_of_last_finalized_block);
         unregister_metric!(self.registry, self.time_of_last_proposed_block);
+        unregister_metric!(self.registry, self.pending_vertex_quota_violations);
+        unregister_metric!(self.registry, self.evicted_pending_vertices);
+        unregister_metric!(self.registry, self.inactive_validators);
     }
 }
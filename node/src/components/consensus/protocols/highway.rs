@@ -1,6 +1,7 @@
 pub(crate) mod config;
 mod participation;
 mod round_success_meter;
+mod state_summary;
 mod synchronizer;
 #[cfg(test)]
 mod tests;
@@ -9,12 +10,13 @@ use std::{
     any::Any,
     collections::{BTreeMap, HashMap, HashSet},
     fmt::Debug,
-    iter,
+    iter, mem,
     path::PathBuf,
 };
 
 use datasize::DataSize;
 use itertools::Itertools;
+use num_rational::Ratio;
 use num_traits::AsPrimitive;
 use serde::{Deserialize, Serialize};
 use tracing::{error, info, trace, warn};
@@ -35,7 +37,7 @@ use crate::{
                 Vertex, VertexError,
             },
             state,
-            state::{Observation, Panorama},
+            state::{Observation, Panorama, Weight},
             validators::{ValidatorIndex, Validators},
         },
         traits::{ConsensusValueT, Context, NodeIdT},
@@ -45,6 +47,7 @@ use crate::{
 };
 
 pub use self::config::Config as HighwayConfig;
+pub(crate) use self::state_summary::HighwayStateSummary;
 use self::{round_success_meter::RoundSuccessMeter, synchronizer::Synchronizer};
 
 /// Never allow more than this many units in a piece of evidence for conflicting endorsements,
@@ -68,6 +71,20 @@ const TIMER_ID_PROGRESS_ALERT: TimerId = TimerId(6);
 
 /// The action of adding a vertex from the `vertices_to_be_added` queue.
 const ACTION_ID_VERTEX: ActionId = ActionId(0);
+/// The action of verifying the signatures of the queued incoming units.
+const ACTION_ID_VERIFY_SIGNATURES: ActionId = ActionId(1);
+
+/// Returns the fault tolerance threshold corresponding to the given fraction of the total weight,
+/// or `None` if it is not in the interval (0, 1) or rounds down to zero.
+fn ftt_for_fraction(total_weight: Weight, fraction: Ratio<u64>) -> Option<Weight> {
+    if fraction <= Ratio::from_integer(0) || fraction >= Ratio::from_integer(1) {
+        return None;
+    }
+    #[allow(clippy::integer_arithmetic)] // The fraction is less than 1, so this can't overflow.
+    let ftt =
+        u128::from(total_weight) * u128::from(*fraction.numer()) / u128::from(*fraction.denom());
+    Some(Weight(ftt as u64)).filter(|ftt| *ftt > Weight(0))
+}
 
 #[derive(DataSize, Debug)]
 pub(crate) struct HighwayProtocol<I, C>
@@ -78,6 +95,10 @@ where
     /// Incoming blocks we can't add yet because we are waiting for validation.
     pending_values: HashMap<ProposedBlock<C>, HashSet<(ValidVertex<C>, I)>>,
     finality_detector: FinalityDetector<C>,
+    /// Additional finality detectors for the configured finality tiers, with their threshold
+    /// fractions. They only report when blocks reach their tier; they don't affect consensus.
+    #[data_size(skip)]
+    tier_detectors: Vec<(Ratio<u64>, FinalityDetector<C>)>,
     highway: Highway<C>,
     /// A tracker for whether we are keeping up with the current round exponent or not.
     round_success_meter: RoundSuccessMeter<C>,
@@ -94,6 +115,11 @@ where
     log_participation_interval: TimeDiff,
     /// Whether to log the size of every incoming and outgoing serialized unit.
     log_unit_sizes: bool,
+    /// Whether to verify incoming units' signatures in batches.
+    batch_signature_verification: bool,
+    /// Incoming units whose signatures haven't been verified yet, with their senders and
+    /// serialized messages.
+    unverified_vertices: Vec<(I, Vec<u8>, Vertex<C>)>,
 }
 
 impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
@@ -131,19 +157,43 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
 
         let highway_config = &protocol_config.highway_config;
 
-        let total_weight = u128::from(validators.total_weight());
+        let total_weight = validators.total_weight();
         let ftt_fraction = highway_config.finality_threshold_fraction;
         assert!(
             ftt_fraction < 1.into(),
             "finality threshold must be less than 100%"
         );
-        #[allow(clippy::integer_arithmetic)] // FTT is less than 1, so this can't overflow.
-        let ftt = total_weight * *ftt_fraction.numer() as u128 / *ftt_fraction.denom() as u128;
-        let ftt = (ftt as u64).into();
+        let chainspec_ftt = ftt_for_fraction(total_weight, ftt_fraction)
+            .expect("chainspec finality threshold must be positive");
+        let ftt = match config.highway.finality_threshold_fraction {
+            None => chainspec_ftt,
+            Some(fraction) => ftt_for_fraction(total_weight, fraction).unwrap_or_else(|| {
+                warn!(%fraction, "ignoring invalid finality threshold override");
+                chainspec_ftt
+            }),
+        };
+        let tier_detectors = config
+            .highway
+            .finality_tiers
+            .iter()
+            .filter_map(|fraction| match ftt_for_fraction(total_weight, *fraction) {
+                Some(tier_ftt) => Some((*fraction, FinalityDetector::new(tier_ftt))),
+                None => {
+                    warn!(%fraction, "ignoring invalid finality tier");
+                    None
+                }
+            })
+            .collect();
 
         let round_success_meter = prev_cp
             .and_then(|cp| cp.as_any().downcast_ref::<HighwayProtocol<I, C>>())
-            .map(|highway_proto| highway_proto.next_era_round_succ_meter(era_start_time.max(now)))
+            .map(|highway_proto| {
+                highway_proto.next_era_round_succ_meter(
+                    era_start_time.max(now),
+                    highway_config.minimum_round_exponent,
+                    highway_config.maximum_round_exponent,
+                )
+            })
             .unwrap_or_else(|| {
                 RoundSuccessMeter::new(
                     highway_config.minimum_round_exponent,
@@ -193,6 +243,7 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
         let hw_proto = Box::new(HighwayProtocol {
             pending_values: HashMap::new(),
             finality_detector: FinalityDetector::new(ftt),
+            tier_detectors,
             highway,
             round_success_meter,
             synchronizer: Synchronizer::new(config.highway.clone(), validators_count, instance_id),
@@ -202,6 +253,8 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
             standstill_timeout: config.highway.standstill_timeout,
             log_participation_interval: config.highway.log_participation_interval,
             log_unit_sizes: config.highway.log_unit_sizes,
+            batch_signature_verification: config.highway.batch_signature_verification,
+            unverified_vertices: Vec::new(),
         });
 
         (hw_proto, outcomes)
@@ -244,7 +297,13 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
             AvEffect::NewVertex(vv) => {
                 self.log_unit_size(vv.inner(), "sending new unit");
                 self.calculate_round_exponent(&vv, now);
-                self.process_new_vertex(vv)
+                let mut outcomes = vv
+                    .inner()
+                    .unit()
+                    .map(|swunit| self.orphaned_value_outcomes(&swunit.hash()))
+                    .unwrap_or_default();
+                outcomes.extend(self.process_new_vertex(vv));
+                outcomes
             }
             AvEffect::ScheduleTimer(timestamp) => {
                 vec![ProtocolOutcome::ScheduleTimer(
@@ -280,8 +339,12 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
     }
 
     fn detect_finality(&mut self) -> ProtocolOutcomes<I, C> {
+        let mut outcomes = self.detect_finality_tiers();
         let faulty_weight = match self.finality_detector.run(&self.highway) {
-            Ok(iter) => return iter.map(ProtocolOutcome::FinalizedBlock).collect(),
+            Ok(iter) => {
+                outcomes.extend(iter.map(ProtocolOutcome::FinalizedBlock));
+                return outcomes;
+            }
             Err(FttExceeded(weight)) => weight.0,
         };
         error!(
@@ -290,7 +353,27 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
             "too many faulty validators"
         );
         self.log_participation();
-        vec![ProtocolOutcome::FttExceeded]
+        outcomes.push(ProtocolOutcome::FttExceeded);
+        outcomes
+    }
+
+    /// Returns an outcome for every block that reached one of the configured finality tiers.
+    ///
+    /// A tier whose fault tolerance threshold has been exceeded doesn't report any blocks.
+    fn detect_finality_tiers(&mut self) -> ProtocolOutcomes<I, C> {
+        let highway = &self.highway;
+        let mut outcomes = Vec::new();
+        for (fraction, detector) in &mut self.tier_detectors {
+            if let Ok(iter) = detector.run(highway) {
+                outcomes.extend(
+                    iter.map(|finalized_block| ProtocolOutcome::FinalityTierReached {
+                        relative_height: finalized_block.relative_height,
+                        finality_threshold_fraction: *fraction,
+                    }),
+                );
+            }
+        }
+        outcomes
     }
 
     /// Adds the given vertices to the protocol state, if possible, or requests missing
@@ -401,6 +484,7 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
         self.log_unit_size(vv.inner(), "adding new unit to the protocol state");
         self.log_proposal(vv.inner(), "adding valid proposal to the protocol state");
         let vertex_id = vv.inner().id();
+        let unit_hash = vv.inner().unit().map(|swunit| swunit.hash());
         // Check whether we should change the round exponent.
         // It's important to do it before the vertex is added to the state - this way if the last
         // round has finished, we now have all the vertices from that round in the state, and no
@@ -409,13 +493,34 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
         let av_effects = self.highway.add_valid_vertex(vv, now);
         // Once vertex is added to the state, we can remove it from the cache.
         self.pvv_cache.remove(&vertex_id);
-        self.process_av_effects(av_effects, now)
+        let mut outcomes = unit_hash
+            .map(|unit_hash| self.orphaned_value_outcomes(&unit_hash))
+            .unwrap_or_default();
+        outcomes.extend(self.process_av_effects(av_effects, now));
+        outcomes
+    }
+
+    /// Returns an `OrphanedValue` outcome for each proposal orphaned by the given unit, which must
+    /// already be in the protocol state.
+    fn orphaned_value_outcomes(&self, unit_hash: &C::Hash) -> ProtocolOutcomes<I, C> {
+        self.highway
+            .orphaned_values(unit_hash)
+            .into_iter()
+            .cloned()
+            .map(ProtocolOutcome::OrphanedValue)
+            .collect()
     }
 
     /// Returns an instance of `RoundSuccessMeter` for the new era: resetting the counters where
-    /// appropriate.
-    fn next_era_round_succ_meter(&self, timestamp: Timestamp) -> RoundSuccessMeter<C> {
-        self.round_success_meter.next_era(timestamp)
+    /// appropriate, and clamping the round exponent to the new era's range.
+    fn next_era_round_succ_meter(
+        &self,
+        timestamp: Timestamp,
+        min_round_exp: u8,
+        max_round_exp: u8,
+    ) -> RoundSuccessMeter<C> {
+        self.round_success_meter
+            .next_era(timestamp, min_round_exp, max_round_exp)
     }
 
     /// Returns an iterator over all the values that are in parents of the given block.
@@ -432,6 +537,11 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
         })
     }
 
+    /// Returns a summary of the protocol state, for debugging.
+    pub(crate) fn state_summary(&self) -> HighwayStateSummary<C> {
+        HighwayStateSummary::new(&self.highway)
+    }
+
     /// Prints a log statement listing the inactive and faulty validators.
     fn log_participation(&self) {
         let instance_id = self.highway.instance_id();
@@ -500,6 +610,24 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
         )]
     }
 
+    /// Returns the outcomes that send the requested dependency to `sender`, if we have it.
+    fn handle_request_dependency(&self, sender: I, dep: Dependency<C>) -> ProtocolOutcomes<I, C> {
+        match self.highway.get_dependency(&dep) {
+            GetDepOutcome::None => {
+                info!(?dep, ?sender, "requested dependency doesn't exist");
+                vec![]
+            }
+            GetDepOutcome::Evidence(vid) => {
+                vec![ProtocolOutcome::SendEvidence(sender, vid)]
+            }
+            // TODO: Should this be done via a gossip service?
+            GetDepOutcome::Vertex(vv) => vec![ProtocolOutcome::CreatedTargetedMessage(
+                HighwayMessage::NewVertex(vv.into()).serialize(),
+                sender,
+            )],
+        }
+    }
+
     /// Prints a log message if the vertex is a proposal unit. Otherwise returns `false`.
     fn log_proposal(&self, vertex: &Vertex<C>, msg: &str) -> bool {
         let (wire_unit, hash) = match vertex.unit() {
@@ -523,17 +651,120 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
         true
     }
 
+    /// Pre-validates an incoming vertex and schedules it for addition to the protocol state.
+    ///
+    /// If `signature_verified` is `true`, the caller has already verified the signature of the
+    /// vertex, if it is a unit.
+    fn handle_new_vertex(
+        &mut self,
+        sender: I,
+        msg: Vec<u8>,
+        v: Vertex<C>,
+        signature_verified: bool,
+        now: Timestamp,
+    ) -> ProtocolOutcomes<I, C> {
+        let v_id = v.id();
+        // If we already have that vertex, do not process it.
+        if self.highway.has_dependency(&v_id) {
+            return vec![];
+        }
+        let pvv = match self.pre_validate_vertex(v, signature_verified) {
+            Ok(pvv) => pvv,
+            Err((_, err)) => {
+                trace!("received an invalid vertex");
+                // drop the vertices that might have depended on this one
+                let faulty_senders = self.synchronizer.invalid_vertices(vec![v_id]);
+                return iter::once(ProtocolOutcome::InvalidIncomingMessage(
+                    msg,
+                    sender,
+                    err.into(),
+                ))
+                .chain(faulty_senders.into_iter().map(ProtocolOutcome::Disconnect))
+                .collect();
+            }
+        };
+        // Keep track of whether the prevalidated vertex was from an equivocator
+        let is_faulty = match pvv.inner().creator() {
+            Some(creator) => self.highway.state().is_faulty(creator),
+            None => false,
+        };
+
+        if is_faulty && !self.synchronizer.is_dependency(&pvv.inner().id()) {
+            trace!("received a vertex from a faulty validator; dropping");
+            return vec![];
+        }
+
+        match pvv.timestamp() {
+            Some(timestamp) if timestamp > now + self.synchronizer.pending_vertex_timeout() => {
+                trace!("received a vertex with a timestamp far in the future; dropping");
+                vec![]
+            }
+            Some(timestamp) if timestamp > now => {
+                // If it's not from an equivocator and from the future, add to queue
+                trace!("received a vertex from the future; storing for later");
+                let mut outcomes = self
+                    .synchronizer
+                    .store_vertex_for_addition_later(timestamp, now, sender, pvv);
+                let timer_id = TIMER_ID_VERTEX_WITH_FUTURE_TIMESTAMP;
+                outcomes.push(ProtocolOutcome::ScheduleTimer(timestamp, timer_id));
+                outcomes
+            }
+            _ => {
+                // If it's not from an equivocator or it is a transitive dependency, add the
+                // vertex
+                if !self.log_proposal(pvv.inner(), "received a proposal") {
+                    trace!("received a valid vertex");
+                }
+                self.synchronizer.schedule_add_vertex(sender, pvv, now)
+            }
+        }
+    }
+
+    /// Verifies the signatures of all queued incoming units as a single batch, and handles the
+    /// units. If the batch is invalid, each unit's signature is verified individually instead, so
+    /// that only the units with invalid signatures are rejected.
+    fn verify_queued_signatures(&mut self, now: Timestamp) -> ProtocolOutcomes<I, C> {
+        let vertices = mem::take(&mut self.unverified_vertices);
+        let signatures: Vec<_> = vertices
+            .iter()
+            .filter_map(|(_, _, v)| self.highway.unit_signature(v))
+            .collect();
+        let items: Vec<_> = signatures
+            .iter()
+            .map(|(hash, v_id, signature)| (hash, *v_id, *signature))
+            .collect();
+        let verified = C::verify_signatures(&items);
+        if !verified {
+            info!(
+                count = items.len(),
+                "batch signature verification failed; verifying individually"
+            );
+        }
+        vertices
+            .into_iter()
+            .flat_map(|(sender, msg, v)| self.handle_new_vertex(sender, msg, v, verified, now))
+            .collect()
+    }
+
     /// Prevalidates the vertex but checks the cache for previously validated vertices.
     /// Avoids multiple validation of the same vertex.
+    ///
+    /// If `signature_verified` is `true`, a unit's signature is assumed to be valid.
     fn pre_validate_vertex(
         &mut self,
         v: Vertex<C>,
+        signature_verified: bool,
     ) -> Result<PreValidatedVertex<C>, (Vertex<C>, VertexError)> {
         let id = v.id();
         if let Some(prev_pvv) = self.pvv_cache.get(&id) {
             return Ok(prev_pvv.clone());
         }
-        let pvv = self.highway.pre_validate_vertex(v)?;
+        let pvv = if signature_verified {
+            self.highway
+                .pre_validate_vertex_with_verified_signature(v)?
+        } else {
+            self.highway.pre_validate_vertex(v)?
+        };
         self.pvv_cache.insert(id, pvv.clone());
         Ok(pvv)
     }
@@ -557,6 +788,7 @@ enum HighwayMessage<C: Context> {
     NewVertex(Vertex<C>),
     RequestDependency(Dependency<C>),
     LatestStateRequest(Panorama<C>),
+    RequestDependencies(Vec<Dependency<C>>),
 }
 
 impl<C: Context> HighwayMessage<C> {
@@ -594,78 +826,40 @@ where
                 vec![]
             }
             Ok(HighwayMessage::NewVertex(v)) => {
-                let v_id = v.id();
-                // If we already have that vertex, do not process it.
-                if self.highway.has_dependency(&v_id) {
-                    return vec![];
-                }
-                let pvv = match self.pre_validate_vertex(v) {
-                    Ok(pvv) => pvv,
-                    Err((_, err)) => {
-                        trace!("received an invalid vertex");
-                        // drop the vertices that might have depended on this one
-                        let faulty_senders = self.synchronizer.invalid_vertices(vec![v_id]);
-                        return iter::once(ProtocolOutcome::InvalidIncomingMessage(
-                            msg,
-                            sender,
-                            err.into(),
-                        ))
-                        .chain(faulty_senders.into_iter().map(ProtocolOutcome::Disconnect))
-                        .collect();
-                    }
-                };
-                // Keep track of whether the prevalidated vertex was from an equivocator
-                let is_faulty = match pvv.inner().creator() {
-                    Some(creator) => self.highway.state().is_faulty(creator),
-                    None => false,
-                };
-
-                if is_faulty && !self.synchronizer.is_dependency(&pvv.inner().id()) {
-                    trace!("received a vertex from a faulty validator; dropping");
-                    return vec![];
-                }
-
-                match pvv.timestamp() {
-                    Some(timestamp)
-                        if timestamp > now + self.synchronizer.pending_vertex_timeout() =>
-                    {
-                        trace!("received a vertex with a timestamp far in the future; dropping");
+                if self.batch_signature_verification
+                    && matches!(v, Vertex::Unit(_))
+                    && !self.pvv_cache.contains_key(&v.id())
+                {
+                    // Verify the signature later, together with any other units that arrive before
+                    // the queued action is handled.
+                    let outcomes = if self.unverified_vertices.is_empty() {
+                        vec![ProtocolOutcome::QueueAction(ACTION_ID_VERIFY_SIGNATURES)]
+                    } else {
                         vec![]
-                    }
-                    Some(timestamp) if timestamp > now => {
-                        // If it's not from an equivocator and from the future, add to queue
-                        trace!("received a vertex from the future; storing for later");
-                        self.synchronizer
-                            .store_vertex_for_addition_later(timestamp, now, sender, pvv);
-                        let timer_id = TIMER_ID_VERTEX_WITH_FUTURE_TIMESTAMP;
-                        vec![ProtocolOutcome::ScheduleTimer(timestamp, timer_id)]
-                    }
-                    _ => {
-                        // If it's not from an equivocator or it is a transitive dependency, add the
-                        // vertex
-                        if !self.log_proposal(pvv.inner(), "received a proposal") {
-                            trace!("received a valid vertex");
-                        }
-                        self.synchronizer.schedule_add_vertex(sender, pvv, now)
-                    }
+                    };
+                    self.unverified_vertices.push((sender, msg, v));
+                    return outcomes;
                 }
+                self.handle_new_vertex(sender, msg, v, false, now)
             }
             Ok(HighwayMessage::RequestDependency(dep)) => {
                 trace!("received a request for a dependency");
-                match self.highway.get_dependency(&dep) {
-                    GetDepOutcome::None => {
-                        info!(?dep, ?sender, "requested dependency doesn't exist");
-                        vec![]
-                    }
-                    GetDepOutcome::Evidence(vid) => {
-                        vec![ProtocolOutcome::SendEvidence(sender, vid)]
-                    }
-                    // TODO: Should this be done via a gossip service?
-                    GetDepOutcome::Vertex(vv) => vec![ProtocolOutcome::CreatedTargetedMessage(
-                        HighwayMessage::NewVertex(vv.into()).serialize(),
-                        sender,
-                    )],
+                self.handle_request_dependency(sender, dep)
+            }
+            Ok(HighwayMessage::RequestDependencies(mut deps)) => {
+                trace!(count = deps.len(), "received a request for dependencies");
+                let max_deps = self.synchronizer.max_requested_dependencies();
+                if deps.len() > max_deps {
+                    info!(
+                        count = deps.len(),
+                        %max_deps, ?sender,
+                        "too many dependencies requested; serving only the first ones"
+                    );
+                    deps.truncate(max_deps);
                 }
+                deps.into_iter()
+                    .flat_map(|dep| self.handle_request_dependency(sender.clone(), dep))
+                    .collect()
             }
             Ok(HighwayMessage::LatestStateRequest(panorama)) => {
                 trace!("received a request for the latest state");
@@ -714,15 +908,26 @@ where
                         }
                     };
 
-                state
+                // Dependencies we are missing are requested in batches, everything else is sent
+                // right away.
+                let mut missing_deps = vec![];
+                let mut outcomes: ProtocolOutcomes<I, C> = vec![];
+                for msg in state
                     .panorama()
                     .enumerate()
                     .zip(&panorama)
                     .filter_map(create_message)
-                    .map(|msg| {
-                        ProtocolOutcome::CreatedTargetedMessage(msg.serialize(), sender.clone())
-                    })
-                    .collect()
+                {
+                    match msg {
+                        HighwayMessage::RequestDependency(dep) => missing_deps.push(dep),
+                        msg => outcomes.push(ProtocolOutcome::CreatedTargetedMessage(
+                            msg.serialize(),
+                            sender.clone(),
+                        )),
+                    }
+                }
+                outcomes.extend(self.synchronizer.request_dependencies(sender, missing_deps));
+                outcomes
             }
         }
     }
@@ -774,6 +979,7 @@ where
     fn handle_action(&mut self, action_id: ActionId, now: Timestamp) -> ProtocolOutcomes<I, C> {
         match action_id {
             ACTION_ID_VERTEX => self.add_vertex(now),
+            ACTION_ID_VERIFY_SIGNATURES => self.verify_queued_signatures(now),
             _ => unreachable!("unexpected action ID"),
         }
     }
@@ -845,10 +1051,16 @@ where
         self.highway.deactivate_validator()
     }
 
+    fn validator_timer_ids(&self) -> &'static [TimerId] {
+        // The active validator ignores any timer before the one it scheduled last.
+        &[TIMER_ID_ACTIVE_VALIDATOR]
+    }
+
     fn set_evidence_only(&mut self) {
-        // TODO: We could also drop the finality detector and round success meter here. Maybe make
-        // HighwayProtocol an enum with an EvidenceOnly variant?
-        self.pending_values.clear();
+        // The finality detector and round success meter are small and of constant size, so we keep
+        // them rather than making the protocol an enum with an evidence-only variant.
+        self.pending_values = HashMap::new();
+        self.pvv_cache = HashMap::new();
         self.synchronizer.retain_evidence_only();
         self.highway.retain_evidence_only();
         self.evidence_only = true;
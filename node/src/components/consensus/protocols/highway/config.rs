@@ -1,5 +1,6 @@
 use std::path::PathBuf;
 
+use num_rational::Ratio;
 use serde::{Deserialize, Serialize};
 
 use datasize::DataSize;
@@ -22,11 +23,42 @@ pub struct Config {
     pub log_participation_interval: TimeDiff,
     /// Log the size of every incoming and outgoing serialized unit.
     pub log_unit_sizes: bool,
+    /// Verify the signatures of incoming units in batches rather than one by one. Incoming
+    /// vertices are queued until the next batch is processed, which is faster if many arrive
+    /// at once.
+    #[serde(default)]
+    pub batch_signature_verification: bool,
     /// The maximum number of blocks by which execution is allowed to lag behind finalization.
     /// If it is more than that, consensus will pause, and resume once the executor has caught up.
     pub max_execution_delay: u64,
     /// The maximum number of peers we request the same vertex from in parallel.
     pub max_requests_for_vertex: usize,
+    /// The maximum number of dependencies requested from or served to a peer in a single message.
+    pub max_requested_dependencies: usize,
+    /// The time during which repeated evidence requests from the same peer for the same validator
+    /// are ignored.
+    pub evidence_request_cooldown: TimeDiff,
+    /// The maximum number of messages for the next era which are kept until that era is created.
+    pub max_early_messages_per_era: usize,
+    /// The maximum number of messages for the next era which are kept from a single peer.
+    pub max_early_messages_per_peer: usize,
+    /// The maximum number of vertices from a single peer kept in the synchronizer's queues.
+    /// Peers exceeding it are disconnected.
+    pub max_pending_vertices_per_peer: u64,
+    /// The maximum total serialized size of the vertices kept in the synchronizer's queues. If it
+    /// is exceeded, the oldest vertices are evicted.
+    pub max_pending_vertices_bytes: u64,
+    /// Overrides the chainspec's finality threshold fraction for this node's finality detector.
+    /// A lower value lets blocks be considered finalized sooner while tolerating less faulty
+    /// weight, a higher one is more conservative.
+    #[serde(default)]
+    #[data_size(skip)]
+    pub finality_threshold_fraction: Option<Ratio<u64>>,
+    /// Additional finality threshold fractions. Whenever a block is finalized with respect to one
+    /// of them, this is announced.
+    #[serde(default)]
+    #[data_size(skip)]
+    pub finality_tiers: Vec<Ratio<u64>>,
     pub round_success_meter: RSMConfig,
 }
 
@@ -38,8 +70,17 @@ impl Default for Config {
             standstill_timeout: "1min".parse().unwrap(),
             log_participation_interval: "10sec".parse().unwrap(),
             log_unit_sizes: false,
+            batch_signature_verification: false,
             max_execution_delay: 3,
             max_requests_for_vertex: 5,
+            max_requested_dependencies: 20,
+            evidence_request_cooldown: "5sec".parse().unwrap(),
+            max_early_messages_per_era: 1000,
+            max_early_messages_per_peer: 100,
+            max_pending_vertices_per_peer: 10_000,
+            max_pending_vertices_bytes: 100_000_000,
+            finality_threshold_fraction: None,
+            finality_tiers: Vec::new(),
             round_success_meter: RSMConfig::default(),
         }
     }
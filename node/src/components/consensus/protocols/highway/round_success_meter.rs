@@ -158,14 +158,24 @@ impl<C: Context> RoundSuccessMeter<C> {
     }
 
     /// Returns an instance of `Self` for the new era: resetting the counters where appropriate.
-    pub fn next_era(&self, timestamp: Timestamp) -> Self {
+    ///
+    /// The new era's round exponent range may differ from the current one, e.g. after an upgrade.
+    /// If the current round exponent is outside of it, it is clamped to the range and the rounds
+    /// counted so far are discarded.
+    pub fn next_era(&self, timestamp: Timestamp, min_round_exp: u8, max_round_exp: u8) -> Self {
+        let current_round_exp = self.current_round_exp.clamp(min_round_exp, max_round_exp);
+        let rounds = if current_round_exp == self.current_round_exp {
+            self.rounds.clone()
+        } else {
+            VecDeque::with_capacity(self.config.num_rounds_to_consider as usize)
+        };
         Self {
-            rounds: self.rounds.clone(),
-            current_round_id: state::round_id(timestamp, self.current_round_exp),
+            rounds,
+            current_round_id: state::round_id(timestamp, current_round_exp),
             proposals: Default::default(),
-            min_round_exp: self.min_round_exp,
-            max_round_exp: self.max_round_exp,
-            current_round_exp: self.current_round_exp,
+            min_round_exp,
+            max_round_exp,
+            current_round_exp,
             config: self.config,
         }
     }
@@ -321,4 +331,35 @@ mod tests {
         }
         assert_eq!(round_success_meter.new_exponent(), TEST_MIN_ROUND_EXP);
     }
+
+    #[test]
+    fn next_era_clamps_exponent_to_new_range() {
+        let mut round_success_meter: super::RoundSuccessMeter<ClContext> =
+            super::RoundSuccessMeter::new(
+                TEST_MAX_ROUND_EXP,
+                TEST_MIN_ROUND_EXP,
+                TEST_MAX_ROUND_EXP,
+                crate::types::Timestamp::now(),
+                Config::default(),
+            );
+        round_success_meter.rounds = vec![false; MAX_FAILED_ROUNDS + 1].into();
+
+        // Within the unchanged range, the exponent and the rounds counted so far are kept.
+        let next_era = round_success_meter.next_era(
+            crate::types::Timestamp::now(),
+            TEST_MIN_ROUND_EXP,
+            TEST_MAX_ROUND_EXP,
+        );
+        assert_eq!(next_era.new_exponent(), TEST_MAX_ROUND_EXP);
+        assert_eq!(next_era.rounds.len(), MAX_FAILED_ROUNDS + 1);
+
+        // If the maximum is lowered, the exponent is clamped and the counts are reset.
+        let next_era = round_success_meter.next_era(
+            crate::types::Timestamp::now(),
+            TEST_MIN_ROUND_EXP,
+            TEST_ROUND_EXP,
+        );
+        assert_eq!(next_era.new_exponent(), TEST_ROUND_EXP);
+        assert!(next_era.rounds.is_empty());
+    }
 }
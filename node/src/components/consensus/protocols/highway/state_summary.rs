@@ -0,0 +1,108 @@
+use serde::Serialize;
+
+use crate::{
+    components::consensus::{
+        highway_core::{
+            highway::Highway,
+            state::{Fault, Observation},
+        },
+        traits::Context,
+    },
+    types::{TimeDiff, Timestamp},
+};
+
+/// The kind of fault a validator is known to have committed.
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FaultKind {
+    /// The validator was known to be faulty from the beginning of the era.
+    Banned,
+    /// We have direct evidence of an equivocation in this era.
+    Direct,
+    /// The validator is known to be faulty, but the evidence is not in this era.
+    Indirect,
+}
+
+impl FaultKind {
+    fn new<C: Context>(fault: &Fault<C>) -> Self {
+        match fault {
+            Fault::Banned => FaultKind::Banned,
+            Fault::Direct(_) => FaultKind::Direct,
+            Fault::Indirect => FaultKind::Indirect,
+        }
+    }
+}
+
+/// A validator's participation in a Highway instance.
+#[derive(Debug, Serialize)]
+#[serde(bound(serialize = "C::ValidatorId: Serialize"))]
+pub struct ValidatorSummary<C: Context> {
+    /// The validator's ID.
+    validator_id: C::ValidatorId,
+    /// The validator's weight.
+    weight: u64,
+    /// The number of units we have received from the validator.
+    unit_count: u64,
+    /// The validator's entry in our latest panorama.
+    latest_unit: Observation<C>,
+    /// The round exponent of the validator's latest unit, unless they are faulty or inactive.
+    round_exp: Option<u8>,
+    /// The time when we last received a unit or ping from the validator.
+    last_seen: Timestamp,
+    /// The validator's fault, if any.
+    fault: Option<FaultKind>,
+}
+
+/// A summary of the state of a Highway instance, to help debugging stalled eras.
+#[derive(Debug, Serialize)]
+#[serde(bound(serialize = "C::ValidatorId: Serialize"))]
+pub struct HighwayStateSummary<C: Context> {
+    /// The ID of the Highway instance.
+    instance_id: C::InstanceId,
+    /// Our next round length, if we are an active validator in this era.
+    next_round_length: Option<TimeDiff>,
+    /// The total number of units in the protocol state.
+    unit_count: u64,
+    /// The total weight of all validators.
+    total_weight: u64,
+    /// The total weight of all faulty validators.
+    faulty_weight: u64,
+    /// The participation of each validator.
+    validators: Vec<ValidatorSummary<C>>,
+}
+
+impl<C: Context> HighwayStateSummary<C> {
+    /// Creates a summary of the given Highway instance's current state.
+    pub(crate) fn new(highway: &Highway<C>) -> Self {
+        let state = highway.state();
+        let unit_counts = state.unit_counts();
+        let validators = highway
+            .validators()
+            .enumerate_ids()
+            .map(|(idx, validator_id)| {
+                let latest_unit = state.panorama()[idx].clone();
+                let round_exp = match &latest_unit {
+                    Observation::Correct(hash) => Some(state.unit(hash).round_exp),
+                    Observation::None | Observation::Faulty => None,
+                };
+                ValidatorSummary {
+                    validator_id: validator_id.clone(),
+                    weight: state.weight(idx).0,
+                    unit_count: unit_counts[idx],
+                    latest_unit,
+                    round_exp,
+                    last_seen: state.last_seen(idx),
+                    fault: state.maybe_fault(idx).map(FaultKind::new),
+                }
+            })
+            .collect();
+        HighwayStateSummary {
+            instance_id: *highway.instance_id(),
+            next_round_length: highway.next_round_length(),
+            unit_count: unit_counts.iter().sum(),
+            total_weight: state.total_weight().0,
+            faulty_weight: state.faulty_weight().0,
+            validators,
+        }
+    }
+}
@@ -6,7 +6,7 @@ use std::{
 
 use datasize::DataSize;
 use itertools::Itertools;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use crate::{
     components::consensus::{
@@ -47,19 +47,58 @@ impl<I: NodeIdT, C: Context> PendingVertices<I, C> {
         self.0.retain(|_, time_by_peer| !time_by_peer.is_empty())
     }
 
-    /// Adds a vertex, or updates its timestamp.
-    fn add(&mut self, sender: I, pvv: PreValidatedVertex<C>, time_received: Timestamp) {
-        self.0
-            .entry(pvv)
-            .or_default()
-            .entry(sender)
-            .and_modify(|timestamp| *timestamp = (*timestamp).max(time_received))
-            .or_insert(time_received);
+    /// Adds a vertex, or updates its timestamp. Returns `true` if there was no entry for this
+    /// vertex and sender yet.
+    fn add(&mut self, sender: I, pvv: PreValidatedVertex<C>, time_received: Timestamp) -> bool {
+        let time_by_sender = self.0.entry(pvv).or_default();
+        match time_by_sender.get_mut(&sender) {
+            Some(timestamp) => {
+                *timestamp = (*timestamp).max(time_received);
+                false
+            }
+            None => {
+                time_by_sender.insert(sender, time_received);
+                true
+            }
+        }
     }
 
     /// Adds a vertex, or updates its timestamp.
     fn push(&mut self, pv: PendingVertex<I, C>) {
-        self.add(pv.sender, pv.pvv, pv.time_received)
+        self.add(pv.sender, pv.pvv, pv.time_received);
+    }
+
+    /// Returns an iterator over all vertices, with their senders and the time they were received.
+    fn entries(&self) -> impl Iterator<Item = (&PreValidatedVertex<C>, &I, Timestamp)> {
+        self.0.iter().flat_map(|(pvv, time_by_sender)| {
+            time_by_sender
+                .iter()
+                .map(move |(sender, timestamp)| (pvv, sender, *timestamp))
+        })
+    }
+
+    /// Removes the entry for the given vertex and sender. Returns `true` if it existed.
+    fn remove_entry(&mut self, pvv: &PreValidatedVertex<C>, sender: &I) -> bool {
+        let (removed, is_empty) = match self.0.get_mut(pvv) {
+            None => return false,
+            Some(time_by_sender) => (
+                time_by_sender.remove(sender).is_some(),
+                time_by_sender.is_empty(),
+            ),
+        };
+        if is_empty {
+            self.0.remove(pvv);
+        }
+        removed
+    }
+
+    /// Removes all entries received from the given sender.
+    fn remove_sender(&mut self, sender: &I) {
+        for time_by_sender in self.0.values_mut() {
+            time_by_sender.remove(sender);
+        }
+        self.0
+            .retain(|_, time_by_sender| !time_by_sender.is_empty())
     }
 
     fn pop(&mut self) -> Option<PendingVertex<I, C>> {
@@ -84,6 +123,7 @@ impl<I: NodeIdT, C: Context> PendingVertices<I, C> {
     /// Drops all pending vertices other than evidence.
     pub(crate) fn retain_evidence_only(&mut self) {
         self.0.retain(|pvv, _| pvv.inner().is_evidence());
+        self.0.shrink_to_fit();
     }
 
     /// Returns number of unique vertices pending in the queue.
@@ -144,6 +184,12 @@ impl<I, C: Context> PendingVertex<I, C> {
     }
 }
 
+/// Returns the serialized size of the vertex, which is counted against the synchronizer's byte
+/// budget.
+fn vertex_size<C: Context>(pvv: &PreValidatedVertex<C>) -> u64 {
+    bincode::serialized_size(pvv.inner()).unwrap_or_default()
+}
+
 impl<I, C: Context> From<PendingVertex<I, C>> for PreValidatedVertex<C> {
     fn from(vertex: PendingVertex<I, C>) -> Self {
         vertex.pvv
@@ -175,6 +221,11 @@ where
     requests_sent: BTreeMap<Dependency<C>, HashSet<I>>,
     /// Boolean flag indicating whether we're synchronizing current era.
     pub(crate) current_era: bool,
+    /// The number of entries in the queues per sender.
+    pending_vertices_by_peer: HashMap<I, u64>,
+    /// The total serialized size of the entries in the queues, counting each vertex once per
+    /// sender.
+    pending_vertices_bytes: u64,
 }
 
 impl<I: NodeIdT, C: Context + 'static> Synchronizer<I, C> {
@@ -193,6 +244,8 @@ impl<I: NodeIdT, C: Context + 'static> Synchronizer<I, C> {
             instance_id,
             requests_sent: BTreeMap::new(),
             current_era: true,
+            pending_vertices_by_peer: HashMap::new(),
+            pending_vertices_bytes: 0,
         }
     }
 
@@ -204,6 +257,112 @@ impl<I: NodeIdT, C: Context + 'static> Synchronizer<I, C> {
         self.requests_sent.clear();
         Self::remove_expired(&mut self.vertices_to_be_added_later, oldest);
         Self::remove_expired(&mut self.vertices_awaiting_deps, oldest);
+        self.recount_pending_vertices();
+    }
+
+    /// Returns an iterator over all queues of pending vertices.
+    fn all_pending(&self) -> impl Iterator<Item = &PendingVertices<I, C>> {
+        iter::once(&self.vertices_no_deps)
+            .chain(self.vertices_awaiting_deps.values())
+            .chain(self.vertices_to_be_added_later.values())
+    }
+
+    /// Returns an iterator over all queues of pending vertices, allowing modification.
+    fn all_pending_mut(&mut self) -> impl Iterator<Item = &mut PendingVertices<I, C>> {
+        iter::once(&mut self.vertices_no_deps)
+            .chain(self.vertices_awaiting_deps.values_mut())
+            .chain(self.vertices_to_be_added_later.values_mut())
+    }
+
+    /// Recomputes the number of entries per sender and the total size of the queues.
+    ///
+    /// The counters are updated incrementally as vertices are added and removed, but some entries
+    /// are merged or dropped in bulk; this corrects any drift.
+    fn recount_pending_vertices(&mut self) {
+        let mut by_peer = HashMap::new();
+        let mut bytes = 0;
+        for (pvv, sender, _) in self.all_pending().flat_map(PendingVertices::entries) {
+            *by_peer.entry(sender.clone()).or_default() += 1;
+            bytes += vertex_size(pvv);
+        }
+        self.pending_vertices_by_peer = by_peer;
+        self.pending_vertices_bytes = bytes;
+    }
+
+    /// Updates the counters for an entry that left the queues.
+    fn untrack_pending_vertex(&mut self, sender: &I, pvv: &PreValidatedVertex<C>) {
+        if let Some(count) = self.pending_vertices_by_peer.get_mut(sender) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                self.pending_vertices_by_peer.remove(sender);
+            }
+        }
+        self.pending_vertices_bytes = self.pending_vertices_bytes.saturating_sub(vertex_size(pvv));
+    }
+
+    /// Updates the counters for a new entry, and enforces the per-peer quota and the total byte
+    /// budget.
+    ///
+    /// If the sender exceeds its quota, all its entries are dropped and the outcome asks to
+    /// disconnect from it. If the queues exceed the byte budget, the oldest entries are evicted.
+    fn track_pending_vertex(&mut self, sender: &I, size: u64) -> ProtocolOutcomes<I, C> {
+        let mut outcomes = Vec::new();
+        let count = self
+            .pending_vertices_by_peer
+            .entry(sender.clone())
+            .or_default();
+        *count += 1;
+        self.pending_vertices_bytes += size;
+        if *count > self.config.max_pending_vertices_per_peer {
+            warn!(
+                %sender, era_id = ?self.instance_id,
+                "peer exceeded the pending vertex quota; dropping its vertices"
+            );
+            for pvs in self.all_pending_mut() {
+                pvs.remove_sender(sender);
+            }
+            Self::remove_empty(&mut self.vertices_awaiting_deps);
+            Self::remove_empty(&mut self.vertices_to_be_added_later);
+            self.recount_pending_vertices();
+            outcomes.push(ProtocolOutcome::PendingVertexQuotaExceeded(sender.clone()));
+        }
+        let mut evicted = 0;
+        while self.pending_vertices_bytes > self.config.max_pending_vertices_bytes {
+            if !self.evict_oldest_pending_vertex() {
+                break;
+            }
+            evicted += 1;
+        }
+        if evicted > 0 {
+            debug!(
+                evicted, era_id = ?self.instance_id,
+                "synchronizer queues exceeded the byte budget; evicted oldest vertices"
+            );
+            outcomes.push(ProtocolOutcome::PendingVerticesEvicted(evicted));
+        }
+        outcomes
+    }
+
+    /// Removes the entry that was received the longest time ago. Returns `false` if the queues
+    /// are empty.
+    fn evict_oldest_pending_vertex(&mut self) -> bool {
+        let (pvv, sender) = match self
+            .all_pending()
+            .flat_map(PendingVertices::entries)
+            .min_by_key(|(_, _, time_received)| *time_received)
+        {
+            None => return false,
+            Some((pvv, sender, _)) => (pvv.clone(), sender.clone()),
+        };
+        for pvs in self.all_pending_mut() {
+            if pvs.remove_entry(&pvv, &sender) {
+                break;
+            }
+        }
+        Self::remove_empty(&mut self.vertices_awaiting_deps);
+        Self::remove_empty(&mut self.vertices_to_be_added_later);
+        self.untrack_pending_vertex(&sender, &pvv);
+        true
     }
 
     // Returns number of elements in the `vertices_to_be_added_later` queue.
@@ -256,11 +415,18 @@ impl<I: NodeIdT, C: Context + 'static> Synchronizer<I, C> {
         now: Timestamp,
         sender: I,
         pvv: PreValidatedVertex<C>,
-    ) {
-        self.vertices_to_be_added_later
+    ) -> ProtocolOutcomes<I, C> {
+        let size = vertex_size(&pvv);
+        let is_new = self
+            .vertices_to_be_added_later
             .entry(future_timestamp)
             .or_default()
-            .add(sender, pvv, now);
+            .add(sender.clone(), pvv, now);
+        if is_new {
+            self.track_pending_vertex(&sender, size)
+        } else {
+            Vec::new()
+        }
     }
 
     /// Schedules calls to `add_vertex` on any vertices in `vertices_to_be_added_later` which are
@@ -295,8 +461,17 @@ impl<I: NodeIdT, C: Context + 'static> Synchronizer<I, C> {
         now: Timestamp,
     ) -> ProtocolOutcomes<I, C> {
         self.update_last_seen(&pvv);
-        let pv = PendingVertex::new(sender, pvv, now);
-        self.schedule_add_vertices(iter::once(pv))
+        let size = vertex_size(&pvv);
+        let was_empty = self.vertices_no_deps.is_empty();
+        let is_new = self.vertices_no_deps.add(sender.clone(), pvv, now);
+        let mut outcomes = Vec::new();
+        if was_empty {
+            outcomes.push(ProtocolOutcome::QueueAction(ACTION_ID_VERTEX));
+        }
+        if is_new {
+            outcomes.extend(self.track_pending_vertex(&sender, size));
+        }
+        outcomes
     }
 
     fn update_last_seen(&mut self, pvv: &PreValidatedVertex<C>) {
@@ -330,10 +505,56 @@ impl<I: NodeIdT, C: Context + 'static> Synchronizer<I, C> {
     /// Pops and returns the next entry from `vertices_to_be_added` that is not yet in the protocol
     /// state. Also returns a `ProtocolOutcome` that schedules the next action to add a vertex,
     /// unless the queue is empty, and `ProtocolOutcome`s to request missing dependencies.
+    ///
+    /// Missing dependencies are requested in batches: all dependencies that have to be requested
+    /// from the same peer are sent in as few messages as possible.
     pub(crate) fn pop_vertex_to_add(
         &mut self,
         highway: &Highway<C>,
         pending_values: &HashMap<ProposedBlock<C>, HashSet<(ValidVertex<C>, I)>>,
+    ) -> (Option<PendingVertex<I, C>>, ProtocolOutcomes<I, C>) {
+        let mut requests = Vec::new();
+        let (maybe_pv, mut outcomes) =
+            self.next_vertex_to_add(highway, pending_values, &mut requests);
+        for (peer, deps) in requests {
+            outcomes.extend(self.request_dependencies(peer, deps));
+        }
+        (maybe_pv, outcomes)
+    }
+
+    /// Returns outcomes requesting the given dependencies from `peer`, using at most
+    /// `max_requested_dependencies` dependencies per message.
+    pub(crate) fn request_dependencies(
+        &self,
+        peer: I,
+        deps: Vec<Dependency<C>>,
+    ) -> ProtocolOutcomes<I, C> {
+        let max_deps = self.max_requested_dependencies();
+        deps.chunks(max_deps)
+            .map(|chunk| {
+                let msg = match chunk {
+                    [dep] => HighwayMessage::RequestDependency(dep.clone()),
+                    deps => HighwayMessage::RequestDependencies(deps.to_vec()),
+                };
+                ProtocolOutcome::CreatedTargetedMessage(msg.serialize(), peer.clone())
+            })
+            .collect()
+    }
+
+    /// Returns the maximum number of dependencies requested from or served to a peer in a single
+    /// message.
+    pub(crate) fn max_requested_dependencies(&self) -> usize {
+        self.config.max_requested_dependencies.max(1)
+    }
+
+    /// Pops and returns the next vertex that can be added, like `pop_vertex_to_add`, but collects
+    /// the missing dependencies per peer in `requests` instead of requesting them right away.
+    #[allow(clippy::type_complexity)]
+    fn next_vertex_to_add(
+        &mut self,
+        highway: &Highway<C>,
+        pending_values: &HashMap<ProposedBlock<C>, HashSet<(ValidVertex<C>, I)>>,
+        requests: &mut Vec<(I, Vec<Dependency<C>>)>,
     ) -> (Option<PendingVertex<I, C>>, ProtocolOutcomes<I, C>) {
         let mut outcomes = Vec::new();
         // Get the next vertex to be added; skip the ones that are already in the protocol state,
@@ -341,7 +562,10 @@ impl<I: NodeIdT, C: Context + 'static> Synchronizer<I, C> {
         loop {
             let pv = match self.vertices_no_deps.pop() {
                 None => return (None, outcomes),
-                Some(pv) if highway.has_vertex(pv.vertex()) => continue,
+                Some(pv) if highway.has_vertex(pv.vertex()) => {
+                    self.untrack_pending_vertex(pv.sender(), pv.pvv());
+                    continue;
+                }
                 Some(pv) => pv,
             };
             if let Some(dep) = highway.missing_dependency(pv.pvv()) {
@@ -411,8 +635,10 @@ impl<I: NodeIdT, C: Context + 'static> Synchronizer<I, C> {
                 }
                 // Otherwise request the missing dependency from the sender.
                 info!(dependency = ?transitive_dependency, %sender, "requesting dependency");
-                let ser_msg = HighwayMessage::RequestDependency(transitive_dependency).serialize();
-                outcomes.push(ProtocolOutcome::CreatedTargetedMessage(ser_msg, sender));
+                match requests.iter_mut().find(|(peer, _)| *peer == sender) {
+                    Some((_, deps)) => deps.push(transitive_dependency),
+                    None => requests.push((sender, vec![transitive_dependency])),
+                }
                 continue;
             }
             // We found the next vertex to add.
@@ -420,6 +646,7 @@ impl<I: NodeIdT, C: Context + 'static> Synchronizer<I, C> {
                 // There are still vertices in the queue: schedule next call.
                 outcomes.push(ProtocolOutcome::QueueAction(ACTION_ID_VERTEX));
             }
+            self.untrack_pending_vertex(pv.sender(), pv.pvv());
             return (Some(pv), outcomes);
         }
     }
@@ -483,15 +710,20 @@ impl<I: NodeIdT, C: Context + 'static> Synchronizer<I, C> {
             vertices = new_vertices;
             senders.extend(new_senders);
         }
+        self.recount_pending_vertices();
         senders
     }
 
     /// Drops all pending vertices other than evidence.
     pub(crate) fn retain_evidence_only(&mut self) {
-        self.vertices_awaiting_deps.clear();
-        self.vertices_to_be_added_later.clear();
+        self.vertices_awaiting_deps = Default::default();
+        self.vertices_to_be_added_later = Default::default();
         self.vertices_no_deps.retain_evidence_only();
-        self.requests_sent.clear();
+        self.requests_sent = Default::default();
+        self.oldest_seen_panorama = iter::repeat(None)
+            .take(self.oldest_seen_panorama.len())
+            .collect();
+        self.recount_pending_vertices();
     }
 
     /// Schedules vertices to be added to the protocol state.
@@ -537,6 +769,11 @@ impl<I: NodeIdT, C: Context + 'static> Synchronizer<I, C> {
         for pvs in map.values_mut() {
             pvs.remove_expired(oldest);
         }
+        Self::remove_empty(map);
+    }
+
+    /// Removes all empty entries from a `BTreeMap` of `Vec`s.
+    fn remove_empty<T: Ord + Clone>(map: &mut BTreeMap<T, PendingVertices<I, C>>) {
         let keys = map
             .iter()
             .filter(|(_, pvs)| pvs.is_empty())
@@ -325,6 +325,136 @@ fn transitive_proposal_dependency() {
     assert!(outcomes.is_empty())
 }
 
+#[test]
+fn batch_dependency_requests_per_peer() {
+    let params = test_params(0);
+    // A Highway and state instances that are used to create PreValidatedVertex instances below.
+
+    let mut state = State::new(WEIGHTS, params.clone(), vec![]);
+    let util_highway =
+        Highway::<TestContext>::new(TEST_INSTANCE_ID, test_validators(), params.clone());
+
+    // We use round exponent 4u8, so a round is 0x10 ms. With seed 0, Carol is the first leader.
+    //
+    // time:  0x00 0x0A 0x1A 0x2A
+    //
+    // Carol   c0 — c1
+    //
+    // Bob               b0 — b1
+
+    let c0 = add_unit!(state, CAROL, 0x00, 4u8, 0xA; N, N, N).unwrap();
+    let c1 = add_unit!(state, CAROL, 0x0A, 4u8, None; N, N, c0).unwrap();
+    let b0 = add_unit!(state, BOB, 0x1A, 4u8, None; N, N, N).unwrap();
+    let b1 = add_unit!(state, BOB, 0x2A, 4u8, None; N, b0, N).unwrap();
+
+    // Returns the WireUnit with the specified hash.
+    let unit = |hash: u64| Vertex::Unit(state.wire_unit(&hash, TEST_INSTANCE_ID).unwrap());
+    // Returns the PreValidatedVertex with the specified hash.
+    let pvv = |hash: u64| util_highway.pre_validate_vertex(unit(hash)).unwrap();
+
+    let peer0 = NodeId(0);
+
+    let mut sync = Synchronizer::<NodeId, TestContext>::new(
+        HighwayConfig {
+            pending_vertex_timeout: 0x20.into(),
+            ..Default::default()
+        },
+        WEIGHTS.len(),
+        TEST_INSTANCE_ID,
+    );
+
+    let highway = Highway::<TestContext>::new(TEST_INSTANCE_ID, test_validators(), params);
+    let now = 0x30.into();
+
+    let _ = sync.schedule_add_vertex(peer0, pvv(c1), now);
+    let _ = sync.schedule_add_vertex(peer0, pvv(b1), now);
+    // Neither `c1` nor `b1` can be added yet. Both missing dependencies are requested from the
+    // same peer, so they must be sent in a single message.
+    let (pv, outcomes) = sync.pop_vertex_to_add(&highway, &Default::default());
+    assert!(pv.is_none());
+    match unwrap_single(outcomes) {
+        ProtocolOutcome::CreatedTargetedMessage(msg, peer) => {
+            assert_eq!(peer, peer0);
+            match bincode::deserialize(msg.as_slice()).expect("deserialization to pass") {
+                HighwayMessage::<TestContext>::RequestDependencies(deps) => {
+                    let deps: BTreeSet<_> = deps.into_iter().collect();
+                    let expected: BTreeSet<_> = vec![Dependency::Unit(c0), Dependency::Unit(b0)]
+                        .into_iter()
+                        .collect();
+                    assert_eq!(deps, expected);
+                }
+                other => panic!("unexpected message: {:?}", other),
+            }
+        }
+        other => panic!("unexpected outcome: {:?}", other),
+    }
+}
+
+#[test]
+fn enforce_pending_vertex_quotas() {
+    let params = test_params(0);
+    let mut state = State::new(WEIGHTS, params.clone(), vec![]);
+
+    // Carol   c0 — c1 — c2
+    //            \
+    // Bob          — b0
+    let c0 = add_unit!(state, CAROL, 0x00, 4u8, 0xA; N, N, N).unwrap();
+    let c1 = add_unit!(state, CAROL, 0x0A, 4u8, None; N, N, c0).unwrap();
+    let c2 = add_unit!(state, CAROL, 0x1A, 4u8, None; N, N, c1).unwrap();
+    let b0 = add_unit!(state, BOB, 0x2A, 4u8, None; N, N, c0).unwrap();
+
+    let util_highway =
+        Highway::<TestContext>::new(TEST_INSTANCE_ID, test_validators(), params.clone());
+    let unit = |hash: u64| Vertex::Unit(state.wire_unit(&hash, TEST_INSTANCE_ID).unwrap());
+    let pvv = |hash: u64| util_highway.pre_validate_vertex(unit(hash)).unwrap();
+
+    let peer0 = NodeId(0);
+    let peer1 = NodeId(1);
+
+    // A peer sending more vertices than its quota gets all of them dropped and is disconnected.
+    let mut sync = Synchronizer::<NodeId, TestContext>::new(
+        HighwayConfig {
+            max_pending_vertices_per_peer: 2,
+            ..Default::default()
+        },
+        WEIGHTS.len(),
+        TEST_INSTANCE_ID,
+    );
+    let now = 0x20.into();
+    sync.schedule_add_vertex(peer0, pvv(c2), now);
+    sync.schedule_add_vertex(peer1, pvv(c2), now);
+    assert!(sync.schedule_add_vertex(peer0, pvv(c1), now).is_empty());
+    let outcomes = sync.schedule_add_vertex(peer0, pvv(b0), now);
+    assert!(
+        matches!(*outcomes, [ProtocolOutcome::PendingVertexQuotaExceeded(peer)] if *peer == peer0),
+        "unexpected outcomes: {:?}",
+        outcomes
+    );
+    // Only the vertex from the other peer remains.
+    assert_eq!(1, sync.vertices_no_deps.len());
+    assert!(!sync.pending_vertices_by_peer.contains_key(&peer0));
+
+    // If the queues exceed the byte budget, the oldest entries are evicted.
+    let mut sync = Synchronizer::<NodeId, TestContext>::new(
+        HighwayConfig {
+            max_pending_vertices_bytes: vertex_size(&pvv(c1)) + vertex_size(&pvv(b0)),
+            ..Default::default()
+        },
+        WEIGHTS.len(),
+        TEST_INSTANCE_ID,
+    );
+    sync.schedule_add_vertex(peer0, pvv(c2), 0x20.into());
+    sync.schedule_add_vertex(peer1, pvv(c1), 0x21.into());
+    let outcomes = sync.schedule_add_vertex(peer1, pvv(b0), 0x22.into());
+    assert!(
+        matches!(*outcomes, [ProtocolOutcome::PendingVerticesEvicted(1)]),
+        "unexpected outcomes: {:?}",
+        outcomes
+    );
+    assert_eq!(2, sync.vertices_no_deps.len());
+    assert!(!sync.pending_vertices_by_peer.contains_key(&peer0));
+}
+
 fn unwrap_single<T: Debug>(vec: Vec<T>) -> T {
     assert_eq!(
         vec.len(),
@@ -1,31 +1,38 @@
-use std::{collections::BTreeSet, sync::Arc};
+use std::{collections::BTreeSet, iter, sync::Arc};
 
 use datasize::DataSize;
 use derive_more::Display;
+use num_rational::Ratio;
 
-use casper_types::{PublicKey, U512};
+use casper_types::{EraId, PublicKey, U512};
 
 use crate::{
     components::consensus::{
         cl_context::{ClContext, Keypair},
-        config::Config,
+        config::{Config, ProtocolConfig},
         consensus_protocol::{ConsensusProtocol, ProtocolOutcome},
         highway_core::{
             highway::{SignedWireUnit, Vertex, WireUnit},
             highway_testing,
-            state::{self, tests::ALICE, Observation, Panorama},
+            state::{
+                self,
+                tests::{ALICE, BOB},
+                Fault, Observation, Panorama, Weight,
+            },
             validators::ValidatorIndex,
             State,
         },
         protocols::highway::{
-            config::Config as HighwayConfig, HighwayMessage, ACTION_ID_VERTEX,
-            TIMER_ID_STANDSTILL_ALERT,
+            config::Config as HighwayConfig, ftt_for_fraction, HighwayMessage,
+            ACTION_ID_VERIFY_SIGNATURES, ACTION_ID_VERTEX, TIMER_ID_STANDSTILL_ALERT,
+        },
+        tests::utils::{
+            new_test_chainspec, ALICE_PUBLIC_KEY, ALICE_SECRET_KEY, BOB_PRIVATE_KEY, BOB_PUBLIC_KEY,
         },
-        tests::utils::{new_test_chainspec, ALICE_PUBLIC_KEY, ALICE_SECRET_KEY, BOB_PUBLIC_KEY},
         traits::Context,
         HighwayProtocol,
     },
-    types::{BlockPayload, TimeDiff, Timestamp},
+    types::{chainspec::BannedValidators, BlockPayload, TimeDiff, Timestamp},
 };
 
 #[derive(DataSize, Debug, Ord, PartialOrd, Copy, Clone, Display, Hash, Eq, PartialEq)]
@@ -56,10 +63,33 @@ where
 const INSTANCE_ID_DATA: &[u8; 1] = &[123u8; 1];
 const STANDSTILL_TIMEOUT: &str = "1min";
 
+fn new_test_highway_config() -> HighwayConfig {
+    HighwayConfig {
+        pending_vertex_timeout: "1min".parse().unwrap(),
+        standstill_timeout: STANDSTILL_TIMEOUT.parse().unwrap(),
+        log_participation_interval: "10sec".parse().unwrap(),
+        max_execution_delay: 3,
+        ..HighwayConfig::default()
+    }
+}
+
 pub(crate) fn new_test_highway_protocol<I1, I2, T>(
     weights: I1,
     init_slashed: I2,
 ) -> Box<dyn ConsensusProtocol<NodeId, ClContext>>
+where
+    I1: IntoIterator<Item = (PublicKey, T)>,
+    I2: IntoIterator<Item = PublicKey>,
+    T: Into<U512>,
+{
+    new_test_highway_protocol_with_config(weights, init_slashed, new_test_highway_config())
+}
+
+fn new_test_highway_protocol_with_config<I1, I2, T>(
+    weights: I1,
+    init_slashed: I2,
+    highway_config: HighwayConfig,
+) -> Box<dyn ConsensusProtocol<NodeId, ClContext>>
 where
     I1: IntoIterator<Item = (PublicKey, T)>,
     I2: IntoIterator<Item = PublicKey>,
@@ -72,13 +102,9 @@ where
     let chainspec = new_test_chainspec(weights.clone());
     let config = Config {
         secret_key_path: Default::default(),
-        highway: HighwayConfig {
-            pending_vertex_timeout: "1min".parse().unwrap(),
-            standstill_timeout: STANDSTILL_TIMEOUT.parse().unwrap(),
-            log_participation_interval: "10sec".parse().unwrap(),
-            max_execution_delay: 3,
-            ..HighwayConfig::default()
-        },
+        observer_mode_when_unbonded: false,
+        gossip_fanout: 0,
+        highway: highway_config,
     };
     // Timestamp of the genesis era start and test start.
     let start_timestamp: Timestamp = 0.into();
@@ -245,6 +271,91 @@ fn send_a_valid_wire_unit() {
     );
 }
 
+#[test]
+fn batch_verify_unit_signatures() {
+    let validators = vec![
+        (ALICE_PUBLIC_KEY.clone(), 100),
+        (BOB_PUBLIC_KEY.clone(), 100),
+    ];
+    let state: State<ClContext> = new_test_state(validators.iter().map(|(_pk, w)| *w), 0);
+    let now = Timestamp::zero();
+    let alice_keypair: Keypair = Keypair::from(Arc::new(ALICE_SECRET_KEY.clone()));
+    let bob_keypair: Keypair = Keypair::from(Arc::new(BOB_PRIVATE_KEY.clone()));
+    let new_unit_msg = |creator: ValidatorIndex, keypair: &Keypair| {
+        let panorama: Panorama<ClContext> = Panorama::from(vec![N, N]);
+        let wunit: WireUnit<ClContext> = WireUnit {
+            seq_number: panorama.next_seq_num(&state, creator),
+            panorama,
+            creator,
+            instance_id: ClContext::hash(INSTANCE_ID_DATA),
+            value: Some(Arc::new(BlockPayload::new(vec![], vec![], vec![], false))),
+            timestamp: now,
+            round_exp: 14,
+            endorsed: BTreeSet::new(),
+        };
+        let highway_message: HighwayMessage<ClContext> = HighwayMessage::NewVertex(Vertex::Unit(
+            SignedWireUnit::new(wunit.into_hashed(), keypair),
+        ));
+        bincode::serialize(&highway_message).unwrap()
+    };
+    let alice_msg = new_unit_msg(ALICE, &alice_keypair);
+    // Bob's unit, but signed by Alice.
+    let forged_bob_msg = new_unit_msg(BOB, &alice_keypair);
+    let bob_msg = new_unit_msg(BOB, &bob_keypair);
+
+    let highway_config = HighwayConfig {
+        batch_signature_verification: true,
+        ..new_test_highway_config()
+    };
+    let mut highway_protocol =
+        new_test_highway_protocol_with_config(validators, vec![], highway_config);
+    let sender = NodeId(123);
+
+    // Only the first incoming unit schedules the verification.
+    let outcomes = highway_protocol.handle_message(sender, alice_msg, now);
+    assert!(
+        matches!(
+            &*outcomes,
+            [ProtocolOutcome::QueueAction(ACTION_ID_VERIFY_SIGNATURES)]
+        ),
+        "Unexpected outcomes: {:?}",
+        outcomes
+    );
+    let outcomes = highway_protocol.handle_message(sender, forged_bob_msg.clone(), now);
+    assert!(outcomes.is_empty(), "Unexpected outcomes: {:?}", outcomes);
+
+    // The batch is invalid, but only the unit with the wrong signature is rejected.
+    let mut outcomes = highway_protocol.handle_action(ACTION_ID_VERIFY_SIGNATURES, now);
+    let mut invalid_msgs = vec![];
+    while let Some(outcome) = outcomes.pop() {
+        match outcome {
+            ProtocolOutcome::QueueAction(ACTION_ID_VERTEX) => {
+                outcomes.extend(highway_protocol.handle_action(ACTION_ID_VERTEX, now))
+            }
+            ProtocolOutcome::InvalidIncomingMessage(msg, _, _) => invalid_msgs.push(msg),
+            _ => (),
+        }
+    }
+    assert_eq!(vec![forged_bob_msg], invalid_msgs);
+
+    // Bob's correctly signed unit is accepted.
+    let outcomes = highway_protocol.handle_message(sender, bob_msg, now);
+    assert!(
+        matches!(
+            &*outcomes,
+            [ProtocolOutcome::QueueAction(ACTION_ID_VERIFY_SIGNATURES)]
+        ),
+        "Unexpected outcomes: {:?}",
+        outcomes
+    );
+    let outcomes = highway_protocol.handle_action(ACTION_ID_VERIFY_SIGNATURES, now);
+    assert!(
+        matches!(&*outcomes, [ProtocolOutcome::QueueAction(ACTION_ID_VERTEX)]),
+        "Unexpected outcomes: {:?}",
+        outcomes
+    );
+}
+
 #[test]
 fn detect_doppelganger() {
     let creator: ValidatorIndex = ALICE;
@@ -294,3 +405,50 @@ fn detect_doppelganger() {
     }
     panic!("failed to return DoppelgangerDetected effect");
 }
+
+#[test]
+fn validators_banned_in_chainspec_are_faulty_from_era_start() {
+    let weights = vec![
+        (ALICE_PUBLIC_KEY.clone(), 100),
+        (BOB_PUBLIC_KEY.clone(), 10),
+    ];
+    let mut chainspec = new_test_chainspec(weights.clone());
+    chainspec.protocol_config.banned_validators = vec![BannedValidators {
+        era_id: EraId::new(0),
+        public_keys: iter::once(BOB_PUBLIC_KEY.clone()).collect(),
+    }];
+    let protocol_config = ProtocolConfig::from(&chainspec);
+    assert_eq!(
+        protocol_config.banned_validators_in(EraId::new(1)).count(),
+        0
+    );
+
+    let banned = protocol_config.banned_validators_in(EraId::new(0)).cloned();
+    let hw_proto = new_test_highway_protocol(weights, banned);
+    let highway_protocol = hw_proto
+        .as_any()
+        .downcast_ref::<HighwayProtocol<NodeId, ClContext>>()
+        .expect("should be a Highway instance");
+    let highway = &highway_protocol.highway;
+    let alice_idx = highway.validators().get_index(&*ALICE_PUBLIC_KEY).unwrap();
+    let bob_idx = highway.validators().get_index(&*BOB_PUBLIC_KEY).unwrap();
+    assert!(highway.state().maybe_fault(alice_idx).is_none());
+    assert!(matches!(
+        highway.state().maybe_fault(bob_idx),
+        Some(Fault::Banned)
+    ));
+    assert_eq!(highway.state().faulty_weight(), Weight(10));
+}
+
+#[test]
+fn ftt_for_fraction_rejects_thresholds_outside_unit_interval() {
+    let total_weight = Weight(300);
+    assert_eq!(
+        ftt_for_fraction(total_weight, Ratio::new(1, 3)),
+        Some(Weight(100))
+    );
+    assert_eq!(ftt_for_fraction(total_weight, Ratio::new(0, 1)), None);
+    assert_eq!(ftt_for_fraction(total_weight, Ratio::new(1, 1)), None);
+    // A fraction rounding down to zero weight can't be used as a threshold either.
+    assert_eq!(ftt_for_fraction(total_weight, Ratio::new(1, 1000)), None);
+}
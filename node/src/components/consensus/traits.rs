@@ -83,4 +83,13 @@ pub trait Context: Clone + DataSize + Debug + Eq + Ord + Hash + Send {
         public_key: &Self::ValidatorId,
         signature: &<Self::ValidatorSecret as ValidatorSecret>::Signature,
     ) -> bool;
+
+    /// Returns `true` if all of the given signatures are valid.
+    ///
+    /// Implementations can override this to verify the whole batch at once, if that is faster.
+    fn verify_signatures(items: &[(&Self::Hash, &Self::ValidatorId, &Self::Signature)]) -> bool {
+        items.iter().all(|(hash, public_key, signature)| {
+            Self::verify_signature(hash, public_key, signature)
+        })
+    }
 }
@@ -1,16 +1,24 @@
 //! Contract Runtime component.
+mod block_summary;
 mod config;
+mod execution_queue;
 mod operations;
+#[cfg(test)]
+mod tests;
 mod types;
 
 use std::{
     collections::{BTreeMap, HashMap, VecDeque},
     fmt::{self, Debug, Formatter},
+    fs::File,
+    io::{self, BufReader, BufWriter},
+    path::Path,
     sync::Arc,
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 pub use config::Config;
+pub use execution_queue::QueryError;
 use smallvec::SmallVec;
 
 pub use types::{EraValidatorsRequest, ValidatorWeightsByEraIdRequest};
@@ -18,38 +26,55 @@ pub use types::{EraValidatorsRequest, ValidatorWeightsByEraIdRequest};
 use datasize::DataSize;
 use derive_more::From;
 use lmdb::DatabaseFlags;
-use prometheus::{self, Histogram, HistogramOpts, IntGauge, Registry};
+use prometheus::{self, Histogram, HistogramOpts, IntCounter, IntGauge, Registry};
 use thiserror::Error;
-use tracing::{debug, error, trace};
+use tokio::task;
+use tracing::{debug, error, trace, warn};
+
+use block_summary::{BlockSummary, BlockSummaryLog};
 
 use casper_execution_engine::{
     core::engine_state::{
-        self, genesis::GenesisResult, step::EvictItem, DeployItem, EngineConfig, EngineState,
-        ExecuteRequest, GetEraValidatorsError, GetEraValidatorsRequest, RewardItem, SlashItem,
-        StepRequest, StepResult,
+        self, genesis::GenesisResult, step::EvictItem, ContractEvent, DeployItem, EngineConfig,
+        EngineState, ExecuteRequest, ExportStateResult, GetEraValidatorsError,
+        GetEraValidatorsRequest, RewardItem, SlashItem, SnapshotError, StepRequest, StepResult,
+    },
+    shared::{
+        additive_map::AdditiveMap,
+        newtypes::{Blake2bHash, CorrelationId},
+        stored_value::StoredValue,
+        transform::Transform,
     },
-    shared::newtypes::{Blake2bHash, CorrelationId},
     storage::{
-        error::lmdb::Error as StorageLmdbError, global_state::lmdb::LmdbGlobalState,
+        error::lmdb::Error as StorageLmdbError,
+        global_state::{lmdb::LmdbGlobalState, CommitResult},
         protocol_data_store::lmdb::LmdbProtocolDataStore,
-        transaction_source::lmdb::LmdbEnvironment, trie_store::lmdb::LmdbTrieStore,
+        transaction_source::lmdb::LmdbEnvironment,
+        trie_store::lmdb::LmdbTrieStore,
     },
 };
 use casper_types::{
-    system::auction::ValidatorWeights, ExecutionResult, ProtocolVersion, PublicKey, U512,
+    system::auction::ValidatorWeights, EraId, ExecutionResult, JournalEntry, Key, ProtocolVersion,
+    PublicKey, U512,
 };
 
+use execution_queue::{ExecutionQueue, ExecutionQueueMetrics};
+
 use crate::{
-    components::Component,
+    components::{fetcher::FetchResult, Component},
     crypto::hash::Digest,
     effect::{
         announcements::ContractRuntimeAnnouncement,
-        requests::{ConsensusRequest, ContractRuntimeRequest, LinearChainRequest, StorageRequest},
+        requests::{
+            ConsensusRequest, ContractRuntimeRequest, FetcherRequest, LinearChainRequest,
+            NetworkInfoRequest, StorageRequest,
+        },
         EffectBuilder, EffectExt, Effects,
     },
     types::{
-        Block, BlockHash, BlockHeader, Chainspec, Deploy, DeployHash, DeployHeader, FinalizedBlock,
-        NodeId,
+        chainspec::DeployConfig, is_in_canonical_order, Block, BlockHash, Chainspec,
+        ContractEventRecord, Deploy, DeployHash, DeployHeader, EraRewards, FinalizedBlock, NodeId,
+        CHAINSPEC_KEY,
     },
     utils::WithDir,
     NodeRng, StorageConfig,
@@ -89,7 +114,7 @@ pub enum ContractRuntimeResult {
         deploys: VecDeque<Deploy>,
         /// Parent of the newly finalized block.
         /// If it's the first block after Genesis then `parent` is `None`.
-        parent: Option<(BlockHash, Digest, Digest)>,
+        parent: Option<(BlockHash, Digest, Digest, u64)>,
     },
     /// The result of running the step on a switch block.
     RunStepResult {
@@ -110,6 +135,8 @@ pub trait ReactorEventT:
     + From<ContractRuntimeRequest>
     + From<ContractRuntimeAnnouncement>
     + From<ConsensusRequest>
+    + From<FetcherRequest<NodeId, Deploy>>
+    + From<NetworkInfoRequest<NodeId>>
     + Send
 {
 }
@@ -121,6 +148,8 @@ impl<REv> ReactorEventT for REv where
         + From<ContractRuntimeRequest>
         + From<ContractRuntimeAnnouncement>
         + From<ConsensusRequest>
+        + From<FetcherRequest<NodeId, Deploy>>
+        + From<NetworkInfoRequest<NodeId>>
         + Send
 {
 }
@@ -130,6 +159,8 @@ struct ExecutedBlockSummary {
     hash: BlockHash,
     state_root_hash: Digest,
     accumulated_seed: Digest,
+    /// The gas price floor applicable to the child of the executed block.
+    next_gas_price_floor: u64,
 }
 
 type BlockHeight = u64;
@@ -142,6 +173,9 @@ pub struct ContractRuntime {
     metrics: Arc<ContractRuntimeMetrics>,
 
     protocol_version: ProtocolVersion,
+    /// The name of the network, which all executed deploys must have been created for.
+    chain_name: String,
+    deploy_config: DeployConfig,
 
     /// A mapping from block height to executed block's ID and post-state hash, to allow
     /// identification of a parent block's details once a finalized block has been executed.
@@ -152,6 +186,16 @@ pub struct ContractRuntime {
 
     /// Finalized blocks waiting for their pre-state hash to start executing.
     exec_queue: HashMap<BlockHeight, (FinalizedBlock, VecDeque<Deploy>)>,
+
+    /// Bounded queue through which API-driven requests are executed.
+    query_queue: ExecutionQueue,
+
+    /// The file to which summaries of executed blocks are appended, if configured.
+    #[data_size(skip)]
+    block_summary_log: Option<BlockSummaryLog>,
+
+    /// Whether the journals of global state operations of executed deploys are stored.
+    record_execution_journal: bool,
 }
 
 impl Debug for ContractRuntime {
@@ -176,6 +220,16 @@ pub struct ContractRuntimeMetrics {
     missing_trie_keys: Histogram,
     put_trie: Histogram,
     read_trie: Histogram,
+    /// Total number of preprocessed wasm modules served from the cache.
+    wasm_cache_hits: IntGauge,
+    /// Total number of wasm modules which had to be preprocessed.
+    wasm_cache_misses: IntGauge,
+    /// Metrics of the queue through which API-driven requests are executed.
+    query_queue: ExecutionQueueMetrics,
+    /// Number of finalized blocks whose execution is stalled on deploys missing from storage.
+    blocks_missing_deploys: IntGauge,
+    /// Total number of deploys whose execution was aborted for exceeding the step limit.
+    execution_step_limit_exceeded: IntCounter,
     /// The current chain height.
     pub chain_height: IntGauge,
 }
@@ -221,6 +275,26 @@ const PUT_TRIE_NAME: &str = "contract_runtime_put_trie";
 const PUT_TRIE_HELP: &str = "tracking run of engine_state.put_trie in seconds.";
 const MISSING_TRIE_KEYS_NAME: &str = "contract_runtime_missing_trie_keys";
 const MISSING_TRIE_KEYS_HELP: &str = "tracking run of engine_state.missing_trie_keys in seconds.";
+const WASM_CACHE_HITS_NAME: &str = "contract_runtime_wasm_cache_hits";
+const WASM_CACHE_HITS_HELP: &str = "number of preprocessed wasm modules served from the cache.";
+const WASM_CACHE_MISSES_NAME: &str = "contract_runtime_wasm_cache_misses";
+const WASM_CACHE_MISSES_HELP: &str = "number of wasm modules preprocessed on a cache miss.";
+const QUERY_QUEUE_DEPTH_NAME: &str = "contract_runtime_query_queue_depth";
+const QUERY_QUEUE_DEPTH_HELP: &str = "number of queries waiting or executing in the query queue.";
+const QUERY_QUEUE_REJECTED_NAME: &str = "contract_runtime_query_queue_rejected";
+const QUERY_QUEUE_REJECTED_HELP: &str = "number of queries rejected due to a full query queue.";
+const QUERY_QUEUE_EXPIRED_NAME: &str = "contract_runtime_query_queue_expired";
+const QUERY_QUEUE_EXPIRED_HELP: &str =
+    "number of queries which expired before leaving the query queue.";
+const BLOCKS_MISSING_DEPLOYS_NAME: &str = "contract_runtime_blocks_missing_deploys";
+const BLOCKS_MISSING_DEPLOYS_HELP: &str =
+    "number of finalized blocks waiting for deploys missing from storage to be fetched.";
+const EXECUTION_STEP_LIMIT_EXCEEDED_NAME: &str = "contract_runtime_execution_step_limit_exceeded";
+const EXECUTION_STEP_LIMIT_EXCEEDED_HELP: &str =
+    "number of deploys whose execution was aborted for exceeding the execution step limit.";
+
+/// How long to wait before retrying to fetch deploys of a finalized block no peer could provide.
+const MISSING_DEPLOYS_RETRY_INTERVAL: Duration = Duration::from_secs(5);
 
 /// Create prometheus Histogram and register.
 fn register_histogram_metric(
@@ -244,8 +318,37 @@ impl ContractRuntimeMetrics {
     fn new(registry: &Registry) -> Result<Self, prometheus::Error> {
         let chain_height = IntGauge::new("chain_height", "current chain height")?;
         registry.register(Box::new(chain_height.clone()))?;
+        let wasm_cache_hits = IntGauge::new(WASM_CACHE_HITS_NAME, WASM_CACHE_HITS_HELP)?;
+        registry.register(Box::new(wasm_cache_hits.clone()))?;
+        let wasm_cache_misses = IntGauge::new(WASM_CACHE_MISSES_NAME, WASM_CACHE_MISSES_HELP)?;
+        registry.register(Box::new(wasm_cache_misses.clone()))?;
+        let query_queue_depth = IntGauge::new(QUERY_QUEUE_DEPTH_NAME, QUERY_QUEUE_DEPTH_HELP)?;
+        registry.register(Box::new(query_queue_depth.clone()))?;
+        let query_queue_rejected =
+            IntCounter::new(QUERY_QUEUE_REJECTED_NAME, QUERY_QUEUE_REJECTED_HELP)?;
+        registry.register(Box::new(query_queue_rejected.clone()))?;
+        let query_queue_expired =
+            IntCounter::new(QUERY_QUEUE_EXPIRED_NAME, QUERY_QUEUE_EXPIRED_HELP)?;
+        registry.register(Box::new(query_queue_expired.clone()))?;
+        let blocks_missing_deploys =
+            IntGauge::new(BLOCKS_MISSING_DEPLOYS_NAME, BLOCKS_MISSING_DEPLOYS_HELP)?;
+        registry.register(Box::new(blocks_missing_deploys.clone()))?;
+        let execution_step_limit_exceeded = IntCounter::new(
+            EXECUTION_STEP_LIMIT_EXCEEDED_NAME,
+            EXECUTION_STEP_LIMIT_EXCEEDED_HELP,
+        )?;
+        registry.register(Box::new(execution_step_limit_exceeded.clone()))?;
         Ok(ContractRuntimeMetrics {
             chain_height,
+            wasm_cache_hits,
+            wasm_cache_misses,
+            query_queue: ExecutionQueueMetrics {
+                depth: query_queue_depth,
+                rejected: query_queue_rejected,
+                expired: query_queue_expired,
+            },
+            blocks_missing_deploys,
+            execution_step_limit_exceeded,
             run_execute: register_histogram_metric(registry, RUN_EXECUTE_NAME, RUN_EXECUTE_HELP)?,
             apply_effect: register_histogram_metric(
                 registry,
@@ -321,6 +424,14 @@ where
                         let result = self.commit_genesis(chainspec);
                         responder.respond(result).ignore()
                     }
+                    ContractRuntimeRequest::CommitGenesisSnapshot {
+                        chainspec,
+                        snapshot_path,
+                        responder,
+                    } => {
+                        let result = self.commit_genesis_snapshot(chainspec, &snapshot_path);
+                        responder.respond(result).ignore()
+                    }
                     ContractRuntimeRequest::Upgrade {
                         upgrade_config,
                         responder,
@@ -348,11 +459,18 @@ where
                         trace!(?query_request, "query");
                         let engine_state = Arc::clone(&self.engine_state);
                         let metrics = Arc::clone(&self.metrics);
+                        let query_queue = self.query_queue.clone();
                         async move {
-                            let correlation_id = CorrelationId::new();
-                            let start = Instant::now();
-                            let result = engine_state.run_query(correlation_id, query_request);
-                            metrics.run_query.observe(start.elapsed().as_secs_f64());
+                            let result = query_queue
+                                .execute(move || {
+                                    let correlation_id = CorrelationId::new();
+                                    let start = Instant::now();
+                                    let result =
+                                        engine_state.run_query(correlation_id, query_request);
+                                    metrics.run_query.observe(start.elapsed().as_secs_f64());
+                                    result
+                                })
+                                .await;
                             trace!(?result, "query result");
                             responder.respond(result).await
                         }
@@ -365,15 +483,21 @@ where
                         trace!(?balance_request, "balance");
                         let engine_state = Arc::clone(&self.engine_state);
                         let metrics = Arc::clone(&self.metrics);
+                        let query_queue = self.query_queue.clone();
                         async move {
-                            let correlation_id = CorrelationId::new();
-                            let start = Instant::now();
-                            let result = engine_state.get_purse_balance(
-                                correlation_id,
-                                balance_request.state_hash(),
-                                balance_request.purse_uref(),
-                            );
-                            metrics.get_balance.observe(start.elapsed().as_secs_f64());
+                            let result = query_queue
+                                .execute(move || {
+                                    let correlation_id = CorrelationId::new();
+                                    let start = Instant::now();
+                                    let result = engine_state.get_purse_balance(
+                                        correlation_id,
+                                        balance_request.state_hash(),
+                                        balance_request.purse_uref(),
+                                    );
+                                    metrics.get_balance.observe(start.elapsed().as_secs_f64());
+                                    result
+                                })
+                                .await;
                             trace!(?result, "balance result");
                             responder.respond(result).await
                         }
@@ -486,16 +610,26 @@ where
                         let engine_state = Arc::clone(&self.engine_state);
                         let metrics = Arc::clone(&self.metrics);
                         async move {
-                            let correlation_id = CorrelationId::new();
-                            let start = Instant::now();
-                            let result = engine_state.read_trie(correlation_id, trie_key);
-                            metrics.read_trie.observe(start.elapsed().as_secs_f64());
+                            // Peers can request tries at will, so read them off the reactor's
+                            // worker threads.
+                            let result = task::spawn_blocking(move || {
+                                let correlation_id = CorrelationId::new();
+                                let start = Instant::now();
+                                let result = engine_state.read_trie(correlation_id, trie_key);
+                                metrics.read_trie.observe(start.elapsed().as_secs_f64());
+                                result
+                            })
+                            .await;
                             let result = match result {
-                                Ok(result) => result,
-                                Err(error) => {
+                                Ok(Ok(result)) => result,
+                                Ok(Err(error)) => {
                                     error!(?error, "read_trie_request");
                                     None
                                 }
+                                Err(error) => {
+                                    error!(%error, "failed to join read_trie task");
+                                    None
+                                }
                             };
                             trace!(?result, "read_trie response");
                             responder.respond(result).await
@@ -538,16 +672,42 @@ where
                         trace!(?get_bids_request, "get bids request");
                         let engine_state = Arc::clone(&self.engine_state);
                         let metrics = Arc::clone(&self.metrics);
+                        let query_queue = self.query_queue.clone();
                         async move {
-                            let correlation_id = CorrelationId::new();
-                            let start = Instant::now();
-                            let result = engine_state.get_bids(correlation_id, get_bids_request);
-                            metrics.get_bids.observe(start.elapsed().as_secs_f64());
+                            let result = query_queue
+                                .execute(move || {
+                                    let correlation_id = CorrelationId::new();
+                                    let start = Instant::now();
+                                    let result =
+                                        engine_state.get_bids(correlation_id, get_bids_request);
+                                    metrics.get_bids.observe(start.elapsed().as_secs_f64());
+                                    result
+                                })
+                                .await;
                             trace!(?result, "get bids result");
                             responder.respond(result).await
                         }
                         .ignore()
                     }
+                    ContractRuntimeRequest::DiffState {
+                        state_diff_request,
+                        responder,
+                    } => {
+                        trace!(?state_diff_request, "diff state request");
+                        let engine_state = Arc::clone(&self.engine_state);
+                        let query_queue = self.query_queue.clone();
+                        async move {
+                            let result = query_queue
+                                .execute(move || {
+                                    let correlation_id = CorrelationId::new();
+                                    engine_state.diff_state(correlation_id, state_diff_request)
+                                })
+                                .await;
+                            trace!(?result, "diff state result");
+                            responder.respond(result).await
+                        }
+                        .ignore()
+                    }
                     ContractRuntimeRequest::MissingTrieKeys {
                         trie_key,
                         responder,
@@ -591,13 +751,16 @@ where
                     parent,
                 } => {
                     trace!(parent_found = %parent.is_some(), finalized_height = %finalized_block.height(), "fetched parent");
-                    let parent_summary = parent.map(|(hash, accumulated_seed, state_root_hash)| {
-                        ExecutedBlockSummary {
-                            hash,
-                            state_root_hash,
-                            accumulated_seed,
-                        }
-                    });
+                    let parent_summary = parent.map(
+                        |(hash, accumulated_seed, state_root_hash, next_gas_price_floor)| {
+                            ExecutedBlockSummary {
+                                hash,
+                                state_root_hash,
+                                accumulated_seed,
+                                next_gas_price_floor,
+                            }
+                        },
+                    );
                     self.handle_get_parent_result(
                         effect_builder,
                         finalized_block,
@@ -615,9 +778,21 @@ where
                         }) => {
                             state.state_root_hash = post_state_hash.into();
                             let era_id = state.finalized_block.era_id();
-                            let mut effects = effect_builder
-                                .announce_step_success(era_id, execution_effect)
-                                .ignore();
+                            let mut effects = Effects::new();
+                            if let Some(era_rewards) =
+                                EraRewards::from_step_effect(era_id, &execution_effect)
+                            {
+                                effects.extend(
+                                    effect_builder
+                                        .put_era_rewards_to_storage(era_rewards)
+                                        .ignore(),
+                                );
+                            }
+                            effects.extend(
+                                effect_builder
+                                    .announce_step_success(era_id, execution_effect)
+                                    .ignore(),
+                            );
                             effects.extend(self.finalize_block_execution(
                                 effect_builder,
                                 state,
@@ -649,54 +824,135 @@ pub enum ConfigError {
     /// Error initializing metrics.
     #[error("failed to initialize metrics for contract runtime: {0}")]
     Prometheus(#[from] prometheus::Error),
+    /// Error opening the block summary log.
+    #[error("failed to open block summary log: {0}")]
+    BlockSummaryLog(io::Error),
+}
+
+/// Error exporting the global state to a snapshot file.
+#[derive(Debug, Error)]
+pub enum ExportStateError {
+    /// Error opening the global state.
+    #[error("failed to open global state: {0}")]
+    Lmdb(#[from] StorageLmdbError),
+    /// Error creating the snapshot file.
+    #[error("failed to create snapshot file: {0}")]
+    CreateFile(io::Error),
+    /// Error exporting the global state.
+    #[error("failed to export global state: {0}")]
+    Export(#[from] engine_state::Error),
+    /// The state root hash is not in the global state.
+    #[error("state root hash {0} not found in global state")]
+    RootNotFound(Digest),
+}
+
+/// Opens the global state in the storage directory.
+pub(crate) fn open_global_state(
+    storage_config: &WithDir<StorageConfig>,
+    contract_runtime_config: &Config,
+) -> Result<LmdbGlobalState, StorageLmdbError> {
+    let path = storage_config.with_dir(storage_config.value().path.clone());
+    let environment = Arc::new(LmdbEnvironment::new(
+        path.as_path(),
+        contract_runtime_config.max_global_state_size(),
+        contract_runtime_config.max_readers(),
+    )?);
+
+    let trie_store = Arc::new(LmdbTrieStore::new(
+        &environment,
+        None,
+        DatabaseFlags::empty(),
+    )?);
+
+    let protocol_data_store = Arc::new(LmdbProtocolDataStore::new(
+        &environment,
+        None,
+        DatabaseFlags::empty(),
+    )?);
+
+    LmdbGlobalState::empty(environment, trie_store, protocol_data_store)
+}
+
+/// Exports the global state under the given state root hash to a new snapshot file, from which a
+/// network can be initialized via the chainspec's `genesis_snapshot`.
+///
+/// Returns the number of exported entries.  The node should not be running while exporting.
+pub fn export_state(
+    storage_config: &WithDir<StorageConfig>,
+    contract_runtime_config: &Config,
+    state_root_hash: Digest,
+    output: &Path,
+) -> Result<u64, ExportStateError> {
+    let global_state = open_global_state(storage_config, contract_runtime_config)?;
+    let engine_state = EngineState::new(global_state, EngineConfig::default());
+    let file = File::create(output).map_err(ExportStateError::CreateFile)?;
+    match engine_state.export_state(
+        CorrelationId::new(),
+        state_root_hash.into(),
+        BufWriter::new(file),
+    )? {
+        ExportStateResult::Success { entry_count } => Ok(entry_count),
+        ExportStateResult::RootNotFound(_) => Err(ExportStateError::RootNotFound(state_root_hash)),
+    }
 }
 
 impl ContractRuntime {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         initial_state_root_hash: Digest,
-        initial_block_header: Option<&BlockHeader>,
+        initial_block: Option<&Block>,
+        genesis_height: u64,
         protocol_version: ProtocolVersion,
+        chain_name: String,
+        deploy_config: DeployConfig,
         storage_config: WithDir<StorageConfig>,
         contract_runtime_config: &Config,
         registry: &Registry,
     ) -> Result<Self, ConfigError> {
-        let initial_state = InitialState::new(initial_state_root_hash, initial_block_header);
-        let path = storage_config.with_dir(storage_config.value().path.clone());
-        let environment = Arc::new(LmdbEnvironment::new(
-            path.as_path(),
-            contract_runtime_config.max_global_state_size(),
-            contract_runtime_config.max_readers(),
-        )?);
-
-        let trie_store = Arc::new(LmdbTrieStore::new(
-            &environment,
-            None,
-            DatabaseFlags::empty(),
-        )?);
-
-        let protocol_data_store = Arc::new(LmdbProtocolDataStore::new(
-            &environment,
-            None,
-            DatabaseFlags::empty(),
-        )?);
-
-        let global_state = LmdbGlobalState::empty(environment, trie_store, protocol_data_store)?;
-        let engine_config = EngineConfig::new(contract_runtime_config.max_query_depth());
+        let initial_state = InitialState::new(
+            initial_state_root_hash,
+            initial_block,
+            genesis_height,
+            &deploy_config,
+        );
+        let global_state = open_global_state(&storage_config, contract_runtime_config)?;
+        let engine_config = EngineConfig::new(
+            contract_runtime_config.max_query_depth(),
+            deploy_config.refund_handling.refund_ratio(),
+        )
+        .with_max_execution_steps(deploy_config.max_execution_steps());
 
         let engine_state = Arc::new(EngineState::new(global_state, engine_config));
 
         let metrics = Arc::new(ContractRuntimeMetrics::new(registry)?);
+        let query_queue = ExecutionQueue::new(
+            contract_runtime_config.max_concurrent_queries(),
+            contract_runtime_config.max_queued_queries(),
+            contract_runtime_config.query_deadline(),
+            metrics.query_queue.clone(),
+        );
+        let block_summary_log = contract_runtime_config
+            .block_summary_log_path()
+            .map(|path| BlockSummaryLog::open(&storage_config.with_dir(path.to_path_buf())))
+            .transpose()
+            .map_err(ConfigError::BlockSummaryLog)?;
         Ok(ContractRuntime {
             initial_state,
             protocol_version,
+            chain_name,
+            deploy_config,
             parent_map: HashMap::new(),
             exec_queue: HashMap::new(),
+            query_queue,
             engine_state,
             metrics,
+            block_summary_log,
+            record_execution_journal: contract_runtime_config.record_execution_journal(),
         })
     }
 
-    /// Commits a genesis using a chainspec
+    /// Commits a genesis using a chainspec, then stores the chainspec itself under
+    /// `CHAINSPEC_KEY` in the resulting global state.
     fn commit_genesis(
         &self,
         chainspec: Arc<Chainspec>,
@@ -706,12 +962,76 @@ impl ContractRuntime {
         let protocol_version = chainspec.protocol_config.version;
         // Transforms a chainspec into a valid genesis config for execution engine.
         let ee_config = chainspec.as_ref().into();
-        self.engine_state.commit_genesis(
+        let genesis_result = self.engine_state.commit_genesis(
             correlation_id,
             genesis_config_hash.into(),
             protocol_version,
             &ee_config,
-        )
+        )?;
+        self.record_chainspec(correlation_id, &chainspec, genesis_result)
+    }
+
+    /// Initializes the global state from the genesis snapshot file specified in the chainspec,
+    /// instead of running the genesis installer.
+    ///
+    /// The snapshot is streamed from the file, so it is never held in memory as a whole.
+    fn commit_genesis_snapshot(
+        &self,
+        chainspec: Arc<Chainspec>,
+        snapshot_path: &Path,
+    ) -> Result<GenesisResult, engine_state::Error> {
+        let correlation_id = CorrelationId::new();
+        let snapshot = File::open(snapshot_path).map_err(SnapshotError::Io)?;
+        let genesis_snapshot = chainspec
+            .protocol_config
+            .genesis_snapshot
+            .expect("chainspec should specify a genesis snapshot");
+        let ee_config = chainspec.as_ref().into();
+        let genesis_result = self.engine_state.commit_genesis_snapshot(
+            correlation_id,
+            chainspec.protocol_config.version,
+            &ee_config,
+            BufReader::new(snapshot),
+            genesis_snapshot.state_root_hash.into(),
+        )?;
+        self.record_chainspec(correlation_id, &chainspec, genesis_result)
+    }
+
+    /// Stores the chainspec under `CHAINSPEC_KEY` in the global state resulting from genesis.
+    fn record_chainspec(
+        &self,
+        correlation_id: CorrelationId,
+        chainspec: &Chainspec,
+        genesis_result: GenesisResult,
+    ) -> Result<GenesisResult, engine_state::Error> {
+        let (post_state_hash, effect) = match genesis_result {
+            GenesisResult::Success {
+                post_state_hash,
+                effect,
+            } => (post_state_hash, effect),
+            _ => return Ok(genesis_result),
+        };
+
+        let chainspec_value = chainspec
+            .to_stored_value()
+            .map_err(|error| engine_state::Error::Bytesrepr(error.to_string()))?;
+        let mut effects = AdditiveMap::new();
+        effects.insert(*CHAINSPEC_KEY, Transform::Write(chainspec_value));
+        let result =
+            match self
+                .engine_state
+                .apply_effect(correlation_id, post_state_hash, effects)?
+            {
+                CommitResult::Success { state_root } => GenesisResult::Success {
+                    post_state_hash: state_root,
+                    effect,
+                },
+                CommitResult::RootNotFound => GenesisResult::RootNotFound,
+                CommitResult::KeyNotFound(key) => GenesisResult::KeyNotFound(key),
+                CommitResult::TypeMismatch(mismatch) => GenesisResult::TypeMismatch(mismatch),
+                CommitResult::Serialization(error) => GenesisResult::Serialization(error),
+            };
+        Ok(result)
     }
 
     /// Retrieve trie keys for the integrity check.
@@ -729,9 +1049,30 @@ impl ContractRuntime {
     pub(crate) fn set_initial_state(
         &mut self,
         initial_state_root_hash: Digest,
-        initial_block_header: Option<&BlockHeader>,
+        initial_block: Option<&Block>,
+        genesis_height: u64,
     ) {
-        self.initial_state = InitialState::new(initial_state_root_hash, initial_block_header);
+        self.initial_state = InitialState::new(
+            initial_state_root_hash,
+            initial_block,
+            genesis_height,
+            &self.deploy_config,
+        );
+    }
+
+    /// Returns the validator weights of the given era, as recorded in the auction state under the
+    /// given state root hash.
+    pub(crate) fn era_validator_weights(
+        &self,
+        state_root_hash: Digest,
+        era_id: EraId,
+        protocol_version: ProtocolVersion,
+    ) -> Result<ValidatorWeights, GetEraValidatorsError> {
+        let request = GetEraValidatorsRequest::new(state_root_hash.into(), protocol_version);
+        self.engine_state
+            .get_era_validators(CorrelationId::new(), request)?
+            .remove(&era_id)
+            .ok_or(GetEraValidatorsError::EraValidatorsMissing)
     }
 
     /// Adds the "parent map" to the instance of `ContractRuntime`.
@@ -749,6 +1090,7 @@ impl ContractRuntime {
                         hash: *block.hash(),
                         state_root_hash: *block.state_root_hash(),
                         accumulated_seed: block.header().accumulated_seed(),
+                        next_gas_price_floor: block.next_gas_price_floor(&self.deploy_config),
                     },
                 )
             })
@@ -757,6 +1099,9 @@ impl ContractRuntime {
     }
 
     /// Gets the deploy(s) of the given finalized block from storage.
+    ///
+    /// Deploys missing from storage (e.g. after a restart) are fetched from the block's proposer
+    /// and other peers, retrying until all of them are available.
     fn get_deploys<REv: ReactorEventT>(
         &mut self,
         effect_builder: EffectBuilder<REv>,
@@ -778,28 +1123,49 @@ impl ContractRuntime {
 
         let era_id = finalized_block.era_id();
         let height = finalized_block.height();
+        let proposer = finalized_block.proposer();
+        let metrics = Arc::clone(&self.metrics);
 
         // Get all deploys in order they appear in the finalized block.
-        effect_builder
-            .get_deploys_from_storage(deploy_hashes)
-            .event(move |result| {
-                Event::Result(Box::new(ContractRuntimeResult::GetDeploysResult {
-                    finalized_block,
-                    deploys: result
-                        .into_iter()
-                        // Assumes all deploys are present
-                        .map(|maybe_deploy| {
-                            maybe_deploy.unwrap_or_else(|| {
-                                panic!(
-                                "deploy for block in era={} and height={} is expected to exist \
-                                in the storage",
-                                era_id, height
-                            )
-                            })
-                        })
-                        .collect(),
-                }))
-            })
+        async move {
+            let mut maybe_deploys = effect_builder
+                .get_deploys_from_storage(deploy_hashes.clone())
+                .await;
+            let mut stalled = false;
+            while maybe_deploys.iter().any(Option::is_none) {
+                if stalled {
+                    effect_builder
+                        .set_timeout(MISSING_DEPLOYS_RETRY_INTERVAL)
+                        .await;
+                } else {
+                    stalled = true;
+                    metrics.blocks_missing_deploys.inc();
+                }
+                let missing_count = maybe_deploys.iter().filter(|d| d.is_none()).count();
+                warn!(
+                    %era_id,
+                    height,
+                    missing_count,
+                    "deploys of finalized block missing from storage, fetching them from peers"
+                );
+                let peers = fetch_peers(effect_builder, proposer.clone()).await;
+                for (deploy_hash, maybe_deploy) in deploy_hashes.iter().zip(&mut maybe_deploys) {
+                    if maybe_deploy.is_none() {
+                        *maybe_deploy = fetch_deploy(effect_builder, *deploy_hash, &peers).await;
+                    }
+                }
+            }
+            if stalled {
+                metrics.blocks_missing_deploys.dec();
+            }
+            maybe_deploys.into_iter().flatten().collect::<VecDeque<_>>()
+        }
+        .event(move |deploys| {
+            Event::Result(Box::new(ContractRuntimeResult::GetDeploysResult {
+                finalized_block,
+                deploys,
+            }))
+        })
     }
 
     /// Creates and announces the linear chain block.
@@ -819,12 +1185,37 @@ impl ContractRuntime {
         let block = self.create_block(
             state.finalized_block,
             state.state_root_hash,
+            state.gas_price_floor,
             next_era_validator_weights,
         );
 
+        let summary = BlockSummary::new(&block, state.execution_start.elapsed().into());
+        summary.log();
+        if let Some(block_summary_log) = self.block_summary_log.as_mut() {
+            if let Err(error) = block_summary_log.write(&summary) {
+                warn!(%error, "failed to write block summary log");
+            }
+        }
+
+        let block_hash = *block.hash();
+        let block_height = block.height();
+        let contract_events = state
+            .contract_events
+            .into_iter()
+            .map(|(deploy_hash, event)| {
+                ContractEventRecord::new(block_hash, block_height, deploy_hash, event)
+            })
+            .collect();
         let mut effects = effect_builder
-            .announce_linear_chain_block(block, state.execution_results)
+            .announce_linear_chain_block(block, state.execution_results, contract_events)
             .ignore();
+        if !state.execution_journals.is_empty() {
+            effects.extend(
+                effect_builder
+                    .put_execution_journals_to_storage(state.execution_journals)
+                    .ignore(),
+            );
+        }
         // If the child is already finalized, start execution.
         if let Some((finalized_block, deploys)) = self.exec_queue.remove(&next_height) {
             effects.extend(self.handle_get_deploys_result(
@@ -897,6 +1288,8 @@ impl ContractRuntime {
         let engine_state = Arc::clone(&self.engine_state);
         let metrics = Arc::clone(&self.metrics);
         let protocol_version = self.protocol_version;
+        let chain_name = self.chain_name.clone();
+        let record_execution_journal = self.record_execution_journal;
         let block_time = state.finalized_block.timestamp().millis();
         let proposer = state.finalized_block.proposer();
         async move {
@@ -911,6 +1304,7 @@ impl ContractRuntime {
                     vec![deploy_item],
                     protocol_version,
                     proposer.clone(),
+                    Some(chain_name.clone()),
                 );
 
                 // TODO: this is currently working coincidentally because we are passing only one
@@ -934,10 +1328,19 @@ impl ContractRuntime {
                 )
                 .await
                 {
-                    Ok((state_hash, execution_result)) => {
+                    Ok((state_hash, execution_result, contract_events, journal)) => {
                         state
                             .execution_results
                             .insert(deploy_hash, (deploy_header, execution_result));
+                        state.contract_events.extend(
+                            contract_events
+                                .into_iter()
+                                .map(|event| (deploy_hash, event)),
+                        );
+                        if record_execution_journal {
+                            let journal = journal.iter().map(Into::into).collect();
+                            state.execution_journals.insert(deploy_hash, journal);
+                        }
                         state.state_root_hash = state_hash;
                     }
                     // When commit fails we panic as we'll not be able to execute the next
@@ -956,21 +1359,40 @@ impl ContractRuntime {
         finalized_block: FinalizedBlock,
         deploys: VecDeque<Deploy>,
     ) -> Effects<Event> {
-        if let Some(state_root_hash) = self.pre_state_hash(&finalized_block) {
+        // Blocks proposed by this version of the node are validated to list their deploys in
+        // canonical order before they can be finalized. Blocks which aren't are still executed in
+        // their given order, as every node must arrive at the same result for them.
+        let deploy_count = finalized_block.deploy_hashes().len();
+        if !is_in_canonical_order(deploys.iter().take(deploy_count))
+            || !is_in_canonical_order(deploys.iter().skip(deploy_count))
+        {
+            warn!(
+                era_id = %finalized_block.era_id(),
+                height = finalized_block.height(),
+                "deploys of finalized block are not in canonical order"
+            );
+        }
+
+        if let Some((state_root_hash, gas_price_floor)) = self.pre_state(&finalized_block) {
             let state = Box::new(RequestState {
                 finalized_block,
                 remaining_deploys: deploys,
                 execution_results: HashMap::new(),
+                contract_events: Vec::new(),
+                execution_journals: HashMap::new(),
                 state_root_hash,
+                gas_price_floor,
+                execution_start: Instant::now(),
             });
             self.execute_all_deploys_or_finalize_block_or_step(effect_builder, state)
         } else {
             // Didn't find parent in the `parent_map` cache.
             // Read it from the storage.
             let height = finalized_block.height();
+            let deploy_config = self.deploy_config;
             effect_builder
                 .get_block_at_height_local(height - 1)
-                .event(|parent| {
+                .event(move |parent| {
                     Event::Result(Box::new(ContractRuntimeResult::GetParentResult {
                         finalized_block,
                         deploys,
@@ -979,6 +1401,7 @@ impl ContractRuntime {
                                 *b.hash(),
                                 b.header().accumulated_seed(),
                                 *b.state_root_hash(),
+                                b.next_gas_price_floor(&deploy_config),
                             )
                         }),
                     }))
@@ -998,12 +1421,16 @@ impl ContractRuntime {
                 let height = finalized_block.height();
                 debug!("no pre-state hash for height {}", height);
                 // re-check the parent map - the parent might have been executed in the meantime!
-                if let Some(state_root_hash) = self.pre_state_hash(&finalized_block) {
+                if let Some((state_root_hash, gas_price_floor)) = self.pre_state(&finalized_block) {
                     let state = Box::new(RequestState {
                         finalized_block,
                         remaining_deploys: deploys,
                         execution_results: HashMap::new(),
+                        contract_events: Vec::new(),
+                        execution_journals: HashMap::new(),
                         state_root_hash,
+                        gas_price_floor,
+                        execution_start: Instant::now(),
                     });
                     self.execute_all_deploys_or_finalize_block_or_step(effect_builder, state)
                 } else {
@@ -1027,6 +1454,7 @@ impl ContractRuntime {
         &mut self,
         finalized_block: FinalizedBlock,
         state_root_hash: Digest,
+        gas_price_floor: u64,
         next_era_validator_weights: Option<BTreeMap<PublicKey, U512>>,
     ) -> Block {
         let (parent_summary_hash, parent_seed) = if self.is_initial_block_child(&finalized_block) {
@@ -1053,6 +1481,11 @@ impl ContractRuntime {
             (summary.hash, summary.accumulated_seed)
         };
         let block_height = finalized_block.height();
+        let next_gas_price_floor = self.deploy_config.next_gas_price_floor(
+            gas_price_floor,
+            finalized_block.deploy_hashes().len(),
+            finalized_block.transfer_hashes().len(),
+        );
         let block = Block::new(
             parent_summary_hash,
             parent_seed,
@@ -1060,26 +1493,32 @@ impl ContractRuntime {
             finalized_block,
             next_era_validator_weights,
             self.protocol_version,
+            next_gas_price_floor,
         );
         let summary = ExecutedBlockSummary {
             hash: *block.hash(),
             state_root_hash,
             accumulated_seed: block.header().accumulated_seed(),
+            next_gas_price_floor: block.next_gas_price_floor(&self.deploy_config),
         };
         let _ = self.parent_map.insert(block_height, summary);
         block
     }
 
-    fn pre_state_hash(&mut self, finalized_block: &FinalizedBlock) -> Option<Digest> {
+    /// Returns the pre-state hash and the gas price floor for executing the given block.
+    fn pre_state(&mut self, finalized_block: &FinalizedBlock) -> Option<(Digest, u64)> {
         if self.is_initial_block_child(finalized_block) {
-            Some(self.initial_state.state_root_hash)
+            Some((
+                self.initial_state.state_root_hash,
+                self.initial_state.child_gas_price_floor,
+            ))
         } else {
             // Try to get the parent's post-state-hash from the `parent_map`.
             // We're subtracting 1 from the height as we want to get _parent's_ post-state hash.
             let parent_block_height = finalized_block.height() - 1;
             self.parent_map
                 .get(&parent_block_height)
-                .map(|summary| summary.state_root_hash)
+                .map(|summary| (summary.state_root_hash, summary.next_gas_price_floor))
         }
     }
 
@@ -1099,9 +1538,17 @@ pub struct RequestState {
     pub remaining_deploys: VecDeque<Deploy>,
     /// A collection of results of executing the deploys.
     pub execution_results: HashMap<DeployHash, (DeployHeader, ExecutionResult)>,
+    /// The events emitted by contracts while executing the deploys, in execution order.
+    pub contract_events: Vec<(DeployHash, ContractEvent)>,
+    /// The journals of global state operations of the executed deploys, if they are recorded.
+    pub execution_journals: HashMap<DeployHash, Vec<JournalEntry>>,
     /// Current state root hash of global storage.  Is initialized with the parent block's
     /// state hash, and is updated after each commit.
     pub state_root_hash: Digest,
+    /// The minimum gas price of the deploys of this block.
+    pub gas_price_floor: u64,
+    /// The time execution of the block started.
+    pub execution_start: Instant,
 }
 
 #[derive(DataSize, Debug, Default)]
@@ -1115,19 +1562,66 @@ struct InitialState {
     block_summary: Option<ExecutedBlockSummary>,
     /// Initial state root hash.
     state_root_hash: Digest,
+    /// Gas price floor of the child of the highest known block.
+    child_gas_price_floor: u64,
 }
 
 impl InitialState {
-    fn new(state_root_hash: Digest, block_header: Option<&BlockHeader>) -> Self {
-        let block_summary = block_header.map(|hdr| ExecutedBlockSummary {
-            hash: hdr.hash(),
+    /// Creates the initial state for a session starting after the given block, or at the given
+    /// genesis height if there is none.
+    fn new(
+        state_root_hash: Digest,
+        block: Option<&Block>,
+        genesis_height: u64,
+        deploy_config: &DeployConfig,
+    ) -> Self {
+        let child_gas_price_floor = block.map_or(deploy_config.min_gas_price, |block| {
+            block.next_gas_price_floor(deploy_config)
+        });
+        let block_summary = block.map(|block| ExecutedBlockSummary {
+            hash: *block.hash(),
             state_root_hash,
-            accumulated_seed: hdr.accumulated_seed(),
+            accumulated_seed: block.header().accumulated_seed(),
+            next_gas_price_floor: child_gas_price_floor,
         });
         Self {
-            child_height: block_header.map_or(0, |hdr| hdr.height() + 1),
+            child_height: block.map_or(genesis_height, |block| block.height() + 1),
             block_summary,
             state_root_hash,
+            child_gas_price_floor,
+        }
+    }
+}
+
+/// Returns the peers to fetch missing deploys from, starting with the block's proposer if it is
+/// connected.
+async fn fetch_peers<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    proposer: PublicKey,
+) -> Vec<NodeId> {
+    let maybe_proposer = effect_builder.network_peer_by_public_key(proposer).await;
+    let other_peers = effect_builder
+        .network_peers::<NodeId>()
+        .await
+        .into_iter()
+        .map(|(peer, _)| peer)
+        .filter(|peer| Some(*peer) != maybe_proposer);
+    maybe_proposer.into_iter().chain(other_peers).collect()
+}
+
+/// Fetches the given deploy, asking the given peers in turn until one of them provides it.
+async fn fetch_deploy<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    deploy_hash: DeployHash,
+    peers: &[NodeId],
+) -> Option<Deploy> {
+    for peer in peers {
+        match effect_builder.fetch_deploy(deploy_hash, *peer).await {
+            Some(FetchResult::FromStorage(deploy)) | Some(FetchResult::FromPeer(deploy, _)) => {
+                return Some(*deploy);
+            }
+            None => debug!(%deploy_hash, %peer, "could not fetch deploy from peer"),
         }
     }
+    None
 }
@@ -0,0 +1,127 @@
+//! Summaries of executed blocks, logged so that chain progress can be monitored from a single
+//! source.
+
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use serde::Serialize;
+use tracing::info;
+
+use casper_types::{EraId, PublicKey};
+
+use crate::{
+    crypto::hash::Digest,
+    types::{Block, BlockHash, TimeDiff},
+};
+
+/// The summary of a block which has been executed by the contract runtime.
+#[derive(Debug, Serialize)]
+pub(super) struct BlockSummary {
+    height: u64,
+    era_id: EraId,
+    block_hash: BlockHash,
+    proposer: PublicKey,
+    deploy_count: usize,
+    transfer_count: usize,
+    /// The time spent executing the block's deploys and step, measured from the start of
+    /// execution until the block was created.
+    execution_time: TimeDiff,
+    /// The state root hash after executing the block.
+    state_root_hash: Digest,
+}
+
+impl BlockSummary {
+    pub(super) fn new(block: &Block, execution_time: TimeDiff) -> Self {
+        BlockSummary {
+            height: block.height(),
+            era_id: block.header().era_id(),
+            block_hash: *block.hash(),
+            proposer: block.body().proposer().clone(),
+            deploy_count: block.body().deploy_hashes().len(),
+            transfer_count: block.body().transfer_hashes().len(),
+            execution_time,
+            state_root_hash: *block.state_root_hash(),
+        }
+    }
+
+    /// Logs the summary as a single structured event at INFO level.
+    pub(super) fn log(&self) {
+        info!(
+            height = self.height,
+            era_id = %self.era_id,
+            block_hash = %self.block_hash,
+            proposer = %self.proposer,
+            deploy_count = self.deploy_count,
+            transfer_count = self.transfer_count,
+            execution_time = %self.execution_time,
+            state_root_hash = %self.state_root_hash,
+            "executed finalized block"
+        );
+    }
+}
+
+/// A file to which block summaries are appended as JSON, one per line.
+#[derive(Debug)]
+pub(super) struct BlockSummaryLog {
+    writer: BufWriter<File>,
+}
+
+impl BlockSummaryLog {
+    /// Opens the file at the given path for appending, creating it if it does not exist.
+    pub(super) fn open(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(BlockSummaryLog {
+            writer: BufWriter::new(file),
+        })
+    }
+
+    /// Appends the given summary to the file.
+    pub(super) fn write(&mut self, summary: &BlockSummary) -> io::Result<()> {
+        serde_json::to_writer(&mut self.writer, summary)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, time::Duration};
+
+    use super::*;
+    use crate::testing::TestRng;
+
+    #[test]
+    fn should_append_summaries_as_json_lines() {
+        let mut rng = TestRng::new();
+        let tempdir = tempfile::tempdir().expect("should create tempdir");
+        let path = tempdir.path().join("block_summaries.jsonl");
+
+        let blocks = vec![Block::random(&mut rng), Block::random(&mut rng)];
+        let mut log = BlockSummaryLog::open(&path).expect("should open log");
+        for block in &blocks {
+            let summary = BlockSummary::new(block, Duration::from_millis(25).into());
+            log.write(&summary).expect("should write summary");
+        }
+
+        let contents = fs::read_to_string(&path).expect("should read log");
+        let lines: Vec<serde_json::Value> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("should parse summary"))
+            .collect();
+        assert_eq!(lines.len(), blocks.len());
+        for (line, block) in lines.iter().zip(&blocks) {
+            assert_eq!(line["height"], block.height());
+            assert_eq!(
+                line["deploy_count"],
+                block.body().deploy_hashes().len() as u64
+            );
+            assert_eq!(
+                line["transfer_count"],
+                block.body().transfer_hashes().len() as u64
+            );
+        }
+    }
+}
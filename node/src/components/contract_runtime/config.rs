@@ -1,14 +1,21 @@
+use std::path::{Path, PathBuf};
+
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
 use casper_execution_engine::shared::utils;
 
+use crate::types::TimeDiff;
+
 const DEFAULT_MAX_GLOBAL_STATE_SIZE: usize = 805_306_368_000; // 750 GiB
 const DEFAULT_MAX_READERS: u32 = 512;
 const DEFAULT_MAX_QUERY_DEPTH: u64 = 5;
+const DEFAULT_MAX_CONCURRENT_QUERIES: usize = 4;
+const DEFAULT_MAX_QUEUED_QUERIES: usize = 256;
+const DEFAULT_QUERY_DEADLINE: TimeDiff = TimeDiff::from_seconds(10);
 
 /// Contract runtime configuration.
-#[derive(Clone, Copy, DataSize, Debug, Deserialize, Serialize)]
+#[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
 // Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
 #[serde(deny_unknown_fields)]
 pub struct Config {
@@ -26,6 +33,29 @@ pub struct Config {
     ///
     /// Defaults to 5.
     max_query_depth: Option<u64>,
+    /// The maximum number of API-driven requests (queries, balance and bid lookups) executed
+    /// concurrently.
+    ///
+    /// Defaults to 4.
+    max_concurrent_queries: Option<usize>,
+    /// The maximum number of API-driven requests waiting or executing at any time.  Further
+    /// requests are rejected.
+    ///
+    /// Defaults to 256.
+    max_queued_queries: Option<usize>,
+    /// The maximum time an API-driven request may wait before it starts executing.
+    ///
+    /// Defaults to 10 seconds.
+    query_deadline: Option<TimeDiff>,
+    /// The file to which a JSON summary of each executed block is appended, one per line.  A
+    /// relative path is relative to the node's configuration directory.
+    ///
+    /// Defaults to not writing block summaries to a file.
+    block_summary_log_path: Option<PathBuf>,
+    /// Whether to store the journal of global state operations of each executed deploy.
+    ///
+    /// Defaults to false.
+    record_execution_journal: Option<bool>,
 }
 
 impl Config {
@@ -44,6 +74,28 @@ impl Config {
     pub(crate) fn max_query_depth(&self) -> u64 {
         self.max_query_depth.unwrap_or(DEFAULT_MAX_QUERY_DEPTH)
     }
+
+    pub(crate) fn max_concurrent_queries(&self) -> usize {
+        self.max_concurrent_queries
+            .unwrap_or(DEFAULT_MAX_CONCURRENT_QUERIES)
+    }
+
+    pub(crate) fn max_queued_queries(&self) -> usize {
+        self.max_queued_queries
+            .unwrap_or(DEFAULT_MAX_QUEUED_QUERIES)
+    }
+
+    pub(crate) fn query_deadline(&self) -> TimeDiff {
+        self.query_deadline.unwrap_or(DEFAULT_QUERY_DEADLINE)
+    }
+
+    pub(crate) fn block_summary_log_path(&self) -> Option<&Path> {
+        self.block_summary_log_path.as_deref()
+    }
+
+    pub(crate) fn record_execution_journal(&self) -> bool {
+        self.record_execution_journal.unwrap_or(false)
+    }
 }
 
 impl Default for Config {
@@ -52,6 +104,11 @@ impl Default for Config {
             max_global_state_size: Some(DEFAULT_MAX_GLOBAL_STATE_SIZE),
             max_readers: Some(DEFAULT_MAX_READERS),
             max_query_depth: Some(DEFAULT_MAX_QUERY_DEPTH),
+            max_concurrent_queries: Some(DEFAULT_MAX_CONCURRENT_QUERIES),
+            max_queued_queries: Some(DEFAULT_MAX_QUEUED_QUERIES),
+            query_deadline: Some(DEFAULT_QUERY_DEADLINE),
+            block_summary_log_path: None,
+            record_execution_journal: Some(false),
         }
     }
 }
@@ -0,0 +1,205 @@
+//! A bounded queue for API-driven requests to the contract runtime.
+//!
+//! Queries, balance lookups and bid lookups are typically triggered by clients of the node's APIs.
+//! Running them unboundedly on the shared thread pool allows a burst of such requests to starve
+//! block execution, so they are funneled through an `ExecutionQueue` which limits how many of them
+//! run at once, how many may wait, and how long each may wait before being abandoned.
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use datasize::DataSize;
+use prometheus::{IntCounter, IntGauge};
+use thiserror::Error;
+use tokio::{sync::Semaphore, time};
+
+use casper_execution_engine::core::engine_state;
+
+use crate::types::TimeDiff;
+
+/// Error returned by a request to the contract runtime which was sent through the execution queue.
+#[derive(Clone, Debug, Error)]
+pub enum QueryError {
+    /// The execution queue was full, so the request was rejected without being run.
+    #[error("contract runtime execution queue is full ({max_queued} requests)")]
+    QueueFull {
+        /// The maximum number of requests allowed in the queue.
+        max_queued: usize,
+    },
+    /// The request did not start executing before its deadline.
+    #[error("request not executed by the contract runtime within {deadline}")]
+    DeadlineExceeded {
+        /// The deadline which was exceeded.
+        deadline: TimeDiff,
+    },
+    /// The request was run, but the execution engine returned an error.
+    #[error(transparent)]
+    Engine(#[from] engine_state::Error),
+}
+
+/// Metrics for the execution queue.
+#[derive(Clone, Debug)]
+pub(super) struct ExecutionQueueMetrics {
+    /// Number of requests currently waiting in or executing from the queue.
+    pub(super) depth: IntGauge,
+    /// Total number of requests rejected because the queue was full.
+    pub(super) rejected: IntCounter,
+    /// Total number of requests which expired before they started executing.
+    pub(super) expired: IntCounter,
+}
+
+/// A queue limiting the concurrency of API-driven contract runtime requests.
+///
+/// Cloning the queue is cheap; all clones share the same limits and counters.
+#[derive(Clone, DataSize, Debug)]
+pub(super) struct ExecutionQueue {
+    /// Permits to execute, one per request allowed to run concurrently.
+    #[data_size(skip)]
+    permits: Arc<Semaphore>,
+    /// Number of requests currently waiting or executing.
+    #[data_size(skip)]
+    depth: Arc<AtomicUsize>,
+    /// Maximum number of requests waiting or executing before new ones are rejected.
+    max_queued: usize,
+    /// Maximum time a request may wait for a permit before it is abandoned.
+    deadline: TimeDiff,
+    #[data_size(skip)]
+    metrics: ExecutionQueueMetrics,
+}
+
+impl ExecutionQueue {
+    /// Creates a new queue.
+    pub(super) fn new(
+        max_concurrent: usize,
+        max_queued: usize,
+        deadline: TimeDiff,
+        metrics: ExecutionQueueMetrics,
+    ) -> Self {
+        ExecutionQueue {
+            permits: Arc::new(Semaphore::new(max_concurrent)),
+            depth: Arc::new(AtomicUsize::new(0)),
+            max_queued,
+            deadline,
+            metrics,
+        }
+    }
+
+    /// Runs `job` once a permit becomes available.
+    ///
+    /// Returns `QueryError::QueueFull` immediately if the queue is saturated, and
+    /// `QueryError::DeadlineExceeded` if no permit became available before the deadline. A job
+    /// which has started executing is always run to completion.
+    pub(super) async fn execute<T, F>(self, job: F) -> Result<T, QueryError>
+    where
+        F: FnOnce() -> Result<T, engine_state::Error>,
+    {
+        let _slot = self.enter()?;
+
+        let _permit = match time::timeout(self.deadline.into(), self.permits.acquire()).await {
+            Ok(Ok(permit)) => permit,
+            // The semaphore is never closed, but treat it as a saturated queue if it were.
+            Ok(Err(_)) => {
+                return Err(QueryError::QueueFull {
+                    max_queued: self.max_queued,
+                })
+            }
+            Err(_) => {
+                self.metrics.expired.inc();
+                return Err(QueryError::DeadlineExceeded {
+                    deadline: self.deadline,
+                });
+            }
+        };
+
+        job().map_err(QueryError::Engine)
+    }
+
+    /// Reserves a slot in the queue, or rejects the request if the queue is full.
+    fn enter(&self) -> Result<QueueSlot, QueryError> {
+        let previous_depth = self.depth.fetch_add(1, Ordering::SeqCst);
+        if previous_depth >= self.max_queued {
+            self.depth.fetch_sub(1, Ordering::SeqCst);
+            self.metrics.rejected.inc();
+            return Err(QueryError::QueueFull {
+                max_queued: self.max_queued,
+            });
+        }
+        self.metrics.depth.inc();
+        Ok(QueueSlot {
+            depth: Arc::clone(&self.depth),
+            depth_metric: self.metrics.depth.clone(),
+        })
+    }
+}
+
+/// A reserved slot in the queue, released on drop.
+struct QueueSlot {
+    depth: Arc<AtomicUsize>,
+    depth_metric: IntGauge,
+}
+
+impl Drop for QueueSlot {
+    fn drop(&mut self) {
+        self.depth.fetch_sub(1, Ordering::SeqCst);
+        self.depth_metric.dec();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures::future;
+
+    use super::*;
+
+    fn new_queue(max_concurrent: usize, max_queued: usize, deadline: TimeDiff) -> ExecutionQueue {
+        let metrics = ExecutionQueueMetrics {
+            depth: IntGauge::new("depth", "depth").unwrap(),
+            rejected: IntCounter::new("rejected", "rejected").unwrap(),
+            expired: IntCounter::new("expired", "expired").unwrap(),
+        };
+        ExecutionQueue::new(max_concurrent, max_queued, deadline, metrics)
+    }
+
+    #[tokio::test]
+    async fn should_execute_job() {
+        let queue = new_queue(1, 1, TimeDiff::from_seconds(1));
+        let result = queue.clone().execute(|| Ok(42)).await;
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(queue.metrics.depth.get(), 0);
+    }
+
+    #[tokio::test]
+    async fn should_reject_when_full() {
+        let queue = new_queue(1, 1, TimeDiff::from_seconds(1));
+        let _slot = queue.enter().unwrap();
+        let result = queue.clone().execute(|| Ok(())).await;
+        assert!(matches!(
+            result,
+            Err(QueryError::QueueFull { max_queued: 1 })
+        ));
+        assert_eq!(queue.metrics.rejected.get(), 1);
+    }
+
+    #[tokio::test]
+    async fn should_expire_after_deadline() {
+        let queue = new_queue(1, 2, TimeDiff::from(Duration::from_millis(10)));
+        let _permit = queue.permits.acquire().await.unwrap();
+        let result = queue.clone().execute(|| Ok(())).await;
+        assert!(matches!(result, Err(QueryError::DeadlineExceeded { .. })));
+        assert_eq!(queue.metrics.expired.get(), 1);
+        assert_eq!(queue.metrics.depth.get(), 0);
+    }
+
+    #[tokio::test]
+    async fn should_run_queued_jobs_in_turn() {
+        let queue = new_queue(1, 3, TimeDiff::from_seconds(10));
+        let jobs = (0..3).map(|index| queue.clone().execute(move || Ok(index)));
+        let results = future::join_all(jobs).await;
+        let values: Vec<_> = results.into_iter().map(Result::unwrap).collect();
+        assert_eq!(values, vec![0, 1, 2]);
+    }
+}
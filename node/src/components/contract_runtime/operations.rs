@@ -3,8 +3,12 @@ use std::{collections::VecDeque, sync::Arc, time::Instant};
 use super::ContractRuntimeMetrics;
 use crate::{crypto::hash::Digest, types::DeployHash};
 use casper_execution_engine::{
-    core::engine_state::{
-        self, EngineState, ExecutionResult as EngineExecutionResult, ExecutionResults,
+    core::{
+        engine_state::{
+            self, ContractEvent, EngineState, ExecutionResult as EngineExecutionResult,
+            ExecutionResults, JournalEntry,
+        },
+        execution,
     },
     shared::{additive_map::AdditiveMap, newtypes::CorrelationId, transform::Transform},
     storage::global_state::{lmdb::LmdbGlobalState, CommitResult},
@@ -14,14 +18,23 @@ use engine_state::ExecuteRequest;
 use itertools::Itertools;
 use tracing::{debug, error, trace};
 
-/// Commits the execution effects.
+/// Commits the execution effects, returning the new state root hash, the execution result, the
+/// events emitted by contracts and the journal of global state operations.
 pub(super) async fn commit_execution_effects(
     engine_state: Arc<EngineState<LmdbGlobalState>>,
     metrics: Arc<ContractRuntimeMetrics>,
     state_root_hash: Digest,
     deploy_hash: DeployHash,
     execution_results: ExecutionResults,
-) -> Result<(Digest, ExecutionResult), ()> {
+) -> Result<
+    (
+        Digest,
+        ExecutionResult,
+        Vec<ContractEvent>,
+        Vec<JournalEntry>,
+    ),
+    (),
+> {
     let ee_execution_result = execution_results
         .into_iter()
         .exactly_one()
@@ -45,9 +58,14 @@ pub(super) async fn commit_execution_effects(
             // We do want to see the deploy hash, error, and cost in the logs.
             // We don't need to see the effects in the logs.
             debug!(?deploy_hash, ?error, %cost, "execution failure");
+            if let engine_state::Error::Exec(execution::Error::ExecutionStepLimit) = error {
+                metrics.execution_step_limit_exceeded.inc();
+            }
             effect
         }
     };
+    let contract_events = execution_effect.events;
+    let journal = execution_effect.journal;
     let commit_result = commit(
         engine_state,
         metrics,
@@ -59,7 +77,12 @@ pub(super) async fn commit_execution_effects(
     match commit_result {
         Ok(CommitResult::Success { state_root }) => {
             debug!(?state_root, "commit succeeded");
-            Ok((state_root.into(), execution_result))
+            Ok((
+                state_root.into(),
+                execution_result,
+                contract_events,
+                journal,
+            ))
         }
         _ => {
             error!(
@@ -96,6 +119,9 @@ pub(super) async fn execute(
     let start = Instant::now();
     let result = engine_state.run_execute(correlation_id, execute_request);
     metrics.run_execute.observe(start.elapsed().as_secs_f64());
+    let wasm_cache = engine_state.wasm_cache();
+    metrics.wasm_cache_hits.set(wasm_cache.hits() as i64);
+    metrics.wasm_cache_misses.set(wasm_cache.misses() as i64);
     trace!(?result, "execute result");
     result
 }
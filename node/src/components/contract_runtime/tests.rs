@@ -0,0 +1,328 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::{self, Display, Formatter},
+    iter,
+    sync::Arc,
+    time::Duration,
+};
+
+use derive_more::From;
+use prometheus::Registry;
+use reactor::ReactorEvent;
+use serde::Serialize;
+use tempfile::TempDir;
+use thiserror::Error;
+use tokio::time;
+
+use casper_types::{EraId, PublicKey, SecretKey};
+
+use super::*;
+use crate::{
+    components::{
+        fetcher::FetchResult,
+        storage::{self, Storage},
+    },
+    effect::{
+        announcements::{ContractRuntimeAnnouncement, ControlAnnouncement, LinearChainBlock},
+        requests::{
+            ConsensusRequest, ContractRuntimeRequest, FetcherRequest, LinearChainRequest,
+            NetworkInfoRequest, StateStoreRequest, StorageRequest,
+        },
+        EffectExt,
+    },
+    reactor::{self, EventQueueHandle, Runner},
+    testing::TestRng,
+    types::{BlockPayload, Chainspec, Deploy, DeployHash, FinalizedBlock, NodeId, Timestamp},
+    utils::{Loadable, WithDir},
+    NodeRng,
+};
+
+/// Top-level event for the reactor.
+#[derive(Debug, From, Serialize)]
+#[must_use]
+enum Event {
+    #[from]
+    Storage(#[serde(skip_serializing)] storage::Event),
+    #[from]
+    ContractRuntime(#[serde(skip_serializing)] super::Event),
+    #[from]
+    FetcherRequest(#[serde(skip_serializing)] FetcherRequest<NodeId, Deploy>),
+    #[from]
+    NetworkInfoRequest(#[serde(skip_serializing)] NetworkInfoRequest<NodeId>),
+    #[from]
+    LinearChainRequest(#[serde(skip_serializing)] LinearChainRequest<NodeId>),
+    #[from]
+    ContractRuntimeAnnouncement(#[serde(skip_serializing)] ContractRuntimeAnnouncement),
+    #[from]
+    ControlAnnouncement(ControlAnnouncement),
+}
+
+impl ReactorEvent for Event {
+    fn as_control(&self) -> Option<&ControlAnnouncement> {
+        if let Self::ControlAnnouncement(ref ctrl_ann) = self {
+            Some(ctrl_ann)
+        } else {
+            None
+        }
+    }
+}
+
+impl From<StorageRequest> for Event {
+    fn from(request: StorageRequest) -> Self {
+        Event::Storage(storage::Event::from(request))
+    }
+}
+
+impl From<StateStoreRequest> for Event {
+    fn from(request: StateStoreRequest) -> Self {
+        Event::Storage(storage::Event::from(request))
+    }
+}
+
+impl From<ContractRuntimeRequest> for Event {
+    fn from(request: ContractRuntimeRequest) -> Self {
+        Event::ContractRuntime(super::Event::Request(Box::new(request)))
+    }
+}
+
+impl From<ConsensusRequest> for Event {
+    fn from(_request: ConsensusRequest) -> Self {
+        unimplemented!("not implemented for contract runtime tests")
+    }
+}
+
+impl Display for Event {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::Storage(event) => write!(formatter, "storage: {}", event),
+            Event::ContractRuntime(event) => {
+                write!(formatter, "contract-runtime event: {:?}", event)
+            }
+            Event::FetcherRequest(req) => write!(formatter, "fetcher request: {}", req),
+            Event::NetworkInfoRequest(req) => write!(formatter, "network info request: {}", req),
+            Event::LinearChainRequest(req) => write!(formatter, "linear chain request: {}", req),
+            Event::ContractRuntimeAnnouncement(ann) => {
+                write!(formatter, "contract-runtime announcement: {}", ann)
+            }
+            Event::ControlAnnouncement(ctrl_ann) => write!(formatter, "control: {}", ctrl_ann),
+        }
+    }
+}
+
+/// Error type returned by the test reactor.
+#[derive(Debug, Error)]
+enum Error {
+    #[error("prometheus (metrics) error: {0}")]
+    Metrics(#[from] prometheus::Error),
+}
+
+/// A reactor running the contract runtime on top of the genesis state of the local chainspec.
+///
+/// Fetch requests are answered from `available_deploys`, standing in for a single peer.
+struct Reactor {
+    storage: Storage,
+    contract_runtime: ContractRuntime,
+    peer: NodeId,
+    available_deploys: HashMap<DeployHash, Deploy>,
+    executed_blocks: Vec<LinearChainBlock>,
+    _storage_tempdir: TempDir,
+}
+
+impl reactor::Reactor for Reactor {
+    type Event = Event;
+    type Config = ();
+    type Error = Error;
+
+    fn new(
+        _config: Self::Config,
+        registry: &Registry,
+        _event_queue: EventQueueHandle<Self::Event>,
+        rng: &mut NodeRng,
+    ) -> Result<(Self, Effects<Self::Event>), Self::Error> {
+        let chainspec = Arc::new(Chainspec::from_resources("local"));
+        let protocol_version = chainspec.protocol_config.version;
+
+        let (storage_config, storage_tempdir) = storage::Config::default_for_tests();
+        let storage_withdir = WithDir::new(storage_tempdir.path(), storage_config);
+        let storage = Storage::new(&storage_withdir, None, protocol_version, registry).unwrap();
+
+        let mut contract_runtime = ContractRuntime::new(
+            Digest::default(),
+            None,
+            0,
+            protocol_version,
+            chainspec.network_config.name.clone(),
+            chainspec.deploy_config,
+            storage_withdir,
+            &Config::default(),
+            registry,
+        )
+        .unwrap();
+        let post_state_hash = match contract_runtime.commit_genesis(Arc::clone(&chainspec)) {
+            Ok(GenesisResult::Success {
+                post_state_hash, ..
+            }) => post_state_hash,
+            result => panic!("failed to commit genesis: {:?}", result),
+        };
+        contract_runtime.set_initial_state(post_state_hash.into(), None, 0);
+
+        let reactor = Reactor {
+            storage,
+            contract_runtime,
+            peer: NodeId::random(rng),
+            available_deploys: HashMap::new(),
+            executed_blocks: Vec::new(),
+            _storage_tempdir: storage_tempdir,
+        };
+
+        Ok((reactor, Effects::new()))
+    }
+
+    fn dispatch_event(
+        &mut self,
+        effect_builder: EffectBuilder<Self::Event>,
+        rng: &mut NodeRng,
+        event: Event,
+    ) -> Effects<Self::Event> {
+        match event {
+            Event::Storage(event) => reactor::wrap_effects(
+                Event::Storage,
+                self.storage.handle_event(effect_builder, rng, event),
+            ),
+            Event::ContractRuntime(event) => reactor::wrap_effects(
+                Event::ContractRuntime,
+                self.contract_runtime
+                    .handle_event(effect_builder, rng, event),
+            ),
+            Event::FetcherRequest(FetcherRequest::Fetch {
+                id,
+                peer,
+                responder,
+            }) => {
+                let maybe_deploy = self
+                    .available_deploys
+                    .get(&id)
+                    .map(|deploy| FetchResult::FromPeer(Box::new(deploy.clone()), peer));
+                responder.respond(maybe_deploy).ignore()
+            }
+            Event::NetworkInfoRequest(NetworkInfoRequest::GetPeers { responder }) => {
+                let peers: BTreeMap<_, _> = iter::once((self.peer, String::from("peer"))).collect();
+                responder.respond(peers).ignore()
+            }
+            Event::NetworkInfoRequest(NetworkInfoRequest::GetPeerByPublicKey {
+                responder, ..
+            }) => responder.respond(None).ignore(),
+            Event::NetworkInfoRequest(request) => {
+                unreachable!("unhandled network info request: {}", request)
+            }
+            Event::LinearChainRequest(LinearChainRequest::BlockAtHeightLocal(_, responder)) => {
+                responder.respond(None).ignore()
+            }
+            Event::LinearChainRequest(request) => {
+                unreachable!("unhandled linear chain request: {}", request)
+            }
+            Event::ContractRuntimeAnnouncement(ContractRuntimeAnnouncement::LinearChainBlock(
+                linear_chain_block,
+            )) => {
+                self.executed_blocks.push(*linear_chain_block);
+                Effects::new()
+            }
+            Event::ContractRuntimeAnnouncement(ann) => {
+                unreachable!("unhandled contract runtime announcement: {}", ann)
+            }
+            Event::ControlAnnouncement(ctrl_ann) => {
+                unreachable!("unhandled control announcement: {}", ctrl_ann)
+            }
+        }
+    }
+
+    fn maybe_exit(&self) -> Option<crate::reactor::ReactorExit> {
+        unimplemented!()
+    }
+}
+
+/// Returns the current value of the gauge counting blocks which are waiting for missing deploys.
+fn blocks_missing_deploys(registry: &Registry) -> i64 {
+    let family = registry
+        .gather()
+        .into_iter()
+        .find(|family| family.get_name() == BLOCKS_MISSING_DEPLOYS_NAME)
+        .expect("metric should be registered");
+    family.get_metric()[0].get_gauge().get_value() as i64
+}
+
+/// Cranks the reactor until `condition` holds, panicking if it doesn't within `timeout`.
+async fn crank_until<F>(
+    runner: &mut Runner<Reactor>,
+    rng: &mut TestRng,
+    timeout: Duration,
+    condition: F,
+) where
+    F: Fn(&Reactor) -> bool,
+{
+    time::timeout(timeout, async {
+        while !condition(runner.reactor()) {
+            runner.crank(rng).await;
+        }
+    })
+    .await
+    .expect("condition should hold before the timeout")
+}
+
+#[tokio::test]
+async fn should_fetch_missing_deploys_before_executing_block() {
+    const TIMEOUT: Duration = Duration::from_secs(30);
+
+    let mut rng = crate::new_rng();
+    let registry = Registry::new();
+    let mut runner = Runner::<Reactor>::with_metrics((), &mut rng, &registry)
+        .await
+        .unwrap();
+
+    // Finalize a block whose deploys are held neither in storage nor by any peer.
+    let deploys = vec![Deploy::random(&mut rng), Deploy::random(&mut rng)];
+    let deploy_hashes: Vec<DeployHash> = deploys.iter().map(|deploy| *deploy.id()).collect();
+    let block_payload = BlockPayload::new(deploy_hashes.clone(), vec![], vec![], true);
+    let proposer = PublicKey::from(&SecretKey::random(&mut rng));
+    let finalized_block = FinalizedBlock::new(
+        block_payload,
+        None,
+        Timestamp::now(),
+        EraId::from(0),
+        0,
+        proposer,
+    );
+    runner
+        .process_injected_effects(|effect_builder| {
+            effect_builder.execute_block(finalized_block).ignore()
+        })
+        .await;
+
+    // The block stalls until its deploys become available.
+    crank_until(&mut runner, &mut rng, TIMEOUT, |_| {
+        blocks_missing_deploys(&registry) == 1
+    })
+    .await;
+    assert!(runner.reactor().executed_blocks.is_empty());
+
+    // Once a peer holds the deploys, they are fetched and the block is executed.
+    runner
+        .reactor_mut()
+        .available_deploys
+        .extend(deploys.into_iter().map(|deploy| (*deploy.id(), deploy)));
+    crank_until(&mut runner, &mut rng, TIMEOUT, |reactor| {
+        !reactor.executed_blocks.is_empty()
+    })
+    .await;
+
+    assert_eq!(blocks_missing_deploys(&registry), 0);
+    let executed_blocks = &runner.reactor().executed_blocks;
+    assert_eq!(executed_blocks.len(), 1);
+    assert_eq!(executed_blocks[0].block.height(), 0);
+    assert_eq!(executed_blocks[0].block.deploy_hashes(), &deploy_hashes);
+    for deploy_hash in &deploy_hashes {
+        assert!(executed_blocks[0]
+            .execution_results
+            .contains_key(deploy_hash));
+    }
+}
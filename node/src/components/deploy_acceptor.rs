@@ -1,7 +1,8 @@
+mod access_lists;
 mod config;
 mod event;
 
-use std::{convert::Infallible, fmt::Debug};
+use std::{collections::BTreeSet, convert::Infallible, fmt::Debug};
 
 use thiserror::Error;
 use tracing::{debug, error, info};
@@ -10,16 +11,18 @@ use crate::{
     components::Component,
     effect::{
         announcements::DeployAcceptorAnnouncement,
-        requests::{ContractRuntimeRequest, StorageRequest},
+        requests::{ContractRuntimeRequest, DeployAcceptorRequest, StorageRequest},
         EffectBuilder, EffectExt, Effects,
     },
     types::{chainspec::DeployConfig, Chainspec, Deploy, DeployValidationFailure, NodeId},
     utils::Source,
     NodeRng,
 };
-use casper_types::Key;
+use casper_execution_engine::shared::account::Account;
+use casper_types::{system::standard_payment::ARG_AMOUNT, EraId, Key, U512};
 
 use crate::effect::Responder;
+pub use access_lists::{AccessDenied, AccessList, AccessLists};
 pub use config::Config;
 pub use event::Event;
 
@@ -34,6 +37,26 @@ pub enum Error {
     /// A deploy was sent from account with insufficient balance.
     #[error("insufficient balance")]
     InsufficientBalance,
+    /// A deploy was rejected by the node's allowlist or denylist.
+    #[error("deploy not permitted: {0}")]
+    NotPermitted(AccessDenied),
+    /// The account of a deploy doesn't exist, or its state couldn't be read.
+    #[error("account not found")]
+    AccountNotFound,
+    /// A deploy was approved by keys not associated with its account.
+    #[error("deploy approved by keys not associated with the account")]
+    InvalidAuthorizationKeys,
+    /// The weight of a deploy's approvals is below its account's deployment threshold.
+    #[error("deploy approvals do not meet the account's deployment threshold")]
+    InsufficientApprovalWeight,
+    /// A deploy's payment amount exceeds the balance of its account's main purse.
+    #[error("account balance {balance} does not cover payment amount {payment_amount}")]
+    BalanceBelowPaymentAmount {
+        /// The balance of the account's main purse.
+        balance: U512,
+        /// The payment amount declared by the deploy.
+        payment_amount: U512,
+    },
 }
 
 /// A helper trait constraining `DeployAcceptor` compatible reactor events.
@@ -65,14 +88,51 @@ pub struct DeployAcceptor {
     chain_name: String,
     deploy_config: DeployConfig,
     verify_accounts: bool,
+    check_account_state: bool,
+    access_lists: AccessLists,
+    /// The era of the highest block added to the linear chain so far, if any.
+    current_era_id: Option<EraId>,
 }
 
 impl DeployAcceptor {
     pub(crate) fn new(config: Config, chainspec: &Chainspec) -> Self {
         DeployAcceptor {
             chain_name: chainspec.network_config.name.clone(),
-            deploy_config: chainspec.deploy_config,
+            deploy_config: DeployConfig {
+                max_ttl: chainspec.max_deploy_ttl(),
+                ..chainspec.deploy_config
+            },
             verify_accounts: config.verify_accounts(),
+            check_account_state: config.check_account_state(),
+            access_lists: config.access_lists(),
+            current_era_id: None,
+        }
+    }
+
+    /// Returns the allowlist and denylist currently applied to new deploys.
+    pub(crate) fn access_lists(&self) -> &AccessLists {
+        &self.access_lists
+    }
+
+    /// Returns an error if the deploy's validity window closed before the current era.
+    ///
+    /// Deploys whose window has not opened yet are accepted; the block proposer holds them back
+    /// until they become valid.
+    fn check_validity_window(&self, deploy: &Deploy) -> Result<(), DeployValidationFailure> {
+        match (deploy.header().valid_until_era(), self.current_era_id) {
+            (Some(valid_until_era), Some(current_era)) if valid_until_era < current_era => {
+                info!(
+                    deploy_hash = %deploy.id(),
+                    %valid_until_era,
+                    %current_era,
+                    "deploy validity window closed"
+                );
+                Err(DeployValidationFailure::ValidityWindowClosed {
+                    valid_until_era,
+                    current_era,
+                })
+            }
+            _ => Ok(()),
         }
     }
 
@@ -89,7 +149,9 @@ impl DeployAcceptor {
     ) -> Effects<Event> {
         let mut cloned_deploy = deploy.clone();
         let mut effects = Effects::new();
-        let is_acceptable = cloned_deploy.is_acceptable(&self.chain_name, &self.deploy_config);
+        let is_acceptable = cloned_deploy
+            .is_acceptable(&self.chain_name, &self.deploy_config)
+            .and_then(|()| self.check_validity_window(&deploy));
         if let Err(error) = is_acceptable {
             // The client has submitted an invalid deploy. Return an error to the RPC component via
             // the responder.
@@ -104,8 +166,40 @@ impl DeployAcceptor {
             return effects;
         }
 
+        if let Err(access_denied) = self.access_lists.check(&deploy) {
+            info!(deploy_hash = %deploy.id(), %access_denied, "rejected deploy");
+            if let Some(responder) = maybe_responder {
+                effects.extend(
+                    responder
+                        .respond(Err(Error::NotPermitted(access_denied)))
+                        .ignore(),
+                );
+            }
+            effects.extend(
+                effect_builder
+                    .announce_invalid_deploy(deploy, source)
+                    .ignore(),
+            );
+            return effects;
+        }
+
         let account_key = deploy.header().account().to_account_hash().into();
 
+        // Check the account's state if deploy received from client and node is configured to do
+        // so.  This supersedes the plain account verification below.
+        if source.from_client() && self.check_account_state {
+            return effect_builder.get_account_and_balance(account_key).event(
+                move |maybe_account_and_balance| Event::AccountStateResult {
+                    deploy,
+                    source,
+                    account_key,
+                    maybe_account_and_balance: maybe_account_and_balance
+                        .map(|(account, balance)| (Box::new(account), balance)),
+                    maybe_responder,
+                },
+            );
+        }
+
         // Verify account if deploy received from client and node is configured to do so.
         if source.from_client() && self.verify_accounts {
             return effect_builder
@@ -191,6 +285,42 @@ impl DeployAcceptor {
         effects
     }
 
+    fn handle_account_state<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        deploy: Box<Deploy>,
+        source: Source<NodeId>,
+        account_key: Key,
+        maybe_account_and_balance: Option<(Box<Account>, U512)>,
+        maybe_responder: Option<Responder<Result<(), Error>>>,
+    ) -> Effects<Event> {
+        let result = match maybe_account_and_balance {
+            Some((account, balance)) => check_account_state(&deploy, &account, balance),
+            None => Err(Error::AccountNotFound),
+        };
+        if let Err(error) = result {
+            info!(deploy_hash = %deploy.id(), %account_key, %error, "rejected deploy");
+            let mut effects = Effects::new();
+            if let Some(responder) = maybe_responder {
+                effects.extend(responder.respond(Err(error)).ignore());
+            }
+            effects.extend(
+                effect_builder
+                    .announce_invalid_deploy(deploy, source)
+                    .ignore(),
+            );
+            return effects;
+        }
+        self.account_verification(
+            effect_builder,
+            deploy,
+            source,
+            account_key,
+            Some(true),
+            maybe_responder,
+        )
+    }
+
     fn handle_put_to_storage<REv: ReactorEventT>(
         &mut self,
         effect_builder: EffectBuilder<REv>,
@@ -243,6 +373,138 @@ impl<REv: ReactorEventT> Component<REv> for DeployAcceptor {
                 verified,
                 maybe_responder,
             ),
+            Event::AccountStateResult {
+                deploy,
+                source,
+                account_key,
+                maybe_account_and_balance,
+                maybe_responder,
+            } => self.handle_account_state(
+                effect_builder,
+                deploy,
+                source,
+                account_key,
+                maybe_account_and_balance,
+                maybe_responder,
+            ),
+            Event::Request(DeployAcceptorRequest::SetAccessLists {
+                access_lists,
+                responder,
+            }) => {
+                info!(?access_lists, "replacing deploy access lists");
+                self.access_lists = access_lists;
+                responder.respond(()).ignore()
+            }
+            Event::BlockAdded(block_header) => {
+                let era_id = block_header.era_id();
+                if self.current_era_id.map_or(true, |current| current < era_id) {
+                    self.current_era_id = Some(era_id);
+                }
+                Effects::new()
+            }
+        }
+    }
+}
+
+/// Checks that the deploy's approvals are from keys associated with the account and meet its
+/// deployment threshold, and that the account's balance covers the standard payment amount.
+fn check_account_state(deploy: &Deploy, account: &Account, balance: U512) -> Result<(), Error> {
+    let authorization_keys: BTreeSet<_> = deploy
+        .approvals()
+        .iter()
+        .map(|approval| approval.signer().to_account_hash())
+        .collect();
+    if !account.can_authorize(&authorization_keys) {
+        return Err(Error::InvalidAuthorizationKeys);
+    }
+    if !account.can_deploy_with(&authorization_keys) {
+        return Err(Error::InsufficientApprovalWeight);
+    }
+    if deploy.payment().is_standard_payment() {
+        let maybe_payment_amount = deploy
+            .payment()
+            .args()
+            .get(ARG_AMOUNT)
+            .and_then(|arg| arg.clone().into_t::<U512>().ok());
+        if let Some(payment_amount) = maybe_payment_amount {
+            if balance < payment_amount {
+                return Err(Error::BalanceBelowPaymentAmount {
+                    balance,
+                    payment_amount,
+                });
+            }
         }
     }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::{account::AccountHash, AccessRights, PublicKey, SecretKey, URef};
+
+    use super::*;
+    use crate::{
+        testing::TestRng,
+        types::{TimeDiff, Timestamp},
+    };
+
+    fn new_transfer(rng: &mut TestRng, secret_key: &SecretKey, payment_amount: u64) -> Deploy {
+        Deploy::new_transfer(
+            U512::from(2_500_000_000u64),
+            AccountHash::new([1; 32]),
+            None,
+            U512::from(payment_amount),
+            Timestamp::random(rng),
+            TimeDiff::from_seconds(60),
+            1,
+            vec![],
+            "casper-example".to_string(),
+            secret_key,
+        )
+    }
+
+    fn account_of(secret_key: &SecretKey) -> Account {
+        Account::create(
+            PublicKey::from(secret_key).to_account_hash(),
+            Default::default(),
+            URef::new([2; 32], AccessRights::READ_ADD_WRITE),
+        )
+    }
+
+    #[test]
+    fn should_accept_deploy_covered_by_account() {
+        let mut rng = TestRng::new();
+        let secret_key = SecretKey::random(&mut rng);
+        let deploy = new_transfer(&mut rng, &secret_key, 1_000);
+        let account = account_of(&secret_key);
+
+        assert!(check_account_state(&deploy, &account, U512::from(1_000)).is_ok());
+    }
+
+    #[test]
+    fn should_reject_deploy_exceeding_balance() {
+        let mut rng = TestRng::new();
+        let secret_key = SecretKey::random(&mut rng);
+        let deploy = new_transfer(&mut rng, &secret_key, 1_000);
+        let account = account_of(&secret_key);
+
+        assert!(matches!(
+            check_account_state(&deploy, &account, U512::from(999)),
+            Err(Error::BalanceBelowPaymentAmount { .. })
+        ));
+    }
+
+    #[test]
+    fn should_reject_deploy_approved_by_unassociated_key() {
+        let mut rng = TestRng::new();
+        let secret_key = SecretKey::random(&mut rng);
+        let mut deploy = new_transfer(&mut rng, &secret_key, 1_000);
+        deploy.sign(&SecretKey::random(&mut rng));
+        let account = account_of(&secret_key);
+
+        assert!(matches!(
+            check_account_state(&deploy, &account, U512::from(1_000)),
+            Err(Error::InvalidAuthorizationKeys)
+        ));
+    }
 }
@@ -0,0 +1,198 @@
+//! Allowlists and denylists restricting which deploys are accepted.
+//!
+//! Operators of private networks can restrict deploys to known accounts or to session code which
+//! calls approved contracts. The lists are only applied by the deploy acceptor, so they have no
+//! effect on deploys which are already included in blocks.
+
+use std::collections::BTreeSet;
+
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use casper_execution_engine::core::engine_state::executable_deploy_item::ExecutableDeployItem;
+use casper_types::{account::AccountHash, ContractHash, ContractPackageHash};
+
+use crate::types::Deploy;
+
+/// Accounts and stored contracts named in an allowlist or a denylist.
+#[derive(Clone, DataSize, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct AccessList {
+    /// Accounts sending deploys.
+    #[serde(default)]
+    accounts: BTreeSet<AccountHash>,
+    /// Contracts called by hash from session code.
+    #[serde(default)]
+    contracts: BTreeSet<ContractHash>,
+    /// Contract packages called by hash from session code.
+    #[serde(default)]
+    contract_packages: BTreeSet<ContractPackageHash>,
+}
+
+impl AccessList {
+    /// Returns whether `session` calls a contract or contract package on this list.
+    ///
+    /// Contracts called by name can't be resolved without reading global state, so they never
+    /// match.
+    fn contains_session_target(&self, session: &ExecutableDeployItem) -> bool {
+        match session {
+            ExecutableDeployItem::StoredContractByHash { hash, .. } => {
+                self.contracts.contains(hash)
+            }
+            ExecutableDeployItem::StoredVersionedContractByHash { hash, .. } => {
+                self.contract_packages.contains(hash)
+            }
+            ExecutableDeployItem::ModuleBytes { .. }
+            | ExecutableDeployItem::StoredContractByName { .. }
+            | ExecutableDeployItem::StoredVersionedContractByName { .. }
+            | ExecutableDeployItem::Transfer { .. } => false,
+        }
+    }
+}
+
+/// The reason a deploy was rejected by the access lists.
+#[derive(Clone, Debug, Error, Eq, PartialEq)]
+pub enum AccessDenied {
+    /// The account sending the deploy is denylisted.
+    #[error("account {0} is denylisted")]
+    DenylistedAccount(AccountHash),
+    /// The session code calls a denylisted contract or contract package.
+    #[error("session code calls a denylisted contract")]
+    DenylistedContract,
+    /// Neither the account nor the contract called by the session code is allowlisted.
+    #[error("neither the account nor the session code is allowlisted")]
+    NotAllowlisted,
+}
+
+/// The allowlist and denylist applied to deploys.
+#[derive(Clone, DataSize, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct AccessLists {
+    /// If set, only deploys from these accounts or whose session code calls these contracts are
+    /// accepted. Native transfers are unaffected, as they have no session code.
+    #[serde(default)]
+    pub allowlist: Option<AccessList>,
+    /// Deploys from these accounts or whose session code calls these contracts are rejected, even
+    /// if they are also allowlisted.
+    #[serde(default)]
+    pub denylist: AccessList,
+}
+
+impl AccessLists {
+    /// Checks whether `deploy` is permitted by the lists.
+    pub(crate) fn check(&self, deploy: &Deploy) -> Result<(), AccessDenied> {
+        let account = deploy.header().account().to_account_hash();
+        let session = deploy.session();
+
+        if self.denylist.accounts.contains(&account) {
+            return Err(AccessDenied::DenylistedAccount(account));
+        }
+        if self.denylist.contains_session_target(session) {
+            return Err(AccessDenied::DenylistedContract);
+        }
+
+        match &self.allowlist {
+            None => Ok(()),
+            Some(allowlist) => {
+                if allowlist.accounts.contains(&account)
+                    || allowlist.contains_session_target(session)
+                    || matches!(session, ExecutableDeployItem::Transfer { .. })
+                {
+                    Ok(())
+                } else {
+                    Err(AccessDenied::NotAllowlisted)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::{PublicKey, RuntimeArgs, SecretKey};
+
+    use super::*;
+    use crate::{
+        testing::TestRng,
+        types::{TimeDiff, Timestamp},
+    };
+
+    fn deploy_calling(contract_hash: ContractHash, secret_key: &SecretKey) -> Deploy {
+        let payment = ExecutableDeployItem::ModuleBytes {
+            module_bytes: Default::default(),
+            args: RuntimeArgs::new(),
+        };
+        let session = ExecutableDeployItem::StoredContractByHash {
+            hash: contract_hash,
+            entry_point: "call".to_string(),
+            args: RuntimeArgs::new(),
+        };
+        Deploy::new(
+            Timestamp::now(),
+            TimeDiff::from_seconds(60),
+            1,
+            vec![],
+            "casper-example".to_string(),
+            payment,
+            session,
+            secret_key,
+        )
+    }
+
+    #[test]
+    fn should_accept_everything_by_default() {
+        let mut rng = TestRng::new();
+        let deploy = Deploy::random(&mut rng);
+        assert_eq!(AccessLists::default().check(&deploy), Ok(()));
+    }
+
+    #[test]
+    fn should_apply_allowlist_and_denylist() {
+        let mut rng = TestRng::new();
+        let secret_key = SecretKey::random(&mut rng);
+        let account = PublicKey::from(&secret_key).to_account_hash();
+        let approved = ContractHash::new([1; 32]);
+        let other = ContractHash::new([2; 32]);
+
+        let mut access_lists = AccessLists {
+            allowlist: Some(AccessList::default()),
+            denylist: AccessList::default(),
+        };
+        let allowlist = access_lists.allowlist.as_mut().unwrap();
+        let _ = allowlist.contracts.insert(approved);
+
+        assert_eq!(
+            access_lists.check(&deploy_calling(approved, &secret_key)),
+            Ok(())
+        );
+        assert_eq!(
+            access_lists.check(&deploy_calling(other, &secret_key)),
+            Err(AccessDenied::NotAllowlisted)
+        );
+
+        let _ = access_lists.denylist.accounts.insert(account);
+        assert_eq!(
+            access_lists.check(&deploy_calling(approved, &secret_key)),
+            Err(AccessDenied::DenylistedAccount(account))
+        );
+    }
+
+    #[test]
+    fn should_parse_from_toml() {
+        let toml = r#"
+            [allowlist]
+            contracts = ["contract-0101010101010101010101010101010101010101010101010101010101010101"]
+
+            [denylist]
+            accounts = ["account-hash-0202020202020202020202020202020202020202020202020202020202020202"]
+        "#;
+        let access_lists: AccessLists = toml::from_str(toml).unwrap();
+        let allowlist = access_lists.allowlist.unwrap();
+        assert!(allowlist.contracts.contains(&ContractHash::new([1; 32])));
+        assert!(access_lists
+            .denylist
+            .accounts
+            .contains(&AccountHash::new([2; 32])));
+    }
+}
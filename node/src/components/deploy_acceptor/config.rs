@@ -1,28 +1,65 @@
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
+use super::{AccessList, AccessLists};
+
 /// Configuration options for fetching.
-#[derive(Copy, Clone, DataSize, Debug, Deserialize, Serialize)]
+#[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
 pub struct Config {
     verify_accounts: bool,
+    /// If true, deploys from clients are only accepted if the account exists, the deploy's
+    /// approvals meet the account's deployment threshold and its main purse covers the payment.
+    #[serde(default)]
+    check_account_state: bool,
+    /// If set, only deploys from these accounts or calling these contracts are accepted.
+    #[serde(default)]
+    allowlist: Option<AccessList>,
+    /// Deploys from these accounts or calling these contracts are rejected.
+    #[serde(default)]
+    denylist: AccessList,
 }
 
 impl Config {
     /// Constructor for deploy_acceptor config.
     pub fn new(verify_accounts: bool) -> Self {
-        Config { verify_accounts }
+        Config {
+            verify_accounts,
+            ..Config::default()
+        }
     }
 
     /// Get verify_accounts setting.
     pub(crate) fn verify_accounts(&self) -> bool {
         self.verify_accounts
     }
+
+    /// Get check_account_state setting.
+    pub(crate) fn check_account_state(&self) -> bool {
+        self.check_account_state
+    }
+
+    /// Get the allowlist and denylist.
+    pub(crate) fn access_lists(&self) -> AccessLists {
+        AccessLists {
+            allowlist: self.allowlist.clone(),
+            denylist: self.denylist.clone(),
+        }
+    }
+
+    /// Replaces the allowlist and denylist.
+    pub(crate) fn set_access_lists(&mut self, access_lists: AccessLists) {
+        self.allowlist = access_lists.allowlist;
+        self.denylist = access_lists.denylist;
+    }
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
             verify_accounts: true,
+            check_account_state: false,
+            allowlist: None,
+            denylist: AccessList::default(),
         }
     }
 }
@@ -5,10 +5,11 @@ use serde::Serialize;
 use super::Source;
 use crate::{
     components::deploy_acceptor::Error,
-    effect::{announcements::RpcServerAnnouncement, Responder},
-    types::{Deploy, NodeId},
+    effect::{announcements::RpcServerAnnouncement, requests::DeployAcceptorRequest, Responder},
+    types::{BlockHeader, Deploy, NodeId},
 };
-use casper_types::Key;
+use casper_execution_engine::shared::account::Account;
+use casper_types::{Key, U512};
 
 /// `DeployAcceptor` events.
 #[derive(Debug, Serialize)]
@@ -33,6 +34,19 @@ pub enum Event {
         verified: Option<bool>,
         maybe_responder: Option<Responder<Result<(), Error>>>,
     },
+    /// The result of reading the `Account` of a deploy and the balance of its main purse.
+    AccountStateResult {
+        deploy: Box<Deploy>,
+        source: Source<NodeId>,
+        account_key: Key,
+        #[serde(skip_serializing)]
+        maybe_account_and_balance: Option<(Box<Account>, U512)>,
+        maybe_responder: Option<Responder<Result<(), Error>>>,
+    },
+    /// A request made of the `DeployAcceptor`.
+    Request(DeployAcceptorRequest),
+    /// A new block has been added to the linear chain.
+    BlockAdded(Box<BlockHeader>),
 }
 
 impl From<RpcServerAnnouncement> for Event {
@@ -47,6 +61,12 @@ impl From<RpcServerAnnouncement> for Event {
     }
 }
 
+impl From<DeployAcceptorRequest> for Event {
+    fn from(request: DeployAcceptorRequest) -> Self {
+        Event::Request(request)
+    }
+}
+
 impl Display for Event {
     fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
         match self {
@@ -75,6 +95,20 @@ impl Display for Event {
                     account_key
                 )
             }
+            Event::AccountStateResult {
+                deploy,
+                account_key,
+                ..
+            } => write!(
+                formatter,
+                "read state of account {} for deploy {}",
+                account_key,
+                deploy.id()
+            ),
+            Event::Request(request) => write!(formatter, "{}", request),
+            Event::BlockAdded(block_header) => {
+                write!(formatter, "block added at height {}", block_header.height())
+            }
         }
     }
 }
@@ -17,10 +17,14 @@
 //! unintended subscriber disconnects, if a disconnected subscriber re-subscribes before the buffer
 //! has advanced past their last received event.
 //!
+//! Clients interested in a single deploy can instead long-poll the deploy watcher endpoint, which
+//! responds with the deploy's block hash and execution result once it has been processed.
+//!
 //! For details about the SSE model and a list of supported SSEs, see:
 //! <https://github.com/CasperLabs/ceps/blob/master/text/0009-client-api.md#rpcs>
 
 mod config;
+mod deploy_watcher;
 mod event;
 mod http_server;
 mod sse_server;
@@ -44,6 +48,7 @@ use crate::{
     NodeRng,
 };
 pub use config::Config;
+pub use deploy_watcher::DeployExecuted;
 pub(crate) use event::Event;
 pub use sse_server::SseData;
 
@@ -92,17 +97,20 @@ impl EventStreamServer {
             * (100 + ADDITIONAL_PERCENT_FOR_BROADCAST_CHANNEL_SIZE);
         let (broadcaster, new_subscriber_info_receiver, sse_filter) =
             sse_server::create_channels_and_filter(broadcast_channel_size as usize);
+        let (new_watcher_info_receiver, deploy_watcher_filter) =
+            deploy_watcher::create_channel_and_filter();
 
         let (shutdown_sender, shutdown_receiver) = oneshot::channel::<()>();
 
-        let (actual_address, server_with_shutdown) = warp::serve(sse_filter)
-            .try_bind_with_graceful_shutdown(required_address, async {
-                shutdown_receiver.await.ok();
-            })
-            .map_err(|error| ListeningError::Listen {
-                address: required_address,
-                error: Box::new(error),
-            })?;
+        let (actual_address, server_with_shutdown) =
+            warp::serve(sse_filter.or(deploy_watcher_filter))
+                .try_bind_with_graceful_shutdown(required_address, async {
+                    shutdown_receiver.await.ok();
+                })
+                .map_err(|error| ListeningError::Listen {
+                    address: required_address,
+                    error: Box::new(error),
+                })?;
         info!(address=%actual_address, "started event stream server");
 
         tokio::spawn(http_server::run(
@@ -113,6 +121,7 @@ impl EventStreamServer {
             sse_data_receiver,
             broadcaster,
             new_subscriber_info_receiver,
+            new_watcher_info_receiver,
         ));
 
         Ok(EventStreamServer { sse_data_sender })
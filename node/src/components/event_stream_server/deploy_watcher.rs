@@ -0,0 +1,219 @@
+//! Types and functions used by the http server to notify clients watching a single deploy.
+//!
+//! A client issues a long-polling `GET` request for a deploy hash, which is answered with a single
+//! JSON-encoded [`DeployExecuted`] once the deploy has been executed in a finalized block.
+
+use std::{collections::HashMap, convert::Infallible};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, oneshot};
+use tracing::{debug, error};
+use warp::{
+    filters::BoxedFilter,
+    http::StatusCode,
+    reply::{self, Json, WithStatus},
+    Filter, Reply,
+};
+
+use casper_types::ExecutionResult;
+
+use super::SseData;
+use crate::{
+    crypto::hash::Digest,
+    types::{BlockHash, DeployHash},
+};
+
+/// The URL path.
+pub const DEPLOY_WATCHER_API_PATH: &str = "deploys";
+
+/// The notification sent to a client once the deploy it is watching has been executed in a
+/// finalized block.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct DeployExecuted {
+    pub deploy_hash: DeployHash,
+    pub block_hash: BlockHash,
+    pub execution_result: ExecutionResult,
+}
+
+impl DeployExecuted {
+    /// Returns the notification for the given SSE data, if it is a `DeployProcessed` event.
+    pub(super) fn from_sse_data(data: &SseData) -> Option<Self> {
+        match data {
+            SseData::DeployProcessed {
+                deploy_hash,
+                block_hash,
+                execution_result,
+                ..
+            } => Some(DeployExecuted {
+                deploy_hash: **deploy_hash,
+                block_hash: **block_hash,
+                execution_result: (**execution_result).clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Passed to the server whenever a client starts watching a deploy.
+pub(super) struct NewWatcherInfo {
+    /// The hash of the watched deploy.
+    pub(super) deploy_hash: DeployHash,
+    /// A channel to send the notification to the client's handler.
+    pub(super) notification_sender: oneshot::Sender<DeployExecuted>,
+}
+
+/// The clients waiting for deploys to be executed, by deploy hash.
+#[derive(Default)]
+pub(super) struct WatcherRegistry {
+    watchers: HashMap<DeployHash, Vec<oneshot::Sender<DeployExecuted>>>,
+}
+
+impl WatcherRegistry {
+    /// Registers a new watcher, dropping those whose clients have disconnected in the meantime.
+    pub(super) fn register(&mut self, watcher: NewWatcherInfo) {
+        self.watchers.retain(|_, senders| {
+            senders.retain(|sender| !sender.is_closed());
+            !senders.is_empty()
+        });
+        self.watchers
+            .entry(watcher.deploy_hash)
+            .or_default()
+            .push(watcher.notification_sender);
+    }
+
+    /// Sends the notification to all clients watching its deploy, and unregisters them.
+    pub(super) fn notify(&mut self, notification: &DeployExecuted) {
+        if let Some(senders) = self.watchers.remove(&notification.deploy_hash) {
+            debug!(
+                deploy_hash = %notification.deploy_hash,
+                count = senders.len(),
+                "notifying deploy watchers"
+            );
+            for sender in senders {
+                // The client may have disconnected already, so we don't care if this errors.
+                let _ = sender.send(notification.clone());
+            }
+        }
+    }
+
+    /// Returns the number of clients currently watching a deploy.
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.watchers.values().map(Vec::len).sum()
+    }
+}
+
+/// Creates the channel passing new watchers to the server and the warp filter for the deploy
+/// watcher endpoint, e.g. `http://localhost:9999/deploys/<deploy hash in hex>`.
+pub(super) fn create_channel_and_filter() -> (
+    mpsc::UnboundedReceiver<NewWatcherInfo>,
+    BoxedFilter<(impl Reply,)>,
+) {
+    let (new_watcher_info_sender, new_watcher_info_receiver) = mpsc::unbounded_channel();
+
+    let filter = warp::get()
+        .and(warp::path(DEPLOY_WATCHER_API_PATH))
+        .and(warp::path::param::<String>())
+        .and(warp::path::end())
+        .and_then(move |hex_deploy_hash: String| {
+            let new_watcher_info_sender = new_watcher_info_sender.clone();
+            async move {
+                Ok::<_, Infallible>(watch_deploy(new_watcher_info_sender, hex_deploy_hash).await)
+            }
+        })
+        .boxed();
+
+    (new_watcher_info_receiver, filter)
+}
+
+/// Registers a watcher for the given deploy and waits for its notification.
+async fn watch_deploy(
+    new_watcher_info_sender: mpsc::UnboundedSender<NewWatcherInfo>,
+    hex_deploy_hash: String,
+) -> WithStatus<Json> {
+    let deploy_hash = match Digest::from_hex(&hex_deploy_hash) {
+        Ok(digest) => DeployHash::new(digest),
+        Err(error) => {
+            return reply::with_status(
+                reply::json(&format!("invalid deploy hash: {}", error)),
+                StatusCode::BAD_REQUEST,
+            );
+        }
+    };
+
+    let (notification_sender, notification_receiver) = oneshot::channel();
+    let new_watcher_info = NewWatcherInfo {
+        deploy_hash,
+        notification_sender,
+    };
+    if new_watcher_info_sender.send(new_watcher_info).is_err() {
+        error!("failed to send new watcher info");
+    }
+
+    match notification_receiver.await {
+        Ok(notification) => reply::with_status(reply::json(&notification), StatusCode::OK),
+        // The server is shutting down.
+        Err(_) => reply::with_status(
+            reply::json(&"server shutting down"),
+            StatusCode::SERVICE_UNAVAILABLE,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+    use crate::testing::TestRng;
+
+    fn new_watcher(deploy_hash: DeployHash) -> (NewWatcherInfo, oneshot::Receiver<DeployExecuted>) {
+        let (notification_sender, notification_receiver) = oneshot::channel();
+        let watcher = NewWatcherInfo {
+            deploy_hash,
+            notification_sender,
+        };
+        (watcher, notification_receiver)
+    }
+
+    #[test]
+    fn should_notify_only_watchers_of_executed_deploy() {
+        let mut rng = TestRng::new();
+        let deploy_hash = DeployHash::random(&mut rng);
+        let other_deploy_hash = DeployHash::random(&mut rng);
+
+        let mut registry = WatcherRegistry::default();
+        let (watcher1, mut receiver1) = new_watcher(deploy_hash);
+        let (watcher2, mut receiver2) = new_watcher(deploy_hash);
+        let (other_watcher, mut other_receiver) = new_watcher(other_deploy_hash);
+        registry.register(watcher1);
+        registry.register(watcher2);
+        registry.register(other_watcher);
+
+        let notification = DeployExecuted {
+            deploy_hash,
+            block_hash: BlockHash::random(&mut rng),
+            execution_result: rng.gen(),
+        };
+        registry.notify(&notification);
+
+        assert_eq!(receiver1.try_recv().unwrap(), notification);
+        assert_eq!(receiver2.try_recv().unwrap(), notification);
+        assert!(other_receiver.try_recv().is_err());
+        assert_eq!(registry.len(), 1);
+    }
+
+    #[test]
+    fn should_drop_watchers_of_disconnected_clients() {
+        let mut rng = TestRng::new();
+        let mut registry = WatcherRegistry::default();
+
+        let (watcher, receiver) = new_watcher(DeployHash::random(&mut rng));
+        registry.register(watcher);
+        drop(receiver);
+
+        let (watcher, _receiver) = new_watcher(DeployHash::random(&mut rng));
+        registry.register(watcher);
+        assert_eq!(registry.len(), 1);
+    }
+}
@@ -10,6 +10,7 @@ use wheelbuf::WheelBuf;
 use casper_types::ProtocolVersion;
 
 use super::{
+    deploy_watcher::{DeployExecuted, NewWatcherInfo, WatcherRegistry},
     sse_server::{BroadcastChannelMessage, NewSubscriberInfo, ServerSentEvent},
     Config, SseData,
 };
@@ -25,6 +26,8 @@ use super::{
 /// * `new_subscriber_info_receiver` is used to notify the server of the details of a new client
 ///   having subscribed to the event stream.  It allows the server to populate that client's stream
 ///   with the requested number of historical events.
+/// * `new_watcher_info_receiver` is used to notify the server of a client waiting for a deploy to
+///   be processed.  If the deploy is still in the buffer, the client is notified right away.
 pub(super) async fn run(
     config: Config,
     api_version: ProtocolVersion,
@@ -33,6 +36,7 @@ pub(super) async fn run(
     mut data_receiver: mpsc::UnboundedReceiver<SseData>,
     broadcaster: broadcast::Sender<BroadcastChannelMessage>,
     mut new_subscriber_info_receiver: mpsc::UnboundedReceiver<NewSubscriberInfo>,
+    mut new_watcher_info_receiver: mpsc::UnboundedReceiver<NewWatcherInfo>,
 ) {
     let server_joiner = task::spawn(server_with_shutdown);
 
@@ -42,9 +46,10 @@ pub(super) async fn run(
         ServerSentEvent::initial_event(api_version);
         config.event_stream_buffer_length as usize
     ]);
+    let mut watchers = WatcherRegistry::default();
 
-    // Start handling received messages from the two channels; info on new client subscribers and
-    // incoming events announced by node components.
+    // Start handling received messages from the three channels; info on new client subscribers,
+    // info on new deploy watchers and incoming events announced by node components.
     let event_stream_fut = async {
         loop {
             select! {
@@ -69,11 +74,29 @@ pub(super) async fn run(
                     }
                 }
 
+                maybe_new_watcher = new_watcher_info_receiver.recv() => {
+                    if let Some(watcher) = maybe_new_watcher {
+                        let deploy_hash = watcher.deploy_hash;
+                        watchers.register(watcher);
+                        // The deploy may have been processed before the client started watching.
+                        if let Some(notification) = buffer
+                            .iter()
+                            .filter_map(|event| DeployExecuted::from_sse_data(&event.data))
+                            .find(|notification| notification.deploy_hash == deploy_hash)
+                        {
+                            watchers.notify(&notification);
+                        }
+                    }
+                }
+
                 maybe_data = data_receiver.recv() => {
                     match maybe_data {
                         Some(data) => {
                             // Buffer the data and broadcast it to subscribed clients.
                             trace!("Event stream server received {:?}", data);
+                            if let Some(notification) = DeployExecuted::from_sse_data(&data) {
+                                watchers.notify(&notification);
+                            }
                             let event = ServerSentEvent { id: Some(event_index), data };
                             buffer.push(event.clone());
                             let message = BroadcastChannelMessage::ServerSentEvent(event);
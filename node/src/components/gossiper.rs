@@ -4,6 +4,7 @@ mod event;
 mod gossip_table;
 mod message;
 mod metrics;
+mod seen_items;
 mod tests;
 
 use datasize::DataSize;
@@ -22,11 +23,11 @@ use crate::{
     components::Component,
     effect::{
         announcements::GossiperAnnouncement,
-        requests::{NetworkRequest, StorageRequest},
+        requests::{NetworkRequest, StateStoreRequest, StorageRequest},
         EffectBuilder, EffectExt, Effects,
     },
     protocol::Message as NodeMessage,
-    types::{Deploy, DeployHash, Item, NodeId},
+    types::{Deploy, DeployHash, Item, NodeId, Timestamp},
     utils::Source,
     NodeRng,
 };
@@ -36,6 +37,11 @@ pub use event::Event;
 use gossip_table::{GossipAction, GossipTable};
 pub use message::Message;
 use metrics::GossiperMetrics;
+pub use seen_items::SeenItems;
+
+/// The delay between an item finishing being gossiped and the set of finished items being saved to
+/// the state store.
+const PERSIST_SEEN_ITEMS_DELAY: Duration = Duration::from_secs(10);
 
 /// A helper trait whose bounds represent the requirements for a reactor event that `Gossiper` can
 /// work with.
@@ -44,6 +50,7 @@ pub trait ReactorEventT<T>:
     + From<NetworkRequest<NodeId, Message<T>>>
     + From<NetworkRequest<NodeId, NodeMessage>>
     + From<StorageRequest>
+    + From<StateStoreRequest>
     + From<GossiperAnnouncement<T>>
     + Send
     + 'static
@@ -61,6 +68,7 @@ where
         + From<NetworkRequest<NodeId, Message<T>>>
         + From<NetworkRequest<NodeId, NodeMessage>>
         + From<StorageRequest>
+        + From<StateStoreRequest>
         + From<GossiperAnnouncement<T>>
         + Send
         + 'static,
@@ -101,7 +109,11 @@ where
     T: Item + 'static,
     REv: ReactorEventT<T>,
 {
+    /// The snake-case name of the gossiper, used to derive the key of its persisted seen items.
+    name: String,
     table: GossipTable<T::Id>,
+    /// Whether saving the seen items to the state store is already scheduled.
+    persist_scheduled: bool,
     gossip_timeout: Duration,
     get_from_peer_timeout: Duration,
     #[data_size(skip)] // Not well supported by datasize.
@@ -124,25 +136,32 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
     ///
     /// Must be supplied with a name, which should be a snake-case identifier to disambiguate the
     /// specific gossiper from other potentially present gossipers.
+    ///
+    /// The returned effects load the items seen before the node was last stopped.
     pub(crate) fn new_for_partial_items(
         name: &str,
         config: Config,
         get_from_holder: impl Fn(EffectBuilder<REv>, T::Id, NodeId) -> Effects<Event<T>>
             + Send
             + 'static,
+        effect_builder: EffectBuilder<REv>,
         registry: &Registry,
-    ) -> Result<Self, prometheus::Error> {
+    ) -> Result<(Self, Effects<Event<T>>), prometheus::Error> {
         assert!(
             !T::ID_IS_COMPLETE_ITEM,
             "this should only be called for types where T::ID_IS_COMPLETE_ITEM is false"
         );
-        Ok(Gossiper {
+        let gossiper = Gossiper {
+            name: name.to_string(),
             table: GossipTable::new(config),
+            persist_scheduled: false,
             gossip_timeout: Duration::from_secs(config.gossip_request_timeout_secs()),
             get_from_peer_timeout: Duration::from_secs(config.get_remainder_timeout_secs()),
             get_from_holder: Box::new(get_from_holder),
             metrics: GossiperMetrics::new(name, registry)?,
-        })
+        };
+        let effects = gossiper.load_seen_items(effect_builder);
+        Ok((gossiper, effects))
     }
 
     /// Constructs a new gossiper component for use where `T::ID_IS_COMPLETE_ITEM == true`, i.e.
@@ -150,24 +169,78 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
     ///
     /// Must be supplied with a name, which should be a snake-case identifier to disambiguate the
     /// specific gossiper from other potentially present gossipers.
+    ///
+    /// The returned effects load the items seen before the node was last stopped.
     pub(crate) fn new_for_complete_items(
         name: &str,
         config: Config,
+        effect_builder: EffectBuilder<REv>,
         registry: &Registry,
-    ) -> Result<Self, prometheus::Error> {
+    ) -> Result<(Self, Effects<Event<T>>), prometheus::Error> {
         assert!(
             T::ID_IS_COMPLETE_ITEM,
             "this should only be called for types where T::ID_IS_COMPLETE_ITEM is true"
         );
-        Ok(Gossiper {
+        let gossiper = Gossiper {
+            name: name.to_string(),
             table: GossipTable::new(config),
+            persist_scheduled: false,
             gossip_timeout: Duration::from_secs(config.gossip_request_timeout_secs()),
             get_from_peer_timeout: Duration::from_secs(config.get_remainder_timeout_secs()),
             get_from_holder: Box::new(|_, item, _| {
                 panic!("gossiper should never try to get {}", item)
             }),
             metrics: GossiperMetrics::new(name, registry)?,
-        })
+        };
+        let effects = gossiper.load_seen_items(effect_builder);
+        Ok((gossiper, effects))
+    }
+
+    /// Loads the items seen before the node was last stopped from the state store.
+    fn load_seen_items(&self, effect_builder: EffectBuilder<REv>) -> Effects<Event<T>> {
+        effect_builder
+            .load_state(seen_items::state_key(&self.name))
+            .event(Event::SeenItemsLoaded)
+    }
+
+    /// Adds the unexpired items loaded from the state store to the finished entries.
+    fn handle_seen_items_loaded(&mut self, maybe_seen_items: Option<SeenItems<T::Id>>) {
+        if let Some(seen_items) = maybe_seen_items {
+            for (item_id, remaining) in seen_items.into_unexpired(Timestamp::now()) {
+                self.table.restore_finished(item_id, remaining);
+            }
+            debug!(
+                count = self.table.items_finished(),
+                "restored seen items from storage"
+            );
+        }
+    }
+
+    /// Schedules saving the finished items to the state store, unless there are none or saving is
+    /// already scheduled.
+    fn schedule_persist_seen_items(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+    ) -> Effects<Event<T>> {
+        if self.persist_scheduled || self.table.items_finished() == 0 {
+            return Effects::new();
+        }
+        self.persist_scheduled = true;
+        effect_builder
+            .set_timeout(PERSIST_SEEN_ITEMS_DELAY)
+            .event(|_| Event::PersistSeenItems)
+    }
+
+    /// Saves the finished items to the state store.
+    fn persist_seen_items(&mut self, effect_builder: EffectBuilder<REv>) -> Effects<Event<T>> {
+        self.persist_scheduled = false;
+        let seen_items = SeenItems::new(
+            Timestamp::now(),
+            self.table.finished_with_remaining_durations(),
+        );
+        effect_builder
+            .save_state(seen_items::state_key(&self.name), seen_items)
+            .ignore()
     }
 
     /// Handles a new item received from a peer or client for which we should begin gossiping.
@@ -493,7 +566,7 @@ where
         _rng: &mut NodeRng,
         event: Self::Event,
     ) -> Effects<Self::Event> {
-        let effects = match event {
+        let mut effects = match event {
             Event::ItemReceived { item_id, source } => {
                 self.handle_item_received(effect_builder, item_id, source)
             }
@@ -523,7 +596,13 @@ where
                 Ok(item) => self.got_from_holder(effect_builder, item, requester),
                 Err(error) => self.failed_to_get_from_holder(item_id, error),
             },
+            Event::SeenItemsLoaded(maybe_seen_items) => {
+                self.handle_seen_items_loaded(maybe_seen_items);
+                Effects::new()
+            }
+            Event::PersistSeenItems => self.persist_seen_items(effect_builder),
         };
+        effects.extend(self.schedule_persist_seen_items(effect_builder));
         self.update_gossip_table_metrics();
         effects
     }
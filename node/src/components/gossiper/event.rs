@@ -5,7 +5,7 @@ use std::{
 
 use serde::Serialize;
 
-use super::{Item, Message};
+use super::{Item, Message, SeenItems};
 use crate::{
     types::NodeId,
     utils::{DisplayIter, Source},
@@ -40,6 +40,10 @@ pub enum Event<T: Item> {
         requester: NodeId,
         result: Box<Result<T, String>>,
     },
+    /// The seen items persisted before the node was last stopped have been loaded.
+    SeenItemsLoaded(Option<SeenItems<T::Id>>),
+    /// The finished items should be saved to the state store.
+    PersistSeenItems,
 }
 
 impl<T: Item> Display for Event<T> {
@@ -76,6 +80,13 @@ impl<T: Item> Display for Event<T> {
                     write!(formatter, "failed to get {} from holder component", item_id)
                 }
             }
+            Event::SeenItemsLoaded(maybe_seen_items) => match maybe_seen_items {
+                Some(seen_items) => {
+                    write!(formatter, "loaded {} seen items", seen_items.len())
+                }
+                None => write!(formatter, "no seen items found in storage"),
+            },
+            Event::PersistSeenItems => write!(formatter, "persist seen items"),
         }
     }
 }
@@ -152,6 +152,18 @@ impl<T> Timeouts<T> {
         self.values.push((timeout, data_id));
     }
 
+    /// Inserts a timeout which may precede existing ones, keeping the values sorted by timeout.
+    fn insert(&mut self, timeout: Instant, data_id: T) {
+        let index = match self
+            .values
+            .binary_search_by(|(existing_timeout, _data_id)| existing_timeout.cmp(&timeout))
+        {
+            Ok(index) => index,
+            Err(index) => index,
+        };
+        self.values.insert(index, (timeout, data_id));
+    }
+
     fn purge(&mut self, now: &Instant) -> impl Iterator<Item = T> + '_ {
         // The values are sorted by timeout.  Locate the index of the first non-expired one.
         let split_index = match self
@@ -518,6 +530,41 @@ impl<T: Copy + Eq + Hash + Display> GossipTable<T> {
         Some(GossipAction::Noop)
     }
 
+    /// Returns the IDs of all finished entries, each with the duration until it times out.
+    pub(crate) fn finished_with_remaining_durations(&mut self) -> Vec<(T, Duration)> {
+        self.purge_finished();
+        let now = Instant::now();
+        self.finished_timeouts
+            .values
+            .iter()
+            .map(|(timeout, data_id)| {
+                let remaining = if *timeout > now {
+                    timeout.duration_since(now)
+                } else {
+                    Duration::from_secs(0)
+                };
+                (*data_id, remaining)
+            })
+            .collect()
+    }
+
+    /// Adds a finished entry which times out after the given duration, e.g. one which was
+    /// persisted before the node was last stopped.
+    ///
+    /// The duration is capped at the configured `finished_entry_duration`.  Entries already
+    /// known to the table are left unchanged.
+    pub(crate) fn restore_finished(&mut self, data_id: T, remaining: Duration) {
+        if self.finished.contains(&data_id)
+            || self.current.contains_key(&data_id)
+            || self.paused.contains_key(&data_id)
+        {
+            return;
+        }
+        let timeout = Instant::now() + remaining.min(self.finished_entry_duration);
+        let _ = self.finished.insert(data_id);
+        self.finished_timeouts.insert(timeout, data_id);
+    }
+
     /// Retains only those finished entries which still haven't timed out.
     fn purge_finished(&mut self) {
         let now = Instant::now();
@@ -1056,6 +1103,36 @@ mod tests {
         assert!(!gossip_table.paused.contains_key(&data_id));
     }
 
+    #[test]
+    fn should_restore_finished() {
+        let mut rng = crate::new_rng();
+        let node_ids = random_node_ids(&mut rng);
+        let data_id: u64 = rng.gen();
+        let restored_id: u64 = rng.gen();
+
+        let mut gossip_table = GossipTable::new(Config::default());
+
+        // Finish gossiping the data, then restore an entry expiring before it.
+        let _ = gossip_table.new_complete_data(&data_id, None);
+        for node_id in &node_ids[0..EXPECTED_DEFAULT_INFECTION_TARGET] {
+            let _ = gossip_table.we_infected(&data_id, *node_id);
+        }
+        gossip_table.restore_finished(restored_id, Duration::from_secs(1));
+        assert!(gossip_table.finished.contains(&restored_id));
+        assert_eq!(gossip_table.finished_with_remaining_durations().len(), 2);
+
+        // Restored data should be treated as finished.
+        let action = gossip_table.new_partial_data(&restored_id, node_ids[0]);
+        assert_eq!(GossipAction::Noop, action);
+
+        // Time the restored data out and check it has been purged before the other entry.
+        Instant::advance_time(1_001);
+        let finished = gossip_table.finished_with_remaining_durations();
+        assert_eq!(finished.len(), 1);
+        assert_eq!(finished[0].0, data_id);
+        assert!(!gossip_table.finished.contains(&restored_id));
+    }
+
     #[bench]
     fn benchmark_purging(bencher: &mut Bencher) {
         const ENTRY_COUNT: usize = 10_000;
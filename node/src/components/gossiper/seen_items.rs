@@ -0,0 +1,70 @@
+//! The IDs of items which recently finished being gossiped.
+//!
+//! They are persisted in the state store so that a restarting node neither gossips them again nor
+//! requests them from the peers which are still gossiping them.
+
+use std::{borrow::Cow, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{TimeDiff, Timestamp};
+
+/// Returns the key under which the seen items of the gossiper with the given name are persisted in
+/// the state store.
+pub(super) fn state_key(name: &str) -> Cow<'static, [u8]> {
+    Cow::Owned(format!("gossiper_{}_seen_items", name).into_bytes())
+}
+
+/// The IDs of finished items, each with the time until which it should be remembered.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SeenItems<I> {
+    items: Vec<(I, Timestamp)>,
+}
+
+impl<I> SeenItems<I> {
+    /// Creates a new set of seen items from the given IDs, each with the duration for which it
+    /// should still be remembered after `now`.
+    pub(super) fn new(now: Timestamp, items: impl IntoIterator<Item = (I, Duration)>) -> Self {
+        let items = items
+            .into_iter()
+            .map(|(item_id, remaining)| (item_id, now + TimeDiff::from(remaining)))
+            .collect();
+        SeenItems { items }
+    }
+
+    /// Returns the IDs which haven't expired at time `now`, each with the duration for which it
+    /// should still be remembered.
+    pub(super) fn into_unexpired(self, now: Timestamp) -> impl Iterator<Item = (I, Duration)> {
+        self.items
+            .into_iter()
+            .filter(move |(_, expiry)| *expiry > now)
+            .map(move |(item_id, expiry)| (item_id, Duration::from(expiry.saturating_diff(now))))
+    }
+
+    /// Returns the number of seen items, including the ones that have expired.
+    pub(super) fn len(&self) -> usize {
+        self.items.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_drop_expired_items() {
+        let now = Timestamp::now();
+        let seen_items = SeenItems::new(
+            now,
+            vec![
+                (1_u64, Duration::from_secs(10)),
+                (2, Duration::from_secs(20)),
+            ],
+        );
+        assert_eq!(seen_items.len(), 2);
+
+        let later = now + TimeDiff::from_seconds(15);
+        let unexpired: Vec<_> = seen_items.into_unexpired(later).collect();
+        assert_eq!(unexpired, vec![(2, Duration::from_secs(5))]);
+    }
+}
@@ -31,7 +31,10 @@ use crate::{
             ContractRuntimeAnnouncement, ControlAnnouncement, DeployAcceptorAnnouncement,
             GossiperAnnouncement, NetworkAnnouncement, RpcServerAnnouncement,
         },
-        requests::{ConsensusRequest, ContractRuntimeRequest, LinearChainRequest},
+        requests::{
+            ConsensusRequest, ContractRuntimeRequest, FetcherRequest, LinearChainRequest,
+            NetworkInfoRequest, StateStoreRequest,
+        },
         Responder,
     },
     protocol::Message as NodeMessage,
@@ -40,7 +43,7 @@ use crate::{
         network::{Network, NetworkedReactor},
         ConditionCheckReactor, TestRng,
     },
-    types::{Chainspec, Deploy, NodeId, Tag},
+    types::{chainspec::DeployConfig, Chainspec, Deploy, NodeId, Tag},
     utils::{Loadable, WithDir},
     NodeRng,
 };
@@ -89,6 +92,12 @@ impl From<StorageRequest> for Event {
     }
 }
 
+impl From<StateStoreRequest> for Event {
+    fn from(request: StateStoreRequest) -> Self {
+        Event::Storage(storage::Event::from(request))
+    }
+}
+
 impl From<ContractRuntimeRequest> for Event {
     fn from(request: ContractRuntimeRequest) -> Self {
         Event::ContractRuntime(contract_runtime::Event::Request(Box::new(request)))
@@ -119,6 +128,18 @@ impl From<ContractRuntimeAnnouncement> for Event {
     }
 }
 
+impl From<FetcherRequest<NodeId, Deploy>> for Event {
+    fn from(_request: FetcherRequest<NodeId, Deploy>) -> Self {
+        unimplemented!("not implemented for gossiper tests")
+    }
+}
+
+impl From<NetworkInfoRequest<NodeId>> for Event {
+    fn from(_request: NetworkInfoRequest<NodeId>) -> Self {
+        unimplemented!("not implemented for gossiper tests")
+    }
+}
+
 impl Display for Event {
     fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
         match self {
@@ -182,14 +203,22 @@ impl reactor::Reactor for Reactor {
 
         let (storage_config, storage_tempdir) = storage::Config::default_for_tests();
         let storage_withdir = WithDir::new(storage_tempdir.path(), storage_config);
-        let storage =
-            Storage::new(&storage_withdir, None, ProtocolVersion::from_parts(1, 0, 0)).unwrap();
+        let storage = Storage::new(
+            &storage_withdir,
+            None,
+            ProtocolVersion::from_parts(1, 0, 0),
+            registry,
+        )
+        .unwrap();
 
         let contract_runtime_config = contract_runtime::Config::default();
         let contract_runtime = ContractRuntime::new(
             Digest::random(rng),
             None,
+            0,
             ProtocolVersion::from_parts(1, 0, 0),
+            String::from("casper-example"),
+            DeployConfig::default(),
             storage_withdir,
             &contract_runtime_config,
             registry,
@@ -200,10 +229,12 @@ impl reactor::Reactor for Reactor {
             deploy_acceptor::Config::new(false),
             &Chainspec::from_resources("local"),
         );
-        let deploy_gossiper = Gossiper::new_for_partial_items(
+        let effect_builder = EffectBuilder::new(event_queue);
+        let (deploy_gossiper, deploy_gossiper_effects) = Gossiper::new_for_partial_items(
             "deploy_gossiper",
             config,
             get_deploy_from_storage,
+            effect_builder,
             registry,
         )?;
 
@@ -216,7 +247,7 @@ impl reactor::Reactor for Reactor {
             _storage_tempdir: storage_tempdir,
         };
 
-        let effects = Effects::new();
+        let effects = reactor::wrap_effects(Event::DeployGossiper, deploy_gossiper_effects);
 
         Ok((reactor, effects))
     }
@@ -447,6 +478,60 @@ async fn should_gossip() {
     }
 }
 
+#[tokio::test]
+async fn should_not_gossip_across_partition() {
+    const NETWORK_SIZE: usize = 4;
+    const QUIET_FOR: Duration = Duration::from_millis(50);
+    const TIMEOUT: Duration = Duration::from_secs(2);
+
+    NetworkController::<NodeMessage>::create_active();
+    let mut network = Network::<Reactor>::new();
+    let mut rng = crate::new_rng();
+
+    // Split the nodes into two halves which cannot reach each other.
+    let node_ids = network.add_nodes(&mut rng, NETWORK_SIZE).await;
+    NetworkController::<NodeMessage>::partition(vec![
+        node_ids[..NETWORK_SIZE / 2].to_vec(),
+        node_ids[NETWORK_SIZE / 2..].to_vec(),
+    ]);
+
+    // Give the deploy to the first node and let the network settle.
+    let deploy = Box::new(Deploy::random(&mut rng));
+    let deploy_hash = *deploy.id();
+    network
+        .process_injected_effect_on(&node_ids[0], announce_deploy_received(deploy, None))
+        .await;
+
+    let first_half_holds_deploy =
+        |nodes: &HashMap<NodeId, Runner<ConditionCheckReactor<Reactor>>>| {
+            node_ids[..NETWORK_SIZE / 2].iter().all(|node_id| {
+                nodes[node_id]
+                    .reactor()
+                    .inner()
+                    .storage
+                    .get_deploy_by_hash(deploy_hash)
+                    .is_some()
+            })
+        };
+    network
+        .settle_on(&mut rng, first_half_holds_deploy, TIMEOUT)
+        .await;
+    network.settle(&mut rng, QUIET_FOR, TIMEOUT).await;
+
+    // The other half should never have received it.
+    for node_id in &node_ids[NETWORK_SIZE / 2..] {
+        let runner = &network.nodes()[node_id];
+        assert!(runner
+            .reactor()
+            .inner()
+            .storage
+            .get_deploy_by_hash(deploy_hash)
+            .is_none());
+    }
+
+    NetworkController::<NodeMessage>::remove_active();
+}
+
 #[tokio::test]
 async fn should_get_from_alternate_source() {
     const NETWORK_SIZE: usize = 3;
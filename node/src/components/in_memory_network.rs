@@ -3,6 +3,11 @@
 //! The `InMemoryNetwork` represents a full virtual network with flawless connectivity and delivery
 //! by default.
 //!
+//! Imperfect networks can be simulated through the `NetworkController`: nodes can be split into
+//! partitions which cannot reach each other (`NetworkController::partition`), and individual
+//! directed links can be given a drop rate and a delivery delay distribution
+//! (`NetworkController::set_link_conditions`).
+//!
 //! # Setup
 //!
 //! The network itself is managed by a `NetworkController` that can be used to create networking
@@ -286,11 +291,15 @@ use std::{
     convert::Infallible,
     fmt::{self, Display, Formatter},
     sync::{Arc, RwLock},
+    time::Duration,
 };
 
-use rand::seq::IteratorRandom;
+use rand::{seq::IteratorRandom, Rng};
 use serde::Serialize;
-use tokio::sync::mpsc::{self, error::SendError};
+use tokio::{
+    sync::mpsc::{self, error::SendError},
+    time,
+};
 use tracing::{debug, error, info, warn};
 
 use crate::{
@@ -309,6 +318,55 @@ use crate::{
 /// A network.
 type Network<P> = Arc<RwLock<HashMap<NodeId, mpsc::UnboundedSender<(NodeId, P)>>>>;
 
+/// The shared link conditions of a network.
+type SharedLinks = Arc<RwLock<Links>>;
+
+/// Delivery conditions of a directed link between two nodes.
+#[derive(Clone, Debug, Default)]
+pub struct LinkConditions {
+    /// Probability in `[0.0, 1.0]` that a message sent over the link is silently dropped.
+    pub drop_rate: f64,
+    /// Minimum delay before a message sent over the link is delivered.
+    pub min_delay: Duration,
+    /// Maximum delay before a message sent over the link is delivered.
+    ///
+    /// Actual delays are uniformly distributed between `min_delay` and `max_delay`.
+    pub max_delay: Duration,
+}
+
+impl LinkConditions {
+    /// Draws the delivery delay for a single message.
+    fn sample_delay(&self, rng: &mut NodeRng) -> Duration {
+        if self.max_delay <= self.min_delay {
+            return self.min_delay;
+        }
+        let spread = (self.max_delay - self.min_delay).as_micros() as u64;
+        self.min_delay + Duration::from_micros(rng.gen_range(0..=spread))
+    }
+}
+
+/// The partitions and link conditions of a network.
+#[derive(Debug, Default)]
+struct Links {
+    /// The partition each node belongs to.
+    ///
+    /// Nodes in different partitions cannot communicate, nodes not assigned to any partition can
+    /// reach every node.
+    partitions: HashMap<NodeId, usize>,
+    /// Conditions of individual directed links, keyed by `(sender, recipient)`.
+    conditions: HashMap<(NodeId, NodeId), LinkConditions>,
+}
+
+impl Links {
+    /// Returns whether `from` and `to` are in different partitions.
+    fn is_partitioned(&self, from: &NodeId, to: &NodeId) -> bool {
+        match (self.partitions.get(from), self.partitions.get(to)) {
+            (Some(from_partition), Some(to_partition)) => from_partition != to_partition,
+            _ => false,
+        }
+    }
+}
+
 /// An in-memory network events.
 #[derive(Debug, Serialize)]
 pub struct Event<P>(NetworkRequest<NodeId, P>);
@@ -338,6 +396,8 @@ thread_local! {
 pub struct NetworkController<P> {
     /// Channels for network communication.
     nodes: Network<P>,
+    /// Partitions and link conditions, shared by all nodes.
+    links: SharedLinks,
 }
 
 impl<P> NetworkController<P>
@@ -349,6 +409,7 @@ where
         let _ = logging::init();
         NetworkController {
             nodes: Default::default(),
+            links: Default::default(),
         }
     }
 
@@ -427,6 +488,73 @@ where
         })
     }
 
+    /// Splits the nodes of the active network into the given partitions.
+    ///
+    /// Nodes in different partitions can no longer exchange messages, any previous partitioning is
+    /// replaced. Nodes not contained in any partition can still reach all other nodes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal lock has been poisoned, there is no active network or the active
+    /// network is not of the correct message type.
+    pub fn partition(partitions: Vec<Vec<NodeId>>) {
+        Self::with_active_links(|links| {
+            links.partitions = partitions
+                .into_iter()
+                .enumerate()
+                .flat_map(|(index, partition)| {
+                    partition.into_iter().map(move |node_id| (node_id, index))
+                })
+                .collect();
+        })
+    }
+
+    /// Removes all partitions from the active network.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal lock has been poisoned, there is no active network or the active
+    /// network is not of the correct message type.
+    pub fn heal_partitions() {
+        Self::with_active_links(|links| links.partitions.clear())
+    }
+
+    /// Sets the delivery conditions of the directed link from `sender` to `recipient` on the
+    /// active network.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal lock has been poisoned, there is no active network or the active
+    /// network is not of the correct message type.
+    pub fn set_link_conditions(sender: NodeId, recipient: NodeId, conditions: LinkConditions) {
+        Self::with_active_links(|links| {
+            let _ = links.conditions.insert((sender, recipient), conditions);
+        })
+    }
+
+    /// Restores flawless delivery on all links of the active network, leaving partitions intact.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal lock has been poisoned, there is no active network or the active
+    /// network is not of the correct message type.
+    pub fn clear_link_conditions() {
+        Self::with_active_links(|links| links.conditions.clear())
+    }
+
+    /// Runs `f` on the links of the active network.
+    fn with_active_links<F: FnOnce(&mut Links)>(f: F) {
+        ACTIVE_NETWORK.with(|active_network| {
+            let mut active_network = active_network.borrow_mut();
+            let controller = active_network
+                .as_mut()
+                .expect("no active network set")
+                .downcast_mut::<Self>()
+                .expect("active network has wrong message type");
+            f(&mut controller.links.write().expect("poisoned lock"))
+        })
+    }
+
     /// Creates a new networking node with a random node ID.
     ///
     /// Returns the already connected new networking component for new node.
@@ -438,7 +566,12 @@ where
     where
         REv: From<NetworkAnnouncement<NodeId, P>> + Send,
     {
-        InMemoryNetwork::new_with_data(event_queue, NodeId::random(rng), self.nodes.clone())
+        InMemoryNetwork::new_with_data(
+            event_queue,
+            NodeId::random(rng),
+            self.nodes.clone(),
+            self.links.clone(),
+        )
     }
 }
 
@@ -450,6 +583,9 @@ pub struct InMemoryNetwork<P> {
 
     /// The nodes map, contains the incoming channel for each virtual node.
     nodes: Network<P>,
+
+    /// Partitions and link conditions of the network.
+    links: SharedLinks,
 }
 
 impl<P> InMemoryNetwork<P>
@@ -471,6 +607,7 @@ where
         event_queue: EventQueueHandle<REv>,
         node_id: NodeId,
         nodes: Network<P>,
+        links: SharedLinks,
     ) -> Self
     where
         REv: From<NetworkAnnouncement<NodeId, P>> + Send,
@@ -486,7 +623,11 @@ where
 
         tokio::spawn(receiver_task(event_queue, receiver));
 
-        InMemoryNetwork { node_id, nodes }
+        InMemoryNetwork {
+            node_id,
+            nodes,
+            links,
+        }
     }
 
     /// Returns this node's ID.
@@ -498,21 +639,59 @@ where
 
 impl<P> InMemoryNetwork<P>
 where
-    P: Display,
+    P: 'static + Send + Display,
 {
     /// Internal helper, sends a payload to a node, ignoring but logging all errors.
+    ///
+    /// Applies the partitions and link conditions of the network, so the payload may be dropped or
+    /// delivered with a delay.
     fn send(
         &self,
         nodes: &HashMap<NodeId, mpsc::UnboundedSender<(NodeId, P)>>,
         dest: NodeId,
         payload: P,
+        rng: &mut NodeRng,
     ) {
         if dest == self.node_id {
             panic!("can't send message to self");
         }
 
+        let conditions = match self.links.read() {
+            Ok(links) => {
+                if links.is_partitioned(&self.node_id, &dest) {
+                    debug!(%dest, %payload, "dropping message across partition");
+                    return;
+                }
+                links.conditions.get(&(self.node_id, dest)).cloned()
+            }
+            Err(_) => {
+                error!("links lock has been poisoned");
+                return;
+            }
+        };
+
         match nodes.get(&dest) {
             Some(sender) => {
+                if let Some(conditions) = conditions {
+                    if rng.gen_bool(conditions.drop_rate.max(0.0).min(1.0)) {
+                        debug!(%dest, %payload, "dropping message (simulated loss)");
+                        return;
+                    }
+
+                    let delay = conditions.sample_delay(rng);
+                    if delay > Duration::from_secs(0) {
+                        let sender = sender.clone();
+                        let node_id = self.node_id;
+                        tokio::spawn(async move {
+                            time::sleep(delay).await;
+                            if let Err(SendError((_, msg))) = sender.send((node_id, payload)) {
+                                warn!(%dest, %msg, "could not send delayed message (send error)");
+                            }
+                        });
+                        return;
+                    }
+                }
+
                 if let Err(SendError((_, msg))) = sender.send((self.node_id, payload)) {
                     warn!(%dest, %msg, "could not send message (send error)");
 
@@ -526,14 +705,14 @@ where
 
 impl<P, REv> Component<REv> for InMemoryNetwork<P>
 where
-    P: Display + Clone,
+    P: 'static + Send + Display + Clone,
 {
     type Event = Event<P>;
     type ConstructionError = Infallible;
 
     fn handle_event(
         &mut self,
-        _effect_builder: EffectBuilder<REv>,
+        effect_builder: EffectBuilder<REv>,
         rng: &mut NodeRng,
         Event(event): Self::Event,
     ) -> Effects<Self::Event> {
@@ -548,7 +727,7 @@ where
                 }
 
                 if let Ok(guard) = self.nodes.read() {
-                    self.send(&guard, *dest, *payload);
+                    self.send(&guard, *dest, *payload, rng);
                 } else {
                     error!("network lock has been poisoned")
                 };
@@ -558,7 +737,7 @@ where
             NetworkRequest::Broadcast { payload, responder } => {
                 if let Ok(guard) = self.nodes.read() {
                     for dest in guard.keys().filter(|&node_id| node_id != &self.node_id) {
-                        self.send(&guard, *dest, *payload.clone());
+                        self.send(&guard, *dest, *payload.clone(), rng);
                     }
                 } else {
                     error!("network lock has been poisoned")
@@ -582,7 +761,7 @@ where
                         .collect();
                     // Not terribly efficient, but will always get us the maximum amount of nodes.
                     for dest in chosen.iter() {
-                        self.send(&guard, *dest, *payload.clone());
+                        self.send(&guard, *dest, *payload.clone(), rng);
                     }
                     responder.respond(chosen).ignore()
                 } else {
@@ -590,6 +769,23 @@ where
                     responder.respond(Default::default()).ignore()
                 }
             }
+            // The in-memory network doesn't know which nodes are validators, so it gossips to
+            // random nodes instead.
+            NetworkRequest::ValidatorGossip {
+                payload,
+                count,
+                responder,
+                ..
+            } => self.handle_event(
+                effect_builder,
+                rng,
+                Event(NetworkRequest::Gossip {
+                    payload,
+                    count,
+                    exclude: HashSet::new(),
+                    responder,
+                }),
+            ),
         }
     }
 }
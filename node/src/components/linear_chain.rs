@@ -19,13 +19,14 @@ use self::{
 use super::Component;
 use crate::{
     effect::{
-        announcements::LinearChainAnnouncement,
+        announcements::{ControlAnnouncement, LinearChainAnnouncement},
         requests::{
             ChainspecLoaderRequest, ContractRuntimeRequest, LinearChainRequest, NetworkRequest,
             StorageRequest,
         },
         EffectBuilder, EffectExt, EffectResultExt, Effects,
     },
+    fatal,
     protocol::Message,
     types::BlockByHeight,
     NodeRng,
@@ -79,12 +80,22 @@ where
             Outcome::StoreBlockSignatures(block_signatures) => effect_builder
                 .put_signatures_to_storage(block_signatures)
                 .ignore(),
-            Outcome::StoreExecutionResults(block_hash, execution_results) => effect_builder
-                .put_execution_results_to_storage(block_hash, execution_results)
-                .ignore(),
-            Outcome::StoreBlock(block) => effect_builder
-                .put_block_to_storage(block.clone())
-                .event(move |_| Event::PutBlockResult { block }),
+            Outcome::StoreExecutedBlock(block, execution_results, contract_events) => {
+                let block_hash = *block.hash();
+                effect_builder
+                    .put_executed_block_to_storage(
+                        block.clone(),
+                        execution_results,
+                        contract_events,
+                    )
+                    .result(
+                        move |_| Event::PutBlockResult { block },
+                        move |error| Event::PutBlockFailed {
+                            block_hash,
+                            error: Box::new(error),
+                        },
+                    )
+            }
             Outcome::Gossip(fs) => {
                 let message = Message::FinalitySignature(fs);
                 effect_builder.broadcast_message(message).ignore()
@@ -128,6 +139,7 @@ where
         + From<LinearChainAnnouncement>
         + From<ContractRuntimeRequest>
         + From<ChainspecLoaderRequest>
+        + From<ControlAnnouncement>
         + Send,
     I: Display + Send + 'static,
 {
@@ -182,10 +194,13 @@ where
             Event::NewLinearChainBlock {
                 block,
                 execution_results,
+                contract_events,
             } => {
-                let outcomes = self
-                    .linear_chain_state
-                    .handle_new_block(block, execution_results);
+                let outcomes = self.linear_chain_state.handle_new_block(
+                    block,
+                    execution_results,
+                    contract_events,
+                );
                 outcomes_to_effects(effect_builder, outcomes)
             }
             Event::PutBlockResult { block } => {
@@ -196,6 +211,13 @@ where
                 let outcomes = self.linear_chain_state.handle_put_block(block);
                 outcomes_to_effects(effect_builder, outcomes)
             }
+            Event::PutBlockFailed { block_hash, error } => fatal!(
+                effect_builder,
+                "failed to store executed block {}: {}",
+                block_hash,
+                error
+            )
+            .ignore(),
             Event::FinalitySignatureReceived(fs, gossiped) => {
                 let outcomes = self
                     .linear_chain_state
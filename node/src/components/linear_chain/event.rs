@@ -7,8 +7,11 @@ use casper_types::ExecutionResult;
 use derive_more::From;
 
 use crate::{
+    components::storage,
     effect::requests::LinearChainRequest,
-    types::{Block, BlockSignatures, DeployHash, FinalitySignature},
+    types::{
+        Block, BlockHash, BlockSignatures, ContractEventRecord, DeployHash, FinalitySignature,
+    },
 };
 
 #[derive(Debug, From)]
@@ -22,6 +25,8 @@ pub enum Event<I> {
         block: Box<Block>,
         /// The deploys' execution results.
         execution_results: HashMap<DeployHash, ExecutionResult>,
+        /// The events emitted by contracts while executing the block.
+        contract_events: Vec<ContractEventRecord>,
     },
     /// Linear chain block we already know but we may refinalize it when syncing protocol state.
     KnownLinearChainBlock(Box<Block>),
@@ -33,6 +38,13 @@ pub enum Event<I> {
         /// The block.
         block: Box<Block>,
     },
+    /// Storing a block failed.
+    PutBlockFailed {
+        /// The hash of the block.
+        block_hash: BlockHash,
+        /// The storage error.
+        error: Box<storage::Error>,
+    },
     /// The result of requesting finality signatures from storage to add pending signatures.
     GetStoredFinalitySignaturesResult(Box<FinalitySignature>, Option<Box<BlockSignatures>>),
     /// Result of testing if creator of the finality signature is bonded validator.
@@ -55,6 +67,13 @@ impl<I: Display> Display for Event<I> {
                 fs.block_hash, fs.public_key, gossiped
             ),
             Event::PutBlockResult { .. } => write!(f, "linear-chain put-block result"),
+            Event::PutBlockFailed { block_hash, error } => {
+                write!(
+                    f,
+                    "linear-chain put-block failed for {}: {}",
+                    block_hash, error
+                )
+            }
             Event::GetStoredFinalitySignaturesResult(finality_signature, maybe_signatures) => {
                 write!(
                     f,
@@ -6,7 +6,9 @@ use tracing::{debug, warn};
 
 use crate::{
     crypto::hash::Digest,
-    types::{Block, BlockHash, BlockSignatures, DeployHash, FinalitySignature},
+    types::{
+        Block, BlockHash, BlockSignatures, ContractEventRecord, DeployHash, FinalitySignature,
+    },
 };
 use casper_types::{ExecutionResult, ProtocolVersion};
 
@@ -30,10 +32,12 @@ pub(crate) struct LinearChain {
 pub(super) enum Outcome {
     // Store block signatures to storage.
     StoreBlockSignatures(BlockSignatures),
-    // Store execution results to storage.
-    StoreExecutionResults(BlockHash, HashMap<DeployHash, ExecutionResult>),
-    // Store block.
-    StoreBlock(Box<Block>),
+    // Store block together with the execution results of its deploys and its contract events.
+    StoreExecutedBlock(
+        Box<Block>,
+        HashMap<DeployHash, ExecutionResult>,
+        Vec<ContractEventRecord>,
+    ),
     // Read finality signatures for the block from storage.
     LoadSignatures(Box<FinalitySignature>),
     // Gossip finality signature to peers.
@@ -223,6 +227,7 @@ impl LinearChain {
         &mut self,
         block: Box<Block>,
         execution_results: HashMap<DeployHash, ExecutionResult>,
+        contract_events: Vec<ContractEventRecord>,
     ) -> Outcomes {
         let mut outcomes = vec![];
         let signatures = self.new_block(&*block);
@@ -239,11 +244,10 @@ impl LinearChain {
                 outcomes.push(Outcome::AnnounceSignature(signature.take()));
             }
         };
-        let block_hash = *block.hash();
-        outcomes.push(Outcome::StoreBlock(block));
-        outcomes.push(Outcome::StoreExecutionResults(
-            block_hash,
+        outcomes.push(Outcome::StoreExecutedBlock(
+            block,
             execution_results,
+            contract_events,
         ));
         outcomes
     }
@@ -394,14 +398,16 @@ mod tests {
         let block = Block::random(&mut rng);
         let execution_results = HashMap::new();
         let new_block_outcomes =
-            lc.handle_new_block(Box::new(block.clone()), execution_results.clone());
-        let block_hash = *block.hash();
+            lc.handle_new_block(Box::new(block.clone()), execution_results.clone(), vec![]);
         match &*new_block_outcomes {
-            [Outcome::StoreBlock(outcome_block), Outcome::StoreExecutionResults(outcome_block_hash, outcome_execution_results)] =>
-            {
+            [Outcome::StoreExecutedBlock(
+                outcome_block,
+                outcome_execution_results,
+                outcome_contract_events,
+            )] => {
                 assert_eq!(&**outcome_block, &block);
-                assert_eq!(outcome_block_hash, &block_hash);
                 assert_eq!(outcome_execution_results, &execution_results);
+                assert!(outcome_contract_events.is_empty());
             }
             others => panic!("unexpected outcome: {:?}", others),
         }
@@ -452,13 +458,10 @@ mod tests {
         let _sig_c = add_pending(&mut lc, block_hash, block_era, false);
 
         let execution_results = HashMap::new();
-        let outcomes = lc.handle_new_block(Box::new(block), execution_results);
+        let outcomes = lc.handle_new_block(Box::new(block), execution_results, vec![]);
         // None of the signatures' creators have been confirmed to be bonded yet.
         // We should not gossip/store/announce any signatures yet.
-        assert!(matches!(
-            &*outcomes,
-            [Outcome::StoreBlock(_), Outcome::StoreExecutionResults(_, _)]
-        ));
+        assert!(matches!(&*outcomes, [Outcome::StoreExecutedBlock(_, _, _)]));
     }
 
     // Check that `left` is a subset of `right`.
@@ -496,7 +499,8 @@ mod tests {
         mark_bonded(&mut lc, sig_a.clone());
         mark_bonded(&mut lc, sig_b.clone());
         let execution_results = HashMap::new();
-        let outcomes = lc.handle_new_block(Box::new(block.clone()), execution_results.clone());
+        let outcomes =
+            lc.handle_new_block(Box::new(block.clone()), execution_results.clone(), vec![]);
         // `sig_a` and `sig_b` are valid and created by bonded validators.
         let expected_outcomes = {
             let mut tmp = vec![];
@@ -508,10 +512,10 @@ mod tests {
             tmp.push(Outcome::Gossip(Box::new(sig_a.clone())));
             tmp.push(Outcome::AnnounceSignature(Box::new(sig_a.clone())));
             tmp.push(Outcome::AnnounceSignature(Box::new(sig_b.clone())));
-            tmp.push(Outcome::StoreBlock(Box::new(block)));
-            tmp.push(Outcome::StoreExecutionResults(
-                block_hash,
+            tmp.push(Outcome::StoreExecutedBlock(
+                Box::new(block),
                 execution_results,
+                vec![],
             ));
             tmp
         };
@@ -653,11 +657,13 @@ mod tests {
         let block = Block::random_with_specifics(&mut rng, EraId::new(3), 10, false);
         let block_hash = *block.hash();
         let block_era = block.header().era_id();
-        let put_block_outcomes = lc.handle_new_block(Box::new(block.clone()), HashMap::new());
-        let expected_outcomes = vec![
-            Outcome::StoreBlock(Box::new(block)),
-            Outcome::StoreExecutionResults(block_hash, HashMap::new()),
-        ];
+        let put_block_outcomes =
+            lc.handle_new_block(Box::new(block.clone()), HashMap::new(), vec![]);
+        let expected_outcomes = vec![Outcome::StoreExecutedBlock(
+            Box::new(block),
+            HashMap::new(),
+            vec![],
+        )];
         // Verify that all outcomes are expected.
         assert_equal(expected_outcomes, put_block_outcomes);
         let valid_sig = FinalitySignature::random_for_block(block_hash, block_era.value());
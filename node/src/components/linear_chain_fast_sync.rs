@@ -54,6 +54,7 @@ impl<I: Clone + PartialEq + 'static> LinearChainFastSync<I> {
         _highest_block: Option<Block>,
         genesis_validator_weights: BTreeMap<PublicKey, U512>,
         _next_upgrade_activation_point: Option<ActivationPoint>,
+        _sync_global_state: bool,
     ) -> Result<(Self, Effects<Event<I>>), Err>
     where
         Err: From<prometheus::Error> + From<storage::Error>,
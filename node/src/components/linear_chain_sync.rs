@@ -10,9 +10,21 @@
 //! 4. Repeat steps 2-3 until trusted hash is reached.
 //! 5. Transition to `SyncingDescendants` state.
 //! 6. Fetch child block of highest block.
-//! 7. Fetch deploys of that block.
-//! 8. Execute that block.
-//! 9. Repeat steps 6-8 as long as there's a child in the linear chain.
+//! 7. Fetch finality signatures of that block and verify them against the validators of its era.
+//! 8. Fetch deploys of that block.
+//! 9. Execute that block.
+//! 10. Repeat steps 6-9 as long as there's a child in the linear chain.
+//!
+//! Blocks up to the trusted hash are verified by following the chain of parent hashes down from
+//! it. Descendants of the trusted block cannot be verified that way, so each of them is only
+//! accepted once validators holding more than the finality threshold fraction of its era's
+//! weight have signed it. The validator set of each era is taken from the switch block of the
+//! previous era, which has already been verified and stored by then.
+//!
+//! With global state syncing enabled, steps 2-4 are replaced: the global state under the trusted
+//! block is downloaded from peers by the chain synchronizer, and the blocks up to the trusted hash
+//! are stored without being executed.  Should the state sync fail, the node falls back to
+//! executing the blocks.
 //!
 //! The order of "download block – download deploys – execute" block steps differ,
 //! in order to increase the chances of catching up with the linear chain quicker.
@@ -29,25 +41,36 @@ mod peers;
 mod state;
 mod traits;
 
-use std::{collections::BTreeMap, convert::Infallible, fmt::Display, mem, str::FromStr};
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::Infallible,
+    fmt::Display,
+    mem,
+    str::FromStr,
+};
 
 use datasize::DataSize;
+use num_rational::Ratio;
 use prometheus::Registry;
+use thiserror::Error;
 use tracing::{error, info, trace, warn};
 
 use self::event::{BlockByHashResult, DeploysResult};
 use casper_types::{EraId, ProtocolVersion, PublicKey, U512};
 
 use super::{
+    chain_synchronizer,
     fetcher::FetchResult,
     storage::{self, Storage},
     Component,
 };
 use crate::{
+    crypto,
     effect::{EffectBuilder, EffectExt, EffectOptionExt, Effects},
     fatal,
     types::{
-        ActivationPoint, Block, BlockByHeight, BlockHash, Chainspec, FinalizedBlock, TimeDiff,
+        ActivationPoint, Block, BlockByHeight, BlockHash, BlockHeader, BlockSignatures, Chainspec,
+        FinalizedBlock, TimeDiff,
     },
     NodeRng,
 };
@@ -80,6 +103,54 @@ pub(crate) struct LinearChainSync<I> {
     started_syncing: bool,
     /// The protocol version the node is currently running with.
     protocol_version: ProtocolVersion,
+    /// The validators of the genesis era, used to verify the finality signatures of its blocks.
+    genesis_validator_weights: BTreeMap<PublicKey, U512>,
+    /// The era of the chain's first block: non-zero if the network was initialized from a genesis
+    /// snapshot.
+    genesis_era_id: EraId,
+    /// The height of the chain's first block.
+    genesis_height: u64,
+    /// The fraction of an era's validator weight which has to sign a block downloaded past the
+    /// trusted hash before it is accepted.
+    #[data_size(skip)]
+    finality_threshold_fraction: Ratio<u64>,
+    /// Whether to download the global state under the trusted block instead of executing the
+    /// blocks up to it.
+    sync_global_state: bool,
+}
+
+/// Error returned when the finality signatures of a downloaded block fail verification.
+#[derive(Debug, Error)]
+pub(crate) enum FinalitySignaturesError {
+    /// The signatures are for a different block or era.
+    #[error(
+        "signatures are for block {signed_hash} in era {signed_era}, \
+         expected block {block_hash} in era {era_id}"
+    )]
+    BlockMismatch {
+        block_hash: BlockHash,
+        era_id: EraId,
+        signed_hash: BlockHash,
+        signed_era: EraId,
+    },
+    /// One of the signatures is invalid.
+    #[error("invalid finality signature: {0}")]
+    InvalidSignature(crypto::Error),
+    /// A signature was made by a key which is not a validator in the block's era.
+    #[error("{public_key} is not a validator in era {era_id}")]
+    UnknownValidator {
+        public_key: PublicKey,
+        era_id: EraId,
+    },
+    /// The signers don't hold enough weight for the block to be considered final.
+    #[error(
+        "signed weight {signed_weight} of total weight {total_weight} is below the finality \
+         threshold"
+    )]
+    InsufficientWeight {
+        signed_weight: U512,
+        total_weight: U512,
+    },
 }
 
 impl<I: Clone + PartialEq + 'static> LinearChainSync<I> {
@@ -92,8 +163,9 @@ impl<I: Clone + PartialEq + 'static> LinearChainSync<I> {
         storage: &Storage,
         init_hash: Option<BlockHash>,
         highest_block: Option<Block>,
-        _genesis_validator_weights: BTreeMap<PublicKey, U512>,
+        genesis_validator_weights: BTreeMap<PublicKey, U512>,
         next_upgrade_activation_point: Option<ActivationPoint>,
+        sync_global_state: bool,
     ) -> Result<(Self, Effects<Event<I>>), Err>
     where
         REv: From<Event<I>> + Send,
@@ -112,6 +184,8 @@ impl<I: Clone + PartialEq + 'static> LinearChainSync<I> {
                 state,
                 next_upgrade_activation_point,
                 protocol_version,
+                genesis_validator_weights,
+                sync_global_state,
             )?;
             Ok((linear_chain_sync, timeout_event))
         } else {
@@ -141,6 +215,11 @@ impl<I: Clone + PartialEq + 'static> LinearChainSync<I> {
                 shortest_era,
                 started_syncing: false,
                 protocol_version,
+                genesis_validator_weights,
+                genesis_era_id: chainspec.protocol_config.genesis_era_id(),
+                genesis_height: chainspec.protocol_config.genesis_height(),
+                finality_threshold_fraction: chainspec.highway_config.finality_threshold_fraction,
+                sync_global_state,
             };
             Ok((linear_chain_sync, timeout_event))
         }
@@ -153,6 +232,8 @@ impl<I: Clone + PartialEq + 'static> LinearChainSync<I> {
         state: State,
         next_upgrade_activation_point: Option<ActivationPoint>,
         protocol_version: ProtocolVersion,
+        genesis_validator_weights: BTreeMap<PublicKey, U512>,
+        sync_global_state: bool,
     ) -> Result<Self, prometheus::Error> {
         let state_key = create_state_key(chainspec);
         info!(?state, "reusing previous state");
@@ -173,6 +254,11 @@ impl<I: Clone + PartialEq + 'static> LinearChainSync<I> {
             shortest_era,
             started_syncing: false,
             protocol_version,
+            genesis_validator_weights,
+            genesis_era_id: chainspec.protocol_config.genesis_era_id(),
+            genesis_height: chainspec.protocol_config.genesis_height(),
+            finality_threshold_fraction: chainspec.highway_config.finality_threshold_fraction,
+            sync_global_state,
         })
     }
 
@@ -196,6 +282,12 @@ impl<I: Clone + PartialEq + 'static> LinearChainSync<I> {
         self.stop_for_upgrade
     }
 
+    /// Returns `true` if the block is the first block of the chain, which may have been
+    /// initialized from a genesis snapshot rather than starting in era 0 at height 0.
+    fn is_first_block(&self, header: &BlockHeader) -> bool {
+        header.era_id() == self.genesis_era_id && header.height() == self.genesis_height
+    }
+
     fn block_downloaded<REv>(
         &mut self,
         rng: &mut NodeRng,
@@ -216,14 +308,32 @@ impl<I: Clone + PartialEq + 'static> LinearChainSync<I> {
             }
             State::SyncingTrustedHash {
                 highest_block_header,
+                linear_chain,
                 ..
             } => {
                 let should_start_downloading_deploys = highest_block_header
                     .as_ref()
                     .map(|hdr| hdr.hash() == *block.header().parent_hash())
                     .unwrap_or(false)
-                    || block.header().is_genesis_child();
-                if should_start_downloading_deploys {
+                    || self.is_first_block(block.header());
+                // The trusted block is the first one downloaded.
+                let maybe_trusted_block = linear_chain.first();
+                if should_start_downloading_deploys && self.sync_global_state {
+                    let state_root_hash = match maybe_trusted_block {
+                        Some(trusted_block) => *trusted_block.state_root_hash(),
+                        None => {
+                            error!("linear chain downloaded without the trusted block");
+                            return fatal!(effect_builder, "trusted block missing").ignore();
+                        }
+                    };
+                    info!(
+                        %state_root_hash,
+                        "linear chain downloaded. Start syncing global state."
+                    );
+                    effect_builder
+                        .sync_trie(state_root_hash)
+                        .event(Event::GlobalStateSynced)
+                } else if should_start_downloading_deploys {
                     info!("linear chain downloaded. Start downloading deploys.");
                     effect_builder
                         .immediately()
@@ -240,6 +350,69 @@ impl<I: Clone + PartialEq + 'static> LinearChainSync<I> {
         }
     }
 
+    /// Handles the result of syncing the global state under the trusted block.
+    ///
+    /// On success, the downloaded blocks are stored without executing them, and syncing continues
+    /// as if the trusted block had just been executed.  On failure, the blocks are executed
+    /// instead.
+    fn global_state_synced<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        result: Result<(), chain_synchronizer::Error>,
+    ) -> Effects<Event<I>>
+    where
+        I: Send + 'static,
+        REv: ReactorEventT<I>,
+    {
+        if let Err(error) = result {
+            warn!(%error, "failed to sync global state. executing the linear chain instead");
+            return effect_builder
+                .immediately()
+                .event(|_| Event::StartDownloadingDeploys);
+        }
+        let blocks = match &mut self.state {
+            State::SyncingTrustedHash {
+                linear_chain,
+                latest_block,
+                ..
+            } => {
+                let blocks = mem::take(linear_chain);
+                match blocks.first() {
+                    Some(trusted_block) => {
+                        latest_block.replace(trusted_block.clone());
+                    }
+                    None => {
+                        error!("global state synced without the trusted block");
+                        return fatal!(effect_builder, "trusted block missing").ignore();
+                    }
+                }
+                blocks
+            }
+            State::None | State::SyncingDescendants { .. } | State::Done(_) => {
+                error!(state=?self.state, "global state synced when in incorrect state.");
+                return fatal!(effect_builder, "global state synced in incorrect state").ignore();
+            }
+        };
+        if let Some(switch_block) = blocks.iter().find(|block| block.header().is_switch_block()) {
+            self.state.new_switch_block(switch_block);
+        }
+        info!(
+            block_count = blocks.len(),
+            "global state synced. storing linear chain without executing it"
+        );
+        let trusted_block = Box::new(blocks[0].clone());
+        async move {
+            // Store the lowest blocks first, so that the stored chain has no gaps if we are
+            // interrupted.
+            for block in blocks.into_iter().rev() {
+                effect_builder
+                    .put_executed_block_to_storage(Box::new(block), HashMap::new(), Vec::new())
+                    .await;
+            }
+        }
+        .event(move |_| Event::BlockHandled(trusted_block))
+    }
+
     fn mark_done(&mut self, latest_block: Option<Block>) {
         let latest_block = latest_block.map(Box::new);
         self.state = State::Done(latest_block);
@@ -647,9 +820,107 @@ where
                                 ),
                             );
                         }
+                        // The block is not linked to the trusted hash, so we only accept it once
+                        // we have verified its finality signatures.
+                        let genesis_validator_weights =
+                            if block.header().era_id() == self.genesis_era_id {
+                                Some(self.genesis_validator_weights.clone())
+                            } else {
+                                None
+                            };
+                        fetch_finality_signatures(
+                            effect_builder,
+                            peer,
+                            *block,
+                            genesis_validator_weights,
+                        )
+                    }
+                }
+            }
+            Event::GetFinalitySignaturesResult {
+                block,
+                peer,
+                validator_weights,
+                maybe_signatures,
+            } => {
+                let block_height = block.height();
+                let era_id = block.header().era_id();
+                let validator_weights = match validator_weights {
+                    Some(validator_weights) => validator_weights,
+                    None => {
+                        error!(
+                            %block_height, %era_id,
+                            "validators of the era are unknown. \
+                            cannot verify finality signatures"
+                        );
+                        return fatal!(
+                            effect_builder,
+                            "failed to verify finality signatures of block at height {}: \
+                             validators of era {} unknown",
+                            block_height,
+                            era_id
+                        )
+                        .ignore();
+                    }
+                };
+                let signatures = match maybe_signatures {
+                    Some(signatures) => signatures,
+                    None => {
+                        trace!(
+                            %block_height, %peer,
+                            "failed to download finality signatures. Trying next peer"
+                        );
+                        return self.handle_event(
+                            effect_builder,
+                            rng,
+                            Event::GetBlockHeightResult(
+                                block_height,
+                                BlockByHeightResult::Absent(peer),
+                            ),
+                        );
+                    }
+                };
+                match check_finality_signatures(
+                    &block,
+                    &signatures,
+                    &validator_weights,
+                    self.finality_threshold_fraction,
+                ) {
+                    Ok(()) => {
                         self.peers.success(peer);
                         self.block_downloaded(rng, effect_builder, &block)
                     }
+                    // A peer which has not yet collected enough signatures is not misbehaving.
+                    Err(error @ FinalitySignaturesError::InsufficientWeight { .. }) => {
+                        info!(
+                            %block_height, %peer, %error,
+                            "block not yet final. Trying next peer"
+                        );
+                        self.handle_event(
+                            effect_builder,
+                            rng,
+                            Event::GetBlockHeightResult(
+                                block_height,
+                                BlockByHeightResult::Absent(peer),
+                            ),
+                        )
+                    }
+                    Err(error) => {
+                        warn!(
+                            %block_height, %peer, %error,
+                            "finality signatures verification failed"
+                        );
+                        // NOTE: Signal misbehaving validator to networking layer.
+                        self.peers.ban(&peer);
+                        self.handle_event(
+                            effect_builder,
+                            rng,
+                            Event::GetBlockHeightResult(
+                                block_height,
+                                BlockByHeightResult::Absent(peer),
+                            ),
+                        )
+                    }
                 }
             }
             Event::GetBlockHashResult(block_hash, fetch_result) => {
@@ -763,6 +1034,7 @@ where
                     }
                 }
             }
+            Event::GlobalStateSynced(result) => self.global_state_synced(effect_builder, result),
             Event::StartDownloadingDeploys => {
                 // Start downloading deploys from the first block of the linear chain.
                 self.peers.reset(rng);
@@ -901,6 +1173,97 @@ where
         )
 }
 
+/// Fetches the finality signatures of `block` from `peer`, along with the weights of the
+/// validators of the block's era.
+///
+/// The validators are read from the switch block of the previous era in storage, unless
+/// `genesis_validator_weights` are given for a block in the genesis era.
+fn fetch_finality_signatures<I: Send + Clone + 'static, REv>(
+    effect_builder: EffectBuilder<REv>,
+    peer: I,
+    block: Block,
+    genesis_validator_weights: Option<BTreeMap<PublicKey, U512>>,
+) -> Effects<Event<I>>
+where
+    REv: ReactorEventT<I>,
+{
+    async move {
+        let validator_weights = match genesis_validator_weights {
+            Some(validator_weights) => Some(validator_weights),
+            None => effect_builder
+                .get_key_block_header_for_era_id_from_storage(block.header().era_id())
+                .await
+                .and_then(|key_block_header| {
+                    key_block_header.next_era_validator_weights().cloned()
+                }),
+        };
+        let maybe_signatures = effect_builder
+            .fetch_block_header_with_metadata(block.height(), peer.clone())
+            .await
+            .map(|fetch_result| match fetch_result {
+                FetchResult::FromStorage(item) | FetchResult::FromPeer(item, _) => {
+                    Box::new(item.block_signatures)
+                }
+            });
+        (block, peer, validator_weights, maybe_signatures)
+    }
+    .event(|(block, peer, validator_weights, maybe_signatures)| {
+        Event::GetFinalitySignaturesResult {
+            block: Box::new(block),
+            peer,
+            validator_weights,
+            maybe_signatures,
+        }
+    })
+}
+
+/// Checks that `signatures` are valid signatures of `block`, made by validators holding more than
+/// `finality_threshold_fraction` of the total weight in `validator_weights`.
+fn check_finality_signatures(
+    block: &Block,
+    signatures: &BlockSignatures,
+    validator_weights: &BTreeMap<PublicKey, U512>,
+    finality_threshold_fraction: Ratio<u64>,
+) -> Result<(), FinalitySignaturesError> {
+    let block_hash = *block.hash();
+    let era_id = block.header().era_id();
+    if signatures.block_hash != block_hash || signatures.era_id != era_id {
+        return Err(FinalitySignaturesError::BlockMismatch {
+            block_hash,
+            era_id,
+            signed_hash: signatures.block_hash,
+            signed_era: signatures.era_id,
+        });
+    }
+
+    let mut signed_weight = U512::zero();
+    for public_key in signatures.proofs.keys() {
+        match validator_weights.get(public_key) {
+            Some(weight) => signed_weight += *weight,
+            None => {
+                return Err(FinalitySignaturesError::UnknownValidator {
+                    public_key: public_key.clone(),
+                    era_id,
+                })
+            }
+        }
+    }
+
+    let total_weight: U512 = validator_weights.values().copied().sum();
+    if signed_weight * U512::from(*finality_threshold_fraction.denom())
+        <= total_weight * U512::from(*finality_threshold_fraction.numer())
+    {
+        return Err(FinalitySignaturesError::InsufficientWeight {
+            signed_weight,
+            total_weight,
+        });
+    }
+
+    signatures
+        .verify()
+        .map_err(FinalitySignaturesError::InvalidSignature)
+}
+
 /// Returns key in the database, under which the LinearChainSync's state is stored.
 fn create_state_key(chainspec: &Chainspec) -> Vec<u8> {
     format!(
@@ -946,3 +1309,75 @@ pub(crate) fn clean_linear_chain_state(
     let key = create_state_key(chainspec);
     storage.del_state_store(key)
 }
+
+#[cfg(test)]
+mod tests {
+    use casper_types::SecretKey;
+
+    use super::*;
+    use crate::{crypto::generate_ed25519_keypair, testing::TestRng, types::FinalitySignature};
+
+    /// Returns the signatures of `block` by the given validators.
+    fn sign(block: &Block, validators: &[&(SecretKey, PublicKey)]) -> BlockSignatures {
+        let era_id = block.header().era_id();
+        let mut signatures = BlockSignatures::new(*block.hash(), era_id);
+        for (secret_key, public_key) in validators {
+            let signature =
+                FinalitySignature::new(*block.hash(), era_id, secret_key, public_key.clone());
+            signatures.insert_proof(public_key.clone(), signature.signature);
+        }
+        signatures
+    }
+
+    #[test]
+    fn should_check_finality_signatures() {
+        let mut rng = TestRng::new();
+        let block = Block::random(&mut rng);
+        let alice = generate_ed25519_keypair();
+        let bob = generate_ed25519_keypair();
+        let carol = generate_ed25519_keypair();
+        let validator_weights: BTreeMap<PublicKey, U512> = vec![
+            (alice.1.clone(), U512::from(60)),
+            (bob.1.clone(), U512::from(30)),
+            (carol.1.clone(), U512::from(10)),
+        ]
+        .into_iter()
+        .collect();
+        let one_third = Ratio::new(1, 3);
+
+        let check = |signatures: &BlockSignatures| {
+            check_finality_signatures(&block, signatures, &validator_weights, one_third)
+        };
+
+        assert!(check(&sign(&block, &[&alice])).is_ok());
+        assert!(check(&sign(&block, &[&bob, &carol])).is_ok());
+        assert!(matches!(
+            check(&sign(&block, &[&bob])),
+            Err(FinalitySignaturesError::InsufficientWeight { .. })
+        ));
+
+        let mallory = generate_ed25519_keypair();
+        assert!(matches!(
+            check(&sign(&block, &[&alice, &mallory])),
+            Err(FinalitySignaturesError::UnknownValidator { .. })
+        ));
+
+        let other_block = Block::random(&mut rng);
+        assert!(matches!(
+            check(&sign(&other_block, &[&alice])),
+            Err(FinalitySignaturesError::BlockMismatch { .. })
+        ));
+
+        let mut forged = sign(&block, &[&alice]);
+        let (_, other_signature) = sign(&other_block, &[&alice])
+            .proofs
+            .into_iter()
+            .next()
+            .unwrap();
+        forged.insert_proof(alice.1.clone(), other_signature);
+        assert!(matches!(
+            check(&forged),
+            Err(FinalitySignaturesError::InvalidSignature(_))
+        ));
+    }
+}
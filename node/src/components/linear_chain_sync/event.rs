@@ -1,14 +1,32 @@
-use crate::types::{ActivationPoint, Block, BlockHash};
+use std::{
+    collections::BTreeMap,
+    fmt::{Debug, Display},
+};
 
-use std::fmt::{Debug, Display};
+use casper_types::{PublicKey, U512};
+
+use crate::{
+    components::chain_synchronizer,
+    types::{ActivationPoint, Block, BlockHash, BlockSignatures},
+};
 
 #[derive(Debug)]
 pub enum Event<I> {
     Start(I),
     GetBlockHashResult(BlockHash, BlockByHashResult<I>),
     GetBlockHeightResult(u64, BlockByHeightResult<I>),
+    /// The result of fetching the finality signatures of a block downloaded from `peer`, along
+    /// with the weights of the validators of the block's era, if known.
+    GetFinalitySignaturesResult {
+        block: Box<Block>,
+        peer: I,
+        validator_weights: Option<BTreeMap<PublicKey, U512>>,
+        maybe_signatures: Option<Box<BlockSignatures>>,
+    },
     GetDeploysResult(DeploysResult<I>),
     StartDownloadingDeploys,
+    /// The result of syncing the global state under the trusted block.
+    GlobalStateSynced(Result<(), chain_synchronizer::Error>),
     NewPeerConnected(I),
     BlockHandled(Box<Block>),
     GotUpgradeActivationPoint(ActivationPoint),
@@ -52,6 +70,10 @@ where
                 write!(f, "Get deploys for block result {:?}", result)
             }
             Event::StartDownloadingDeploys => write!(f, "Start downloading deploys event."),
+            Event::GlobalStateSynced(Ok(())) => write!(f, "Global state synced."),
+            Event::GlobalStateSynced(Err(error)) => {
+                write!(f, "Failed to sync global state: {}", error)
+            }
             Event::NewPeerConnected(peer_id) => write!(f, "A new peer connected: {}", peer_id),
             Event::BlockHandled(block) => {
                 let hash = block.hash();
@@ -65,6 +87,20 @@ where
             Event::GetBlockHeightResult(height, res) => {
                 write!(f, "Get block result for height {}: {:?}", height, res)
             }
+            Event::GetFinalitySignaturesResult {
+                block,
+                peer,
+                maybe_signatures,
+                ..
+            } => match maybe_signatures {
+                Some(signatures) => write!(f, "Got {} from {}", signatures, peer),
+                None => write!(
+                    f,
+                    "Failed to get finality signatures of block {} from {}",
+                    block.hash(),
+                    peer
+                ),
+            },
             Event::GotUpgradeActivationPoint(activation_point) => {
                 write!(f, "new upgrade activation point: {:?}", activation_point)
             }
@@ -2,18 +2,20 @@ use crate::{
     effect::{
         announcements::ControlAnnouncement,
         requests::{
-            BlockValidationRequest, ContractRuntimeRequest, FetcherRequest, StateStoreRequest,
-            StorageRequest,
+            BlockValidationRequest, ChainSynchronizerRequest, ContractRuntimeRequest,
+            FetcherRequest, StateStoreRequest, StorageRequest,
         },
     },
-    types::{Block, BlockByHeight},
+    types::{Block, BlockByHeight, BlockHeaderWithMetadata},
 };
 pub trait ReactorEventT<I>:
     From<StorageRequest>
     + From<FetcherRequest<I, Block>>
     + From<FetcherRequest<I, BlockByHeight>>
+    + From<FetcherRequest<I, BlockHeaderWithMetadata>>
     + From<BlockValidationRequest<I>>
     + From<ContractRuntimeRequest>
+    + From<ChainSynchronizerRequest>
     + From<StateStoreRequest>
     + From<ControlAnnouncement>
     + Send
@@ -24,8 +26,11 @@ impl<I, REv> ReactorEventT<I> for REv where
     REv: From<StorageRequest>
         + From<FetcherRequest<I, Block>>
         + From<FetcherRequest<I, BlockByHeight>>
+        + From<FetcherRequest<I, BlockHeaderWithMetadata>>
+        + From<FetcherRequest<I, BlockHeaderWithMetadata>>
         + From<BlockValidationRequest<I>>
         + From<ContractRuntimeRequest>
+        + From<ChainSynchronizerRequest>
         + From<StateStoreRequest>
         + From<ControlAnnouncement>
         + Send
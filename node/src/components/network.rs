@@ -1014,6 +1014,18 @@ impl<REv: ReactorEventT<P>, P: PayloadT> Component<REv> for Network<REv, P> {
                     let sent_to = self.send_message_to_n_peers(rng, *payload, count, exclude);
                     responder.respond(sent_to).ignore()
                 }
+                NetworkRequest::ValidatorGossip {
+                    payload,
+                    count,
+                    responder,
+                    ..
+                } => {
+                    // This network doesn't know which peers are validators, so it gossips to
+                    // random peers instead.
+                    let sent_to =
+                        self.send_message_to_n_peers(rng, *payload, count, HashSet::new());
+                    responder.respond(sent_to).ignore()
+                }
             },
             Event::NetworkInfoRequest { info_request } => match info_request {
                 NetworkInfoRequest::GetPeers { responder } => {
@@ -1026,6 +1038,20 @@ impl<REv: ReactorEventT<P>, P: PayloadT> Component<REv> for Network<REv, P> {
                         .collect();
                     responder.respond(peers).ignore()
                 }
+                NetworkInfoRequest::GetPeerByPublicKey { responder, .. } => {
+                    // This network doesn't know the consensus keys of its peers.
+                    responder.respond(None).ignore()
+                }
+                NetworkInfoRequest::Ban {
+                    target, responder, ..
+                } => {
+                    warn!(%target, "banning peers is not supported by this networking component");
+                    responder.respond(()).ignore()
+                }
+                NetworkInfoRequest::GetConnections { responder } => {
+                    // This network doesn't keep traffic statistics of its connections.
+                    responder.respond(Vec::new()).ignore()
+                }
             },
         }
     }
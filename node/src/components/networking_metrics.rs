@@ -1,11 +1,18 @@
-use std::sync::Weak;
+use std::{sync::Weak, time::Duration};
 
-use prometheus::{IntCounter, IntGauge, Registry};
+use prometheus::{Histogram, HistogramOpts, IntCounter, IntGauge, Registry};
 use tracing::debug;
 
 use super::small_network::MessageKind;
 use crate::unregister_metric;
 
+/// Lower bound of the smallest bucket of the (de)compression time histograms, in seconds.
+const COMPRESSION_TIME_BUCKET_START: f64 = 0.000_01;
+/// Factor by which the upper bounds of consecutive (de)compression time buckets grow.
+const COMPRESSION_TIME_BUCKET_FACTOR: f64 = 4.0;
+/// Number of buckets of the (de)compression time histograms.
+const COMPRESSION_TIME_BUCKET_COUNT: usize = 10;
+
 /// Network-type agnostic networking metrics.
 #[derive(Debug)]
 pub(super) struct NetworkingMetrics {
@@ -19,6 +26,8 @@ pub(super) struct NetworkingMetrics {
     pub(super) queued_messages: IntGauge,
     /// Number of connected peers.
     pub(super) peers: IntGauge,
+    /// Number of active bans on peers and addresses.
+    pub(super) active_bans: IntGauge,
 
     /// Count of outgoing messages that are protocol overhead.
     pub(super) out_count_protocol: IntCounter,
@@ -32,6 +41,8 @@ pub(super) struct NetworkingMetrics {
     pub(super) out_count_deploy_transfer: IntCounter,
     /// Count of outgoing messages with block request/response payload.
     pub(super) out_count_block_transfer: IntCounter,
+    /// Count of outgoing messages with trie request/response payload.
+    pub(super) out_count_trie_transfer: IntCounter,
     /// Count of outgoing messages with other payload.
     pub(super) out_count_other: IntCounter,
 
@@ -47,9 +58,22 @@ pub(super) struct NetworkingMetrics {
     pub(super) out_bytes_deploy_transfer: IntCounter,
     /// Volume in bytes of outgoing messages with block request/response payload.
     pub(super) out_bytes_block_transfer: IntCounter,
+    /// Volume in bytes of outgoing messages with trie request/response payload.
+    pub(super) out_bytes_trie_transfer: IntCounter,
     /// Volume in bytes of outgoing messages with other payload.
     pub(super) out_bytes_other: IntCounter,
 
+    /// Count of outgoing messages that were compressed.
+    compressed_messages: IntCounter,
+    /// Volume in bytes of outgoing messages before compression.
+    compression_bytes_in: IntCounter,
+    /// Volume in bytes of outgoing messages after compression.
+    compression_bytes_out: IntCounter,
+    /// Time taken to compress outgoing messages.
+    compression_time: Histogram,
+    /// Time taken to decompress incoming messages.
+    decompression_time: Histogram,
+
     // Potentially temporary metrics, not supported by all networking components:
     /// Number of do-nothing futures that have not finished executing for read requests.
     pub(super) read_futures_in_flight: prometheus::Gauge,
@@ -80,6 +104,10 @@ impl NetworkingMetrics {
             "number of messages waiting to be sent out",
         )?;
         let peers = IntGauge::new("peers", "number of connected peers")?;
+        let active_bans = IntGauge::new(
+            "net_active_bans",
+            "number of active bans on peers and addresses",
+        )?;
 
         let out_count_protocol = IntCounter::new(
             "net_out_count_protocol",
@@ -105,6 +133,10 @@ impl NetworkingMetrics {
             "net_out_count_block_transfer",
             "count of outgoing messages with block request/response payload",
         )?;
+        let out_count_trie_transfer = IntCounter::new(
+            "net_out_count_trie_transfer",
+            "count of outgoing messages with trie request/response payload",
+        )?;
         let out_count_other = IntCounter::new(
             "net_out_count_other",
             "count of outgoing messages with other payload",
@@ -134,11 +166,50 @@ impl NetworkingMetrics {
             "net_out_bytes_block_transfer",
             "volume in bytes of outgoing messages with block request/response payload",
         )?;
+        let out_bytes_trie_transfer = IntCounter::new(
+            "net_out_bytes_trie_transfer",
+            "volume in bytes of outgoing messages with trie request/response payload",
+        )?;
         let out_bytes_other = IntCounter::new(
             "net_out_bytes_other",
             "volume in bytes of outgoing messages with other payload",
         )?;
 
+        let compressed_messages = IntCounter::new(
+            "net_compressed_messages",
+            "count of outgoing messages that were compressed",
+        )?;
+        let compression_bytes_in = IntCounter::new(
+            "net_compression_bytes_in",
+            "volume in bytes of compressed outgoing messages before compression",
+        )?;
+        let compression_bytes_out = IntCounter::new(
+            "net_compression_bytes_out",
+            "volume in bytes of compressed outgoing messages after compression",
+        )?;
+        let compression_time = Histogram::with_opts(
+            HistogramOpts::new(
+                "net_compression_time",
+                "time in seconds taken to compress an outgoing message",
+            )
+            .buckets(prometheus::exponential_buckets(
+                COMPRESSION_TIME_BUCKET_START,
+                COMPRESSION_TIME_BUCKET_FACTOR,
+                COMPRESSION_TIME_BUCKET_COUNT,
+            )?),
+        )?;
+        let decompression_time = Histogram::with_opts(
+            HistogramOpts::new(
+                "net_decompression_time",
+                "time in seconds taken to decompress an incoming message",
+            )
+            .buckets(prometheus::exponential_buckets(
+                COMPRESSION_TIME_BUCKET_START,
+                COMPRESSION_TIME_BUCKET_FACTOR,
+                COMPRESSION_TIME_BUCKET_COUNT,
+            )?),
+        )?;
+
         let read_futures_in_flight = prometheus::Gauge::new(
             "owm_read_futures_in_flight",
             "number of do-nothing futures in flight created by `Codec::read_response`",
@@ -161,6 +232,7 @@ impl NetworkingMetrics {
         registry.register(Box::new(open_connections.clone()))?;
         registry.register(Box::new(queued_messages.clone()))?;
         registry.register(Box::new(peers.clone()))?;
+        registry.register(Box::new(active_bans.clone()))?;
 
         registry.register(Box::new(out_count_protocol.clone()))?;
         registry.register(Box::new(out_count_consensus.clone()))?;
@@ -168,6 +240,7 @@ impl NetworkingMetrics {
         registry.register(Box::new(out_count_address_gossip.clone()))?;
         registry.register(Box::new(out_count_deploy_transfer.clone()))?;
         registry.register(Box::new(out_count_block_transfer.clone()))?;
+        registry.register(Box::new(out_count_trie_transfer.clone()))?;
         registry.register(Box::new(out_count_other.clone()))?;
 
         registry.register(Box::new(out_bytes_protocol.clone()))?;
@@ -176,8 +249,15 @@ impl NetworkingMetrics {
         registry.register(Box::new(out_bytes_address_gossip.clone()))?;
         registry.register(Box::new(out_bytes_deploy_transfer.clone()))?;
         registry.register(Box::new(out_bytes_block_transfer.clone()))?;
+        registry.register(Box::new(out_bytes_trie_transfer.clone()))?;
         registry.register(Box::new(out_bytes_other.clone()))?;
 
+        registry.register(Box::new(compressed_messages.clone()))?;
+        registry.register(Box::new(compression_bytes_in.clone()))?;
+        registry.register(Box::new(compression_bytes_out.clone()))?;
+        registry.register(Box::new(compression_time.clone()))?;
+        registry.register(Box::new(decompression_time.clone()))?;
+
         registry.register(Box::new(read_futures_in_flight.clone()))?;
         registry.register(Box::new(read_futures_total.clone()))?;
         registry.register(Box::new(write_futures_in_flight.clone()))?;
@@ -189,12 +269,14 @@ impl NetworkingMetrics {
             open_connections,
             queued_messages,
             peers,
+            active_bans,
             out_count_protocol,
             out_count_consensus,
             out_count_deploy_gossip,
             out_count_address_gossip,
             out_count_deploy_transfer,
             out_count_block_transfer,
+            out_count_trie_transfer,
             out_count_other,
             out_bytes_protocol,
             out_bytes_consensus,
@@ -202,7 +284,13 @@ impl NetworkingMetrics {
             out_bytes_address_gossip,
             out_bytes_deploy_transfer,
             out_bytes_block_transfer,
+            out_bytes_trie_transfer,
             out_bytes_other,
+            compressed_messages,
+            compression_bytes_in,
+            compression_bytes_out,
+            compression_time,
+            decompression_time,
             read_futures_in_flight,
             read_futures_total,
             write_futures_in_flight,
@@ -239,6 +327,10 @@ impl NetworkingMetrics {
                     metrics.out_bytes_block_transfer.inc_by(size);
                     metrics.out_count_block_transfer.inc();
                 }
+                MessageKind::TrieTransfer => {
+                    metrics.out_bytes_trie_transfer.inc_by(size);
+                    metrics.out_count_trie_transfer.inc();
+                }
                 MessageKind::Other => {
                     metrics.out_bytes_other.inc_by(size);
                     metrics.out_count_other.inc();
@@ -248,6 +340,32 @@ impl NetworkingMetrics {
             debug!("not recording metrics, component already shut down");
         }
     }
+
+    /// Records the compression of an outgoing message.
+    pub(crate) fn record_compression(
+        this: &Weak<Self>,
+        uncompressed_size: u64,
+        compressed_size: u64,
+        duration: Duration,
+    ) {
+        if let Some(metrics) = this.upgrade() {
+            metrics.compressed_messages.inc();
+            metrics.compression_bytes_in.inc_by(uncompressed_size);
+            metrics.compression_bytes_out.inc_by(compressed_size);
+            metrics.compression_time.observe(duration.as_secs_f64());
+        } else {
+            debug!("not recording metrics, component already shut down");
+        }
+    }
+
+    /// Records the decompression of an incoming message.
+    pub(crate) fn record_decompression(this: &Weak<Self>, duration: Duration) {
+        if let Some(metrics) = this.upgrade() {
+            metrics.decompression_time.observe(duration.as_secs_f64());
+        } else {
+            debug!("not recording metrics, component already shut down");
+        }
+    }
 }
 
 impl Drop for NetworkingMetrics {
@@ -257,6 +375,7 @@ impl Drop for NetworkingMetrics {
         unregister_metric!(self.registry, self.open_connections);
         unregister_metric!(self.registry, self.queued_messages);
         unregister_metric!(self.registry, self.peers);
+        unregister_metric!(self.registry, self.active_bans);
 
         unregister_metric!(self.registry, self.out_count_protocol);
         unregister_metric!(self.registry, self.out_count_consensus);
@@ -264,6 +383,7 @@ impl Drop for NetworkingMetrics {
         unregister_metric!(self.registry, self.out_count_address_gossip);
         unregister_metric!(self.registry, self.out_count_deploy_transfer);
         unregister_metric!(self.registry, self.out_count_block_transfer);
+        unregister_metric!(self.registry, self.out_count_trie_transfer);
         unregister_metric!(self.registry, self.out_count_other);
         unregister_metric!(self.registry, self.out_bytes_protocol);
         unregister_metric!(self.registry, self.out_bytes_consensus);
@@ -271,8 +391,15 @@ impl Drop for NetworkingMetrics {
         unregister_metric!(self.registry, self.out_bytes_address_gossip);
         unregister_metric!(self.registry, self.out_bytes_deploy_transfer);
         unregister_metric!(self.registry, self.out_bytes_block_transfer);
+        unregister_metric!(self.registry, self.out_bytes_trie_transfer);
         unregister_metric!(self.registry, self.out_bytes_other);
 
+        unregister_metric!(self.registry, self.compressed_messages);
+        unregister_metric!(self.registry, self.compression_bytes_in);
+        unregister_metric!(self.registry, self.compression_bytes_out);
+        unregister_metric!(self.registry, self.compression_time);
+        unregister_metric!(self.registry, self.decompression_time);
+
         unregister_metric!(self.registry, self.read_futures_in_flight);
         unregister_metric!(self.registry, self.read_futures_total);
         unregister_metric!(self.registry, self.write_futures_in_flight);
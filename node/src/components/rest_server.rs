@@ -12,11 +12,47 @@
 //! reactor, and an external facing http server that exposes various uri routes and converts
 //! HTTP requests into the appropriate component events.
 //!
-//! Currently this component supports two endpoints, each of which takes no arguments:
+//! Currently this component supports the following endpoints:
 //! /status : a human readable JSON equivalent of the info-get-status rpc method.
 //!     example: curl -X GET 'http://<ip>:8888/status'
 //! /metrics : time series data collected from the internals of the node being queried.
 //!     example: curl -X GET 'http://<ip>:8888/metrics'
+//! /ban : bans a peer or address for a given duration. Only available if `enable_admin_api` is set.
+//!     example: curl -X POST 'http://<ip>:8888/ban' -H 'Content-Type: application/json' \
+//!         -d '{"target": {"Address": "10.0.0.1"}, "duration": "1hour"}'
+//! /deploy_access_lists : replaces the deploy acceptor's allowlist and denylist until the node
+//!     restarts. Only available if `enable_admin_api` is set.
+//!     example: curl -X POST 'http://<ip>:8888/deploy_access_lists' \
+//!         -H 'Content-Type: application/json' \
+//!         -d '{"allowlist": {"accounts": ["account-hash-<hex>"]}, "denylist": {}}'
+//! /state_diff : returns the added, removed and changed values between two state root hashes,
+//!     optionally only for keys whose serialized form starts with the hex-encoded `key_prefix`.
+//!     Only available if `enable_admin_api` is set.
+//!     example: curl -X POST 'http://<ip>:8888/state_diff' -H 'Content-Type: application/json' \
+//!         -d '{"pre_state_root_hash": "<hex>", "post_state_root_hash": "<hex>", \
+//!             "key_prefix": "00"}'
+//! /consensus_state/<era id> : returns a summary of the era's Highway protocol state: the
+//!     validators' weights, unit counts, faults and latest units.  Only available if
+//!     `enable_admin_api` is set.
+//!     example: curl -X GET 'http://<ip>:8888/consensus_state/42'
+//! /dependency_cycles : returns the most recently rejected deploys whose dependencies led back to
+//!     themselves through pending deploys, with the offending chain of dependencies.  Only
+//!     available if `enable_admin_api` is set.
+//!     example: curl -X GET 'http://<ip>:8888/dependency_cycles'
+//! /pending_deploys : returns the headers of the deploys the block proposer intends to propose,
+//!     oldest first, optionally filtered by `account`, `min_age`, `max_age`, `min_gas_price` and
+//!     `max_gas_price`, and paginated with `offset` and `limit` (at most 100).  Only available if
+//!     `enable_admin_api` is set.
+//!     example: curl -X GET 'http://<ip>:8888/pending_deploys?min_age=10min&limit=20'
+//! /deferred_deploys : returns the hashes of the pending deploys which were left out of the most
+//!     recently proposed block, each with the reason.  Only available if `enable_admin_api` is
+//!     set.
+//!     example: curl -X GET 'http://<ip>:8888/deferred_deploys'
+//! /network_connections : returns the node's incoming and outgoing connections, each with the
+//!     peer's node ID and address, the connection's age, the bytes sent and received and the
+//!     times of the last sent and received messages.  Only available if `enable_admin_api` is
+//!     set.
+//!     example: curl -X GET 'http://<ip>:8888/network_connections'
 
 mod config;
 mod event;
@@ -36,8 +72,9 @@ use super::Component;
 use crate::{
     effect::{
         requests::{
-            ChainspecLoaderRequest, ConsensusRequest, MetricsRequest, NetworkInfoRequest,
-            StorageRequest,
+            BlockProposerRequest, ChainSynchronizerRequest, ChainspecLoaderRequest,
+            ConsensusRequest, ContractRuntimeRequest, DeployAcceptorRequest, MetricsRequest,
+            NetworkInfoRequest, StorageRequest,
         },
         EffectBuilder, EffectExt, Effects,
     },
@@ -57,9 +94,13 @@ pub trait ReactorEventT:
     + From<RestRequest<NodeId>>
     + From<NetworkInfoRequest<NodeId>>
     + From<StorageRequest>
+    + From<ChainSynchronizerRequest>
     + From<ChainspecLoaderRequest>
     + From<ConsensusRequest>
     + From<MetricsRequest>
+    + From<DeployAcceptorRequest>
+    + From<ContractRuntimeRequest>
+    + From<BlockProposerRequest>
     + Send
 {
 }
@@ -69,9 +110,13 @@ impl<REv> ReactorEventT for REv where
         + From<RestRequest<NodeId>>
         + From<NetworkInfoRequest<NodeId>>
         + From<StorageRequest>
+        + From<ChainSynchronizerRequest>
         + From<ChainspecLoaderRequest>
         + From<ConsensusRequest>
         + From<MetricsRequest>
+        + From<DeployAcceptorRequest>
+        + From<ContractRuntimeRequest>
+        + From<BlockProposerRequest>
         + Send
         + 'static
 {
@@ -105,6 +150,7 @@ impl RestServer {
             api_version,
             shutdown_receiver,
             config.qps_limit,
+            config.enable_admin_api,
         ));
 
         Ok(RestServer {
@@ -128,18 +174,32 @@ where
         event: Self::Event,
     ) -> Effects<Self::Event> {
         match event {
-            Event::RestRequest(RestRequest::GetStatus { responder }) => async move {
-                let (last_added_block, peers, chainspec_info, consensus_status) = join!(
-                    effect_builder.get_highest_block_from_storage(),
-                    effect_builder.network_peers(),
-                    effect_builder.get_chainspec_info(),
-                    effect_builder.consensus_status()
-                );
-                let status_feed =
-                    StatusFeed::new(last_added_block, peers, chainspec_info, consensus_status);
-                responder.respond(status_feed).await;
+            Event::RestRequest(RestRequest::GetStatus { responder }) => {
+                async move {
+                    let (
+                        last_added_block,
+                        peers,
+                        chainspec_info,
+                        consensus_status,
+                        trie_sync_progress,
+                    ) = join!(
+                        effect_builder.get_highest_block_from_storage(),
+                        effect_builder.network_peers(),
+                        effect_builder.get_chainspec_info(),
+                        effect_builder.consensus_status(),
+                        effect_builder.trie_sync_progress()
+                    );
+                    let status_feed = StatusFeed::new(
+                        last_added_block,
+                        peers,
+                        chainspec_info,
+                        consensus_status,
+                        trie_sync_progress,
+                    );
+                    responder.respond(status_feed).await;
+                }
+                .ignore()
             }
-            .ignore(),
             Event::RestRequest(RestRequest::GetMetrics { responder }) => effect_builder
                 .get_metrics()
                 .event(move |text| Event::GetMetricsResult {
@@ -150,6 +210,58 @@ where
                 let schema = OPEN_RPC_SCHEMA.clone();
                 responder.respond(schema).ignore()
             }
+            Event::RestRequest(RestRequest::BanPeer {
+                target,
+                duration,
+                responder,
+            }) => async move {
+                effect_builder.ban_peer(target, duration).await;
+                responder.respond(()).await;
+            }
+            .ignore(),
+            Event::RestRequest(RestRequest::SetDeployAccessLists {
+                access_lists,
+                responder,
+            }) => async move {
+                effect_builder.set_deploy_access_lists(access_lists).await;
+                responder.respond(()).await;
+            }
+            .ignore(),
+            Event::RestRequest(RestRequest::DiffState {
+                state_diff_request,
+                responder,
+            }) => async move {
+                let result = effect_builder.diff_state(state_diff_request).await;
+                responder.respond(result).await;
+            }
+            .ignore(),
+            Event::RestRequest(RestRequest::GetConsensusStateSummary { era_id, responder }) => {
+                async move {
+                    let summary = effect_builder.consensus_state_summary(era_id).await;
+                    responder.respond(summary).await;
+                }
+                .ignore()
+            }
+            Event::RestRequest(RestRequest::GetDependencyCycles { responder }) => async move {
+                let cycles = effect_builder.get_dependency_cycles().await;
+                responder.respond(cycles).await;
+            }
+            .ignore(),
+            Event::RestRequest(RestRequest::GetPendingDeploys { query, responder }) => async move {
+                let pending_deploys = effect_builder.get_pending_deploys(query).await;
+                responder.respond(pending_deploys).await;
+            }
+            .ignore(),
+            Event::RestRequest(RestRequest::GetDeferredDeploys { responder }) => async move {
+                let deferred_deploys = effect_builder.get_deferred_deploys().await;
+                responder.respond(deferred_deploys).await;
+            }
+            .ignore(),
+            Event::RestRequest(RestRequest::GetConnections { responder }) => async move {
+                let connections = effect_builder.get_network_connections().await;
+                responder.respond(connections).await;
+            }
+            .ignore(),
             Event::GetMetricsResult {
                 text,
                 main_responder,
@@ -13,19 +13,27 @@ const DEFAULT_QPS_LIMIT: u64 = 100;
 // Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
 #[serde(deny_unknown_fields)]
 pub struct Config {
+    /// Whether to run the server.
+    pub enable_server: bool,
+
     /// Address to bind REST HTTP server to.
     pub address: String,
 
     /// Max rate limit in qps.
     pub qps_limit: u64,
+
+    /// Whether to serve the admin endpoints, e.g. for banning peers.
+    pub enable_admin_api: bool,
 }
 
 impl Config {
     /// Creates a default instance for `RestServer`.
     pub fn new() -> Self {
         Config {
+            enable_server: true,
             address: DEFAULT_ADDRESS.to_string(),
             qps_limit: DEFAULT_QPS_LIMIT,
+            enable_admin_api: false,
         }
     }
 }
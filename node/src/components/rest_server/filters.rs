@@ -1,22 +1,34 @@
+use std::convert::{Infallible, TryFrom};
+
 use futures::FutureExt;
 use http::Response;
 use hyper::Body;
-use tracing::warn;
+use serde::Deserialize;
+use tracing::{debug, warn};
 use warp::{
     filters::BoxedFilter,
     http::StatusCode,
     reject::Rejection,
-    reply::{self, Reply},
+    reply::{self, reply, Reply},
     Filter,
 };
 
-use casper_types::ProtocolVersion;
+use casper_execution_engine::core::engine_state::{StateDiffRequest, StateDiffResult};
+use casper_types::{EraId, ProtocolVersion};
 
 use super::ReactorEventT;
 use crate::{
+    components::{
+        block_proposer::PendingDeploysQuery,
+        contract_runtime::QueryError,
+        deploy_acceptor::AccessLists,
+        rpc_server::rpcs::{ApiError, ErrorCode},
+        small_network::BanTarget,
+    },
+    crypto::hash::Digest,
     effect::{requests::RestRequest, EffectBuilder},
     reactor::QueueKind,
-    types::GetStatusResult,
+    types::{json_compatibility, GetStatusResult, NodeId, TimeDiff},
 };
 
 /// The status URL path.
@@ -28,6 +40,53 @@ pub const METRICS_API_PATH: &str = "metrics";
 /// The OpenRPC scehma URL path.
 pub const JSON_RPC_SCHEMA_API_PATH: &str = "rpc-schema";
 
+/// The URL path for banning peers.
+pub const BAN_API_PATH: &str = "ban";
+
+/// The URL path for replacing the deploy acceptor's allowlist and denylist.
+pub const DEPLOY_ACCESS_LISTS_API_PATH: &str = "deploy_access_lists";
+
+/// The URL path for comparing the global states under two state root hashes.
+pub const STATE_DIFF_API_PATH: &str = "state_diff";
+
+/// The URL path for summarizing an era's consensus protocol state.
+pub const CONSENSUS_STATE_API_PATH: &str = "consensus_state";
+
+/// The URL path for listing deploys rejected because of dependency cycles.
+pub const DEPENDENCY_CYCLES_API_PATH: &str = "dependency_cycles";
+
+/// The URL path for inspecting the block proposer's pending deploys.
+pub const PENDING_DEPLOYS_API_PATH: &str = "pending_deploys";
+
+/// The URL path for listing the deploys left out of the most recently proposed block.
+pub const DEFERRED_DEPLOYS_API_PATH: &str = "deferred_deploys";
+
+/// The URL path for inspecting the node's network connections.
+pub const NETWORK_CONNECTIONS_API_PATH: &str = "network_connections";
+
+/// The body of a request to ban a peer or address.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BanRequest {
+    /// The peer or address to ban.
+    target: BanTarget<NodeId>,
+    /// How long the ban lasts.
+    duration: TimeDiff,
+}
+
+/// The body of a request to compare the global states under two state root hashes.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StateDiffRequestBody {
+    /// The state root hash of the state to compare against.
+    pre_state_root_hash: Digest,
+    /// The state root hash of the state to compare.
+    post_state_root_hash: Digest,
+    /// The hex-encoded prefix of the serialized keys to compare. All keys are compared if empty.
+    #[serde(default)]
+    key_prefix: String,
+}
+
 pub(super) fn create_status_filter<REv: ReactorEventT>(
     effect_builder: EffectBuilder<REv>,
     api_version: ProtocolVersion,
@@ -65,11 +124,10 @@ pub(super) fn create_metrics_filter<REv: ReactorEventT>(
                     ),
                     None => {
                         warn!("metrics not available");
-                        Ok(reply::with_status(
-                            "metrics not available",
-                            StatusCode::INTERNAL_SERVER_ERROR,
+                        Ok(
+                            ApiError::new(ErrorCode::Unavailable, "metrics not available")
+                                .into_http_response(),
                         )
-                        .into_response())
                     }
                 })
         })
@@ -93,3 +151,270 @@ pub(super) fn create_rpc_schema_filter<REv: ReactorEventT>(
         })
         .boxed()
 }
+
+/// Creates the filter for banning peers.
+///
+/// If `enable_admin_api` is `false`, all requests are rejected as if the endpoint didn't exist.
+pub(super) fn create_ban_filter<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    enable_admin_api: bool,
+) -> BoxedFilter<(Response<Body>,)> {
+    admin_api_filter(enable_admin_api)
+        .and(warp::post())
+        .and(warp::path(BAN_API_PATH))
+        .and(warp::body::json())
+        .and_then(move |ban_request: BanRequest| {
+            effect_builder
+                .make_request(
+                    |responder| RestRequest::BanPeer {
+                        target: ban_request.target,
+                        duration: ban_request.duration,
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .map(|()| Ok::<_, Rejection>(reply().into_response()))
+        })
+        .boxed()
+}
+
+/// Creates the filter for replacing the deploy acceptor's allowlist and denylist.
+///
+/// If `enable_admin_api` is `false`, all requests are rejected as if the endpoint didn't exist.
+pub(super) fn create_deploy_access_lists_filter<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    enable_admin_api: bool,
+) -> BoxedFilter<(Response<Body>,)> {
+    admin_api_filter(enable_admin_api)
+        .and(warp::post())
+        .and(warp::path(DEPLOY_ACCESS_LISTS_API_PATH))
+        .and(warp::body::json())
+        .and_then(move |access_lists: AccessLists| {
+            effect_builder
+                .make_request(
+                    |responder| RestRequest::SetDeployAccessLists {
+                        access_lists,
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .map(|()| Ok::<_, Rejection>(reply().into_response()))
+        })
+        .boxed()
+}
+
+/// Creates the filter for comparing the global states under two state root hashes.
+///
+/// If `enable_admin_api` is `false`, all requests are rejected as if the endpoint didn't exist.
+pub(super) fn create_state_diff_filter<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    enable_admin_api: bool,
+) -> BoxedFilter<(Response<Body>,)> {
+    admin_api_filter(enable_admin_api)
+        .and(warp::post())
+        .and(warp::path(STATE_DIFF_API_PATH))
+        .and(warp::body::json())
+        .and_then(move |body: StateDiffRequestBody| async move {
+            let key_prefix = match hex::decode(&body.key_prefix) {
+                Ok(key_prefix) => key_prefix,
+                Err(error) => {
+                    return Ok::<_, Rejection>(
+                        ApiError::new(
+                            ErrorCode::ParseQueryKey,
+                            format!("failed to parse key prefix: {}", error),
+                        )
+                        .into_http_response(),
+                    );
+                }
+            };
+            let state_diff_request = StateDiffRequest::new(
+                body.pre_state_root_hash.into(),
+                body.post_state_root_hash.into(),
+                key_prefix,
+            );
+            let result = effect_builder
+                .make_request(
+                    |responder| RestRequest::DiffState {
+                        state_diff_request,
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+            Ok(state_diff_response(result))
+        })
+        .boxed()
+}
+
+/// Converts the result of a state diff request into a JSON response.
+fn state_diff_response(result: Result<StateDiffResult, QueryError>) -> Response<Body> {
+    match result {
+        Ok(StateDiffResult::Success(state_diff)) => {
+            match json_compatibility::StateDiff::try_from(&state_diff) {
+                Ok(json_state_diff) => reply::json(&json_state_diff).into_response(),
+                Err(error) => ApiError::new(
+                    ErrorCode::InternalError,
+                    format!("failed to encode state diff: {}", error),
+                )
+                .into_http_response(),
+            }
+        }
+        Ok(StateDiffResult::RootNotFound(state_root_hash)) => ApiError::new(
+            ErrorCode::QueryFailed,
+            format!("state root hash {} not found", state_root_hash),
+        )
+        .into_http_response(),
+        Err(error) => {
+            warn!(%error, "failed to diff state");
+            ApiError::new(ErrorCode::QueryFailedToExecute, error.to_string()).into_http_response()
+        }
+    }
+}
+
+/// Creates the filter for summarizing an era's consensus protocol state.
+///
+/// If `enable_admin_api` is `false`, all requests are rejected as if the endpoint didn't exist.
+pub(super) fn create_consensus_state_filter<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    enable_admin_api: bool,
+) -> BoxedFilter<(Response<Body>,)> {
+    admin_api_filter(enable_admin_api)
+        .and(warp::get())
+        .and(warp::path(CONSENSUS_STATE_API_PATH))
+        .and(warp::path::param::<u64>())
+        .and(warp::path::end())
+        .and_then(move |era_id: u64| async move {
+            let era_id = EraId::from(era_id);
+            let maybe_summary = effect_builder
+                .make_request(
+                    |responder| RestRequest::GetConsensusStateSummary { era_id, responder },
+                    QueueKind::Api,
+                )
+                .await;
+            let response = match maybe_summary {
+                Some(summary) => reply::json(&summary).into_response(),
+                None => ApiError::new(ErrorCode::NotFound, format!("{} is not active", era_id))
+                    .into_http_response(),
+            };
+            Ok::<_, Rejection>(response)
+        })
+        .boxed()
+}
+
+pub(super) fn create_dependency_cycles_filter<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    enable_admin_api: bool,
+) -> BoxedFilter<(Response<Body>,)> {
+    admin_api_filter(enable_admin_api)
+        .and(warp::get())
+        .and(warp::path(DEPENDENCY_CYCLES_API_PATH))
+        .and(warp::path::end())
+        .and_then(move || async move {
+            let cycles = effect_builder
+                .make_request(
+                    |responder| RestRequest::GetDependencyCycles { responder },
+                    QueueKind::Api,
+                )
+                .await;
+            Ok::<_, Rejection>(reply::json(&cycles).into_response())
+        })
+        .boxed()
+}
+
+/// Creates the filter for inspecting the block proposer's pending deploys.
+///
+/// If `enable_admin_api` is `false`, all requests are rejected as if the endpoint didn't exist.
+pub(super) fn create_pending_deploys_filter<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    enable_admin_api: bool,
+) -> BoxedFilter<(Response<Body>,)> {
+    admin_api_filter(enable_admin_api)
+        .and(warp::get())
+        .and(warp::path(PENDING_DEPLOYS_API_PATH))
+        .and(warp::path::end())
+        .and(warp::query::<PendingDeploysQuery>())
+        .and_then(move |query: PendingDeploysQuery| async move {
+            let pending_deploys = effect_builder
+                .make_request(
+                    |responder| RestRequest::GetPendingDeploys { query, responder },
+                    QueueKind::Api,
+                )
+                .await;
+            Ok::<_, Rejection>(reply::json(&pending_deploys).into_response())
+        })
+        .boxed()
+}
+
+/// Creates the filter for listing the deploys left out of the most recently proposed block.
+///
+/// If `enable_admin_api` is `false`, all requests are rejected as if the endpoint didn't exist.
+pub(super) fn create_deferred_deploys_filter<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    enable_admin_api: bool,
+) -> BoxedFilter<(Response<Body>,)> {
+    admin_api_filter(enable_admin_api)
+        .and(warp::get())
+        .and(warp::path(DEFERRED_DEPLOYS_API_PATH))
+        .and(warp::path::end())
+        .and_then(move || async move {
+            let deferred_deploys = effect_builder
+                .make_request(
+                    |responder| RestRequest::GetDeferredDeploys { responder },
+                    QueueKind::Api,
+                )
+                .await;
+            Ok::<_, Rejection>(reply::json(&deferred_deploys).into_response())
+        })
+        .boxed()
+}
+
+/// Creates the filter for inspecting the node's network connections.
+///
+/// If `enable_admin_api` is `false`, all requests are rejected as if the endpoint didn't exist.
+pub(super) fn create_network_connections_filter<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    enable_admin_api: bool,
+) -> BoxedFilter<(Response<Body>,)> {
+    admin_api_filter(enable_admin_api)
+        .and(warp::get())
+        .and(warp::path(NETWORK_CONNECTIONS_API_PATH))
+        .and(warp::path::end())
+        .and_then(move || async move {
+            let connections = effect_builder
+                .make_request(
+                    |responder| RestRequest::GetConnections { responder },
+                    QueueKind::Api,
+                )
+                .await;
+            Ok::<_, Rejection>(reply::json(&connections).into_response())
+        })
+        .boxed()
+}
+
+/// Rejects all requests as if the endpoint didn't exist, unless `enable_admin_api` is `true`.
+fn admin_api_filter(enable_admin_api: bool) -> BoxedFilter<()> {
+    warp::any()
+        .and_then(move || async move {
+            if enable_admin_api {
+                Ok(())
+            } else {
+                Err(warp::reject::not_found())
+            }
+        })
+        .untuple_one()
+        .boxed()
+}
+
+/// Converts rejections of all REST filters into structured JSON errors.
+pub(super) async fn handle_rejection(rejection: Rejection) -> Result<Response<Body>, Infallible> {
+    let error = if rejection.is_not_found() {
+        ApiError::new(ErrorCode::NotFound, "no such endpoint")
+    } else {
+        debug!(?rejection, "rejected REST request");
+        ApiError::new(
+            ErrorCode::InternalError,
+            format!("failed to handle request: {:?}", rejection),
+        )
+    };
+    Ok(error.into_http_response())
+}
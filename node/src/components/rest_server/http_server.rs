@@ -21,13 +21,41 @@ pub(super) async fn run<REv: ReactorEventT>(
     api_version: ProtocolVersion,
     shutdown_receiver: oneshot::Receiver<()>,
     qps_limit: u64,
+    enable_admin_api: bool,
 ) {
     // REST filters.
     let rest_status = filters::create_status_filter(effect_builder, api_version);
     let rest_metrics = filters::create_metrics_filter(effect_builder);
     let rest_open_rpc = filters::create_rpc_schema_filter(effect_builder);
+    let rest_ban = filters::create_ban_filter(effect_builder, enable_admin_api);
+    let rest_deploy_access_lists =
+        filters::create_deploy_access_lists_filter(effect_builder, enable_admin_api);
+    let rest_state_diff = filters::create_state_diff_filter(effect_builder, enable_admin_api);
+    let rest_consensus_state =
+        filters::create_consensus_state_filter(effect_builder, enable_admin_api);
+    let rest_dependency_cycles =
+        filters::create_dependency_cycles_filter(effect_builder, enable_admin_api);
+    let rest_pending_deploys =
+        filters::create_pending_deploys_filter(effect_builder, enable_admin_api);
+    let rest_deferred_deploys =
+        filters::create_deferred_deploys_filter(effect_builder, enable_admin_api);
+    let rest_network_connections =
+        filters::create_network_connections_filter(effect_builder, enable_admin_api);
 
-    let service = warp::service(rest_status.or(rest_metrics).or(rest_open_rpc));
+    let service = warp::service(
+        rest_status
+            .or(rest_metrics)
+            .or(rest_open_rpc)
+            .or(rest_ban)
+            .or(rest_deploy_access_lists)
+            .or(rest_state_diff)
+            .or(rest_consensus_state)
+            .or(rest_dependency_cycles)
+            .or(rest_pending_deploys)
+            .or(rest_deferred_deploys)
+            .or(rest_network_connections)
+            .recover(filters::handle_rejection),
+    );
 
     // Start the server, passing a oneshot receiver to allow the server to be shut down gracefully.
     let make_svc =
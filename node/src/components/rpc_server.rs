@@ -17,12 +17,16 @@
 mod config;
 mod event;
 mod http_server;
+mod incoming;
 pub mod rpcs;
 
-use std::{convert::Infallible, fmt::Debug};
+use std::{convert::Infallible, fmt::Debug, path::Path};
 
 use datasize::DataSize;
-use futures::join;
+use futures::{future, join};
+use http::{Method, Uri};
+use thiserror::Error;
+use warp::cors::Builder as CorsBuilder;
 
 use casper_execution_engine::{
     core::engine_state::{
@@ -33,33 +37,55 @@ use casper_execution_engine::{
 };
 use casper_types::{system::auction::EraValidators, Key, ProtocolVersion, URef};
 
-use self::rpcs::chain::BlockIdentifier;
-
 use super::Component;
 use crate::{
-    components::contract_runtime::EraValidatorsRequest,
+    components::contract_runtime::{EraValidatorsRequest, QueryError},
     crypto::hash::Digest,
     effect::{
         announcements::RpcServerAnnouncement,
         requests::{
-            ChainspecLoaderRequest, ConsensusRequest, ContractRuntimeRequest, LinearChainRequest,
-            MetricsRequest, NetworkInfoRequest, RpcRequest, StorageRequest,
+            ChainSynchronizerRequest, ChainspecLoaderRequest, ConsensusRequest,
+            ContractRuntimeRequest, LinearChainRequest, MetricsRequest, NetworkInfoRequest,
+            RpcRequest, StorageRequest,
         },
         EffectBuilder, EffectExt, Effects, Responder,
     },
-    types::{NodeId, StatusFeed},
+    types::{chainspec::DeployConfig, BlockHash, BlockIdentifier, Chainspec, NodeId, StatusFeed},
     utils::{self, ListeningError},
     NodeRng,
 };
+use incoming::Incoming;
 
 pub use config::Config;
 pub(crate) use event::Event;
 
+/// The outcome of reading a purse's balance at the state root of a given block.
+#[derive(Debug)]
+pub enum BalanceAtBlock {
+    /// The requested block is not stored locally.
+    NoSuchBlock,
+    /// The given key is neither a purse nor an account with a main purse at the block's state
+    /// root.
+    NoSuchPurse(String),
+    /// The balance query was run against the block's state root.
+    Balance {
+        /// The hash of the resolved block.
+        block_hash: BlockHash,
+        /// The height of the resolved block.
+        block_height: u64,
+        /// The state root hash of the resolved block.
+        state_root_hash: Digest,
+        /// The result of the balance query.
+        result: Result<BalanceResult, QueryError>,
+    },
+}
+
 /// A helper trait capturing all of this components Request type dependencies.
 pub trait ReactorEventT:
     From<Event>
     + From<RpcRequest<NodeId>>
     + From<RpcServerAnnouncement>
+    + From<ChainSynchronizerRequest>
     + From<ChainspecLoaderRequest>
     + From<ContractRuntimeRequest>
     + From<ConsensusRequest>
@@ -75,6 +101,7 @@ impl<REv> ReactorEventT for REv where
     REv: From<Event>
         + From<RpcRequest<NodeId>>
         + From<RpcServerAnnouncement>
+        + From<ChainSynchronizerRequest>
         + From<ChainspecLoaderRequest>
         + From<ContractRuntimeRequest>
         + From<ConsensusRequest>
@@ -87,30 +114,110 @@ impl<REv> ReactorEventT for REv where
 {
 }
 
+/// Error returned when a batch of deploys exceeds the configured maximum size.
+#[derive(Debug, Error)]
+#[error("batch of {got} deploys exceeds the limit of {max_deploys_per_batch}")]
+pub struct ExcessiveBatchSize {
+    /// The maximum number of deploys in a batch.
+    pub max_deploys_per_batch: u32,
+    /// The number of deploys in the received batch.
+    pub got: usize,
+}
+
 #[derive(DataSize, Debug)]
-pub(crate) struct RpcServer {}
+pub(crate) struct RpcServer {
+    /// The maximum number of deploys in a single batch submission.
+    max_deploys_per_batch: u32,
+    /// The name of the chain deploys must be for.
+    chain_name: String,
+    /// The deploy configuration pending deploys are checked against.
+    deploy_config: DeployConfig,
+}
 
 impl RpcServer {
+    /// Starts the JSON-RPC server.  Relative TLS certificate and key paths are resolved against
+    /// `root`.
     pub(crate) fn new<REv>(
         config: Config,
+        chainspec: &Chainspec,
         effect_builder: EffectBuilder<REv>,
-        api_version: ProtocolVersion,
+        root: &Path,
     ) -> Result<Self, ListeningError>
     where
         REv: ReactorEventT,
     {
-        let builder = utils::start_listening(&config.address)?;
+        let tls_acceptor = match (&config.tls_cert_path, &config.tls_key_path) {
+            (Some(cert_path), Some(key_path)) => Some(
+                incoming::create_tls_acceptor(&root.join(cert_path), &root.join(key_path))
+                    .map_err(ListeningError::Tls)?,
+            ),
+            (None, None) => None,
+            _ => {
+                return Err(ListeningError::InvalidConfig(
+                    "TLS requires both a certificate and a key path".to_string(),
+                ))
+            }
+        };
+        let cors = cors(&config)?;
+        let incoming = Incoming::new(utils::bind_incoming(&config.address)?, tls_acceptor);
         tokio::spawn(http_server::run(
-            builder,
+            incoming,
             effect_builder,
-            api_version,
+            chainspec.protocol_config.version,
             config.qps_limit,
+            cors,
         ));
 
-        Ok(RpcServer {})
+        Ok(RpcServer {
+            max_deploys_per_batch: config.max_deploys_per_batch,
+            chain_name: chainspec.network_config.name.clone(),
+            deploy_config: DeployConfig {
+                max_ttl: chainspec.max_deploy_ttl(),
+                ..chainspec.deploy_config
+            },
+        })
     }
 }
 
+/// Returns the CORS settings of the server, or `None` if no cross-origin requests are allowed.
+fn cors(config: &Config) -> Result<Option<CorsBuilder>, ListeningError> {
+    if config.cors_origins.is_empty() {
+        return Ok(None);
+    }
+    let methods = config
+        .cors_methods
+        .iter()
+        .map(|method| {
+            Method::from_bytes(method.as_bytes()).map_err(|_| {
+                ListeningError::InvalidConfig(format!("invalid CORS method '{}'", method))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let cors = warp::cors()
+        .allow_methods(methods)
+        .allow_header("content-type");
+    if config.cors_origins.iter().any(|origin| origin == "*") {
+        return Ok(Some(cors.allow_any_origin()));
+    }
+    for origin in &config.cors_origins {
+        let is_valid = origin.parse::<Uri>().map_or(false, |uri| {
+            uri.scheme().is_some()
+                && uri.authority().is_some()
+                && matches!(uri.path(), "" | "/")
+                && uri.query().is_none()
+        });
+        if !is_valid {
+            return Err(ListeningError::InvalidConfig(format!(
+                "invalid CORS origin '{}'",
+                origin
+            )));
+        }
+    }
+    Ok(Some(cors.allow_origins(
+        config.cors_origins.iter().map(String::as_str),
+    )))
+}
+
 impl RpcServer {
     fn handle_protocol_data<REv: ReactorEventT>(
         &mut self,
@@ -132,7 +239,7 @@ impl RpcServer {
         state_root_hash: Digest,
         base_key: Key,
         path: Vec<String>,
-        responder: Responder<Result<QueryResult, engine_state::Error>>,
+        responder: Responder<Result<QueryResult, QueryError>>,
     ) -> Effects<Event> {
         let query = QueryRequest::new(state_root_hash.into(), base_key, path);
         effect_builder
@@ -159,12 +266,73 @@ impl RpcServer {
             })
     }
 
+    fn handle_get_balance_at_block<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        block_identifier: BlockIdentifier,
+        purse_or_account: Key,
+        responder: Responder<BalanceAtBlock>,
+    ) -> Effects<Event> {
+        async move {
+            let maybe_header = effect_builder
+                .get_block_header_by_identifier_from_storage(block_identifier)
+                .await;
+            let header = match maybe_header {
+                Some(header) => header,
+                None => return responder.respond(BalanceAtBlock::NoSuchBlock).await,
+            };
+            let state_root_hash = *header.state_root_hash();
+
+            let purse_uref = match purse_or_account {
+                Key::URef(uref) => uref,
+                Key::Account(_) => {
+                    let query = QueryRequest::new(state_root_hash.into(), purse_or_account, vec![]);
+                    match effect_builder.query_global_state(query).await {
+                        Ok(QueryResult::Success { value, .. }) => match value.as_account() {
+                            Some(account) => account.main_purse(),
+                            None => {
+                                let msg = format!("{} is not an account", purse_or_account);
+                                return responder.respond(BalanceAtBlock::NoSuchPurse(msg)).await;
+                            }
+                        },
+                        Ok(query_result) => {
+                            let msg =
+                                format!("failed to read {}: {:?}", purse_or_account, query_result);
+                            return responder.respond(BalanceAtBlock::NoSuchPurse(msg)).await;
+                        }
+                        Err(error) => {
+                            let msg = format!("failed to read {}: {}", purse_or_account, error);
+                            return responder.respond(BalanceAtBlock::NoSuchPurse(msg)).await;
+                        }
+                    }
+                }
+                other => {
+                    let msg = format!("{} is neither a purse nor an account", other);
+                    return responder.respond(BalanceAtBlock::NoSuchPurse(msg)).await;
+                }
+            };
+
+            let result = effect_builder
+                .get_balance(BalanceRequest::new(state_root_hash.into(), purse_uref))
+                .await;
+            responder
+                .respond(BalanceAtBlock::Balance {
+                    block_hash: header.hash(),
+                    block_height: header.height(),
+                    state_root_hash,
+                    result,
+                })
+                .await
+        }
+        .ignore()
+    }
+
     fn handle_get_balance<REv: ReactorEventT>(
         &mut self,
         effect_builder: EffectBuilder<REv>,
         state_root_hash: Digest,
         purse_uref: URef,
-        responder: Responder<Result<BalanceResult, engine_state::Error>>,
+        responder: Responder<Result<BalanceResult, QueryError>>,
     ) -> Effects<Event> {
         let query = BalanceRequest::new(state_root_hash.into(), purse_uref);
         effect_builder
@@ -193,36 +361,113 @@ where
             Event::RpcRequest(RpcRequest::SubmitDeploy { deploy, responder }) => effect_builder
                 .announce_deploy_received(deploy, Some(responder))
                 .ignore(),
+            Event::RpcRequest(RpcRequest::SubmitDeploys { deploys, responder }) => {
+                if deploys.len() > self.max_deploys_per_batch as usize {
+                    let error = ExcessiveBatchSize {
+                        max_deploys_per_batch: self.max_deploys_per_batch,
+                        got: deploys.len(),
+                    };
+                    return responder.respond(Err(error)).ignore();
+                }
+                async move {
+                    let results = future::join_all(deploys.into_iter().map(|deploy| {
+                        effect_builder
+                            .announce_deploy_received_and_await_acceptance(Box::new(deploy))
+                    }))
+                    .await;
+                    responder.respond(Ok(results)).await
+                }
+                .ignore()
+            }
             Event::RpcRequest(RpcRequest::GetBlock {
-                maybe_id: Some(BlockIdentifier::Hash(hash)),
+                block_identifier,
                 responder,
             }) => effect_builder
-                .get_block_with_metadata_from_storage(hash)
+                .get_block_with_metadata_by_identifier_from_storage(block_identifier)
                 .event(move |result| Event::GetBlockResult {
-                    maybe_id: Some(BlockIdentifier::Hash(hash)),
+                    block_identifier,
                     result: Box::new(result),
                     main_responder: responder,
                 }),
-            Event::RpcRequest(RpcRequest::GetBlock {
-                maybe_id: Some(BlockIdentifier::Height(height)),
+            Event::RpcRequest(RpcRequest::GetBlocksByProposer {
+                public_key,
+                era_id,
                 responder,
             }) => effect_builder
-                .get_block_at_height_with_metadata_from_storage(height)
-                .event(move |result| Event::GetBlockResult {
-                    maybe_id: Some(BlockIdentifier::Height(height)),
-                    result: Box::new(result),
+                .get_blocks_by_proposer_from_storage(public_key.clone(), era_id)
+                .event(move |result| Event::GetBlocksByProposerResult {
+                    public_key,
+                    era_id,
+                    result,
                     main_responder: responder,
                 }),
-            Event::RpcRequest(RpcRequest::GetBlock {
-                maybe_id: None,
+            Event::RpcRequest(RpcRequest::GetDeploysByAccount {
+                account_hash,
+                limit,
+                offset,
                 responder,
             }) => effect_builder
-                .get_highest_block_with_metadata_from_storage()
-                .event(move |result| Event::GetBlockResult {
-                    maybe_id: None,
-                    result: Box::new(result),
+                .get_deploys_by_account_from_storage(account_hash, limit, offset)
+                .event(move |result| Event::GetDeploysByAccountResult {
+                    account_hash,
+                    result,
                     main_responder: responder,
                 }),
+            Event::RpcRequest(RpcRequest::GetContractEvents {
+                contract_hash,
+                from_block,
+                to_block,
+                responder,
+            }) => async move {
+                let events = effect_builder
+                    .get_contract_events_from_storage(contract_hash, from_block, to_block)
+                    .await;
+                responder.respond(events).await;
+            }
+            .ignore(),
+            Event::RpcRequest(RpcRequest::GetEraRewards { era_id, responder }) => async move {
+                let era_rewards = effect_builder.get_era_rewards_from_storage(era_id).await;
+                responder.respond(era_rewards).await;
+            }
+            .ignore(),
+            Event::RpcRequest(RpcRequest::GetExecutionJournal {
+                deploy_hash,
+                responder,
+            }) => async move {
+                let journal = effect_builder
+                    .get_execution_journal_from_storage(deploy_hash)
+                    .await;
+                responder.respond(journal).await;
+            }
+            .ignore(),
+            Event::RpcRequest(RpcRequest::GetDeployConfig { responder }) => responder
+                .respond((self.chain_name.clone(), self.deploy_config))
+                .ignore(),
+            Event::RpcRequest(RpcRequest::PutPendingDeploy { deploy, responder }) => async move {
+                let result = effect_builder.put_pending_deploy_to_storage(deploy).await;
+                responder.respond(result).await;
+            }
+            .ignore(),
+            Event::RpcRequest(RpcRequest::GetPendingDeploy {
+                deploy_hash,
+                responder,
+            }) => async move {
+                let maybe_deploy = effect_builder
+                    .get_pending_deploy_from_storage(deploy_hash)
+                    .await;
+                responder.respond(maybe_deploy).await;
+            }
+            .ignore(),
+            Event::RpcRequest(RpcRequest::RemovePendingDeploy {
+                deploy_hash,
+                responder,
+            }) => async move {
+                let _ = effect_builder
+                    .remove_pending_deploy_from_storage(deploy_hash)
+                    .await;
+                responder.respond(()).await;
+            }
+            .ignore(),
             Event::RpcRequest(RpcRequest::GetBlockTransfers {
                 block_hash,
                 responder,
@@ -270,6 +515,16 @@ where
                 purse_uref,
                 responder,
             }) => self.handle_get_balance(effect_builder, state_root_hash, purse_uref, responder),
+            Event::RpcRequest(RpcRequest::GetBalanceAtBlock {
+                block_identifier,
+                purse_or_account,
+                responder,
+            }) => self.handle_get_balance_at_block(
+                effect_builder,
+                block_identifier,
+                purse_or_account,
+                responder,
+            ),
             Event::RpcRequest(RpcRequest::GetDeploy { hash, responder }) => effect_builder
                 .get_deploy_and_metadata_from_storage(hash)
                 .event(move |result| Event::GetDeployResult {
@@ -283,18 +538,32 @@ where
                     peers,
                     main_responder: responder,
                 }),
-            Event::RpcRequest(RpcRequest::GetStatus { responder }) => async move {
-                let (last_added_block, peers, chainspec_info, consensus_status) = join!(
-                    effect_builder.get_highest_block_from_storage(),
-                    effect_builder.network_peers(),
-                    effect_builder.get_chainspec_info(),
-                    effect_builder.consensus_status()
-                );
-                let status_feed =
-                    StatusFeed::new(last_added_block, peers, chainspec_info, consensus_status);
-                responder.respond(status_feed).await;
+            Event::RpcRequest(RpcRequest::GetStatus { responder }) => {
+                async move {
+                    let (
+                        last_added_block,
+                        peers,
+                        chainspec_info,
+                        consensus_status,
+                        trie_sync_progress,
+                    ) = join!(
+                        effect_builder.get_highest_block_from_storage(),
+                        effect_builder.network_peers(),
+                        effect_builder.get_chainspec_info(),
+                        effect_builder.consensus_status(),
+                        effect_builder.trie_sync_progress()
+                    );
+                    let status_feed = StatusFeed::new(
+                        last_added_block,
+                        peers,
+                        chainspec_info,
+                        consensus_status,
+                        trie_sync_progress,
+                    );
+                    responder.respond(status_feed).await;
+                }
+                .ignore()
             }
-            .ignore(),
             Event::RpcRequest(RpcRequest::GetMetrics { responder }) => effect_builder
                 .get_metrics()
                 .event(move |text| Event::GetMetricsResult {
@@ -302,10 +571,20 @@ where
                     main_responder: responder,
                 }),
             Event::GetBlockResult {
-                maybe_id: _,
+                block_identifier: _,
                 result,
                 main_responder,
             } => main_responder.respond(*result).ignore(),
+            Event::GetBlocksByProposerResult {
+                result,
+                main_responder,
+                ..
+            } => main_responder.respond(result).ignore(),
+            Event::GetDeploysByAccountResult {
+                result,
+                main_responder,
+                ..
+            } => main_responder.respond(result).ignore(),
             Event::GetBlockTransfersResult {
                 result,
                 main_responder,
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
@@ -7,25 +9,68 @@ use serde::{Deserialize, Serialize};
 const DEFAULT_ADDRESS: &str = "0.0.0.0:0";
 /// Default rate limit in qps.
 const DEFAULT_QPS_LIMIT: u64 = 100;
+/// Default maximum number of deploys in a single "account_put_deploys" request.
+const DEFAULT_MAX_DEPLOYS_PER_BATCH: u32 = 100;
+/// Default HTTP methods allowed in cross-origin requests.
+const DEFAULT_CORS_METHODS: [&str; 2] = ["POST", "OPTIONS"];
 
 /// JSON-RPC HTTP server configuration.
 #[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
 // Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
 #[serde(deny_unknown_fields)]
 pub struct Config {
+    /// Whether to run the server.
+    pub enable_server: bool,
+
     /// Address to bind JSON-RPC HTTP server to.
     pub address: String,
 
     /// Max rate limit in qps.
     pub qps_limit: u64,
+
+    /// Maximum number of deploys in a single batch submission.
+    pub max_deploys_per_batch: u32,
+
+    /// Origins allowed to make cross-origin requests, or `"*"` to allow any origin.
+    ///
+    /// If empty, no CORS headers are served.
+    #[serde(default)]
+    pub cors_origins: Vec<String>,
+
+    /// HTTP methods allowed in cross-origin requests.
+    #[serde(default = "default_cors_methods")]
+    pub cors_methods: Vec<String>,
+
+    /// Path to the PEM-encoded certificate chain to serve over TLS.
+    ///
+    /// If set, `tls_key_path` must be set too, and the server only accepts TLS connections.
+    #[serde(default)]
+    pub tls_cert_path: Option<PathBuf>,
+
+    /// Path to the PEM-encoded private key of the TLS certificate.
+    #[serde(default)]
+    pub tls_key_path: Option<PathBuf>,
+}
+
+fn default_cors_methods() -> Vec<String> {
+    DEFAULT_CORS_METHODS
+        .iter()
+        .map(|method| method.to_string())
+        .collect()
 }
 
 impl Config {
     /// Creates a default instance for `RpcServer`.
     pub fn new() -> Self {
         Config {
+            enable_server: true,
             address: DEFAULT_ADDRESS.to_string(),
             qps_limit: DEFAULT_QPS_LIMIT,
+            max_deploys_per_batch: DEFAULT_MAX_DEPLOYS_PER_BATCH,
+            cors_origins: vec![],
+            cors_methods: default_cors_methods(),
+            tls_cert_path: None,
+            tls_key_path: None,
         }
     }
 }
@@ -9,12 +9,17 @@ use casper_execution_engine::{
     core::engine_state::{self, BalanceResult, GetBidsResult, GetEraValidatorsError, QueryResult},
     storage::protocol_data::ProtocolData,
 };
-use casper_types::{system::auction::EraValidators, Transfer};
+use casper_types::{
+    account::AccountHash, system::auction::EraValidators, EraId, PublicKey, Transfer,
+};
 
 use crate::{
+    components::contract_runtime::QueryError,
     effect::{requests::RpcRequest, Responder},
-    rpcs::chain::BlockIdentifier,
-    types::{Block, BlockHash, BlockSignatures, Deploy, DeployHash, DeployMetadata, NodeId},
+    types::{
+        Block, BlockHash, BlockIdentifier, BlockSignatures, Deploy, DeployHash, DeployMetadata,
+        NodeId,
+    },
 };
 
 #[derive(Debug, From)]
@@ -22,10 +27,21 @@ pub enum Event {
     #[from]
     RpcRequest(RpcRequest<NodeId>),
     GetBlockResult {
-        maybe_id: Option<BlockIdentifier>,
+        block_identifier: BlockIdentifier,
         result: Box<Option<(Block, BlockSignatures)>>,
         main_responder: Responder<Option<(Block, BlockSignatures)>>,
     },
+    GetBlocksByProposerResult {
+        public_key: PublicKey,
+        era_id: EraId,
+        result: Vec<Block>,
+        main_responder: Responder<Vec<Block>>,
+    },
+    GetDeploysByAccountResult {
+        account_hash: AccountHash,
+        result: Vec<DeployHash>,
+        main_responder: Responder<Vec<DeployHash>>,
+    },
     GetBlockTransfersResult {
         block_hash: BlockHash,
         result: Box<Option<Vec<Transfer>>>,
@@ -36,16 +52,16 @@ pub enum Event {
         main_responder: Responder<Result<Option<Box<ProtocolData>>, engine_state::Error>>,
     },
     QueryGlobalStateResult {
-        result: Result<QueryResult, engine_state::Error>,
-        main_responder: Responder<Result<QueryResult, engine_state::Error>>,
+        result: Result<QueryResult, QueryError>,
+        main_responder: Responder<Result<QueryResult, QueryError>>,
     },
     QueryEraValidatorsResult {
         result: Result<EraValidators, GetEraValidatorsError>,
         main_responder: Responder<Result<EraValidators, GetEraValidatorsError>>,
     },
     GetBidsResult {
-        result: Result<GetBidsResult, engine_state::Error>,
-        main_responder: Responder<Result<GetBidsResult, engine_state::Error>>,
+        result: Result<GetBidsResult, QueryError>,
+        main_responder: Responder<Result<GetBidsResult, QueryError>>,
     },
     GetDeployResult {
         hash: DeployHash,
@@ -61,8 +77,8 @@ pub enum Event {
         main_responder: Responder<Option<String>>,
     },
     GetBalanceResult {
-        result: Result<BalanceResult, engine_state::Error>,
-        main_responder: Responder<Result<BalanceResult, engine_state::Error>>,
+        result: Result<BalanceResult, QueryError>,
+        main_responder: Responder<Result<BalanceResult, QueryError>>,
     },
 }
 
@@ -71,20 +87,36 @@ impl Display for Event {
         match self {
             Event::RpcRequest(request) => write!(formatter, "{}", request),
             Event::GetBlockResult {
-                maybe_id: Some(BlockIdentifier::Hash(hash)),
+                block_identifier,
                 result,
                 ..
-            } => write!(formatter, "get block result for {}: {:?}", hash, result),
-            Event::GetBlockResult {
-                maybe_id: Some(BlockIdentifier::Height(height)),
+            } => write!(
+                formatter,
+                "get block result for {}: {:?}",
+                block_identifier, result
+            ),
+            Event::GetBlocksByProposerResult {
+                public_key,
+                era_id,
                 result,
                 ..
-            } => write!(formatter, "get block result for {}: {:?}", height, result),
-            Event::GetBlockResult {
-                maybe_id: None,
+            } => write!(
+                formatter,
+                "get blocks by {} in era {} result: {} blocks",
+                public_key,
+                era_id,
+                result.len()
+            ),
+            Event::GetDeploysByAccountResult {
+                account_hash,
                 result,
                 ..
-            } => write!(formatter, "get latest block result: {:?}", result),
+            } => write!(
+                formatter,
+                "get deploys of {} result: {} deploy hashes",
+                account_hash,
+                result.len()
+            ),
             Event::GetBlockTransfersResult {
                 block_hash, result, ..
             } => write!(
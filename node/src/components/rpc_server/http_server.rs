@@ -1,68 +1,67 @@
 use std::{convert::Infallible, time::Duration};
 
 use futures::future;
-use http::{Response, StatusCode};
-use hyper::{
-    server::{conn::AddrIncoming, Builder},
-    Body,
-};
-use serde::Serialize;
+use http::StatusCode;
+use hyper::Server;
 use tokio::sync::oneshot;
 use tower::builder::ServiceBuilder;
 use tracing::{info, trace};
-use warp::{Filter, Rejection};
+use warp::{cors::Builder as CorsBuilder, Filter, Rejection, Reply};
 
 use casper_types::ProtocolVersion;
 
 use super::{
-    rpcs::{self, RpcWithOptionalParamsExt, RpcWithParamsExt, RpcWithoutParamsExt, RPC_API_PATH},
+    incoming::Incoming,
+    rpcs::{
+        self, error::new_json_rpc_error_response, RpcWithOptionalParamsExt, RpcWithParamsExt,
+        RpcWithoutParamsExt, RPC_API_PATH,
+    },
     ReactorEventT,
 };
 use crate::effect::EffectBuilder;
 
-// This is a workaround for not being able to create a `warp_json_rpc::Response` without a
-// `warp_json_rpc::Builder`.
-fn new_error_response(error: warp_json_rpc::Error) -> Response<Body> {
-    #[derive(Serialize)]
-    struct JsonRpcErrorResponse {
-        jsonrpc: String,
-        id: Option<()>,
-        error: warp_json_rpc::Error,
-    }
-
-    let json_response = JsonRpcErrorResponse {
-        jsonrpc: "2.0".to_string(),
-        id: None,
-        error,
-    };
-
-    let body = Body::from(serde_json::to_vec(&json_response).unwrap());
-    Response::builder()
-        .status(StatusCode::OK)
-        .header("Content-Type", "application/json")
-        .body(body)
-        .unwrap()
-}
-
 /// Run the JSON-RPC server.
+///
+/// If `cors` is given, CORS headers are served and preflight requests answered accordingly.
 pub(super) async fn run<REv: ReactorEventT>(
-    builder: Builder<AddrIncoming>,
+    incoming: Incoming,
     effect_builder: EffectBuilder<REv>,
     api_version: ProtocolVersion,
     qps_limit: u64,
+    cors: Option<CorsBuilder>,
 ) {
     // RPC filters.
     let rpc_put_deploy = rpcs::account::PutDeploy::create_filter(effect_builder, api_version);
+    let rpc_put_deploys = rpcs::account::PutDeploys::create_filter(effect_builder, api_version);
+    let rpc_get_deploys = rpcs::account::GetDeploys::create_filter(effect_builder, api_version);
+    let rpc_put_pending_deploy =
+        rpcs::account::PutPendingDeploy::create_filter(effect_builder, api_version);
+    let rpc_add_deploy_approvals =
+        rpcs::account::AddDeployApprovals::create_filter(effect_builder, api_version);
+    let rpc_get_pending_deploy =
+        rpcs::info::GetPendingDeploy::create_filter(effect_builder, api_version);
     let rpc_get_block = rpcs::chain::GetBlock::create_filter(effect_builder, api_version);
     let rpc_get_block_transfers =
         rpcs::chain::GetBlockTransfers::create_filter(effect_builder, api_version);
     let rpc_get_state_root_hash =
         rpcs::chain::GetStateRootHash::create_filter(effect_builder, api_version);
+    let rpc_get_blocks_by_proposer =
+        rpcs::chain::GetBlocksByProposer::create_filter(effect_builder, api_version);
+    let rpc_get_events = rpcs::chain::GetEvents::create_filter(effect_builder, api_version);
+    let rpc_get_era_rewards =
+        rpcs::chain::GetEraRewards::create_filter(effect_builder, api_version);
     let rpc_get_item = rpcs::state::GetItem::create_filter(effect_builder, api_version);
     let rpc_get_balance = rpcs::state::GetBalance::create_filter(effect_builder, api_version);
+    let rpc_get_balance_at_block =
+        rpcs::state::GetBalanceAtBlock::create_filter(effect_builder, api_version);
+    let rpc_get_account_info =
+        rpcs::state::GetAccountInfo::create_filter(effect_builder, api_version);
     let rpc_get_deploy = rpcs::info::GetDeploy::create_filter(effect_builder, api_version);
+    let rpc_get_execution_journal =
+        rpcs::info::GetExecutionJournal::create_filter(effect_builder, api_version);
     let rpc_get_peers = rpcs::info::GetPeers::create_filter(effect_builder, api_version);
     let rpc_get_status = rpcs::info::GetStatus::create_filter(effect_builder, api_version);
+    let rpc_get_chainspec = rpcs::info::GetChainspec::create_filter(effect_builder, api_version);
     let rpc_get_era_info =
         rpcs::chain::GetEraInfoBySwitchBlock::create_filter(effect_builder, api_version);
     let rpc_get_auction_info =
@@ -80,29 +79,50 @@ pub(super) async fn run<REv: ReactorEventT>(
 
     // Catch requests which don't parse as JSON.
     let parse_failure = warp::path(RPC_API_PATH).and_then(move || async move {
-        let error_response = new_error_response(warp_json_rpc::Error::PARSE_ERROR);
+        let error_response =
+            new_json_rpc_error_response(warp_json_rpc::Error::PARSE_ERROR, StatusCode::OK);
         Ok::<_, Rejection>(error_response)
     });
 
     // TODO - we can't catch cases where we should return `warp_json_rpc::Error::INVALID_REQUEST`
     //        (i.e. where the request is JSON, but not valid JSON-RPC).  This will require an
     //        update to or move away from warp_json_rpc.
-    let service = warp_json_rpc::service(
-        rpc_put_deploy
-            .or(rpc_get_block)
-            .or(rpc_get_block_transfers)
-            .or(rpc_get_state_root_hash)
-            .or(rpc_get_item)
-            .or(rpc_get_balance)
-            .or(rpc_get_deploy)
-            .or(rpc_get_peers)
-            .or(rpc_get_status)
-            .or(rpc_get_era_info)
-            .or(rpc_get_auction_info)
-            .or(rpc_get_rpcs)
-            .or(unknown_method)
-            .or(parse_failure),
-    );
+    let rpc_filters = rpc_put_deploy
+        .or(rpc_put_deploys)
+        .or(rpc_get_deploys)
+        .or(rpc_put_pending_deploy)
+        .or(rpc_add_deploy_approvals)
+        .or(rpc_get_pending_deploy)
+        .or(rpc_get_block)
+        .or(rpc_get_block_transfers)
+        .or(rpc_get_state_root_hash)
+        .or(rpc_get_blocks_by_proposer)
+        .or(rpc_get_events)
+        .or(rpc_get_era_rewards)
+        .or(rpc_get_item)
+        .or(rpc_get_balance)
+        .or(rpc_get_balance_at_block)
+        .or(rpc_get_account_info)
+        .or(rpc_get_deploy)
+        .or(rpc_get_execution_journal)
+        .or(rpc_get_peers)
+        .or(rpc_get_status)
+        .or(rpc_get_chainspec)
+        .or(rpc_get_era_info)
+        .or(rpc_get_auction_info)
+        .or(rpc_get_rpcs)
+        .or(unknown_method)
+        .or(parse_failure);
+    let rpc_filters = match cors {
+        Some(cors) => rpc_filters
+            .with(cors)
+            .map(|reply| Box::new(reply) as Box<dyn Reply>)
+            .boxed(),
+        None => rpc_filters
+            .map(|reply| Box::new(reply) as Box<dyn Reply>)
+            .boxed(),
+    };
+    let service = warp_json_rpc::service(rpc_filters);
 
     // Start the server, passing a oneshot receiver to allow the server to be shut down gracefully.
     let make_svc =
@@ -114,8 +134,8 @@ pub(super) async fn run<REv: ReactorEventT>(
 
     let (shutdown_sender, shutdown_receiver) = oneshot::channel::<()>();
 
-    let server = builder.serve(make_svc);
-    info!(address = %server.local_addr(), "started JSON-RPC server");
+    info!(address = %incoming.local_addr(), "started JSON-RPC server");
+    let server = Server::builder(incoming).serve(make_svc);
 
     let server_with_shutdown = server.with_graceful_shutdown(async {
         shutdown_receiver.await.ok();
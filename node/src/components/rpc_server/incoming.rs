@@ -0,0 +1,161 @@
+//! Incoming connections to the JSON-RPC server, optionally secured by TLS.
+
+use std::{
+    io,
+    net::SocketAddr,
+    path::Path,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use futures::{
+    future,
+    stream::{self, BoxStream},
+    StreamExt, TryStreamExt,
+};
+use hyper::server::{
+    accept::Accept,
+    conn::{AddrIncoming, AddrStream},
+};
+use openssl::{
+    error::ErrorStack,
+    ssl::{Ssl, SslAcceptor, SslFiletype, SslMethod},
+};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio_openssl::SslStream;
+use tracing::{debug, warn};
+
+/// Maximum number of TLS handshakes performed concurrently.
+const MAX_CONCURRENT_HANDSHAKES: usize = 64;
+
+/// Creates a TLS acceptor serving the PEM-encoded certificate chain and private key at the given
+/// paths.
+pub(super) fn create_tls_acceptor(
+    cert_path: &Path,
+    key_path: &Path,
+) -> Result<SslAcceptor, ErrorStack> {
+    let mut builder = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls_server())?;
+    builder.set_certificate_chain_file(cert_path)?;
+    builder.set_private_key_file(key_path, SslFiletype::PEM)?;
+    builder.check_private_key()?;
+    Ok(builder.build())
+}
+
+/// A connection accepted by the server.
+pub(super) enum Connection {
+    Plain(AddrStream),
+    Tls(Box<SslStream<AddrStream>>),
+}
+
+impl AsyncRead for Connection {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Connection::Plain(stream) => Pin::new(stream).poll_read(cx, buf),
+            Connection::Tls(stream) => Pin::new(stream).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for Connection {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Connection::Plain(stream) => Pin::new(stream).poll_write(cx, buf),
+            Connection::Tls(stream) => Pin::new(stream).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Connection::Plain(stream) => Pin::new(stream).poll_flush(cx),
+            Connection::Tls(stream) => Pin::new(stream).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Connection::Plain(stream) => Pin::new(stream).poll_shutdown(cx),
+            Connection::Tls(stream) => Pin::new(stream).poll_shutdown(cx),
+        }
+    }
+}
+
+/// The incoming connections of a bound listener, performing the TLS handshake on each if a TLS
+/// acceptor is given.
+pub(super) struct Incoming {
+    local_addr: SocketAddr,
+    connections: BoxStream<'static, io::Result<Connection>>,
+}
+
+impl Incoming {
+    pub(super) fn new(mut incoming: AddrIncoming, tls_acceptor: Option<SslAcceptor>) -> Self {
+        let local_addr = incoming.local_addr();
+        let streams = stream::poll_fn(move |cx| Pin::new(&mut incoming).poll_accept(cx));
+        let connections = match tls_acceptor {
+            None => streams.map_ok(Connection::Plain).boxed(),
+            Some(tls_acceptor) => {
+                let tls_acceptor = Arc::new(tls_acceptor);
+                streams
+                    .map(move |result| {
+                        let tls_acceptor = Arc::clone(&tls_acceptor);
+                        async move {
+                            match result {
+                                Ok(stream) => tls_handshake(&tls_acceptor, stream).await.map(Ok),
+                                Err(error) => Some(Err(error)),
+                            }
+                        }
+                    })
+                    .buffer_unordered(MAX_CONCURRENT_HANDSHAKES)
+                    .filter_map(future::ready)
+                    .boxed()
+            }
+        };
+        Incoming {
+            local_addr,
+            connections,
+        }
+    }
+
+    /// Returns the address the listener is bound to.
+    pub(super) fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+impl Accept for Incoming {
+    type Conn = Connection;
+    type Error = io::Error;
+
+    fn poll_accept(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<io::Result<Connection>>> {
+        self.connections.poll_next_unpin(cx)
+    }
+}
+
+/// Performs the server-side TLS handshake, returning `None` if it fails.
+async fn tls_handshake(tls_acceptor: &SslAcceptor, stream: AddrStream) -> Option<Connection> {
+    let remote_addr = stream.remote_addr();
+    let mut tls_stream =
+        match Ssl::new(tls_acceptor.context()).and_then(|ssl| SslStream::new(ssl, stream)) {
+            Ok(tls_stream) => tls_stream,
+            Err(error) => {
+                warn!(%error, %remote_addr, "failed to create TLS stream");
+                return None;
+            }
+        };
+    if let Err(error) = SslStream::accept(Pin::new(&mut tls_stream)).await {
+        debug!(%error, %remote_addr, "TLS handshake with JSON-RPC client failed");
+        return None;
+    }
+    Some(Connection::Tls(Box::new(tls_stream)))
+}
@@ -5,6 +5,7 @@
 pub mod account;
 pub mod chain;
 pub mod docs;
+pub mod error;
 pub mod info;
 pub mod state;
 
@@ -16,11 +17,7 @@ use hyper::Body;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use warp::{
-    filters::BoxedFilter,
-    reject::{self, Reject},
-    Filter,
-};
+use warp::{filters::BoxedFilter, reject, Filter, Rejection};
 use warp_json_rpc::{filters, Builder};
 
 use casper_types::ProtocolVersion;
@@ -28,37 +25,12 @@ use casper_types::ProtocolVersion;
 use super::{ReactorEventT, RpcRequest};
 use crate::effect::EffectBuilder;
 use docs::DocExample;
+pub(super) use error::{error_response, Error};
+pub use error::{ApiError, ErrorCode};
 
 /// The URL path.
 pub const RPC_API_PATH: &str = "rpc";
 
-/// Error code returned if the JSON-RPC response indicates failure.
-///
-/// See <https://www.jsonrpc.org/specification#error_object> for details.
-#[repr(i64)]
-enum ErrorCode {
-    NoSuchDeploy = -32000,
-    NoSuchBlock = -32001,
-    ParseQueryKey = -32002,
-    QueryFailed = -32003,
-    QueryFailedToExecute = -32004,
-    ParseGetBalanceURef = -32005,
-    GetBalanceFailed = -32006,
-    GetBalanceFailedToExecute = -32007,
-    InvalidDeploy = -32008,
-}
-
-#[derive(Debug)]
-pub(super) struct Error(String);
-
-impl Reject for Error {}
-
-impl From<anyhow::Error> for Error {
-    fn from(error: anyhow::Error) -> Self {
-        Error(error.to_string())
-    }
-}
-
 /// A JSON-RPC requiring the "params" field to be present.
 pub trait RpcWithParams {
     /// The JSON-RPC "method" name.
@@ -95,7 +67,9 @@ pub(super) trait RpcWithParamsExt: RpcWithParams {
             .and_then(
                 move |response_builder: Builder, params: Self::RequestParams| {
                     Self::handle_request(effect_builder, response_builder, params, api_version)
-                        .map_err(reject::custom)
+                        .or_else(|error| async move {
+                            Ok::<_, Rejection>(error.into_json_rpc_response())
+                        })
                 },
             );
         let with_invalid_params = warp::path(RPC_API_PATH)
@@ -159,8 +133,9 @@ pub(super) trait RpcWithoutParamsExt: RpcWithoutParams {
             .and(filters::json_rpc())
             .and(filters::method(Self::METHOD))
             .and_then(move |response_builder: Builder| {
-                Self::handle_request(effect_builder, response_builder, api_version)
-                    .map_err(reject::custom)
+                Self::handle_request(effect_builder, response_builder, api_version).or_else(
+                    |error| async move { Ok::<_, Rejection>(error.into_json_rpc_response()) },
+                )
             });
         let with_params = warp::path(RPC_API_PATH)
             .and(filters::json_rpc())
@@ -226,7 +201,9 @@ pub(super) trait RpcWithOptionalParamsExt: RpcWithOptionalParams {
                         Some(params),
                         api_version,
                     )
-                    .map_err(reject::custom)
+                    .or_else(|error| async move {
+                        Ok::<_, Rejection>(error.into_json_rpc_response())
+                    })
                 },
             );
         let with_invalid_params = warp::path(RPC_API_PATH)
@@ -244,8 +221,9 @@ pub(super) trait RpcWithOptionalParamsExt: RpcWithOptionalParams {
             .and(filters::json_rpc())
             .and(filters::method(Self::METHOD))
             .and_then(move |response_builder: Builder| {
-                Self::handle_request(effect_builder, response_builder, None, api_version)
-                    .map_err(reject::custom)
+                Self::handle_request(effect_builder, response_builder, None, api_version).or_else(
+                    |error| async move { Ok::<_, Rejection>(error.into_json_rpc_response()) },
+                )
             });
         with_params
             .or(without_params)
@@ -269,11 +247,11 @@ mod common {
 
     use once_cell::sync::Lazy;
 
-    use casper_execution_engine::core::engine_state::{self, QueryResult};
+    use casper_execution_engine::core::engine_state::QueryResult;
     use casper_types::bytesrepr::ToBytes;
 
-    use super::ErrorCode;
-    use crate::types::json_compatibility::StoredValue;
+    use super::{ApiError, ErrorCode};
+    use crate::{components::contract_runtime::QueryError, types::json_compatibility::StoredValue};
 
     pub(super) static MERKLE_PROOF: Lazy<String> = Lazy::new(|| {
         String::from(
@@ -292,17 +270,17 @@ mod common {
 
     // Extract the EE `(StoredValue, Vec<TrieMerkleProof<Key, StoredValue>>)` from the result.
     pub(super) fn extract_query_result(
-        query_result: Result<QueryResult, engine_state::Error>,
-    ) -> Result<(StoredValue, Vec<u8>), (ErrorCode, String)> {
+        query_result: Result<QueryResult, QueryError>,
+    ) -> Result<(StoredValue, Vec<u8>), ApiError> {
         let (value, proof) = match query_result {
             Ok(QueryResult::Success { value, proofs }) => (value, proofs),
             Ok(query_result) => {
                 let error_msg = format!("state query failed: {:?}", query_result);
-                return Err((ErrorCode::QueryFailed, error_msg));
+                return Err(ApiError::new(ErrorCode::QueryFailed, error_msg));
             }
             Err(error) => {
                 let error_msg = format!("state query failed to execute: {:?}", error);
-                return Err((ErrorCode::QueryFailedToExecute, error_msg));
+                return Err(ApiError::new(ErrorCode::QueryFailedToExecute, error_msg));
             }
         };
 
@@ -310,7 +288,7 @@ mod common {
             Ok(value_compat) => value_compat,
             Err(error) => {
                 let error_msg = format!("failed to encode stored value: {:?}", error);
-                return Err((ErrorCode::QueryFailed, error_msg));
+                return Err(ApiError::new(ErrorCode::QueryFailed, error_msg));
             }
         };
 
@@ -318,7 +296,7 @@ mod common {
             Ok(proof_bytes) => proof_bytes,
             Err(error) => {
                 let error_msg = format!("failed to encode stored value: {:?}", error);
-                return Err((ErrorCode::QueryFailed, error_msg));
+                return Err(ApiError::new(ErrorCode::QueryFailed, error_msg));
             }
         };
 
@@ -3,7 +3,7 @@
 // TODO - remove once schemars stops causing warning.
 #![allow(clippy::field_reassign_with_default)]
 
-use std::str;
+use std::{collections::BTreeSet, str};
 
 use futures::{future::BoxFuture, FutureExt};
 use http::Response;
@@ -14,17 +14,21 @@ use serde::{Deserialize, Serialize};
 use tracing::info;
 use warp_json_rpc::Builder;
 
-use casper_types::ProtocolVersion;
+use casper_execution_engine::{core::engine_state::QueryResult, shared::stored_value::StoredValue};
+use casper_types::{account::AccountHash, Key, ProtocolVersion};
 
 use super::{
     docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
-    Error, ReactorEventT, RpcRequest, RpcWithParams, RpcWithParamsExt,
+    error_response, ApiError, Error, ReactorEventT, RpcRequest, RpcWithParams, RpcWithParamsExt,
 };
 use crate::{
     components::rpc_server::rpcs::ErrorCode,
     effect::EffectBuilder,
     reactor::QueueKind,
-    types::{Deploy, DeployHash},
+    types::{
+        chainspec::DeployConfig, json_compatibility::cl_value, Approval, BlockIdentifier, Deploy,
+        DeployHash, Timestamp,
+    },
 };
 
 static PUT_DEPLOY_PARAMS: Lazy<PutDeployParams> = Lazy::new(|| PutDeployParams {
@@ -34,12 +38,60 @@ static PUT_DEPLOY_RESULT: Lazy<PutDeployResult> = Lazy::new(|| PutDeployResult {
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     deploy_hash: *Deploy::doc_example().id(),
 });
+static PUT_DEPLOYS_PARAMS: Lazy<PutDeploysParams> = Lazy::new(|| PutDeploysParams {
+    deploys: vec![Deploy::doc_example().clone()],
+});
+static PUT_DEPLOYS_RESULT: Lazy<PutDeploysResult> = Lazy::new(|| PutDeploysResult {
+    api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+    results: vec![PutDeploysItemResult {
+        deploy_hash: *Deploy::doc_example().id(),
+        accepted: true,
+        error: None,
+    }],
+});
+static GET_DEPLOYS_PARAMS: Lazy<GetDeploysParams> = Lazy::new(|| GetDeploysParams {
+    account_hash: Deploy::doc_example().header().account().to_account_hash(),
+    limit: 10,
+    offset: 0,
+});
+static GET_DEPLOYS_RESULT: Lazy<GetDeploysResult> = Lazy::new(|| GetDeploysResult {
+    api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+    deploy_hashes: vec![*Deploy::doc_example().id()],
+});
+static PUT_PENDING_DEPLOY_PARAMS: Lazy<PutPendingDeployParams> =
+    Lazy::new(|| PutPendingDeployParams {
+        deploy: Deploy::doc_example().clone(),
+    });
+static PUT_PENDING_DEPLOY_RESULT: Lazy<PutPendingDeployResult> =
+    Lazy::new(|| PutPendingDeployResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        deploy_hash: *Deploy::doc_example().id(),
+        submitted: false,
+    });
+static ADD_DEPLOY_APPROVALS_PARAMS: Lazy<AddDeployApprovalsParams> =
+    Lazy::new(|| AddDeployApprovalsParams {
+        deploy_hash: *Deploy::doc_example().id(),
+        approvals: Deploy::doc_example().approvals().to_vec(),
+    });
+static ADD_DEPLOY_APPROVALS_RESULT: Lazy<AddDeployApprovalsResult> =
+    Lazy::new(|| AddDeployApprovalsResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        deploy_hash: *Deploy::doc_example().id(),
+        submitted: true,
+    });
+
+/// The maximum number of deploy hashes returned by a single "account_get_deploys" request.
+const MAX_DEPLOYS_PER_REQUEST: u32 = 1000;
 
 /// Params for "account_put_deploy" RPC request.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct PutDeployParams {
     /// The `Deploy`.
+    ///
+    /// Runtime args may be given as just a `cl_type` and `parsed` value, in which case their
+    /// `bytes` are derived from these.
+    #[serde(deserialize_with = "cl_value::deserialize_deploy")]
     pub deploy: Deploy,
 }
 
@@ -113,13 +165,624 @@ impl RpcWithParamsExt for PutDeploy {
                         %error,
                         "the deploy submitted by the client was invalid",
                     );
-                    Ok(response_builder.error(warp_json_rpc::Error::custom(
-                        ErrorCode::InvalidDeploy as i64,
-                        error.to_string(),
-                    ))?)
+                    error_response(
+                        response_builder,
+                        ApiError::new(ErrorCode::InvalidDeploy, error.to_string()),
+                    )
+                }
+            }
+        }
+        .boxed()
+    }
+}
+
+/// Params for "account_put_deploys" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PutDeploysParams {
+    /// The `Deploy`s.
+    ///
+    /// Runtime args may be given as just a `cl_type` and `parsed` value, in which case their
+    /// `bytes` are derived from these.
+    #[serde(deserialize_with = "cl_value::deserialize_deploys")]
+    pub deploys: Vec<Deploy>,
+}
+
+impl DocExample for PutDeploysParams {
+    fn doc_example() -> &'static Self {
+        &*PUT_DEPLOYS_PARAMS
+    }
+}
+
+/// The outcome of submitting a single deploy of an "account_put_deploys" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PutDeploysItemResult {
+    /// The deploy hash.
+    pub deploy_hash: DeployHash,
+    /// Whether the deploy was accepted.
+    pub accepted: bool,
+    /// The reason the deploy was rejected, if it was.
+    pub error: Option<String>,
+}
+
+/// Result for "account_put_deploys" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PutDeploysResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The outcome for each of the submitted deploys, in the order they were given.
+    pub results: Vec<PutDeploysItemResult>,
+}
+
+impl DocExample for PutDeploysResult {
+    fn doc_example() -> &'static Self {
+        &*PUT_DEPLOYS_RESULT
+    }
+}
+
+/// "account_put_deploys" RPC
+pub struct PutDeploys {}
+
+impl RpcWithParams for PutDeploys {
+    const METHOD: &'static str = "account_put_deploys";
+    type RequestParams = PutDeploysParams;
+    type ResponseResult = PutDeploysResult;
+}
+
+impl RpcWithParamsExt for PutDeploys {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+        api_version: ProtocolVersion,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            let deploy_hashes: Vec<DeployHash> =
+                params.deploys.iter().map(|deploy| *deploy.id()).collect();
+
+            // Submit the new deploys to be announced.
+            let put_deploys_result = effect_builder
+                .make_request(
+                    |responder| RpcRequest::SubmitDeploys {
+                        deploys: params.deploys,
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+
+            let results = match put_deploys_result {
+                Ok(results) => results,
+                Err(error) => {
+                    info!(%error, "the deploy batch submitted by the client was rejected");
+                    return error_response(
+                        response_builder,
+                        ApiError::new(ErrorCode::InvalidDeploy, error.to_string()),
+                    );
+                }
+            };
+
+            let results = deploy_hashes
+                .into_iter()
+                .zip(results)
+                .map(|(deploy_hash, result)| match result {
+                    Ok(()) => {
+                        info!(%deploy_hash, "deploy was stored");
+                        PutDeploysItemResult {
+                            deploy_hash,
+                            accepted: true,
+                            error: None,
+                        }
+                    }
+                    Err(error) => {
+                        info!(
+                            %deploy_hash,
+                            %error,
+                            "a deploy submitted by the client was invalid",
+                        );
+                        PutDeploysItemResult {
+                            deploy_hash,
+                            accepted: false,
+                            error: Some(error.to_string()),
+                        }
+                    }
+                })
+                .collect();
+            let result = Self::ResponseResult {
+                api_version,
+                results,
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
+
+/// Params for "account_get_deploys" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetDeploysParams {
+    /// The hash of the account which submitted the deploys.
+    pub account_hash: AccountHash,
+    /// The maximum number of deploy hashes to return, capped at 1000.
+    pub limit: u32,
+    /// The number of most recent deploy hashes to skip.
+    #[serde(default)]
+    pub offset: u32,
+}
+
+impl DocExample for GetDeploysParams {
+    fn doc_example() -> &'static Self {
+        &*GET_DEPLOYS_PARAMS
+    }
+}
+
+/// Result for "account_get_deploys" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetDeploysResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The hashes of the deploys submitted by the account and known to this node, most recent
+    /// first.
+    pub deploy_hashes: Vec<DeployHash>,
+}
+
+impl DocExample for GetDeploysResult {
+    fn doc_example() -> &'static Self {
+        &*GET_DEPLOYS_RESULT
+    }
+}
+
+/// "account_get_deploys" RPC
+pub struct GetDeploys {}
+
+impl RpcWithParams for GetDeploys {
+    const METHOD: &'static str = "account_get_deploys";
+    type RequestParams = GetDeploysParams;
+    type ResponseResult = GetDeploysResult;
+}
+
+impl RpcWithParamsExt for GetDeploys {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+        api_version: ProtocolVersion,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            let deploy_hashes = effect_builder
+                .make_request(
+                    |responder| RpcRequest::GetDeploysByAccount {
+                        account_hash: params.account_hash,
+                        limit: params.limit.min(MAX_DEPLOYS_PER_REQUEST) as usize,
+                        offset: params.offset as usize,
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+
+            let result = Self::ResponseResult {
+                api_version,
+                deploy_hashes,
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
+
+/// Params for "account_put_pending_deploy" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PutPendingDeployParams {
+    /// The `Deploy`, with any number of approvals.
+    ///
+    /// Runtime args may be given as just a `cl_type` and `parsed` value, in which case their
+    /// `bytes` are derived from these.
+    #[serde(deserialize_with = "cl_value::deserialize_deploy")]
+    pub deploy: Deploy,
+}
+
+impl DocExample for PutPendingDeployParams {
+    fn doc_example() -> &'static Self {
+        &*PUT_PENDING_DEPLOY_PARAMS
+    }
+}
+
+/// Result for "account_put_pending_deploy" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PutPendingDeployResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The deploy hash.
+    pub deploy_hash: DeployHash,
+    /// Whether the approvals met the account's deployment threshold, so that the deploy was
+    /// submitted to the network. If `false`, it is held by this node until more approvals are
+    /// added or it expires.
+    pub submitted: bool,
+}
+
+impl DocExample for PutPendingDeployResult {
+    fn doc_example() -> &'static Self {
+        &*PUT_PENDING_DEPLOY_RESULT
+    }
+}
+
+/// "account_put_pending_deploy" RPC
+pub struct PutPendingDeploy {}
+
+impl RpcWithParams for PutPendingDeploy {
+    const METHOD: &'static str = "account_put_pending_deploy";
+    type RequestParams = PutPendingDeployParams;
+    type ResponseResult = PutPendingDeployResult;
+}
+
+impl RpcWithParamsExt for PutPendingDeploy {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+        api_version: ProtocolVersion,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            let deploy_hash = *params.deploy.id();
+            match submit_or_store_pending(effect_builder, params.deploy).await {
+                Ok(submitted) => {
+                    let result = Self::ResponseResult {
+                        api_version,
+                        deploy_hash,
+                        submitted,
+                    };
+                    Ok(response_builder.success(result)?)
+                }
+                Err(error) => {
+                    info!(%deploy_hash, "{}", error.message);
+                    error_response(response_builder, error)
+                }
+            }
+        }
+        .boxed()
+    }
+}
+
+/// Params for "account_add_deploy_approvals" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AddDeployApprovalsParams {
+    /// The hash of the pending deploy.
+    pub deploy_hash: DeployHash,
+    /// The approvals to add, i.e. signatures over the deploy hash.
+    pub approvals: Vec<Approval>,
+}
+
+impl DocExample for AddDeployApprovalsParams {
+    fn doc_example() -> &'static Self {
+        &*ADD_DEPLOY_APPROVALS_PARAMS
+    }
+}
+
+/// Result for "account_add_deploy_approvals" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AddDeployApprovalsResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The deploy hash.
+    pub deploy_hash: DeployHash,
+    /// Whether the approvals met the account's deployment threshold, so that the deploy was
+    /// submitted to the network. If `false`, it is held by this node until more approvals are
+    /// added or it expires.
+    pub submitted: bool,
+}
+
+impl DocExample for AddDeployApprovalsResult {
+    fn doc_example() -> &'static Self {
+        &*ADD_DEPLOY_APPROVALS_RESULT
+    }
+}
+
+/// "account_add_deploy_approvals" RPC
+pub struct AddDeployApprovals {}
+
+impl RpcWithParams for AddDeployApprovals {
+    const METHOD: &'static str = "account_add_deploy_approvals";
+    type RequestParams = AddDeployApprovalsParams;
+    type ResponseResult = AddDeployApprovalsResult;
+}
+
+impl RpcWithParamsExt for AddDeployApprovals {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+        api_version: ProtocolVersion,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            let deploy_hash = params.deploy_hash;
+            let maybe_deploy = effect_builder
+                .make_request(
+                    |responder| RpcRequest::GetPendingDeploy {
+                        deploy_hash,
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+
+            let mut deploy = match maybe_deploy {
+                Some(deploy) => *deploy,
+                None => {
+                    let error_msg = format!("no pending deploy with hash {}", deploy_hash);
+                    info!("{}", error_msg);
+                    return error_response(
+                        response_builder,
+                        ApiError::new(ErrorCode::NoSuchDeploy, error_msg),
+                    );
+                }
+            };
+            deploy.add_approvals(params.approvals);
+
+            match submit_or_store_pending(effect_builder, deploy).await {
+                Ok(submitted) => {
+                    let result = Self::ResponseResult {
+                        api_version,
+                        deploy_hash,
+                        submitted,
+                    };
+                    Ok(response_builder.success(result)?)
+                }
+                Err(error) => {
+                    info!(%deploy_hash, "{}", error.message);
+                    error_response(response_builder, error)
                 }
             }
         }
         .boxed()
     }
 }
+
+/// Submits `deploy` to the deploy acceptor if its approvals meet the deployment threshold of its
+/// account as of the latest block, or stores it as a pending deploy otherwise.
+///
+/// Returns `true` if the deploy was submitted.
+async fn submit_or_store_pending<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    mut deploy: Deploy,
+) -> Result<bool, ApiError> {
+    let (chain_name, deploy_config) = effect_builder
+        .make_request(
+            |responder| RpcRequest::GetDeployConfig { responder },
+            QueueKind::Api,
+        )
+        .await;
+    check_acceptable(&mut deploy, &chain_name, &deploy_config, Timestamp::now())?;
+
+    // Read the account record as of the latest block.
+    let maybe_block = effect_builder
+        .make_request(
+            |responder| RpcRequest::GetBlock {
+                block_identifier: BlockIdentifier::Latest,
+                responder,
+            },
+            QueueKind::Api,
+        )
+        .await;
+    let state_root_hash = match maybe_block {
+        Some((block, _)) => *block.header().state_root_hash(),
+        None => {
+            return Err(ApiError::new(
+                ErrorCode::NoSuchBlock,
+                "failed to get last added block",
+            ))
+        }
+    };
+    let account_hash = deploy.header().account().to_account_hash();
+    let query_result = effect_builder
+        .make_request(
+            |responder| RpcRequest::QueryGlobalState {
+                state_root_hash,
+                base_key: Key::Account(account_hash),
+                path: vec![],
+                responder,
+            },
+            QueueKind::Api,
+        )
+        .await;
+    let account = match query_result {
+        Ok(QueryResult::Success { value, .. }) => match *value {
+            StoredValue::Account(account) => account,
+            other => {
+                return Err(ApiError::new(
+                    ErrorCode::QueryFailed,
+                    format!("{} is not an account: {:?}", account_hash, other),
+                ))
+            }
+        },
+        Ok(query_result) => {
+            return Err(ApiError::new(
+                ErrorCode::QueryFailed,
+                format!("state query failed: {:?}", query_result),
+            ))
+        }
+        Err(error) => {
+            return Err(ApiError::new(
+                ErrorCode::QueryFailedToExecute,
+                format!("state query failed to execute: {:?}", error),
+            ))
+        }
+    };
+
+    // Approvals by keys not associated with the account would make the deploy fail to execute.
+    let authorization_keys: BTreeSet<AccountHash> = deploy
+        .approvals()
+        .iter()
+        .map(|approval| approval.signer().to_account_hash())
+        .collect();
+    if !authorization_keys.is_empty() && !account.can_authorize(&authorization_keys) {
+        return Err(ApiError::new(
+            ErrorCode::InvalidDeploy,
+            format!(
+                "deploy is approved by keys not associated with account {}",
+                account_hash
+            ),
+        ));
+    }
+
+    let deploy_hash = *deploy.id();
+    if authorization_keys.is_empty() || !account.can_deploy_with(&authorization_keys) {
+        effect_builder
+            .make_request(
+                |responder| RpcRequest::PutPendingDeploy {
+                    deploy: Box::new(deploy),
+                    responder,
+                },
+                QueueKind::Api,
+            )
+            .await
+            .map_err(|error| ApiError::new(ErrorCode::Unavailable, error.to_string()))?;
+        info!(%deploy_hash, "stored pending deploy");
+        return Ok(false);
+    }
+
+    effect_builder
+        .make_request(
+            |responder| RpcRequest::SubmitDeploy {
+                deploy: Box::new(deploy),
+                responder,
+            },
+            QueueKind::Api,
+        )
+        .await
+        .map_err(|error| ApiError::new(ErrorCode::InvalidDeploy, error.to_string()))?;
+    effect_builder
+        .make_request(
+            |responder| RpcRequest::RemovePendingDeploy {
+                deploy_hash,
+                responder,
+            },
+            QueueKind::Api,
+        )
+        .await;
+    info!(%deploy_hash, "submitted pending deploy");
+    Ok(true)
+}
+
+/// Checks that `deploy` would be accepted by the network at time `now`, regardless of whether its
+/// approvals meet the deployment threshold of its account.
+fn check_acceptable(
+    deploy: &mut Deploy,
+    chain_name: &str,
+    deploy_config: &DeployConfig,
+    now: Timestamp,
+) -> Result<(), ApiError> {
+    if let Err(error) = deploy.is_acceptable(chain_name, deploy_config) {
+        return Err(ApiError::new(
+            ErrorCode::InvalidDeploy,
+            format!("invalid deploy: {}", error),
+        ));
+    }
+    if deploy.header().expired(now) {
+        return Err(ApiError::new(
+            ErrorCode::InvalidDeploy,
+            "deploy has expired",
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_execution_engine::core::engine_state::MAX_PAYMENT_AMOUNT;
+    use casper_types::{bytesrepr::Bytes, runtime_args, RuntimeArgs, SecretKey, U512};
+
+    use super::*;
+    use crate::{
+        crypto::AsymmetricKeyExt,
+        testing::TestRng,
+        types::{ExecutableDeployItem, TimeDiff},
+    };
+
+    const CHAIN_NAME: &str = "casper-example";
+
+    fn create_deploy(rng: &mut TestRng, timestamp: Timestamp, chain_name: &str) -> Deploy {
+        let amount_args = runtime_args! { "amount" => U512::from(MAX_PAYMENT_AMOUNT) };
+        Deploy::new(
+            timestamp,
+            TimeDiff::from_seconds(60),
+            1,
+            vec![],
+            chain_name.to_string(),
+            ExecutableDeployItem::ModuleBytes {
+                module_bytes: Bytes::new(),
+                args: amount_args.clone(),
+            },
+            ExecutableDeployItem::Transfer { args: amount_args },
+            &SecretKey::random(rng),
+        )
+    }
+
+    #[test]
+    fn should_accept_pending_deploy() {
+        let mut rng = crate::new_rng();
+        let mut deploy = create_deploy(&mut rng, Timestamp::now(), CHAIN_NAME);
+        let result = check_acceptable(
+            &mut deploy,
+            CHAIN_NAME,
+            &DeployConfig::default(),
+            Timestamp::now(),
+        );
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn should_reject_pending_deploy_for_other_chain() {
+        let mut rng = crate::new_rng();
+        let mut deploy = create_deploy(&mut rng, Timestamp::now(), "other-chain");
+        let error = check_acceptable(
+            &mut deploy,
+            CHAIN_NAME,
+            &DeployConfig::default(),
+            Timestamp::now(),
+        )
+        .unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidDeploy as i64);
+        assert!(error.message.contains("chain name"));
+    }
+
+    #[test]
+    fn should_reject_pending_deploy_exceeding_deploy_config() {
+        let mut rng = crate::new_rng();
+        let mut deploy = create_deploy(&mut rng, Timestamp::now(), CHAIN_NAME);
+        let deploy_config = DeployConfig {
+            max_ttl: TimeDiff::from_seconds(1),
+            ..DeployConfig::default()
+        };
+        let error = check_acceptable(&mut deploy, CHAIN_NAME, &deploy_config, Timestamp::now())
+            .unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidDeploy as i64);
+    }
+
+    #[test]
+    fn should_reject_expired_pending_deploy() {
+        let mut rng = crate::new_rng();
+        let mut deploy = create_deploy(&mut rng, Timestamp::zero(), CHAIN_NAME);
+        let error = check_acceptable(
+            &mut deploy,
+            CHAIN_NAME,
+            &DeployConfig::default(),
+            Timestamp::now(),
+        )
+        .unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidDeploy as i64);
+        assert_eq!(error.message, "deploy has expired");
+    }
+}
@@ -16,18 +16,26 @@ use serde::{Deserialize, Serialize};
 use tracing::info;
 use warp_json_rpc::Builder;
 
-use casper_types::{Key, ProtocolVersion, Transfer};
+use casper_types::{
+    bytesrepr::Bytes,
+    system::auction::{EraInfo, SeigniorageAllocation},
+    ContractHash, EraId, Key, ProtocolVersion, PublicKey, SecretKey, Transfer, U512,
+};
 
 use super::{
     docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
-    Error, ErrorCode, ReactorEventT, RpcRequest, RpcWithOptionalParams, RpcWithOptionalParamsExt,
+    error_response, ApiError, Error, ErrorCode, ReactorEventT, RpcRequest, RpcWithOptionalParams,
+    RpcWithOptionalParamsExt, RpcWithParams, RpcWithParamsExt,
 };
 use crate::{
     crypto::hash::Digest,
     effect::EffectBuilder,
     reactor::QueueKind,
     rpcs::common::{self},
-    types::{Block, BlockHash, BlockSignatures, Item, JsonBlock},
+    types::{
+        Block, BlockHash, BlockIdentifier, BlockSignatures, ContractEventRecord, Deploy,
+        EraRewards, Item, JsonBlock,
+    },
 };
 pub use era_summary::EraSummary;
 use era_summary::ERA_SUMMARY;
@@ -65,16 +73,64 @@ static GET_ERA_INFO_RESULT: Lazy<GetEraInfoResult> = Lazy::new(|| GetEraInfoResu
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     era_summary: Some(ERA_SUMMARY.clone()),
 });
+static GET_BLOCKS_BY_PROPOSER_PARAMS: Lazy<GetBlocksByProposerParams> =
+    Lazy::new(|| GetBlocksByProposerParams {
+        public_key: Block::doc_example().body().proposer().clone(),
+        era_id: Block::doc_example().header().era_id(),
+    });
+static GET_BLOCKS_BY_PROPOSER_RESULT: Lazy<GetBlocksByProposerResult> =
+    Lazy::new(|| GetBlocksByProposerResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        blocks: vec![JsonBlock::new(Block::doc_example().clone(), None)],
+    });
+static GET_EVENTS_PARAMS: Lazy<GetEventsParams> = Lazy::new(|| GetEventsParams {
+    contract_hash: ContractHash::new([7; 32]),
+    from_block: Block::doc_example().height(),
+    to_block: Block::doc_example().height(),
+});
+static GET_EVENTS_RESULT: Lazy<GetEventsResult> = Lazy::new(|| GetEventsResult {
+    api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+    events: vec![ContractEventRecord {
+        block_hash: *Block::doc_example().hash(),
+        block_height: Block::doc_example().height(),
+        deploy_hash: *Deploy::doc_example().id(),
+        contract_hash: ContractHash::new([7; 32]),
+        topic: "transfer".to_string(),
+        data: Bytes::from(vec![1, 2, 3]),
+    }],
+});
+static GET_ERA_REWARDS_PARAMS: Lazy<GetEraRewardsParams> = Lazy::new(|| GetEraRewardsParams {
+    era_id: Block::doc_example().header().era_id(),
+});
+static GET_ERA_REWARDS_RESULT: Lazy<GetEraRewardsResult> = Lazy::new(|| {
+    let validator_public_key = Block::doc_example().body().proposer().clone();
+    let delegator_public_key =
+        PublicKey::from(&SecretKey::ed25519_from_bytes([7u8; SecretKey::ED25519_LENGTH]).unwrap());
+    let mut era_info = EraInfo::new();
+    era_info
+        .seigniorage_allocations_mut()
+        .push(SeigniorageAllocation::validator(
+            validator_public_key.clone(),
+            U512::from(1_000_000_000),
+        ));
+    era_info
+        .seigniorage_allocations_mut()
+        .push(SeigniorageAllocation::delegator(
+            delegator_public_key,
+            validator_public_key,
+            U512::from(250_000_000),
+        ));
+    GetEraRewardsResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        era_rewards: Some(EraRewards::new(
+            Block::doc_example().header().era_id(),
+            &era_info,
+        )),
+    }
+});
 
-/// Identifier for possible ways to retrieve a block.
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, JsonSchema)]
-#[serde(deny_unknown_fields)]
-pub enum BlockIdentifier {
-    /// Identify and retrieve the block with its hash.
-    Hash(BlockHash),
-    /// Identify and retrieve the block with its height.
-    Height(u64),
-}
+/// The maximum number of blocks which can be covered by a single "chain_get_events" request.
+const MAX_EVENTS_BLOCK_RANGE: u64 = 1_000;
 
 /// Params for "chain_get_block" RPC request.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
@@ -125,18 +181,17 @@ impl RpcWithOptionalParamsExt for GetBlock {
     ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
         async move {
             // Get the block.
-            let maybe_block_id = maybe_params.map(|params| params.block_identifier);
+            let block_identifier = maybe_params
+                .map(|params| params.block_identifier)
+                .unwrap_or(BlockIdentifier::Latest);
             let (block, signatures) =
-                match get_block_with_metadata(maybe_block_id, effect_builder).await {
+                match get_block_with_metadata(block_identifier, effect_builder).await {
                     Ok(Some((block, signatures))) => (block, signatures),
                     Ok(None) => {
-                        let error = warp_json_rpc::Error::custom(
-                            ErrorCode::NoSuchBlock as i64,
-                            "block not known",
-                        );
-                        return Ok(response_builder.error(error)?);
+                        let error = ApiError::new(ErrorCode::NoSuchBlock, "block not known");
+                        return error_response(response_builder, error);
                     }
-                    Err(error) => return Ok(response_builder.error(error)?),
+                    Err(error) => return error_response(response_builder, error),
                 };
 
             let json_block = JsonBlock::new(block, Some(signatures));
@@ -218,8 +273,10 @@ impl RpcWithOptionalParamsExt for GetBlockTransfers {
     ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
         async move {
             // Get the block.
-            let maybe_block_id = maybe_params.map(|params| params.block_identifier);
-            let block_hash = match get_block(maybe_block_id, effect_builder).await {
+            let block_identifier = maybe_params
+                .map(|params| params.block_identifier)
+                .unwrap_or(BlockIdentifier::Latest);
+            let block_hash = match get_block(block_identifier, effect_builder).await {
                 Ok(Some(block)) => *block.hash(),
                 Ok(None) => {
                     return Ok(response_builder.success(Self::ResponseResult::new(
@@ -228,7 +285,7 @@ impl RpcWithOptionalParamsExt for GetBlockTransfers {
                         None,
                     ))?)
                 }
-                Err(error) => return Ok(response_builder.error(error)?),
+                Err(error) => return error_response(response_builder, error),
             };
 
             let transfers = effect_builder
@@ -298,10 +355,12 @@ impl RpcWithOptionalParamsExt for GetStateRootHash {
     ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
         async move {
             // Get the block.
-            let maybe_block_id = maybe_params.map(|params| params.block_identifier);
-            let maybe_block = match get_block(maybe_block_id, effect_builder).await {
+            let block_identifier = maybe_params
+                .map(|params| params.block_identifier)
+                .unwrap_or(BlockIdentifier::Latest);
+            let maybe_block = match get_block(block_identifier, effect_builder).await {
                 Ok(maybe_block) => maybe_block,
-                Err(error) => return Ok(response_builder.error(error)?),
+                Err(error) => return error_response(response_builder, error),
             };
 
             // Return the result.
@@ -364,10 +423,12 @@ impl RpcWithOptionalParamsExt for GetEraInfoBySwitchBlock {
     ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
         async move {
             // TODO: decide if/how to handle era id
-            let maybe_block_id = maybe_params.map(|params| params.block_identifier);
-            let maybe_block = match get_block(maybe_block_id, effect_builder).await {
+            let block_identifier = maybe_params
+                .map(|params| params.block_identifier)
+                .unwrap_or(BlockIdentifier::Latest);
+            let maybe_block = match get_block(block_identifier, effect_builder).await {
                 Ok(maybe_block) => maybe_block,
-                Err(error) => return Ok(response_builder.error(error)?),
+                Err(error) => return error_response(response_builder, error),
             };
 
             let block = match maybe_block {
@@ -407,10 +468,9 @@ impl RpcWithOptionalParamsExt for GetEraInfoBySwitchBlock {
 
             let (stored_value, proof_bytes) = match common::extract_query_result(query_result) {
                 Ok(tuple) => tuple,
-                Err((error_code, error_msg)) => {
-                    info!("{}", error_msg);
-                    return Ok(response_builder
-                        .error(warp_json_rpc::Error::custom(error_code as i64, error_msg))?);
+                Err(error) => {
+                    info!("{}", error.message);
+                    return error_response(response_builder, error);
                 }
             };
 
@@ -433,42 +493,269 @@ impl RpcWithOptionalParamsExt for GetEraInfoBySwitchBlock {
     }
 }
 
-async fn get_block<REv: ReactorEventT>(
-    maybe_id: Option<BlockIdentifier>,
+/// Params for "chain_get_blocks_by_proposer" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetBlocksByProposerParams {
+    /// The public key of the validator which proposed the blocks.
+    pub public_key: PublicKey,
+    /// The era in which the blocks were proposed.
+    pub era_id: EraId,
+}
+
+impl DocExample for GetBlocksByProposerParams {
+    fn doc_example() -> &'static Self {
+        &*GET_BLOCKS_BY_PROPOSER_PARAMS
+    }
+}
+
+/// Result for "chain_get_blocks_by_proposer" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetBlocksByProposerResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The blocks proposed by the validator in the era, ordered by height.
+    pub blocks: Vec<JsonBlock>,
+}
+
+impl DocExample for GetBlocksByProposerResult {
+    fn doc_example() -> &'static Self {
+        &*GET_BLOCKS_BY_PROPOSER_RESULT
+    }
+}
+
+/// "chain_get_blocks_by_proposer" RPC.
+pub struct GetBlocksByProposer {}
+
+impl RpcWithParams for GetBlocksByProposer {
+    const METHOD: &'static str = "chain_get_blocks_by_proposer";
+    type RequestParams = GetBlocksByProposerParams;
+    type ResponseResult = GetBlocksByProposerResult;
+}
+
+impl RpcWithParamsExt for GetBlocksByProposer {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+        api_version: ProtocolVersion,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            let blocks = effect_builder
+                .make_request(
+                    |responder| RpcRequest::GetBlocksByProposer {
+                        public_key: params.public_key,
+                        era_id: params.era_id,
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+
+            // Return the result.
+            let result = Self::ResponseResult {
+                api_version,
+                blocks: blocks
+                    .into_iter()
+                    .map(|block| JsonBlock::new(block, None))
+                    .collect(),
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
+
+/// Params for "chain_get_events" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetEventsParams {
+    /// The hash of the contract which emitted the events.
+    pub contract_hash: ContractHash,
+    /// The height of the first block to include.
+    pub from_block: u64,
+    /// The height of the last block to include.
+    pub to_block: u64,
+}
+
+impl DocExample for GetEventsParams {
+    fn doc_example() -> &'static Self {
+        &*GET_EVENTS_PARAMS
+    }
+}
+
+/// Result for "chain_get_events" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetEventsResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The events emitted by the contract, ordered by block height and then by emission.
+    pub events: Vec<ContractEventRecord>,
+}
+
+impl DocExample for GetEventsResult {
+    fn doc_example() -> &'static Self {
+        &*GET_EVENTS_RESULT
+    }
+}
+
+/// "chain_get_events" RPC.
+pub struct GetEvents {}
+
+impl RpcWithParams for GetEvents {
+    const METHOD: &'static str = "chain_get_events";
+    type RequestParams = GetEventsParams;
+    type ResponseResult = GetEventsResult;
+}
+
+impl RpcWithParamsExt for GetEvents {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+        api_version: ProtocolVersion,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            let block_count = params
+                .to_block
+                .checked_sub(params.from_block)
+                .map(|difference| difference.saturating_add(1));
+            if !matches!(block_count, Some(count) if count <= MAX_EVENTS_BLOCK_RANGE) {
+                let error_msg = format!(
+                    "invalid block range {} to {}: must be non-empty and span at most {} blocks",
+                    params.from_block, params.to_block, MAX_EVENTS_BLOCK_RANGE
+                );
+                info!("{}", error_msg);
+                return error_response(
+                    response_builder,
+                    ApiError::new(ErrorCode::InvalidBlockRange, error_msg),
+                );
+            }
+
+            let events = effect_builder
+                .make_request(
+                    |responder| RpcRequest::GetContractEvents {
+                        contract_hash: params.contract_hash,
+                        from_block: params.from_block,
+                        to_block: params.to_block,
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+
+            let result = Self::ResponseResult {
+                api_version,
+                events,
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
+
+/// Params for "chain_get_era_rewards" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetEraRewardsParams {
+    /// The era at the end of which the rewards were distributed.
+    pub era_id: EraId,
+}
+
+impl DocExample for GetEraRewardsParams {
+    fn doc_example() -> &'static Self {
+        &*GET_ERA_REWARDS_PARAMS
+    }
+}
+
+/// Result for "chain_get_era_rewards" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetEraRewardsResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The rewards distributed to each validator and delegator, or `None` if the era hasn't ended
+    /// yet or its switch block wasn't executed by this node.
+    pub era_rewards: Option<EraRewards>,
+}
+
+impl DocExample for GetEraRewardsResult {
+    fn doc_example() -> &'static Self {
+        &*GET_ERA_REWARDS_RESULT
+    }
+}
+
+/// "chain_get_era_rewards" RPC.
+pub struct GetEraRewards {}
+
+impl RpcWithParams for GetEraRewards {
+    const METHOD: &'static str = "chain_get_era_rewards";
+    type RequestParams = GetEraRewardsParams;
+    type ResponseResult = GetEraRewardsResult;
+}
+
+impl RpcWithParamsExt for GetEraRewards {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+        api_version: ProtocolVersion,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            let era_rewards = effect_builder
+                .make_request(
+                    |responder| RpcRequest::GetEraRewards {
+                        era_id: params.era_id,
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+
+            let result = Self::ResponseResult {
+                api_version,
+                era_rewards,
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
+
+pub(super) async fn get_block<REv: ReactorEventT>(
+    block_identifier: BlockIdentifier,
     effect_builder: EffectBuilder<REv>,
-) -> Result<Option<Block>, warp_json_rpc::Error> {
-    match get_block_with_metadata(maybe_id, effect_builder).await {
+) -> Result<Option<Block>, ApiError> {
+    match get_block_with_metadata(block_identifier, effect_builder).await {
         Ok(Some((block, _))) => Ok(Some(block)),
-        Ok(None) => Err(warp_json_rpc::Error::custom(
-            ErrorCode::NoSuchBlock as i64,
-            "block not known",
-        )),
+        Ok(None) => Err(ApiError::new(ErrorCode::NoSuchBlock, "block not known")),
         Err(error) => Err(error),
     }
 }
 
 async fn get_block_with_metadata<REv: ReactorEventT>(
-    maybe_id: Option<BlockIdentifier>,
+    block_identifier: BlockIdentifier,
     effect_builder: EffectBuilder<REv>,
-) -> Result<Option<(Block, BlockSignatures)>, warp_json_rpc::Error> {
+) -> Result<Option<(Block, BlockSignatures)>, ApiError> {
     // Get the block from storage or the latest from the linear chain.
-    let getting_specific_block = maybe_id.is_some();
     let maybe_result = effect_builder
         .make_request(
             |responder| RpcRequest::GetBlock {
-                maybe_id,
+                block_identifier,
                 responder,
             },
             QueueKind::Api,
         )
         .await;
 
-    if maybe_result.is_none() && getting_specific_block {
-        info!("failed to get {:?} from storage", maybe_id.unwrap());
-        return Err(warp_json_rpc::Error::custom(
-            ErrorCode::NoSuchBlock as i64,
-            "block not known",
-        ));
+    if maybe_result.is_none() && block_identifier != BlockIdentifier::Latest {
+        info!("failed to get {} from storage", block_identifier);
+        return Err(ApiError::new(ErrorCode::NoSuchBlock, "block not known"));
     }
 
     Ok(maybe_result)
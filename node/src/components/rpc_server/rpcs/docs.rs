@@ -19,10 +19,13 @@ use warp_json_rpc::Builder;
 use casper_types::ProtocolVersion;
 
 use super::{
-    account::PutDeploy,
-    chain::{GetBlock, GetBlockTransfers, GetStateRootHash},
-    info::{GetDeploy, GetPeers, GetStatus},
-    state::{GetAuctionInfo, GetBalance, GetItem},
+    account::{AddDeployApprovals, GetDeploys, PutDeploy, PutDeploys, PutPendingDeploy},
+    chain::{
+        GetBlock, GetBlockTransfers, GetBlocksByProposer, GetEraRewards, GetEvents,
+        GetStateRootHash,
+    },
+    info::{GetChainspec, GetDeploy, GetExecutionJournal, GetPeers, GetPendingDeploy, GetStatus},
+    state::{GetAccountInfo, GetAuctionInfo, GetBalance, GetBalanceAtBlock, GetItem},
     Error, ReactorEventT, RpcWithOptionalParams, RpcWithParams, RpcWithoutParams,
     RpcWithoutParamsExt,
 };
@@ -33,61 +36,7 @@ pub(crate) const DOCS_EXAMPLE_PROTOCOL_VERSION: ProtocolVersion =
 const DEFINITIONS_PATH: &str = "#/components/schemas/";
 
 // As per https://spec.open-rpc.org/#service-discovery-method.
-pub(crate) static OPEN_RPC_SCHEMA: Lazy<OpenRpcSchema> = Lazy::new(|| {
-    let contact = OpenRpcContactField {
-        name: "CasperLabs".to_string(),
-        url: "https://casperlabs.io".to_string(),
-    };
-    let license = OpenRpcLicenseField {
-        name: "CasperLabs Open Source License Version 1.0".to_string(),
-        url: "https://raw.githubusercontent.com/CasperLabs/casper-node/master/LICENSE".to_string(),
-    };
-    let info = OpenRpcInfoField {
-        version: DOCS_EXAMPLE_PROTOCOL_VERSION.to_string(),
-        title: "Client API of Casper Node".to_string(),
-        description: "This describes the JSON-RPC 2.0 API of a node on the Casper network."
-            .to_string(),
-        contact,
-        license,
-    };
-
-    let server = OpenRpcServerEntry {
-        name: "any Casper Network node".to_string(),
-        url: "http://IP:PORT/rpc/".to_string(),
-    };
-
-    let mut schema = OpenRpcSchema {
-        openrpc: "1.0.0-rc1".to_string(),
-        info,
-        servers: vec![server],
-        methods: vec![],
-        components: Components {
-            schemas: Map::new(),
-        },
-    };
-
-    schema.push_with_params::<PutDeploy>("receives a Deploy to be executed by the network");
-    schema.push_with_params::<GetDeploy>("returns a Deploy from the network");
-    schema.push_without_params::<GetPeers>("returns a list of peers connected to the node");
-    schema.push_without_params::<GetStatus>("returns the current status of the node");
-    schema.push_with_optional_params::<GetBlock>("returns a Block from the network");
-    schema.push_with_optional_params::<GetBlockTransfers>(
-        "returns all transfers for a Block from the network",
-    );
-    schema.push_with_optional_params::<GetStateRootHash>(
-        "returns a state root hash at a given Block",
-    );
-    schema.push_with_params::<GetItem>("returns a stored value from the network");
-    schema.push_with_params::<GetBalance>("returns a purse's balance from the network");
-    schema.push_with_optional_params::<GetEraInfoBySwitchBlock>(
-        "returns an EraInfo from the network",
-    );
-    schema.push_without_params::<GetAuctionInfo>(
-        "returns the bids and validators as of the most recently added Block",
-    );
-
-    schema
-});
+pub(crate) static OPEN_RPC_SCHEMA: Lazy<OpenRpcSchema> = Lazy::new(OpenRpcSchema::new);
 static LIST_RPCS_RESULT: Lazy<ListRpcsResult> = Lazy::new(|| ListRpcsResult {
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     name: "OpenRPC Schema".to_string(),
@@ -111,6 +60,106 @@ pub struct OpenRpcSchema {
 }
 
 impl OpenRpcSchema {
+    /// Generates the schema for all supported RPCs.
+    ///
+    /// The component schemas are sorted by name, so the generated document only changes when the
+    /// API types do, regardless of the order in which the RPCs are registered.
+    fn new() -> Self {
+        let contact = OpenRpcContactField {
+            name: "CasperLabs".to_string(),
+            url: "https://casperlabs.io".to_string(),
+        };
+        let license = OpenRpcLicenseField {
+            name: "CasperLabs Open Source License Version 1.0".to_string(),
+            url: "https://raw.githubusercontent.com/CasperLabs/casper-node/master/LICENSE"
+                .to_string(),
+        };
+        let info = OpenRpcInfoField {
+            version: DOCS_EXAMPLE_PROTOCOL_VERSION.to_string(),
+            title: "Client API of Casper Node".to_string(),
+            description: "This describes the JSON-RPC 2.0 API of a node on the Casper network."
+                .to_string(),
+            contact,
+            license,
+        };
+
+        let server = OpenRpcServerEntry {
+            name: "any Casper Network node".to_string(),
+            url: "http://IP:PORT/rpc/".to_string(),
+        };
+
+        let mut schema = OpenRpcSchema {
+            openrpc: "1.0.0-rc1".to_string(),
+            info,
+            servers: vec![server],
+            methods: vec![],
+            components: Components {
+                schemas: Map::new(),
+            },
+        };
+
+        schema.push_with_params::<PutDeploy>("receives a Deploy to be executed by the network");
+        schema.push_with_params::<PutDeploys>(
+        "receives a batch of Deploys to be executed by the network, returning the outcome for each",
+    );
+        schema.push_with_params::<GetDeploy>("returns a Deploy from the network");
+        schema.push_with_params::<GetDeploys>(
+            "returns the hashes of Deploys submitted by an account, most recent first",
+        );
+        schema.push_with_params::<PutPendingDeploy>(
+        "receives a Deploy which may lack approvals, holding it until its account's deployment \
+        threshold is met",
+    );
+        schema.push_with_params::<AddDeployApprovals>(
+            "adds approvals to a pending Deploy, submitting it once its account's deployment \
+        threshold is met",
+        );
+        schema.push_with_params::<GetPendingDeploy>(
+            "returns a pending Deploy with the approvals collected so far",
+        );
+        schema.push_with_params::<GetExecutionJournal>(
+            "returns the global state operations of an executed Deploy, if the node records them",
+        );
+        schema.push_without_params::<GetPeers>("returns a list of peers connected to the node");
+        schema.push_without_params::<GetStatus>("returns the current status of the node");
+        schema.push_without_params::<GetChainspec>(
+            "returns the chainspec the network is running, as stored in global state",
+        );
+        schema.push_with_optional_params::<GetBlock>("returns a Block from the network");
+        schema.push_with_optional_params::<GetBlockTransfers>(
+            "returns all transfers for a Block from the network",
+        );
+        schema.push_with_optional_params::<GetStateRootHash>(
+            "returns a state root hash at a given Block",
+        );
+        schema.push_with_params::<GetBlocksByProposer>(
+            "returns the Blocks proposed by a validator in a given era",
+        );
+        schema.push_with_params::<GetEvents>(
+            "returns the events emitted by a contract within a range of Blocks",
+        );
+        schema.push_with_params::<GetEraRewards>(
+            "returns the rewards distributed to each validator and delegator at the end of an era",
+        );
+        schema.push_with_params::<GetItem>("returns a stored value from the network");
+        schema.push_with_params::<GetBalance>("returns a purse's balance from the network");
+        schema.push_with_params::<GetBalanceAtBlock>(
+            "returns a purse's balance as of a given Block, or the most recently added Block",
+        );
+        schema.push_with_params::<GetAccountInfo>(
+            "returns an account with its main purse balance as of the most recently added Block",
+        );
+        schema.push_with_optional_params::<GetEraInfoBySwitchBlock>(
+            "returns an EraInfo from the network",
+        );
+        schema.push_without_params::<GetAuctionInfo>(
+            "returns the bids and validators as of the most recently added Block",
+        );
+
+        schema.components.schemas.sort_keys();
+        schema
+    }
+
     fn new_generator() -> SchemaGenerator {
         let settings = SchemaSettings::default().with(|settings| {
             settings.definitions_path = DEFINITIONS_PATH.to_string();
@@ -425,4 +474,18 @@ mod tests {
             'resources/production/chainspec.toml'"
         );
     }
+
+    #[test]
+    fn open_rpc_schema_should_be_deterministic() {
+        let schema = OpenRpcSchema::new();
+        let names: Vec<_> = schema.components.schemas.keys().cloned().collect();
+        let mut sorted_names = names.clone();
+        sorted_names.sort();
+        assert_eq!(names, sorted_names);
+
+        assert_eq!(
+            serde_json::to_string(&schema).unwrap(),
+            serde_json::to_string(&*OPEN_RPC_SCHEMA).unwrap()
+        );
+    }
 }
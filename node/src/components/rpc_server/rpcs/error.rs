@@ -0,0 +1,296 @@
+//! Structured errors returned by the API servers.
+//!
+//! Each error carries a stable numeric [`ErrorCode`], which is used as the JSON-RPC error code by
+//! the JSON-RPC server and mapped to an HTTP status code by the REST server.
+
+use std::fmt::{self, Display, Formatter};
+
+use http::{Response, StatusCode};
+use hyper::Body;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use warp_json_rpc::Builder;
+
+/// Error code returned if the JSON-RPC response indicates failure.
+///
+/// See <https://www.jsonrpc.org/specification#error_object> for details.
+///
+/// The numeric values are part of the public API and must not be changed.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(i64)]
+pub enum ErrorCode {
+    /// The requested deploy was not found.
+    NoSuchDeploy = -32000,
+    /// The requested block was not found.
+    NoSuchBlock = -32001,
+    /// The provided global state key could not be parsed.
+    ParseQueryKey = -32002,
+    /// The global state query did not yield a value.
+    QueryFailed = -32003,
+    /// The global state query could not be executed.
+    QueryFailedToExecute = -32004,
+    /// The provided purse `URef` could not be parsed.
+    ParseGetBalanceURef = -32005,
+    /// The balance query did not yield a value.
+    GetBalanceFailed = -32006,
+    /// The balance query could not be executed.
+    GetBalanceFailedToExecute = -32007,
+    /// The submitted deploy was rejected as invalid.
+    InvalidDeploy = -32008,
+    /// An unexpected internal error occurred while handling the request.
+    InternalError = -32009,
+    /// The requested resource is currently unavailable.
+    Unavailable = -32010,
+    /// The requested path or resource does not exist.
+    NotFound = -32011,
+    /// The requested range of blocks is empty or too large.
+    InvalidBlockRange = -32012,
+}
+
+impl ErrorCode {
+    /// Returns the HTTP status code corresponding to this error code.
+    pub fn http_status(self) -> StatusCode {
+        match self {
+            ErrorCode::NoSuchDeploy | ErrorCode::NoSuchBlock | ErrorCode::NotFound => {
+                StatusCode::NOT_FOUND
+            }
+            ErrorCode::ParseQueryKey
+            | ErrorCode::ParseGetBalanceURef
+            | ErrorCode::InvalidDeploy
+            | ErrorCode::InvalidBlockRange => StatusCode::BAD_REQUEST,
+            ErrorCode::QueryFailed | ErrorCode::GetBalanceFailed => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            ErrorCode::QueryFailedToExecute
+            | ErrorCode::GetBalanceFailedToExecute
+            | ErrorCode::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            ErrorCode::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+}
+
+/// A structured error returned by the API servers.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ApiError {
+    /// The stable numeric error code.
+    pub code: i64,
+    /// A human-readable description of the error.
+    pub message: String,
+}
+
+impl ApiError {
+    /// Constructs a new `ApiError`.
+    pub fn new<T: Into<String>>(code: ErrorCode, message: T) -> Self {
+        ApiError {
+            code: code as i64,
+            message: message.into(),
+        }
+    }
+
+    /// Returns the HTTP status code for this error.
+    ///
+    /// Codes unknown to this version of the node are treated as internal errors.
+    pub fn http_status(&self) -> StatusCode {
+        ErrorCode::from_i64(self.code)
+            .unwrap_or(ErrorCode::InternalError)
+            .http_status()
+    }
+
+    /// Converts the error into a JSON-RPC error object.
+    pub(crate) fn into_rpc_error(self) -> warp_json_rpc::Error {
+        warp_json_rpc::Error::custom(self.code, self.message)
+    }
+
+    /// Converts the error into an HTTP response with a JSON body and matching status code.
+    pub(crate) fn into_http_response(self) -> Response<Body> {
+        let status = self.http_status();
+        json_response(&self, status)
+    }
+}
+
+impl ErrorCode {
+    fn from_i64(code: i64) -> Option<Self> {
+        let error_code = match code {
+            -32000 => ErrorCode::NoSuchDeploy,
+            -32001 => ErrorCode::NoSuchBlock,
+            -32002 => ErrorCode::ParseQueryKey,
+            -32003 => ErrorCode::QueryFailed,
+            -32004 => ErrorCode::QueryFailedToExecute,
+            -32005 => ErrorCode::ParseGetBalanceURef,
+            -32006 => ErrorCode::GetBalanceFailed,
+            -32007 => ErrorCode::GetBalanceFailedToExecute,
+            -32008 => ErrorCode::InvalidDeploy,
+            -32009 => ErrorCode::InternalError,
+            -32010 => ErrorCode::Unavailable,
+            -32011 => ErrorCode::NotFound,
+            -32012 => ErrorCode::InvalidBlockRange,
+            _ => return None,
+        };
+        Some(error_code)
+    }
+}
+
+impl Display for ApiError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "{} (code {})", self.message, self.code)
+    }
+}
+
+/// An error which causes a JSON-RPC request handler to be aborted.
+///
+/// It is converted into a JSON-RPC error response carrying the structured [`ApiError`] and the
+/// matching HTTP status code.
+#[derive(Debug)]
+pub(crate) struct Error(pub(crate) ApiError);
+
+impl Error {
+    /// Converts the error into a JSON-RPC error response.
+    pub(crate) fn into_json_rpc_response(self) -> Response<Body> {
+        let status = self.0.http_status();
+        new_json_rpc_error_response(self.0.into_rpc_error(), status)
+    }
+}
+
+impl From<anyhow::Error> for Error {
+    fn from(error: anyhow::Error) -> Self {
+        Error(ApiError::new(ErrorCode::InternalError, error.to_string()))
+    }
+}
+
+impl From<ApiError> for Error {
+    fn from(error: ApiError) -> Self {
+        Error(error)
+    }
+}
+
+/// Creates the JSON-RPC error response to the request being handled by `response_builder`, with
+/// the HTTP status code corresponding to `error`.
+pub(crate) fn error_response(
+    response_builder: Builder,
+    error: ApiError,
+) -> Result<Response<Body>, Error> {
+    let status = error.http_status();
+    let mut response = response_builder.error(error.into_rpc_error())?;
+    *response.status_mut() = status;
+    Ok(response)
+}
+
+/// Creates a JSON-RPC error response with the given HTTP status code.
+///
+/// This is a workaround for not being able to create a `warp_json_rpc::Response` without a
+/// `warp_json_rpc::Builder`.
+pub(crate) fn new_json_rpc_error_response(
+    error: warp_json_rpc::Error,
+    status: StatusCode,
+) -> Response<Body> {
+    #[derive(Serialize)]
+    struct JsonRpcErrorResponse {
+        jsonrpc: String,
+        id: Option<()>,
+        error: warp_json_rpc::Error,
+    }
+
+    let json_response = JsonRpcErrorResponse {
+        jsonrpc: "2.0".to_string(),
+        id: None,
+        error,
+    };
+
+    json_response(&json_response, status)
+}
+
+fn json_response<T: Serialize>(value: &T, status: StatusCode) -> Response<Body> {
+    let body = Body::from(serde_json::to_vec(value).unwrap_or_default());
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .body(body)
+        .unwrap_or_else(|_| {
+            let mut response = Response::new(Body::empty());
+            *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+            response
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+    use warp::Filter;
+    use warp_json_rpc::filters;
+
+    use super::*;
+
+    const METHOD: &str = "test_method";
+
+    /// Replies to a JSON-RPC request with `error`, returning the HTTP status and the JSON body.
+    async fn reply_with_error(error: ApiError) -> (StatusCode, Value) {
+        let filter = filters::json_rpc().and(filters::method(METHOD)).and_then(
+            move |response_builder: Builder| {
+                let error = error.clone();
+                async move { error_response(response_builder, error).map_err(|_| warp::reject()) }
+            },
+        );
+        let request = json!({ "jsonrpc": "2.0", "id": 7, "method": METHOD });
+        let response = warp::test::request()
+            .method("POST")
+            .json(&request)
+            .reply(&filter)
+            .await;
+        let body = serde_json::from_slice(response.body()).unwrap();
+        (response.status(), body)
+    }
+
+    #[test]
+    fn should_roundtrip_error_codes() {
+        for error_code in &[
+            ErrorCode::NoSuchDeploy,
+            ErrorCode::NoSuchBlock,
+            ErrorCode::ParseQueryKey,
+            ErrorCode::QueryFailed,
+            ErrorCode::QueryFailedToExecute,
+            ErrorCode::ParseGetBalanceURef,
+            ErrorCode::GetBalanceFailed,
+            ErrorCode::GetBalanceFailedToExecute,
+            ErrorCode::InvalidDeploy,
+            ErrorCode::InternalError,
+            ErrorCode::Unavailable,
+            ErrorCode::NotFound,
+            ErrorCode::InvalidBlockRange,
+        ] {
+            assert_eq!(ErrorCode::from_i64(*error_code as i64), Some(*error_code));
+        }
+    }
+
+    #[test]
+    fn should_map_error_codes_to_http_status() {
+        let not_found = ApiError::new(ErrorCode::NoSuchBlock, "no such block");
+        assert_eq!(not_found.http_status(), StatusCode::NOT_FOUND);
+
+        let invalid = ApiError::new(ErrorCode::InvalidDeploy, "invalid deploy");
+        assert_eq!(invalid.http_status(), StatusCode::BAD_REQUEST);
+
+        let unknown = ApiError {
+            code: 1,
+            message: "unknown".to_string(),
+        };
+        assert_eq!(unknown.http_status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn should_set_http_status_of_json_rpc_error_responses() {
+        for (error_code, expected_status) in &[
+            (ErrorCode::NoSuchDeploy, StatusCode::NOT_FOUND),
+            (ErrorCode::NoSuchBlock, StatusCode::NOT_FOUND),
+            (ErrorCode::ParseQueryKey, StatusCode::BAD_REQUEST),
+            (ErrorCode::QueryFailed, StatusCode::UNPROCESSABLE_ENTITY),
+            (ErrorCode::InternalError, StatusCode::INTERNAL_SERVER_ERROR),
+        ] {
+            let (status, body) = reply_with_error(ApiError::new(*error_code, "failed")).await;
+            assert_eq!(status, *expected_status);
+            assert_eq!(body["id"], json!(7));
+            assert_eq!(body["error"]["code"], json!(*error_code as i64));
+            assert_eq!(body["error"]["message"], json!("failed"));
+        }
+    }
+}
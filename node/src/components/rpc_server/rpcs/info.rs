@@ -11,20 +11,27 @@ use hyper::Body;
 use once_cell::sync::Lazy;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use tracing::info;
 use warp_json_rpc::Builder;
 
-use casper_types::{ExecutionResult, ProtocolVersion};
+use casper_execution_engine::core::engine_state::QueryResult;
+use casper_types::{bytesrepr::ToBytes, ExecutionResult, JournalEntry, OpKind, ProtocolVersion};
 
 use super::{
+    chain,
+    common::MERKLE_PROOF,
     docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
-    Error, ErrorCode, ReactorEventT, RpcRequest, RpcWithParams, RpcWithParamsExt, RpcWithoutParams,
-    RpcWithoutParamsExt,
+    error_response, ApiError, Error, ErrorCode, ReactorEventT, RpcRequest, RpcWithParams,
+    RpcWithParamsExt, RpcWithoutParams, RpcWithoutParamsExt,
 };
 use crate::{
     effect::EffectBuilder,
     reactor::QueueKind,
-    types::{Block, BlockHash, Deploy, DeployHash, GetStatusResult, Item, PeersMap},
+    types::{
+        Block, BlockHash, BlockIdentifier, Chainspec, Deploy, DeployHash, GetStatusResult, Item,
+        PeersMap, CHAINSPEC_KEY,
+    },
 };
 
 static GET_DEPLOY_PARAMS: Lazy<GetDeployParams> = Lazy::new(|| GetDeployParams {
@@ -38,10 +45,60 @@ static GET_DEPLOY_RESULT: Lazy<GetDeployResult> = Lazy::new(|| GetDeployResult {
         result: ExecutionResult::example().clone(),
     }],
 });
+static GET_PENDING_DEPLOY_PARAMS: Lazy<GetPendingDeployParams> =
+    Lazy::new(|| GetPendingDeployParams {
+        deploy_hash: *Deploy::doc_example().id(),
+    });
+static GET_PENDING_DEPLOY_RESULT: Lazy<GetPendingDeployResult> =
+    Lazy::new(|| GetPendingDeployResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        deploy: Deploy::doc_example().clone(),
+    });
+static GET_EXECUTION_JOURNAL_PARAMS: Lazy<GetExecutionJournalParams> =
+    Lazy::new(|| GetExecutionJournalParams {
+        deploy_hash: *Deploy::doc_example().id(),
+    });
+static GET_EXECUTION_JOURNAL_RESULT: Lazy<GetExecutionJournalResult> =
+    Lazy::new(|| GetExecutionJournalResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        journal: vec![
+            JournalEntry {
+                key: "uref-2c4a11c062a8a337bfc97e27fd66291caeb2c65865dcb5d3ef3759c4c97efecb-007"
+                    .to_string(),
+                kind: OpKind::Read,
+                value_type: "U512".to_string(),
+            },
+            JournalEntry {
+                key: "uref-2c4a11c062a8a337bfc97e27fd66291caeb2c65865dcb5d3ef3759c4c97efecb-007"
+                    .to_string(),
+                kind: OpKind::Add,
+                value_type: "U512".to_string(),
+            },
+        ],
+    });
 static GET_PEERS_RESULT: Lazy<GetPeersResult> = Lazy::new(|| GetPeersResult {
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     peers: GetStatusResult::doc_example().peers.clone(),
 });
+static GET_CHAINSPEC_RESULT: Lazy<GetChainspecResult> = Lazy::new(|| GetChainspecResult {
+    api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+    chainspec: json!({
+        "protocol": {
+            "version": DOCS_EXAMPLE_PROTOCOL_VERSION.to_string(),
+            "hard_reset": false,
+            "activation_point": 0
+        },
+        "network": { "name": "casper-example" },
+        "core": {
+            "era_duration": "2h",
+            "minimum_era_height": 20,
+            "validator_slots": 100,
+            "auction_delay": 3
+        },
+        "deploys": { "max_payment_cost": "0", "max_ttl": "1day" }
+    }),
+    merkle_proof: MERKLE_PROOF.clone(),
+});
 
 /// Params for "info_get_deploy" RPC request.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
@@ -121,10 +178,10 @@ impl RpcWithParamsExt for GetDeploy {
                         "failed to get {} and metadata from storage",
                         params.deploy_hash
                     );
-                    return Ok(response_builder.error(warp_json_rpc::Error::custom(
-                        ErrorCode::NoSuchDeploy as i64,
-                        "deploy not known",
-                    ))?);
+                    return error_response(
+                        response_builder,
+                        ApiError::new(ErrorCode::NoSuchDeploy, "deploy not known"),
+                    );
                 }
             };
 
@@ -146,6 +203,167 @@ impl RpcWithParamsExt for GetDeploy {
     }
 }
 
+/// Params for "info_get_pending_deploy" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetPendingDeployParams {
+    /// The deploy hash.
+    pub deploy_hash: DeployHash,
+}
+
+impl DocExample for GetPendingDeployParams {
+    fn doc_example() -> &'static Self {
+        &*GET_PENDING_DEPLOY_PARAMS
+    }
+}
+
+/// Result for "info_get_pending_deploy" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetPendingDeployResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The pending deploy, with all approvals collected so far.
+    pub deploy: Deploy,
+}
+
+impl DocExample for GetPendingDeployResult {
+    fn doc_example() -> &'static Self {
+        &*GET_PENDING_DEPLOY_RESULT
+    }
+}
+
+/// "info_get_pending_deploy" RPC.
+pub struct GetPendingDeploy {}
+
+impl RpcWithParams for GetPendingDeploy {
+    const METHOD: &'static str = "info_get_pending_deploy";
+    type RequestParams = GetPendingDeployParams;
+    type ResponseResult = GetPendingDeployResult;
+}
+
+impl RpcWithParamsExt for GetPendingDeploy {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+        api_version: ProtocolVersion,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            let maybe_deploy = effect_builder
+                .make_request(
+                    |responder| RpcRequest::GetPendingDeploy {
+                        deploy_hash: params.deploy_hash,
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+
+            let deploy = match maybe_deploy {
+                Some(deploy) => *deploy,
+                None => {
+                    info!("failed to get pending {} from storage", params.deploy_hash);
+                    return error_response(
+                        response_builder,
+                        ApiError::new(ErrorCode::NoSuchDeploy, "pending deploy not known"),
+                    );
+                }
+            };
+
+            let result = Self::ResponseResult {
+                api_version,
+                deploy,
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
+
+/// Params for "info_get_execution_journal" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetExecutionJournalParams {
+    /// The deploy hash.
+    pub deploy_hash: DeployHash,
+}
+
+impl DocExample for GetExecutionJournalParams {
+    fn doc_example() -> &'static Self {
+        &*GET_EXECUTION_JOURNAL_PARAMS
+    }
+}
+
+/// Result for "info_get_execution_journal" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetExecutionJournalResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The global state operations performed by the deploy, in execution order.
+    pub journal: Vec<JournalEntry>,
+}
+
+impl DocExample for GetExecutionJournalResult {
+    fn doc_example() -> &'static Self {
+        &*GET_EXECUTION_JOURNAL_RESULT
+    }
+}
+
+/// "info_get_execution_journal" RPC.
+pub struct GetExecutionJournal {}
+
+impl RpcWithParams for GetExecutionJournal {
+    const METHOD: &'static str = "info_get_execution_journal";
+    type RequestParams = GetExecutionJournalParams;
+    type ResponseResult = GetExecutionJournalResult;
+}
+
+impl RpcWithParamsExt for GetExecutionJournal {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+        api_version: ProtocolVersion,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            let maybe_journal = effect_builder
+                .make_request(
+                    |responder| RpcRequest::GetExecutionJournal {
+                        deploy_hash: params.deploy_hash,
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+
+            let journal = match maybe_journal {
+                Some(journal) => journal,
+                None => {
+                    info!(
+                        "failed to get execution journal of {} from storage",
+                        params.deploy_hash
+                    );
+                    return error_response(
+                        response_builder,
+                        ApiError::new(ErrorCode::NoSuchDeploy, "execution journal not known"),
+                    );
+                }
+            };
+
+            let result = Self::ResponseResult {
+                api_version,
+                journal,
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
+
 /// Result for "info_get_peers" RPC response.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 #[serde(deny_unknown_fields)]
@@ -225,3 +443,120 @@ impl RpcWithoutParamsExt for GetStatus {
         .boxed()
     }
 }
+
+/// Result for "info_get_chainspec" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetChainspecResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The chainspec the network is running, as stored in global state at the latest block.
+    pub chainspec: Value,
+    /// The merkle proof of the stored chainspec.
+    pub merkle_proof: String,
+}
+
+impl DocExample for GetChainspecResult {
+    fn doc_example() -> &'static Self {
+        &*GET_CHAINSPEC_RESULT
+    }
+}
+
+/// "info_get_chainspec" RPC.
+pub struct GetChainspec {}
+
+impl RpcWithoutParams for GetChainspec {
+    const METHOD: &'static str = "info_get_chainspec";
+    type ResponseResult = GetChainspecResult;
+}
+
+impl RpcWithoutParamsExt for GetChainspec {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        api_version: ProtocolVersion,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            // Get the latest block.
+            let state_root_hash =
+                match chain::get_block(BlockIdentifier::Latest, effect_builder).await {
+                    Ok(Some(block)) => *block.state_root_hash(),
+                    Ok(None) => {
+                        return error_response(
+                            response_builder,
+                            ApiError::new(ErrorCode::NoSuchBlock, "block not known"),
+                        )
+                    }
+                    Err(error) => return error_response(response_builder, error),
+                };
+
+            // Read the chainspec stored in global state.
+            let query_result = effect_builder
+                .make_request(
+                    |responder| RpcRequest::QueryGlobalState {
+                        state_root_hash,
+                        base_key: *CHAINSPEC_KEY,
+                        path: vec![],
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+
+            let (stored_value, proofs) = match query_result {
+                Ok(QueryResult::Success { value, proofs }) => (*value, proofs),
+                Ok(query_result) => {
+                    let error_msg = format!("chainspec not found: {:?}", query_result);
+                    info!("{}", error_msg);
+                    return error_response(
+                        response_builder,
+                        ApiError::new(ErrorCode::QueryFailed, error_msg),
+                    );
+                }
+                Err(error) => {
+                    let error_msg = format!("chainspec query failed to execute: {:?}", error);
+                    info!("{}", error_msg);
+                    return error_response(
+                        response_builder,
+                        ApiError::new(ErrorCode::QueryFailedToExecute, error_msg),
+                    );
+                }
+            };
+
+            let chainspec = match Chainspec::from_stored_value(stored_value) {
+                Some(chainspec) => chainspec,
+                None => {
+                    info!("failed to parse chainspec stored in global state");
+                    return error_response(
+                        response_builder,
+                        ApiError::new(
+                            ErrorCode::InternalError,
+                            "failed to parse chainspec stored in global state",
+                        ),
+                    );
+                }
+            };
+
+            let (chainspec, merkle_proof) =
+                match (serde_json::to_value(&chainspec), proofs.to_bytes()) {
+                    (Ok(chainspec), Ok(proof_bytes)) => (chainspec, hex::encode(proof_bytes)),
+                    _ => {
+                        info!("failed to encode chainspec or its merkle proof");
+                        return error_response(
+                            response_builder,
+                            ApiError::new(ErrorCode::InternalError, "failed to encode chainspec"),
+                        );
+                    }
+                };
+
+            let result = Self::ResponseResult {
+                api_version,
+                chainspec,
+                merkle_proof,
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
@@ -14,15 +14,23 @@ use serde::{Deserialize, Serialize};
 use tracing::info;
 use warp_json_rpc::Builder;
 
-use casper_execution_engine::core::engine_state::{BalanceResult, GetBidsResult};
-use casper_types::{bytesrepr::ToBytes, CLValue, Key, ProtocolVersion, URef, U512};
+use casper_execution_engine::{
+    core::engine_state::{BalanceResult, GetBidsResult, QueryResult},
+    shared::account::Account as ExecutionEngineAccount,
+};
+use casper_types::{
+    account::AccountHash, bytesrepr::ToBytes, AccessRights, CLValue, Key, ProtocolVersion,
+    PublicKey, SecretKey, URef, U512,
+};
 
 use super::{
     docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
-    Error, ErrorCode, ReactorEventT, RpcRequest, RpcWithParams, RpcWithParamsExt,
+    error_response, ApiError, Error, ErrorCode, ReactorEventT, RpcRequest, RpcWithParams,
+    RpcWithParamsExt,
 };
 use crate::{
-    crypto::hash::Digest,
+    components::{contract_runtime::QueryError, rpc_server::BalanceAtBlock},
+    crypto::{hash::Digest, AsymmetricKeyExt},
     effect::EffectBuilder,
     reactor::QueueKind,
     rpcs::{
@@ -30,8 +38,8 @@ use crate::{
         RpcWithoutParams, RpcWithoutParamsExt,
     },
     types::{
-        json_compatibility::{AuctionState, StoredValue},
-        Block,
+        json_compatibility::{Account, AuctionState, StoredValue},
+        Block, BlockHash, BlockIdentifier,
     },
 };
 
@@ -55,6 +63,40 @@ static GET_BALANCE_RESULT: Lazy<GetBalanceResult> = Lazy::new(|| GetBalanceResul
     balance_value: U512::from(123_456),
     merkle_proof: MERKLE_PROOF.clone(),
 });
+static GET_ACCOUNT_INFO_PARAMS: Lazy<GetAccountInfoParams> = Lazy::new(|| {
+    let secret_key = SecretKey::doc_example();
+    GetAccountInfoParams {
+        account_identifier: AccountIdentifier::PublicKey(PublicKey::from(secret_key)),
+    }
+});
+static GET_ACCOUNT_INFO_RESULT: Lazy<GetAccountInfoResult> = Lazy::new(|| {
+    let account_hash = PublicKey::from(SecretKey::doc_example()).to_account_hash();
+    let main_purse = URef::new([2; 32], AccessRights::READ_ADD_WRITE);
+    let account = ExecutionEngineAccount::create(account_hash, Default::default(), main_purse);
+    GetAccountInfoResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        state_root_hash: *Block::doc_example().header().state_root_hash(),
+        account: Account::from(&account),
+        main_purse_balance: U512::from(123_456),
+        merkle_proof: MERKLE_PROOF.clone(),
+    }
+});
+static GET_BALANCE_AT_BLOCK_PARAMS: Lazy<GetBalanceAtBlockParams> =
+    Lazy::new(|| GetBalanceAtBlockParams {
+        block_identifier: Some(BlockIdentifier::Hash(*Block::doc_example().hash())),
+        purse_identifier: PurseIdentifier::MainPurseUnderAccount(AccountIdentifier::PublicKey(
+            PublicKey::from(SecretKey::doc_example()),
+        )),
+    });
+static GET_BALANCE_AT_BLOCK_RESULT: Lazy<GetBalanceAtBlockResult> =
+    Lazy::new(|| GetBalanceAtBlockResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        block_hash: *Block::doc_example().hash(),
+        block_height: Block::doc_example().height(),
+        state_root_hash: *Block::doc_example().header().state_root_hash(),
+        balance_value: U512::from(123_456),
+        merkle_proof: MERKLE_PROOF.clone(),
+    });
 static GET_AUCTION_INFO_RESULT: Lazy<GetAuctionInfoResult> = Lazy::new(|| GetAuctionInfoResult {
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     auction_state: AuctionState::doc_example().clone(),
@@ -122,10 +164,10 @@ impl RpcWithParamsExt for GetItem {
                 Ok(key) => key,
                 Err(error_msg) => {
                     info!("{}", error_msg);
-                    return Ok(response_builder.error(warp_json_rpc::Error::custom(
-                        ErrorCode::ParseQueryKey as i64,
-                        error_msg,
-                    ))?);
+                    return error_response(
+                        response_builder,
+                        ApiError::new(ErrorCode::ParseQueryKey, error_msg),
+                    );
                 }
             };
 
@@ -144,10 +186,9 @@ impl RpcWithParamsExt for GetItem {
 
             let (stored_value, proof_bytes) = match common::extract_query_result(query_result) {
                 Ok(tuple) => tuple,
-                Err((error_code, error_msg)) => {
-                    info!("{}", error_msg);
-                    return Ok(response_builder
-                        .error(warp_json_rpc::Error::custom(error_code as i64, error_msg))?);
+                Err(error) => {
+                    info!("{}", error.message);
+                    return error_response(response_builder, error);
                 }
             };
 
@@ -222,10 +263,10 @@ impl RpcWithParamsExt for GetBalance {
                 Ok(uref) => uref,
                 Err(error_msg) => {
                     info!("{}", error_msg);
-                    return Ok(response_builder.error(warp_json_rpc::Error::custom(
-                        ErrorCode::ParseGetBalanceURef as i64,
-                        error_msg,
-                    ))?);
+                    return error_response(
+                        response_builder,
+                        ApiError::new(ErrorCode::ParseGetBalanceURef, error_msg),
+                    );
                 }
             };
 
@@ -246,18 +287,18 @@ impl RpcWithParamsExt for GetBalance {
                 Ok(balance_result) => {
                     let error_msg = format!("get-balance failed: {:?}", balance_result);
                     info!("{}", error_msg);
-                    return Ok(response_builder.error(warp_json_rpc::Error::custom(
-                        ErrorCode::GetBalanceFailed as i64,
-                        error_msg,
-                    ))?);
+                    return error_response(
+                        response_builder,
+                        ApiError::new(ErrorCode::GetBalanceFailed, error_msg),
+                    );
                 }
                 Err(error) => {
                     let error_msg = format!("get-balance failed to execute: {}", error);
                     info!("{}", error_msg);
-                    return Ok(response_builder.error(warp_json_rpc::Error::custom(
-                        ErrorCode::GetBalanceFailedToExecute as i64,
-                        error_msg,
-                    ))?);
+                    return error_response(
+                        response_builder,
+                        ApiError::new(ErrorCode::GetBalanceFailedToExecute, error_msg),
+                    );
                 }
             };
 
@@ -265,7 +306,10 @@ impl RpcWithParamsExt for GetBalance {
                 Ok(proof_bytes) => proof_bytes,
                 Err(error) => {
                     info!("failed to encode stored value: {}", error);
-                    return Ok(response_builder.error(warp_json_rpc::Error::INTERNAL_ERROR)?);
+                    return error_response(
+                        response_builder,
+                        ApiError::new(ErrorCode::InternalError, "failed to encode stored value"),
+                    );
                 }
             };
 
@@ -283,6 +327,357 @@ impl RpcWithParamsExt for GetBalance {
     }
 }
 
+/// Identifier of an account, either by its public key or by its account hash.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone)]
+#[serde(deny_unknown_fields)]
+pub enum AccountIdentifier {
+    /// The public key of the account.
+    PublicKey(PublicKey),
+    /// The account hash of the account.
+    AccountHash(AccountHash),
+}
+
+impl AccountIdentifier {
+    /// Returns the account hash identified by `self`.
+    pub fn account_hash(&self) -> AccountHash {
+        match self {
+            AccountIdentifier::PublicKey(public_key) => public_key.to_account_hash(),
+            AccountIdentifier::AccountHash(account_hash) => *account_hash,
+        }
+    }
+}
+
+/// Params for "state_get_account_info" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetAccountInfoParams {
+    /// The identifier of the account.
+    pub account_identifier: AccountIdentifier,
+}
+
+impl DocExample for GetAccountInfoParams {
+    fn doc_example() -> &'static Self {
+        &*GET_ACCOUNT_INFO_PARAMS
+    }
+}
+
+/// Result for "state_get_account_info" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetAccountInfoResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The state root hash of the most recently added block, against which the account was read.
+    pub state_root_hash: Digest,
+    /// The account, including its named keys, associated keys and action thresholds.
+    pub account: Account,
+    /// The balance of the account's main purse.
+    pub main_purse_balance: U512,
+    /// The merkle proof of the account.
+    pub merkle_proof: String,
+}
+
+impl DocExample for GetAccountInfoResult {
+    fn doc_example() -> &'static Self {
+        &*GET_ACCOUNT_INFO_RESULT
+    }
+}
+
+/// "state_get_account_info" RPC.
+pub struct GetAccountInfo {}
+
+impl RpcWithParams for GetAccountInfo {
+    const METHOD: &'static str = "state_get_account_info";
+    type RequestParams = GetAccountInfoParams;
+    type ResponseResult = GetAccountInfoResult;
+}
+
+impl RpcWithParamsExt for GetAccountInfo {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+        api_version: ProtocolVersion,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            // Get the state root hash of the latest block.
+            let maybe_block = effect_builder
+                .make_request(
+                    |responder| RpcRequest::GetBlock {
+                        block_identifier: BlockIdentifier::Latest,
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+            let state_root_hash = match maybe_block {
+                Some((block, _)) => *block.header().state_root_hash(),
+                None => {
+                    let error_msg = "get-account-info failed to get last added block";
+                    info!("{}", error_msg);
+                    return error_response(
+                        response_builder,
+                        ApiError::new(ErrorCode::NoSuchBlock, error_msg),
+                    );
+                }
+            };
+
+            // Read the account record.
+            let base_key = Key::Account(params.account_identifier.account_hash());
+            let query_result = effect_builder
+                .make_request(
+                    |responder| RpcRequest::QueryGlobalState {
+                        state_root_hash,
+                        base_key,
+                        path: vec![],
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+
+            let (account, proof_bytes) = match extract_account(base_key, query_result) {
+                Ok(tuple) => tuple,
+                Err(error) => {
+                    info!("{}", error.message);
+                    return error_response(response_builder, error);
+                }
+            };
+
+            // Resolve the balance of the main purse.
+            let purse_uref = account.main_purse();
+            let balance_result = effect_builder
+                .make_request(
+                    |responder| RpcRequest::GetBalance {
+                        state_root_hash,
+                        purse_uref,
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+
+            let main_purse_balance = match balance_result {
+                Ok(BalanceResult::Success { motes, .. }) => motes,
+                Ok(balance_result) => {
+                    let error_msg = format!("get-balance failed: {:?}", balance_result);
+                    info!("{}", error_msg);
+                    return error_response(
+                        response_builder,
+                        ApiError::new(ErrorCode::GetBalanceFailed, error_msg),
+                    );
+                }
+                Err(error) => {
+                    let error_msg = format!("get-balance failed to execute: {}", error);
+                    info!("{}", error_msg);
+                    return error_response(
+                        response_builder,
+                        ApiError::new(ErrorCode::GetBalanceFailedToExecute, error_msg),
+                    );
+                }
+            };
+
+            let result = Self::ResponseResult {
+                api_version,
+                state_root_hash,
+                account,
+                main_purse_balance,
+                merkle_proof: hex::encode(proof_bytes),
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
+
+/// Extracts the account and the serialized merkle proof from the result of querying `base_key`.
+fn extract_account(
+    base_key: Key,
+    query_result: Result<QueryResult, QueryError>,
+) -> Result<(Account, Vec<u8>), ApiError> {
+    let (stored_value, proof_bytes) = common::extract_query_result(query_result)?;
+    match stored_value {
+        StoredValue::Account(account) => Ok((account, proof_bytes)),
+        other => {
+            let error_msg = format!("{} is not an account: {:?}", base_key, other);
+            Err(ApiError::new(ErrorCode::QueryFailed, error_msg))
+        }
+    }
+}
+
+/// Identifier of a purse, either as the main purse of an account or by its URef.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone)]
+#[serde(deny_unknown_fields)]
+pub enum PurseIdentifier {
+    /// The main purse of the identified account.
+    MainPurseUnderAccount(AccountIdentifier),
+    /// Formatted URef of the purse.
+    PurseUref(String),
+}
+
+/// Params for "state_get_balance_at_block" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetBalanceAtBlockParams {
+    /// The identifier of the block at whose state root the balance is read.  If `None`, the most
+    /// recently added block is used.
+    #[serde(default)]
+    pub block_identifier: Option<BlockIdentifier>,
+    /// The identifier of the purse.
+    pub purse_identifier: PurseIdentifier,
+}
+
+impl DocExample for GetBalanceAtBlockParams {
+    fn doc_example() -> &'static Self {
+        &*GET_BALANCE_AT_BLOCK_PARAMS
+    }
+}
+
+/// Result for "state_get_balance_at_block" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetBalanceAtBlockResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The hash of the block the balance was read at.
+    pub block_hash: BlockHash,
+    /// The height of the block the balance was read at.
+    pub block_height: u64,
+    /// The state root hash of the block the balance was read at.
+    pub state_root_hash: Digest,
+    /// The balance value.
+    pub balance_value: U512,
+    /// The merkle proof.
+    pub merkle_proof: String,
+}
+
+impl DocExample for GetBalanceAtBlockResult {
+    fn doc_example() -> &'static Self {
+        &*GET_BALANCE_AT_BLOCK_RESULT
+    }
+}
+
+/// "state_get_balance_at_block" RPC.
+pub struct GetBalanceAtBlock {}
+
+impl RpcWithParams for GetBalanceAtBlock {
+    const METHOD: &'static str = "state_get_balance_at_block";
+    type RequestParams = GetBalanceAtBlockParams;
+    type ResponseResult = GetBalanceAtBlockResult;
+}
+
+impl RpcWithParamsExt for GetBalanceAtBlock {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+        api_version: ProtocolVersion,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            let purse_or_account = match params.purse_identifier {
+                PurseIdentifier::MainPurseUnderAccount(account_identifier) => {
+                    Key::Account(account_identifier.account_hash())
+                }
+                PurseIdentifier::PurseUref(purse_uref) => {
+                    match URef::from_formatted_str(&purse_uref) {
+                        Ok(uref) => Key::URef(uref),
+                        Err(error) => {
+                            let error_msg = format!("failed to parse purse_uref: {:?}", error);
+                            info!("{}", error_msg);
+                            return error_response(
+                                response_builder,
+                                ApiError::new(ErrorCode::ParseGetBalanceURef, error_msg),
+                            );
+                        }
+                    }
+                }
+            };
+
+            let balance_at_block = effect_builder
+                .make_request(
+                    |responder| RpcRequest::GetBalanceAtBlock {
+                        block_identifier: params
+                            .block_identifier
+                            .unwrap_or(BlockIdentifier::Latest),
+                        purse_or_account,
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+
+            let (block_hash, block_height, state_root_hash, balance_result) = match balance_at_block
+            {
+                BalanceAtBlock::Balance {
+                    block_hash,
+                    block_height,
+                    state_root_hash,
+                    result,
+                } => (block_hash, block_height, state_root_hash, result),
+                BalanceAtBlock::NoSuchBlock => {
+                    let error_msg = "get-balance-at-block failed to get specified block";
+                    info!("{}", error_msg);
+                    return error_response(
+                        response_builder,
+                        ApiError::new(ErrorCode::NoSuchBlock, error_msg),
+                    );
+                }
+                BalanceAtBlock::NoSuchPurse(error_msg) => {
+                    info!("{}", error_msg);
+                    return error_response(
+                        response_builder,
+                        ApiError::new(ErrorCode::QueryFailed, error_msg),
+                    );
+                }
+            };
+
+            let (balance_value, balance_proof) = match balance_result {
+                Ok(BalanceResult::Success { motes, proof }) => (motes, proof),
+                Ok(balance_result) => {
+                    let error_msg = format!("get-balance failed: {:?}", balance_result);
+                    info!("{}", error_msg);
+                    return error_response(
+                        response_builder,
+                        ApiError::new(ErrorCode::GetBalanceFailed, error_msg),
+                    );
+                }
+                Err(error) => {
+                    let error_msg = format!("get-balance failed to execute: {}", error);
+                    info!("{}", error_msg);
+                    return error_response(
+                        response_builder,
+                        ApiError::new(ErrorCode::GetBalanceFailedToExecute, error_msg),
+                    );
+                }
+            };
+
+            let proof_bytes = match balance_proof.to_bytes() {
+                Ok(proof_bytes) => proof_bytes,
+                Err(error) => {
+                    info!("failed to encode stored value: {}", error);
+                    return error_response(
+                        response_builder,
+                        ApiError::new(ErrorCode::InternalError, "failed to encode stored value"),
+                    );
+                }
+            };
+
+            let result = Self::ResponseResult {
+                api_version,
+                block_hash,
+                block_height,
+                state_root_hash,
+                balance_value,
+                merkle_proof: hex::encode(proof_bytes),
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
+
 /// Result for "state_get_auction_info" RPC response.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 #[serde(deny_unknown_fields)]
@@ -319,7 +714,7 @@ impl RpcWithoutParamsExt for GetAuctionInfo {
                 let maybe_block = effect_builder
                     .make_request(
                         |responder| RpcRequest::GetBlock {
-                            maybe_id: None,
+                            block_identifier: BlockIdentifier::Latest,
                             responder,
                         },
                         QueueKind::Api,
@@ -331,10 +726,10 @@ impl RpcWithoutParamsExt for GetAuctionInfo {
                         let error_msg =
                             "get-auction-info failed to get last added block".to_string();
                         info!("{}", error_msg);
-                        return Ok(response_builder.error(warp_json_rpc::Error::custom(
-                            ErrorCode::NoSuchBlock as i64,
-                            error_msg,
-                        ))?);
+                        return error_response(
+                            response_builder,
+                            ApiError::new(ErrorCode::NoSuchBlock, error_msg),
+                        );
                     }
                     Some((block, _)) => block,
                 }
@@ -388,3 +783,61 @@ impl RpcWithoutParamsExt for GetAuctionInfo {
         .boxed()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use casper_execution_engine::shared::stored_value::StoredValue as ExecutionEngineStoredValue;
+
+    use super::*;
+
+    fn account_key() -> Key {
+        Key::Account(PublicKey::from(SecretKey::doc_example()).to_account_hash())
+    }
+
+    fn query_success(value: ExecutionEngineStoredValue) -> Result<QueryResult, QueryError> {
+        Ok(QueryResult::Success {
+            value: Box::new(value),
+            proofs: vec![],
+        })
+    }
+
+    #[test]
+    fn should_extract_found_account() {
+        let account_hash = PublicKey::from(SecretKey::doc_example()).to_account_hash();
+        let main_purse = URef::new([2; 32], AccessRights::READ_ADD_WRITE);
+        let account = ExecutionEngineAccount::create(account_hash, Default::default(), main_purse);
+        let query_result = query_success(ExecutionEngineStoredValue::Account(account.clone()));
+
+        let (extracted, _proof_bytes) = extract_account(account_key(), query_result).unwrap();
+        assert_eq!(extracted, Account::from(&account));
+        assert_eq!(extracted.main_purse(), main_purse);
+    }
+
+    #[test]
+    fn should_fail_to_extract_missing_account() {
+        let query_result = Ok(QueryResult::ValueNotFound("no account".to_string()));
+        let error = extract_account(account_key(), query_result).unwrap_err();
+        assert_eq!(error.code, ErrorCode::QueryFailed as i64);
+    }
+
+    #[test]
+    fn should_fail_to_extract_account_from_other_value() {
+        let value = ExecutionEngineStoredValue::CLValue(CLValue::from_t(1u64).unwrap());
+        let error = extract_account(account_key(), query_success(value)).unwrap_err();
+        assert_eq!(error.code, ErrorCode::QueryFailed as i64);
+        assert!(error.message.contains("is not an account"));
+    }
+
+    #[test]
+    fn should_reject_malformed_account_identifier() {
+        let params = json!({ "account_identifier": { "AccountHash": "account-hash-not-hex" } });
+        assert!(serde_json::from_value::<GetAccountInfoParams>(params).is_err());
+
+        let account_hash = PublicKey::from(SecretKey::doc_example()).to_account_hash();
+        let params = json!({ "account_identifier": { "AccountHash": account_hash } });
+        let params = serde_json::from_value::<GetAccountInfoParams>(params).unwrap();
+        assert_eq!(params.account_identifier.account_hash(), account_hash);
+    }
+}
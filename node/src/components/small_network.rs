@@ -20,11 +20,21 @@
 //! Any incoming connection is, after a handshake process, strictly read from, while any outgoing
 //! connection is strictly used for sending messages, also after a handshake.
 //!
+//! Handshakes carry the network name, protocol version and chainspec hash of the sender. Peers
+//! disagreeing with us on any of these are disconnected, so that all payloads exchanged over a
+//! connection are known to be of our own protocol version.
+//!
+//! Handshakes also announce whether the sender accepts compressed messages. Payloads exceeding the
+//! configured compression threshold are only compressed once the peer's handshake announced it.
+//!
 //! Nodes gossip their public listening addresses periodically, and will try to establish and
 //! maintain an outgoing connection to any new address learned.
 
+mod blocklist;
 mod chain_info;
+mod compression_format;
 mod config;
+mod connection_stats;
 mod counting_format;
 mod error;
 mod event;
@@ -57,11 +67,11 @@ use futures::{
     stream::{SplitSink, SplitStream},
     FutureExt, SinkExt, StreamExt,
 };
-use once_cell::sync::Lazy;
+use num_traits::AsPrimitive;
 use openssl::{error::ErrorStack as OpenSslErrorStack, pkey, ssl::Ssl};
 use pkey::{PKey, Private};
 use prometheus::{IntGauge, Registry};
-use rand::seq::IteratorRandom;
+use rand::seq::{IteratorRandom, SliceRandom};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use thiserror::Error;
 use tokio::{
@@ -77,9 +87,15 @@ use tokio_serde::SymmetricallyFramed;
 use tokio_util::codec::{Framed, LengthDelimitedCodec};
 use tracing::{debug, error, info, trace, warn};
 
+use casper_types::{EraId, PublicKey, SecretKey, U512};
+
 use self::{
+    blocklist::Blocklist,
+    compression_format::CompressionFormat,
+    connection_stats::ConnectionStats,
     counting_format::{ConnectionId, CountingFormat, Role},
     error::Result,
+    message::ConsensusCertificate,
     message_pack_format::MessagePackFormat,
 };
 pub(crate) use self::{
@@ -91,7 +107,7 @@ use crate::{
     },
     effect::{
         announcements::{BlocklistAnnouncement, NetworkAnnouncement},
-        requests::{NetworkInfoRequest, NetworkRequest},
+        requests::{NetworkInfoRequest, NetworkRequest, StateStoreRequest},
         EffectBuilder, EffectExt, EffectResultExt, Effects,
     },
     reactor::{EventQueueHandle, Finalize, QueueKind, ReactorEvent},
@@ -99,18 +115,20 @@ use crate::{
     types::{NodeId, TimeDiff, Timestamp},
     utils, NodeRng,
 };
+pub use blocklist::BanTarget;
 use chain_info::ChainInfo;
 pub use config::Config;
+pub use connection_stats::{ConnectionDirection, ConnectionInfo};
 pub use error::Error;
 
 const MAX_ASYMMETRIC_CONNECTION_SEEN: u16 = 4;
 
+/// The number of most recent eras whose validators are preferred peers.
+const PREFERRED_PEER_ERAS: usize = 2;
+
 const MAX_METRICS_DROP_ATTEMPTS: usize = 25;
 const DROP_RETRY_DELAY: Duration = Duration::from_millis(100);
 
-static BLOCKLIST_RETAIN_DURATION: Lazy<TimeDiff> =
-    Lazy::new(|| Duration::from_secs(60 * 10).into());
-
 /// Network message payload.
 ///
 /// Payloads are what is transferred across the network outside of control messages from the
@@ -135,6 +153,8 @@ pub enum MessageKind {
     DeployTransfer,
     /// Blocks for finality signatures being transferred directly (via requests and other means).
     BlockTransfer,
+    /// Global state trie nodes being transferred directly (via requests).
+    TrieTransfer,
     /// Any other kind of payload (or missing classification).
     Other,
 }
@@ -148,6 +168,7 @@ impl Display for MessageKind {
             MessageKind::AddressGossip => f.write_str("address_gossip"),
             MessageKind::DeployTransfer => f.write_str("deploy_transfer"),
             MessageKind::BlockTransfer => f.write_str("block_transfer"),
+            MessageKind::TrieTransfer => f.write_str("trie_transfer"),
             MessageKind::Other => f.write_str("other"),
         }
     }
@@ -158,6 +179,8 @@ pub(crate) struct OutgoingConnection<P> {
     #[data_size(skip)] // Unfortunately, there is no way to inspect an `UnboundedSender`.
     sender: UnboundedSender<Message<P>>,
     peer_address: SocketAddr,
+    #[data_size(skip)]
+    stats: Arc<ConnectionStats>,
 
     // for keeping track of connection asymmetry, tracking the number of times we've seen this
     // connection be asymmetric.
@@ -167,6 +190,8 @@ pub(crate) struct OutgoingConnection<P> {
 #[derive(DataSize, Debug)]
 pub(crate) struct IncomingConnection {
     peer_address: SocketAddr,
+    #[data_size(skip)]
+    stats: Arc<ConnectionStats>,
 
     // for keeping track of connection asymmetry, tracking the number of times we've seen this
     // connection be asymmetric.
@@ -198,8 +223,8 @@ where
     /// Outgoing network connections' messages.
     outgoing: HashMap<NodeId, OutgoingConnection<P>>,
 
-    /// List of addresses which this node will avoid connecting to and the time they were added.
-    blocklist: HashMap<SocketAddr, Timestamp>,
+    /// Peers and addresses which this node will not connect to or accept connections from.
+    blocklist: Blocklist,
 
     /// Pending outgoing connections: ones for which we are currently trying to make a connection.
     pending: HashMap<SocketAddr, Instant>,
@@ -228,17 +253,32 @@ where
 
     /// Known addresses for this node.
     known_addresses: HashSet<SocketAddr>,
+
+    /// Our certificate proving we hold our consensus key, if we have one.
+    consensus_certificate: Option<ConsensusCertificate>,
+    /// The consensus keys that connected peers proved to hold in their handshakes.
+    peer_consensus_keys: HashMap<NodeId, PublicKey>,
+    /// The validators of the most recent eras, with their weights. Connections to them are
+    /// preferred.
+    era_validators: BTreeMap<EraId, BTreeMap<PublicKey, U512>>,
 }
 
 impl<REv, P> SmallNetwork<REv, P>
 where
     P: Payload + 'static,
-    REv: ReactorEvent + From<Event<P>> + From<NetworkAnnouncement<NodeId, P>>,
+    REv: ReactorEvent
+        + From<Event<P>>
+        + From<NetworkAnnouncement<NodeId, P>>
+        + From<StateStoreRequest>,
 {
     /// Creates a new small network component instance.
     ///
     /// If `notify` is set to `false`, no systemd notifications will be sent, regardless of
     /// configuration.
+    ///
+    /// If a `consensus_secret_key` is given and `send_consensus_certificate` is enabled in the
+    /// config, our handshakes prove to peers that we hold it, so that they can give us priority
+    /// while we are a validator.
     #[allow(clippy::type_complexity)]
     pub(crate) fn new<C: Into<ChainInfo>>(
         event_queue: EventQueueHandle<REv>,
@@ -246,6 +286,7 @@ where
         registry: &Registry,
         small_network_identity: SmallNetworkIdentity,
         chain_info_source: C,
+        consensus_secret_key: Option<&SecretKey>,
         notify: bool,
     ) -> Result<(SmallNetwork<REv, P>, Effects<Event<P>>)> {
         let mut known_addresses = HashSet::new();
@@ -272,6 +313,9 @@ where
             utils::resolve_address(&cfg.public_address).map_err(Error::ResolveAddr)?;
 
         let our_id = NodeId::from(&small_network_identity);
+        let consensus_certificate = consensus_secret_key
+            .filter(|_| cfg.send_consensus_certificate)
+            .map(|secret_key| ConsensusCertificate::create(our_id, secret_key));
         let secret_key = small_network_identity.secret_key;
         let certificate = small_network_identity.tls_certificate;
 
@@ -292,13 +336,16 @@ where
                 incoming: HashMap::new(),
                 outgoing: HashMap::new(),
                 pending: HashMap::new(),
-                blocklist: HashMap::new(),
+                blocklist: Blocklist::default(),
                 chain_info,
                 shutdown_sender: None,
                 shutdown_receiver: watch::channel(()).1,
                 server_join_handle: None,
                 is_stopped: Arc::new(AtomicBool::new(true)),
                 net_metrics: Arc::new(NetworkingMetrics::new(&Registry::default())?),
+                consensus_certificate,
+                peer_consensus_keys: HashMap::new(),
+                era_validators: BTreeMap::new(),
             };
             return Ok((model, Effects::new()));
         }
@@ -360,13 +407,16 @@ where
             incoming: HashMap::new(),
             outgoing: HashMap::new(),
             pending: HashMap::new(),
-            blocklist: HashMap::new(),
+            blocklist: Blocklist::default(),
             chain_info,
             shutdown_sender: Some(server_shutdown_sender),
             shutdown_receiver,
             server_join_handle: Some(server_join_handle),
             is_stopped: Arc::new(AtomicBool::new(false)),
             net_metrics: Arc::new(net_metrics),
+            consensus_certificate,
+            peer_consensus_keys: HashMap::new(),
+            era_validators: BTreeMap::new(),
         };
 
         // Bootstrap process.
@@ -377,6 +427,13 @@ where
         // `connect_to_known_addresses` for details).
         let mut effects = model.connect_to_known_addresses();
 
+        // Restore the bans that were in effect before the node was last stopped.
+        effects.extend(
+            effect_builder
+                .load_state(blocklist::state_key())
+                .event(Event::BlocklistLoaded),
+        );
+
         // Start broadcasting our public listening address.
         effects.extend(
             effect_builder
@@ -462,6 +519,80 @@ where
         peer_ids.into_iter().copied().collect()
     }
 
+    /// Queues a message to up to `count` nodes on the network, preferring validators of `era_id`.
+    ///
+    /// Validators are chosen with a probability proportional to their weight. If fewer than
+    /// `count` of them are connected, the remaining nodes are chosen randomly among the others.
+    fn gossip_message_to_validators(
+        &self,
+        rng: &mut NodeRng,
+        msg: Message<P>,
+        era_id: EraId,
+        count: usize,
+    ) -> HashSet<NodeId> {
+        let validators = self.era_validators.get(&era_id);
+        let weighted_peers: Vec<(NodeId, U512)> = self
+            .outgoing
+            .keys()
+            .map(|peer_id| {
+                let weight = self
+                    .peer_consensus_keys
+                    .get(peer_id)
+                    .and_then(|public_key| validators?.get(public_key))
+                    .copied()
+                    .unwrap_or_else(U512::zero);
+                (*peer_id, weight)
+            })
+            .collect();
+
+        // Weighted sampling works on floating point weights, so we scale them down to fit into a
+        // `u64` first, the same way Highway does.
+        let total_weight: U512 = weighted_peers.iter().map(|(_, weight)| *weight).sum();
+        let scaling_factor =
+            ((total_weight + U512::from(u64::MAX) - 1) / U512::from(u64::MAX)).max(U512::one());
+        let (validator_peers, other_peers): (Vec<_>, Vec<_>) = weighted_peers
+            .into_iter()
+            .map(|(peer_id, weight)| {
+                let scaled_weight = AsPrimitive::<u64>::as_(weight / scaling_factor) as f64;
+                (peer_id, scaled_weight)
+            })
+            .partition(|(_, scaled_weight)| *scaled_weight > 0.0);
+
+        let chosen_validators =
+            validator_peers
+                .choose_multiple_weighted(rng, count, |(_, scaled_weight)| *scaled_weight);
+        let mut peer_ids: Vec<NodeId> = match chosen_validators {
+            Ok(chosen) => chosen.map(|(peer_id, _)| *peer_id).collect(),
+            Err(error) => {
+                warn!(our_id=%self.our_id, %error, "failed to choose validators for gossiping");
+                Vec::new()
+            }
+        };
+        let missing = count.saturating_sub(peer_ids.len());
+        peer_ids.extend(
+            other_peers
+                .into_iter()
+                .map(|(peer_id, _)| peer_id)
+                .choose_multiple(rng, missing),
+        );
+
+        if peer_ids.len() != count {
+            trace!(
+                our_id=%self.our_id,
+                wanted = count,
+                selected = peer_ids.len(),
+                "could not select enough nodes for gossiping to validators, not enough outgoing \
+                connections"
+            );
+        }
+
+        for &peer_id in &peer_ids {
+            self.send_message(peer_id, msg.clone());
+        }
+
+        peer_ids.into_iter().collect()
+    }
+
     /// Queues a message to be sent to a specific node.
     fn send_message(&self, dest: NodeId, msg: Message<P>) {
         // Try to send the message.
@@ -538,6 +669,11 @@ where
                     return Effects::new();
                 }
 
+                if self.is_banned(&peer_id, &peer_address) {
+                    debug!(our_id=%self.our_id, %peer_id, %peer_address, "dropping incoming connection from banned peer");
+                    return Effects::new();
+                }
+
                 // If the peer has already disconnected, allow the connection to drop.
                 if let Err(ref err) = transport.get_ref().peer_addr() {
                     debug!(
@@ -552,15 +688,20 @@ where
 
                 info!(our_id=%self.our_id, %peer_id, %peer_address, "established incoming connection");
                 // The sink is only used to send a single handshake message, then dropped.
+                let stats = Arc::new(ConnectionStats::new());
                 let (mut sink, stream) = framed::<P>(
                     Arc::downgrade(&self.net_metrics),
                     ConnectionId::from_connection(transport.ssl(), self.our_id, peer_id),
                     transport,
                     Role::Listener,
+                    stats.clone(),
                     self.chain_info.maximum_net_message_size,
+                    self.cfg.compression_threshold,
                 )
                 .split();
-                let handshake = self.chain_info.create_handshake(self.public_address);
+                let handshake = self
+                    .chain_info
+                    .create_handshake(self.public_address, self.consensus_certificate.clone());
                 let mut effects = async move {
                     let _ = sink.send(handshake).await;
                 }
@@ -570,6 +711,7 @@ where
                     peer_id,
                     IncomingConnection {
                         peer_address,
+                        stats,
                         times_seen_asymmetric: 0,
                     },
                 );
@@ -639,13 +781,21 @@ where
             return self.reconnect_if_not_connected_to_any_known_addresses(effect_builder);
         }
 
+        if self.is_banned(&peer_id, &peer_address) {
+            debug!(our_id=%self.our_id, %peer_id, %peer_address, "dropping outgoing connection to banned peer");
+            return self.reconnect_if_not_connected_to_any_known_addresses(effect_builder);
+        }
+
         // The stream is only used to receive a single handshake message and then dropped.
+        let stats = Arc::new(ConnectionStats::new());
         let (sink, stream) = framed::<P>(
             Arc::downgrade(&self.net_metrics),
             ConnectionId::from_connection(transport.ssl(), self.our_id, peer_id),
             transport,
             Role::Dialer,
+            stats.clone(),
             self.chain_info.maximum_net_message_size,
+            self.cfg.compression_threshold,
         )
         .split();
         debug!(our_id=%self.our_id, %peer_id, %peer_address, "established outgoing connection");
@@ -654,6 +804,7 @@ where
         let connection = OutgoingConnection {
             peer_address,
             sender,
+            stats,
             times_seen_asymmetric: 0,
         };
         if self.outgoing.insert(peer_id, connection).is_some() {
@@ -665,7 +816,9 @@ where
 
         let mut effects = self.check_connection_complete(effect_builder, peer_id);
 
-        let handshake = self.chain_info.create_handshake(self.public_address);
+        let handshake = self
+            .chain_info
+            .create_handshake(self.public_address, self.consensus_certificate.clone());
 
         effects.extend(
             message_sender(
@@ -738,6 +891,7 @@ where
         peer_id: &NodeId,
         add_to_blocklist: bool,
     ) -> Effects<Event<P>> {
+        let mut effects = Effects::new();
         if let Some(incoming) = self.incoming.remove(peer_id) {
             trace!(our_id=%self.our_id, %peer_id, "removing peer from the incoming connections");
             let _ = self.pending.remove(&incoming.peer_address);
@@ -746,16 +900,106 @@ where
                 .open_connections
                 .set(self.incoming.len() as i64);
         }
+        let _ = self.peer_consensus_keys.remove(peer_id);
         if let Some(outgoing) = self.outgoing.remove(peer_id) {
             trace!(our_id=%self.our_id, %peer_id, "removing peer from the outgoing connections");
             if add_to_blocklist && !self.known_addresses.contains(&outgoing.peer_address) {
                 info!(our_id=%self.our_id, %peer_id, "blocklisting peer");
-                self.blocklist
-                    .insert(outgoing.peer_address, Timestamp::now());
+                let expiry = Timestamp::now() + self.cfg.blocklist_retain_duration;
+                if self.blocklist.ban(BanTarget::Peer(*peer_id), expiry) {
+                    effects.extend(self.persist_blocklist(effect_builder));
+                }
             }
         }
 
-        self.reconnect_if_not_connected_to_any_known_addresses(effect_builder)
+        effects.extend(self.reconnect_if_not_connected_to_any_known_addresses(effect_builder));
+        effects
+    }
+
+    /// Returns whether the peer or the address it connects from is banned.
+    fn is_banned(&self, peer_id: &NodeId, peer_address: &SocketAddr) -> bool {
+        let now = Timestamp::now();
+        self.blocklist.is_banned(&BanTarget::Peer(*peer_id), now)
+            || self
+                .blocklist
+                .is_banned(&BanTarget::Address(peer_address.ip()), now)
+    }
+
+    /// Bans `target` for `duration` and closes all connections to it.
+    fn ban(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        target: BanTarget<NodeId>,
+        duration: TimeDiff,
+    ) -> Effects<Event<P>> {
+        info!(our_id=%self.our_id, %target, %duration, "banning");
+        let mut effects = Effects::new();
+        if self.blocklist.ban(target, Timestamp::now() + duration) {
+            effects.extend(self.persist_blocklist(effect_builder));
+        }
+        effects.extend(self.disconnect_banned_peers(effect_builder));
+        effects
+    }
+
+    /// Closes all connections to peers which are banned.
+    fn disconnect_banned_peers(&mut self, effect_builder: EffectBuilder<REv>) -> Effects<Event<P>> {
+        let banned_peers: Vec<NodeId> = self
+            .incoming
+            .iter()
+            .map(|(peer_id, connection)| (peer_id, &connection.peer_address))
+            .chain(
+                self.outgoing
+                    .iter()
+                    .map(|(peer_id, connection)| (peer_id, &connection.peer_address)),
+            )
+            .filter(|(peer_id, peer_address)| self.is_banned(peer_id, peer_address))
+            .map(|(peer_id, _)| *peer_id)
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+
+        let mut effects = Effects::new();
+        for peer_id in banned_peers {
+            info!(our_id=%self.our_id, %peer_id, "dropping connection to banned peer");
+            effects.extend(self.remove(effect_builder, &peer_id, false));
+        }
+        self.update_peers_metric();
+        effects
+    }
+
+    /// Removes expired bans, persisting the blocklist if it changed.
+    fn purge_expired_bans(&mut self, effect_builder: EffectBuilder<REv>) -> Effects<Event<P>> {
+        if self.blocklist.purge_expired(Timestamp::now()) {
+            self.persist_blocklist(effect_builder)
+        } else {
+            Effects::new()
+        }
+    }
+
+    /// Updates the ban metric and saves the blocklist to the state store.
+    fn persist_blocklist(&self, effect_builder: EffectBuilder<REv>) -> Effects<Event<P>> {
+        self.net_metrics
+            .active_bans
+            .set(self.blocklist.len() as i64);
+        effect_builder
+            .save_state(blocklist::state_key(), self.blocklist.clone())
+            .ignore()
+    }
+
+    /// Merges the bans loaded from the state store into the current blocklist.
+    fn handle_blocklist_loaded(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        maybe_blocklist: Option<Blocklist>,
+    ) -> Effects<Event<P>> {
+        if let Some(blocklist) = maybe_blocklist {
+            self.blocklist.merge(blocklist);
+        }
+        let _ = self.blocklist.purge_expired(Timestamp::now());
+        debug!(our_id=%self.our_id, bans=self.blocklist.len(), "restored blocklist");
+        let mut effects = self.persist_blocklist(effect_builder);
+        effects.extend(self.disconnect_banned_peers(effect_builder));
+        effects
     }
 
     /// Gossips our public listening address, and schedules the next such gossip round.
@@ -823,8 +1067,14 @@ where
                 network_name,
                 public_address,
                 protocol_version,
+                consensus_certificate,
+                chainspec_hash,
+                accepts_compression: _,
             } => {
-                if network_name != self.chain_info.network_name {
+                if let Some(reason) =
+                    self.chain_info
+                        .incompatibility(&network_name, protocol_version, chainspec_hash)
+                {
                     info!(
                         our_id=%self.our_id,
                         %peer_id,
@@ -832,7 +1082,41 @@ where
                         their_network=?network_name,
                         our_protocol_version=%self.chain_info.protocol_version,
                         their_protocol_version=%protocol_version,
-                        "dropping connection due to network name mismatch"
+                        our_chainspec_hash=%self.chain_info.chainspec_hash,
+                        their_chainspec_hash=?chainspec_hash,
+                        "dropping connection due to {}",
+                        reason
+                    );
+                    let remove = self.remove(effect_builder, &peer_id, false);
+                    self.update_peers_metric();
+                    return remove;
+                }
+
+                if let Some(certificate) = consensus_certificate {
+                    match certificate.validate(peer_id) {
+                        Ok(public_key) => {
+                            let _ = self.peer_consensus_keys.insert(peer_id, public_key);
+                        }
+                        Err(error) => {
+                            warn!(
+                                our_id=%self.our_id,
+                                %peer_id,
+                                %error,
+                                "dropping connection due to invalid consensus certificate"
+                            );
+                            let remove = self.remove(effect_builder, &peer_id, true);
+                            self.update_peers_metric();
+                            return remove;
+                        }
+                    }
+                }
+
+                if self.incoming.contains_key(&peer_id) && !self.has_incoming_slot(&peer_id) {
+                    info!(
+                        our_id=%self.our_id,
+                        %peer_id,
+                        incoming=self.incoming.len(),
+                        "dropping connection due to incoming connection limit"
                     );
                     let remove = self.remove(effect_builder, &peer_id, false);
                     self.update_peers_metric();
@@ -851,16 +1135,60 @@ where
         }
     }
 
+    /// Returns whether the peer proved to be a validator in one of the most recent eras.
+    fn is_preferred_peer(&self, peer_id: &NodeId) -> bool {
+        self.peer_consensus_keys
+            .get(peer_id)
+            .map_or(false, |public_key| {
+                self.era_validators
+                    .values()
+                    .any(|validators| validators.contains_key(public_key))
+            })
+    }
+
+    /// Returns whether the incoming connection from `peer_id` is within the limit on incoming
+    /// connections. Validators may use the reserved slots, other peers may not.
+    fn has_incoming_slot(&self, peer_id: &NodeId) -> bool {
+        let max_incoming = self.cfg.max_incoming_peer_connections as usize;
+        if max_incoming == 0 {
+            return true;
+        }
+        let limit = if self.is_preferred_peer(peer_id) {
+            max_incoming
+        } else {
+            max_incoming.saturating_sub(self.cfg.validator_reserved_connections as usize)
+        };
+        self.incoming.len() <= limit
+    }
+
+    /// Records the validators of a new era, keeping only the most recent eras.
+    fn handle_era_validators_changed(
+        &mut self,
+        era_id: EraId,
+        validators: BTreeMap<PublicKey, U512>,
+    ) {
+        let _ = self.era_validators.insert(era_id, validators);
+        while self.era_validators.len() > PREFERRED_PEER_ERAS {
+            if let Some(oldest_era_id) = self.era_validators.keys().next().copied() {
+                let _ = self.era_validators.remove(&oldest_era_id);
+            }
+        }
+        debug!(
+            our_id=%self.our_id,
+            eras=?self.era_validators.keys().collect::<Vec<_>>(),
+            "updated preferred peers"
+        );
+    }
+
     fn update_peers_metric(&mut self) {
         self.net_metrics.peers.set(self.peers().len() as i64);
     }
 
     fn connect_to_peer_if_required(&mut self, peer_address: SocketAddr) -> Effects<Event<P>> {
-        let now = Timestamp::now();
-        self.blocklist
-            .retain(|_, ts| *ts > now.saturating_sub(*BLOCKLIST_RETAIN_DURATION));
         if self.pending.contains_key(&peer_address)
-            || self.blocklist.contains_key(&peer_address)
+            || self
+                .blocklist
+                .is_banned(&BanTarget::Address(peer_address.ip()), Timestamp::now())
             || self
                 .outgoing
                 .iter()
@@ -939,6 +1267,25 @@ where
         ret
     }
 
+    /// Returns diagnostic information about all incoming and outgoing connections.
+    fn connections(&self) -> Vec<ConnectionInfo<NodeId>> {
+        let incoming = self.incoming.iter().map(|(node_id, connection)| {
+            connection.stats.info(
+                *node_id,
+                connection.peer_address,
+                ConnectionDirection::Incoming,
+            )
+        });
+        let outgoing = self.outgoing.iter().map(|(node_id, connection)| {
+            connection.stats.info(
+                *node_id,
+                connection.peer_address,
+                ConnectionDirection::Outgoing,
+            )
+        });
+        incoming.chain(outgoing).collect()
+    }
+
     /// Returns whether or not this node has been disconnected from all known nodes.
     fn is_not_connected_to_any_known_address(&self) -> bool {
         for &known_address in &self.known_addresses {
@@ -1001,7 +1348,10 @@ where
 
 impl<REv, P> Component<REv> for SmallNetwork<REv, P>
 where
-    REv: ReactorEvent + From<Event<P>> + From<NetworkAnnouncement<NodeId, P>>,
+    REv: ReactorEvent
+        + From<Event<P>>
+        + From<NetworkAnnouncement<NodeId, P>>
+        + From<StateStoreRequest>,
     P: Payload,
 {
     type Event = Event<P>;
@@ -1027,6 +1377,14 @@ where
                 stream,
                 peer_address,
             } => {
+                if self
+                    .blocklist
+                    .is_banned(&BanTarget::Address(peer_address.ip()), Timestamp::now())
+                {
+                    debug!(our_id=%self.our_id, %peer_address, "dropping incoming connection from banned address");
+                    return Effects::new();
+                }
+
                 debug!(our_id=%self.our_id, %peer_address, "incoming connection, starting TLS handshake");
 
                 setup_tls(stream, self.certificate.clone(), self.secret_key.clone())
@@ -1098,16 +1456,54 @@ where
                             self.gossip_message(rng, Message::Payload(*payload), count, exclude);
                         responder.respond(sent_to).ignore()
                     }
+                    NetworkRequest::ValidatorGossip {
+                        payload,
+                        era_id,
+                        count,
+                        responder,
+                    } => {
+                        let sent_to = self.gossip_message_to_validators(
+                            rng,
+                            Message::Payload(*payload),
+                            era_id,
+                            count,
+                        );
+                        responder.respond(sent_to).ignore()
+                    }
                 }
             }
             Event::NetworkInfoRequest { req } => match *req {
                 NetworkInfoRequest::GetPeers { responder } => {
                     responder.respond(self.peers()).ignore()
                 }
+                NetworkInfoRequest::GetPeerByPublicKey {
+                    public_key,
+                    responder,
+                } => {
+                    let peer = self
+                        .peer_consensus_keys
+                        .iter()
+                        .find(|(_, peer_key)| **peer_key == *public_key)
+                        .map(|(peer_id, _)| *peer_id);
+                    responder.respond(peer).ignore()
+                }
+                NetworkInfoRequest::Ban {
+                    target,
+                    duration,
+                    responder,
+                } => {
+                    let mut effects = self.ban(effect_builder, target, duration);
+                    effects.extend(responder.respond(()).ignore());
+                    effects
+                }
+                NetworkInfoRequest::GetConnections { responder } => {
+                    responder.respond(self.connections()).ignore()
+                }
             },
             Event::GossipOurAddress => {
                 let mut effects = self.gossip_our_address(effect_builder);
                 effects.extend(self.enforce_symmetric_connections(effect_builder));
+                effects.extend(self.purge_expired_bans(effect_builder));
                 effects
             }
             Event::PeerAddressReceived(gossiped_address) => {
@@ -1117,6 +1513,13 @@ where
                 warn!(%peer_id, "adding peer to blocklist after transgression");
                 self.remove(effect_builder, peer_id, true)
             }
+            Event::EraValidatorsChanged { era_id, validators } => {
+                self.handle_era_validators_changed(era_id, validators);
+                Effects::new()
+            }
+            Event::BlocklistLoaded(maybe_blocklist) => {
+                self.handle_blocklist_loaded(effect_builder, maybe_blocklist)
+            }
         }
     }
 }
@@ -1378,7 +1781,7 @@ type Transport = SslStream<TcpStream>;
 type FramedTransport<P> = SymmetricallyFramed<
     Framed<Transport, LengthDelimitedCodec>,
     Message<P>,
-    CountingFormat<MessagePackFormat>,
+    CountingFormat<CompressionFormat<MessagePackFormat>>,
 >;
 
 /// Constructs a new framed transport on a stream.
@@ -1387,7 +1790,9 @@ fn framed<P>(
     connection_id: ConnectionId,
     stream: Transport,
     role: Role,
+    stats: Arc<ConnectionStats>,
     maximum_net_message_size: u32,
+    compression_threshold: u32,
 ) -> FramedTransport<P>
 where
     for<'de> P: Serialize + Deserialize<'de>,
@@ -1400,9 +1805,21 @@ where
             .new_codec(),
     );
 
+    let compression_threshold = if compression_threshold == 0 {
+        None
+    } else {
+        Some(compression_threshold as usize)
+    };
+    let compression_format = CompressionFormat::new(
+        metrics.clone(),
+        compression_threshold,
+        maximum_net_message_size as usize,
+        MessagePackFormat,
+    );
+
     SymmetricallyFramed::new(
         length_delimited,
-        CountingFormat::new(metrics, connection_id, role, MessagePackFormat),
+        CountingFormat::new(metrics, connection_id, role, stats, compression_format),
     )
 }
 
@@ -0,0 +1,146 @@
+//! Bans on peers and addresses.
+//!
+//! A ban prevents any connection to or from the banned peer or address until it expires. The
+//! blocklist is persisted in the state store so that bans survive restarts of the node.
+
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+    net::IpAddr,
+};
+
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{NodeId, Timestamp};
+
+/// The key under which the blocklist is persisted in the state store.
+const BLOCKLIST_STATE_KEY: &[u8] = b"small_network_blocklist";
+
+/// Returns the key under which the blocklist is persisted in the state store.
+pub(super) fn state_key() -> Cow<'static, [u8]> {
+    Cow::Borrowed(BLOCKLIST_STATE_KEY)
+}
+
+/// A peer or address which can be banned.
+#[derive(
+    Clone, Copy, DataSize, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize,
+)]
+pub enum BanTarget<I> {
+    /// A peer, identified by its node ID.
+    Peer(I),
+    /// All peers connecting from or listening on the given IP address.
+    Address(IpAddr),
+}
+
+impl<I: Display> Display for BanTarget<I> {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            BanTarget::Peer(peer_id) => write!(formatter, "peer {}", peer_id),
+            BanTarget::Address(address) => write!(formatter, "address {}", address),
+        }
+    }
+}
+
+/// The set of active bans, each with the time it expires.
+#[derive(Clone, DataSize, Debug, Default, Deserialize, Serialize)]
+pub struct Blocklist {
+    bans: BTreeMap<BanTarget<NodeId>, Timestamp>,
+}
+
+impl Blocklist {
+    /// Bans `target` until `expiry`. An existing ban is only ever extended, never shortened.
+    ///
+    /// Returns `true` if the blocklist changed.
+    pub(super) fn ban(&mut self, target: BanTarget<NodeId>, expiry: Timestamp) -> bool {
+        let current_expiry = self.bans.entry(target).or_insert_with(Timestamp::zero);
+        if *current_expiry >= expiry {
+            return false;
+        }
+        *current_expiry = expiry;
+        true
+    }
+
+    /// Adds all bans of `other`, e.g. after loading them from storage.
+    pub(super) fn merge(&mut self, other: Blocklist) {
+        for (target, expiry) in other.bans {
+            let _ = self.ban(target, expiry);
+        }
+    }
+
+    /// Returns whether `target` is banned at time `now`.
+    pub(super) fn is_banned(&self, target: &BanTarget<NodeId>, now: Timestamp) -> bool {
+        self.bans.get(target).map_or(false, |expiry| *expiry > now)
+    }
+
+    /// Removes all bans that have expired at time `now`.
+    ///
+    /// Returns `true` if the blocklist changed.
+    pub(super) fn purge_expired(&mut self, now: Timestamp) -> bool {
+        let len_before = self.bans.len();
+        self.bans.retain(|_, expiry| *expiry > now);
+        self.bans.len() != len_before
+    }
+
+    /// Returns the number of bans, including the ones that have expired but not been purged yet.
+    pub(super) fn len(&self) -> usize {
+        self.bans.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+    use crate::{testing::TestRng, types::TimeDiff};
+
+    #[test]
+    fn should_expire_bans() {
+        let mut rng = TestRng::new();
+        let mut blocklist = Blocklist::default();
+        let now = Timestamp::now();
+        let peer = BanTarget::Peer(NodeId::random_tls(&mut rng));
+        let address = BanTarget::Address(Ipv4Addr::new(10, 0, 0, 1).into());
+
+        assert!(blocklist.ban(peer, now + TimeDiff::from_seconds(10)));
+        assert!(blocklist.ban(address, now + TimeDiff::from_seconds(20)));
+        assert!(blocklist.is_banned(&peer, now));
+        assert!(blocklist.is_banned(&address, now));
+
+        let later = now + TimeDiff::from_seconds(15);
+        assert!(!blocklist.is_banned(&peer, later));
+        assert!(blocklist.is_banned(&address, later));
+
+        assert!(blocklist.purge_expired(later));
+        assert_eq!(blocklist.len(), 1);
+        assert!(!blocklist.purge_expired(later));
+    }
+
+    #[test]
+    fn should_never_shorten_bans() {
+        let mut rng = TestRng::new();
+        let mut blocklist = Blocklist::default();
+        let now = Timestamp::now();
+        let peer = BanTarget::Peer(NodeId::random_tls(&mut rng));
+
+        assert!(blocklist.ban(peer, now + TimeDiff::from_seconds(20)));
+        assert!(!blocklist.ban(peer, now + TimeDiff::from_seconds(10)));
+        assert!(blocklist.is_banned(&peer, now + TimeDiff::from_seconds(15)));
+    }
+
+    #[test]
+    fn should_roundtrip_through_bincode() {
+        let mut rng = TestRng::new();
+        let mut blocklist = Blocklist::default();
+        let now = Timestamp::now();
+        let peer = BanTarget::Peer(NodeId::random(&mut rng));
+        let _ = blocklist.ban(peer, now + TimeDiff::from_seconds(10));
+
+        let serialized = bincode::serialize(&blocklist).unwrap();
+        let mut loaded = Blocklist::default();
+        loaded.merge(bincode::deserialize(&serialized).unwrap());
+        assert!(loaded.is_banned(&peer, now));
+    }
+}
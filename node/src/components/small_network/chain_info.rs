@@ -8,8 +8,8 @@ use std::net::SocketAddr;
 use casper_types::ProtocolVersion;
 use datasize::DataSize;
 
-use super::Message;
-use crate::types::Chainspec;
+use super::{message::ConsensusCertificate, Message};
+use crate::{crypto::hash::Digest, types::Chainspec};
 
 /// Data retained from the chainspec by the small networking component.
 ///
@@ -23,6 +23,9 @@ pub(crate) struct ChainInfo {
     pub(super) maximum_net_message_size: u32,
     /// The protocol version.
     pub(super) protocol_version: ProtocolVersion,
+    /// The hash of the chainspec. We only remain connected to peers with the same chainspec hash
+    /// as us, if they advertise one.
+    pub(super) chainspec_hash: Digest,
 }
 
 impl ChainInfo {
@@ -33,15 +36,43 @@ impl ChainInfo {
             network_name: "rust-tests-network".to_string(),
             maximum_net_message_size: 22 * 1024 * 1024, // Hardcoded at 22M.
             protocol_version: ProtocolVersion::V1_0_0,
+            chainspec_hash: Digest::default(),
         }
     }
 
     /// Create a handshake based on chain identification data.
-    pub(super) fn create_handshake<P>(&self, public_address: SocketAddr) -> Message<P> {
+    pub(super) fn create_handshake<P>(
+        &self,
+        public_address: SocketAddr,
+        consensus_certificate: Option<ConsensusCertificate>,
+    ) -> Message<P> {
         Message::Handshake {
             network_name: self.network_name.clone(),
             public_address,
             protocol_version: self.protocol_version,
+            consensus_certificate,
+            chainspec_hash: Some(self.chainspec_hash),
+            // We can always decompress messages, whether or not we compress our own.
+            accepts_compression: true,
+        }
+    }
+
+    /// Returns the reason why a peer sending a handshake with the given data is incompatible with
+    /// us, or `None` if it is compatible.
+    pub(super) fn incompatibility(
+        &self,
+        network_name: &str,
+        protocol_version: ProtocolVersion,
+        chainspec_hash: Option<Digest>,
+    ) -> Option<&'static str> {
+        if network_name != self.network_name {
+            Some("network name mismatch")
+        } else if protocol_version != self.protocol_version {
+            Some("protocol version mismatch")
+        } else if chainspec_hash.map_or(false, |hash| hash != self.chainspec_hash) {
+            Some("chainspec hash mismatch")
+        } else {
+            None
         }
     }
 }
@@ -52,6 +83,35 @@ impl From<&Chainspec> for ChainInfo {
             network_name: chainspec.network_config.name.clone(),
             maximum_net_message_size: chainspec.network_config.maximum_net_message_size,
             protocol_version: chainspec.protocol_version(),
+            chainspec_hash: chainspec.hash(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use casper_types::ProtocolVersion;
+
+    use super::ChainInfo;
+    use crate::crypto::hash;
+
+    #[test]
+    fn should_detect_incompatible_peers() {
+        let chain_info = ChainInfo::create_for_testing();
+        let name = chain_info.network_name.clone();
+        let version = chain_info.protocol_version;
+        let hash = chain_info.chainspec_hash;
+
+        assert_eq!(chain_info.incompatibility(&name, version, Some(hash)), None);
+        assert_eq!(chain_info.incompatibility(&name, version, None), None);
+        assert!(chain_info
+            .incompatibility("other-network", version, Some(hash))
+            .is_some());
+        assert!(chain_info
+            .incompatibility(&name, ProtocolVersion::from_parts(1, 1, 0), Some(hash))
+            .is_some());
+        assert!(chain_info
+            .incompatibility(&name, version, Some(hash::hash(b"other chainspec")))
+            .is_some());
+    }
+}
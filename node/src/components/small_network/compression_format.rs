@@ -0,0 +1,246 @@
+//! Transparent compression of large network messages.
+//!
+//! Serialized payloads larger than a configurable threshold are deflate-compressed before being
+//! sent, provided the peer announced in its handshake that it accepts compressed messages.
+//! Compressed frames are prefixed with a marker byte which MessagePack never uses, so they can be
+//! told apart from uncompressed ones without any further negotiation.
+
+use std::{
+    io::{self, Read, Write},
+    pin::Pin,
+    sync::Weak,
+    time::Instant,
+};
+
+use bytes::{Bytes, BytesMut};
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
+use pin_project::pin_project;
+use tokio_serde::{Deserializer, Serializer};
+
+use super::Message;
+use crate::components::networking_metrics::NetworkingMetrics;
+
+/// Marker byte prefixed to compressed frames.
+///
+/// `0xc1` is reserved by the MessagePack specification and never appears as the first byte of a
+/// serialized message.
+const COMPRESSED_MARKER: u8 = 0xc1;
+
+/// A compressing serializer/deserializer wrapper for network messages.
+#[pin_project]
+#[derive(Debug)]
+pub(super) struct CompressionFormat<F> {
+    /// The actual serializer performing the work.
+    #[pin]
+    inner: F,
+    /// The size in bytes above which serialized payloads are compressed, or `None` if compression
+    /// is disabled.
+    threshold: Option<usize>,
+    /// The maximum size of a decompressed message.
+    max_decompressed_size: usize,
+    /// Whether the peer announced in its handshake that it accepts compressed messages.
+    peer_accepts_compression: bool,
+    /// Metrics to update.
+    metrics: Weak<NetworkingMetrics>,
+}
+
+impl<F> CompressionFormat<F> {
+    /// Creates a new compression formatter.
+    pub(super) fn new(
+        metrics: Weak<NetworkingMetrics>,
+        threshold: Option<usize>,
+        max_decompressed_size: usize,
+        inner: F,
+    ) -> Self {
+        CompressionFormat {
+            inner,
+            threshold,
+            max_decompressed_size,
+            peer_accepts_compression: false,
+            metrics,
+        }
+    }
+}
+
+impl<F, P> Serializer<Message<P>> for CompressionFormat<F>
+where
+    F: Serializer<Message<P>, Error = io::Error>,
+{
+    type Error = io::Error;
+
+    fn serialize(self: Pin<&mut Self>, item: &Message<P>) -> Result<Bytes, Self::Error> {
+        let this = self.project();
+        let serialized = F::serialize(this.inner, item)?;
+
+        // Handshakes are never compressed, as the peer's support is not known before receiving its
+        // own handshake.
+        let exceeds_threshold = this
+            .threshold
+            .map_or(false, |threshold| serialized.len() > threshold);
+        if !*this.peer_accepts_compression
+            || !exceeds_threshold
+            || !matches!(item, Message::Payload(_))
+        {
+            return Ok(serialized);
+        }
+
+        let start = Instant::now();
+        let compressed = compress(&serialized)?;
+        NetworkingMetrics::record_compression(
+            this.metrics,
+            serialized.len() as u64,
+            compressed.len() as u64,
+            start.elapsed(),
+        );
+        if compressed.len() < serialized.len() {
+            Ok(compressed.into())
+        } else {
+            Ok(serialized)
+        }
+    }
+}
+
+impl<F, P> Deserializer<Message<P>> for CompressionFormat<F>
+where
+    F: Deserializer<Message<P>, Error = io::Error>,
+{
+    type Error = io::Error;
+
+    fn deserialize(self: Pin<&mut Self>, src: &BytesMut) -> Result<Message<P>, Self::Error> {
+        let this = self.project();
+        let message = match src.split_first() {
+            Some((&COMPRESSED_MARKER, compressed)) => {
+                let start = Instant::now();
+                let decompressed = decompress(compressed, *this.max_decompressed_size)?;
+                NetworkingMetrics::record_decompression(this.metrics, start.elapsed());
+                F::deserialize(this.inner, &BytesMut::from(decompressed.as_slice()))?
+            }
+            _ => F::deserialize(this.inner, src)?,
+        };
+
+        if let Message::Handshake {
+            accepts_compression: true,
+            ..
+        } = &message
+        {
+            *this.peer_accepts_compression = true;
+        }
+        Ok(message)
+    }
+}
+
+/// Compresses the given serialized message into a marked frame.
+fn compress(serialized: &[u8]) -> io::Result<Vec<u8>> {
+    let mut buffer = Vec::with_capacity(serialized.len() / 2);
+    buffer.push(COMPRESSED_MARKER);
+    // Fast compression keeps the CPU cost low, while still shrinking large payloads considerably.
+    let mut encoder = DeflateEncoder::new(buffer, Compression::fast());
+    encoder.write_all(serialized)?;
+    encoder.finish()
+}
+
+/// Decompresses the given frame, without its marker, failing if the result would exceed
+/// `max_size` bytes.
+fn decompress(compressed: &[u8], max_size: usize) -> io::Result<Vec<u8>> {
+    let mut decompressed = Vec::new();
+    DeflateDecoder::new(compressed)
+        .take(max_size as u64 + 1)
+        .read_to_end(&mut decompressed)?;
+    if decompressed.len() > max_size {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "decompressed message exceeds maximum message size",
+        ));
+    }
+    Ok(decompressed)
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::ProtocolVersion;
+
+    use super::*;
+    use crate::components::small_network::message_pack_format::MessagePackFormat;
+
+    const MAX_SIZE: usize = 1024 * 1024;
+
+    fn format(threshold: Option<usize>) -> CompressionFormat<MessagePackFormat> {
+        CompressionFormat::new(Weak::new(), threshold, MAX_SIZE, MessagePackFormat)
+    }
+
+    fn handshake(accepts_compression: bool) -> Message<String> {
+        Message::Handshake {
+            network_name: "compression-test".to_string(),
+            public_address: ([12, 34, 56, 78], 12346).into(),
+            protocol_version: ProtocolVersion::V1_0_0,
+            consensus_certificate: None,
+            chainspec_hash: None,
+            accepts_compression,
+        }
+    }
+
+    fn large_payload() -> Message<String> {
+        Message::Payload("casper".repeat(1000))
+    }
+
+    #[test]
+    fn should_compress_only_after_peer_accepted_compression() {
+        let mut sender = format(Some(100));
+        let mut receiver = format(Some(100));
+        let payload = large_payload();
+
+        let uncompressed = Pin::new(&mut sender).serialize(&payload).unwrap();
+        assert_ne!(uncompressed[0], COMPRESSED_MARKER);
+
+        let their_handshake = Pin::new(&mut receiver).serialize(&handshake(true)).unwrap();
+        let _ = Pin::new(&mut sender)
+            .deserialize(&BytesMut::from(&their_handshake[..]))
+            .unwrap();
+
+        let compressed = Pin::new(&mut sender).serialize(&payload).unwrap();
+        assert_eq!(compressed[0], COMPRESSED_MARKER);
+        assert!(compressed.len() < uncompressed.len());
+
+        match Pin::new(&mut receiver)
+            .deserialize(&BytesMut::from(&compressed[..]))
+            .unwrap()
+        {
+            Message::Payload(decoded) => assert_eq!(decoded, "casper".repeat(1000)),
+            Message::Handshake { .. } => panic!("expected payload"),
+        }
+    }
+
+    #[test]
+    fn should_not_compress_for_legacy_peers_or_small_messages() {
+        let mut sender = format(Some(100));
+        let their_handshake = Pin::new(&mut format(None))
+            .serialize(&handshake(false))
+            .unwrap();
+        let _ = Pin::new(&mut sender)
+            .deserialize(&BytesMut::from(&their_handshake[..]))
+            .unwrap();
+        let serialized = Pin::new(&mut sender).serialize(&large_payload()).unwrap();
+        assert_ne!(serialized[0], COMPRESSED_MARKER);
+
+        let mut sender = format(Some(100_000));
+        let their_handshake = Pin::new(&mut format(None))
+            .serialize(&handshake(true))
+            .unwrap();
+        let _ = Pin::new(&mut sender)
+            .deserialize(&BytesMut::from(&their_handshake[..]))
+            .unwrap();
+        let serialized = Pin::new(&mut sender).serialize(&large_payload()).unwrap();
+        assert_ne!(serialized[0], COMPRESSED_MARKER);
+    }
+
+    #[test]
+    fn should_reject_oversized_decompressed_messages() {
+        let compressed = compress(&vec![0; MAX_SIZE + 1]).unwrap();
+        assert!(decompress(&compressed[1..], MAX_SIZE).is_err());
+        let compressed = compress(&vec![0; MAX_SIZE]).unwrap();
+        assert_eq!(
+            decompress(&compressed[1..], MAX_SIZE).unwrap().len(),
+            MAX_SIZE
+        );
+    }
+}
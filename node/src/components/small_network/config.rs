@@ -20,6 +20,9 @@ const DEFAULT_PUBLIC_ADDRESS: &str = "127.0.0.1:0";
 /// Default interval for gossiping network addresses.
 const DEFAULT_GOSSIP_INTERVAL: Duration = Duration::from_secs(30);
 
+/// Default size in bytes above which outgoing messages are compressed.
+const DEFAULT_COMPRESSION_THRESHOLD: u32 = 4 * 1024;
+
 // Default values for networking configuration:
 impl Default for Config {
     fn default() -> Self {
@@ -32,6 +35,11 @@ impl Default for Config {
             isolation_reconnect_delay: TimeDiff::from_seconds(2),
             initial_gossip_delay: TimeDiff::from_seconds(5),
             max_addr_pending_time: TimeDiff::from_seconds(60),
+            max_incoming_peer_connections: 0,
+            validator_reserved_connections: 0,
+            send_consensus_certificate: true,
+            blocklist_retain_duration: TimeDiff::from_seconds(600),
+            compression_threshold: DEFAULT_COMPRESSION_THRESHOLD,
         }
     }
 }
@@ -60,6 +68,27 @@ pub struct Config {
     pub initial_gossip_delay: TimeDiff,
     /// Maximum allowed time for an address to be kept in the pending set.
     pub max_addr_pending_time: TimeDiff,
+    /// Maximum number of incoming connections from peers. `0` means unlimited.
+    pub max_incoming_peer_connections: u32,
+    /// Number of the incoming connection slots that are reserved for validators of the current
+    /// and upcoming era, i.e. not available to other peers.
+    pub validator_reserved_connections: u32,
+    /// Whether to prove to peers during the handshake that we hold our consensus key, so that
+    /// they can prioritize our connection while we are a validator.
+    ///
+    /// Disabling it keeps the association between our consensus key and our network address
+    /// private, at the cost of being treated like any other peer.
+    pub send_consensus_certificate: bool,
+    /// How long a peer stays banned after it was caught misbehaving.
+    pub blocklist_retain_duration: TimeDiff,
+    /// The size in bytes above which serialized messages are compressed before being sent to peers
+    /// accepting compressed messages.  `0` disables compression.
+    #[serde(default = "default_compression_threshold")]
+    pub compression_threshold: u32,
+}
+
+fn default_compression_threshold() -> u32 {
+    DEFAULT_COMPRESSION_THRESHOLD
 }
 
 #[cfg(test)]
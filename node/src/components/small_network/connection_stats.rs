@@ -0,0 +1,130 @@
+//! Traffic statistics of individual connections, to aid debugging connectivity issues.
+
+use std::{
+    net::SocketAddr,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use serde::Serialize;
+
+use crate::types::{TimeDiff, Timestamp};
+
+/// The direction of a connection: Incoming connections are only read from, outgoing ones are only
+/// written to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub enum ConnectionDirection {
+    /// A connection the peer established to us.
+    Incoming,
+    /// A connection we established to the peer.
+    Outgoing,
+}
+
+/// Diagnostic information about a connection to a peer.
+#[derive(Clone, Debug, Serialize)]
+pub struct ConnectionInfo<I> {
+    /// The peer's node ID.
+    pub peer_id: I,
+    /// The peer's address.
+    pub peer_address: SocketAddr,
+    /// The direction of the connection.
+    pub direction: ConnectionDirection,
+    /// The time since the connection was established.
+    pub age: TimeDiff,
+    /// The number of bytes received over the connection, including the handshake.
+    pub bytes_in: u64,
+    /// The number of bytes sent over the connection, including the handshake.
+    pub bytes_out: u64,
+    /// The time the last message was received, if any.
+    pub last_message_in: Option<Timestamp>,
+    /// The time the last message was sent, if any.
+    pub last_message_out: Option<Timestamp>,
+}
+
+/// Traffic statistics of a single connection, updated by its framed transport.
+#[derive(Debug)]
+pub(super) struct ConnectionStats {
+    /// The time the connection was established.
+    established: Timestamp,
+    /// The number of bytes received.
+    bytes_in: AtomicU64,
+    /// The number of bytes sent.
+    bytes_out: AtomicU64,
+    /// The time in milliseconds the last message was received, or zero if there was none.
+    last_message_in: AtomicU64,
+    /// The time in milliseconds the last message was sent, or zero if there was none.
+    last_message_out: AtomicU64,
+}
+
+impl ConnectionStats {
+    /// Creates the statistics of a connection established now.
+    pub(super) fn new() -> Self {
+        ConnectionStats {
+            established: Timestamp::now(),
+            bytes_in: AtomicU64::new(0),
+            bytes_out: AtomicU64::new(0),
+            last_message_in: AtomicU64::new(0),
+            last_message_out: AtomicU64::new(0),
+        }
+    }
+
+    /// Records a received message of the given size in bytes.
+    pub(super) fn record_in(&self, msg_size: u64) {
+        self.bytes_in.fetch_add(msg_size, Ordering::Relaxed);
+        self.last_message_in
+            .store(Timestamp::now().millis(), Ordering::Relaxed);
+    }
+
+    /// Records a sent message of the given size in bytes.
+    pub(super) fn record_out(&self, msg_size: u64) {
+        self.bytes_out.fetch_add(msg_size, Ordering::Relaxed);
+        self.last_message_out
+            .store(Timestamp::now().millis(), Ordering::Relaxed);
+    }
+
+    /// Returns the diagnostic information about the connection to the given peer.
+    pub(super) fn info<I>(
+        &self,
+        peer_id: I,
+        peer_address: SocketAddr,
+        direction: ConnectionDirection,
+    ) -> ConnectionInfo<I> {
+        let load_timestamp = |millis: &AtomicU64| match millis.load(Ordering::Relaxed) {
+            0 => None,
+            millis => Some(Timestamp::from(millis)),
+        };
+        ConnectionInfo {
+            peer_id,
+            peer_address,
+            direction,
+            age: self.established.elapsed(),
+            bytes_in: self.bytes_in.load(Ordering::Relaxed),
+            bytes_out: self.bytes_out.load(Ordering::Relaxed),
+            last_message_in: load_timestamp(&self.last_message_in),
+            last_message_out: load_timestamp(&self.last_message_out),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_record_traffic() {
+        let stats = ConnectionStats::new();
+        let address = ([12, 34, 56, 78], 12346).into();
+        let info = stats.info("peer", address, ConnectionDirection::Incoming);
+        assert_eq!(info.bytes_in, 0);
+        assert!(info.last_message_in.is_none());
+        assert!(info.last_message_out.is_none());
+
+        stats.record_in(100);
+        stats.record_in(20);
+        stats.record_out(7);
+        let info = stats.info("peer", address, ConnectionDirection::Incoming);
+        assert_eq!(info.bytes_in, 120);
+        assert_eq!(info.bytes_out, 7);
+        assert!(info.last_message_in.is_some());
+        assert!(info.last_message_out.is_some());
+    }
+}
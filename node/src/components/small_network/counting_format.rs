@@ -9,7 +9,7 @@ use std::{
     convert::TryFrom,
     fmt::{self, Display, Formatter},
     pin::Pin,
-    sync::Weak,
+    sync::{Arc, Weak},
 };
 
 use bytes::{Bytes, BytesMut};
@@ -22,7 +22,7 @@ use static_assertions::const_assert;
 use tokio_serde::{Deserializer, Serializer};
 use tracing::{error, trace, warn};
 
-use super::{tls::KeyFingerprint, Message, Payload};
+use super::{connection_stats::ConnectionStats, tls::KeyFingerprint, Message, Payload};
 #[cfg(test)]
 use crate::testing::TestRng;
 use crate::{
@@ -65,6 +65,8 @@ pub(super) struct CountingFormat<F> {
     role: Role,
     /// Metrics to update.
     metrics: Weak<NetworkingMetrics>,
+    /// Traffic statistics of the connection to update.
+    stats: Arc<ConnectionStats>,
 }
 
 impl<F> CountingFormat<F> {
@@ -74,10 +76,12 @@ impl<F> CountingFormat<F> {
         metrics: Weak<NetworkingMetrics>,
         connection_id: ConnectionId,
         role: Role,
+        stats: Arc<ConnectionStats>,
         inner: F,
     ) -> Self {
         Self {
             metrics,
+            stats,
             connection_id,
             out_count: 0,
             in_count: 0,
@@ -103,6 +107,7 @@ where
         let msg_size = serialized.len() as u64;
         let msg_kind = item.classify();
         NetworkingMetrics::record_payload_out(this.metrics, msg_kind, msg_size);
+        this.stats.record_out(msg_size);
 
         let trace_id = this
             .connection_id
@@ -134,6 +139,7 @@ where
 
         let deserialized = F::deserialize(projection, src)?;
         let msg_kind = deserialized.classify();
+        this.stats.record_in(msg_size);
 
         let trace_id = this
             .connection_id
@@ -1,4 +1,5 @@
 use std::{
+    collections::BTreeMap,
     fmt::{self, Debug, Display, Formatter},
     io, mem,
     net::SocketAddr,
@@ -9,7 +10,9 @@ use serde::Serialize;
 use static_assertions::const_assert;
 use tokio::net::TcpStream;
 
-use super::{Error, GossipedAddress, Message, NodeId, Transport};
+use casper_types::{EraId, PublicKey, U512};
+
+use super::{blocklist::Blocklist, Error, GossipedAddress, Message, NodeId, Transport};
 use crate::{
     effect::{
         announcements::BlocklistAnnouncement,
@@ -87,6 +90,15 @@ pub enum Event<P> {
     /// Blocklist announcement
     #[from]
     BlocklistAnnouncement(BlocklistAnnouncement<NodeId>),
+
+    /// The validators of a new era became known.
+    EraValidatorsChanged {
+        era_id: EraId,
+        validators: BTreeMap<PublicKey, U512>,
+    },
+
+    /// The blocklist persisted before the node was last stopped has been loaded.
+    BlocklistLoaded(Option<Blocklist>),
 }
 
 impl From<NetworkRequest<NodeId, ProtocolMessage>> for Event<ProtocolMessage> {
@@ -156,6 +168,13 @@ impl<P: Display> Display for Event<P> {
             Event::BlocklistAnnouncement(ann) => {
                 write!(f, "handling blocklist announcement: {}", ann)
             }
+            Event::EraValidatorsChanged { era_id, validators } => {
+                write!(f, "{} validators in {}", validators.len(), era_id)
+            }
+            Event::BlocklistLoaded(maybe_blocklist) => match maybe_blocklist {
+                Some(blocklist) => write!(f, "loaded blocklist with {} bans", blocklist.len()),
+                None => write!(f, "no blocklist found in storage"),
+            },
         }
     }
 }
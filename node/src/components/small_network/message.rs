@@ -3,10 +3,14 @@ use std::{
     net::SocketAddr,
 };
 
-use casper_types::ProtocolVersion;
+use casper_types::{ProtocolVersion, PublicKey, SecretKey, Signature};
 use serde::{Deserialize, Serialize};
 
 use super::{MessageKind, Payload};
+use crate::{
+    crypto::{self, hash::Digest},
+    types::NodeId,
+};
 
 /// The default protocol version to use in absence of one in the protocol version field.
 #[inline]
@@ -24,10 +28,57 @@ pub enum Message<P> {
         /// Protocol version the node is speaking.
         #[serde(default = "default_protocol_version")]
         protocol_version: ProtocolVersion,
+        /// A proof that the node connecting holds the secret key of a consensus public key.
+        #[serde(default)]
+        consensus_certificate: Option<ConsensusCertificate>,
+        /// Hash of the chainspec the node is running.
+        #[serde(default)]
+        chainspec_hash: Option<Digest>,
+        /// Whether the node connecting accepts compressed messages.
+        #[serde(default)]
+        accepts_compression: bool,
     },
+    /// A payload, exchanged only after both sides accepted the other's handshake. Its protocol
+    /// version is thus the one both nodes announced in their handshakes.
     Payload(P),
 }
 
+/// A signature of a node's ID with its consensus secret key.
+///
+/// Since the node ID is bound to the TLS connection, this proves to the peer that the other end of
+/// the connection holds the consensus key, e.g. that it is a validator.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub(crate) struct ConsensusCertificate {
+    public_key: PublicKey,
+    signature: Signature,
+}
+
+impl ConsensusCertificate {
+    /// Creates a certificate for the node with the ID `node_id`.
+    pub(super) fn create(node_id: NodeId, secret_key: &SecretKey) -> Self {
+        let public_key = PublicKey::from(secret_key);
+        let signature = crypto::sign(Self::signed_data(node_id), secret_key, &public_key);
+        ConsensusCertificate {
+            public_key,
+            signature,
+        }
+    }
+
+    /// Returns the certified public key, if the certificate was created for `node_id`.
+    pub(super) fn validate(self, node_id: NodeId) -> Result<PublicKey, crypto::Error> {
+        crypto::verify(
+            Self::signed_data(node_id),
+            &self.signature,
+            &self.public_key,
+        )?;
+        Ok(self.public_key)
+    }
+
+    fn signed_data(node_id: NodeId) -> Vec<u8> {
+        bincode::serialize(&node_id).expect("should serialize node ID")
+    }
+}
+
 impl<P: Payload> Message<P> {
     /// Classifies a message based on its payload.
     #[inline]
@@ -46,11 +97,26 @@ impl<P: Display> Display for Message<P> {
                 network_name,
                 public_address,
                 protocol_version,
-            } => write!(
-                f,
-                "handshake: {}, public addr: {}, protocol_version: {}",
-                network_name, public_address, protocol_version,
-            ),
+                consensus_certificate,
+                chainspec_hash,
+                accepts_compression,
+            } => {
+                write!(
+                    f,
+                    "handshake: {}, public addr: {}, protocol_version: {}",
+                    network_name, public_address, protocol_version,
+                )?;
+                if let Some(certificate) = consensus_certificate {
+                    write!(f, ", consensus key: {}", certificate.public_key)?;
+                }
+                if let Some(hash) = chainspec_hash {
+                    write!(f, ", chainspec hash: {}", hash)?;
+                }
+                if *accepts_compression {
+                    write!(f, ", accepts compression")?;
+                }
+                Ok(())
+            }
             Message::Payload(payload) => write!(f, "payload: {}", payload),
         }
     }
@@ -62,12 +128,16 @@ impl<P: Display> Display for Message<P> {
 mod tests {
     use std::net::SocketAddr;
 
-    use casper_types::ProtocolVersion;
+    use casper_types::{ProtocolVersion, PublicKey, SecretKey};
     use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
-    use crate::protocol;
+    use crate::{
+        crypto::{hash, AsymmetricKeyExt},
+        protocol,
+        types::NodeId,
+    };
 
-    use super::Message;
+    use super::{ConsensusCertificate, Message};
 
     /// Version 1.0.0 network level message.
     ///
@@ -171,6 +241,9 @@ mod tests {
             network_name: "example-handshake".to_string(),
             public_address: ([12, 34, 56, 78], 12346).into(),
             protocol_version: ProtocolVersion::from_parts(5, 6, 7),
+            consensus_certificate: None,
+            chainspec_hash: Some(hash::hash(b"example chainspec")),
+            accepts_compression: true,
         };
 
         let legacy_handshake: V1_0_0_Message = roundtrip_message(&modern_handshake);
@@ -203,7 +276,13 @@ mod tests {
                 network_name,
                 public_address,
                 protocol_version,
+                consensus_certificate,
+                chainspec_hash,
+                accepts_compression,
             } => {
+                assert!(consensus_certificate.is_none());
+                assert!(chainspec_hash.is_none());
+                assert!(!accepts_compression);
                 assert_eq!(network_name, "example-handshake");
                 assert_eq!(public_address, ([12, 34, 56, 78], 12346).into());
                 assert_eq!(protocol_version, ProtocolVersion::V1_0_0);
@@ -214,6 +293,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn consensus_certificate_is_bound_to_node_id() {
+        let mut rng = crate::new_rng();
+        let secret_key = SecretKey::random(&mut rng);
+        let node_id = NodeId::random_tls(&mut rng);
+        let other_node_id = NodeId::random_tls(&mut rng);
+
+        let certificate = ConsensusCertificate::create(node_id, &secret_key);
+        assert!(certificate.clone().validate(other_node_id).is_err());
+        assert_eq!(
+            certificate.validate(node_id).expect("should be valid"),
+            PublicKey::from(&secret_key)
+        );
+    }
+
     #[test]
     fn current_handshake_decodes_from_historic_v1_0_0() {
         let modern_handshake: Message<protocol::Message> = deserialize_message(V1_0_0_HANDSHAKE);
@@ -223,7 +317,13 @@ mod tests {
                 network_name,
                 public_address,
                 protocol_version,
+                consensus_certificate,
+                chainspec_hash,
+                accepts_compression,
             } => {
+                assert!(consensus_certificate.is_none());
+                assert!(chainspec_hash.is_none());
+                assert!(!accepts_compression);
                 assert_eq!(network_name, "serialization-test");
                 assert_eq!(public_address, ([12, 34, 56, 78], 12346).into());
                 assert_eq!(protocol_version, ProtocolVersion::V1_0_0);
@@ -30,8 +30,8 @@ use crate::{
     },
     effect::{
         announcements::{ControlAnnouncement, GossiperAnnouncement, NetworkAnnouncement},
-        requests::{NetworkRequest, StorageRequest},
-        EffectBuilder, Effects,
+        requests::{NetworkRequest, StateStoreRequest, StorageRequest},
+        EffectBuilder, EffectExt, Effects,
     },
     protocol,
     reactor::{self, EventQueueHandle, Finalize, Reactor, Runner},
@@ -60,6 +60,8 @@ enum Event {
     NetworkAnnouncement(#[serde(skip_serializing)] NetworkAnnouncement<NodeId, Message>),
     #[from]
     AddressGossiperAnnouncement(#[serde(skip_serializing)] GossiperAnnouncement<GossipedAddress>),
+    #[from]
+    StateStoreRequest(StateStoreRequest),
 }
 
 impl ReactorEvent for Event {
@@ -152,18 +154,29 @@ impl Reactor for TestReactor {
             registry,
             small_network_identity,
             ChainInfo::create_for_testing(),
+            None,
             false,
         )?;
         let gossiper_config = gossiper::Config::new_with_small_timeouts();
-        let address_gossiper =
-            Gossiper::new_for_complete_items("address_gossiper", gossiper_config, registry)?;
+        let (address_gossiper, address_gossiper_effects) = Gossiper::new_for_complete_items(
+            "address_gossiper",
+            gossiper_config,
+            EffectBuilder::new(event_queue),
+            registry,
+        )?;
+
+        let mut effects = reactor::wrap_effects(Event::SmallNet, effects);
+        effects.extend(reactor::wrap_effects(
+            Event::AddressGossiper,
+            address_gossiper_effects,
+        ));
 
         Ok((
             TestReactor {
                 net,
                 address_gossiper,
             },
-            reactor::wrap_effects(Event::SmallNet, effects),
+            effects,
         ))
     }
 
@@ -219,6 +232,13 @@ impl Reactor for TestReactor {
                     Event::SmallNet(SmallNetworkEvent::PeerAddressReceived(gossiped_address));
                 self.dispatch_event(effect_builder, rng, reactor_event)
             }
+            // The test nodes do not persist any state.
+            Event::StateStoreRequest(StateStoreRequest::Load { responder, .. }) => {
+                responder.respond(None).ignore()
+            }
+            Event::StateStoreRequest(StateStoreRequest::Save { responder, .. }) => {
+                responder.respond(()).ignore()
+            }
         }
     }
 
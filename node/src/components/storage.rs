@@ -26,43 +26,69 @@
 //! * Storing a deploy or block that already exists (same hash) is fine and will silently be
 //!   accepted.
 //!
+//! ## Read-ahead
+//!
+//! When blocks are requested by consecutive heights, e.g. by peers syncing the chain, the next few
+//! blocks are read in the same transaction and cached in memory.  The cache is discarded as soon
+//! as the access pattern stops being sequential.
+//!
 //! ## Indices
 //!
 //! The current implementation keeps only in-memory indices, which are not persisted, based upon the
 //! estimate that they are reasonably quick to rebuild on start-up and do not take up much memory.
 //!
+//! ## Backups
+//!
+//! The databases can be backed up and restored along with the global state using the `storage`
+//! subcommands of the node binary, see the `backup` module for details.
+//!
 //! ## Errors
 //!
 //! The storage component itself is panic free and in general reports three classes of errors:
 //! Corruption, temporary resource exhaustion and potential bugs.
+//!
+//! Transient errors when beginning a transaction, such as running out of reader slots, are retried
+//! a few times right away before being reported.  Requests whose responders carry a `Result` get
+//! any remaining error back, unless it indicates corruption, and can retry later.  All other
+//! errors are fatal, with corruption being reported as such since it requires the database to be
+//! cleared.
 
+mod backup;
 mod lmdb_ext;
+mod metrics;
+mod pending_deploys;
 #[cfg(test)]
 mod tests;
 
 #[cfg(test)]
 use std::{collections::BTreeSet, convert::TryFrom};
 use std::{
-    collections::{btree_map::Entry, BTreeMap, HashSet},
+    collections::{btree_map::Entry, BTreeMap, HashMap, HashSet},
     fmt::{self, Display, Formatter},
     fs, io, mem,
     path::PathBuf,
+    time::{Duration, Instant},
 };
 
 use datasize::DataSize;
 use derive_more::From;
 use lmdb::{
-    Cursor, Database, DatabaseFlags, Environment, EnvironmentFlags, Transaction, WriteFlags,
+    Cursor, Database, DatabaseFlags, Environment, EnvironmentFlags, RwTransaction, Transaction,
+    WriteFlags,
 };
+use prometheus::Registry;
 use serde::{Deserialize, Serialize};
 use static_assertions::const_assert;
 #[cfg(test)]
 use tempfile::TempDir;
 use thiserror::Error;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 use casper_execution_engine::shared::newtypes::Blake2bHash;
-use casper_types::{EraId, ExecutionResult, ProtocolVersion, Transfer, Transform};
+use casper_types::{
+    account::AccountHash, ContractHash, EraId, ExecutionResult, JournalEntry, ProtocolVersion,
+    PublicKey, Transfer, Transform,
+};
 
 use super::Component;
 #[cfg(test)]
@@ -70,18 +96,23 @@ use crate::crypto::hash::Digest;
 use crate::{
     effect::{
         requests::{StateStoreRequest, StorageRequest},
-        EffectBuilder, EffectExt, Effects,
+        EffectBuilder, EffectExt, Effects, Responder,
     },
     fatal,
     reactor::ReactorEvent,
     types::{
-        Block, BlockBody, BlockHash, BlockHeader, BlockHeaderWithMetadata, BlockSignatures, Deploy,
-        DeployHash, DeployHeader, DeployMetadata, TimeDiff,
+        Block, BlockBody, BlockHash, BlockHeader, BlockHeaderWithMetadata, BlockIdentifier,
+        BlockSignatures, BlockWithMetadata, ContractEventRecord, Deploy, DeployHash, DeployHeader,
+        DeployMetadata, EraRewards, ExecutionResultSummary, LegacyBlockHeader, LegacyDeploy,
+        TimeDiff, Timestamp,
     },
     utils::WithDir,
     NodeRng,
 };
-use lmdb_ext::{LmdbExtError, TransactionExt, WriteTransactionExt};
+pub use backup::{backup, restore, BackupError, StorageSummary};
+use lmdb_ext::{EnvironmentExt, LmdbExtError, TransactionExt, WriteTransactionExt};
+use metrics::StorageMetrics;
+use pending_deploys::PendingDeployIndex;
 
 /// Filename for the LMDB database created by the Storage component.
 const STORAGE_DB_FILENAME: &str = "storage.lmdb";
@@ -101,8 +132,14 @@ const DEFAULT_MAX_DEPLOY_STORE_SIZE: usize = 300 * GIB;
 const DEFAULT_MAX_DEPLOY_METADATA_STORE_SIZE: usize = 300 * GIB;
 /// Default max state store size.
 const DEFAULT_MAX_STATE_STORE_SIZE: usize = 10 * GIB;
+/// Default max number of pending deploys per account.
+const DEFAULT_MAX_PENDING_DEPLOYS_PER_ACCOUNT: usize = 20;
+/// Default max total size of the pending deploys.
+const DEFAULT_MAX_PENDING_DEPLOYS_SIZE: usize = 64 * 1024 * 1024;
 /// Maximum number of allowed dbs.
-const MAX_DB_COUNT: u32 = 7;
+const MAX_DB_COUNT: u32 = 13;
+/// Minimum time between two updates of the database statistics metrics.
+const STATS_UPDATE_INTERVAL: Duration = Duration::from_secs(10);
 
 /// OS-specific lmdb flags.
 #[cfg(not(target_os = "macos"))]
@@ -163,9 +200,70 @@ pub enum Error {
         /// Second block hash encountered at `deploy_hash`.
         second: BlockHash,
     },
+    /// A stored block refers to a deploy which is not stored.
+    #[error("deploy {deploy_hash} of block {block_hash} is not stored")]
+    MissingDeploy {
+        /// The block referring to the deploy.
+        block_hash: BlockHash,
+        /// The missing deploy.
+        deploy_hash: DeployHash,
+    },
     /// LMDB error while operating.
     #[error("internal database error: {0}")]
     InternalStorage(#[from] LmdbExtError),
+    /// Failure to register metrics.
+    #[error("failed to register storage metrics: {0}")]
+    Metrics(#[from] prometheus::Error),
+}
+
+impl Error {
+    /// Returns `true` if the error indicates that the database is corrupted and needs to be
+    /// cleared, rather than the node merely needing a restart.
+    fn is_corruption(&self) -> bool {
+        match self {
+            Error::CreateDatabaseDirectory(..) | Error::Metrics(_) => false,
+            Error::DuplicateBlockIndex { .. }
+            | Error::DuplicateEraIdIndex { .. }
+            | Error::DuplicateDeployIndex { .. }
+            | Error::MissingDeploy { .. } => true,
+            Error::InternalStorage(error) => error.is_corruption(),
+        }
+    }
+}
+
+/// Passes the result of a request on to the requester, unless it is an error indicating corruption,
+/// which is returned instead so that it is treated as fatal.
+fn respond_unless_corrupted<T>(
+    responder: Responder<Result<T, Error>>,
+    result: Result<T, Error>,
+) -> Result<Effects<Event>, Error>
+where
+    T: Send + 'static,
+{
+    match result {
+        Err(error) if error.is_corruption() => Err(error),
+        result => Ok(responder.respond(result).ignore()),
+    }
+}
+
+/// Error returned when a pending deploy is rejected because a limit of the pending deploy store
+/// would be exceeded.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum PendingDeployStoreFull {
+    /// The account already has the maximum number of pending deploys.
+    #[error("account {account_hash} already has the maximum of {max} pending deploys")]
+    TooManyForAccount {
+        /// The account of the rejected deploy.
+        account_hash: AccountHash,
+        /// The maximum number of pending deploys per account.
+        max: usize,
+    },
+    /// The pending deploys would exceed the maximum total size.
+    #[error("pending deploys would exceed the maximum total size of {max_size} bytes")]
+    TooLarge {
+        /// The maximum total size of the pending deploys in bytes.
+        max_size: usize,
+    },
 }
 
 // We wholesale wrap lmdb errors and treat them as internal errors here.
@@ -203,12 +301,53 @@ pub struct Storage {
     /// The state storage database.
     #[data_size(skip)]
     state_store_db: Database,
+    /// The database of deploy hashes by the account which submitted them, in insertion order.
+    #[data_size(skip)]
+    deploys_by_account_db: Database,
+    /// The database of deploys which don't have enough approvals to be submitted yet.
+    #[data_size(skip)]
+    pending_deploy_db: Database,
+    /// An index of the pending deploys by expiry and account.
+    pending_deploy_index: PendingDeployIndex,
+    /// The maximum number of pending deploys per account.
+    max_pending_deploys_per_account: usize,
+    /// The maximum total serialized size of the pending deploys.
+    max_pending_deploys_size: usize,
+    /// The database of hashes of blocks whose execution results are stored, keyed by era.
+    #[data_size(skip)]
+    execution_results_era_db: Database,
+    /// The database of events emitted by contracts, keyed by contract hash and block height.
+    #[data_size(skip)]
+    contract_events_db: Database,
+    /// The database of rewards distributed at the end of each era, keyed by era.
+    #[data_size(skip)]
+    era_rewards_db: Database,
+    /// The database of journals of global state operations, keyed by deploy hash.  It is only
+    /// written to if the contract runtime records execution journals.
+    #[data_size(skip)]
+    execution_journal_db: Database,
+    /// The number of eras for which execution results are kept, or `None` to keep them forever.
+    execution_results_retention_eras: Option<u64>,
+    /// The number of blocks to read ahead when blocks are requested by consecutive heights.
+    block_read_ahead: u64,
+    /// The height of the most recently requested block.
+    last_requested_height: Option<u64>,
+    /// Blocks read ahead of sequential requests, by height.
+    read_ahead_cache: BTreeMap<u64, Block>,
     /// A map of block height to block ID.
     block_height_index: BTreeMap<u64, BlockHash>,
     /// A map of era ID to switch block ID.
     switch_block_era_id_index: BTreeMap<EraId, BlockHash>,
     /// A map of deploy hashes to hashes of blocks containing them.
     deploy_hash_index: BTreeMap<DeployHash, BlockHash>,
+    /// A map of era ID and proposer to the blocks proposed in that era, keyed by height.
+    proposer_index: BTreeMap<(EraId, PublicKey), BTreeMap<u64, BlockHash>>,
+    /// Metrics of the LMDB databases.
+    #[data_size(skip)]
+    metrics: StorageMetrics,
+    /// The time the database statistics metrics were last updated.
+    #[data_size(skip)]
+    last_stats_update: Instant,
 }
 
 impl<REv> Component<REv> for Storage
@@ -231,11 +370,21 @@ where
             }
         };
 
-        // Any error is turned into a fatal effect, the component itself does not panic. Note that
-        // we are dropping a lot of responders this way, but since we are crashing with fatal
-        // anyway, it should not matter.
+        if self.last_stats_update.elapsed() >= STATS_UPDATE_INTERVAL {
+            self.update_stats();
+        }
+
+        // Any error is turned into a fatal effect, the component itself does not panic. Transient
+        // errors have already been retried at this point. Note that we are dropping a lot of
+        // responders this way, but since we are crashing with fatal anyway, it should not matter.
         match result {
             Ok(effects) => effects,
+            Err(err) if err.is_corruption() => fatal!(
+                effect_builder,
+                "storage corrupted, the database needs to be cleared: {}",
+                err
+            )
+            .ignore(),
             Err(err) => fatal!(effect_builder, "storage error: {}", err).ignore(),
         }
     }
@@ -247,6 +396,7 @@ impl Storage {
         cfg: &WithDir<Config>,
         hard_reset_to_start_of_era: Option<EraId>,
         protocol_version: ProtocolVersion,
+        registry: &Registry,
     ) -> Result<Self, Error> {
         let config = cfg.value();
 
@@ -257,12 +407,6 @@ impl Storage {
                 .map_err(|err| Error::CreateDatabaseDirectory(root.clone(), err))?;
         }
 
-        // Calculate the upper bound for the memory map that is potentially used.
-        let total_size = config
-            .max_block_store_size
-            .saturating_add(config.max_deploy_store_size)
-            .saturating_add(config.max_deploy_metadata_store_size);
-
         // Creates the environment and databases.
         let env = Environment::new()
             .set_flags(
@@ -274,7 +418,7 @@ impl Storage {
             )
             .set_max_readers(MAX_TRANSACTIONS)
             .set_max_dbs(MAX_DB_COUNT)
-            .set_map_size(total_size)
+            .set_map_size(config.map_size())
             .open(&root.join(STORAGE_DB_FILENAME))?;
 
         let block_header_db = env.create_db(Some("block_header"), DatabaseFlags::empty())?;
@@ -284,12 +428,23 @@ impl Storage {
         let transfer_db = env.create_db(Some("transfer"), DatabaseFlags::empty())?;
         let state_store_db = env.create_db(Some("state_store"), DatabaseFlags::empty())?;
         let block_body_db = env.create_db(Some("block_body"), DatabaseFlags::empty())?;
+        let deploys_by_account_db =
+            env.create_db(Some("deploys_by_account"), DatabaseFlags::empty())?;
+        let pending_deploy_db = env.create_db(Some("pending_deploys"), DatabaseFlags::empty())?;
+        let execution_results_era_db =
+            env.create_db(Some("execution_results_by_era"), DatabaseFlags::empty())?;
+        let contract_events_db = env.create_db(Some("contract_events"), DatabaseFlags::empty())?;
+        let era_rewards_db = env.create_db(Some("era_rewards"), DatabaseFlags::empty())?;
+        let execution_journal_db =
+            env.create_db(Some("execution_journals"), DatabaseFlags::empty())?;
 
         // We now need to restore the block-height index. Log messages allow timing here.
         info!("reindexing block store");
         let mut block_height_index = BTreeMap::new();
         let mut switch_block_era_id_index = BTreeMap::new();
         let mut deploy_hash_index = BTreeMap::new();
+        let mut proposer_index = BTreeMap::new();
+        let mut blocks_by_era: BTreeMap<EraId, Vec<BlockHash>> = BTreeMap::new();
         let mut block_txn = env.begin_rw_txn()?;
         let mut cursor = block_txn.open_rw_cursor(block_header_db)?;
 
@@ -297,7 +452,14 @@ impl Storage {
         // Note: `iter_start` has an undocumented panic if called on an empty database. We rely on
         //       the iterator being at the start when created.
         for (raw_key, raw_val) in cursor.iter() {
-            let block: BlockHeader = lmdb_ext::deserialize(raw_val)?;
+            // Headers written before the gas price floor was introduced are migrated in place.
+            let (block, is_legacy): (BlockHeader, bool) = match lmdb_ext::deserialize(raw_val) {
+                Ok(block) => (block, false),
+                Err(_) => {
+                    let legacy_block: LegacyBlockHeader = lmdb_ext::deserialize(raw_val)?;
+                    (legacy_block.into(), true)
+                }
+            };
             if let Some(invalid_era) = hard_reset_to_start_of_era {
                 // Remove blocks that are in to-be-upgraded eras, but have obsolete protocol
                 // versions - they were most likely created before the upgrade and should be
@@ -314,11 +476,24 @@ impl Storage {
                 block.hash().as_ref(),
                 "found corrupt block in database"
             );
+            if is_legacy {
+                cursor.put(
+                    &block.hash(),
+                    &lmdb_ext::serialize(&block)?,
+                    WriteFlags::CURRENT,
+                )?;
+            }
             insert_to_block_header_indices(
                 &mut block_height_index,
                 &mut switch_block_era_id_index,
                 &block,
             )?;
+            if config.execution_results_retention_eras.is_some() {
+                blocks_by_era
+                    .entry(block.era_id())
+                    .or_default()
+                    .push(block.hash());
+            }
 
             let mut body_txn = env.begin_ro_txn()?;
             let block_body: BlockBody = body_txn
@@ -331,6 +506,7 @@ impl Storage {
                 "found corrupt block body in database"
             );
             insert_to_deploy_index(&mut deploy_hash_index, block.hash(), &block_body)?;
+            insert_to_proposer_index(&mut proposer_index, &block, &block_body);
         }
         info!("block store reindexing complete");
         drop(cursor);
@@ -341,6 +517,31 @@ impl Storage {
         initialize_block_body_db(&env, &block_body_db, &deleted_block_hashes_raw)?;
         initialize_block_metadata_db(&env, &block_metadata_db, &deleted_block_hashes_raw)?;
         initialize_deploy_metadata_db(&env, &deploy_metadata_db, &deleted_block_hashes)?;
+        initialize_deploy_db(&env, &deploy_db)?;
+        let pending_deploy_index = initialize_pending_deploy_db(&env, &pending_deploy_db)?;
+        initialize_execution_results_era_db(&env, &execution_results_era_db, blocks_by_era)?;
+
+        let metrics = StorageMetrics::new(
+            registry,
+            &[
+                ("block_header", block_header_db),
+                ("block_body", block_body_db),
+                ("block_metadata", block_metadata_db),
+                ("deploys", deploy_db),
+                ("deploy_metadata", deploy_metadata_db),
+                ("transfer", transfer_db),
+                ("state_store", state_store_db),
+                ("deploys_by_account", deploys_by_account_db),
+                ("pending_deploys", pending_deploy_db),
+                ("execution_results_by_era", execution_results_era_db),
+                ("contract_events", contract_events_db),
+                ("era_rewards", era_rewards_db),
+                ("execution_journals", execution_journal_db),
+            ],
+        )?;
+        if let Err(error) = metrics.update_stats(&env) {
+            warn!(%error, "failed to read database statistics");
+        }
 
         Ok(Storage {
             root,
@@ -352,12 +553,36 @@ impl Storage {
             deploy_metadata_db,
             transfer_db,
             state_store_db,
+            deploys_by_account_db,
+            pending_deploy_db,
+            pending_deploy_index,
+            max_pending_deploys_per_account: config.max_pending_deploys_per_account,
+            max_pending_deploys_size: config.max_pending_deploys_size,
+            execution_results_era_db,
+            contract_events_db,
+            era_rewards_db,
+            execution_journal_db,
+            execution_results_retention_eras: config.execution_results_retention_eras,
+            block_read_ahead: config.block_read_ahead,
+            last_requested_height: None,
+            read_ahead_cache: BTreeMap::new(),
             block_height_index,
             switch_block_era_id_index,
             deploy_hash_index,
+            proposer_index,
+            metrics,
+            last_stats_update: Instant::now(),
         })
     }
 
+    /// Updates the metrics derived from the LMDB database statistics.
+    fn update_stats(&mut self) {
+        if let Err(error) = self.metrics.update_stats(&self.env) {
+            warn!(%error, "failed to read database statistics");
+        }
+        self.last_stats_update = Instant::now();
+    }
+
     /// Handles a state store request.
     fn handle_state_store_request<REv>(
         &mut self,
@@ -375,13 +600,15 @@ impl Storage {
                 data,
                 responder,
             } => {
-                let mut txn = self.env.begin_rw_txn()?;
+                let mut txn = self.env.begin_rw_txn_with_retry()?;
+                self.metrics.record_put(self.state_store_db);
                 txn.put(self.state_store_db, &key, &data, WriteFlags::default())?;
                 txn.commit()?;
                 Ok(responder.respond(()).ignore())
             }
             StateStoreRequest::Load { key, responder } => {
-                let txn = self.env.begin_ro_txn()?;
+                let txn = self.env.begin_ro_txn_with_retry()?;
+                self.metrics.record_get(self.state_store_db);
                 let bytes = match txn.get(self.state_store_db, &key) {
                     Ok(slice) => Some(slice.to_owned()),
                     Err(lmdb::Error::NotFound) => None,
@@ -400,7 +627,8 @@ impl Storage {
     where
         K: AsRef<[u8]>,
     {
-        let txn = self.env.begin_ro_txn()?;
+        let txn = self.env.begin_ro_txn_with_retry()?;
+        self.metrics.record_get(self.state_store_db);
         let bytes = match txn.get(self.state_store_db, &key) {
             Ok(slice) => Some(slice.to_owned()),
             Err(lmdb::Error::NotFound) => None,
@@ -415,7 +643,7 @@ impl Storage {
     where
         K: AsRef<[u8]>,
     {
-        let mut txn = self.env.begin_rw_txn()?;
+        let mut txn = self.env.begin_rw_txn_with_retry()?;
         let result = match txn.del(self.state_store_db, &key, None) {
             Ok(_) => Ok(true),
             Err(lmdb::Error::NotFound) => Ok(false),
@@ -425,6 +653,64 @@ impl Storage {
         Ok(result)
     }
 
+    /// Stores a pending deploy, unless a limit of the pending deploy store would be exceeded.
+    ///
+    /// Pending deploys which have expired are removed first.
+    fn put_pending_deploy(
+        &mut self,
+        deploy: &Deploy,
+    ) -> Result<Result<(), PendingDeployStoreFull>, Error> {
+        let deploy_hash = *deploy.id();
+        let account_hash = deploy.header().account().to_account_hash();
+        let serialized_deploy = lmdb_ext::serialize(deploy)?;
+
+        let mut txn = self.env.begin_rw_txn_with_retry()?;
+        for expired_deploy_hash in self.pending_deploy_index.expired(Timestamp::now()) {
+            match txn.del(self.pending_deploy_db, &expired_deploy_hash, None) {
+                Ok(()) | Err(lmdb::Error::NotFound) => (),
+                Err(err) => return Err(err.into()),
+            }
+            let _ = self.pending_deploy_index.remove(&expired_deploy_hash);
+        }
+
+        let result = if self
+            .pending_deploy_index
+            .count_for_account(&account_hash, &deploy_hash)
+            >= self.max_pending_deploys_per_account
+        {
+            Err(PendingDeployStoreFull::TooManyForAccount {
+                account_hash,
+                max: self.max_pending_deploys_per_account,
+            })
+        } else if self
+            .pending_deploy_index
+            .total_size(&deploy_hash)
+            .saturating_add(serialized_deploy.len())
+            > self.max_pending_deploys_size
+        {
+            Err(PendingDeployStoreFull::TooLarge {
+                max_size: self.max_pending_deploys_size,
+            })
+        } else {
+            self.metrics.record_put(self.pending_deploy_db);
+            txn.put(
+                self.pending_deploy_db,
+                &deploy_hash,
+                &serialized_deploy,
+                WriteFlags::empty(),
+            )?;
+            self.pending_deploy_index.insert(
+                deploy_hash,
+                account_hash,
+                deploy.header().expires(),
+                serialized_deploy.len(),
+            );
+            Ok(())
+        };
+        txn.commit()?;
+        Ok(result)
+    }
+
     /// Handles a storage request.
     fn handle_storage_request<REv>(&mut self, req: StorageRequest) -> Result<Effects<Event>, Error>
     where
@@ -435,74 +721,80 @@ impl Storage {
         // average the actual execution time will be very low.
         Ok(match req {
             StorageRequest::PutBlock { block, responder } => {
-                let mut txn = self.env.begin_rw_txn()?;
-                if !txn.put_value(
-                    self.block_body_db,
-                    block.header().body_hash(),
-                    block.body(),
-                    true,
-                )? {
-                    error!("Could not insert block body for block: {}", block);
-                    txn.abort();
-                    return Ok(responder.respond(false).ignore());
-                }
-                if !txn.put_value(self.block_header_db, block.hash(), block.header(), true)? {
-                    error!("Could not insert block header for block: {}", block);
+                let mut txn = self.env.begin_rw_txn_with_retry()?;
+                if !self.write_block(&mut txn, &block)? {
                     txn.abort();
                     return Ok(responder.respond(false).ignore());
                 }
                 txn.commit()?;
-                insert_to_block_header_indices(
-                    &mut self.block_height_index,
-                    &mut self.switch_block_era_id_index,
-                    block.header(),
-                )?;
-                insert_to_deploy_index(
-                    &mut self.deploy_hash_index,
-                    block.header().hash(),
-                    block.body(),
-                )?;
+                self.index_block(&block)?;
                 responder.respond(true).ignore()
             }
+            StorageRequest::PutExecutedBlock {
+                block,
+                execution_results,
+                contract_events,
+                responder,
+            } => {
+                let result = self.put_executed_block(&block, execution_results, contract_events);
+                respond_unless_corrupted(responder, result)?
+            }
             StorageRequest::GetBlock {
                 block_hash,
                 responder,
             } => responder
-                .respond(self.get_single_block(&mut self.env.begin_ro_txn()?, &block_hash)?)
-                .ignore(),
-            StorageRequest::GetBlockHeaderAtHeight { height, responder } => responder
-                .respond(self.get_block_header_by_height(&mut self.env.begin_ro_txn()?, height)?)
+                .respond(
+                    self.get_single_block(&mut self.env.begin_ro_txn_with_retry()?, &block_hash)?,
+                )
                 .ignore(),
+            StorageRequest::GetBlockHeaderAtHeight { height, responder } => {
+                responder
+                    .respond(self.get_block_header_by_height(
+                        &mut self.env.begin_ro_txn_with_retry()?,
+                        height,
+                    )?)
+                    .ignore()
+            }
             StorageRequest::GetBlockAtHeight { height, responder } => responder
-                .respond(self.get_block_by_height(&mut self.env.begin_ro_txn()?, height)?)
+                .respond(self.get_block_by_height_with_read_ahead(height)?)
+                .ignore(),
+            StorageRequest::GetBlockHeaderAndMetadataAtHeight { height, responder } => responder
+                .respond(self.get_block_header_and_metadata_by_height(
+                    &mut self.env.begin_ro_txn_with_retry()?,
+                    height,
+                )?)
                 .ignore(),
             StorageRequest::GetHighestBlock { responder } => {
-                let mut txn = self.env.begin_ro_txn()?;
+                let mut txn = self.env.begin_ro_txn_with_retry()?;
                 responder
                     .respond(self.get_highest_block(&mut txn)?)
                     .ignore()
             }
             StorageRequest::GetSwitchBlockHeaderAtEraId { era_id, responder } => responder
-                .respond(
-                    self.get_switch_block_header_by_era_id(&mut self.env.begin_ro_txn()?, era_id)?,
-                )
+                .respond(self.get_switch_block_header_by_era_id(
+                    &mut self.env.begin_ro_txn_with_retry()?,
+                    era_id,
+                )?)
                 .ignore(),
-            StorageRequest::GetSwitchBlockAtEraId { era_id, responder } => responder
-                .respond(self.get_switch_block_by_era_id(&mut self.env.begin_ro_txn()?, era_id)?)
-                .ignore(),
-            StorageRequest::GetBlockHeaderForDeploy {
-                deploy_hash,
-                responder,
-            } => {
+            StorageRequest::GetSwitchBlockAtEraId { era_id, responder } => {
                 responder
-                    .respond(self.get_block_header_by_deploy_hash(
-                        &mut self.env.begin_ro_txn()?,
-                        deploy_hash,
+                    .respond(self.get_switch_block_by_era_id(
+                        &mut self.env.begin_ro_txn_with_retry()?,
+                        era_id,
                     )?)
                     .ignore()
             }
+            StorageRequest::GetBlockHeaderForDeploy {
+                deploy_hash,
+                responder,
+            } => responder
+                .respond(self.get_block_header_by_deploy_hash(
+                    &mut self.env.begin_ro_txn_with_retry()?,
+                    deploy_hash,
+                )?)
+                .ignore(),
             StorageRequest::GetHighestSwitchBlock { responder } => {
-                let mut txn = self.env.begin_ro_txn()?;
+                let mut txn = self.env.begin_ro_txn_with_retry()?;
                 responder
                     .respond(
                         self.switch_block_era_id_index
@@ -523,27 +815,116 @@ impl Storage {
                 // TODO: Find a solution for efficiently retrieving the blocker header without the
                 // block. Deserialization that allows trailing bytes could be a possible solution.
                 .respond(
-                    self.get_single_block(&mut self.env.begin_ro_txn()?, &block_hash)?
+                    self.get_single_block(&mut self.env.begin_ro_txn_with_retry()?, &block_hash)?
                         .map(|block| block.header().clone()),
                 )
                 .ignore(),
+            StorageRequest::GetBlocksByProposer {
+                public_key,
+                era_id,
+                responder,
+            } => {
+                let mut txn = self.env.begin_ro_txn_with_retry()?;
+                let mut blocks = vec![];
+                if let Some(block_hashes) = self.proposer_index.get(&(era_id, public_key)) {
+                    for block_hash in block_hashes.values() {
+                        if let Some(block) = self.get_single_block(&mut txn, block_hash)? {
+                            blocks.push(block);
+                        }
+                    }
+                }
+                responder.respond(blocks).ignore()
+            }
             StorageRequest::GetBlockTransfers {
                 block_hash,
                 responder,
             } => responder
-                .respond(self.get_transfers(&mut self.env.begin_ro_txn()?, &block_hash)?)
+                .respond(self.get_transfers(&mut self.env.begin_ro_txn_with_retry()?, &block_hash)?)
                 .ignore(),
             StorageRequest::PutDeploy { deploy, responder } => {
-                let mut txn = self.env.begin_rw_txn()?;
+                let mut txn = self.env.begin_rw_txn_with_retry()?;
+                self.metrics.record_put(self.deploy_db);
                 let outcome = txn.put_value(self.deploy_db, deploy.id(), &deploy, false)?;
+                if outcome {
+                    let account_hash = deploy.header().account().to_account_hash();
+                    self.metrics.record_get(self.deploys_by_account_db);
+                    let mut deploy_hashes: Vec<DeployHash> = txn
+                        .get_value(self.deploys_by_account_db, &account_hash.value())?
+                        .unwrap_or_default();
+                    deploy_hashes.push(*deploy.id());
+                    self.metrics.record_put(self.deploys_by_account_db);
+                    let _ = txn.put_value(
+                        self.deploys_by_account_db,
+                        &account_hash.value(),
+                        &deploy_hashes,
+                        true,
+                    )?;
+                }
                 txn.commit()?;
                 responder.respond(outcome).ignore()
             }
+            StorageRequest::GetDeploysByAccount {
+                account_hash,
+                limit,
+                offset,
+                responder,
+            } => {
+                self.metrics.record_get(self.deploys_by_account_db);
+                let deploy_hashes: Vec<DeployHash> = self
+                    .env
+                    .begin_ro_txn_with_retry()?
+                    .get_value(self.deploys_by_account_db, &account_hash.value())?
+                    .unwrap_or_default();
+                // Return the most recently stored deploys first.
+                let page = deploy_hashes
+                    .into_iter()
+                    .rev()
+                    .skip(offset)
+                    .take(limit)
+                    .collect();
+                responder.respond(page).ignore()
+            }
+            StorageRequest::PutPendingDeploy { deploy, responder } => {
+                let result = self.put_pending_deploy(&deploy)?;
+                responder.respond(result).ignore()
+            }
+            StorageRequest::GetPendingDeploy {
+                deploy_hash,
+                responder,
+            } => {
+                self.metrics.record_get(self.pending_deploy_db);
+                let maybe_deploy: Option<Deploy> = self
+                    .env
+                    .begin_ro_txn_with_retry()?
+                    .get_value(self.pending_deploy_db, &deploy_hash)?;
+                let now = Timestamp::now();
+                let unexpired_deploy = maybe_deploy
+                    .filter(|deploy| !deploy.header().expired(now))
+                    .map(Box::new);
+                responder.respond(unexpired_deploy).ignore()
+            }
+            StorageRequest::RemovePendingDeploy {
+                deploy_hash,
+                responder,
+            } => {
+                let mut txn = self.env.begin_rw_txn_with_retry()?;
+                let removed = match txn.del(self.pending_deploy_db, &deploy_hash, None) {
+                    Ok(()) => true,
+                    Err(lmdb::Error::NotFound) => false,
+                    Err(err) => return Err(err.into()),
+                };
+                txn.commit()?;
+                let _ = self.pending_deploy_index.remove(&deploy_hash);
+                responder.respond(removed).ignore()
+            }
             StorageRequest::GetDeploys {
                 deploy_hashes,
                 responder,
             } => responder
-                .respond(self.get_deploys(&mut self.env.begin_ro_txn()?, deploy_hashes.as_slice())?)
+                .respond(self.get_deploys(
+                    &mut self.env.begin_ro_txn_with_retry()?,
+                    deploy_hashes.as_slice(),
+                )?)
                 .ignore(),
             StorageRequest::GetDeployHeaders {
                 deploy_hashes,
@@ -551,10 +932,13 @@ impl Storage {
             } => responder
                 .respond(
                     // TODO: Similarly to getting block headers, requires optimized function.
-                    self.get_deploys(&mut self.env.begin_ro_txn()?, deploy_hashes.as_slice())?
-                        .into_iter()
-                        .map(|opt| opt.map(|deploy| deploy.header().clone()))
-                        .collect(),
+                    self.get_deploys(
+                        &mut self.env.begin_ro_txn_with_retry()?,
+                        deploy_hashes.as_slice(),
+                    )?
+                    .into_iter()
+                    .map(|opt| opt.map(|deploy| deploy.header().clone()))
+                    .collect(),
                 )
                 .ignore(),
             StorageRequest::PutExecutionResults {
@@ -562,69 +946,90 @@ impl Storage {
                 execution_results,
                 responder,
             } => {
-                let mut txn = self.env.begin_rw_txn()?;
-
-                let mut transfers: Vec<Transfer> = vec![];
-
-                for (deploy_hash, execution_result) in execution_results {
-                    let mut metadata = self
-                        .get_deploy_metadata(&mut txn, &deploy_hash)?
-                        .unwrap_or_default();
-
-                    // If we have a previous execution result, we can continue if it is the same.
-                    if let Some(prev) = metadata.execution_results.get(&block_hash) {
-                        if prev == &execution_result {
-                            continue;
-                        } else {
-                            debug!(%deploy_hash, %block_hash, "different execution result");
-                        }
-                    }
-
-                    if let ExecutionResult::Success { effect, .. } = execution_result.clone() {
-                        for transform_entry in effect.transforms {
-                            if let Transform::WriteTransfer(transfer) = transform_entry.transform {
-                                transfers.push(transfer);
-                            }
-                        }
-                    }
-
-                    // TODO: this is currently done like this because rpc get_deploy returns the
-                    // data, but the organization of deploy, block_hash, and
-                    // execution_result is incorrectly represented. it should be
-                    // inverted; for a given block_hash 0n deploys and each deploy has exactly 1
-                    // result (aka deploy_metadata in this context).
-
-                    // Update metadata and write back to db.
-                    metadata
-                        .execution_results
-                        .insert(*block_hash, execution_result);
-                    let was_written =
-                        txn.put_value(self.deploy_metadata_db, &deploy_hash, &metadata, true)?;
-                    assert!(
-                        was_written,
-                        "failed to write deploy metadata for block_hash {} deploy_hash {}",
-                        block_hash, deploy_hash
-                    );
+                let mut txn = self.env.begin_rw_txn_with_retry()?;
+                self.write_execution_results(&mut txn, &block_hash, execution_results)?;
+                // Results can only be tagged with an era if their block is already known.
+                if let Some(block_header) = self.get_single_block_header(&mut txn, &block_hash)? {
+                    self.tag_execution_results(&mut txn, block_header.era_id(), &block_hash)?;
+                }
+                txn.commit()?;
+                responder.respond(()).ignore()
+            }
+            StorageRequest::GetExecutionResultsByBlock {
+                block_hash,
+                responder,
+            } => responder
+                .respond(self.get_execution_results_by_block(
+                    &mut self.env.begin_ro_txn_with_retry()?,
+                    &block_hash,
+                )?)
+                .ignore(),
+            StorageRequest::GetContractEvents {
+                contract_hash,
+                from_block,
+                to_block,
+                responder,
+            } => responder
+                .respond(self.get_contract_events(
+                    &mut self.env.begin_ro_txn_with_retry()?,
+                    &contract_hash,
+                    from_block,
+                    to_block,
+                )?)
+                .ignore(),
+            StorageRequest::PutEraRewards {
+                era_rewards,
+                responder,
+            } => {
+                let mut txn = self.env.begin_rw_txn_with_retry()?;
+                self.metrics.record_put(self.era_rewards_db);
+                let _ = txn.put_value(
+                    self.era_rewards_db,
+                    &era_rewards.era_id.value().to_be_bytes(),
+                    &era_rewards,
+                    true,
+                )?;
+                txn.commit()?;
+                responder.respond(()).ignore()
+            }
+            StorageRequest::GetEraRewards { era_id, responder } => {
+                let mut txn = self.env.begin_ro_txn_with_retry()?;
+                self.metrics.record_get(self.era_rewards_db);
+                let era_rewards =
+                    txn.get_value(self.era_rewards_db, &era_id.value().to_be_bytes())?;
+                responder.respond(era_rewards).ignore()
+            }
+            StorageRequest::PutExecutionJournals {
+                journals,
+                responder,
+            } => {
+                let mut txn = self.env.begin_rw_txn_with_retry()?;
+                for (deploy_hash, journal) in journals {
+                    self.metrics.record_put(self.execution_journal_db);
+                    let _ =
+                        txn.put_value(self.execution_journal_db, &deploy_hash, &journal, true)?;
                 }
-
-                let was_written =
-                    txn.put_value(self.transfer_db, &*block_hash, &transfers, true)?;
-                assert!(
-                    was_written,
-                    "failed to write transfers for block_hash {}",
-                    block_hash
-                );
-
                 txn.commit()?;
                 responder.respond(()).ignore()
             }
+            StorageRequest::GetExecutionJournal {
+                deploy_hash,
+                responder,
+            } => {
+                let mut txn = self.env.begin_ro_txn_with_retry()?;
+                self.metrics.record_get(self.execution_journal_db);
+                let journal: Option<Vec<JournalEntry>> =
+                    txn.get_value(self.execution_journal_db, &deploy_hash)?;
+                responder.respond(journal).ignore()
+            }
             StorageRequest::GetDeployAndMetadata {
                 deploy_hash,
                 responder,
             } => {
-                let mut txn = self.env.begin_ro_txn()?;
+                let mut txn = self.env.begin_ro_txn_with_retry()?;
 
                 // A missing deploy causes an early `None` return.
+                self.metrics.record_get(self.deploy_db);
                 let deploy: Deploy =
                     if let Some(deploy) = txn.get_value(self.deploy_db, &deploy_hash)? {
                         deploy
@@ -638,41 +1043,33 @@ impl Storage {
                     .unwrap_or_default();
                 responder.respond(Some((deploy, metadata))).ignore()
             }
-            StorageRequest::GetBlockAndMetadataByHash {
+            StorageRequest::GetBlockWithMetadata {
                 block_hash,
                 responder,
-            } => {
-                let mut txn = self.env.begin_ro_txn()?;
-
-                let block: Block =
-                    if let Some(block) = self.get_single_block(&mut txn, &block_hash)? {
-                        block
-                    } else {
-                        return Ok(responder.respond(None).ignore());
-                    };
-                // Check that the hash of the block retrieved is correct.
-                assert_eq!(&block_hash, block.hash());
-                let signatures = match self.get_finality_signatures(&mut txn, &block_hash)? {
-                    Some(signatures) => signatures,
-                    None => BlockSignatures::new(block_hash, block.header().era_id()),
-                };
-                assert!(signatures.verify().is_ok());
-
-                responder.respond(Some((block, signatures))).ignore()
-            }
-            StorageRequest::GetBlockAndMetadataByHeight {
-                block_height,
+            } => responder
+                .respond(self.get_block_with_metadata(
+                    &mut self.env.begin_ro_txn_with_retry()?,
+                    &block_hash,
+                )?)
+                .ignore(),
+            StorageRequest::GetBlockHeaderByIdentifier {
+                block_identifier,
+                responder,
+            } => responder
+                .respond(self.get_block_header_by_identifier(
+                    &mut self.env.begin_ro_txn_with_retry()?,
+                    block_identifier,
+                )?)
+                .ignore(),
+            StorageRequest::GetBlockAndMetadataByIdentifier {
+                block_identifier,
                 responder,
             } => {
-                let mut txn = self.env.begin_ro_txn()?;
-
-                let block: Block =
-                    if let Some(block) = self.get_block_by_height(&mut txn, block_height)? {
-                        block
-                    } else {
-                        return Ok(responder.respond(None).ignore());
-                    };
-
+                let mut txn = self.env.begin_ro_txn_with_retry()?;
+                let block = match self.get_block_by_identifier(&mut txn, block_identifier)? {
+                    Some(block) => block,
+                    None => return Ok(responder.respond(None).ignore()),
+                };
                 let hash = block.hash();
                 let signatures = match self.get_finality_signatures(&mut txn, hash)? {
                     Some(signatures) => signatures,
@@ -680,33 +1077,12 @@ impl Storage {
                 };
                 responder.respond(Some((block, signatures))).ignore()
             }
-            StorageRequest::GetHighestBlockWithMetadata { responder } => {
-                let mut txn = self.env.begin_ro_txn()?;
-                let highest_block: Block = if let Some(block) = self
-                    .block_height_index
-                    .keys()
-                    .last()
-                    .and_then(|&height| self.get_block_by_height(&mut txn, height).transpose())
-                    .transpose()?
-                {
-                    block
-                } else {
-                    return Ok(responder.respond(None).ignore());
-                };
-                let hash = highest_block.hash();
-                let signatures = match self.get_finality_signatures(&mut txn, hash)? {
-                    Some(signatures) => signatures,
-                    None => BlockSignatures::new(*hash, highest_block.header().era_id()),
-                };
-                responder
-                    .respond(Some((highest_block, signatures)))
-                    .ignore()
-            }
             StorageRequest::PutBlockSignatures {
                 signatures,
                 responder,
             } => {
-                let mut txn = self.env.begin_rw_txn()?;
+                let mut txn = self.env.begin_rw_txn_with_retry()?;
+                self.metrics.record_get(self.block_metadata_db);
                 let old_data: Option<BlockSignatures> =
                     txn.get_value(self.block_metadata_db, &signatures.block_hash)?;
                 let new_data = match old_data {
@@ -718,6 +1094,7 @@ impl Storage {
                         data
                     }
                 };
+                self.metrics.record_put(self.block_metadata_db);
                 let outcome = txn.put_value(
                     self.block_metadata_db,
                     &new_data.block_hash,
@@ -731,12 +1108,14 @@ impl Storage {
                 block_hash,
                 responder,
             } => {
-                let result =
-                    self.get_finality_signatures(&mut self.env.begin_ro_txn()?, &block_hash)?;
+                let result = self.get_finality_signatures(
+                    &mut self.env.begin_ro_txn_with_retry()?,
+                    &block_hash,
+                )?;
                 responder.respond(result).ignore()
             }
             StorageRequest::GetFinalizedDeploys { ttl, responder } => {
-                responder.respond(self.get_finalized_deploys(ttl)?).ignore()
+                respond_unless_corrupted(responder, self.get_finalized_deploys(ttl))?
             }
         })
     }
@@ -770,7 +1149,7 @@ impl Storage {
         &self,
         height: u64,
     ) -> Result<Option<BlockHeaderWithMetadata>, Error> {
-        let mut txn = self.env.begin_ro_txn()?;
+        let mut txn = self.env.begin_ro_txn_with_retry()?;
         let maybe_block_header_and_finality_signatures =
             self.get_block_header_and_metadata_by_height(&mut txn, height)?;
         drop(txn);
@@ -801,6 +1180,40 @@ impl Storage {
             .transpose()
     }
 
+    /// Retrieves a single block by height, reading ahead if blocks are requested sequentially.
+    ///
+    /// If the previous request was for the preceding height, the block is served from the
+    /// read-ahead cache, or else read together with the next `block_read_ahead` blocks, which are
+    /// cached for the following requests.
+    fn get_block_by_height_with_read_ahead(&mut self, height: u64) -> Result<Option<Block>, Error> {
+        let is_sequential = self
+            .last_requested_height
+            .and_then(|last| last.checked_add(1))
+            == Some(height);
+        self.last_requested_height = Some(height);
+        if !is_sequential {
+            self.read_ahead_cache.clear();
+        } else if let Some(block) = self.read_ahead_cache.remove(&height) {
+            return Ok(Some(block));
+        }
+
+        let mut txn = self.env.begin_ro_txn_with_retry()?;
+        let maybe_block = self.get_block_by_height(&mut txn, height)?;
+        if !is_sequential || maybe_block.is_none() {
+            return Ok(maybe_block);
+        }
+        let mut read_ahead = Vec::new();
+        for next_height in (height + 1)..=height.saturating_add(self.block_read_ahead) {
+            match self.get_block_by_height(&mut txn, next_height)? {
+                Some(block) => read_ahead.push((next_height, block)),
+                None => break,
+            }
+        }
+        drop(txn);
+        self.read_ahead_cache.extend(read_ahead);
+        Ok(maybe_block)
+    }
+
     /// Retrieves single switch block header by era ID by looking it up in the index and returning
     /// it.
     fn get_switch_block_header_by_era_id<Tx: Transaction>(
@@ -840,6 +1253,37 @@ impl Storage {
             .transpose()
     }
 
+    /// Retrieves a single block header by its hash or height, or the highest one.
+    fn get_block_header_by_identifier<Tx: Transaction>(
+        &self,
+        tx: &mut Tx,
+        block_identifier: BlockIdentifier,
+    ) -> Result<Option<BlockHeader>, LmdbExtError> {
+        match block_identifier {
+            BlockIdentifier::Hash(block_hash) => self.get_single_block_header(tx, &block_hash),
+            BlockIdentifier::Height(height) => self.get_block_header_by_height(tx, height),
+            BlockIdentifier::Latest => self
+                .block_height_index
+                .keys()
+                .last()
+                .and_then(|&height| self.get_block_header_by_height(tx, height).transpose())
+                .transpose(),
+        }
+    }
+
+    /// Retrieves a single block by its hash or height, or the highest one.
+    fn get_block_by_identifier<Tx: Transaction>(
+        &self,
+        tx: &mut Tx,
+        block_identifier: BlockIdentifier,
+    ) -> Result<Option<Block>, LmdbExtError> {
+        match block_identifier {
+            BlockIdentifier::Hash(block_hash) => self.get_single_block(tx, &block_hash),
+            BlockIdentifier::Height(height) => self.get_block_by_height(tx, height),
+            BlockIdentifier::Latest => self.get_highest_block(tx),
+        }
+    }
+
     /// Returns vector blocks that satisfy the predicate, starting from the latest one and following
     /// the ancestry chain.
     fn get_blocks_while<F, Tx: Transaction>(
@@ -868,12 +1312,38 @@ impl Storage {
         Ok(blocks)
     }
 
+    /// Stores a block together with its execution results and contract events.
+    ///
+    /// Returns `false` if the block was already stored.
+    fn put_executed_block(
+        &mut self,
+        block: &Block,
+        execution_results: HashMap<DeployHash, ExecutionResult>,
+        contract_events: Vec<ContractEventRecord>,
+    ) -> Result<bool, Error> {
+        // The block, its execution results and its contract events are written in a single
+        // transaction, so a crash can never leave a stored block without its deploy metadata or
+        // events, or vice versa.
+        let mut txn = self.env.begin_rw_txn_with_retry()?;
+        if !self.write_block(&mut txn, block)? {
+            txn.abort();
+            return Ok(false);
+        }
+        self.write_execution_results(&mut txn, block.hash(), execution_results)?;
+        self.write_contract_events(&mut txn, contract_events)?;
+        self.tag_execution_results(&mut txn, block.header().era_id(), block.hash())?;
+        self.prune_execution_results(&mut txn, block.header().era_id())?;
+        txn.commit()?;
+        self.index_block(block)?;
+        Ok(true)
+    }
+
     /// Returns the vector of deploys whose TTL hasn't expired yet.
     fn get_finalized_deploys(
         &self,
         ttl: TimeDiff,
-    ) -> Result<Vec<(DeployHash, DeployHeader)>, LmdbExtError> {
-        let mut txn = self.env.begin_ro_txn()?;
+    ) -> Result<Vec<(DeployHash, DeployHeader)>, Error> {
+        let mut txn = self.env.begin_ro_txn_with_retry()?;
         // We're interested in deploys whose TTL hasn't expired yet.
         let ttl_expired = |block: &Block| block.timestamp().elapsed() < ttl;
         let mut deploys = Vec::new();
@@ -884,9 +1354,12 @@ impl Storage {
                 .iter()
                 .chain(block.body().transfer_hashes())
             {
-                let deploy_header = self
-                    .get_deploy_header(&mut txn, deploy_hash)?
-                    .expect("deploy to exist in storage");
+                let deploy_header =
+                    self.get_deploy_header(&mut txn, deploy_hash)?
+                        .ok_or(Error::MissingDeploy {
+                            block_hash: *block.hash(),
+                            deploy_hash: *deploy_hash,
+                        })?;
                 // If block's deploy has already expired, ignore it.
                 // It may happen that deploy was not expired at the time of proposing a block but it
                 // is now.
@@ -940,6 +1413,7 @@ impl Storage {
         tx: &mut Tx,
         block_hash: &BlockHash,
     ) -> Result<Option<BlockHeader>, LmdbExtError> {
+        self.metrics.record_get(self.block_header_db);
         let block_header: BlockHeader = match tx.get_value(self.block_header_db, &block_hash)? {
             Some(block_header) => block_header,
             None => return Ok(None),
@@ -959,7 +1433,7 @@ impl Storage {
         &self,
         block_hash: &BlockHash,
     ) -> Result<Option<BlockHeader>, LmdbExtError> {
-        let mut txn = self.env.begin_ro_txn()?;
+        let mut txn = self.env.begin_ro_txn_with_retry()?;
         let maybe_block_header = self.get_single_block_header(&mut txn, block_hash)?;
         drop(txn);
         Ok(maybe_block_header)
@@ -975,6 +1449,7 @@ impl Storage {
             Some(block_header) => block_header,
             None => return Ok(None),
         };
+        self.metrics.record_get(self.block_body_db);
         let block_body: BlockBody =
             match tx.get_value(self.block_body_db, block_header.body_hash())? {
                 Some(block_header) => block_header,
@@ -999,7 +1474,10 @@ impl Storage {
     ) -> Result<Vec<Option<Deploy>>, LmdbExtError> {
         deploy_hashes
             .iter()
-            .map(|deploy_hash| tx.get_value(self.deploy_db, deploy_hash))
+            .map(|deploy_hash| {
+                self.metrics.record_get(self.deploy_db);
+                tx.get_value(self.deploy_db, deploy_hash)
+            })
             .collect()
     }
 
@@ -1009,6 +1487,7 @@ impl Storage {
         txn: &mut Tx,
         deploy_hash: &DeployHash,
     ) -> Result<Option<DeployHeader>, LmdbExtError> {
+        self.metrics.record_get(self.deploy_db);
         let maybe_deploy: Option<Deploy> = txn.get_value(self.deploy_db, deploy_hash)?;
         Ok(maybe_deploy.map(|deploy| deploy.header().clone()))
     }
@@ -1022,6 +1501,7 @@ impl Storage {
         tx: &mut Tx,
         deploy_hash: &DeployHash,
     ) -> Result<Option<DeployMetadata>, Error> {
+        self.metrics.record_get(self.deploy_metadata_db);
         Ok(tx.get_value(self.deploy_metadata_db, deploy_hash)?)
     }
 
@@ -1034,15 +1514,313 @@ impl Storage {
         tx: &mut Tx,
         block_hash: &BlockHash,
     ) -> Result<Option<Vec<Transfer>>, Error> {
+        self.metrics.record_get(self.transfer_db);
         Ok(tx.get_value(self.transfer_db, block_hash)?)
     }
 
+    /// Writes the header and body of a block within the given transaction.
+    ///
+    /// Returns `false` if either could not be inserted, in which case the transaction should be
+    /// aborted.
+    fn write_block(&self, txn: &mut RwTransaction<'_>, block: &Block) -> Result<bool, Error> {
+        self.metrics.record_put(self.block_body_db);
+        if !txn.put_value(
+            self.block_body_db,
+            block.header().body_hash(),
+            block.body(),
+            true,
+        )? {
+            error!("Could not insert block body for block: {}", block);
+            return Ok(false);
+        }
+        self.metrics.record_put(self.block_header_db);
+        if !txn.put_value(self.block_header_db, block.hash(), block.header(), true)? {
+            error!("Could not insert block header for block: {}", block);
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Writes the execution results of a block's deploys, together with the transfers they
+    /// performed, within the given transaction.
+    fn write_execution_results(
+        &self,
+        txn: &mut RwTransaction<'_>,
+        block_hash: &BlockHash,
+        execution_results: HashMap<DeployHash, ExecutionResult>,
+    ) -> Result<(), Error> {
+        let mut transfers: Vec<Transfer> = vec![];
+
+        for (deploy_hash, execution_result) in execution_results {
+            let mut metadata = self
+                .get_deploy_metadata(txn, &deploy_hash)?
+                .unwrap_or_default();
+
+            // If we have a previous execution result, we can continue if it is the same.
+            if let Some(prev) = metadata.execution_results.get(block_hash) {
+                if prev == &execution_result {
+                    continue;
+                } else {
+                    debug!(%deploy_hash, %block_hash, "different execution result");
+                }
+            }
+
+            if let ExecutionResult::Success { effect, .. } = execution_result.clone() {
+                for transform_entry in effect.transforms {
+                    if let Transform::WriteTransfer(transfer) = transform_entry.transform {
+                        transfers.push(transfer);
+                    }
+                }
+            }
+
+            // TODO: this is currently done like this because rpc get_deploy returns the
+            // data, but the organization of deploy, block_hash, and
+            // execution_result is incorrectly represented. it should be
+            // inverted; for a given block_hash 0n deploys and each deploy has exactly 1
+            // result (aka deploy_metadata in this context).
+
+            // Update metadata and write back to db.
+            metadata
+                .execution_results
+                .insert(*block_hash, execution_result);
+            self.metrics.record_put(self.deploy_metadata_db);
+            let _ = txn.put_value(self.deploy_metadata_db, &deploy_hash, &metadata, true)?;
+        }
+
+        self.metrics.record_put(self.transfer_db);
+        let _ = txn.put_value(self.transfer_db, block_hash, &transfers, true)?;
+
+        Ok(())
+    }
+
+    /// Writes the events emitted by contracts in a single block, grouped by contract, within the
+    /// given transaction.
+    ///
+    /// Events previously stored for the same contract and block height are replaced.
+    fn write_contract_events(
+        &self,
+        txn: &mut RwTransaction<'_>,
+        events: Vec<ContractEventRecord>,
+    ) -> Result<(), Error> {
+        let mut events_by_key: BTreeMap<Vec<u8>, Vec<ContractEventRecord>> = BTreeMap::new();
+        for event in events {
+            let key = contract_events_key(&event.contract_hash, event.block_height);
+            events_by_key.entry(key).or_default().push(event);
+        }
+        for (key, events) in events_by_key {
+            self.metrics.record_put(self.contract_events_db);
+            let _ = txn.put_value(self.contract_events_db, &key, &events, true)?;
+        }
+        Ok(())
+    }
+
+    /// Retrieves the events emitted by the given contract in the locally stored blocks with heights
+    /// from `from_block` to `to_block`, inclusive.
+    fn get_contract_events<Tx: Transaction>(
+        &self,
+        tx: &mut Tx,
+        contract_hash: &ContractHash,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Vec<ContractEventRecord>, Error> {
+        let mut events = vec![];
+        if from_block > to_block {
+            return Ok(events);
+        }
+        for height in self
+            .block_height_index
+            .range(from_block..=to_block)
+            .map(|(h, _)| *h)
+        {
+            let key = contract_events_key(contract_hash, height);
+            self.metrics.record_get(self.contract_events_db);
+            if let Some(block_events) =
+                tx.get_value::<_, Vec<ContractEventRecord>>(self.contract_events_db, &key)?
+            {
+                events.extend(block_events);
+            }
+        }
+        Ok(events)
+    }
+
+    /// Records that execution results for the given block, which belongs to `era_id`, are stored.
+    fn tag_execution_results(
+        &self,
+        txn: &mut RwTransaction<'_>,
+        era_id: EraId,
+        block_hash: &BlockHash,
+    ) -> Result<(), Error> {
+        let key = era_id.value().to_be_bytes();
+        self.metrics.record_get(self.execution_results_era_db);
+        let mut block_hashes: Vec<BlockHash> = txn
+            .get_value(self.execution_results_era_db, &key)?
+            .unwrap_or_default();
+        if block_hashes.contains(block_hash) {
+            return Ok(());
+        }
+        block_hashes.push(*block_hash);
+        self.metrics.record_put(self.execution_results_era_db);
+        let _ = txn.put_value(self.execution_results_era_db, &key, &block_hashes, true)?;
+        Ok(())
+    }
+
+    /// Removes the execution results, execution journals and transfers of all blocks from eras
+    /// which are more than `execution_results_retention_eras` eras older than `current_era_id`.
+    ///
+    /// The deploys themselves, as well as the blocks, are kept.
+    fn prune_execution_results(
+        &self,
+        txn: &mut RwTransaction<'_>,
+        current_era_id: EraId,
+    ) -> Result<(), Error> {
+        let retention_eras = match self.execution_results_retention_eras {
+            Some(retention_eras) => retention_eras,
+            None => return Ok(()),
+        };
+        let cutoff = current_era_id.value().saturating_sub(retention_eras);
+
+        // Keys are big-endian era IDs, so the cursor visits eras in ascending order.
+        let mut expired: Vec<(u64, Vec<BlockHash>)> = vec![];
+        {
+            let mut cursor = txn.open_ro_cursor(self.execution_results_era_db)?;
+            for (raw_key, raw_val) in cursor.iter() {
+                let mut era_bytes = [0u8; 8];
+                era_bytes.copy_from_slice(raw_key);
+                let era = u64::from_be_bytes(era_bytes);
+                if era >= cutoff {
+                    break;
+                }
+                expired.push((era, lmdb_ext::deserialize(raw_val)?));
+            }
+        }
+
+        for (era, block_hashes) in expired {
+            for block_hash in block_hashes {
+                if let Some(block) = self.get_single_block(txn, &block_hash)? {
+                    let body = block.body();
+                    for deploy_hash in body.deploy_hashes().iter().chain(body.transfer_hashes()) {
+                        match txn.del(self.execution_journal_db, deploy_hash, None) {
+                            Ok(()) | Err(lmdb::Error::NotFound) => (),
+                            Err(err) => return Err(err.into()),
+                        }
+                        let mut metadata = match self.get_deploy_metadata(txn, deploy_hash)? {
+                            Some(metadata) => metadata,
+                            None => continue,
+                        };
+                        if metadata.execution_results.remove(&block_hash).is_none() {
+                            continue;
+                        }
+                        if metadata.execution_results.is_empty() {
+                            txn.del(self.deploy_metadata_db, deploy_hash, None)?;
+                        } else {
+                            self.metrics.record_put(self.deploy_metadata_db);
+                            let _ = txn.put_value(
+                                self.deploy_metadata_db,
+                                deploy_hash,
+                                &metadata,
+                                true,
+                            )?;
+                        }
+                    }
+                }
+                match txn.del(self.transfer_db, &block_hash, None) {
+                    Ok(()) | Err(lmdb::Error::NotFound) => (),
+                    Err(err) => return Err(err.into()),
+                }
+            }
+            txn.del(self.execution_results_era_db, &era.to_be_bytes(), None)?;
+            debug!(%era, "pruned execution results");
+        }
+        Ok(())
+    }
+
+    /// Retrieves the execution results of all deploys and transfers of the given block.
+    ///
+    /// Returns `None` if the block is not stored.
+    fn get_execution_results_by_block<Tx: Transaction>(
+        &self,
+        tx: &mut Tx,
+        block_hash: &BlockHash,
+    ) -> Result<Option<Vec<(DeployHash, ExecutionResult)>>, Error> {
+        match self.get_single_block(tx, block_hash)? {
+            Some(block) => Ok(Some(self.get_execution_results_of_block(tx, &block)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Retrieves the stored execution results of all deploys and transfers of the given block.
+    fn get_execution_results_of_block<Tx: Transaction>(
+        &self,
+        tx: &mut Tx,
+        block: &Block,
+    ) -> Result<Vec<(DeployHash, ExecutionResult)>, Error> {
+        let body = block.body();
+        let mut execution_results = vec![];
+        for deploy_hash in body.deploy_hashes().iter().chain(body.transfer_hashes()) {
+            if let Some(mut metadata) = self.get_deploy_metadata(tx, deploy_hash)? {
+                if let Some(execution_result) = metadata.execution_results.remove(block.hash()) {
+                    execution_results.push((*deploy_hash, execution_result));
+                }
+            }
+        }
+        Ok(execution_results)
+    }
+
+    /// Retrieves a block together with its finality signatures and the execution result summaries
+    /// of its deploys.
+    ///
+    /// Since all reads happen within the given transaction, the parts are guaranteed to be
+    /// consistent with each other even if writes happen concurrently.
+    fn get_block_with_metadata<Tx: Transaction>(
+        &self,
+        tx: &mut Tx,
+        block_hash: &BlockHash,
+    ) -> Result<Option<BlockWithMetadata>, Error> {
+        let block = match self.get_single_block(tx, block_hash)? {
+            Some(block) => block,
+            None => return Ok(None),
+        };
+        let block_signatures = match self.get_finality_signatures(tx, block_hash)? {
+            Some(signatures) => signatures,
+            None => BlockSignatures::new(*block_hash, block.header().era_id()),
+        };
+        let execution_results = self
+            .get_execution_results_of_block(tx, &block)?
+            .iter()
+            .map(|(deploy_hash, execution_result)| {
+                ExecutionResultSummary::new(*deploy_hash, execution_result)
+            })
+            .collect();
+        Ok(Some(BlockWithMetadata {
+            block,
+            block_signatures,
+            execution_results,
+        }))
+    }
+
+    /// Adds a committed block to the in-memory indices.
+    fn index_block(&mut self, block: &Block) -> Result<(), Error> {
+        insert_to_block_header_indices(
+            &mut self.block_height_index,
+            &mut self.switch_block_era_id_index,
+            block.header(),
+        )?;
+        insert_to_deploy_index(
+            &mut self.deploy_hash_index,
+            block.header().hash(),
+            block.body(),
+        )?;
+        insert_to_proposer_index(&mut self.proposer_index, block.header(), block.body());
+        Ok(())
+    }
+
     /// Retrieves finality signatures for a block with a given block hash
     fn get_finality_signatures<Tx: Transaction>(
         &self,
         tx: &mut Tx,
         block_hash: &BlockHash,
     ) -> Result<Option<BlockSignatures>, Error> {
+        self.metrics.record_get(self.block_metadata_db);
         Ok(tx.get_value(self.block_metadata_db, block_hash)?)
     }
 
@@ -1053,6 +1831,17 @@ impl Storage {
     }
 }
 
+/// Returns the key under which the events emitted by a contract in the block at the given height
+/// are stored: the contract hash followed by the big-endian block height.
+fn contract_events_key(contract_hash: &ContractHash, block_height: u64) -> Vec<u8> {
+    contract_hash
+        .value()
+        .iter()
+        .copied()
+        .chain(block_height.to_be_bytes().iter().copied())
+        .collect()
+}
+
 /// Inserts the relevant entries to the two indices.
 ///
 /// If a duplicate entry is encountered, neither index is updated and an error is returned.
@@ -1129,6 +1918,18 @@ fn insert_to_deploy_index(
     Ok(())
 }
 
+/// Inserts the block into the era/proposer index.
+fn insert_to_proposer_index(
+    proposer_index: &mut BTreeMap<(EraId, PublicKey), BTreeMap<u64, BlockHash>>,
+    block_header: &BlockHeader,
+    block_body: &BlockBody,
+) {
+    let _ = proposer_index
+        .entry((block_header.era_id(), block_body.proposer().clone()))
+        .or_default()
+        .insert(block_header.height(), block_header.hash());
+}
+
 /// On-disk storage configuration.
 #[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
@@ -1153,6 +1954,26 @@ pub struct Config {
     ///
     /// The size should be a multiple of the OS page size.
     max_state_store_size: usize,
+    /// The number of eras for which deploy execution results are kept.
+    ///
+    /// Results of blocks from older eras are dropped, while the blocks and deploys themselves are
+    /// kept.  If unset, execution results are never dropped.
+    #[serde(default)]
+    execution_results_retention_eras: Option<u64>,
+    /// The number of blocks to read ahead when blocks are requested by consecutive heights.
+    ///
+    /// This speeds up serving peers which sync the chain block by block.  If zero, blocks are
+    /// never read ahead.
+    #[serde(default)]
+    block_read_ahead: u64,
+    /// The maximum number of pending deploys, i.e. deploys awaiting further approvals, held for
+    /// a single account.  Further pending deploys of the account are rejected.
+    #[serde(default = "default_max_pending_deploys_per_account")]
+    max_pending_deploys_per_account: usize,
+    /// The maximum total serialized size in bytes of all pending deploys.  Pending deploys which
+    /// would exceed it are rejected.
+    #[serde(default = "default_max_pending_deploys_size")]
+    max_pending_deploys_size: usize,
 }
 
 impl Default for Config {
@@ -1164,11 +1985,30 @@ impl Default for Config {
             max_deploy_store_size: DEFAULT_MAX_DEPLOY_STORE_SIZE,
             max_deploy_metadata_store_size: DEFAULT_MAX_DEPLOY_METADATA_STORE_SIZE,
             max_state_store_size: DEFAULT_MAX_STATE_STORE_SIZE,
+            execution_results_retention_eras: None,
+            block_read_ahead: 0,
+            max_pending_deploys_per_account: default_max_pending_deploys_per_account(),
+            max_pending_deploys_size: default_max_pending_deploys_size(),
         }
     }
 }
 
+fn default_max_pending_deploys_per_account() -> usize {
+    DEFAULT_MAX_PENDING_DEPLOYS_PER_ACCOUNT
+}
+
+fn default_max_pending_deploys_size() -> usize {
+    DEFAULT_MAX_PENDING_DEPLOYS_SIZE
+}
+
 impl Config {
+    /// Returns the upper bound for the memory map that is potentially used.
+    fn map_size(&self) -> usize {
+        self.max_block_store_size
+            .saturating_add(self.max_deploy_store_size)
+            .saturating_add(self.max_deploy_metadata_store_size)
+    }
+
     /// Returns a default `Config` suitable for tests, along with a `TempDir` which must be kept
     /// alive for the duration of the test since its destructor removes the dir from the filesystem.
     #[cfg(test)]
@@ -1210,7 +2050,10 @@ impl Storage {
         self.env
             .begin_ro_txn()
             .map_err(Into::into)
-            .and_then(|mut tx| tx.get_value(self.deploy_db, &deploy_hash))
+            .and_then(|mut tx| {
+                self.metrics.record_get(self.deploy_db);
+                tx.get_value(self.deploy_db, &deploy_hash)
+            })
             .expect("legacy direct deploy request failed")
     }
 }
@@ -1233,6 +2076,11 @@ impl Storage {
             .expect("could not retrieve value from storage")
     }
 
+    /// Returns the index of block heights to hashes of all stored blocks.
+    pub(crate) fn block_height_index(&self) -> &BTreeMap<u64, BlockHash> {
+        &self.block_height_index
+    }
+
     /// Reads all known deploy hashes from the internal store.
     ///
     /// # Panics
@@ -1388,3 +2236,116 @@ fn initialize_deploy_metadata_db(
     info!("deploy metadata database initialized");
     Ok(())
 }
+
+/// Migrates deploys stored before deploys had a validity window to the current encoding.
+fn initialize_deploy_db(env: &Environment, deploy_db: &Database) -> Result<(), LmdbExtError> {
+    info!("initializing deploy database");
+    let mut txn = env.begin_rw_txn()?;
+    let mut cursor = txn.open_rw_cursor(*deploy_db)?;
+
+    let mut migrated = 0;
+    for (raw_key, raw_val) in cursor.iter() {
+        let (deploy, is_legacy) = deserialize_deploy(raw_val)?;
+        if !is_legacy {
+            continue;
+        }
+        cursor.put(
+            &raw_key,
+            &lmdb_ext::serialize(&deploy)?,
+            WriteFlags::CURRENT,
+        )?;
+        migrated += 1;
+    }
+
+    drop(cursor);
+    txn.commit()?;
+
+    info!(%migrated, "deploy database initialized");
+    Ok(())
+}
+
+/// Indexes the pending deploys, migrating any stored in the legacy encoding.
+fn initialize_pending_deploy_db(
+    env: &Environment,
+    pending_deploy_db: &Database,
+) -> Result<PendingDeployIndex, LmdbExtError> {
+    info!("initializing pending deploy database");
+    let mut txn = env.begin_rw_txn()?;
+    let mut cursor = txn.open_rw_cursor(*pending_deploy_db)?;
+
+    let mut pending_deploy_index = PendingDeployIndex::default();
+    for (raw_key, raw_val) in cursor.iter() {
+        let (deploy, is_legacy) = deserialize_deploy(raw_val)?;
+        let size = if is_legacy {
+            let serialized_deploy = lmdb_ext::serialize(&deploy)?;
+            cursor.put(&raw_key, &serialized_deploy, WriteFlags::CURRENT)?;
+            serialized_deploy.len()
+        } else {
+            raw_val.len()
+        };
+        pending_deploy_index.insert(
+            *deploy.id(),
+            deploy.header().account().to_account_hash(),
+            deploy.header().expires(),
+            size,
+        );
+    }
+
+    drop(cursor);
+    txn.commit()?;
+
+    info!("pending deploy database initialized");
+    Ok(pending_deploy_index)
+}
+
+/// Deserializes a stored deploy, which may be in the legacy encoding without a validity window.
+///
+/// Returns the deploy, and whether it was stored in the legacy encoding.
+fn deserialize_deploy(raw_val: &[u8]) -> Result<(Deploy, bool), LmdbExtError> {
+    // The legacy encoding lacks the validity window, so a deploy that only decodes by leaving
+    // some of the bytes unread is a legacy one, too.
+    let maybe_deploy = lmdb_ext::deserialize::<Deploy>(raw_val)
+        .ok()
+        .filter(|deploy| {
+            bincode::serialized_size(deploy).map_or(false, |size| size == raw_val.len() as u64)
+        });
+    match maybe_deploy {
+        Some(deploy) => Ok((deploy, false)),
+        None => {
+            let legacy_deploy: LegacyDeploy = lmdb_ext::deserialize(raw_val)?;
+            Ok((Deploy::from(legacy_deploy), true))
+        }
+    }
+}
+
+/// Tags every stored block with its era in the execution results era database.
+///
+/// Blocks stored before execution results were tagged by era, or while no retention period was
+/// configured, would otherwise never be pruned.
+fn initialize_execution_results_era_db(
+    env: &Environment,
+    execution_results_era_db: &Database,
+    blocks_by_era: BTreeMap<EraId, Vec<BlockHash>>,
+) -> Result<(), LmdbExtError> {
+    if blocks_by_era.is_empty() {
+        return Ok(());
+    }
+    info!("initializing execution results era database");
+    let mut txn = env.begin_rw_txn()?;
+    for (era_id, mut block_hashes) in blocks_by_era {
+        let key = era_id.value().to_be_bytes();
+        let mut tagged: Vec<BlockHash> = txn
+            .get_value(*execution_results_era_db, &key)?
+            .unwrap_or_default();
+        block_hashes.retain(|block_hash| !tagged.contains(block_hash));
+        if block_hashes.is_empty() {
+            continue;
+        }
+        tagged.extend(block_hashes);
+        let _ = txn.put_value(*execution_results_era_db, &key, &tagged, true)?;
+    }
+    txn.commit()?;
+
+    info!("execution results era database initialized");
+    Ok(())
+}
@@ -0,0 +1,408 @@
+//! Backup and restore of the storage databases.
+//!
+//! A backup covers both LMDB environments in the storage directory: the storage component's
+//! databases and the contract runtime's global state.  Every database of an environment is copied
+//! within a single read transaction, so the copy is consistent even if a node is writing to the
+//! storage at the same time.  The global state is only ever added to, so copying it after the
+//! storage databases ensures the backup contains the global state of every block in it.
+//!
+//! Copies are verified before they are reported as complete: every block header must be stored
+//! under its own hash and have a matching body, block heights must be contiguous, every deploy and
+//! transfer referenced by a block must be present, and every trie of the global state must be
+//! stored under its own hash.  A backup is verified again before it is restored, and can only be
+//! restored into a data directory which doesn't contain a database yet.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use lmdb::{Cursor, DatabaseFlags, Environment, EnvironmentFlags, Transaction, WriteFlags};
+use thiserror::Error;
+use tracing::info;
+
+use casper_execution_engine::{
+    shared::newtypes::Blake2bHash,
+    storage::{transaction_source::lmdb::EE_DB_FILENAME, trie_store},
+};
+
+use super::{
+    lmdb_ext::{self, EnvironmentExt, LmdbExtError, TransactionExt},
+    Config, MAX_DB_COUNT, MAX_TRANSACTIONS, OS_FLAGS, STORAGE_DB_FILENAME,
+};
+use crate::{
+    components::contract_runtime::Config as ContractRuntimeConfig,
+    types::{BlockBody, BlockHash, BlockHeader, DeployHash},
+    utils::WithDir,
+};
+
+/// The number of entries written to the destination per transaction, and between two progress
+/// reports.
+const BATCH_SIZE: usize = 10_000;
+
+/// An error backing up or restoring the storage databases.
+#[derive(Debug, Error)]
+pub enum BackupError {
+    /// The database to be copied doesn't exist.
+    #[error("no storage database found at `{}`", .0.display())]
+    SourceNotFound(PathBuf),
+    /// The destination already contains a database.
+    #[error("`{}` already exists, refusing to overwrite it", .0.display())]
+    DestinationExists(PathBuf),
+    /// Failure to create the destination directory.
+    #[error("failed to create directory `{}`: {}", .0.display(), .1)]
+    CreateDirectory(PathBuf, io::Error),
+    /// LMDB error while copying or verifying.
+    #[error("database error: {0}")]
+    Lmdb(#[from] LmdbExtError),
+    /// A block header is not stored under its own hash.
+    #[error("block header at height {height} is stored under a different hash than {block_hash}")]
+    InvalidBlockHash {
+        /// The height of the block.
+        height: u64,
+        /// The actual hash of the block header.
+        block_hash: BlockHash,
+    },
+    /// The body of a block is missing.
+    #[error("missing body of block {0}")]
+    MissingBlockBody(BlockHash),
+    /// The body of a block doesn't match the body hash in its header.
+    #[error("invalid body of block {0}")]
+    InvalidBlockBody(BlockHash),
+    /// Two blocks are stored at the same height.
+    #[error("duplicate blocks at height {height}: {first} / {second}")]
+    DuplicateBlockHeight {
+        /// The height of the blocks.
+        height: u64,
+        /// The first block encountered at `height`.
+        first: BlockHash,
+        /// The second block encountered at `height`.
+        second: BlockHash,
+    },
+    /// No block is stored at a height between the lowest and highest stored blocks.
+    #[error("missing block at height {0}")]
+    MissingBlockHeight(u64),
+    /// A deploy or transfer referenced by a block is missing.
+    #[error("missing deploy {deploy_hash} of block {block_hash}")]
+    MissingDeploy {
+        /// The block referencing the deploy.
+        block_hash: BlockHash,
+        /// The missing deploy.
+        deploy_hash: DeployHash,
+    },
+    /// A trie of the global state is not stored under its own hash.
+    #[error("global state trie with hash {0} is stored under a different key")]
+    InvalidTrieHash(Blake2bHash),
+}
+
+impl From<lmdb::Error> for BackupError {
+    fn from(err: lmdb::Error) -> Self {
+        LmdbExtError::from(err).into()
+    }
+}
+
+/// A summary of the contents of verified storage databases.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StorageSummary {
+    /// The number of blocks.
+    pub blocks: usize,
+    /// The lowest and highest block heights, if there are any blocks.
+    pub heights: Option<(u64, u64)>,
+    /// The number of deploys.
+    pub deploys: usize,
+    /// The number of tries in the global state.
+    pub tries: usize,
+}
+
+impl Display for StorageSummary {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{} blocks", self.blocks)?;
+        if let Some((lowest, highest)) = self.heights {
+            write!(formatter, " (heights {} to {})", lowest, highest)?;
+        }
+        write!(
+            formatter,
+            ", {} deploys, {} global state tries",
+            self.deploys, self.tries
+        )
+    }
+}
+
+/// Returns the file names and maximum sizes of the LMDB environments in a storage directory, in
+/// the order in which they are copied.
+fn environment_files(
+    config: &Config,
+    contract_runtime_config: &ContractRuntimeConfig,
+) -> [(&'static str, usize); 2] {
+    [
+        (STORAGE_DB_FILENAME, config.map_size()),
+        (
+            EE_DB_FILENAME,
+            contract_runtime_config.max_global_state_size(),
+        ),
+    ]
+}
+
+/// The LMDB environments in a storage directory.
+struct Environments {
+    /// The storage component's databases.
+    storage: Environment,
+    /// The contract runtime's global state.
+    global_state: Environment,
+}
+
+impl Environments {
+    /// Opens the existing environments in the given directory for reading.
+    fn open(
+        dir: &Path,
+        config: &Config,
+        contract_runtime_config: &ContractRuntimeConfig,
+    ) -> Result<Self, BackupError> {
+        let [storage, global_state] = environment_files(config, contract_runtime_config);
+        for (filename, _) in &[storage, global_state] {
+            let path = dir.join(filename);
+            if !path.is_file() {
+                return Err(BackupError::SourceNotFound(path));
+            }
+        }
+        Ok(Environments {
+            storage: open_environment(&dir.join(storage.0), storage.1, true)?,
+            global_state: open_environment(&dir.join(global_state.0), global_state.1, true)?,
+        })
+    }
+
+    /// Creates new environments in the given directory, which must not contain any of them yet.
+    fn create(
+        dir: &Path,
+        config: &Config,
+        contract_runtime_config: &ContractRuntimeConfig,
+    ) -> Result<Self, BackupError> {
+        let [storage, global_state] = environment_files(config, contract_runtime_config);
+        for (filename, _) in &[storage, global_state] {
+            let path = dir.join(filename);
+            if path.exists() {
+                return Err(BackupError::DestinationExists(path));
+            }
+        }
+        fs::create_dir_all(dir).map_err(|err| BackupError::CreateDirectory(dir.to_owned(), err))?;
+        Ok(Environments {
+            storage: open_environment(&dir.join(storage.0), storage.1, false)?,
+            global_state: open_environment(&dir.join(global_state.0), global_state.1, false)?,
+        })
+    }
+
+    /// Copies all databases into the empty `destination`.
+    ///
+    /// The storage databases are copied before the global state, so that the copied global state
+    /// contains the state of every copied block.
+    fn copy_to(&self, destination: &Environments) -> Result<(), BackupError> {
+        copy_databases(&self.storage, &destination.storage)?;
+        copy_databases(&self.global_state, &destination.global_state)
+    }
+
+    /// Checks the integrity of the environments.
+    fn verify(&self) -> Result<StorageSummary, BackupError> {
+        let mut summary = verify(&self.storage)?;
+        summary.tries = verify_global_state(&self.global_state)?;
+        Ok(summary)
+    }
+}
+
+/// Creates a consistent backup of the storage databases and the global state in the given
+/// directory, and verifies it.
+pub fn backup(
+    config: &WithDir<Config>,
+    contract_runtime_config: &ContractRuntimeConfig,
+    backup_dir: &Path,
+) -> Result<StorageSummary, BackupError> {
+    let root = config.with_dir(config.value().path.clone());
+    let source = Environments::open(&root, config.value(), contract_runtime_config)?;
+    let destination = Environments::create(backup_dir, config.value(), contract_runtime_config)?;
+
+    info!(source = %root.display(), "backing up storage");
+    source.copy_to(&destination)?;
+    destination.verify()
+}
+
+/// Verifies the backup in the given directory, and restores it into the empty storage directory.
+pub fn restore(
+    backup_dir: &Path,
+    config: &WithDir<Config>,
+    contract_runtime_config: &ContractRuntimeConfig,
+) -> Result<StorageSummary, BackupError> {
+    let source = Environments::open(backup_dir, config.value(), contract_runtime_config)?;
+
+    info!(source = %backup_dir.display(), "verifying backup");
+    let summary = source.verify()?;
+    info!(%summary, "backup verified");
+
+    let root = config.with_dir(config.value().path.clone());
+    let destination = Environments::create(&root, config.value(), contract_runtime_config)?;
+    source.copy_to(&destination)?;
+    destination.verify()
+}
+
+/// Opens the LMDB environment at the given path.
+fn open_environment(
+    path: &Path,
+    map_size: usize,
+    read_only: bool,
+) -> Result<Environment, BackupError> {
+    let flags = if read_only {
+        EnvironmentFlags::READ_ONLY
+    } else {
+        OS_FLAGS
+    };
+    let env = Environment::new()
+        .set_flags(flags | EnvironmentFlags::NO_SUB_DIR | EnvironmentFlags::NO_TLS)
+        .set_max_readers(MAX_TRANSACTIONS)
+        .set_max_dbs(MAX_DB_COUNT)
+        .set_map_size(map_size)
+        .open(path)?;
+    Ok(env)
+}
+
+/// Copies all named databases from `source` into the empty environment `destination`.
+///
+/// All databases are read in a single transaction, so the copy is a consistent snapshot.
+fn copy_databases(source: &Environment, destination: &Environment) -> Result<(), BackupError> {
+    // Databases have to be opened before starting the read transaction, since opening them
+    // requires a transaction slot of its own.
+    let mut databases = vec![];
+    for name in database_names(source)? {
+        let source_db = source.open_db(Some(&name))?;
+        let destination_db = destination.create_db(Some(&name), DatabaseFlags::empty())?;
+        databases.push((name, source_db, destination_db));
+    }
+
+    let source_txn = source.begin_ro_txn_with_retry()?;
+    for (name, source_db, destination_db) in databases {
+        let entries = source_txn.stat(source_db)?.entries();
+        info!(database = %name, %entries, "copying database");
+        let mut cursor = source_txn.open_ro_cursor(source_db)?;
+        let mut destination_txn = destination.begin_rw_txn()?;
+        let mut copied = 0;
+        for (key, value) in cursor.iter() {
+            // Entries are read in key order, so they can be appended.
+            destination_txn.put(destination_db, &key, &value, WriteFlags::APPEND)?;
+            copied += 1;
+            if copied % BATCH_SIZE == 0 {
+                destination_txn.commit()?;
+                destination_txn = destination.begin_rw_txn()?;
+                info!(database = %name, %copied, %entries, "copying database");
+            }
+        }
+        destination_txn.commit()?;
+        info!(database = %name, %copied, "copied database");
+    }
+    Ok(())
+}
+
+/// Returns the names of all named databases in the environment.
+fn database_names(env: &Environment) -> Result<Vec<String>, BackupError> {
+    // The keys of the unnamed database are the names of all other databases.
+    let main_db = env.open_db(None)?;
+    let txn = env.begin_ro_txn_with_retry()?;
+    let mut cursor = txn.open_ro_cursor(main_db)?;
+    let names = cursor
+        .iter()
+        .map(|(name, _)| String::from_utf8_lossy(name).into_owned())
+        .collect();
+    Ok(names)
+}
+
+/// Checks the integrity of the blocks and deploys in the storage environment.
+fn verify(env: &Environment) -> Result<StorageSummary, BackupError> {
+    let block_header_db = env.open_db(Some("block_header"))?;
+    let block_body_db = env.open_db(Some("block_body"))?;
+    let deploy_db = env.open_db(Some("deploys"))?;
+
+    let txn = env.begin_ro_txn_with_retry()?;
+    let mut block_height_index = BTreeMap::new();
+    let mut cursor = txn.open_ro_cursor(block_header_db)?;
+    for (raw_key, raw_value) in cursor.iter() {
+        let block_header: BlockHeader = lmdb_ext::deserialize(raw_value)?;
+        let block_hash = block_header.hash();
+        let height = block_header.height();
+        if raw_key != block_hash.as_ref() {
+            return Err(BackupError::InvalidBlockHash { height, block_hash });
+        }
+
+        let block_body: BlockBody = txn
+            .get_value(block_body_db, block_header.body_hash())?
+            .ok_or(BackupError::MissingBlockBody(block_hash))?;
+        if block_body.hash() != *block_header.body_hash() {
+            return Err(BackupError::InvalidBlockBody(block_hash));
+        }
+        for deploy_hash in block_body
+            .deploy_hashes()
+            .iter()
+            .chain(block_body.transfer_hashes())
+        {
+            match txn.get(deploy_db, deploy_hash) {
+                Ok(_) => (),
+                Err(lmdb::Error::NotFound) => {
+                    return Err(BackupError::MissingDeploy {
+                        block_hash,
+                        deploy_hash: *deploy_hash,
+                    })
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        if let Some(first) = block_height_index.insert(height, block_hash) {
+            return Err(BackupError::DuplicateBlockHeight {
+                height,
+                first,
+                second: block_hash,
+            });
+        }
+        if block_height_index.len() % BATCH_SIZE == 0 {
+            info!(blocks = block_height_index.len(), "verifying blocks");
+        }
+    }
+
+    let heights = block_height_index
+        .keys()
+        .next()
+        .zip(block_height_index.keys().next_back())
+        .map(|(lowest, highest)| (*lowest, *highest));
+    if let Some((lowest, _)) = heights {
+        for (expected_height, height) in (lowest..).zip(block_height_index.keys()) {
+            if *height != expected_height {
+                return Err(BackupError::MissingBlockHeight(expected_height));
+            }
+        }
+    }
+
+    Ok(StorageSummary {
+        blocks: block_height_index.len(),
+        heights,
+        deploys: txn.stat(deploy_db)?.entries(),
+        tries: 0,
+    })
+}
+
+/// Checks that every trie in the global state environment is stored under its own hash, and
+/// returns the number of tries.
+fn verify_global_state(env: &Environment) -> Result<usize, BackupError> {
+    let trie_db = env.open_db(Some(trie_store::NAME))?;
+
+    let txn = env.begin_ro_txn_with_retry()?;
+    let mut cursor = txn.open_ro_cursor(trie_db)?;
+    let mut tries = 0;
+    for (raw_key, raw_value) in cursor.iter() {
+        let trie_hash = Blake2bHash::new(raw_value);
+        if trie_hash.as_ref() != raw_key {
+            return Err(BackupError::InvalidTrieHash(trie_hash));
+        }
+        tries += 1;
+        if tries % BATCH_SIZE == 0 {
+            info!(%tries, "verifying global state");
+        }
+    }
+    Ok(tries)
+}
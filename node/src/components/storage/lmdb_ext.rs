@@ -11,9 +11,13 @@
 //! interchange of the serialization format if desired.
 
 use crate::{crypto::hash::Digest, types::BlockHash};
-use lmdb::{Database, RwTransaction, Transaction, WriteFlags};
+use lmdb::{Database, Environment, RoTransaction, RwTransaction, Transaction, WriteFlags};
 use serde::{de::DeserializeOwned, Serialize};
 use thiserror::Error;
+use tracing::warn;
+
+/// Number of times beginning a transaction is retried after a transient error.
+const TXN_BEGIN_RETRIES: u32 = 4;
 
 /// Error wrapper for lower-level storage errors.
 ///
@@ -90,6 +94,71 @@ impl From<lmdb::Error> for LmdbExtError {
     }
 }
 
+impl LmdbExtError {
+    /// Returns `true` if the error indicates that the stored data can no longer be trusted, in
+    /// which case restarting the node alone will not help.
+    pub(super) fn is_corruption(&self) -> bool {
+        match self {
+            LmdbExtError::LmdbCorrupted(_)
+            | LmdbExtError::DataCorrupted(_)
+            | LmdbExtError::BlockHeaderNotStoredUnderItsHash { .. }
+            | LmdbExtError::BlockBodyNotStoredUnderItsHash { .. } => true,
+            LmdbExtError::ResourceExhausted(_) | LmdbExtError::Other(_) => false,
+        }
+    }
+}
+
+/// Returns `true` if the error is likely to go away on its own, e.g. because another reader slot
+/// becomes available or an interrupted system call can simply be repeated.
+fn is_transient(lmdb_error: &lmdb::Error) -> bool {
+    match lmdb_error {
+        lmdb::Error::ReadersFull | lmdb::Error::TlsFull => true,
+        lmdb::Error::Other(code) => {
+            *code == libc::EINTR || *code == libc::EAGAIN || *code == libc::EBUSY
+        }
+        _ => false,
+    }
+}
+
+/// Calls `begin` until it succeeds, fails with a non-transient error or runs out of retries.
+///
+/// Retries happen immediately, as the storage component must never block the reactor.  Errors
+/// which persist are reported to the caller, which may retry the whole request later.
+fn retry_transient<T, F>(mut begin: F) -> Result<T, LmdbExtError>
+where
+    F: FnMut() -> Result<T, lmdb::Error>,
+{
+    let mut attempt = 0;
+    loop {
+        match begin() {
+            Err(error) if is_transient(&error) && attempt < TXN_BEGIN_RETRIES => {
+                attempt += 1;
+                warn!(%error, attempt, "transient storage error, retrying");
+            }
+            result => return result.map_err(LmdbExtError::from),
+        }
+    }
+}
+
+/// Additional methods on the environment.
+pub(super) trait EnvironmentExt {
+    /// Begins a read-only transaction, retrying on transient errors.
+    fn begin_ro_txn_with_retry(&self) -> Result<RoTransaction<'_>, LmdbExtError>;
+
+    /// Begins a read-write transaction, retrying on transient errors.
+    fn begin_rw_txn_with_retry(&self) -> Result<RwTransaction<'_>, LmdbExtError>;
+}
+
+impl EnvironmentExt for Environment {
+    fn begin_ro_txn_with_retry(&self) -> Result<RoTransaction<'_>, LmdbExtError> {
+        retry_transient(|| self.begin_ro_txn())
+    }
+
+    fn begin_rw_txn_with_retry(&self) -> Result<RwTransaction<'_>, LmdbExtError> {
+        retry_transient(|| self.begin_rw_txn())
+    }
+}
+
 /// Additional methods on transaction.
 pub(super) trait TransactionExt {
     /// Helper function to load a value from a database.
@@ -171,3 +240,44 @@ pub(super) fn deserialize<T: DeserializeOwned>(raw: &[u8]) -> Result<T, LmdbExtE
 pub(super) fn serialize<T: Serialize>(value: &T) -> Result<Vec<u8>, LmdbExtError> {
     bincode::serialize(value).map_err(|err| LmdbExtError::Other(Box::new(err)))
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    #[test]
+    fn should_retry_only_transient_errors() {
+        let attempts = Cell::new(0);
+        let result = retry_transient(|| {
+            attempts.set(attempts.get() + 1);
+            if attempts.get() < 3 {
+                Err(lmdb::Error::ReadersFull)
+            } else {
+                Ok(())
+            }
+        });
+        assert!(result.is_ok());
+        assert_eq!(attempts.get(), 3);
+
+        attempts.set(0);
+        let result: Result<(), _> = retry_transient(|| {
+            attempts.set(attempts.get() + 1);
+            Err(lmdb::Error::Corrupted)
+        });
+        assert!(result.unwrap_err().is_corruption());
+        assert_eq!(attempts.get(), 1);
+
+        attempts.set(0);
+        let result: Result<(), _> = retry_transient(|| {
+            attempts.set(attempts.get() + 1);
+            Err(lmdb::Error::ReadersFull)
+        });
+        assert!(matches!(
+            result,
+            Err(LmdbExtError::ResourceExhausted(lmdb::Error::ReadersFull))
+        ));
+        assert_eq!(attempts.get(), TXN_BEGIN_RETRIES + 1);
+    }
+}
@@ -0,0 +1,175 @@
+use lmdb::{Database, Environment, Transaction};
+use prometheus::{self, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry};
+
+use crate::unregister_metric;
+
+/// Label distinguishing the individual LMDB databases.
+const DATABASE_LABEL: &str = "database";
+
+/// Metrics of a single LMDB database.
+#[derive(Debug)]
+struct DatabaseMetrics {
+    /// The database handle.
+    db: Database,
+    /// Number of pages used by the database, including branch, leaf and overflow pages.
+    pages: IntGauge,
+    /// Number of entries in the database.
+    entries: IntGauge,
+    /// Total number of values read from the database.
+    gets: IntCounter,
+    /// Total number of values written to the database.
+    puts: IntCounter,
+}
+
+/// Metrics for the storage component.
+#[derive(Debug)]
+pub(super) struct StorageMetrics {
+    /// Number of pages used, by database.
+    db_pages: IntGaugeVec,
+    /// Number of entries, by database.
+    db_entries: IntGaugeVec,
+    /// Total number of values read, by database.
+    db_gets: IntCounterVec,
+    /// Total number of values written, by database.
+    db_puts: IntCounterVec,
+    /// Size of the memory map in bytes.
+    map_size: IntGauge,
+    /// Number of pages allocated in the data file.
+    allocated_pages: IntGauge,
+    /// Number of allocated pages which are free for reuse.
+    free_pages: IntGauge,
+    /// The per-database metrics.
+    databases: Vec<DatabaseMetrics>,
+    /// Registry stored to allow deregistration later.
+    registry: Registry,
+}
+
+impl StorageMetrics {
+    /// Creates a new instance of the storage metrics for the given named databases.
+    pub(super) fn new(
+        registry: &Registry,
+        databases: &[(&str, Database)],
+    ) -> Result<Self, prometheus::Error> {
+        let db_pages = IntGaugeVec::new(
+            Opts::new(
+                "storage_db_pages",
+                "number of pages used by each storage database",
+            ),
+            &[DATABASE_LABEL],
+        )?;
+        let db_entries = IntGaugeVec::new(
+            Opts::new(
+                "storage_db_entries",
+                "number of entries in each storage database",
+            ),
+            &[DATABASE_LABEL],
+        )?;
+        let db_gets = IntCounterVec::new(
+            Opts::new(
+                "storage_db_gets",
+                "total number of values read from each storage database",
+            ),
+            &[DATABASE_LABEL],
+        )?;
+        let db_puts = IntCounterVec::new(
+            Opts::new(
+                "storage_db_puts",
+                "total number of values written to each storage database",
+            ),
+            &[DATABASE_LABEL],
+        )?;
+        let map_size = IntGauge::new(
+            "storage_map_size_bytes",
+            "size of the memory map of the storage environment in bytes",
+        )?;
+        let allocated_pages = IntGauge::new(
+            "storage_allocated_pages",
+            "number of pages allocated in the storage data file",
+        )?;
+        let free_pages = IntGauge::new(
+            "storage_free_pages",
+            "number of allocated pages in the storage data file which are free for reuse",
+        )?;
+
+        let databases = databases
+            .iter()
+            .map(|(name, db)| DatabaseMetrics {
+                db: *db,
+                pages: db_pages.with_label_values(&[name]),
+                entries: db_entries.with_label_values(&[name]),
+                gets: db_gets.with_label_values(&[name]),
+                puts: db_puts.with_label_values(&[name]),
+            })
+            .collect();
+
+        registry.register(Box::new(db_pages.clone()))?;
+        registry.register(Box::new(db_entries.clone()))?;
+        registry.register(Box::new(db_gets.clone()))?;
+        registry.register(Box::new(db_puts.clone()))?;
+        registry.register(Box::new(map_size.clone()))?;
+        registry.register(Box::new(allocated_pages.clone()))?;
+        registry.register(Box::new(free_pages.clone()))?;
+
+        Ok(StorageMetrics {
+            db_pages,
+            db_entries,
+            db_gets,
+            db_puts,
+            map_size,
+            allocated_pages,
+            free_pages,
+            databases,
+            registry: registry.clone(),
+        })
+    }
+
+    /// Counts a value read from the given database.
+    pub(super) fn record_get(&self, db: Database) {
+        if let Some(metrics) = self.database(db) {
+            metrics.gets.inc();
+        }
+    }
+
+    /// Counts a value written to the given database.
+    pub(super) fn record_put(&self, db: Database) {
+        if let Some(metrics) = self.database(db) {
+            metrics.puts.inc();
+        }
+    }
+
+    /// Updates the page usage and size gauges from the statistics reported by LMDB.
+    pub(super) fn update_stats(&self, env: &Environment) -> Result<(), lmdb::Error> {
+        let txn = env.begin_ro_txn()?;
+        for metrics in &self.databases {
+            let stat = txn.stat(metrics.db)?;
+            let pages = stat.branch_pages() + stat.leaf_pages() + stat.overflow_pages();
+            metrics.pages.set(pages as i64);
+            metrics.entries.set(stat.entries() as i64);
+        }
+        txn.commit()?;
+
+        let info = env.info()?;
+        self.map_size.set(info.map_size() as i64);
+        // Page numbers start at zero, so the number of allocated pages is one more than the last.
+        self.allocated_pages.set(info.last_pgno() as i64 + 1);
+        self.free_pages.set(env.freelist()? as i64);
+        Ok(())
+    }
+
+    /// Returns the metrics of the given database, if it is known.
+    fn database(&self, db: Database) -> Option<&DatabaseMetrics> {
+        self.databases.iter().find(|metrics| metrics.db == db)
+    }
+}
+
+impl Drop for StorageMetrics {
+    fn drop(&mut self) {
+        unregister_metric!(self.registry, self.db_pages);
+        unregister_metric!(self.registry, self.db_entries);
+        unregister_metric!(self.registry, self.db_gets);
+        unregister_metric!(self.registry, self.db_puts);
+        unregister_metric!(self.registry, self.map_size);
+        unregister_metric!(self.registry, self.allocated_pages);
+        unregister_metric!(self.registry, self.free_pages);
+    }
+}
@@ -0,0 +1,108 @@
+//! In-memory index of the pending deploys database.
+//!
+//! The index tracks the expiry, account and serialized size of every pending deploy, so that
+//! expired deploys can be purged and the store limits enforced without reading the database.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use datasize::DataSize;
+
+use casper_types::account::AccountHash;
+
+use crate::types::{DeployHash, Timestamp};
+
+/// The indexed properties of a single pending deploy.
+#[derive(DataSize, Debug)]
+struct IndexedDeploy {
+    account_hash: AccountHash,
+    expires: Timestamp,
+    size: usize,
+}
+
+/// Index of the pending deploys, by hash, expiry and account.
+#[derive(DataSize, Debug, Default)]
+pub(super) struct PendingDeployIndex {
+    /// The indexed deploys by hash.
+    deploys: BTreeMap<DeployHash, IndexedDeploy>,
+    /// The deploy hashes ordered by expiry.
+    by_expiry: BTreeSet<(Timestamp, DeployHash)>,
+    /// The number of pending deploys of each account.
+    count_by_account: BTreeMap<AccountHash, usize>,
+    /// The total serialized size of all pending deploys.
+    total_size: usize,
+}
+
+impl PendingDeployIndex {
+    /// Adds a deploy to the index, replacing any previous entry for the same hash.
+    pub(super) fn insert(
+        &mut self,
+        deploy_hash: DeployHash,
+        account_hash: AccountHash,
+        expires: Timestamp,
+        size: usize,
+    ) {
+        self.remove(&deploy_hash);
+        let _ = self.by_expiry.insert((expires, deploy_hash));
+        *self.count_by_account.entry(account_hash).or_default() += 1;
+        self.total_size += size;
+        let _ = self.deploys.insert(
+            deploy_hash,
+            IndexedDeploy {
+                account_hash,
+                expires,
+                size,
+            },
+        );
+    }
+
+    /// Removes a deploy from the index.  Returns `true` if it was indexed.
+    pub(super) fn remove(&mut self, deploy_hash: &DeployHash) -> bool {
+        let indexed = match self.deploys.remove(deploy_hash) {
+            Some(indexed) => indexed,
+            None => return false,
+        };
+        let _ = self.by_expiry.remove(&(indexed.expires, *deploy_hash));
+        if let Some(count) = self.count_by_account.get_mut(&indexed.account_hash) {
+            *count -= 1;
+            if *count == 0 {
+                let _ = self.count_by_account.remove(&indexed.account_hash);
+            }
+        }
+        self.total_size -= indexed.size;
+        true
+    }
+
+    /// Returns the hashes of all deploys which have expired at time `now`.
+    pub(super) fn expired(&self, now: Timestamp) -> Vec<DeployHash> {
+        // A deploy has expired if its expiry lies strictly before `now`.
+        self.by_expiry
+            .iter()
+            .take_while(|(expires, _)| *expires < now)
+            .map(|(_, deploy_hash)| *deploy_hash)
+            .collect()
+    }
+
+    /// Returns the number of pending deploys of the given account, not counting `excluded`.
+    pub(super) fn count_for_account(
+        &self,
+        account_hash: &AccountHash,
+        excluded: &DeployHash,
+    ) -> usize {
+        let count = self
+            .count_by_account
+            .get(account_hash)
+            .copied()
+            .unwrap_or_default();
+        if self.deploys.contains_key(excluded) {
+            count.saturating_sub(1)
+        } else {
+            count
+        }
+    }
+
+    /// Returns the total serialized size of all pending deploys, not counting `excluded`.
+    pub(super) fn total_size(&self, excluded: &DeployHash) -> usize {
+        let excluded_size = self.deploys.get(excluded).map_or(0, |indexed| indexed.size);
+        self.total_size - excluded_size
+    }
+}
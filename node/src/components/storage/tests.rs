@@ -1,17 +1,38 @@
 //! Unit tests for the storage component.
 
-use std::{borrow::Cow, collections::HashMap};
+use std::{borrow::Cow, collections::HashMap, iter};
 
 use lmdb::Transaction;
+use prometheus::Registry;
 use rand::{prelude::SliceRandom, Rng};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use smallvec::smallvec;
 
-use casper_types::{EraId, ExecutionResult, ProtocolVersion, PublicKey, SecretKey};
+use casper_execution_engine::{
+    core::engine_state::executable_deploy_item::ExecutableDeployItem,
+    shared::{
+        additive_map::AdditiveMap,
+        newtypes::{Blake2bHash, CorrelationId},
+        stored_value::StoredValue,
+        transform::Transform as GlobalStateTransform,
+    },
+    storage::global_state::{CommitResult, StateProvider, StateReader},
+};
+use casper_types::{
+    account::AccountHash,
+    bytesrepr::Bytes,
+    system::auction::{EraInfo, SeigniorageAllocation},
+    AccessRights, CLValue, ContractHash, EraId, ExecutionEffect, ExecutionResult, JournalEntry,
+    Key, OpKind, ProtocolVersion, PublicKey, SecretKey, Transfer, TransferAddr, Transform,
+    TransformEntry, URef, U512,
+};
 
-use super::{Config, Storage};
+use super::{BackupError, Config, PendingDeployStoreFull, Storage};
 use crate::{
-    components::storage::lmdb_ext::WriteTransactionExt,
+    components::{
+        contract_runtime::{self, Config as ContractRuntimeConfig},
+        storage::lmdb_ext::WriteTransactionExt,
+    },
     crypto::AsymmetricKeyExt,
     effect::{
         requests::{StateStoreRequest, StorageRequest},
@@ -19,8 +40,9 @@ use crate::{
     },
     testing::{ComponentHarness, TestRng, UnitTestEvent},
     types::{
-        Block, BlockHash, BlockHeader, BlockSignatures, Deploy, DeployHash, DeployMetadata,
-        FinalitySignature,
+        Block, BlockHash, BlockHeader, BlockIdentifier, BlockSignatures, BlockWithMetadata,
+        ContractEventRecord, Deploy, DeployHash, DeployMetadata, EraRewards,
+        ExecutionResultSummary, FinalitySignature, LegacyDeploy, TimeDiff, Timestamp,
     },
     utils::WithDir,
 };
@@ -35,6 +57,10 @@ fn new_config(harness: &ComponentHarness<UnitTestEvent>) -> Config {
         max_deploy_store_size: 50 * MIB,
         max_deploy_metadata_store_size: 50 * MIB,
         max_state_store_size: 50 * MIB,
+        execution_results_retention_eras: None,
+        block_read_ahead: 0,
+        max_pending_deploys_per_account: 3,
+        max_pending_deploys_size: MIB,
     }
 }
 
@@ -51,6 +77,7 @@ fn storage_fixture(harness: &ComponentHarness<UnitTestEvent>) -> Storage {
         &WithDir::new(harness.tmp.path(), cfg),
         None,
         ProtocolVersion::from_parts(1, 0, 0),
+        &Registry::new(),
     )
     .expect("could not create storage component fixture")
 }
@@ -71,6 +98,7 @@ fn storage_fixture_with_hard_reset(
         &WithDir::new(harness.tmp.path(), cfg),
         Some(reset_era_id),
         ProtocolVersion::from_parts(1, 1, 0),
+        &Registry::new(),
     )
     .expect("could not create storage component fixture")
 }
@@ -82,6 +110,16 @@ fn random_block_at_height(rng: &mut TestRng, height: u64) -> Box<Block> {
     block
 }
 
+/// Creates a random non-switch block in the given era, containing at least one deploy.
+fn random_block_with_deploys(rng: &mut TestRng, era_id: u64, height: u64) -> Box<Block> {
+    loop {
+        let block = Block::random_with_specifics(rng, EraId::from(era_id), height, false);
+        if !block.deploy_hashes().is_empty() {
+            return Box::new(block);
+        }
+    }
+}
+
 /// Creates 3 random signatures for the given block.
 fn random_signatures(rng: &mut TestRng, block: &Block) -> BlockSignatures {
     let block_hash = *block.hash();
@@ -126,6 +164,93 @@ fn get_block_at_height(
     response
 }
 
+/// Requests the blocks proposed by a validator in an era from a storage component.
+fn get_blocks_by_proposer(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    public_key: PublicKey,
+    era_id: EraId,
+) -> Vec<Block> {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::GetBlocksByProposer {
+            public_key,
+            era_id,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
+/// Requests the hashes of deploys submitted by an account from a storage component.
+fn get_deploys_by_account(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    account_hash: AccountHash,
+    limit: usize,
+    offset: usize,
+) -> Vec<DeployHash> {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::GetDeploysByAccount {
+            account_hash,
+            limit,
+            offset,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
+/// Loads a pending deploy from a storage component.
+fn get_pending_deploy(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    deploy_hash: DeployHash,
+) -> Option<Box<Deploy>> {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::GetPendingDeploy {
+            deploy_hash,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
+/// Stores a pending deploy in a storage component.
+fn put_pending_deploy(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    deploy: Box<Deploy>,
+) -> Result<(), PendingDeployStoreFull> {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::PutPendingDeploy { deploy, responder }.into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
+/// Removes a pending deploy from a storage component.
+fn remove_pending_deploy(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    deploy_hash: DeployHash,
+) -> bool {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::RemovePendingDeploy {
+            deploy_hash,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
 /// Loads a block from a storage component.
 fn get_block(
     harness: &mut ComponentHarness<UnitTestEvent>,
@@ -194,6 +319,40 @@ fn get_deploy_and_metadata(
     response
 }
 
+/// Requests the execution results of all deploys of a block from a storage component.
+fn get_execution_results_by_block(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    block_hash: BlockHash,
+) -> Option<Vec<(DeployHash, ExecutionResult)>> {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::GetExecutionResultsByBlock {
+            block_hash,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
+/// Requests a block with its full metadata from a storage component.
+fn get_block_with_metadata(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    block_hash: BlockHash,
+) -> Option<BlockWithMetadata> {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::GetBlockWithMetadata {
+            block_hash,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
 /// Requests the highest block from a storage component.
 fn get_highest_block(
     harness: &mut ComponentHarness<UnitTestEvent>,
@@ -206,6 +365,40 @@ fn get_highest_block(
     response
 }
 
+/// Requests a block header by its identifier from a storage component.
+fn get_block_header_by_identifier(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    block_identifier: BlockIdentifier,
+) -> Option<BlockHeader> {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::GetBlockHeaderByIdentifier {
+            block_identifier,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
+/// Requests a block and its signatures by the block's identifier from a storage component.
+fn get_block_and_metadata_by_identifier(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    block_identifier: BlockIdentifier,
+) -> Option<(Block, BlockSignatures)> {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::GetBlockAndMetadataByIdentifier {
+            block_identifier,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
 /// Loads state from the storage component.
 fn load_state<T>(
     harness: &mut ComponentHarness<UnitTestEvent>,
@@ -237,6 +430,27 @@ fn put_block(
     response
 }
 
+/// Stores a block together with its execution results and contract events in a storage component.
+fn put_executed_block(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    block: Box<Block>,
+    execution_results: HashMap<DeployHash, ExecutionResult>,
+    contract_events: Vec<ContractEventRecord>,
+) -> bool {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::PutExecutedBlock {
+            block,
+            execution_results,
+            contract_events,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response.expect("storing an executed block failed")
+}
+
 /// Stores a block's signatures in a storage component.
 fn put_block_signatures(
     harness: &mut ComponentHarness<UnitTestEvent>,
@@ -286,6 +500,108 @@ fn put_execution_results(
     response
 }
 
+/// Requests the transfers of a block from a storage component.
+fn get_block_transfers(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    block_hash: BlockHash,
+) -> Option<Vec<Transfer>> {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::GetBlockTransfers {
+            block_hash,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
+/// Requests the events emitted by a contract in a range of blocks from a storage component.
+fn get_contract_events(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    contract_hash: ContractHash,
+    from_block: u64,
+    to_block: u64,
+) -> Vec<ContractEventRecord> {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::GetContractEvents {
+            contract_hash,
+            from_block,
+            to_block,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
+/// Stores the rewards distributed at the end of an era in a storage component.
+fn put_era_rewards(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    era_rewards: EraRewards,
+) {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::PutEraRewards {
+            era_rewards: Box::new(era_rewards),
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
+/// Requests the rewards distributed at the end of an era from a storage component.
+fn get_era_rewards(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    era_id: EraId,
+) -> Option<EraRewards> {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::GetEraRewards { era_id, responder }.into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
+/// Stores the journals of executed deploys in a storage component.
+fn put_execution_journals(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    journals: HashMap<DeployHash, Vec<JournalEntry>>,
+) {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::PutExecutionJournals {
+            journals,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
+/// Requests the journal of an executed deploy from a storage component.
+fn get_execution_journal(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    deploy_hash: DeployHash,
+) -> Option<Vec<JournalEntry>> {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::GetExecutionJournal {
+            deploy_hash,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
 /// Saves state from the storage component.
 fn save_state<T>(
     harness: &mut ComponentHarness<UnitTestEvent>,
@@ -331,26 +647,365 @@ fn can_put_and_get_block() {
     let was_new = put_block(&mut harness, &mut storage, block.clone());
     assert!(was_new, "putting block should have returned `true`");
 
-    // Storing the same block again should work, but yield a result of `true`.
-    let was_new_second_time = put_block(&mut harness, &mut storage, block.clone());
-    assert!(
-        was_new_second_time,
-        "storing block the second time should have returned `true`"
+    // Storing the same block again should work, but yield a result of `true`.
+    let was_new_second_time = put_block(&mut harness, &mut storage, block.clone());
+    assert!(
+        was_new_second_time,
+        "storing block the second time should have returned `true`"
+    );
+
+    let response = get_block(&mut harness, &mut storage, *block.hash());
+    assert_eq!(response.as_ref(), Some(&*block));
+
+    // Also ensure we can retrieve just the header.
+    let response = harness.send_request(&mut storage, |responder| {
+        StorageRequest::GetBlockHeader {
+            block_hash: *block.hash(),
+            responder,
+        }
+        .into()
+    });
+
+    assert_eq!(response.as_ref(), Some(block.header()));
+}
+
+#[test]
+fn can_retrieve_blocks_by_proposer() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let block_a = Box::new(Block::random(&mut harness.rng));
+    let block_b = Box::new(Block::random(&mut harness.rng));
+    assert!(put_block(&mut harness, &mut storage, block_a.clone()));
+    assert!(put_block(&mut harness, &mut storage, block_b.clone()));
+
+    let proposer = block_a.body().proposer().clone();
+    let era_id = block_a.header().era_id();
+    assert_eq!(
+        get_blocks_by_proposer(&mut harness, &mut storage, proposer.clone(), era_id),
+        vec![*block_a]
+    );
+
+    // Neither a different era nor an unknown proposer should yield any blocks.
+    assert!(
+        get_blocks_by_proposer(&mut harness, &mut storage, proposer, era_id.successor()).is_empty()
+    );
+    let unknown = PublicKey::from(&SecretKey::random(&mut harness.rng));
+    assert!(get_blocks_by_proposer(&mut harness, &mut storage, unknown, era_id).is_empty());
+}
+
+#[test]
+fn can_retrieve_era_rewards() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let validator = PublicKey::random(&mut harness.rng);
+    let delegator = PublicKey::random(&mut harness.rng);
+    let mut era_info = EraInfo::new();
+    era_info.seigniorage_allocations_mut().extend(vec![
+        SeigniorageAllocation::validator(validator.clone(), U512::from(100)),
+        SeigniorageAllocation::delegator(delegator.clone(), validator.clone(), U512::from(20)),
+        SeigniorageAllocation::delegator(delegator.clone(), validator.clone(), U512::from(5)),
+    ]);
+    let era_rewards = EraRewards::new(EraId::from(3), &era_info);
+
+    // Allocations are summed up per validator and delegator.
+    assert_eq!(era_rewards.total, U512::from(125));
+    assert_eq!(era_rewards.validators.len(), 1);
+    assert_eq!(era_rewards.validators[0].validator_public_key, validator);
+    assert_eq!(era_rewards.validators[0].amount, U512::from(100));
+    assert_eq!(era_rewards.validators[0].delegators.len(), 1);
+    assert_eq!(
+        era_rewards.validators[0].delegators[0].delegator_public_key,
+        delegator
+    );
+    assert_eq!(
+        era_rewards.validators[0].delegators[0].amount,
+        U512::from(25)
+    );
+
+    assert!(get_era_rewards(&mut harness, &mut storage, EraId::from(3)).is_none());
+    put_era_rewards(&mut harness, &mut storage, era_rewards.clone());
+    assert_eq!(
+        get_era_rewards(&mut harness, &mut storage, EraId::from(3)),
+        Some(era_rewards)
+    );
+    assert!(get_era_rewards(&mut harness, &mut storage, EraId::from(4)).is_none());
+}
+
+#[test]
+fn can_retrieve_execution_journals() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let deploy_hash = DeployHash::random(&mut harness.rng);
+    let unknown = DeployHash::random(&mut harness.rng);
+    let journal = vec![
+        JournalEntry {
+            key: Key::Hash(harness.rng.gen()).to_formatted_string(),
+            kind: OpKind::Read,
+            value_type: "Contract".to_string(),
+        },
+        JournalEntry {
+            key: Key::URef(URef::new(harness.rng.gen(), AccessRights::READ_ADD_WRITE))
+                .to_formatted_string(),
+            kind: OpKind::Add,
+            value_type: "U512".to_string(),
+        },
+    ];
+
+    assert!(get_execution_journal(&mut harness, &mut storage, deploy_hash).is_none());
+    let journals = iter::once((deploy_hash, journal.clone())).collect();
+    put_execution_journals(&mut harness, &mut storage, journals);
+    assert_eq!(
+        get_execution_journal(&mut harness, &mut storage, deploy_hash),
+        Some(journal)
+    );
+    assert!(get_execution_journal(&mut harness, &mut storage, unknown).is_none());
+}
+
+#[test]
+fn can_retrieve_contract_events_by_block_range() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let contract_a = ContractHash::new(harness.rng.gen());
+    let contract_b = ContractHash::new(harness.rng.gen());
+
+    let blocks: Vec<_> = (0..3)
+        .map(|height| random_block_at_height(&mut harness.rng, height))
+        .collect();
+
+    let event = |rng: &mut TestRng, block: &Block, contract_hash: ContractHash, topic: &str| {
+        ContractEventRecord {
+            block_hash: *block.hash(),
+            block_height: block.height(),
+            deploy_hash: DeployHash::random(rng),
+            contract_hash,
+            topic: topic.to_string(),
+            data: Bytes::from(topic.as_bytes().to_vec()),
+        }
+    };
+    let event_a0 = event(&mut harness.rng, &blocks[0], contract_a, "first");
+    let event_a1 = event(&mut harness.rng, &blocks[0], contract_a, "second");
+    let event_b1 = event(&mut harness.rng, &blocks[1], contract_b, "other");
+    let event_a2 = event(&mut harness.rng, &blocks[2], contract_a, "third");
+
+    // The events are stored along with the blocks they were emitted in.
+    let events_by_block = vec![
+        vec![event_a0.clone(), event_a1.clone()],
+        vec![event_b1.clone()],
+        vec![event_a2.clone()],
+    ];
+    for (block, events) in blocks.into_iter().zip(events_by_block) {
+        assert!(put_executed_block(
+            &mut harness,
+            &mut storage,
+            block,
+            HashMap::new(),
+            events
+        ));
+    }
+
+    assert_eq!(
+        get_contract_events(&mut harness, &mut storage, contract_a, 0, 2),
+        vec![event_a0.clone(), event_a1.clone(), event_a2.clone()]
+    );
+    assert_eq!(
+        get_contract_events(&mut harness, &mut storage, contract_a, 1, 2),
+        vec![event_a2]
+    );
+    assert_eq!(
+        get_contract_events(&mut harness, &mut storage, contract_b, 0, 10),
+        vec![event_b1]
+    );
+
+    // Blocks outside the range and unknown contracts yield no events.
+    assert!(get_contract_events(&mut harness, &mut storage, contract_a, 3, 10).is_empty());
+    let unknown = ContractHash::new(harness.rng.gen());
+    assert!(get_contract_events(&mut harness, &mut storage, unknown, 0, 2).is_empty());
+}
+
+#[test]
+fn can_retrieve_deploys_by_account() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let secret_key = SecretKey::random(&mut harness.rng);
+    let account_hash = PublicKey::from(&secret_key).to_account_hash();
+    let deploys: Vec<Deploy> = (0..3)
+        .map(|_| {
+            Deploy::new(
+                Timestamp::now(),
+                TimeDiff::from_seconds(60),
+                1,
+                vec![],
+                "casper-example".to_string(),
+                harness.rng.gen(),
+                harness.rng.gen(),
+                &secret_key,
+            )
+        })
+        .collect();
+    for deploy in &deploys {
+        assert!(put_deploy(
+            &mut harness,
+            &mut storage,
+            Box::new(deploy.clone())
+        ));
+    }
+    // Storing a deploy again must not list it twice.
+    assert!(!put_deploy(
+        &mut harness,
+        &mut storage,
+        Box::new(deploys[0].clone())
+    ));
+    // Deploys of other accounts must not be listed.
+    let other_deploy = Box::new(Deploy::random(&mut harness.rng));
+    assert!(put_deploy(&mut harness, &mut storage, other_deploy));
+
+    // The most recently stored deploy is returned first.
+    let newest_first: Vec<DeployHash> = deploys.iter().rev().map(|deploy| *deploy.id()).collect();
+    assert_eq!(
+        get_deploys_by_account(&mut harness, &mut storage, account_hash, 10, 0),
+        newest_first
+    );
+    assert_eq!(
+        get_deploys_by_account(&mut harness, &mut storage, account_hash, 1, 1),
+        newest_first[1..2].to_vec()
+    );
+    assert!(get_deploys_by_account(&mut harness, &mut storage, account_hash, 10, 3).is_empty());
+}
+
+#[test]
+fn can_store_and_remove_pending_deploys() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let secret_key = SecretKey::random(&mut harness.rng);
+    let mut deploy = Deploy::new(
+        Timestamp::now(),
+        TimeDiff::from_seconds(60),
+        1,
+        vec![],
+        "casper-example".to_string(),
+        harness.rng.gen(),
+        harness.rng.gen(),
+        &secret_key,
+    );
+    let deploy_hash = *deploy.id();
+    let expired_deploy = Deploy::new(
+        Timestamp::zero(),
+        TimeDiff::from_seconds(60),
+        1,
+        vec![],
+        "casper-example".to_string(),
+        harness.rng.gen(),
+        harness.rng.gen(),
+        &secret_key,
+    );
+    let expired_deploy_hash = *expired_deploy.id();
+
+    put_pending_deploy(&mut harness, &mut storage, Box::new(expired_deploy)).unwrap();
+    assert!(get_pending_deploy(&mut harness, &mut storage, expired_deploy_hash).is_none());
+
+    // Storing a pending deploy again replaces it, and purges the expired one.
+    put_pending_deploy(&mut harness, &mut storage, Box::new(deploy.clone())).unwrap();
+    deploy.sign(&SecretKey::random(&mut harness.rng));
+    put_pending_deploy(&mut harness, &mut storage, Box::new(deploy.clone())).unwrap();
+    assert_eq!(
+        get_pending_deploy(&mut harness, &mut storage, deploy_hash),
+        Some(Box::new(deploy))
+    );
+    assert!(!remove_pending_deploy(
+        &mut harness,
+        &mut storage,
+        expired_deploy_hash
+    ));
+
+    // Pending deploys are separate from deploys which have been accepted.
+    assert_eq!(
+        get_deploys(&mut harness, &mut storage, smallvec![deploy_hash]),
+        vec![None]
+    );
+
+    assert!(remove_pending_deploy(
+        &mut harness,
+        &mut storage,
+        deploy_hash
+    ));
+    assert!(get_pending_deploy(&mut harness, &mut storage, deploy_hash).is_none());
+}
+
+/// Creates a pending deploy of the account of `secret_key` with session code of the given size.
+fn pending_deploy(rng: &mut TestRng, secret_key: &SecretKey, session_size: usize) -> Deploy {
+    Deploy::new(
+        Timestamp::now(),
+        TimeDiff::from_seconds(60),
+        1,
+        vec![],
+        "casper-example".to_string(),
+        rng.gen(),
+        ExecutableDeployItem::ModuleBytes {
+            module_bytes: Bytes::from(vec![0; session_size]),
+            args: Default::default(),
+        },
+        secret_key,
+    )
+}
+
+#[test]
+fn should_limit_pending_deploys() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    // The fixture allows three pending deploys per account.
+    let secret_key = SecretKey::random(&mut harness.rng);
+    let account_hash = PublicKey::from(&secret_key).to_account_hash();
+    let deploys: Vec<_> = iter::repeat_with(|| pending_deploy(&mut harness.rng, &secret_key, 0))
+        .take(4)
+        .collect();
+    for deploy in &deploys[..3] {
+        put_pending_deploy(&mut harness, &mut storage, Box::new(deploy.clone())).unwrap();
+    }
+    let too_many = Err(PendingDeployStoreFull::TooManyForAccount {
+        account_hash,
+        max: 3,
+    });
+    assert_eq!(
+        put_pending_deploy(&mut harness, &mut storage, Box::new(deploys[3].clone())),
+        too_many
     );
 
-    let response = get_block(&mut harness, &mut storage, *block.hash());
-    assert_eq!(response.as_ref(), Some(&*block));
+    // Replacing a stored pending deploy is still possible.
+    put_pending_deploy(&mut harness, &mut storage, Box::new(deploys[0].clone())).unwrap();
 
-    // Also ensure we can retrieve just the header.
-    let response = harness.send_request(&mut storage, |responder| {
-        StorageRequest::GetBlockHeader {
-            block_hash: *block.hash(),
-            responder,
-        }
-        .into()
-    });
+    // The limits are restored from the database after a restart.
+    drop(storage);
+    let mut storage = storage_fixture(&harness);
+    assert_eq!(
+        put_pending_deploy(&mut harness, &mut storage, Box::new(deploys[3].clone())),
+        too_many
+    );
 
-    assert_eq!(response.as_ref(), Some(block.header()));
+    // Removing a pending deploy makes room for another one.
+    assert!(remove_pending_deploy(
+        &mut harness,
+        &mut storage,
+        *deploys[0].id()
+    ));
+    put_pending_deploy(&mut harness, &mut storage, Box::new(deploys[3].clone())).unwrap();
+
+    // The fixture limits the total size of pending deploys to one mebibyte.
+    let other_secret_key = SecretKey::random(&mut harness.rng);
+    let large_deploy = pending_deploy(&mut harness.rng, &other_secret_key, 1024 * 1024);
+    assert_eq!(
+        put_pending_deploy(&mut harness, &mut storage, Box::new(large_deploy.clone())),
+        Err(PendingDeployStoreFull::TooLarge {
+            max_size: 1024 * 1024
+        })
+    );
+    assert!(get_pending_deploy(&mut harness, &mut storage, *large_deploy.id()).is_none());
 }
 
 #[test]
@@ -567,6 +1222,104 @@ fn can_retrieve_block_by_height() {
     );
 }
 
+#[test]
+fn reads_ahead_blocks_requested_by_consecutive_heights() {
+    let mut harness = ComponentHarness::default();
+    let mut cfg = new_config(&harness);
+    cfg.block_read_ahead = 2;
+    let mut storage = Storage::new(
+        &WithDir::new(harness.tmp.path(), cfg),
+        None,
+        ProtocolVersion::from_parts(1, 0, 0),
+        &Registry::new(),
+    )
+    .expect("could not create storage component fixture");
+
+    let blocks: Vec<_> = (0..5)
+        .map(|height| {
+            Box::new(Block::random_with_specifics(
+                &mut harness.rng,
+                EraId::new(1),
+                height,
+                false,
+            ))
+        })
+        .collect();
+    for block in &blocks {
+        assert!(put_block(&mut harness, &mut storage, block.clone()));
+    }
+    let cached_heights =
+        |storage: &Storage| storage.read_ahead_cache.keys().copied().collect::<Vec<_>>();
+
+    // The first request isn't known to be sequential yet.
+    let block_0 = get_block_at_height(&mut harness, &mut storage, 0);
+    assert_eq!(block_0.as_ref(), Some(&*blocks[0]));
+    assert!(cached_heights(&storage).is_empty());
+
+    let block_1 = get_block_at_height(&mut harness, &mut storage, 1);
+    assert_eq!(block_1.as_ref(), Some(&*blocks[1]));
+    assert_eq!(cached_heights(&storage), vec![2, 3]);
+
+    let block_2 = get_block_at_height(&mut harness, &mut storage, 2);
+    assert_eq!(block_2.as_ref(), Some(&*blocks[2]));
+    assert_eq!(cached_heights(&storage), vec![3]);
+
+    // Reading ahead stops at the highest stored block.
+    let block_3 = get_block_at_height(&mut harness, &mut storage, 3);
+    assert_eq!(block_3.as_ref(), Some(&*blocks[3]));
+    assert!(cached_heights(&storage).is_empty());
+    let block_4 = get_block_at_height(&mut harness, &mut storage, 4);
+    assert_eq!(block_4.as_ref(), Some(&*blocks[4]));
+    assert!(cached_heights(&storage).is_empty());
+
+    // A non-sequential request discards the cache.
+    let _ = get_block_at_height(&mut harness, &mut storage, 1);
+    let _ = get_block_at_height(&mut harness, &mut storage, 2);
+    assert_eq!(cached_heights(&storage), vec![3, 4]);
+    let block_0 = get_block_at_height(&mut harness, &mut storage, 0);
+    assert_eq!(block_0.as_ref(), Some(&*blocks[0]));
+    assert!(cached_heights(&storage).is_empty());
+}
+
+#[test]
+fn can_retrieve_block_by_identifier() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let latest = BlockIdentifier::Latest;
+    assert!(get_block_header_by_identifier(&mut harness, &mut storage, latest).is_none());
+    assert!(get_block_and_metadata_by_identifier(&mut harness, &mut storage, latest).is_none());
+
+    let block_14 = random_block_at_height(&mut harness.rng, 14);
+    let block_33 = random_block_at_height(&mut harness.rng, 33);
+    assert!(put_block(&mut harness, &mut storage, block_14.clone()));
+    assert!(put_block(&mut harness, &mut storage, block_33.clone()));
+
+    let cases = vec![
+        (BlockIdentifier::Hash(*block_14.hash()), &block_14),
+        (BlockIdentifier::Height(14), &block_14),
+        (BlockIdentifier::Hash(*block_33.hash()), &block_33),
+        (BlockIdentifier::Height(33), &block_33),
+        (BlockIdentifier::Latest, &block_33),
+    ];
+    for (block_identifier, expected) in cases {
+        let header = get_block_header_by_identifier(&mut harness, &mut storage, block_identifier);
+        assert_eq!(header.as_ref(), Some(expected.header()));
+        let (block, signatures) =
+            get_block_and_metadata_by_identifier(&mut harness, &mut storage, block_identifier)
+                .expect("should have block");
+        assert_eq!(block, **expected);
+        assert_eq!(signatures.block_hash, *expected.hash());
+    }
+
+    let unknown_height = BlockIdentifier::Height(15);
+    assert!(get_block_header_by_identifier(&mut harness, &mut storage, unknown_height).is_none());
+    let unknown_hash = BlockIdentifier::Hash(BlockHash::random(&mut harness.rng));
+    assert!(
+        get_block_and_metadata_by_identifier(&mut harness, &mut storage, unknown_hash).is_none()
+    );
+}
+
 #[test]
 #[should_panic(expected = "duplicate entries")]
 fn different_block_at_height_is_fatal() {
@@ -659,6 +1412,75 @@ fn can_retrieve_store_and_load_deploys() {
     assert_eq!(metadata_response, DeployMetadata::default());
 }
 
+/// Returns the value of the storage metric with the given name, for the given database if any.
+fn storage_metric(registry: &Registry, name: &str, database: Option<&str>) -> f64 {
+    let family = registry
+        .gather()
+        .into_iter()
+        .find(|family| family.get_name() == name)
+        .expect("metric should be registered");
+    let metric = family
+        .get_metric()
+        .iter()
+        .find(|metric| match database {
+            Some(database) => metric
+                .get_label()
+                .iter()
+                .any(|label| label.get_name() == "database" && label.get_value() == database),
+            None => true,
+        })
+        .expect("metric should have a value");
+    if metric.has_counter() {
+        metric.get_counter().get_value()
+    } else {
+        metric.get_gauge().get_value()
+    }
+}
+
+#[test]
+fn should_record_database_metrics() {
+    let mut harness = ComponentHarness::default();
+    let registry = Registry::new();
+    let mut storage = Storage::new(
+        &WithDir::new(harness.tmp.path(), new_config(&harness)),
+        None,
+        ProtocolVersion::from_parts(1, 0, 0),
+        &registry,
+    )
+    .expect("could not create storage component fixture");
+
+    let deploy = Box::new(Deploy::random(&mut harness.rng));
+    assert!(put_deploy(&mut harness, &mut storage, deploy.clone()));
+    assert!(!put_deploy(&mut harness, &mut storage, deploy.clone()));
+    let _ = get_deploys(&mut harness, &mut storage, smallvec![*deploy.id()]);
+
+    assert_eq!(
+        storage_metric(&registry, "storage_db_puts", Some("deploys")),
+        2.0
+    );
+    assert_eq!(
+        storage_metric(&registry, "storage_db_gets", Some("deploys")),
+        1.0
+    );
+    assert_eq!(
+        storage_metric(&registry, "storage_db_puts", Some("deploys_by_account")),
+        1.0
+    );
+
+    storage.update_stats();
+    assert_eq!(
+        storage_metric(&registry, "storage_db_entries", Some("deploys")),
+        1.0
+    );
+    assert!(storage_metric(&registry, "storage_db_pages", Some("deploys")) >= 1.0);
+    assert!(storage_metric(&registry, "storage_map_size_bytes", None) > 0.0);
+    assert!(storage_metric(&registry, "storage_allocated_pages", None) > 0.0);
+
+    // Dropping the component should unregister its metrics.
+    drop(storage);
+    assert!(registry.gather().is_empty());
+}
+
 #[test]
 fn storing_and_loading_a_lot_of_deploys_does_not_exhaust_handles() {
     let mut harness = ComponentHarness::default();
@@ -842,6 +1664,271 @@ fn store_random_execution_results() {
     }
 }
 
+#[test]
+fn can_put_executed_block_and_read_block_and_metadata() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let block = random_block_at_height(&mut harness.rng, 7);
+    let deploy = Deploy::random(&mut harness.rng);
+    put_deploy(&mut harness, &mut storage, Box::new(deploy.clone()));
+
+    let execution_result: ExecutionResult = harness.rng.gen();
+    let mut execution_results = HashMap::new();
+    execution_results.insert(*deploy.id(), execution_result.clone());
+
+    assert!(put_executed_block(
+        &mut harness,
+        &mut storage,
+        block.clone(),
+        execution_results,
+        vec![]
+    ));
+
+    // Both the block and the deploy metadata must be visible, as well as the height index.
+    assert_eq!(
+        get_block(&mut harness, &mut storage, *block.hash()).as_ref(),
+        Some(&*block)
+    );
+    assert_eq!(
+        get_block_at_height(&mut harness, &mut storage, 7).as_ref(),
+        Some(&*block)
+    );
+    let (_, metadata) = get_deploy_and_metadata(&mut harness, &mut storage, *deploy.id())
+        .expect("missing deploy metadata");
+    let mut expected = HashMap::new();
+    expected.insert(*block.hash(), execution_result);
+    assert_eq!(metadata.execution_results, expected);
+}
+
+#[test]
+fn can_retrieve_execution_results_by_block() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let block = random_block_with_deploys(&mut harness.rng, 1, 10);
+    assert!(get_execution_results_by_block(&mut harness, &mut storage, *block.hash()).is_none());
+
+    let execution_results: HashMap<DeployHash, ExecutionResult> = block
+        .deploy_hashes()
+        .iter()
+        .map(|deploy_hash| (*deploy_hash, harness.rng.gen()))
+        .collect();
+    assert!(put_executed_block(
+        &mut harness,
+        &mut storage,
+        block.clone(),
+        execution_results.clone(),
+        vec![]
+    ));
+
+    let retrieved = get_execution_results_by_block(&mut harness, &mut storage, *block.hash())
+        .expect("block should be stored");
+    assert_eq!(retrieved.len(), execution_results.len());
+    assert_eq!(
+        retrieved.into_iter().collect::<HashMap<_, _>>(),
+        execution_results
+    );
+}
+
+#[test]
+fn can_retrieve_block_with_full_metadata() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let block = random_block_with_deploys(&mut harness.rng, 1, 10);
+    assert!(get_block_with_metadata(&mut harness, &mut storage, *block.hash()).is_none());
+
+    let execution_results: HashMap<DeployHash, ExecutionResult> = block
+        .deploy_hashes()
+        .iter()
+        .map(|deploy_hash| (*deploy_hash, harness.rng.gen()))
+        .collect();
+    assert!(put_executed_block(
+        &mut harness,
+        &mut storage,
+        block.clone(),
+        execution_results.clone(),
+        vec![]
+    ));
+    let signatures = random_signatures(&mut harness.rng, &block);
+    assert!(put_block_signatures(
+        &mut harness,
+        &mut storage,
+        signatures.clone()
+    ));
+
+    let block_with_metadata = get_block_with_metadata(&mut harness, &mut storage, *block.hash())
+        .expect("block should be stored");
+    assert_eq!(block_with_metadata.block, *block);
+    assert_eq!(block_with_metadata.block_signatures, signatures);
+
+    let mut expected: Vec<ExecutionResultSummary> = execution_results
+        .iter()
+        .map(|(deploy_hash, execution_result)| {
+            ExecutionResultSummary::new(*deploy_hash, execution_result)
+        })
+        .collect();
+    expected.sort_by_key(|summary| summary.deploy_hash);
+    let mut actual = block_with_metadata.execution_results;
+    actual.sort_by_key(|summary| summary.deploy_hash);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn prunes_execution_results_of_old_eras() {
+    let mut harness = ComponentHarness::default();
+    let mut cfg = new_config(&harness);
+    cfg.execution_results_retention_eras = Some(2);
+    let mut storage = Storage::new(
+        &WithDir::new(harness.tmp.path(), cfg),
+        None,
+        ProtocolVersion::from_parts(1, 0, 0),
+        &Registry::new(),
+    )
+    .expect("could not create storage component fixture");
+
+    let mut put_block_with_results =
+        |harness: &mut ComponentHarness<UnitTestEvent>, era_id: u64, height: u64| {
+            let block = random_block_with_deploys(&mut harness.rng, era_id, height);
+            let execution_results: HashMap<DeployHash, ExecutionResult> = block
+                .deploy_hashes()
+                .iter()
+                .map(|deploy_hash| (*deploy_hash, harness.rng.gen()))
+                .collect();
+            assert!(put_executed_block(
+                harness,
+                &mut storage,
+                block.clone(),
+                execution_results,
+                vec![]
+            ));
+            block
+        };
+
+    let old_block = put_block_with_results(&mut harness, 1, 10);
+    let recent_block = put_block_with_results(&mut harness, 3, 30);
+
+    // Era 1 is still within the retention window of era 3.
+    let old_results = get_execution_results_by_block(&mut harness, &mut storage, *old_block.hash())
+        .expect("block should be stored");
+    assert_eq!(old_results.len(), old_block.deploy_hashes().len());
+
+    let newest_block = random_block_with_deploys(&mut harness.rng, 4, 40);
+    assert!(put_executed_block(
+        &mut harness,
+        &mut storage,
+        newest_block,
+        HashMap::new(),
+        vec![]
+    ));
+
+    // Storing a block of era 4 drops the results of era 1, but keeps the block itself.
+    let old_results = get_execution_results_by_block(&mut harness, &mut storage, *old_block.hash())
+        .expect("block should still be stored");
+    assert!(old_results.is_empty());
+    assert_eq!(
+        get_block(&mut harness, &mut storage, *old_block.hash()).as_ref(),
+        Some(&*old_block)
+    );
+    let recent_results =
+        get_execution_results_by_block(&mut harness, &mut storage, *recent_block.hash())
+            .expect("block should be stored");
+    assert_eq!(recent_results.len(), recent_block.deploy_hashes().len());
+}
+
+#[test]
+fn prunes_execution_results_of_blocks_stored_before_retention_was_configured() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    // Results stored ahead of their block are never tagged with an era.
+    let old_block = random_block_with_deploys(&mut harness.rng, 1, 10);
+    let execution_results: HashMap<DeployHash, ExecutionResult> = old_block
+        .deploy_hashes()
+        .iter()
+        .map(|deploy_hash| (*deploy_hash, harness.rng.gen()))
+        .collect();
+    put_execution_results(
+        &mut harness,
+        &mut storage,
+        *old_block.hash(),
+        execution_results,
+    );
+    assert!(put_block(&mut harness, &mut storage, old_block.clone()));
+    drop(storage);
+
+    // Reopening the store with a retention period tags the existing blocks with their eras.
+    let mut cfg = new_config(&harness);
+    cfg.execution_results_retention_eras = Some(2);
+    let mut storage = Storage::new(
+        &WithDir::new(harness.tmp.path(), cfg),
+        None,
+        ProtocolVersion::from_parts(1, 0, 0),
+        &Registry::new(),
+    )
+    .expect("could not reopen storage component fixture");
+    let old_results = get_execution_results_by_block(&mut harness, &mut storage, *old_block.hash())
+        .expect("block should be stored");
+    assert_eq!(old_results.len(), old_block.deploy_hashes().len());
+
+    let newest_block = random_block_with_deploys(&mut harness.rng, 4, 40);
+    assert!(put_executed_block(
+        &mut harness,
+        &mut storage,
+        newest_block,
+        HashMap::new(),
+        vec![]
+    ));
+
+    let old_results = get_execution_results_by_block(&mut harness, &mut storage, *old_block.hash())
+        .expect("block should still be stored");
+    assert!(old_results.is_empty());
+}
+
+#[test]
+fn stores_transfers_of_execution_results_by_block() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let block_hash = BlockHash::random(&mut harness.rng);
+    let deploy_hash = DeployHash::random(&mut harness.rng);
+    let transfer = Transfer::new(
+        casper_types::DeployHash::new(deploy_hash.inner().to_array()),
+        AccountHash::new(harness.rng.gen()),
+        Some(AccountHash::new(harness.rng.gen())),
+        URef::new(harness.rng.gen(), AccessRights::READ_ADD_WRITE),
+        URef::new(harness.rng.gen(), AccessRights::ADD),
+        U512::from(1_000),
+        U512::from(100),
+        Some(42),
+    );
+    let transfer_addr = TransferAddr::new(harness.rng.gen());
+    let execution_result = ExecutionResult::Success {
+        effect: ExecutionEffect {
+            operations: vec![],
+            transforms: vec![TransformEntry {
+                key: Key::Transfer(transfer_addr).to_formatted_string(),
+                transform: Transform::WriteTransfer(transfer),
+            }],
+        },
+        transfers: vec![transfer_addr],
+        cost: U512::from(100),
+    };
+    let mut execution_results = HashMap::new();
+    execution_results.insert(deploy_hash, execution_result);
+    put_execution_results(&mut harness, &mut storage, block_hash, execution_results);
+
+    assert_eq!(
+        get_block_transfers(&mut harness, &mut storage, block_hash),
+        Some(vec![transfer])
+    );
+
+    // There are no transfer records for blocks whose execution results were never stored.
+    let other_block_hash = BlockHash::random(&mut harness.rng);
+    assert!(get_block_transfers(&mut harness, &mut storage, other_block_hash).is_none());
+}
+
 #[test]
 fn store_execution_results_twice_for_same_block_deploy_pair() {
     let mut harness = ComponentHarness::default();
@@ -978,6 +2065,35 @@ fn test_legacy_interface() {
         .is_none())
 }
 
+#[test]
+fn should_migrate_legacy_deploys() {
+    let mut harness = ComponentHarness::default();
+    let storage = storage_fixture(&harness);
+
+    // Store a deploy in the encoding used before deploys had a validity window.
+    let deploy = Deploy::random(&mut harness.rng);
+    let mut txn = storage.env.begin_rw_txn().unwrap();
+    txn.put_value(
+        storage.deploy_db,
+        deploy.id(),
+        &LegacyDeploy::from(&deploy),
+        false,
+    )
+    .unwrap();
+    txn.commit().unwrap();
+
+    // After reopening the storage, the deploy can be read and still has the same hash.
+    drop(storage);
+    let mut storage = storage_fixture(&harness);
+    let mut stored_deploys = get_deploys(&mut harness, &mut storage, smallvec![*deploy.id()]);
+    let mut stored_deploy = stored_deploys
+        .pop()
+        .unwrap()
+        .expect("should have migrated deploy");
+    assert_eq!(stored_deploy, deploy);
+    assert!(stored_deploy.is_valid().is_ok());
+}
+
 #[test]
 fn persist_blocks_deploys_and_deploy_metadata_across_instantiations() {
     let mut harness = ComponentHarness::default();
@@ -1142,3 +2258,188 @@ fn should_hard_reset() {
     // Test with a hard reset to era 0, deleting all blocks and associated data.
     check(0);
 }
+
+/// Creates a random non-switch block in era 0 which doesn't contain any deploys.
+fn random_block_without_deploys(rng: &mut TestRng, height: u64) -> Box<Block> {
+    loop {
+        let block = Block::random_with_specifics(rng, EraId::from(0), height, false);
+        if block.deploy_hashes().is_empty() && block.transfer_hashes().is_empty() {
+            return Box::new(block);
+        }
+    }
+}
+
+/// Writes a value to the global state in the storage directory, returning the new state root hash.
+fn put_global_state_value(config: &WithDir<Config>, key: Key, value: StoredValue) -> Blake2bHash {
+    let global_state =
+        contract_runtime::open_global_state(config, &ContractRuntimeConfig::default())
+            .expect("should open global state");
+    let mut effects = AdditiveMap::new();
+    effects.insert(key, GlobalStateTransform::Write(value));
+    match global_state
+        .commit(CorrelationId::new(), global_state.empty_root(), effects)
+        .expect("should commit to global state")
+    {
+        CommitResult::Success { state_root } => state_root,
+        result => panic!("unexpected commit result: {:?}", result),
+    }
+}
+
+/// Reads a value from the global state in the storage directory.
+fn get_global_state_value(
+    config: &WithDir<Config>,
+    state_root_hash: Blake2bHash,
+    key: &Key,
+) -> Option<StoredValue> {
+    let global_state =
+        contract_runtime::open_global_state(config, &ContractRuntimeConfig::default())
+            .expect("should open global state");
+    global_state
+        .checkout(state_root_hash)
+        .expect("should check out global state")
+        .expect("state root hash should exist")
+        .read(CorrelationId::new(), key)
+        .expect("should read from global state")
+}
+
+#[test]
+fn should_back_up_and_restore_storage() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let blocks: Vec<_> = (5..8)
+        .map(|height| random_block_without_deploys(&mut harness.rng, height))
+        .collect();
+    for block in &blocks {
+        put_block(&mut harness, &mut storage, block.clone());
+    }
+    let deploy = Box::new(Deploy::random(&mut harness.rng));
+    put_deploy(&mut harness, &mut storage, deploy.clone());
+    // The environment must not be opened twice within the same process.
+    drop(storage);
+
+    let config = WithDir::new(harness.tmp.path(), new_config(&harness));
+    let key = Key::Hash([7; 32]);
+    let value = StoredValue::CLValue(CLValue::from_t(7u64).unwrap());
+    let state_root_hash = put_global_state_value(&config, key, value.clone());
+
+    let contract_runtime_config = ContractRuntimeConfig::default();
+    let backup_dir = harness.tmp.path().join("backup");
+    let summary = super::backup(&config, &contract_runtime_config, &backup_dir)
+        .expect("should back up storage");
+    assert_eq!(summary.blocks, 3);
+    assert_eq!(summary.heights, Some((5, 7)));
+    assert_eq!(summary.deploys, 1);
+    assert!(summary.tries > 0);
+
+    // Backing up into the same directory again must fail.
+    assert!(matches!(
+        super::backup(&config, &contract_runtime_config, &backup_dir),
+        Err(BackupError::DestinationExists(_))
+    ));
+
+    // Restoring into a storage directory already containing a database must fail.
+    assert!(matches!(
+        super::restore(&backup_dir, &config, &contract_runtime_config),
+        Err(BackupError::DestinationExists(_))
+    ));
+
+    let restored_config = WithDir::new(
+        harness.tmp.path(),
+        Config {
+            path: harness.tmp.path().join("restored"),
+            ..new_config(&harness)
+        },
+    );
+    let restored_summary = super::restore(&backup_dir, &restored_config, &contract_runtime_config)
+        .expect("should restore storage");
+    assert_eq!(restored_summary, summary);
+    assert_eq!(
+        get_global_state_value(&restored_config, state_root_hash, &key),
+        Some(value)
+    );
+
+    let mut restored_storage = Storage::new(
+        &restored_config,
+        None,
+        ProtocolVersion::from_parts(1, 0, 0),
+        &Registry::new(),
+    )
+    .expect("should open restored storage");
+    for block in &blocks {
+        let restored_block = get_block(&mut harness, &mut restored_storage, *block.hash());
+        assert_eq!(restored_block.as_ref(), Some(&**block));
+    }
+    let restored_deploys =
+        get_deploys(&mut harness, &mut restored_storage, smallvec![*deploy.id()]);
+    assert_eq!(restored_deploys, vec![Some(*deploy)]);
+}
+
+#[test]
+fn should_reject_backup_with_missing_deploys() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let block = random_block_with_deploys(&mut harness.rng, 0, 0);
+    put_block(&mut harness, &mut storage, block.clone());
+    drop(storage);
+
+    let config = WithDir::new(harness.tmp.path(), new_config(&harness));
+    put_global_state_value(
+        &config,
+        Key::Hash([7; 32]),
+        StoredValue::CLValue(CLValue::unit()),
+    );
+    let backup_dir = harness.tmp.path().join("backup");
+    match super::backup(&config, &ContractRuntimeConfig::default(), &backup_dir) {
+        Err(BackupError::MissingDeploy {
+            block_hash,
+            deploy_hash,
+        }) => {
+            assert_eq!(block_hash, *block.hash());
+            assert!(block.deploy_hashes().contains(&deploy_hash));
+        }
+        result => panic!("unexpected result: {:?}", result),
+    }
+}
+
+#[test]
+fn should_reject_backup_with_missing_heights() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    for height in &[0, 1, 3] {
+        let block = random_block_without_deploys(&mut harness.rng, *height);
+        put_block(&mut harness, &mut storage, block);
+    }
+    drop(storage);
+
+    let config = WithDir::new(harness.tmp.path(), new_config(&harness));
+    put_global_state_value(
+        &config,
+        Key::Hash([7; 32]),
+        StoredValue::CLValue(CLValue::unit()),
+    );
+    let backup_dir = harness.tmp.path().join("backup");
+    assert!(matches!(
+        super::backup(&config, &ContractRuntimeConfig::default(), &backup_dir),
+        Err(BackupError::MissingBlockHeight(2))
+    ));
+}
+
+#[test]
+fn should_reject_backup_without_global_state() {
+    let harness = ComponentHarness::default();
+    drop(storage_fixture(&harness));
+
+    let config = WithDir::new(harness.tmp.path(), new_config(&harness));
+    let backup_dir = harness.tmp.path().join("backup");
+    match super::backup(&config, &ContractRuntimeConfig::default(), &backup_dir) {
+        Err(BackupError::SourceNotFound(path)) => {
+            assert_eq!(path, config.value().path.join("data.lmdb"))
+        }
+        result => panic!("unexpected result: {:?}", result),
+    }
+    // Nothing must have been written to the backup directory.
+    assert!(!backup_dir.exists());
+}
@@ -5,6 +5,6 @@ mod asymmetric_key_ext;
 mod error;
 pub mod hash;
 
-pub use asymmetric_key::{generate_ed25519_keypair, sign, verify};
+pub use asymmetric_key::{generate_ed25519_keypair, sign, verify, verify_batch};
 pub use asymmetric_key_ext::AsymmetricKeyExt;
 pub use error::{Error, Result};
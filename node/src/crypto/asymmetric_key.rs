@@ -3,6 +3,7 @@
 // TODO - remove once schemars stops causing warning.
 #![allow(clippy::field_reassign_with_default)]
 
+use curve25519_dalek::edwards::CompressedEdwardsY;
 use ed25519_dalek::ExpandedSecretKey;
 use k256::ecdsa::{
     signature::{Signer, Verifier},
@@ -78,6 +79,54 @@ pub fn verify<T: AsRef<[u8]>>(
     }
 }
 
+/// Verifies the signatures of all the given messages against the corresponding public keys.
+///
+/// Ed25519 signatures are checked together using batch verification, which is considerably faster
+/// than verifying them one by one. The result is always the same as calling `verify` for each item:
+/// batch verification only agrees with `verify`'s strict check if neither the public key nor the
+/// signature's `R` component has a torsion component, so any Ed25519 items which don't satisfy
+/// that, as well as all secp256k1 items, are verified individually.
+pub fn verify_batch<T: AsRef<[u8]>>(items: &[(T, &Signature, &PublicKey)]) -> Result<()> {
+    let mut messages = Vec::with_capacity(items.len());
+    let mut signatures = Vec::with_capacity(items.len());
+    let mut public_keys = Vec::with_capacity(items.len());
+    for (message, signature, public_key) in items {
+        match (signature, public_key) {
+            (Signature::Ed25519(ed_signature), PublicKey::Ed25519(ed_public_key))
+                if is_batch_verifiable(ed_signature, ed_public_key) =>
+            {
+                messages.push(message.as_ref());
+                signatures.push(*ed_signature);
+                public_keys.push(*ed_public_key);
+            }
+            _ => verify(message, signature, public_key)?,
+        }
+    }
+    if messages.is_empty() {
+        return Ok(());
+    }
+    ed25519_dalek::verify_batch(&messages, &signatures, &public_keys).map_err(|_| {
+        Error::AsymmetricKey(String::from("failed to batch verify Ed25519 signatures"))
+    })
+}
+
+/// Returns `true` if both the public key and the signature's `R` component are valid points of
+/// prime order, i.e. if batch verification of the signature is equivalent to strict verification.
+fn is_batch_verifiable(
+    signature: &ed25519_dalek::Signature,
+    public_key: &ed25519_dalek::PublicKey,
+) -> bool {
+    let is_prime_order = |bytes: &[u8]| {
+        CompressedEdwardsY::from_slice(bytes)
+            .decompress()
+            .map_or(false, |point| {
+                !point.is_small_order() && point.is_torsion_free()
+            })
+    };
+    let signature_bytes = signature.to_bytes();
+    is_prime_order(&signature_bytes[..32]) && is_prime_order(public_key.as_bytes())
+}
+
 #[cfg(test)]
 mod tests {
     use std::{
@@ -90,6 +139,7 @@ mod tests {
     use rand::RngCore;
 
     use openssl::pkey::{PKey, Private, Public};
+    use test::Bencher;
 
     use casper_types::{bytesrepr, AsymmetricType, Tagged};
 
@@ -717,6 +767,97 @@ kv+kBR5u4ISEAkuc2TFWQHX0Yj9oTB9fx9+vvQdxJOhMtu46kGo0Uw==
         assert!(verify(&message[1..], &secp256k1_signature, &secp256k1_public_key).is_err());
     }
 
+    #[test]
+    fn sign_and_verify_batch() {
+        let mut rng = crate::new_rng();
+        let secret_keys: Vec<SecretKey> = (0..10)
+            .map(|i| {
+                if i % 3 == 0 {
+                    SecretKey::random_secp256k1(&mut rng)
+                } else {
+                    SecretKey::random_ed25519(&mut rng)
+                }
+            })
+            .collect();
+        let public_keys: Vec<PublicKey> = secret_keys.iter().map(PublicKey::from).collect();
+        let messages: Vec<Vec<u8>> = (0..10u8).map(|i| vec![i; 32]).collect();
+        let signatures: Vec<Signature> = messages
+            .iter()
+            .zip(&secret_keys)
+            .zip(&public_keys)
+            .map(|((message, secret_key), public_key)| sign(message, secret_key, public_key))
+            .collect();
+        let items: Vec<(&[u8], &Signature, &PublicKey)> = messages
+            .iter()
+            .map(Vec::as_slice)
+            .zip(&signatures)
+            .zip(&public_keys)
+            .map(|((message, signature), public_key)| (message, signature, public_key))
+            .collect();
+
+        assert!(verify_batch::<&[u8]>(&[]).is_ok());
+        assert!(verify_batch(&items).is_ok());
+
+        // A single wrong message, of either key type, makes the whole batch invalid.
+        for index in 0..2 {
+            let mut invalid_items = items.clone();
+            invalid_items[index].0 = &b"wrong message"[..];
+            assert!(verify_batch(&invalid_items).is_err());
+        }
+    }
+
+    #[test]
+    fn verify_batch_should_be_as_strict_as_verify() {
+        // The identity point as a public key, and a signature with `R` the identity and `s` zero,
+        // satisfy the (cofactored) verification equation for any message, but are rejected by
+        // strict verification.
+        let mut identity = [0; 32];
+        identity[0] = 1;
+        let public_key = PublicKey::ed25519_from_bytes(identity).unwrap();
+        let mut signature_bytes = [0; Signature::ED25519_LENGTH];
+        signature_bytes[..32].copy_from_slice(&identity);
+        let signature = Signature::ed25519(signature_bytes).unwrap();
+
+        let message = b"message";
+        assert!(verify(message, &signature, &public_key).is_err());
+        assert!(verify_batch(&[(message, &signature, &public_key)]).is_err());
+    }
+
+    /// Returns the given number of random messages with Ed25519 signatures and public keys.
+    fn random_ed25519_signed_messages(count: usize) -> Vec<(Vec<u8>, Signature, PublicKey)> {
+        let mut rng = crate::new_rng();
+        iter::repeat_with(|| {
+            let secret_key = SecretKey::random_ed25519(&mut rng);
+            let public_key = PublicKey::from(&secret_key);
+            let mut message = vec![0; 32];
+            rng.fill_bytes(&mut message);
+            let signature = sign(&message, &secret_key, &public_key);
+            (message, signature, public_key)
+        })
+        .take(count)
+        .collect()
+    }
+
+    #[bench]
+    fn benchmark_verify_individually(bencher: &mut Bencher) {
+        let signed_messages = random_ed25519_signed_messages(100);
+        bencher.iter(|| {
+            for (message, signature, public_key) in &signed_messages {
+                verify(message, signature, public_key).unwrap();
+            }
+        });
+    }
+
+    #[bench]
+    fn benchmark_verify_batch(bencher: &mut Bencher) {
+        let signed_messages = random_ed25519_signed_messages(100);
+        let items: Vec<_> = signed_messages
+            .iter()
+            .map(|(message, signature, public_key)| (message, signature, public_key))
+            .collect();
+        bencher.iter(|| verify_batch(&items).unwrap());
+    }
+
     #[test]
     fn should_construct_secp256k1_from_uncompressed_bytes() {
         let mut rng = crate::new_rng();
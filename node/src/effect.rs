@@ -67,12 +67,18 @@ use std::{
     collections::{BTreeMap, HashMap, HashSet},
     fmt::{self, Debug, Display, Formatter},
     future::Future,
+    path::PathBuf,
     sync::Arc,
     time::{Duration, Instant},
 };
 
 use datasize::DataSize;
-use futures::{channel::oneshot, future::BoxFuture, FutureExt};
+use futures::{
+    channel::oneshot,
+    future::{self, AbortHandle, BoxFuture},
+    FutureExt,
+};
+use num_rational::Ratio;
 use once_cell::sync::Lazy;
 use serde::{de::DeserializeOwned, Serialize};
 use smallvec::{smallvec, SmallVec};
@@ -90,45 +96,50 @@ use casper_execution_engine::{
         step::{StepRequest, StepResult},
         upgrade::{UpgradeConfig, UpgradeResult},
         BalanceRequest, BalanceResult, GetBidsRequest, GetBidsResult, QueryRequest, QueryResult,
-        MAX_PAYMENT,
+        StateDiffRequest, StateDiffResult, MAX_PAYMENT,
     },
-    shared::{newtypes::Blake2bHash, stored_value::StoredValue},
+    shared::{account::Account, newtypes::Blake2bHash, stored_value::StoredValue},
     storage::{protocol_data::ProtocolData, trie::Trie},
 };
 use casper_types::{
-    system::auction::EraValidators, EraId, ExecutionResult, Key, ProtocolVersion, PublicKey,
-    Transfer, U512,
+    account::AccountHash, system::auction::EraValidators, ContractHash, EraId, ExecutionResult,
+    JournalEntry, Key, ProtocolVersion, PublicKey, Transfer, U512,
 };
 
 use crate::{
     components::{
+        block_proposer::{DeferralReason, DependencyCycle, PendingDeploys, PendingDeploysQuery},
         block_validator::ValidatingBlock,
+        chain_synchronizer::{self, TrieSyncProgress},
         chainspec_loader::{CurrentRunInfo, NextUpgrade},
-        consensus::{BlockContext, ClContext},
-        contract_runtime::EraValidatorsRequest,
-        deploy_acceptor,
+        consensus::{BlockContext, ClContext, HighwayStateSummary},
+        contract_runtime::{EraValidatorsRequest, QueryError},
+        deploy_acceptor::{self, AccessLists},
         fetcher::FetchResult,
-        small_network::GossipedAddress,
+        small_network::{BanTarget, ConnectionInfo, GossipedAddress},
+        storage,
     },
     crypto::hash::Digest,
     effect::requests::LinearChainRequest,
     reactor::{EventQueueHandle, QueueKind},
     types::{
-        Block, BlockByHeight, BlockHash, BlockHeader, BlockPayload, BlockSignatures, Chainspec,
-        ChainspecInfo, Deploy, DeployHash, DeployHeader, DeployMetadata, FinalitySignature,
-        FinalizedBlock, Item, TimeDiff, Timestamp,
+        Block, BlockByHeight, BlockHash, BlockHeader, BlockHeaderWithMetadata, BlockIdentifier,
+        BlockPayload, BlockSignatures, Chainspec, ChainspecInfo, ContractEventRecord, Deploy,
+        DeployHash, DeployHeader, DeployMetadata, EraRewards, FinalitySignature, FinalizedBlock,
+        Item, TimeDiff, Timestamp,
     },
     utils::Source,
 };
 use announcements::{
-    ChainspecLoaderAnnouncement, ConsensusAnnouncement, ContractRuntimeAnnouncement,
-    ControlAnnouncement, DeployAcceptorAnnouncement, GossiperAnnouncement, LinearChainAnnouncement,
-    NetworkAnnouncement, RpcServerAnnouncement,
+    BlockProposerAnnouncement, ChainspecLoaderAnnouncement, ConsensusAnnouncement,
+    ContractRuntimeAnnouncement, ControlAnnouncement, DeployAcceptorAnnouncement,
+    GossiperAnnouncement, LinearChainAnnouncement, NetworkAnnouncement, RpcServerAnnouncement,
 };
 use requests::{
-    BlockPayloadRequest, BlockProposerRequest, BlockValidationRequest, ChainspecLoaderRequest,
-    ConsensusRequest, ContractRuntimeRequest, FetcherRequest, MetricsRequest, NetworkInfoRequest,
-    NetworkRequest, StateStoreRequest, StorageRequest,
+    BlockPayloadRequest, BlockProposerRequest, BlockValidationRequest, ChainSynchronizerRequest,
+    ChainspecLoaderRequest, ConsensusRequest, ContractRuntimeRequest, DeployAcceptorRequest,
+    FetcherRequest, MetricsRequest, NetworkInfoRequest, NetworkRequest, StateStoreRequest,
+    StorageRequest,
 };
 
 use self::announcements::BlocklistAnnouncement;
@@ -220,6 +231,19 @@ impl<T> Serialize for Responder<T> {
     }
 }
 
+/// A handle to a timeout set via `EffectBuilder::set_cancellable_timeout`.
+///
+/// Dropping the handle does not cancel the timeout.
+#[derive(DataSize, Debug)]
+pub(crate) struct TimerHandle(#[data_size(skip)] AbortHandle);
+
+impl TimerHandle {
+    /// Cancels the timeout. If it has not elapsed yet, its future resolves to `None` immediately.
+    pub(crate) fn cancel(&self) {
+        self.0.abort()
+    }
+}
+
 /// Effect extension for futures, used to convert futures into actual effects.
 pub trait EffectExt: Future + Send {
     /// Finalizes a future into an effect that returns an event.
@@ -461,6 +485,18 @@ impl<REv> EffectBuilder<REv> {
         Instant::now() - then
     }
 
+    /// Sets a timeout which can be cancelled using the returned handle.
+    ///
+    /// The future resolves to the elapsed time, or to `None` if the timeout was cancelled, so that
+    /// cancelled timeouts do not produce any events when finalized using `map_some`.
+    pub(crate) fn set_cancellable_timeout(
+        self,
+        timeout: Duration,
+    ) -> (TimerHandle, impl Future<Output = Option<Duration>>) {
+        let (timeout, abort_handle) = future::abortable(self.set_timeout(timeout));
+        (TimerHandle(abort_handle), timeout.map(Result::ok))
+    }
+
     /// Retrieve a snapshot of the nodes current metrics formatted as string.
     ///
     /// If an error occurred producing the metrics, `None` is returned.
@@ -552,6 +588,36 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Gossips a network message, preferring validators of the given era.
+    ///
+    /// Selects up to `count` connected peers, choosing validators of `era_id` with a probability
+    /// proportional to their weight, and falling back to randomly chosen other peers. Sends each
+    /// a copy of the message.
+    ///
+    /// Returns the IDs of the chosen nodes.
+    pub async fn gossip_message_to_validators<I, P>(
+        self,
+        payload: P,
+        era_id: EraId,
+        count: usize,
+    ) -> HashSet<I>
+    where
+        REv: From<NetworkRequest<I, P>>,
+        I: Send + 'static,
+        P: Send,
+    {
+        self.make_request(
+            |responder| NetworkRequest::ValidatorGossip {
+                payload: Box::new(payload),
+                era_id,
+                count,
+                responder,
+            },
+            QueueKind::Network,
+        )
+        .await
+    }
+
     /// Gets connected network peers.
     pub async fn network_peers<I>(self) -> BTreeMap<I, String>
     where
@@ -565,6 +631,67 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Gets the connected peer which has identified itself with the given consensus key, if any.
+    pub(crate) async fn network_peer_by_public_key<I>(self, public_key: PublicKey) -> Option<I>
+    where
+        REv: From<NetworkInfoRequest<I>>,
+        I: Send + 'static,
+    {
+        self.make_request(
+            |responder| NetworkInfoRequest::GetPeerByPublicKey {
+                public_key: Box::new(public_key),
+                responder,
+            },
+            QueueKind::Network,
+        )
+        .await
+    }
+
+    /// Bans a peer or address for the given duration.
+    pub(crate) async fn ban_peer<I>(self, target: BanTarget<I>, duration: TimeDiff)
+    where
+        REv: From<NetworkInfoRequest<I>>,
+        I: Send + 'static,
+    {
+        self.make_request(
+            |responder| NetworkInfoRequest::Ban {
+                target,
+                duration,
+                responder,
+            },
+            QueueKind::Api,
+        )
+        .await
+    }
+
+    /// Retrieves diagnostic information about the network connections.
+    pub(crate) async fn get_network_connections<I>(self) -> Vec<ConnectionInfo<I>>
+    where
+        REv: From<NetworkInfoRequest<I>>,
+        I: Send + 'static,
+    {
+        self.make_request(
+            |responder| NetworkInfoRequest::GetConnections { responder },
+            QueueKind::Api,
+        )
+        .await
+    }
+
+    /// Replaces the allowlist and denylist applied to new deploys by the deploy acceptor.
+    pub(crate) async fn set_deploy_access_lists(self, access_lists: AccessLists)
+    where
+        REv: From<DeployAcceptorRequest>,
+    {
+        self.make_request(
+            |responder| DeployAcceptorRequest::SetAccessLists {
+                access_lists,
+                responder,
+            },
+            QueueKind::Api,
+        )
+        .await
+    }
+
     /// Announces that a network message has been received.
     pub(crate) async fn announce_message_received<I, P>(self, sender: I, payload: P)
     where
@@ -638,6 +765,25 @@ impl<REv> EffectBuilder<REv> {
             .await;
     }
 
+    /// Announces that a deploy has been received by the RPC server and waits for the deploy
+    /// acceptor's verdict on it.
+    pub(crate) async fn announce_deploy_received_and_await_acceptance(
+        self,
+        deploy: Box<Deploy>,
+    ) -> Result<(), deploy_acceptor::Error>
+    where
+        REv: From<RpcServerAnnouncement>,
+    {
+        self.make_request(
+            |responder| RpcServerAnnouncement::DeployReceived {
+                deploy,
+                responder: Some(responder),
+            },
+            QueueKind::Api,
+        )
+        .await
+    }
+
     /// Announces that a deploy not previously stored has now been accepted and stored.
     pub(crate) fn announce_new_deploy_accepted<I>(
         self,
@@ -673,12 +819,17 @@ impl<REv> EffectBuilder<REv> {
         self,
         block: Block,
         execution_results: HashMap<DeployHash, (DeployHeader, ExecutionResult)>,
+        contract_events: Vec<ContractEventRecord>,
     ) where
         REv: From<ContractRuntimeAnnouncement>,
     {
         self.0
             .schedule(
-                ContractRuntimeAnnouncement::linear_chain_block(block, execution_results),
+                ContractRuntimeAnnouncement::linear_chain_block(
+                    block,
+                    execution_results,
+                    contract_events,
+                ),
                 QueueKind::Regular,
             )
             .await
@@ -726,13 +877,39 @@ impl<REv> EffectBuilder<REv> {
             .await
     }
 
-    /// Puts the given block into the linear block store.
-    pub(crate) async fn put_block_to_storage(self, block: Box<Block>) -> bool
+    /// Announce that pending deploys were evicted from the block proposer.
+    pub(crate) async fn announce_deploys_evicted(self, deploy_hashes: Vec<DeployHash>)
+    where
+        REv: From<BlockProposerAnnouncement>,
+    {
+        self.0
+            .schedule(
+                BlockProposerAnnouncement::DeploysEvicted(deploy_hashes),
+                QueueKind::Regular,
+            )
+            .await
+    }
+
+    /// Puts the given block, the execution results of its deploys and the events emitted by
+    /// contracts while executing it into the linear block store in a single atomic write.
+    ///
+    /// Returns whether the block was newly stored, or the storage error which prevented it.
+    pub(crate) async fn put_executed_block_to_storage(
+        self,
+        block: Box<Block>,
+        execution_results: HashMap<DeployHash, ExecutionResult>,
+        contract_events: Vec<ContractEventRecord>,
+    ) -> Result<bool, storage::Error>
     where
         REv: From<StorageRequest>,
     {
         self.make_request(
-            |responder| StorageRequest::PutBlock { block, responder },
+            |responder| StorageRequest::PutExecutedBlock {
+                block,
+                execution_results,
+                contract_events,
+                responder,
+            },
             QueueKind::Regular,
         )
         .await
@@ -823,6 +1000,100 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Gets the blocks proposed by the given validator in the given era from storage.
+    pub(crate) async fn get_blocks_by_proposer_from_storage(
+        self,
+        public_key: PublicKey,
+        era_id: EraId,
+    ) -> Vec<Block>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetBlocksByProposer {
+                public_key,
+                era_id,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Gets the hashes of deploys submitted by the given account from storage, most recent first.
+    pub(crate) async fn get_deploys_by_account_from_storage(
+        self,
+        account_hash: AccountHash,
+        limit: usize,
+        offset: usize,
+    ) -> Vec<DeployHash>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetDeploysByAccount {
+                account_hash,
+                limit,
+                offset,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Puts a deploy which doesn't have enough approvals to be submitted yet to storage.
+    ///
+    /// Returns an error if a limit of the pending deploy store would be exceeded.
+    pub(crate) async fn put_pending_deploy_to_storage(
+        self,
+        deploy: Box<Deploy>,
+    ) -> Result<(), storage::PendingDeployStoreFull>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::PutPendingDeploy { deploy, responder },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Gets the pending deploy with the given hash from storage, if it exists and hasn't expired.
+    pub(crate) async fn get_pending_deploy_from_storage(
+        self,
+        deploy_hash: DeployHash,
+    ) -> Option<Box<Deploy>>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetPendingDeploy {
+                deploy_hash,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Removes the pending deploy with the given hash from storage.
+    ///
+    /// Returns `true` if the deploy was stored.
+    pub(crate) async fn remove_pending_deploy_from_storage(self, deploy_hash: DeployHash) -> bool
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::RemovePendingDeploy {
+                deploy_hash,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Requests the block header at the given height.
     pub(crate) async fn get_block_header_at_height_from_storage(
         self,
@@ -868,6 +1139,21 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Requests the header of the block at the given height, along with its finality signatures.
+    pub(crate) async fn get_block_header_and_metadata_at_height_from_storage(
+        self,
+        height: u64,
+    ) -> Option<BlockHeaderWithMetadata>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetBlockHeaderAndMetadataAtHeight { height, responder },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Requests the highest block.
     pub(crate) async fn get_highest_block_from_storage(self) -> Option<Block>
     where
@@ -984,19 +1270,43 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
-    /// Stores the given execution results for the deploys in the given block in the linear block
-    /// store.
-    pub(crate) async fn put_execution_results_to_storage(
+    /// Stores the rewards distributed at the end of an era.
+    pub(crate) async fn put_era_rewards_to_storage(self, era_rewards: EraRewards)
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::PutEraRewards {
+                era_rewards: Box::new(era_rewards),
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Gets the rewards distributed at the end of the given era.
+    pub(crate) async fn get_era_rewards_from_storage(self, era_id: EraId) -> Option<EraRewards>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetEraRewards { era_id, responder },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Stores the journals of global state operations of executed deploys.
+    pub(crate) async fn put_execution_journals_to_storage(
         self,
-        block_hash: BlockHash,
-        execution_results: HashMap<DeployHash, ExecutionResult>,
+        journals: HashMap<DeployHash, Vec<JournalEntry>>,
     ) where
         REv: From<StorageRequest>,
     {
         self.make_request(
-            |responder| StorageRequest::PutExecutionResults {
-                block_hash: Box::new(block_hash),
-                execution_results,
+            |responder| StorageRequest::PutExecutionJournals {
+                journals,
                 responder,
             },
             QueueKind::Regular,
@@ -1004,16 +1314,16 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
-    /// Gets the requested deploys from the deploy store.
-    pub(crate) async fn get_deploy_and_metadata_from_storage(
+    /// Gets the journal of global state operations of the given executed deploy.
+    pub(crate) async fn get_execution_journal_from_storage(
         self,
         deploy_hash: DeployHash,
-    ) -> Option<(Deploy, DeployMetadata)>
+    ) -> Option<Vec<JournalEntry>>
     where
         REv: From<StorageRequest>,
     {
         self.make_request(
-            |responder| StorageRequest::GetDeployAndMetadata {
+            |responder| StorageRequest::GetExecutionJournal {
                 deploy_hash,
                 responder,
             },
@@ -1022,17 +1332,22 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
-    /// Gets the requested block and its associated metadata.
-    pub(crate) async fn get_block_at_height_with_metadata_from_storage(
+    /// Gets the events emitted by a contract in the blocks from `from_block` to `to_block`,
+    /// inclusive.
+    pub(crate) async fn get_contract_events_from_storage(
         self,
-        block_height: u64,
-    ) -> Option<(Block, BlockSignatures)>
+        contract_hash: ContractHash,
+        from_block: u64,
+        to_block: u64,
+    ) -> Vec<ContractEventRecord>
     where
         REv: From<StorageRequest>,
     {
         self.make_request(
-            |responder| StorageRequest::GetBlockAndMetadataByHeight {
-                block_height,
+            |responder| StorageRequest::GetContractEvents {
+                contract_hash,
+                from_block,
+                to_block,
                 responder,
             },
             QueueKind::Regular,
@@ -1040,17 +1355,17 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
-    /// Gets the requested block by hash with its associated metadata.
-    pub(crate) async fn get_block_with_metadata_from_storage(
+    /// Gets the requested deploys from the deploy store.
+    pub(crate) async fn get_deploy_and_metadata_from_storage(
         self,
-        block_hash: BlockHash,
-    ) -> Option<(Block, BlockSignatures)>
+        deploy_hash: DeployHash,
+    ) -> Option<(Deploy, DeployMetadata)>
     where
         REv: From<StorageRequest>,
     {
         self.make_request(
-            |responder| StorageRequest::GetBlockAndMetadataByHash {
-                block_hash,
+            |responder| StorageRequest::GetDeployAndMetadata {
+                deploy_hash,
                 responder,
             },
             QueueKind::Regular,
@@ -1058,15 +1373,38 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
-    /// Get the highest block with its associated metadata.
-    pub(crate) async fn get_highest_block_with_metadata_from_storage(
+    /// Gets the header of the block with the given hash or height, or of the highest block.
+    pub(crate) async fn get_block_header_by_identifier_from_storage(
         self,
+        block_identifier: BlockIdentifier,
+    ) -> Option<BlockHeader>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetBlockHeaderByIdentifier {
+                block_identifier,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Gets the block with the given hash or height, or the highest block, with its associated
+    /// metadata.
+    pub(crate) async fn get_block_with_metadata_by_identifier_from_storage(
+        self,
+        block_identifier: BlockIdentifier,
     ) -> Option<(Block, BlockSignatures)>
     where
         REv: From<StorageRequest>,
     {
         self.make_request(
-            |responder| StorageRequest::GetHighestBlockWithMetadata { responder },
+            |responder| StorageRequest::GetBlockAndMetadataByIdentifier {
+                block_identifier,
+                responder,
+            },
             QueueKind::Regular,
         )
         .await
@@ -1135,10 +1473,56 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Requests the header of the linear chain block at `block_height`, along with its finality
+    /// signatures.
+    pub(crate) async fn fetch_block_header_with_metadata<I>(
+        self,
+        block_height: u64,
+        peer: I,
+    ) -> Option<FetchResult<BlockHeaderWithMetadata, I>>
+    where
+        REv: From<FetcherRequest<I, BlockHeaderWithMetadata>>,
+        I: Send + 'static,
+    {
+        self.make_request(
+            |responder| FetcherRequest::Fetch {
+                id: block_height,
+                peer,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Requests a global state trie node with the given hash from a peer.
+    pub(crate) async fn fetch_trie<I>(
+        self,
+        trie_key: Blake2bHash,
+        peer: I,
+    ) -> Option<FetchResult<Trie<Key, StoredValue>, I>>
+    where
+        REv: From<FetcherRequest<I, Trie<Key, StoredValue>>>,
+        I: Send + 'static,
+    {
+        self.make_request(
+            |responder| FetcherRequest::Fetch {
+                id: trie_key,
+                peer,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Passes the timestamp of a future block for which deploys are to be proposed.
     pub(crate) async fn request_block_payload(
         self,
         context: BlockContext<ClContext>,
+        gas_price_floor: u64,
+        era_id: EraId,
+        proposer: PublicKey,
         next_finalized: u64,
         accusations: Vec<PublicKey>,
         random_bit: bool,
@@ -1150,6 +1534,9 @@ impl<REv> EffectBuilder<REv> {
             |responder| {
                 BlockProposerRequest::RequestBlockPayload(BlockPayloadRequest {
                     context,
+                    gas_price_floor,
+                    era_id,
+                    proposer,
                     next_finalized,
                     responder,
                     accusations,
@@ -1161,6 +1548,43 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Returns the pending deploys that were not included in the most recently proposed block,
+    /// together with the reason.
+    pub(crate) async fn get_deferred_deploys(self) -> Vec<(DeployHash, DeferralReason)>
+    where
+        REv: From<BlockProposerRequest>,
+    {
+        self.make_request(
+            |responder| BlockProposerRequest::GetDeferredDeploys { responder },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Returns the most recently rejected deploys whose dependencies formed a cycle, oldest first.
+    pub(crate) async fn get_dependency_cycles(self) -> Vec<DependencyCycle>
+    where
+        REv: From<BlockProposerRequest>,
+    {
+        self.make_request(
+            |responder| BlockProposerRequest::GetDependencyCycles { responder },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Returns a page of the pending deploys matching the query, oldest first.
+    pub(crate) async fn get_pending_deploys(self, query: PendingDeploysQuery) -> PendingDeploys
+    where
+        REv: From<BlockProposerRequest>,
+    {
+        self.make_request(
+            |responder| BlockProposerRequest::GetPendingDeploys { query, responder },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Passes a finalized proto-block to the block executor component to execute it.
     pub(crate) async fn execute_block(self, finalized_block: FinalizedBlock)
     where
@@ -1205,6 +1629,19 @@ impl<REv> EffectBuilder<REv> {
             .await
     }
 
+    /// Announces that a proposed block was orphaned.
+    pub(crate) async fn announce_orphaned_proto_block(self, block_payload: Arc<BlockPayload>)
+    where
+        REv: From<ConsensusAnnouncement>,
+    {
+        self.0
+            .schedule(
+                ConsensusAnnouncement::OrphanedProtoBlock(block_payload),
+                QueueKind::Regular,
+            )
+            .await
+    }
+
     /// Announces that a finality signature has been created.
     pub(crate) async fn announce_created_finality_signature(
         self,
@@ -1241,6 +1678,60 @@ impl<REv> EffectBuilder<REv> {
             .await
     }
 
+    /// Announces that the block at `height` was finalized with respect to the given finality
+    /// threshold fraction.
+    pub(crate) async fn announce_finality_tier_reached(
+        self,
+        era_id: EraId,
+        height: u64,
+        finality_threshold_fraction: Ratio<u64>,
+    ) where
+        REv: From<ConsensusAnnouncement>,
+    {
+        self.0
+            .schedule(
+                ConsensusAnnouncement::FinalityTierReached {
+                    era_id,
+                    height,
+                    finality_threshold_fraction,
+                },
+                QueueKind::Regular,
+            )
+            .await
+    }
+
+    /// Announces the validators of a newly created era.
+    pub(crate) async fn announce_era_validators_changed(
+        self,
+        era_id: EraId,
+        validators: BTreeMap<PublicKey, U512>,
+    ) where
+        REv: From<ConsensusAnnouncement>,
+    {
+        self.0
+            .schedule(
+                ConsensusAnnouncement::EraValidatorsChanged { era_id, validators },
+                QueueKind::Regular,
+            )
+            .await
+    }
+
+    /// Announces that this node is no longer a validator in any of the bonded eras.
+    pub(crate) async fn announce_no_longer_bonded(self, era_id: EraId, public_key: PublicKey)
+    where
+        REv: From<ConsensusAnnouncement>,
+    {
+        self.0
+            .schedule(
+                ConsensusAnnouncement::NoLongerBonded {
+                    era_id,
+                    public_key: Box::new(public_key),
+                },
+                QueueKind::Regular,
+            )
+            .await
+    }
+
     /// Announce the intent to disconnect from a specific peer, which consensus thinks is faulty.
     pub(crate) async fn announce_disconnect_from_peer<I>(self, peer: I)
     where
@@ -1298,6 +1789,27 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Initializes the global state from the genesis snapshot file at `snapshot_path`, instead of
+    /// running the genesis process.
+    pub(crate) async fn commit_genesis_snapshot(
+        self,
+        chainspec: Arc<Chainspec>,
+        snapshot_path: PathBuf,
+    ) -> Result<GenesisResult, engine_state::Error>
+    where
+        REv: From<ContractRuntimeRequest>,
+    {
+        self.make_request(
+            |responder| ContractRuntimeRequest::CommitGenesisSnapshot {
+                chainspec,
+                snapshot_path,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Runs the upgrade process on the contract runtime.
     pub(crate) async fn upgrade_contract_runtime(
         self,
@@ -1373,7 +1885,7 @@ impl<REv> EffectBuilder<REv> {
     pub(crate) async fn get_finalized_deploys(
         self,
         ttl: TimeDiff,
-    ) -> Vec<(DeployHash, DeployHeader)>
+    ) -> Result<Vec<(DeployHash, DeployHeader)>, storage::Error>
     where
         REv: From<StorageRequest>,
     {
@@ -1421,7 +1933,7 @@ impl<REv> EffectBuilder<REv> {
     pub(crate) async fn query_global_state(
         self,
         query_request: QueryRequest,
-    ) -> Result<QueryResult, engine_state::Error>
+    ) -> Result<QueryResult, QueryError>
     where
         REv: From<ContractRuntimeRequest>,
     {
@@ -1460,11 +1972,35 @@ impl<REv> EffectBuilder<REv> {
         None
     }
 
+    /// Reads the given account and the balance of its main purse at the state root of the highest
+    /// block.
+    ///
+    /// Returns `None` if there is no highest block yet, or if either can't be read.
+    pub(crate) async fn get_account_and_balance(self, account_key: Key) -> Option<(Account, U512)>
+    where
+        REv: From<ContractRuntimeRequest>,
+        REv: From<StorageRequest>,
+    {
+        let block = self.get_highest_block_from_storage().await?;
+        let state_hash = (*block.state_root_hash()).into();
+        let query_request = QueryRequest::new(state_hash, account_key, vec![]);
+        let account = match self.query_global_state(query_request).await {
+            Ok(QueryResult::Success { value, .. }) => match *value {
+                StoredValue::Account(account) => account,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        let balance_request = BalanceRequest::new(state_hash, account.main_purse());
+        let balance = *self.get_balance(balance_request).await.ok()?.motes()?;
+        Some((account, balance))
+    }
+
     /// Requests a query be executed on the Contract Runtime component.
     pub(crate) async fn get_balance(
         self,
         balance_request: BalanceRequest,
-    ) -> Result<BalanceResult, engine_state::Error>
+    ) -> Result<BalanceResult, QueryError>
     where
         REv: From<ContractRuntimeRequest>,
     {
@@ -1519,7 +2055,7 @@ impl<REv> EffectBuilder<REv> {
     pub(crate) async fn get_bids(
         self,
         get_bids_request: GetBidsRequest,
-    ) -> Result<GetBidsResult, engine_state::Error>
+    ) -> Result<GetBidsResult, QueryError>
     where
         REv: From<ContractRuntimeRequest>,
     {
@@ -1533,6 +2069,24 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Requests the differences between the global states under two state root hashes.
+    pub(crate) async fn diff_state(
+        self,
+        state_diff_request: StateDiffRequest,
+    ) -> Result<StateDiffResult, QueryError>
+    where
+        REv: From<ContractRuntimeRequest>,
+    {
+        self.make_request(
+            |responder| ContractRuntimeRequest::DiffState {
+                state_diff_request,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Runs the end of era step using the system smart contract.
     pub(crate) async fn run_step(
         self,
@@ -1561,9 +2115,11 @@ impl<REv> EffectBuilder<REv> {
             protocol_version,
             initial_state_root_hash,
             last_emergency_restart,
+            genesis_era_id,
+            genesis_height,
             ..
         } = self.get_current_run_info().await;
-        let cutoff_era_id = last_emergency_restart.unwrap_or_else(|| EraId::new(0));
+        let cutoff_era_id = last_emergency_restart.unwrap_or(genesis_era_id);
         if era_id < cutoff_era_id {
             // we don't support getting the validators from before the last emergency restart
             return None;
@@ -1572,9 +2128,9 @@ impl<REv> EffectBuilder<REv> {
             // in the activation era, we read the validators from the global state; we use the
             // global state hash of the first block in the era, if it exists - if we can't get it,
             // we use the initial_state_root_hash passed from the chainspec loader
-            let root_hash = if era_id.is_genesis() {
-                // genesis era - use block at height 0
-                self.get_block_header_at_height_from_storage(0)
+            let root_hash = if era_id == genesis_era_id {
+                // genesis era - use the chain's first block
+                self.get_block_header_at_height_from_storage(genesis_height)
                     .await
                     .map(|hdr| *hdr.state_root_hash())
                     .unwrap_or(initial_state_root_hash)
@@ -1666,6 +2222,50 @@ impl<REv> EffectBuilder<REv> {
             .await
     }
 
+    /// Returns a summary of the given era's Highway protocol state, or `None` if the era is not
+    /// active.
+    pub(crate) async fn consensus_state_summary(
+        self,
+        era_id: EraId,
+    ) -> Option<HighwayStateSummary<ClContext>>
+    where
+        REv: From<ConsensusRequest>,
+    {
+        self.make_request(
+            |responder| ConsensusRequest::StateSummary { era_id, responder },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Downloads the global state trie under `state_root_hash` from peers into the local trie
+    /// store.
+    pub(crate) async fn sync_trie(
+        self,
+        state_root_hash: Digest,
+    ) -> Result<(), chain_synchronizer::Error>
+    where
+        REv: From<ChainSynchronizerRequest>,
+    {
+        self.make_request(
+            |responder| ChainSynchronizerRequest::SyncTrie {
+                state_root_hash,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Gets the progress of the most recent global state trie sync, if any.
+    pub(crate) async fn trie_sync_progress(self) -> Option<TrieSyncProgress>
+    where
+        REv: From<ChainSynchronizerRequest>,
+    {
+        self.make_request(ChainSynchronizerRequest::Progress, QueueKind::Regular)
+            .await
+    }
+
     /// Collects the key blocks for the eras identified by provided era IDs. Returns
     /// `Some(HashMap(era_id → block_header))` if all the blocks have been read correctly, and
     /// `None` if at least one was missing. The header for EraId `n` is from the key block for that
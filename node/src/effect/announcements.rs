@@ -4,13 +4,15 @@
 //! module documentation for details.
 
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     fmt::{self, Display, Formatter},
+    sync::Arc,
 };
 
+use num_rational::Ratio;
 use serde::Serialize;
 
-use casper_types::{EraId, ExecutionEffect, ExecutionResult, PublicKey};
+use casper_types::{EraId, ExecutionEffect, ExecutionResult, PublicKey, U512};
 
 use crate::{
     components::{
@@ -18,7 +20,8 @@ use crate::{
     },
     effect::Responder,
     types::{
-        Block, Deploy, DeployHash, DeployHeader, FinalitySignature, FinalizedBlock, Item, Timestamp,
+        Block, BlockPayload, ContractEventRecord, Deploy, DeployHash, DeployHeader,
+        FinalitySignature, FinalizedBlock, Item, Timestamp,
     },
     utils::Source,
 };
@@ -160,6 +163,8 @@ impl<I: Display> Display for DeployAcceptorAnnouncement<I> {
 pub enum ConsensusAnnouncement {
     /// A block was finalized.
     Finalized(Box<FinalizedBlock>),
+    /// A proposed block was orphaned and will never be finalized.
+    OrphanedProtoBlock(Arc<BlockPayload>),
     /// A finality signature was created.
     CreatedFinalitySignature(Box<FinalitySignature>),
     /// An equivocation has been detected.
@@ -171,6 +176,30 @@ pub enum ConsensusAnnouncement {
         /// The timestamp when the evidence of the equivocation was detected.
         timestamp: Timestamp,
     },
+    /// A block was finalized with respect to one of the configured additional finality
+    /// thresholds.
+    FinalityTierReached {
+        /// The ID of the era the block belongs to.
+        era_id: EraId,
+        /// The height of the block.
+        height: u64,
+        /// The finality threshold fraction the block was finalized with respect to.
+        finality_threshold_fraction: Ratio<u64>,
+    },
+    /// A new era was created, with the given set of validators.
+    EraValidatorsChanged {
+        /// The ID of the new era.
+        era_id: EraId,
+        /// The validators of the new era, with their weights.
+        validators: BTreeMap<PublicKey, U512>,
+    },
+    /// This node is no longer a validator in any of the bonded eras.
+    NoLongerBonded {
+        /// The ID of the era whose creation unbonded this node.
+        era_id: EraId,
+        /// Our public key.
+        public_key: Box<PublicKey>,
+    },
 }
 
 impl Display for ConsensusAnnouncement {
@@ -179,6 +208,9 @@ impl Display for ConsensusAnnouncement {
             ConsensusAnnouncement::Finalized(block) => {
                 write!(formatter, "finalized block payload {}", block)
             }
+            ConsensusAnnouncement::OrphanedProtoBlock(block_payload) => {
+                write!(formatter, "orphaned {}", block_payload)
+            }
             ConsensusAnnouncement::CreatedFinalitySignature(fs) => {
                 write!(formatter, "signed an executed block: {}", fs)
             }
@@ -191,6 +223,25 @@ impl Display for ConsensusAnnouncement {
                 "Validator fault with public key: {} has been identified at time: {} in era: {}",
                 public_key, timestamp, era_id,
             ),
+            ConsensusAnnouncement::FinalityTierReached {
+                era_id,
+                height,
+                finality_threshold_fraction,
+            } => write!(
+                formatter,
+                "block at height {} in {} finalized with finality threshold {}",
+                height, era_id, finality_threshold_fraction
+            ),
+            ConsensusAnnouncement::EraValidatorsChanged { era_id, validators } => {
+                write!(formatter, "{} validators in {}", validators.len(), era_id)
+            }
+            ConsensusAnnouncement::NoLongerBonded { era_id, public_key } => {
+                write!(
+                    formatter,
+                    "{} no longer bonded as of {}",
+                    public_key, era_id
+                )
+            }
         }
     }
 }
@@ -236,10 +287,12 @@ impl ContractRuntimeAnnouncement {
     pub fn linear_chain_block(
         block: Block,
         execution_results: HashMap<DeployHash, (DeployHeader, ExecutionResult)>,
+        contract_events: Vec<ContractEventRecord>,
     ) -> Self {
         Self::LinearChainBlock(Box::new(LinearChainBlock {
             block,
             execution_results,
+            contract_events,
         }))
     }
     /// Create a ContractRuntimeAnnouncement::BlockAlreadyExecuted from a Block.
@@ -263,6 +316,8 @@ pub struct LinearChainBlock {
     pub block: Block,
     /// The results of executing the deploys in this block.
     pub execution_results: HashMap<DeployHash, (DeployHeader, ExecutionResult)>,
+    /// The events emitted by contracts while executing the deploys in this block.
+    pub contract_events: Vec<ContractEventRecord>,
 }
 
 impl Display for ContractRuntimeAnnouncement {
@@ -322,6 +377,23 @@ impl Display for LinearChainAnnouncement {
     }
 }
 
+/// A block proposer announcement.
+#[derive(Debug, Serialize)]
+pub enum BlockProposerAnnouncement {
+    /// Pending deploys were evicted from the block proposer because its capacity was exceeded.
+    DeploysEvicted(Vec<DeployHash>),
+}
+
+impl Display for BlockProposerAnnouncement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            BlockProposerAnnouncement::DeploysEvicted(deploy_hashes) => {
+                write!(f, "{} pending deploys evicted", deploy_hashes.len())
+            }
+        }
+    }
+}
+
 /// A chainspec loader announcement.
 #[derive(Debug, Serialize)]
 pub enum ChainspecLoaderAnnouncement {
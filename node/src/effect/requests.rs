@@ -8,6 +8,7 @@ use std::{
     collections::{BTreeMap, HashMap, HashSet},
     fmt::{self, Debug, Display, Formatter},
     mem,
+    path::PathBuf,
     sync::Arc,
 };
 
@@ -23,6 +24,7 @@ use casper_execution_engine::{
         era_validators::GetEraValidatorsError,
         genesis::GenesisResult,
         query::{GetBidsRequest, GetBidsResult, QueryRequest, QueryResult},
+        state_diff::{StateDiffRequest, StateDiffResult},
         step::{StepRequest, StepResult},
         upgrade::{UpgradeConfig, UpgradeResult},
     },
@@ -30,26 +32,34 @@ use casper_execution_engine::{
     storage::{protocol_data::ProtocolData, trie::Trie},
 };
 use casper_types::{
+    account::AccountHash,
     system::auction::{EraValidators, ValidatorWeights},
-    EraId, ExecutionResult, Key, ProtocolVersion, PublicKey, Transfer, URef,
+    ContractHash, EraId, ExecutionResult, JournalEntry, Key, ProtocolVersion, PublicKey, Transfer,
+    URef,
 };
 
 use super::Responder;
 use crate::{
     components::{
+        block_proposer::{DeferralReason, DependencyCycle, PendingDeploys, PendingDeploysQuery},
         block_validator::ValidatingBlock,
+        chain_synchronizer::{Error as ChainSynchronizerError, TrieSyncProgress},
         chainspec_loader::CurrentRunInfo,
-        consensus::{BlockContext, ClContext},
-        contract_runtime::{EraValidatorsRequest, ValidatorWeightsByEraIdRequest},
-        deploy_acceptor::Error,
+        consensus::{BlockContext, ClContext, HighwayStateSummary},
+        contract_runtime::{EraValidatorsRequest, QueryError, ValidatorWeightsByEraIdRequest},
+        deploy_acceptor::{AccessLists, Error},
         fetcher::FetchResult,
+        rpc_server::{BalanceAtBlock, ExcessiveBatchSize},
+        small_network::{BanTarget, ConnectionInfo},
+        storage::{Error as StorageError, PendingDeployStoreFull},
     },
     crypto::hash::Digest,
-    rpcs::{chain::BlockIdentifier, docs::OpenRpcSchema},
+    rpcs::docs::OpenRpcSchema,
     types::{
-        Block as LinearBlock, Block, BlockHash, BlockHeader, BlockPayload, BlockSignatures,
-        Chainspec, ChainspecInfo, Deploy, DeployHash, DeployHeader, DeployMetadata, FinalizedBlock,
-        Item, NodeId, StatusFeed, TimeDiff,
+        chainspec::DeployConfig, Block as LinearBlock, Block, BlockHash, BlockHeader,
+        BlockHeaderWithMetadata, BlockIdentifier, BlockPayload, BlockSignatures, BlockWithMetadata,
+        Chainspec, ChainspecInfo, ContractEventRecord, Deploy, DeployHash, DeployHeader,
+        DeployMetadata, EraRewards, FinalizedBlock, Item, NodeId, StatusFeed, TimeDiff,
     },
     utils::DisplayIter,
 };
@@ -117,6 +127,19 @@ pub enum NetworkRequest<I, P> {
         #[serde(skip_serializing)]
         responder: Responder<HashSet<I>>,
     },
+    /// Gossip a message to a subset of peers, preferring validators of the given era.
+    ValidatorGossip {
+        /// Payload to gossip.
+        payload: Box<P>,
+        /// The era whose validators are preferred, with a probability proportional to their
+        /// weight.
+        era_id: EraId,
+        /// Number of peers to gossip to. This is an upper bound, otherwise best-effort.
+        count: usize,
+        /// Responder to be called when all messages are queued.
+        #[serde(skip_serializing)]
+        responder: Responder<HashSet<I>>,
+    },
 }
 
 impl<I, P> NetworkRequest<I, P> {
@@ -152,6 +175,17 @@ impl<I, P> NetworkRequest<I, P> {
                 exclude,
                 responder,
             },
+            NetworkRequest::ValidatorGossip {
+                payload,
+                era_id,
+                count,
+                responder,
+            } => NetworkRequest::ValidatorGossip {
+                payload: Box::new(wrap_payload(*payload)),
+                era_id,
+                count,
+                responder,
+            },
         }
     }
 }
@@ -170,6 +204,9 @@ where
                 write!(formatter, "broadcast: {}", payload)
             }
             NetworkRequest::Gossip { payload, .. } => write!(formatter, "gossip: {}", payload),
+            NetworkRequest::ValidatorGossip {
+                payload, era_id, ..
+            } => write!(formatter, "gossip to validators of {}: {}", era_id, payload),
         }
     }
 }
@@ -184,6 +221,27 @@ pub enum NetworkInfoRequest<I> {
         // TODO - change the `String` field to a `libp2p::Multiaddr` once small_network is removed.
         responder: Responder<BTreeMap<I, String>>,
     },
+    /// Get the connected peer which has identified itself with the given consensus key, if any.
+    GetPeerByPublicKey {
+        /// The consensus key of the peer.
+        public_key: Box<PublicKey>,
+        /// Responder to be called with the peer, if connected.
+        responder: Responder<Option<I>>,
+    },
+    /// Bans a peer or address for the given duration, closing any existing connections to it.
+    Ban {
+        /// The peer or address to ban.
+        target: BanTarget<I>,
+        /// How long the ban lasts.
+        duration: TimeDiff,
+        /// Responder to be called once the ban is in effect.
+        responder: Responder<()>,
+    },
+    /// Get diagnostic information about all incoming and outgoing connections.
+    GetConnections {
+        /// Responder to be called with the connections.
+        responder: Responder<Vec<ConnectionInfo<I>>>,
+    },
 }
 
 impl<I> Display for NetworkInfoRequest<I>
@@ -193,6 +251,13 @@ where
     fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
         match self {
             NetworkInfoRequest::GetPeers { responder: _ } => write!(formatter, "get peers"),
+            NetworkInfoRequest::GetPeerByPublicKey { public_key, .. } => {
+                write!(formatter, "get peer by public key {}", public_key)
+            }
+            NetworkInfoRequest::Ban {
+                target, duration, ..
+            } => write!(formatter, "ban {} for {}", target, duration),
+            NetworkInfoRequest::GetConnections { .. } => write!(formatter, "get connections"),
         }
     }
 }
@@ -210,6 +275,23 @@ pub enum StorageRequest {
         /// attempt or false if it was previously stored.
         responder: Responder<bool>,
     },
+    /// Store given block together with the execution results of its deploys and the events emitted
+    /// by contracts while executing it.
+    ///
+    /// The block, the execution results and the events are committed atomically: either all are
+    /// stored or none is.
+    PutExecutedBlock {
+        /// Block to be stored.
+        block: Box<Block>,
+        /// Mapping of deploys to execution results of the block.
+        execution_results: HashMap<DeployHash, ExecutionResult>,
+        /// The events emitted by contracts while executing the block.
+        contract_events: Vec<ContractEventRecord>,
+        /// Responder to call with the result.  Returns true if the block was stored on this
+        /// attempt or false if it was previously stored.  Errors other than corruption, which is
+        /// fatal, are passed on to the requester.
+        responder: Responder<Result<bool, StorageError>>,
+    },
     /// Retrieve block with given hash.
     GetBlock {
         /// Hash of block to be retrieved.
@@ -232,6 +314,13 @@ pub enum StorageRequest {
         /// Responder.
         responder: Responder<Option<Block>>,
     },
+    /// Retrieve the header of the block with given height, along with its finality signatures.
+    GetBlockHeaderAndMetadataAtHeight {
+        /// Height of the block.
+        height: BlockHeight,
+        /// Responder.
+        responder: Responder<Option<BlockHeaderWithMetadata>>,
+    },
     /// Retrieve highest block.
     GetHighestBlock {
         /// Responder.
@@ -271,6 +360,15 @@ pub enum StorageRequest {
         /// local storage.
         responder: Responder<Option<BlockHeader>>,
     },
+    /// Retrieve all blocks proposed by the given validator in the given era, ordered by height.
+    GetBlocksByProposer {
+        /// Public key of the proposer.
+        public_key: PublicKey,
+        /// Era in which the blocks were proposed.
+        era_id: EraId,
+        /// Responder to call with the result.
+        responder: Responder<Vec<Block>>,
+    },
     /// Retrieve all transfers in a block with given hash.
     GetBlockTransfers {
         /// Hash of block to get transfers of.
@@ -287,6 +385,39 @@ pub enum StorageRequest {
         /// attempt or false if it was previously stored.
         responder: Responder<bool>,
     },
+    /// Retrieve the hashes of deploys submitted by the given account, most recent first.
+    GetDeploysByAccount {
+        /// Hash of the account which submitted the deploys.
+        account_hash: AccountHash,
+        /// Maximum number of deploy hashes to return.
+        limit: usize,
+        /// Number of most recent deploy hashes to skip.
+        offset: usize,
+        /// Responder to call with the results.
+        responder: Responder<Vec<DeployHash>>,
+    },
+    /// Store a deploy which doesn't have enough approvals to be submitted yet, replacing any
+    /// previously stored version of it. Pending deploys are kept until they expire.
+    PutPendingDeploy {
+        /// Deploy to store.
+        deploy: Box<Deploy>,
+        /// Responder to call once the deploy is stored, or with the reason it was rejected.
+        responder: Responder<Result<(), PendingDeployStoreFull>>,
+    },
+    /// Retrieve the pending deploy with the given hash, if it exists and hasn't expired.
+    GetPendingDeploy {
+        /// Hash of the pending deploy.
+        deploy_hash: DeployHash,
+        /// Responder to call with the result.
+        responder: Responder<Option<Box<Deploy>>>,
+    },
+    /// Remove the pending deploy with the given hash.
+    RemovePendingDeploy {
+        /// Hash of the pending deploy.
+        deploy_hash: DeployHash,
+        /// Responder to call with the result. Returns true if the deploy was stored.
+        responder: Responder<bool>,
+    },
     /// Retrieve deploys with given hashes.
     GetDeploys {
         /// Hashes of deploys to be retrieved.
@@ -306,8 +437,9 @@ pub enum StorageRequest {
         /// Maximum TTL of block we're interested in.
         /// I.e. we don't want deploys from blocks that are older than this.
         ttl: TimeDiff,
-        /// Responder to call with the results.
-        responder: Responder<Vec<(DeployHash, DeployHeader)>>,
+        /// Responder to call with the results.  Errors other than corruption, which is fatal, are
+        /// passed on to the requester.
+        responder: Responder<Result<Vec<(DeployHash, DeployHeader)>, StorageError>>,
     },
     /// Store execution results for a set of deploys of a single block.
     ///
@@ -324,6 +456,54 @@ pub enum StorageRequest {
         /// Responder to call when done storing.
         responder: Responder<()>,
     },
+    /// Retrieve the execution results of all deploys and transfers of a block.
+    GetExecutionResultsByBlock {
+        /// Hash of the block.
+        block_hash: BlockHash,
+        /// Responder to call with the results.  Returns `None` if the block is not stored
+        /// locally.  Results which were pruned or never stored are omitted.
+        responder: Responder<Option<Vec<(DeployHash, ExecutionResult)>>>,
+    },
+    /// Retrieve the events emitted by a contract within a range of blocks.
+    GetContractEvents {
+        /// The hash of the contract which emitted the events.
+        contract_hash: ContractHash,
+        /// The height of the first block to include.
+        from_block: u64,
+        /// The height of the last block to include.
+        to_block: u64,
+        /// Responder to call with the events, in order of emission.
+        responder: Responder<Vec<ContractEventRecord>>,
+    },
+    /// Store the rewards distributed at the end of an era.
+    PutEraRewards {
+        /// The rewards.  Rewards previously stored for the same era are replaced.
+        era_rewards: Box<EraRewards>,
+        /// Responder to call when done storing.
+        responder: Responder<()>,
+    },
+    /// Retrieve the rewards distributed at the end of an era.
+    GetEraRewards {
+        /// The era.
+        era_id: EraId,
+        /// Responder to call with the rewards, or `None` if none are stored for the era.
+        responder: Responder<Option<EraRewards>>,
+    },
+    /// Store the journals of global state operations of executed deploys.
+    PutExecutionJournals {
+        /// The journals by deploy hash.  Journals previously stored for the same deploys are
+        /// replaced.
+        journals: HashMap<DeployHash, Vec<JournalEntry>>,
+        /// Responder to call when done storing.
+        responder: Responder<()>,
+    },
+    /// Retrieve the journal of global state operations of an executed deploy.
+    GetExecutionJournal {
+        /// The hash of the deploy.
+        deploy_hash: DeployHash,
+        /// Responder to call with the journal, or `None` if none is stored for the deploy.
+        responder: Responder<Option<Vec<JournalEntry>>>,
+    },
     /// Retrieve deploy and its metadata.
     GetDeployAndMetadata {
         /// Hash of deploy to be retrieved.
@@ -331,23 +511,28 @@ pub enum StorageRequest {
         /// Responder to call with the results.
         responder: Responder<Option<(Deploy, DeployMetadata)>>,
     },
-    /// Retrieve block and its metadata by its hash.
-    GetBlockAndMetadataByHash {
+    /// Retrieve block, its finality signatures and the execution result summaries of its deploys
+    /// by the block's hash.
+    ///
+    /// All parts are read within a single database transaction, so they form a consistent view.
+    GetBlockWithMetadata {
         /// The hash of the block.
         block_hash: BlockHash,
         /// The responder to call with the results.
-        responder: Responder<Option<(Block, BlockSignatures)>>,
+        responder: Responder<Option<BlockWithMetadata>>,
     },
-    /// Retrieve block and its metadata at a given height.
-    GetBlockAndMetadataByHeight {
-        /// The height of the block.
-        block_height: BlockHeight,
+    /// Retrieve a block header by its hash or height, or the highest one.
+    GetBlockHeaderByIdentifier {
+        /// The identifier of the block.
+        block_identifier: BlockIdentifier,
         /// The responder to call with the results.
-        responder: Responder<Option<(Block, BlockSignatures)>>,
+        responder: Responder<Option<BlockHeader>>,
     },
-    /// Get the highest block and its metadata.
-    GetHighestBlockWithMetadata {
-        /// The responder to call the results with.
+    /// Retrieve a block by its hash or height, or the highest one, with its metadata.
+    GetBlockAndMetadataByIdentifier {
+        /// The identifier of the block.
+        block_identifier: BlockIdentifier,
+        /// The responder to call with the results.
         responder: Responder<Option<(Block, BlockSignatures)>>,
     },
     /// Get finality signatures for a Block hash.
@@ -371,6 +556,9 @@ impl Display for StorageRequest {
     fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
         match self {
             StorageRequest::PutBlock { block, .. } => write!(formatter, "put {}", block),
+            StorageRequest::PutExecutedBlock { block, .. } => {
+                write!(formatter, "put {} with execution results", block)
+            }
             StorageRequest::GetBlock { block_hash, .. } => write!(formatter, "get {}", block_hash),
             StorageRequest::GetBlockHeaderAtHeight { height, .. } => {
                 write!(formatter, "get block header at height {}", height)
@@ -378,6 +566,11 @@ impl Display for StorageRequest {
             StorageRequest::GetBlockAtHeight { height, .. } => {
                 write!(formatter, "get block at height {}", height)
             }
+            StorageRequest::GetBlockHeaderAndMetadataAtHeight { height, .. } => write!(
+                formatter,
+                "get block header and metadata at height {}",
+                height
+            ),
             StorageRequest::GetHighestBlock { .. } => write!(formatter, "get highest block"),
             StorageRequest::GetSwitchBlockHeaderAtEraId { era_id, .. } => {
                 write!(formatter, "get switch block header at era id {}", era_id)
@@ -394,10 +587,36 @@ impl Display for StorageRequest {
             StorageRequest::GetBlockHeader { block_hash, .. } => {
                 write!(formatter, "get {}", block_hash)
             }
+            StorageRequest::GetBlocksByProposer {
+                public_key, era_id, ..
+            } => write!(
+                formatter,
+                "get blocks proposed by {} in era {}",
+                public_key, era_id
+            ),
             StorageRequest::GetBlockTransfers { block_hash, .. } => {
                 write!(formatter, "get transfers for {}", block_hash)
             }
             StorageRequest::PutDeploy { deploy, .. } => write!(formatter, "put {}", deploy),
+            StorageRequest::GetDeploysByAccount {
+                account_hash,
+                limit,
+                offset,
+                ..
+            } => write!(
+                formatter,
+                "get {} deploys of {} from offset {}",
+                limit, account_hash, offset
+            ),
+            StorageRequest::PutPendingDeploy { deploy, .. } => {
+                write!(formatter, "put pending {}", deploy)
+            }
+            StorageRequest::GetPendingDeploy { deploy_hash, .. } => {
+                write!(formatter, "get pending {}", deploy_hash)
+            }
+            StorageRequest::RemovePendingDeploy { deploy_hash, .. } => {
+                write!(formatter, "remove pending {}", deploy_hash)
+            }
             StorageRequest::GetDeploys { deploy_hashes, .. } => {
                 write!(formatter, "get {}", DisplayIter::new(deploy_hashes.iter()))
             }
@@ -409,26 +628,43 @@ impl Display for StorageRequest {
             StorageRequest::PutExecutionResults { block_hash, .. } => {
                 write!(formatter, "put execution results for {}", block_hash)
             }
-            StorageRequest::GetDeployAndMetadata { deploy_hash, .. } => {
-                write!(formatter, "get deploy and metadata for {}", deploy_hash)
+            StorageRequest::GetExecutionResultsByBlock { block_hash, .. } => {
+                write!(formatter, "get execution results for {}", block_hash)
             }
-            StorageRequest::GetBlockAndMetadataByHash { block_hash, .. } => {
-                write!(
-                    formatter,
-                    "get block and metadata for block with hash: {}",
-                    block_hash
-                )
+            StorageRequest::GetContractEvents {
+                contract_hash,
+                from_block,
+                to_block,
+                ..
+            } => write!(
+                formatter,
+                "get events of {} from block {} to {}",
+                contract_hash, from_block, to_block
+            ),
+            StorageRequest::PutEraRewards { era_rewards, .. } => {
+                write!(formatter, "put rewards of {}", era_rewards.era_id)
             }
-            StorageRequest::GetBlockAndMetadataByHeight { block_height, .. } => {
-                write!(
-                    formatter,
-                    "get block and metadata for block at height: {}",
-                    block_height
-                )
+            StorageRequest::GetEraRewards { era_id, .. } => {
+                write!(formatter, "get rewards of {}", era_id)
+            }
+            StorageRequest::PutExecutionJournals { journals, .. } => {
+                write!(formatter, "put {} execution journals", journals.len())
             }
-            StorageRequest::GetHighestBlockWithMetadata { .. } => {
-                write!(formatter, "get highest block with metadata")
+            StorageRequest::GetExecutionJournal { deploy_hash, .. } => {
+                write!(formatter, "get execution journal of {}", deploy_hash)
             }
+            StorageRequest::GetDeployAndMetadata { deploy_hash, .. } => {
+                write!(formatter, "get deploy and metadata for {}", deploy_hash)
+            }
+            StorageRequest::GetBlockWithMetadata { block_hash, .. } => {
+                write!(formatter, "get block with full metadata for {}", block_hash)
+            }
+            StorageRequest::GetBlockHeaderByIdentifier {
+                block_identifier, ..
+            } => write!(formatter, "get block header for {}", block_identifier),
+            StorageRequest::GetBlockAndMetadataByIdentifier {
+                block_identifier, ..
+            } => write!(formatter, "get block and metadata for {}", block_identifier),
             StorageRequest::GetBlockSignatures { block_hash, .. } => {
                 write!(
                     formatter,
@@ -487,6 +723,12 @@ impl Display for StateStoreRequest {
 pub struct BlockPayloadRequest {
     /// The context in which the new block will be proposed.
     pub(crate) context: BlockContext<ClContext>,
+    /// The minimum gas price of the deploys in the new block.
+    pub(crate) gas_price_floor: u64,
+    /// The era in which the new block will be proposed.
+    pub(crate) era_id: EraId,
+    /// The public key of the validator proposing the block.
+    pub(crate) proposer: PublicKey,
     /// The height of the next block to be finalized at the point the request was made.
     /// This is _only_ a way of expressing how many blocks have been finalized at the moment the
     /// request was made. Block Proposer uses this in order to determine if there might be any
@@ -506,6 +748,24 @@ pub struct BlockPayloadRequest {
 pub enum BlockProposerRequest {
     /// Request a list of deploys to propose in a new block.
     RequestBlockPayload(BlockPayloadRequest),
+    /// Request the pending deploys that were not included in the most recently proposed block,
+    /// together with the reason.
+    GetDeferredDeploys {
+        /// Responder to call with the result.
+        responder: Responder<Vec<(DeployHash, DeferralReason)>>,
+    },
+    /// Request the most recently rejected deploys whose dependencies formed a cycle.
+    GetDependencyCycles {
+        /// Responder to call with the result.
+        responder: Responder<Vec<DependencyCycle>>,
+    },
+    /// Request a page of the pending deploys matching the query.
+    GetPendingDeploys {
+        /// The filters and pagination to apply.
+        query: PendingDeploysQuery,
+        /// Responder to call with the result.
+        responder: Responder<PendingDeploys>,
+    },
 }
 
 impl Display for BlockProposerRequest {
@@ -513,17 +773,31 @@ impl Display for BlockProposerRequest {
         match self {
             BlockProposerRequest::RequestBlockPayload(BlockPayloadRequest {
                 context,
+                gas_price_floor: _,
+                era_id,
+                proposer,
                 next_finalized,
                 responder: _,
                 accusations: _,
                 random_bit: _,
             }) => write!(
                 formatter,
-                "list for inclusion: instant {} height {} next_finalized {}",
+                "list for inclusion: instant {} {} height {} next_finalized {} proposer {}",
                 context.timestamp(),
+                era_id,
                 context.height(),
-                next_finalized
+                next_finalized,
+                proposer
             ),
+            BlockProposerRequest::GetDeferredDeploys { .. } => {
+                write!(formatter, "get deferred deploys")
+            }
+            BlockProposerRequest::GetDependencyCycles { .. } => {
+                write!(formatter, "get dependency cycles")
+            }
+            BlockProposerRequest::GetPendingDeploys { query, .. } => {
+                write!(formatter, "get pending deploys: {:?}", query)
+            }
         }
     }
 }
@@ -542,14 +816,91 @@ pub enum RpcRequest<I> {
         /// Responder to call.
         responder: Responder<Result<(), Error>>,
     },
-    /// If `maybe_identifier` is `Some`, return the specified block if it exists, else `None`.  If
-    /// `maybe_identifier` is `None`, return the latest block.
+    /// Submit a batch of deploys to be announced, responding with the result for each of them.
+    SubmitDeploys {
+        /// The deploys to be announced.
+        deploys: Vec<Deploy>,
+        /// Responder to call.
+        responder: Responder<Result<Vec<Result<(), Error>>, ExcessiveBatchSize>>,
+    },
+    /// Return the specified block and its signatures if it exists, else `None`.
     GetBlock {
-        /// The identifier (can either be a hash or the height) of the block to be retrieved.
-        maybe_id: Option<BlockIdentifier>,
+        /// The identifier (hash, height or latest) of the block to be retrieved.
+        block_identifier: BlockIdentifier,
         /// Responder to call with the result.
         responder: Responder<Option<(LinearBlock, BlockSignatures)>>,
     },
+    /// Return the blocks proposed by a validator in an era.
+    GetBlocksByProposer {
+        /// The public key of the proposer.
+        public_key: PublicKey,
+        /// The era to retrieve blocks for.
+        era_id: EraId,
+        /// Responder to call with the result.
+        responder: Responder<Vec<LinearBlock>>,
+    },
+    /// Return the hashes of deploys submitted by an account, most recent first.
+    GetDeploysByAccount {
+        /// The hash of the account which submitted the deploys.
+        account_hash: AccountHash,
+        /// The maximum number of deploy hashes to return.
+        limit: usize,
+        /// The number of most recent deploy hashes to skip.
+        offset: usize,
+        /// Responder to call with the result.
+        responder: Responder<Vec<DeployHash>>,
+    },
+    /// Return the events emitted by a contract within a range of blocks.
+    GetContractEvents {
+        /// The hash of the contract which emitted the events.
+        contract_hash: ContractHash,
+        /// The height of the first block to include.
+        from_block: u64,
+        /// The height of the last block to include.
+        to_block: u64,
+        /// Responder to call with the result.
+        responder: Responder<Vec<ContractEventRecord>>,
+    },
+    /// Return the rewards distributed at the end of an era.
+    GetEraRewards {
+        /// The era.
+        era_id: EraId,
+        /// Responder to call with the result.
+        responder: Responder<Option<EraRewards>>,
+    },
+    /// Return the journal of global state operations of an executed deploy.
+    GetExecutionJournal {
+        /// The hash of the deploy.
+        deploy_hash: DeployHash,
+        /// Responder to call with the result.
+        responder: Responder<Option<Vec<JournalEntry>>>,
+    },
+    /// Return the chain name and the deploy configuration against which deploys are checked.
+    GetDeployConfig {
+        /// Responder to call with the chain name and deploy configuration.
+        responder: Responder<(String, DeployConfig)>,
+    },
+    /// Store a deploy which doesn't have enough approvals to be submitted yet.
+    PutPendingDeploy {
+        /// The pending deploy.
+        deploy: Box<Deploy>,
+        /// Responder to call once the deploy is stored, or with the reason it was rejected.
+        responder: Responder<Result<(), PendingDeployStoreFull>>,
+    },
+    /// Return the pending deploy with the given hash, if it exists and hasn't expired.
+    GetPendingDeploy {
+        /// The hash of the pending deploy.
+        deploy_hash: DeployHash,
+        /// Responder to call with the result.
+        responder: Responder<Option<Box<Deploy>>>,
+    },
+    /// Remove the pending deploy with the given hash, e.g. once it has been submitted.
+    RemovePendingDeploy {
+        /// The hash of the pending deploy.
+        deploy_hash: DeployHash,
+        /// Responder to call once the deploy is removed.
+        responder: Responder<()>,
+    },
     /// Return transfers for block by hash (if any).
     GetBlockTransfers {
         /// The hash of the block to retrieve transfers for.
@@ -566,7 +917,7 @@ pub enum RpcRequest<I> {
         /// The path components starting from the key as base.
         path: Vec<String>,
         /// Responder to call with the result.
-        responder: Responder<Result<QueryResult, engine_state::Error>>,
+        responder: Responder<Result<QueryResult, QueryError>>,
     },
     /// Query the global state at the given root hash.
     QueryEraValidators {
@@ -582,7 +933,7 @@ pub enum RpcRequest<I> {
         /// The global state hash.
         state_root_hash: Digest,
         /// Responder to call with the result.
-        responder: Responder<Result<GetBidsResult, engine_state::Error>>,
+        responder: Responder<Result<GetBidsResult, QueryError>>,
     },
     /// Query the contract runtime for protocol version data.
     QueryProtocolData {
@@ -598,7 +949,16 @@ pub enum RpcRequest<I> {
         /// The purse URef.
         purse_uref: URef,
         /// Responder to call with the result.
-        responder: Responder<Result<BalanceResult, engine_state::Error>>,
+        responder: Responder<Result<BalanceResult, QueryError>>,
+    },
+    /// Return the balance of a purse as of the given block.
+    GetBalanceAtBlock {
+        /// The identifier of the block at whose state root the balance is read.
+        block_identifier: BlockIdentifier,
+        /// Either the purse's `Key::URef`, or a `Key::Account` whose main purse is used.
+        purse_or_account: Key,
+        /// Responder to call with the result.
+        responder: Responder<BalanceAtBlock>,
     },
     /// Return the specified deploy and metadata if it exists, else `None`.
     GetDeploy {
@@ -628,15 +988,51 @@ impl<I> Display for RpcRequest<I> {
     fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
         match self {
             RpcRequest::SubmitDeploy { deploy, .. } => write!(formatter, "submit {}", *deploy),
+            RpcRequest::SubmitDeploys { deploys, .. } => {
+                write!(formatter, "submit {} deploys", deploys.len())
+            }
             RpcRequest::GetBlock {
-                maybe_id: Some(BlockIdentifier::Hash(hash)),
+                block_identifier, ..
+            } => write!(formatter, "get {}", block_identifier),
+            RpcRequest::GetBlocksByProposer {
+                public_key, era_id, ..
+            } => write!(formatter, "get blocks by {} in era {}", public_key, era_id),
+            RpcRequest::GetDeploysByAccount {
+                account_hash,
+                limit,
+                offset,
                 ..
-            } => write!(formatter, "get {}", hash),
-            RpcRequest::GetBlock {
-                maybe_id: Some(BlockIdentifier::Height(height)),
+            } => write!(
+                formatter,
+                "get {} deploys of {} from offset {}",
+                limit, account_hash, offset
+            ),
+            RpcRequest::GetContractEvents {
+                contract_hash,
+                from_block,
+                to_block,
                 ..
-            } => write!(formatter, "get {}", height),
-            RpcRequest::GetBlock { maybe_id: None, .. } => write!(formatter, "get latest block"),
+            } => write!(
+                formatter,
+                "get events of {} from block {} to {}",
+                contract_hash, from_block, to_block
+            ),
+            RpcRequest::GetEraRewards { era_id, .. } => {
+                write!(formatter, "get rewards of {}", era_id)
+            }
+            RpcRequest::GetExecutionJournal { deploy_hash, .. } => {
+                write!(formatter, "get execution journal of {}", deploy_hash)
+            }
+            RpcRequest::GetDeployConfig { .. } => write!(formatter, "get deploy config"),
+            RpcRequest::PutPendingDeploy { deploy, .. } => {
+                write!(formatter, "put pending {}", deploy)
+            }
+            RpcRequest::GetPendingDeploy { deploy_hash, .. } => {
+                write!(formatter, "get pending {}", deploy_hash)
+            }
+            RpcRequest::RemovePendingDeploy { deploy_hash, .. } => {
+                write!(formatter, "remove pending {}", deploy_hash)
+            }
             RpcRequest::GetBlockTransfers { block_hash, .. } => {
                 write!(formatter, "get transfers {}", block_hash)
             }
@@ -670,6 +1066,15 @@ impl<I> Display for RpcRequest<I> {
                 "balance {}, purse_uref: {}",
                 state_root_hash, purse_uref
             ),
+            RpcRequest::GetBalanceAtBlock {
+                block_identifier,
+                purse_or_account,
+                ..
+            } => write!(
+                formatter,
+                "balance of {} at {}",
+                purse_or_account, block_identifier
+            ),
             RpcRequest::GetDeploy { hash, .. } => write!(formatter, "get {}", hash),
             RpcRequest::GetPeers { .. } => write!(formatter, "get peers"),
             RpcRequest::GetStatus { .. } => write!(formatter, "get status"),
@@ -700,14 +1105,105 @@ pub enum RestRequest<I> {
         /// Responder to call with the result
         responder: Responder<OpenRpcSchema>,
     },
+    /// Bans a peer or address for the given duration.
+    BanPeer {
+        /// The peer or address to ban.
+        target: BanTarget<I>,
+        /// How long the ban lasts.
+        duration: TimeDiff,
+        /// Responder to call once the ban is in effect.
+        responder: Responder<()>,
+    },
+    /// Replaces the deploy acceptor's allowlist and denylist.
+    SetDeployAccessLists {
+        /// The new lists.
+        access_lists: AccessLists,
+        /// Responder to call once the new lists are in effect.
+        responder: Responder<()>,
+    },
+    /// Returns the differences between the global states under two state root hashes.
+    DiffState {
+        /// State diff request.
+        state_diff_request: StateDiffRequest,
+        /// Responder to call with the result.
+        responder: Responder<Result<StateDiffResult, QueryError>>,
+    },
+    /// Returns a summary of the given era's Highway protocol state, if the era is active.
+    GetConsensusStateSummary {
+        /// The era to summarize.
+        era_id: EraId,
+        /// Responder to call with the result.
+        responder: Responder<Option<HighwayStateSummary<ClContext>>>,
+    },
+    /// Returns the most recently rejected deploys whose dependencies formed a cycle.
+    GetDependencyCycles {
+        /// Responder to call with the result.
+        responder: Responder<Vec<DependencyCycle>>,
+    },
+    /// Returns a page of the block proposer's pending deploys matching the query.
+    GetPendingDeploys {
+        /// The filters and pagination to apply.
+        query: PendingDeploysQuery,
+        /// Responder to call with the result.
+        responder: Responder<PendingDeploys>,
+    },
+    /// Returns the pending deploys left out of the most recently proposed block, with the reason.
+    GetDeferredDeploys {
+        /// Responder to call with the result.
+        responder: Responder<Vec<(DeployHash, DeferralReason)>>,
+    },
+    /// Returns diagnostic information about the node's network connections.
+    GetConnections {
+        /// Responder to call with the result.
+        responder: Responder<Vec<ConnectionInfo<I>>>,
+    },
 }
 
-impl<I> Display for RestRequest<I> {
+impl<I: Display> Display for RestRequest<I> {
     fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
         match self {
             RestRequest::GetStatus { .. } => write!(formatter, "get status"),
             RestRequest::GetMetrics { .. } => write!(formatter, "get metrics"),
             RestRequest::GetRpcSchema { .. } => write!(formatter, "get openrpc"),
+            RestRequest::BanPeer { target, .. } => write!(formatter, "ban {}", target),
+            RestRequest::SetDeployAccessLists { .. } => {
+                write!(formatter, "set deploy access lists")
+            }
+            RestRequest::DiffState {
+                state_diff_request, ..
+            } => write!(formatter, "diff state: {:?}", state_diff_request),
+            RestRequest::GetConsensusStateSummary { era_id, .. } => {
+                write!(formatter, "get consensus state summary for {}", era_id)
+            }
+            RestRequest::GetDependencyCycles { .. } => write!(formatter, "get dependency cycles"),
+            RestRequest::GetPendingDeploys { query, .. } => {
+                write!(formatter, "get pending deploys: {:?}", query)
+            }
+            RestRequest::GetDeferredDeploys { .. } => write!(formatter, "get deferred deploys"),
+            RestRequest::GetConnections { .. } => write!(formatter, "get connections"),
+        }
+    }
+}
+
+/// A deploy acceptor request.
+#[derive(Debug, Serialize)]
+#[must_use]
+pub enum DeployAcceptorRequest {
+    /// Replaces the allowlist and denylist applied to new deploys.
+    SetAccessLists {
+        /// The new lists.
+        access_lists: AccessLists,
+        /// Responder to call once the new lists are in effect.
+        responder: Responder<()>,
+    },
+}
+
+impl Display for DeployAcceptorRequest {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            DeployAcceptorRequest::SetAccessLists { .. } => {
+                write!(formatter, "set access lists")
+            }
         }
     }
 }
@@ -733,6 +1229,15 @@ pub enum ContractRuntimeRequest {
         /// Responder to call with the result.
         responder: Responder<Result<GenesisResult, engine_state::Error>>,
     },
+    /// Commit genesis from the previously exported state snapshot specified in the chainspec.
+    CommitGenesisSnapshot {
+        /// The chainspec.
+        chainspec: Arc<Chainspec>,
+        /// The path of the state snapshot file.
+        snapshot_path: PathBuf,
+        /// Responder to call with the result.
+        responder: Responder<Result<GenesisResult, engine_state::Error>>,
+    },
     /// A request to run upgrade.
     Upgrade {
         /// Upgrade config.
@@ -747,7 +1252,7 @@ pub enum ContractRuntimeRequest {
         #[serde(skip_serializing)]
         query_request: QueryRequest,
         /// Responder to call with the query result.
-        responder: Responder<Result<QueryResult, engine_state::Error>>,
+        responder: Responder<Result<QueryResult, QueryError>>,
     },
     /// A balance request.
     GetBalance {
@@ -755,7 +1260,7 @@ pub enum ContractRuntimeRequest {
         #[serde(skip_serializing)]
         balance_request: BalanceRequest,
         /// Responder to call with the balance result.
-        responder: Responder<Result<BalanceResult, engine_state::Error>>,
+        responder: Responder<Result<BalanceResult, QueryError>>,
     },
     /// Returns validator weights.
     GetEraValidators {
@@ -779,7 +1284,7 @@ pub enum ContractRuntimeRequest {
         #[serde(skip_serializing)]
         get_bids_request: GetBidsRequest,
         /// Responder to call with the result.
-        responder: Responder<Result<GetBidsResult, engine_state::Error>>,
+        responder: Responder<Result<GetBidsResult, QueryError>>,
     },
     /// Performs a step consisting of calculating rewards, slashing and running the auction at the
     /// end of an era.
@@ -824,6 +1329,14 @@ pub enum ContractRuntimeRequest {
         /// Responder to call with the result.
         responder: Responder<Result<Vec<Blake2bHash>, engine_state::Error>>,
     },
+    /// Returns the differences between the global states under two state root hashes.
+    DiffState {
+        /// State diff request.
+        #[serde(skip_serializing)]
+        state_diff_request: StateDiffRequest,
+        /// Responder to call with the result.
+        responder: Responder<Result<StateDiffResult, QueryError>>,
+    },
 }
 
 impl Display for ContractRuntimeRequest {
@@ -839,6 +1352,18 @@ impl Display for ContractRuntimeRequest {
                     chainspec.protocol_config.version
                 )
             }
+            ContractRuntimeRequest::CommitGenesisSnapshot {
+                chainspec,
+                snapshot_path,
+                ..
+            } => {
+                write!(
+                    formatter,
+                    "commit genesis {} from snapshot {}",
+                    chainspec.protocol_config.version,
+                    snapshot_path.display()
+                )
+            }
 
             ContractRuntimeRequest::Upgrade { upgrade_config, .. } => {
                 write!(formatter, "upgrade request: {:?}", upgrade_config)
@@ -885,6 +1410,9 @@ impl Display for ContractRuntimeRequest {
             ContractRuntimeRequest::PutTrie { trie, .. } => {
                 write!(formatter, "trie: {:?}", trie)
             }
+            ContractRuntimeRequest::DiffState {
+                state_diff_request, ..
+            } => write!(formatter, "diff state: {:?}", state_diff_request),
             ContractRuntimeRequest::MissingTrieKeys { trie_key, .. } => {
                 write!(
                     formatter,
@@ -976,6 +1504,13 @@ impl<I: Display> Display for LinearChainRequest<I> {
 pub enum ConsensusRequest {
     /// Request for our public key, and if we're a validator, the next round length.
     Status(Responder<Option<(PublicKey, Option<TimeDiff>)>>),
+    /// Request for a summary of the given era's Highway protocol state, if the era is active.
+    StateSummary {
+        /// The era to summarize.
+        era_id: EraId,
+        /// Responder to call with the result.
+        responder: Responder<Option<HighwayStateSummary<ClContext>>>,
+    },
 }
 
 /// ChainspecLoader component requests.
@@ -995,3 +1530,29 @@ impl Display for ChainspecLoaderRequest {
         }
     }
 }
+
+/// Chain synchronizer component requests.
+#[derive(Debug, Serialize)]
+#[must_use]
+pub enum ChainSynchronizerRequest {
+    /// Download the global state trie under the given state root hash from peers.
+    SyncTrie {
+        /// The state root hash of the trie to download.
+        state_root_hash: Digest,
+        /// Responder to call once the whole trie is stored locally or the sync failed.
+        responder: Responder<Result<(), ChainSynchronizerError>>,
+    },
+    /// Request for the progress of the most recent global state trie sync.
+    Progress(Responder<Option<TrieSyncProgress>>),
+}
+
+impl Display for ChainSynchronizerRequest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChainSynchronizerRequest::SyncTrie {
+                state_root_hash, ..
+            } => write!(f, "sync trie under state root {}", state_root_hash),
+            ChainSynchronizerRequest::Progress(_) => write!(f, "get trie sync progress"),
+        }
+    }
+}
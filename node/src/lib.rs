@@ -54,8 +54,11 @@ use signal_hook::{
 
 pub use components::{
     block_proposer::Config as BlockProposerConfig,
+    chain_synchronizer::Config as ChainSynchronizerConfig,
     consensus::Config as ConsensusConfig,
-    contract_runtime::Config as ContractRuntimeConfig,
+    contract_runtime::{
+        export_state as export_global_state, Config as ContractRuntimeConfig, ExportStateError,
+    },
     deploy_acceptor::Config as DeployAcceptorConfig,
     event_stream_server::Config as EventStreamServerConfig,
     fetcher::Config as FetcherConfig,
@@ -63,7 +66,10 @@ pub use components::{
     rest_server::Config as RestServerConfig,
     rpc_server::{rpcs, Config as RpcServerConfig},
     small_network::{Config as SmallNetworkConfig, Error as SmallNetworkError},
-    storage::{Config as StorageConfig, Error as StorageError},
+    storage::{
+        backup as backup_storage, restore as restore_storage, BackupError as StorageBackupError,
+        Config as StorageConfig, Error as StorageError, StorageSummary,
+    },
 };
 pub use config_migration::{migrate_config, Error as ConfigMigrationError};
 pub use data_migration::{migrate_data, Error as DataMigrationError};
@@ -62,6 +62,7 @@ impl Payload for Message {
                     Tag::BlockByHeight => MessageKind::BlockTransfer,
                     Tag::BlockHeaderByHash => MessageKind::BlockTransfer,
                     Tag::BlockHeaderAndFinalitySignaturesByHeight => MessageKind::BlockTransfer,
+                    Tag::TrieNode => MessageKind::TrieTransfer,
                 }
             }
             Message::FinalitySignature(_) => MessageKind::Consensus,
@@ -25,9 +25,11 @@
 //! [`run`](struct.Runner.html#method.crank).
 
 mod event_queue_metrics;
+mod event_tracing;
 pub mod initializer;
 pub mod joiner;
 mod queue_kind;
+mod scheduler_config;
 pub mod validator;
 
 #[cfg(test)]
@@ -47,12 +49,17 @@ use datasize::DataSize;
 use futures::{future::BoxFuture, FutureExt};
 use jemalloc_ctl::{epoch as jemalloc_epoch, stats::allocated as jemalloc_allocated};
 use once_cell::sync::Lazy;
-use prometheus::{self, Histogram, HistogramOpts, IntCounter, IntGauge, Registry};
+use prometheus::{
+    self, Histogram, HistogramOpts, IntCounter, IntCounterVec, IntGauge, Opts, Registry,
+};
 use quanta::{Clock, IntoNanoseconds};
 use serde::Serialize;
 use signal_hook::consts::signal::{SIGINT, SIGQUIT, SIGTERM};
-use tokio::time::{Duration, Instant};
-use tracing::{debug, debug_span, error, info, instrument, trace, warn};
+use tokio::{
+    task,
+    time::{Duration, Instant},
+};
+use tracing::{debug, debug_span, error, field, info, instrument, trace, warn};
 use tracing_futures::Instrument;
 
 #[cfg(target_os = "linux")]
@@ -62,12 +69,14 @@ use crate::{
     effect::{announcements::ControlAnnouncement, Effect, EffectBuilder, Effects},
     types::{ExitCode, Timestamp},
     unregister_metric,
-    utils::{self, WeightedRoundRobin},
+    utils::{self, CorrelationId, WeightedRoundRobin},
     NodeRng, QUEUE_DUMP_REQUESTED, TERMINATION_REQUESTED,
 };
 #[cfg(test)]
 use crate::{reactor::initializer::Reactor as InitializerReactor, types::Chainspec};
+use event_tracing::EventTracer;
 pub use queue_kind::QueueKind;
+pub use scheduler_config::{Config as SchedulerConfig, FairnessPolicyKind};
 
 /// Optional upper threshold for total RAM allocated in mB before dumping queues to disk.
 const MEM_DUMP_THRESHOLD_MB_ENV_VAR: &str = "CL_MEM_DUMP_THRESHOLD_MB";
@@ -84,12 +93,11 @@ static MEM_DUMP_THRESHOLD_MB: Lazy<Option<u64>> = Lazy::new(|| {
         .ok()
 });
 
-/// Default threshold for when an event is considered slow.  Can be overridden by setting the env
-/// var `CL_EVENT_MAX_MICROSECS=<MICROSECONDS>`.
-const DEFAULT_DISPATCH_EVENT_THRESHOLD: Duration = Duration::from_secs(1);
+/// Override of the configured threshold for when an event is considered slow, set via the env var
+/// `CL_EVENT_MAX_MICROSECS=<MICROSECONDS>`.
 const DISPATCH_EVENT_THRESHOLD_ENV_VAR: &str = "CL_EVENT_MAX_MICROSECS";
 
-static DISPATCH_EVENT_THRESHOLD: Lazy<Duration> = Lazy::new(|| {
+static DISPATCH_EVENT_THRESHOLD: Lazy<Option<Duration>> = Lazy::new(|| {
     env::var(DISPATCH_EVENT_THRESHOLD_ENV_VAR)
         .map(|threshold_str| {
             let threshold_microsecs = u64::from_str(&threshold_str).unwrap_or_else(|error| {
@@ -100,7 +108,7 @@ static DISPATCH_EVENT_THRESHOLD: Lazy<Duration> = Lazy::new(|| {
             });
             Duration::from_micros(threshold_microsecs)
         })
-        .unwrap_or_else(|_| DEFAULT_DISPATCH_EVENT_THRESHOLD)
+        .ok()
 });
 
 #[cfg(target_os = "linux")]
@@ -206,6 +214,12 @@ impl<REv> EventQueueHandle<REv> {
     pub(crate) fn event_queues_counts(&self) -> HashMap<QueueKind, usize> {
         self.0.event_queues_counts()
     }
+
+    /// Returns the age of the oldest event in each of the scheduler's queues.
+    #[inline]
+    pub(crate) fn oldest_event_ages(&self) -> HashMap<QueueKind, Duration> {
+        self.0.oldest_event_ages()
+    }
 }
 
 /// Reactor core.
@@ -327,6 +341,21 @@ where
 
     /// Last queue dump timestamp
     last_queue_dump: Option<Timestamp>,
+
+    /// Tracer of events across components, if event tracing is enabled.
+    event_tracer: Option<EventTracer>,
+
+    /// Maximum number of events processed in a row before yielding to the async runtime.
+    max_events_per_crank: usize,
+
+    /// Number of events processed since the last yield to the async runtime.
+    events_since_yield: usize,
+
+    /// Time a single event may take to dispatch before it is considered to starve the reactor.
+    event_time_budget: Duration,
+
+    /// Whether the last dispatched event exceeded `event_time_budget`.
+    exceeded_time_budget: bool,
 }
 
 /// Metric data for the Runner
@@ -336,6 +365,10 @@ struct RunnerMetrics {
     events: IntCounter,
     /// Histogram of how long it took to dispatch an event.
     event_dispatch_duration: Histogram,
+    /// Number of events exceeding the time budget, per component.
+    slow_events: IntCounterVec,
+    /// Number of times the runner yielded to the async runtime.
+    yields: IntCounter,
     /// Total allocated RAM in bytes, as reported by jemalloc.
     allocated_ram_bytes: IntGauge,
     /// Total consumed RAM in bytes, as reported by sys-info.
@@ -380,6 +413,18 @@ impl RunnerMetrics {
             ]),
         )?;
 
+        let slow_events = IntCounterVec::new(
+            Opts::new(
+                "runner_slow_events",
+                "number of events exceeding the time budget for dispatching, per component",
+            ),
+            &["component"],
+        )?;
+        let yields = IntCounter::new(
+            "runner_yields",
+            "number of times the runner yielded to the async runtime",
+        )?;
+
         let allocated_ram_bytes =
             IntGauge::new("allocated_ram_bytes", "total allocated ram in bytes")?;
         let consumed_ram_bytes =
@@ -388,6 +433,8 @@ impl RunnerMetrics {
 
         registry.register(Box::new(events.clone()))?;
         registry.register(Box::new(event_dispatch_duration.clone()))?;
+        registry.register(Box::new(slow_events.clone()))?;
+        registry.register(Box::new(yields.clone()))?;
         registry.register(Box::new(allocated_ram_bytes.clone()))?;
         registry.register(Box::new(consumed_ram_bytes.clone()))?;
         registry.register(Box::new(total_ram_bytes.clone()))?;
@@ -395,6 +442,8 @@ impl RunnerMetrics {
         Ok(RunnerMetrics {
             events,
             event_dispatch_duration,
+            slow_events,
+            yields,
             registry: registry.clone(),
             allocated_ram_bytes,
             consumed_ram_bytes,
@@ -407,6 +456,8 @@ impl Drop for RunnerMetrics {
     fn drop(&mut self) {
         unregister_metric!(self.registry, self.events);
         unregister_metric!(self.registry, self.event_dispatch_duration);
+        unregister_metric!(self.registry, self.slow_events);
+        unregister_metric!(self.registry, self.yields);
         unregister_metric!(self.registry, self.allocated_ram_bytes);
         unregister_metric!(self.registry, self.consumed_ram_bytes);
         unregister_metric!(self.registry, self.total_ram_bytes);
@@ -431,11 +482,27 @@ where
 
     /// Creates a new runner from a given configuration, using existing metrics.
     #[inline]
-    #[instrument("runner creation", level = "debug", skip(cfg, rng, registry))]
     pub async fn with_metrics(
         cfg: R::Config,
         rng: &mut NodeRng,
         registry: &Registry,
+    ) -> Result<Self, R::Error> {
+        Self::with_scheduler_config(cfg, rng, registry, &SchedulerConfig::default()).await
+    }
+
+    /// Creates a new runner from a given configuration, using existing metrics and a scheduler
+    /// configured according to `scheduler_config`.
+    #[inline]
+    #[instrument(
+        "runner creation",
+        level = "debug",
+        skip(cfg, rng, registry, scheduler_config)
+    )]
+    pub async fn with_scheduler_config(
+        cfg: R::Config,
+        rng: &mut NodeRng,
+        registry: &Registry,
+        scheduler_config: &SchedulerConfig,
     ) -> Result<Self, R::Error> {
         adjust_open_files_limit();
 
@@ -451,13 +518,16 @@ where
             );
         }
 
-        let scheduler = utils::leak(Scheduler::new(QueueKind::weights()));
+        let scheduler = utils::leak(Scheduler::with_policy(
+            scheduler_config.weights(),
+            scheduler_config.fairness_policy(),
+        ));
 
         let event_queue = EventQueueHandle::new(scheduler);
         let (reactor, initial_effects) = R::new(cfg, registry, event_queue, rng)?;
 
         // Run all effects from component instantiation.
-        process_effects(scheduler, initial_effects)
+        process_effects(scheduler, initial_effects, None)
             .instrument(debug_span!("process initial effects"))
             .await;
 
@@ -473,6 +543,12 @@ where
             event_metrics_threshold: 1000,
             clock: Clock::new(),
             last_queue_dump: None,
+            event_tracer: scheduler_config.event_tracer(),
+            max_events_per_crank: scheduler_config.max_events_per_crank.get(),
+            events_since_yield: 0,
+            event_time_budget: (*DISPATCH_EVENT_THRESHOLD)
+                .unwrap_or_else(|| scheduler_config.event_time_budget.into()),
+            exceeded_time_budget: false,
         })
     }
 
@@ -488,7 +564,7 @@ where
 
         let effects = create_effects(effect_builder);
 
-        process_effects(self.scheduler, effects)
+        process_effects(self.scheduler, effects, None)
             .instrument(debug_span!(
                 "process injected effects",
                 ev = self.event_count
@@ -552,11 +628,18 @@ where
             QUEUE_DUMP_REQUESTED.store(false, Ordering::SeqCst);
         }
 
-        let (event, q) = self.scheduler.pop().await;
+        let (event, q, queued_for, inherited_correlation_id) = self.scheduler.pop_traced().await;
+        let correlation_id = self
+            .event_tracer
+            .as_ref()
+            .and_then(|event_tracer| event_tracer.correlate(inherited_correlation_id, q));
 
         // Create another span for tracing the processing of one event.
-        let event_span = debug_span!("dispatch events", ev = self.event_count);
-        let (effects, keep_going) = event_span.in_scope(|| {
+        let event_span = debug_span!("dispatch events", ev = self.event_count, cid = field::Empty);
+        if let Some(correlation_id) = correlation_id {
+            event_span.record("cid", &field::display(correlation_id));
+        }
+        let (effects, keep_going, dispatch_duration, event_as_string) = event_span.in_scope(|| {
             // We log events twice, once in display and once in debug mode.
             let event_as_string = format!("{}", event);
             debug!(event=%event_as_string, ?q);
@@ -582,23 +665,47 @@ where
 
             let end = self.clock.end();
 
-            // Warn if processing took a long time, record to histogram.
+            // Warn and count per component if processing exceeded the time budget, record to
+            // histogram.
             let delta = self.clock.delta(start, end);
-            if delta > *DISPATCH_EVENT_THRESHOLD {
+            self.exceeded_time_budget = delta > self.event_time_budget;
+            if self.exceeded_time_budget {
+                let component = event_tracing::component_name(&event_as_string);
                 warn!(
                     ns = delta.into_nanos(),
+                    budget_ns = self.event_time_budget.as_nanos() as u64,
+                    %component,
                     event = %event_as_string,
-                    "event took very long to dispatch"
+                    "event took very long to dispatch, starving the reactor"
                 );
+                self.metrics
+                    .slow_events
+                    .with_label_values(&[component])
+                    .inc();
             }
             self.metrics
                 .event_dispatch_duration
                 .observe(delta.into_nanos() as f64);
 
-            (effects, keep_going)
+            (effects, keep_going, delta, event_as_string)
         });
 
-        process_effects(self.scheduler, effects)
+        // Charge the time spent against the queue, which matters for deficit-based scheduling.
+        self.scheduler.charge(q, dispatch_duration).await;
+
+        if let Some(event_tracer) = self.event_tracer.as_mut() {
+            if let Some(correlation_id) = correlation_id {
+                event_tracer.record(
+                    correlation_id,
+                    &event_as_string,
+                    queued_for,
+                    dispatch_duration,
+                );
+            }
+            event_tracer.sweep();
+        }
+
+        process_effects(self.scheduler, effects, correlation_id)
             .instrument(debug_span!("process effects", ev = self.event_count))
             .await;
 
@@ -698,6 +805,20 @@ where
         }
     }
 
+    /// Yields to the async runtime once `max_events_per_crank` events have been processed in a row,
+    /// or right away if the last event exceeded its time budget.
+    ///
+    /// Popping from a non-empty scheduler does not yield, so without this a busy reactor would
+    /// never give other tasks on the same worker thread a chance to run.
+    async fn maybe_yield(&mut self) {
+        self.events_since_yield += 1;
+        if self.events_since_yield >= self.max_events_per_crank || self.exceeded_time_budget {
+            self.events_since_yield = 0;
+            self.metrics.yields.inc();
+            task::yield_now().await;
+        }
+    }
+
     /// Runs the reactor until `maybe_exit()` returns `Some` or we get interrupted by a termination
     /// signal.
     #[inline]
@@ -729,6 +850,7 @@ where
                     if !self.crank(rng).await {
                         break ReactorExit::ProcessShouldExit(ExitCode::Abort);
                     }
+                    self.maybe_yield().await;
                 }
                 SIGINT => break ReactorExit::ProcessShouldExit(ExitCode::SigInt),
                 SIGQUIT => break ReactorExit::ProcessShouldExit(ExitCode::SigQuit),
@@ -772,7 +894,7 @@ impl Runner<InitializerReactor> {
 
         // Run all effects from component instantiation.
         let span = debug_span!("process initial effects");
-        process_effects(scheduler, initial_effects)
+        process_effects(scheduler, initial_effects, None)
             .instrument(span)
             .await;
 
@@ -792,25 +914,36 @@ impl Runner<InitializerReactor> {
             event_metrics_threshold: 1000,
             clock: Clock::new(),
             last_queue_dump: None,
+            event_tracer: None,
         })
     }
 }
 
 /// Spawns tasks that will process the given effects.
+///
+/// If a correlation ID is given, the tasks run with it set, passing it on to every event they
+/// schedule.
 #[inline]
-async fn process_effects<Ev>(scheduler: &'static Scheduler<Ev>, effects: Effects<Ev>)
-where
+async fn process_effects<Ev>(
+    scheduler: &'static Scheduler<Ev>,
+    effects: Effects<Ev>,
+    correlation_id: Option<CorrelationId>,
+) where
     Ev: Send + 'static,
 {
     // TODO: Properly carry around priorities.
     let queue_kind = QueueKind::default();
 
     for effect in effects {
-        tokio::spawn(async move {
+        let task = async move {
             for event in effect.await {
                 scheduler.push(event, queue_kind).await
             }
-        });
+        };
+        match correlation_id {
+            Some(correlation_id) => tokio::spawn(correlation_id.scope(task)),
+            None => tokio::spawn(task),
+        };
     }
 }
 
@@ -14,6 +14,8 @@ use crate::{
 pub(super) struct EventQueueMetrics {
     /// Per queue kind gauges that measure number of event in the queue.
     event_queue_gauges: HashMap<QueueKind, IntGauge>,
+    /// Per queue kind gauges that measure the age of the oldest event in the queue.
+    event_queue_age_gauges: HashMap<QueueKind, IntGauge>,
     /// Total events count.
     event_total: IntGauge,
     /// Instance of registry to unregister from when being dropped.
@@ -27,12 +29,25 @@ impl EventQueueMetrics {
         event_queue_handle: EventQueueHandle<REv>,
     ) -> Result<Self, prometheus::Error> {
         let mut event_queue_gauges: HashMap<QueueKind, IntGauge> = HashMap::new();
+        let mut event_queue_age_gauges: HashMap<QueueKind, IntGauge> = HashMap::new();
         for queue_kind in event_queue_handle.event_queues_counts().keys() {
             let key = format!("scheduler_queue_{}_count", queue_kind.metrics_name());
             let queue_event_counter = IntGauge::new(key, "Event in the queue.".to_string())?;
             registry.register(Box::new(queue_event_counter.clone()))?;
             let result = event_queue_gauges.insert(*queue_kind, queue_event_counter);
             assert!(result.is_none(), "Map keys should not be overwritten.");
+
+            let key = format!(
+                "scheduler_queue_{}_oldest_event_age_ms",
+                queue_kind.metrics_name()
+            );
+            let queue_age_gauge = IntGauge::new(
+                key,
+                "Age of the oldest event in the queue in milliseconds.".to_string(),
+            )?;
+            registry.register(Box::new(queue_age_gauge.clone()))?;
+            let result = event_queue_age_gauges.insert(*queue_kind, queue_age_gauge);
+            assert!(result.is_none(), "Map keys should not be overwritten.");
         }
 
         let event_total = IntGauge::new(
@@ -43,6 +58,7 @@ impl EventQueueMetrics {
 
         Ok(EventQueueMetrics {
             event_queue_gauges,
+            event_queue_age_gauges,
             event_total,
             registry,
         })
@@ -73,7 +89,13 @@ impl EventQueueMetrics {
             })
             .join(",");
 
-        debug!(%total, %event_counts, "Collected new set of event queue sizes metrics.")
+        debug!(%total, %event_counts, "Collected new set of event queue sizes metrics.");
+
+        for (queue, age) in event_queue_handle.oldest_event_ages() {
+            if let Some(gauge) = self.event_queue_age_gauges.get(&queue) {
+                gauge.set(age.as_millis() as i64);
+            }
+        }
     }
 }
 
@@ -82,6 +104,7 @@ impl Drop for EventQueueMetrics {
         unregister_metric!(self.registry, self.event_total);
         self.event_queue_gauges
             .iter()
+            .chain(self.event_queue_age_gauges.iter())
             .for_each(|(key, queue_gauge)| {
                 self.registry
                     .unregister(Box::new(queue_gauge.clone()))
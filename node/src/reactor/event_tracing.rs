@@ -0,0 +1,236 @@
+//! Tracing of events across components.
+//!
+//! With event tracing enabled, every root event, i.e. an incoming network message or API request,
+//! is assigned a fresh [`CorrelationId`]. Effects created while dispatching an event run with the
+//! event's correlation ID set, so every event they schedule in turn inherits it. The ID is attached
+//! to the dispatch span of each event, and once a trace has seen no activity for a while, a
+//! breakdown of the time its events spent queued and being dispatched is logged per component.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::{self, Display, Formatter},
+};
+
+use tokio::time::{Duration, Instant};
+use tracing::info;
+
+use super::QueueKind;
+use crate::utils::CorrelationId;
+
+/// How often the tracer looks for traces that have gone idle.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Returns the name of the component an event is destined for.
+///
+/// `description` is the event's `Display` representation, the component is taken to be the part
+/// preceding the first colon.
+pub(super) fn component_name(description: &str) -> &str {
+    description.split(':').next().unwrap_or(description)
+}
+
+/// Collects timings of events per correlation ID.
+#[derive(Debug)]
+pub(super) struct EventTracer {
+    /// How long a trace has to be idle before it is considered complete.
+    idle_timeout: Duration,
+    /// Traces which have not completed yet.
+    traces: HashMap<CorrelationId, Trace>,
+    /// Time of the last sweep for idle traces.
+    last_sweep: Instant,
+}
+
+/// Timings of all events sharing a correlation ID.
+#[derive(Debug)]
+struct Trace {
+    /// The component which handled the root event.
+    root: String,
+    /// Time at which the root event was dispatched.
+    started: Instant,
+    /// Time at which the most recent event was dispatched.
+    last_activity: Instant,
+    /// Timings per component.
+    components: BTreeMap<String, Timings>,
+}
+
+/// Accumulated timings of a number of events.
+#[derive(Debug, Default)]
+struct Timings {
+    /// Number of events.
+    events: usize,
+    /// Total time the events spent in the event queue.
+    queued: Duration,
+    /// Total time spent dispatching the events.
+    dispatch: Duration,
+}
+
+impl EventTracer {
+    /// Creates a new event tracer, logging traces after they have been idle for `idle_timeout`.
+    pub(super) fn new(idle_timeout: Duration) -> Self {
+        EventTracer {
+            idle_timeout,
+            traces: HashMap::new(),
+            last_sweep: Instant::now(),
+        }
+    }
+
+    /// Returns the correlation ID an event popped from `queue_kind` should be dispatched under.
+    ///
+    /// Events inherit the ID they were scheduled with, while root events without one are assigned
+    /// a fresh ID.
+    pub(super) fn correlate(
+        &self,
+        inherited: Option<CorrelationId>,
+        queue_kind: QueueKind,
+    ) -> Option<CorrelationId> {
+        inherited.or_else(|| match queue_kind {
+            QueueKind::NetworkIncoming | QueueKind::Api => Some(CorrelationId::fresh()),
+            QueueKind::Control | QueueKind::Network | QueueKind::Regular => None,
+        })
+    }
+
+    /// Records the timings of a dispatched event.
+    ///
+    /// `description` is the event's `Display` representation, see [`component_name`].
+    pub(super) fn record(
+        &mut self,
+        correlation_id: CorrelationId,
+        description: &str,
+        queued: Duration,
+        dispatch: Duration,
+    ) {
+        let component = component_name(description).to_string();
+        let now = Instant::now();
+        let trace = self.traces.entry(correlation_id).or_insert_with(|| Trace {
+            root: component.clone(),
+            started: now,
+            last_activity: now,
+            components: BTreeMap::new(),
+        });
+        trace.last_activity = now;
+        let timings = trace.components.entry(component).or_default();
+        timings.events += 1;
+        timings.queued += queued;
+        timings.dispatch += dispatch;
+    }
+
+    /// Logs and forgets all traces which have been idle for longer than the idle timeout.
+    ///
+    /// Cheap to call on every event, as the traces are only inspected once per `SWEEP_INTERVAL`.
+    pub(super) fn sweep(&mut self) {
+        let now = Instant::now();
+        if now.duration_since(self.last_sweep) < SWEEP_INTERVAL {
+            return;
+        }
+        self.last_sweep = now;
+
+        let idle_timeout = self.idle_timeout;
+        let completed: Vec<CorrelationId> = self
+            .traces
+            .iter()
+            .filter(|(_, trace)| now.duration_since(trace.last_activity) >= idle_timeout)
+            .map(|(correlation_id, _)| *correlation_id)
+            .collect();
+
+        for correlation_id in completed {
+            if let Some(trace) = self.traces.remove(&correlation_id) {
+                let total = trace.total();
+                info!(
+                    %correlation_id,
+                    root = %trace.root,
+                    events = total.events,
+                    elapsed_ms = trace.last_activity.duration_since(trace.started).as_millis() as u64,
+                    queued_ms = total.queued.as_millis() as u64,
+                    dispatch_us = total.dispatch.as_micros() as u64,
+                    breakdown = %trace,
+                    "event trace completed"
+                );
+            }
+        }
+    }
+}
+
+impl Trace {
+    /// Returns the timings summed over all components.
+    fn total(&self) -> Timings {
+        self.components
+            .values()
+            .fold(Timings::default(), |mut total, timings| {
+                total.events += timings.events;
+                total.queued += timings.queued;
+                total.dispatch += timings.dispatch;
+                total
+            })
+    }
+}
+
+impl Display for Trace {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (index, (component, timings)) in self.components.iter().enumerate() {
+            if index > 0 {
+                write!(f, "; ")?;
+            }
+            write!(
+                f,
+                "{}: {} events, {}ms queued, {}us dispatching",
+                component,
+                timings.events,
+                timings.queued.as_millis(),
+                timings.dispatch.as_micros()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_assign_fresh_ids_to_root_events_only() {
+        let tracer = EventTracer::new(Duration::from_secs(1));
+        let inherited = CorrelationId::fresh();
+
+        assert_eq!(
+            tracer.correlate(Some(inherited), QueueKind::Api),
+            Some(inherited)
+        );
+        assert_eq!(tracer.correlate(None, QueueKind::Regular), None);
+
+        let root = tracer.correlate(None, QueueKind::NetworkIncoming);
+        assert!(root.is_some());
+        assert_ne!(root, Some(inherited));
+    }
+
+    #[test]
+    fn should_take_component_name_from_event_description() {
+        assert_eq!(
+            component_name("contract runtime: execute block"),
+            "contract runtime"
+        );
+        assert_eq!(component_name("shutdown"), "shutdown");
+    }
+
+    #[test]
+    fn should_break_down_timings_per_component() {
+        let mut tracer = EventTracer::new(Duration::from_secs(1));
+        let correlation_id = CorrelationId::fresh();
+        let millis = Duration::from_millis;
+
+        tracer.record(correlation_id, "rpc server: request", millis(1), millis(2));
+        tracer.record(correlation_id, "storage: get block", millis(3), millis(4));
+        tracer.record(correlation_id, "storage: put block", millis(5), millis(6));
+
+        let trace = &tracer.traces[&correlation_id];
+        assert_eq!(trace.root, "rpc server");
+        let total = trace.total();
+        assert_eq!(total.events, 3);
+        assert_eq!(total.queued, millis(9));
+        assert_eq!(total.dispatch, millis(12));
+        assert_eq!(
+            trace.to_string(),
+            "rpc server: 1 events, 1ms queued, 2000us dispatching; \
+             storage: 2 events, 8ms queued, 10000us dispatching"
+        );
+    }
+}
@@ -25,14 +25,14 @@ use crate::{
             ChainspecLoaderAnnouncement, ContractRuntimeAnnouncement, ControlAnnouncement,
         },
         requests::{
-            ConsensusRequest, ContractRuntimeRequest, LinearChainRequest, NetworkRequest,
-            RestRequest, StateStoreRequest, StorageRequest,
+            ConsensusRequest, ContractRuntimeRequest, FetcherRequest, LinearChainRequest,
+            NetworkInfoRequest, NetworkRequest, RestRequest, StateStoreRequest, StorageRequest,
         },
         EffectBuilder, Effects,
     },
     protocol::Message,
     reactor::{self, validator, EventQueueHandle, ReactorExit},
-    types::{chainspec, NodeId},
+    types::{chainspec, Deploy, NodeId},
     utils::WithDir,
     NodeRng,
 };
@@ -127,6 +127,18 @@ impl From<ContractRuntimeAnnouncement> for Event {
     }
 }
 
+impl From<FetcherRequest<NodeId, Deploy>> for Event {
+    fn from(_request: FetcherRequest<NodeId, Deploy>) -> Self {
+        unreachable!("no deploys are fetched during initialization")
+    }
+}
+
+impl From<NetworkInfoRequest<NodeId>> for Event {
+    fn from(_request: NetworkInfoRequest<NodeId>) -> Self {
+        unreachable!("no network traffic happens during initialization")
+    }
+}
+
 impl Display for Event {
     fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
         match self {
@@ -195,12 +207,19 @@ impl Reactor {
             &storage_config,
             hard_reset_to_start_of_era,
             chainspec_loader.chainspec().protocol_config.version,
+            registry,
         )?;
 
         let contract_runtime = ContractRuntime::new(
             chainspec_loader.initial_state_root_hash(),
-            chainspec_loader.initial_block_header(),
+            chainspec_loader.initial_block(),
+            chainspec_loader
+                .chainspec()
+                .protocol_config
+                .genesis_height(),
             chainspec_loader.chainspec().protocol_config.version,
+            chainspec_loader.chainspec().network_config.name.clone(),
+            chainspec_loader.chainspec().deploy_config,
             storage_config,
             &config.value().contract_runtime,
             registry,
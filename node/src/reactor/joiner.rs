@@ -30,7 +30,9 @@ use crate::components::{
 use crate::testing::network::NetworkedReactor;
 use crate::{
     components::{
+        block_proposer::PendingDeploys,
         block_validator::{self, BlockValidator},
+        chain_synchronizer::{self, ChainSynchronizer},
         chainspec_loader::{self, ChainspecLoader},
         contract_runtime::{self, ContractRuntime},
         deploy_acceptor::{self, DeployAcceptor},
@@ -53,8 +55,9 @@ use crate::{
             LinearChainBlock, NetworkAnnouncement,
         },
         requests::{
-            BlockProposerRequest, BlockValidationRequest, ChainspecLoaderRequest, ConsensusRequest,
-            ContractRuntimeRequest, FetcherRequest, LinearChainRequest, MetricsRequest,
+            BlockProposerRequest, BlockValidationRequest, ChainSynchronizerRequest,
+            ChainspecLoaderRequest, ConsensusRequest, ContractRuntimeRequest,
+            DeployAcceptorRequest, FetcherRequest, LinearChainRequest, MetricsRequest,
             NetworkInfoRequest, NetworkRequest, RestRequest, StateStoreRequest, StorageRequest,
         },
         EffectBuilder, EffectExt, Effects,
@@ -74,7 +77,8 @@ use crate::{
     utils::{Source, WithDir},
     NodeRng,
 };
-use casper_types::{PublicKey, U512};
+use casper_execution_engine::{shared::stored_value::StoredValue, storage::trie::Trie};
+use casper_types::{Key, PublicKey, U512};
 
 /// Top-level event for the reactor.
 #[allow(clippy::large_enum_variant)]
@@ -112,6 +116,9 @@ pub enum Event {
     /// Chainspec info request
     #[from]
     ChainspecLoaderRequest(#[serde(skip_serializing)] ChainspecLoaderRequest),
+    /// Deploy acceptor request.
+    #[from]
+    DeployAcceptorRequest(#[serde(skip_serializing)] DeployAcceptorRequest),
 
     /// Network info request.
     #[from]
@@ -125,10 +132,24 @@ pub enum Event {
     #[from]
     BlockByHeightFetcher(#[serde(skip_serializing)] fetcher::Event<BlockByHeight>),
 
+    /// Linear chain block header and finality signatures (by height) fetcher event.
+    #[from]
+    BlockHeaderWithMetadataFetcher(
+        #[serde(skip_serializing)] fetcher::Event<BlockHeaderWithMetadata>,
+    ),
+
     /// Deploy fetcher event.
     #[from]
     DeployFetcher(#[serde(skip_serializing)] fetcher::Event<Deploy>),
 
+    /// Global state trie node fetcher event.
+    #[from]
+    TrieFetcher(#[serde(skip_serializing)] fetcher::Event<Trie<Key, StoredValue>>),
+
+    /// Chain synchronizer event.
+    #[from]
+    ChainSynchronizer(#[serde(skip_serializing)] chain_synchronizer::Event),
+
     /// Deploy acceptor event.
     #[from]
     DeployAcceptor(#[serde(skip_serializing)] deploy_acceptor::Event),
@@ -162,10 +183,24 @@ pub enum Event {
     #[from]
     BlockByHeightFetcherRequest(#[serde(skip_serializing)] FetcherRequest<NodeId, BlockByHeight>),
 
+    /// Linear chain block header and finality signatures (by height) fetcher request.
+    #[from]
+    BlockHeaderWithMetadataFetcherRequest(
+        #[serde(skip_serializing)] FetcherRequest<NodeId, BlockHeaderWithMetadata>,
+    ),
+
     /// Deploy fetcher request.
     #[from]
     DeployFetcherRequest(#[serde(skip_serializing)] FetcherRequest<NodeId, Deploy>),
 
+    /// Global state trie node fetcher request.
+    #[from]
+    TrieFetcherRequest(#[serde(skip_serializing)] FetcherRequest<NodeId, Trie<Key, StoredValue>>),
+
+    /// Chain synchronizer request.
+    #[from]
+    ChainSynchronizerRequest(#[serde(skip_serializing)] ChainSynchronizerRequest),
+
     /// Block validation request.
     #[from]
     BlockValidatorRequest(#[serde(skip_serializing)] BlockValidationRequest<NodeId>),
@@ -276,6 +311,7 @@ impl Display for Event {
             Event::MetricsRequest(req) => write!(f, "metrics request: {}", req),
             Event::ChainspecLoader(event) => write!(f, "chainspec loader: {}", event),
             Event::ChainspecLoaderRequest(req) => write!(f, "chainspec loader request: {}", req),
+            Event::DeployAcceptorRequest(req) => write!(f, "deploy acceptor request: {}", req),
             Event::NetworkInfoRequest(req) => write!(f, "network info request: {}", req),
             Event::BlockFetcherRequest(request) => write!(f, "block fetcher request: {}", request),
             Event::BlockValidatorRequest(request) => {
@@ -284,13 +320,22 @@ impl Display for Event {
             Event::DeployFetcherRequest(request) => {
                 write!(f, "deploy fetcher request: {}", request)
             }
+            Event::TrieFetcherRequest(request) => write!(f, "trie fetcher request: {}", request),
+            Event::ChainSynchronizerRequest(request) => {
+                write!(f, "chain synchronizer request: {}", request)
+            }
             Event::LinearChainSync(event) => write!(f, "linear chain: {}", event),
             Event::BlockFetcher(event) => write!(f, "block fetcher: {}", event),
             Event::BlockByHeightFetcherRequest(request) => {
                 write!(f, "block by height fetcher request: {}", request)
             }
+            Event::BlockHeaderWithMetadataFetcherRequest(request) => {
+                write!(f, "block header with metadata fetcher request: {}", request)
+            }
             Event::BlockValidator(event) => write!(f, "block validator event: {}", event),
             Event::DeployFetcher(event) => write!(f, "deploy fetcher event: {}", event),
+            Event::TrieFetcher(event) => write!(f, "trie fetcher event: {}", event),
+            Event::ChainSynchronizer(event) => write!(f, "chain synchronizer: {}", event),
             Event::BlockProposerRequest(req) => write!(f, "block proposer request: {}", req),
             Event::ContractRuntime(event) => write!(f, "contract runtime event: {:?}", event),
             Event::LinearChain(event) => write!(f, "linear chain event: {}", event),
@@ -304,6 +349,9 @@ impl Display for Event {
             Event::BlockByHeightFetcher(event) => {
                 write!(f, "block by height fetcher event: {}", event)
             }
+            Event::BlockHeaderWithMetadataFetcher(event) => {
+                write!(f, "block header with metadata fetcher event: {}", event)
+            }
             Event::DeployAcceptorAnnouncement(ann) => {
                 write!(f, "deploy acceptor announcement: {}", ann)
             }
@@ -335,6 +383,10 @@ pub struct Reactor {
     linear_chain_sync: LinearChainSync<NodeId>,
     block_validator: BlockValidator<NodeId>,
     deploy_fetcher: Fetcher<Deploy>,
+    #[data_size(skip)]
+    trie_fetcher: Fetcher<Trie<Key, StoredValue>>,
+    #[data_size(skip)]
+    chain_synchronizer: ChainSynchronizer,
     linear_chain: linear_chain::LinearChainComponent<NodeId>,
     // Handles request for linear chain block by height.
     block_by_height_fetcher: Fetcher<BlockByHeight>,
@@ -345,9 +397,9 @@ pub struct Reactor {
     #[data_size(skip)]
     event_queue_metrics: EventQueueMetrics,
     #[data_size(skip)]
-    rest_server: RestServer,
+    rest_server: Option<RestServer>,
     #[data_size(skip)]
-    event_stream_server: EventStreamServer,
+    event_stream_server: Option<EventStreamServer>,
     // Attach memory metrics for the joiner.
     #[data_size(skip)] // Never allocates data on the heap.
     memory_metrics: MemoryMetrics,
@@ -402,6 +454,7 @@ impl reactor::Reactor for Reactor {
             registry,
             small_network_identity,
             chainspec_loader.chainspec().as_ref(),
+            None,
             false,
         )?;
 
@@ -413,11 +466,19 @@ impl reactor::Reactor for Reactor {
             small_network_effects,
         ));
 
-        let address_gossiper =
-            Gossiper::new_for_complete_items("address_gossiper", config.gossip, registry)?;
-
         let effect_builder = EffectBuilder::new(event_queue);
 
+        let (address_gossiper, address_gossiper_effects) = Gossiper::new_for_complete_items(
+            "address_gossiper",
+            config.gossip,
+            effect_builder,
+            registry,
+        )?;
+        effects.extend(reactor::wrap_effects(
+            Event::AddressGossiper,
+            address_gossiper_effects,
+        ));
+
         let init_hash = config
             .node
             .trusted_hash
@@ -449,19 +510,35 @@ impl reactor::Reactor for Reactor {
         }
 
         let protocol_version = &chainspec_loader.chainspec().protocol_config.version;
-        let rest_server = RestServer::new(
-            config.rest_server.clone(),
-            effect_builder,
-            *protocol_version,
-        )?;
+        let rest_server = if config.rest_server.enable_server {
+            Some(RestServer::new(
+                config.rest_server.clone(),
+                effect_builder,
+                *protocol_version,
+            )?)
+        } else {
+            info!("REST server disabled");
+            None
+        };
 
-        let event_stream_server =
-            EventStreamServer::new(config.event_stream_server.clone(), *protocol_version)?;
+        let event_stream_server = if config.event_stream_server.enable_server {
+            Some(EventStreamServer::new(
+                config.event_stream_server.clone(),
+                *protocol_version,
+            )?)
+        } else {
+            info!("event stream server disabled");
+            None
+        };
 
         let block_validator = BlockValidator::new(Arc::clone(chainspec_loader.chainspec()));
 
         let deploy_fetcher = Fetcher::new("deploy", config.fetcher, registry)?;
 
+        let trie_fetcher = Fetcher::new("trie", config.fetcher, registry)?;
+
+        let chain_synchronizer = ChainSynchronizer::new(config.chain_synchronizer, registry)?;
+
         let block_by_height_fetcher = Fetcher::new("block_by_height", config.fetcher, registry)?;
 
         let block_header_and_finality_signatures_by_height_fetcher: Fetcher<
@@ -475,12 +552,18 @@ impl reactor::Reactor for Reactor {
         let block_header_by_hash_fetcher: Fetcher<BlockHeader> =
             Fetcher::new("block_header_by_hash", config.fetcher, registry)?;
 
-        let deploy_acceptor =
-            DeployAcceptor::new(config.deploy_acceptor, &*chainspec_loader.chainspec());
+        let deploy_acceptor = DeployAcceptor::new(
+            config.deploy_acceptor.clone(),
+            &*chainspec_loader.chainspec(),
+        );
 
         contract_runtime.set_initial_state(
             chainspec_loader.initial_state_root_hash(),
-            chainspec_loader.initial_block_header(),
+            chainspec_loader.initial_block(),
+            chainspec_loader
+                .chainspec()
+                .protocol_config
+                .genesis_height(),
         );
 
         let linear_chain = linear_chain::LinearChainComponent::new(
@@ -490,13 +573,31 @@ impl reactor::Reactor for Reactor {
             chainspec_loader.chainspec().core_config.unbonding_delay,
         )?;
 
-        let validator_weights: BTreeMap<PublicKey, U512> = chainspec_loader
+        let validator_weights: BTreeMap<PublicKey, U512> = match chainspec_loader
             .chainspec()
-            .network_config
-            .chainspec_validator_stakes()
-            .into_iter()
-            .map(|(pk, motes)| (pk, motes.value()))
-            .collect();
+            .protocol_config
+            .genesis_snapshot
+        {
+            // A network initialized from a snapshot starts with the validators recorded in the
+            // snapshot's auction state rather than the ones listed in the chainspec.
+            Some(genesis_snapshot) => contract_runtime
+                .era_validator_weights(
+                    genesis_snapshot.state_root_hash,
+                    genesis_snapshot.era_id,
+                    *protocol_version,
+                )
+                .unwrap_or_else(|error| {
+                    error!(%error, "failed to read genesis era validators from snapshot");
+                    BTreeMap::new()
+                }),
+            None => chainspec_loader
+                .chainspec()
+                .network_config
+                .chainspec_validator_stakes()
+                .into_iter()
+                .map(|(pk, motes)| (pk, motes.value()))
+                .collect(),
+        };
         let maybe_next_activation_point = chainspec_loader
             .next_upgrade()
             .map(|next_upgrade| next_upgrade.activation_point());
@@ -509,6 +610,7 @@ impl reactor::Reactor for Reactor {
             chainspec_loader.initial_block().cloned(),
             validator_weights,
             maybe_next_activation_point,
+            config.chain_synchronizer.sync_global_state,
         )?;
 
         effects.extend(reactor::wrap_effects(
@@ -535,6 +637,8 @@ impl reactor::Reactor for Reactor {
                 linear_chain_fetcher,
                 block_validator,
                 deploy_fetcher,
+                trie_fetcher,
+                chain_synchronizer,
                 linear_chain,
                 block_by_height_fetcher,
                 block_header_by_hash_fetcher,
@@ -568,14 +672,23 @@ impl reactor::Reactor for Reactor {
             Event::ControlAnnouncement(ctrl_ann) => {
                 unreachable!("unhandled control announcement: {}", ctrl_ann)
             }
-            Event::NetworkAnnouncement(NetworkAnnouncement::NewPeer(id)) => reactor::wrap_effects(
-                Event::LinearChainSync,
-                self.linear_chain_sync.handle_event(
+            Event::NetworkAnnouncement(NetworkAnnouncement::NewPeer(id)) => {
+                let mut effects = reactor::wrap_effects(
+                    Event::LinearChainSync,
+                    self.linear_chain_sync.handle_event(
+                        effect_builder,
+                        rng,
+                        linear_chain_sync::Event::NewPeerConnected(id),
+                    ),
+                );
+                let event = chain_synchronizer::Event::NewPeerConnected(id);
+                effects.extend(self.dispatch_event(
                     effect_builder,
                     rng,
-                    linear_chain_sync::Event::NewPeerConnected(id),
-                ),
-            ),
+                    Event::ChainSynchronizer(event),
+                ));
+                effects
+            }
             Event::NetworkAnnouncement(NetworkAnnouncement::GossipOurAddress(gossiped_address)) => {
                 let event = gossiper::Event::ItemReceived {
                     item_id: gossiped_address,
@@ -629,6 +742,30 @@ impl reactor::Reactor for Reactor {
                     };
                     self.dispatch_event(effect_builder, rng, Event::BlockByHeightFetcher(event))
                 }
+                Message::GetResponse {
+                    tag: Tag::BlockHeaderAndFinalitySignaturesByHeight,
+                    serialized_item,
+                } => {
+                    let block_header_with_metadata = match bincode::deserialize(&serialized_item) {
+                        Ok(block_header_with_metadata) => Box::new(block_header_with_metadata),
+                        Err(err) => {
+                            error!(
+                                "failed to decode block header and finality signatures from {}: {}",
+                                sender, err
+                            );
+                            return Effects::new();
+                        }
+                    };
+                    let event = fetcher::Event::GotRemotely {
+                        item: block_header_with_metadata,
+                        source: Source::Peer(sender),
+                    };
+                    self.dispatch_event(
+                        effect_builder,
+                        rng,
+                        Event::BlockHeaderWithMetadataFetcher(event),
+                    )
+                }
                 Message::GetResponse {
                     tag: Tag::Deploy,
                     serialized_item,
@@ -647,6 +784,23 @@ impl reactor::Reactor for Reactor {
                     });
                     self.dispatch_event(effect_builder, rng, event)
                 }
+                Message::GetResponse {
+                    tag: Tag::TrieNode,
+                    serialized_item,
+                } => {
+                    let trie = match bincode::deserialize(&serialized_item) {
+                        Ok(trie) => Box::new(trie),
+                        Err(err) => {
+                            error!("failed to decode trie from {}: {}", sender, err);
+                            return Effects::new();
+                        }
+                    };
+                    let event = fetcher::Event::GotRemotely {
+                        item: trie,
+                        source: Source::Peer(sender),
+                    };
+                    self.dispatch_event(effect_builder, rng, Event::TrieFetcher(event))
+                }
                 Message::AddressGossiper(message) => {
                     let event = Event::AddressGossiper(gossiper::Event::MessageReceived {
                         sender,
@@ -721,14 +875,41 @@ impl reactor::Reactor for Reactor {
                 self.block_by_height_fetcher
                     .handle_event(effect_builder, rng, event),
             ),
+            Event::BlockHeaderWithMetadataFetcher(event) => reactor::wrap_effects(
+                Event::BlockHeaderWithMetadataFetcher,
+                self.block_header_with_metadata_fetcher
+                    .handle_event(effect_builder, rng, event),
+            ),
             Event::DeployFetcherRequest(request) => {
                 self.dispatch_event(effect_builder, rng, Event::DeployFetcher(request.into()))
             }
+            Event::TrieFetcher(event) => reactor::wrap_effects(
+                Event::TrieFetcher,
+                self.trie_fetcher.handle_event(effect_builder, rng, event),
+            ),
+            Event::TrieFetcherRequest(request) => {
+                self.dispatch_event(effect_builder, rng, Event::TrieFetcher(request.into()))
+            }
+            Event::ChainSynchronizer(event) => reactor::wrap_effects(
+                Event::ChainSynchronizer,
+                self.chain_synchronizer
+                    .handle_event(effect_builder, rng, event),
+            ),
+            Event::ChainSynchronizerRequest(request) => self.dispatch_event(
+                effect_builder,
+                rng,
+                Event::ChainSynchronizer(request.into()),
+            ),
             Event::BlockByHeightFetcherRequest(request) => self.dispatch_event(
                 effect_builder,
                 rng,
                 Event::BlockByHeightFetcher(request.into()),
             ),
+            Event::BlockHeaderWithMetadataFetcherRequest(request) => self.dispatch_event(
+                effect_builder,
+                rng,
+                Event::BlockHeaderWithMetadataFetcher(request.into()),
+            ),
             Event::ContractRuntime(event) => reactor::wrap_effects(
                 Event::ContractRuntime,
                 self.contract_runtime
@@ -740,6 +921,7 @@ impl reactor::Reactor for Reactor {
                 let LinearChainBlock {
                     block,
                     execution_results,
+                    contract_events,
                 } = *linear_chain_block;
                 let mut effects = Effects::new();
                 let block_hash = *block.hash();
@@ -751,6 +933,7 @@ impl reactor::Reactor for Reactor {
                         .iter()
                         .map(|(hash, (_header, results))| (*hash, results.clone()))
                         .collect(),
+                    contract_events,
                 });
                 effects.extend(self.dispatch_event(effect_builder, rng, reactor_event));
 
@@ -790,6 +973,16 @@ impl reactor::Reactor for Reactor {
                 Event::LinearChain,
                 self.linear_chain.handle_event(effect_builder, rng, event),
             ),
+            Event::BlockProposerRequest(BlockProposerRequest::GetDependencyCycles {
+                responder,
+            }) => {
+                // There are no pending deploys while joining.
+                responder.respond(Vec::new()).ignore()
+            }
+            Event::BlockProposerRequest(BlockProposerRequest::GetPendingDeploys {
+                responder,
+                ..
+            }) => responder.respond(PendingDeploys::default()).ignore(),
             Event::BlockProposerRequest(request) => {
                 // Consensus component should not be trying to create new blocks during joining
                 // phase.
@@ -810,14 +1003,13 @@ impl reactor::Reactor for Reactor {
             }
 
             Event::LinearChainAnnouncement(LinearChainAnnouncement::BlockAdded(block)) => {
-                let mut effects = reactor::wrap_effects(
-                    Event::EventStreamServer,
-                    self.event_stream_server.handle_event(
-                        effect_builder,
-                        rng,
-                        event_stream_server::Event::BlockAdded(block.clone()),
-                    ),
-                );
+                let reactor_event =
+                    Event::EventStreamServer(event_stream_server::Event::BlockAdded(block.clone()));
+                let mut effects = self.dispatch_event(effect_builder, rng, reactor_event);
+                let reactor_event = Event::DeployAcceptor(deploy_acceptor::Event::BlockAdded(
+                    Box::new(block.header().clone()),
+                ));
+                effects.extend(self.dispatch_event(effect_builder, rng, reactor_event));
                 let reactor_event =
                     Event::LinearChainSync(linear_chain_sync::Event::BlockHandled(block));
                 effects.extend(self.dispatch_event(effect_builder, rng, reactor_event));
@@ -828,15 +1020,21 @@ impl reactor::Reactor for Reactor {
                     Event::EventStreamServer(event_stream_server::Event::FinalitySignature(fs));
                 self.dispatch_event(effect_builder, rng, reactor_event)
             }
-            Event::RestServer(event) => reactor::wrap_effects(
-                Event::RestServer,
-                self.rest_server.handle_event(effect_builder, rng, event),
-            ),
-            Event::EventStreamServer(event) => reactor::wrap_effects(
-                Event::EventStreamServer,
-                self.event_stream_server
-                    .handle_event(effect_builder, rng, event),
-            ),
+            // Events for disabled components are dropped.
+            Event::RestServer(event) => match self.rest_server.as_mut() {
+                Some(rest_server) => reactor::wrap_effects(
+                    Event::RestServer,
+                    rest_server.handle_event(effect_builder, rng, event),
+                ),
+                None => Effects::new(),
+            },
+            Event::EventStreamServer(event) => match self.event_stream_server.as_mut() {
+                Some(event_stream_server) => reactor::wrap_effects(
+                    Event::EventStreamServer,
+                    event_stream_server.handle_event(effect_builder, rng, event),
+                ),
+                None => Effects::new(),
+            },
             Event::MetricsRequest(req) => reactor::wrap_effects(
                 Event::MetricsRequest,
                 self.metrics.handle_event(effect_builder, rng, req),
@@ -849,6 +1047,9 @@ impl reactor::Reactor for Reactor {
             Event::ChainspecLoaderRequest(req) => {
                 self.dispatch_event(effect_builder, rng, Event::ChainspecLoader(req.into()))
             }
+            Event::DeployAcceptorRequest(req) => {
+                self.dispatch_event(effect_builder, rng, Event::DeployAcceptor(req.into()))
+            }
             Event::StateStoreRequest(req) => {
                 self.dispatch_event(effect_builder, rng, Event::Storage(req.into()))
             }
@@ -880,6 +1081,9 @@ impl reactor::Reactor for Reactor {
                 // no consensus, respond with None
                 responder.respond(None).ignore()
             }
+            Event::ConsensusRequest(ConsensusRequest::StateSummary { responder, .. }) => {
+                responder.respond(None).ignore()
+            }
         }
     }
 
@@ -904,7 +1108,11 @@ impl Reactor {
     /// Deconstructs the reactor into config useful for creating a Validator reactor. Shuts down
     /// the network, closing all incoming and outgoing connections, and frees up the listening
     /// socket.
-    pub async fn into_validator_config(self) -> Result<ValidatorInitConfig, Error> {
+    pub async fn into_validator_config(mut self) -> Result<ValidatorInitConfig, Error> {
+        // Keep any access lists set via the admin API while joining.
+        self.config
+            .deploy_acceptor
+            .set_access_lists(self.deploy_acceptor.access_lists().clone());
         let latest_block = self.linear_chain_sync.latest_block().cloned();
         // Clean the state of the linear_chain_sync before shutting it down.
         #[cfg(not(feature = "fast-sync"))]
@@ -925,7 +1133,9 @@ impl Reactor {
         };
         self.network.finalize().await;
         self.small_network.finalize().await;
-        self.rest_server.finalize().await;
+        if let Some(rest_server) = self.rest_server {
+            rest_server.finalize().await;
+        }
         Ok(config)
     }
 }
@@ -37,6 +37,12 @@ pub(super) struct MemoryMetrics {
     mem_deploy_fetcher: IntGauge,
     /// Estimated heap memory usage of linear chain component.
     mem_linear_chain: IntGauge,
+    /// Estimated heap memory usage of block by height fetcher component.
+    mem_block_by_height_fetcher: IntGauge,
+    /// Estimated heap memory usage of block header by hash fetcher component.
+    mem_block_header_by_hash_fetcher: IntGauge,
+    /// Estimated heap memory usage of block header with metadata fetcher component.
+    mem_block_header_with_metadata_fetcher: IntGauge,
 
     /// Histogram detailing how long it took to estimate memory usage.
     mem_estimator_runtime_s: Histogram,
@@ -89,6 +95,18 @@ impl MemoryMetrics {
             "joiner_mem_linear_chain",
             "linear_chain memory usage in bytes",
         )?;
+        let mem_block_by_height_fetcher = IntGauge::new(
+            "joiner_mem_block_by_height_fetcher",
+            "block_by_height_fetcher memory usage in bytes",
+        )?;
+        let mem_block_header_by_hash_fetcher = IntGauge::new(
+            "joiner_mem_block_header_by_hash_fetcher",
+            "block_header_by_hash_fetcher memory usage in bytes",
+        )?;
+        let mem_block_header_with_metadata_fetcher = IntGauge::new(
+            "joiner_mem_block_header_with_metadata_fetcher",
+            "block_header_with_metadata_fetcher memory usage in bytes",
+        )?;
         let mem_estimator_runtime_s = Histogram::with_opts(
             HistogramOpts::new(
                 "joiner_mem_estimator_runtime_s",
@@ -112,6 +130,9 @@ impl MemoryMetrics {
         registry.register(Box::new(mem_block_validator.clone()))?;
         registry.register(Box::new(mem_deploy_fetcher.clone()))?;
         registry.register(Box::new(mem_linear_chain.clone()))?;
+        registry.register(Box::new(mem_block_by_height_fetcher.clone()))?;
+        registry.register(Box::new(mem_block_header_by_hash_fetcher.clone()))?;
+        registry.register(Box::new(mem_block_header_with_metadata_fetcher.clone()))?;
         registry.register(Box::new(mem_estimator_runtime_s.clone()))?;
 
         Ok(MemoryMetrics {
@@ -129,6 +150,9 @@ impl MemoryMetrics {
             mem_block_validator,
             mem_deploy_fetcher,
             mem_linear_chain,
+            mem_block_by_height_fetcher,
+            mem_block_header_by_hash_fetcher,
+            mem_block_header_with_metadata_fetcher,
             mem_estimator_runtime_s,
             registry,
         })
@@ -151,6 +175,12 @@ impl MemoryMetrics {
         let block_validator = reactor.block_validator.estimate_heap_size() as i64;
         let deploy_fetcher = reactor.deploy_fetcher.estimate_heap_size() as i64;
         let linear_chain = reactor.linear_chain.estimate_heap_size() as i64;
+        let block_by_height_fetcher = reactor.block_by_height_fetcher.estimate_heap_size() as i64;
+        let block_header_by_hash_fetcher =
+            reactor.block_header_by_hash_fetcher.estimate_heap_size() as i64;
+        let block_header_with_metadata_fetcher = reactor
+            .block_header_with_metadata_fetcher
+            .estimate_heap_size() as i64;
 
         let total = metrics
             + network
@@ -164,7 +194,10 @@ impl MemoryMetrics {
             + linear_chain_sync
             + block_validator
             + deploy_fetcher
-            + linear_chain;
+            + linear_chain
+            + block_by_height_fetcher
+            + block_header_by_hash_fetcher
+            + block_header_with_metadata_fetcher;
 
         self.mem_total.set(total);
         self.mem_metrics.set(metrics);
@@ -180,6 +213,12 @@ impl MemoryMetrics {
         self.mem_block_validator.set(block_validator);
         self.mem_deploy_fetcher.set(deploy_fetcher);
         self.mem_linear_chain.set(linear_chain);
+        self.mem_block_by_height_fetcher
+            .set(block_by_height_fetcher);
+        self.mem_block_header_by_hash_fetcher
+            .set(block_header_by_hash_fetcher);
+        self.mem_block_header_with_metadata_fetcher
+            .set(block_header_with_metadata_fetcher);
 
         // Stop the timer explicitly, don't count logging.
         let duration_s = timer.stop_and_record();
@@ -200,6 +239,9 @@ impl MemoryMetrics {
         %block_validator,
         %deploy_fetcher,
         %linear_chain,
+        %block_by_height_fetcher,
+        %block_header_by_hash_fetcher,
+        %block_header_with_metadata_fetcher,
         "Collected new set of memory metrics for the joiner");
     }
 }
@@ -220,6 +262,9 @@ impl Drop for MemoryMetrics {
         unregister_metric!(self.registry, self.mem_block_validator);
         unregister_metric!(self.registry, self.mem_deploy_fetcher);
         unregister_metric!(self.registry, self.mem_linear_chain);
+        unregister_metric!(self.registry, self.mem_block_by_height_fetcher);
+        unregister_metric!(self.registry, self.mem_block_header_by_hash_fetcher);
+        unregister_metric!(self.registry, self.mem_block_header_with_metadata_fetcher);
         unregister_metric!(self.registry, self.mem_estimator_runtime_s);
     }
 }
@@ -0,0 +1,123 @@
+//! Configuration of the reactor's event scheduler.
+
+use std::{collections::BTreeMap, num::NonZeroUsize};
+
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+
+use super::{EventTracer, QueueKind};
+use crate::{types::TimeDiff, utils::FairnessPolicy};
+
+/// The kind of fairness policy to use when selecting the next event queue to process.
+#[derive(Copy, Clone, DataSize, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum FairnessPolicyKind {
+    /// A queue's weight is the number of events processed from it per round.
+    WeightedRoundRobin,
+    /// A queue's weight, multiplied by `deficit_quantum`, is the processing time granted to it
+    /// per round, so that queues with expensive events cannot starve other queues.
+    DeficitRoundRobin,
+}
+
+impl Default for FairnessPolicyKind {
+    fn default() -> Self {
+        FairnessPolicyKind::WeightedRoundRobin
+    }
+}
+
+/// Scheduler configuration.
+#[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// The fairness policy used to select the next event queue.
+    #[serde(default)]
+    pub fairness_policy: FairnessPolicyKind,
+    /// Processing time granted per unit of queue weight and round when using the deficit
+    /// round-robin policy.
+    #[serde(default = "default_deficit_quantum")]
+    pub deficit_quantum: TimeDiff,
+    /// Overrides of the default weights of individual queues, keyed by the queue's metrics name
+    /// (e.g. `api` or `network_incoming`). Zero weights and unknown queues are ignored.
+    #[serde(default)]
+    pub queue_weights: BTreeMap<String, usize>,
+    /// Whether to assign correlation IDs to incoming network messages and API requests, propagate
+    /// them through all events derived from these and log a per-component timing breakdown.
+    #[serde(default)]
+    pub event_tracing: bool,
+    /// How long a trace has to be idle before its timing breakdown is logged.
+    #[serde(default = "default_event_trace_idle_timeout")]
+    pub event_trace_idle_timeout: TimeDiff,
+    /// The maximum number of events processed in a row before yielding back to the async runtime.
+    #[serde(default = "default_max_events_per_crank")]
+    pub max_events_per_crank: NonZeroUsize,
+    /// The time a single event may take to dispatch before its component is considered to be
+    /// starving the reactor. Exceeding it is logged and counted, and causes an immediate yield.
+    #[serde(default = "default_event_time_budget")]
+    pub event_time_budget: TimeDiff,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            fairness_policy: FairnessPolicyKind::default(),
+            deficit_quantum: default_deficit_quantum(),
+            queue_weights: BTreeMap::new(),
+            event_tracing: false,
+            event_trace_idle_timeout: default_event_trace_idle_timeout(),
+            max_events_per_crank: default_max_events_per_crank(),
+            event_time_budget: default_event_time_budget(),
+        }
+    }
+}
+
+impl Config {
+    /// Returns the fairness policy to construct the scheduler with.
+    pub(crate) fn fairness_policy(&self) -> FairnessPolicy {
+        match self.fairness_policy {
+            FairnessPolicyKind::WeightedRoundRobin => FairnessPolicy::WeightedRoundRobin,
+            FairnessPolicyKind::DeficitRoundRobin => FairnessPolicy::DeficitRoundRobin {
+                quantum: self.deficit_quantum.into(),
+            },
+        }
+    }
+
+    /// Returns an event tracer if event tracing is enabled.
+    pub(super) fn event_tracer(&self) -> Option<EventTracer> {
+        if self.event_tracing {
+            Some(EventTracer::new(self.event_trace_idle_timeout.into()))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the weights of all queues, taking configured overrides into account.
+    pub(crate) fn weights(&self) -> Vec<(QueueKind, NonZeroUsize)> {
+        QueueKind::weights()
+            .into_iter()
+            .map(|(queue_kind, default_weight)| {
+                let weight = self
+                    .queue_weights
+                    .get(queue_kind.metrics_name())
+                    .and_then(|weight| NonZeroUsize::new(*weight))
+                    .unwrap_or(default_weight);
+                (queue_kind, weight)
+            })
+            .collect()
+    }
+}
+
+fn default_deficit_quantum() -> TimeDiff {
+    "1ms".parse().unwrap()
+}
+
+fn default_event_trace_idle_timeout() -> TimeDiff {
+    "5s".parse().unwrap()
+}
+
+fn default_max_events_per_crank() -> NonZeroUsize {
+    NonZeroUsize::new(64).unwrap()
+}
+
+fn default_event_time_budget() -> TimeDiff {
+    "1s".parse().unwrap()
+}
@@ -20,15 +20,19 @@ use derive_more::From;
 use prometheus::Registry;
 use reactor::ReactorEvent;
 use serde::Serialize;
-use tracing::{debug, error, trace, warn};
+use tracing::{debug, error, info, trace, warn};
 
 #[cfg(test)]
-use crate::testing::network::NetworkedReactor;
+use std::collections::BTreeMap;
+
+#[cfg(test)]
+use crate::testing::network::{FinalizingReactor, NetworkedReactor};
 
 use crate::{
     components::{
         block_proposer::{self, BlockProposer},
         block_validator::{self, BlockValidator},
+        chain_synchronizer,
         chainspec_loader::{self, ChainspecLoader},
         consensus::{self, EraSupervisor, HighwayProtocol},
         contract_runtime::{self, ContractRuntime},
@@ -47,14 +51,15 @@ use crate::{
     },
     effect::{
         announcements::{
-            BlocklistAnnouncement, ChainspecLoaderAnnouncement, ConsensusAnnouncement,
-            ContractRuntimeAnnouncement, ControlAnnouncement, DeployAcceptorAnnouncement,
-            GossiperAnnouncement, LinearChainAnnouncement, LinearChainBlock, NetworkAnnouncement,
-            RpcServerAnnouncement,
+            BlockProposerAnnouncement, BlocklistAnnouncement, ChainspecLoaderAnnouncement,
+            ConsensusAnnouncement, ContractRuntimeAnnouncement, ControlAnnouncement,
+            DeployAcceptorAnnouncement, GossiperAnnouncement, LinearChainAnnouncement,
+            LinearChainBlock, NetworkAnnouncement, RpcServerAnnouncement,
         },
         requests::{
-            BlockProposerRequest, BlockValidationRequest, ChainspecLoaderRequest, ConsensusRequest,
-            ContractRuntimeRequest, FetcherRequest, LinearChainRequest, MetricsRequest,
+            BlockProposerRequest, BlockValidationRequest, ChainSynchronizerRequest,
+            ChainspecLoaderRequest, ConsensusRequest, ContractRuntimeRequest,
+            DeployAcceptorRequest, FetcherRequest, LinearChainRequest, MetricsRequest,
             NetworkInfoRequest, NetworkRequest, RestRequest, RpcRequest, StateStoreRequest,
             StorageRequest,
         },
@@ -146,12 +151,18 @@ pub enum Event {
     /// Chainspec info request
     #[from]
     ChainspecLoaderRequest(#[serde(skip_serializing)] ChainspecLoaderRequest),
+    /// Deploy acceptor request.
+    #[from]
+    DeployAcceptorRequest(#[serde(skip_serializing)] DeployAcceptorRequest),
     /// Storage request.
     #[from]
     StorageRequest(#[serde(skip_serializing)] StorageRequest),
     /// Request for state storage.
     #[from]
     StateStoreRequest(StateStoreRequest),
+    /// Chain synchronizer request.
+    #[from]
+    ChainSynchronizerRequest(#[serde(skip_serializing)] ChainSynchronizerRequest),
 
     // Announcements
     /// Control announcement.
@@ -187,6 +198,9 @@ pub enum Event {
     /// Blocklist announcement.
     #[from]
     BlocklistAnnouncement(BlocklistAnnouncement<NodeId>),
+    /// Block proposer announcement.
+    #[from]
+    BlockProposerAnnouncement(#[serde(skip_serializing)] BlockProposerAnnouncement),
 }
 
 impl ReactorEvent for Event {
@@ -264,13 +278,17 @@ impl Display for Event {
             Event::DeployGossiper(event) => write!(f, "deploy gossiper: {}", event),
             Event::AddressGossiper(event) => write!(f, "address gossiper: {}", event),
             Event::ContractRuntime(event) => write!(f, "contract runtime: {:?}", event),
-            Event::LinearChain(event) => write!(f, "linear-chain event {}", event),
+            Event::LinearChain(event) => write!(f, "linear chain: {}", event),
             Event::BlockValidator(event) => write!(f, "block validator: {}", event),
             Event::NetworkRequest(req) => write!(f, "network request: {}", req),
             Event::NetworkInfoRequest(req) => write!(f, "network info request: {}", req),
             Event::ChainspecLoaderRequest(req) => write!(f, "chainspec loader request: {}", req),
+            Event::DeployAcceptorRequest(req) => write!(f, "deploy acceptor request: {}", req),
             Event::StorageRequest(req) => write!(f, "storage request: {}", req),
             Event::StateStoreRequest(req) => write!(f, "state store request: {}", req),
+            Event::ChainSynchronizerRequest(req) => {
+                write!(f, "chain synchronizer request: {}", req)
+            }
             Event::DeployFetcherRequest(req) => write!(f, "deploy fetcher request: {}", req),
             Event::BlockProposerRequest(req) => write!(f, "block proposer request: {}", req),
             Event::BlockValidatorRequest(req) => {
@@ -300,6 +318,9 @@ impl Display for Event {
             Event::BlocklistAnnouncement(ann) => {
                 write!(f, "blocklist announcement: {}", ann)
             }
+            Event::BlockProposerAnnouncement(ann) => {
+                write!(f, "block proposer announcement: {}", ann)
+            }
         }
     }
 }
@@ -312,7 +333,7 @@ pub struct ValidatorInitConfig {
     pub(super) storage: Storage,
     pub(super) contract_runtime: ContractRuntime,
     pub(super) latest_block: Option<Block>,
-    pub(super) event_stream_server: EventStreamServer,
+    pub(super) event_stream_server: Option<EventStreamServer>,
     pub(super) small_network_identity: SmallNetworkIdentity,
     pub(super) network_identity: NetworkIdentity,
 }
@@ -340,9 +361,9 @@ pub struct Reactor {
     address_gossiper: Gossiper<GossipedAddress, Event>,
     storage: Storage,
     contract_runtime: ContractRuntime,
-    rpc_server: RpcServer,
-    rest_server: RestServer,
-    event_stream_server: EventStreamServer,
+    rpc_server: Option<RpcServer>,
+    rest_server: Option<RestServer>,
+    event_stream_server: Option<EventStreamServer>,
     chainspec_loader: ChainspecLoader,
     consensus: EraSupervisor<NodeId>,
     #[data_size(skip)]
@@ -415,34 +436,62 @@ impl reactor::Reactor for Reactor {
             chainspec_loader.chainspec(),
             true,
         )?;
+        let consensus_secret_key = config
+            .consensus
+            .secret_key_path
+            .clone()
+            .load(&root)
+            .map_err(anyhow::Error::from)?;
         let (small_network, small_network_effects) = SmallNetwork::new(
             event_queue,
             config.network,
             registry,
             small_network_identity,
             chainspec_loader.chainspec().as_ref(),
+            Some(&consensus_secret_key),
             true,
         )?;
 
-        let address_gossiper =
-            Gossiper::new_for_complete_items("address_gossiper", config.gossip, registry)?;
-
-        let protocol_version = &chainspec_loader.chainspec().protocol_config.version;
-        let rpc_server =
-            RpcServer::new(config.rpc_server.clone(), effect_builder, *protocol_version)?;
-        let rest_server = RestServer::new(
-            config.rest_server.clone(),
+        let (address_gossiper, address_gossiper_effects) = Gossiper::new_for_complete_items(
+            "address_gossiper",
+            config.gossip,
             effect_builder,
-            *protocol_version,
+            registry,
         )?;
 
-        let deploy_acceptor =
-            DeployAcceptor::new(config.deploy_acceptor, &*chainspec_loader.chainspec());
+        let protocol_version = &chainspec_loader.chainspec().protocol_config.version;
+        let rpc_server = if config.rpc_server.enable_server {
+            Some(RpcServer::new(
+                config.rpc_server.clone(),
+                chainspec_loader.chainspec(),
+                effect_builder,
+                &root,
+            )?)
+        } else {
+            info!("JSON-RPC server disabled");
+            None
+        };
+        let rest_server = if config.rest_server.enable_server {
+            Some(RestServer::new(
+                config.rest_server.clone(),
+                effect_builder,
+                *protocol_version,
+            )?)
+        } else {
+            info!("REST server disabled");
+            None
+        };
+
+        let deploy_acceptor = DeployAcceptor::new(
+            config.deploy_acceptor.clone(),
+            &*chainspec_loader.chainspec(),
+        );
         let deploy_fetcher = Fetcher::new("deploy", config.fetcher, registry)?;
-        let deploy_gossiper = Gossiper::new_for_partial_items(
+        let (deploy_gossiper, deploy_gossiper_effects) = Gossiper::new_for_partial_items(
             "deploy_gossiper",
             config.gossip,
             gossiper::get_deploy_from_storage::<Deploy, Event>,
+            effect_builder,
             registry,
         )?;
         let (block_proposer, block_proposer_effects) = BlockProposer::new(
@@ -451,7 +500,12 @@ impl reactor::Reactor for Reactor {
             latest_block
                 .as_ref()
                 .map(|block| block.height() + 1)
-                .unwrap_or(0),
+                .unwrap_or_else(|| {
+                    chainspec_loader
+                        .chainspec()
+                        .protocol_config
+                        .genesis_height()
+                }),
             chainspec_loader.chainspec().as_ref(),
             config.block_proposer,
         )?;
@@ -481,7 +535,11 @@ impl reactor::Reactor for Reactor {
         ));
         contract_runtime.set_initial_state(
             chainspec_loader.initial_state_root_hash(),
-            chainspec_loader.initial_block_header(),
+            chainspec_loader.initial_block(),
+            chainspec_loader
+                .chainspec()
+                .protocol_config
+                .genesis_height(),
         );
         contract_runtime.set_parent_map_from_block(latest_block);
 
@@ -498,6 +556,14 @@ impl reactor::Reactor for Reactor {
             Event::SmallNetwork,
             small_network_effects,
         ));
+        effects.extend(reactor::wrap_effects(
+            Event::AddressGossiper,
+            address_gossiper_effects,
+        ));
+        effects.extend(reactor::wrap_effects(
+            Event::DeployGossiper,
+            deploy_gossiper_effects,
+        ));
         effects.extend(reactor::wrap_effects(
             Event::ChainspecLoader,
             chainspec_loader.start_checking_for_upgrades(effect_builder),
@@ -552,19 +618,28 @@ impl reactor::Reactor for Reactor {
                 Event::Storage,
                 self.storage.handle_event(effect_builder, rng, event),
             ),
-            Event::RpcServer(event) => reactor::wrap_effects(
-                Event::RpcServer,
-                self.rpc_server.handle_event(effect_builder, rng, event),
-            ),
-            Event::RestServer(event) => reactor::wrap_effects(
-                Event::RestServer,
-                self.rest_server.handle_event(effect_builder, rng, event),
-            ),
-            Event::EventStreamServer(event) => reactor::wrap_effects(
-                Event::EventStreamServer,
-                self.event_stream_server
-                    .handle_event(effect_builder, rng, event),
-            ),
+            // Events for disabled components are dropped.
+            Event::RpcServer(event) => match self.rpc_server.as_mut() {
+                Some(rpc_server) => reactor::wrap_effects(
+                    Event::RpcServer,
+                    rpc_server.handle_event(effect_builder, rng, event),
+                ),
+                None => Effects::new(),
+            },
+            Event::RestServer(event) => match self.rest_server.as_mut() {
+                Some(rest_server) => reactor::wrap_effects(
+                    Event::RestServer,
+                    rest_server.handle_event(effect_builder, rng, event),
+                ),
+                None => Effects::new(),
+            },
+            Event::EventStreamServer(event) => match self.event_stream_server.as_mut() {
+                Some(event_stream_server) => reactor::wrap_effects(
+                    Event::EventStreamServer,
+                    event_stream_server.handle_event(effect_builder, rng, event),
+                ),
+                None => Effects::new(),
+            },
             Event::ChainspecLoader(event) => reactor::wrap_effects(
                 Event::ChainspecLoader,
                 self.chainspec_loader
@@ -643,12 +718,25 @@ impl reactor::Reactor for Reactor {
             Event::ChainspecLoaderRequest(req) => {
                 self.dispatch_event(effect_builder, rng, Event::ChainspecLoader(req.into()))
             }
+            Event::DeployAcceptorRequest(req) => {
+                self.dispatch_event(effect_builder, rng, Event::DeployAcceptor(req.into()))
+            }
             Event::StorageRequest(req) => {
                 self.dispatch_event(effect_builder, rng, Event::Storage(req.into()))
             }
             Event::StateStoreRequest(req) => {
                 self.dispatch_event(effect_builder, rng, Event::Storage(req.into()))
             }
+            // The chain synchronizer only runs in the joiner reactor.
+            Event::ChainSynchronizerRequest(ChainSynchronizerRequest::SyncTrie {
+                responder,
+                ..
+            }) => responder
+                .respond(Err(chain_synchronizer::Error::NotRunning))
+                .ignore(),
+            Event::ChainSynchronizerRequest(ChainSynchronizerRequest::Progress(responder)) => {
+                responder.respond(None).ignore()
+            }
 
             // Announcements:
             Event::ControlAnnouncement(ctrl_ann) => {
@@ -820,6 +908,33 @@ impl reactor::Reactor for Reactor {
                                 }
                             }
                         }
+                        Tag::TrieNode => {
+                            let trie_key = match bincode::deserialize(&serialized_id) {
+                                Ok(trie_key) => trie_key,
+                                Err(error) => {
+                                    error!(
+                                        "failed to decode {:?} from {}: {}",
+                                        serialized_id, sender, error
+                                    );
+                                    return Effects::new();
+                                }
+                            };
+                            // Reading the trie store blocks, so it must not happen on the reactor.
+                            return async move {
+                                match effect_builder.read_trie(trie_key).await {
+                                    Some(trie) => match Message::new_get_response(&trie) {
+                                        Ok(message) => {
+                                            effect_builder.send_message(sender, message).await
+                                        }
+                                        Err(error) => {
+                                            error!("failed to create get-response: {}", error)
+                                        }
+                                    },
+                                    None => debug!("failed to get {} for {}", trie_key, sender),
+                                }
+                            }
+                            .ignore();
+                        }
                     },
                     Message::GetResponse {
                         tag,
@@ -875,6 +990,10 @@ impl reactor::Reactor for Reactor {
                             );
                             return Effects::new();
                         }
+                        Tag::TrieNode => {
+                            error!("cannot handle get response for trie-node from {}", sender);
+                            return Effects::new();
+                        }
                     },
                     Message::FinalitySignature(fs) => {
                         Event::LinearChain(linear_chain::Event::FinalitySignatureReceived(fs, true))
@@ -916,12 +1035,16 @@ impl reactor::Reactor for Reactor {
                     }
                 };
 
-                let event = block_proposer::Event::BufferDeploy {
-                    hash: *deploy.id(),
-                    deploy_type: Box::new(deploy_type),
+                // In observer mode we won't propose blocks, so there is no need to buffer deploys.
+                let mut effects = if self.consensus.is_observer() {
+                    Effects::new()
+                } else {
+                    let event = block_proposer::Event::BufferDeploy {
+                        hash: *deploy.id(),
+                        deploy_type: Box::new(deploy_type),
+                    };
+                    self.dispatch_event(effect_builder, rng, Event::BlockProposer(event))
                 };
-                let mut effects =
-                    self.dispatch_event(effect_builder, rng, Event::BlockProposer(event));
 
                 let event = gossiper::Event::ItemReceived {
                     item_id: *deploy.id(),
@@ -955,6 +1078,12 @@ impl reactor::Reactor for Reactor {
                         Event::BlockProposer(block_proposer::Event::FinalizedBlock(block));
                     self.dispatch_event(effect_builder, rng, reactor_event)
                 }
+                ConsensusAnnouncement::OrphanedProtoBlock(block_payload) => {
+                    let reactor_event = Event::BlockProposer(
+                        block_proposer::Event::OrphanedProtoBlock(block_payload),
+                    );
+                    self.dispatch_event(effect_builder, rng, reactor_event)
+                }
                 ConsensusAnnouncement::CreatedFinalitySignature(fs) => self.dispatch_event(
                     effect_builder,
                     rng,
@@ -973,6 +1102,24 @@ impl reactor::Reactor for Reactor {
                         });
                     self.dispatch_event(effect_builder, rng, reactor_event)
                 }
+                ConsensusAnnouncement::FinalityTierReached { .. } => Effects::new(),
+                ConsensusAnnouncement::NoLongerBonded { era_id, public_key } => {
+                    info!(
+                        %era_id,
+                        %public_key,
+                        observer = self.consensus.is_observer(),
+                        "no longer bonded"
+                    );
+                    Effects::new()
+                }
+                ConsensusAnnouncement::EraValidatorsChanged { era_id, validators } => {
+                    let reactor_event =
+                        Event::SmallNetwork(small_network::Event::EraValidatorsChanged {
+                            era_id,
+                            validators,
+                        });
+                    self.dispatch_event(effect_builder, rng, reactor_event)
+                }
             },
             Event::ContractRuntimeAnnouncement(ContractRuntimeAnnouncement::LinearChainBlock(
                 linear_chain_block,
@@ -980,6 +1127,7 @@ impl reactor::Reactor for Reactor {
                 let LinearChainBlock {
                     block,
                     execution_results,
+                    contract_events,
                 } = *linear_chain_block;
                 let mut effects = Effects::new();
                 let block_hash = *block.hash();
@@ -991,6 +1139,7 @@ impl reactor::Reactor for Reactor {
                         .iter()
                         .map(|(hash, (_header, results))| (*hash, results.clone()))
                         .collect(),
+                    contract_events,
                 });
                 effects.extend(self.dispatch_event(effect_builder, rng, reactor_event));
 
@@ -1038,10 +1187,18 @@ impl reactor::Reactor for Reactor {
                 let reactor_event_consensus = Event::Consensus(consensus::Event::BlockAdded(
                     Box::new(block.header().clone()),
                 ));
+                let reactor_event_deploy_acceptor = Event::DeployAcceptor(
+                    deploy_acceptor::Event::BlockAdded(Box::new(block.header().clone())),
+                );
                 let reactor_event_es =
                     Event::EventStreamServer(event_stream_server::Event::BlockAdded(block));
                 let mut effects = self.dispatch_event(effect_builder, rng, reactor_event_es);
                 effects.extend(self.dispatch_event(effect_builder, rng, reactor_event_consensus));
+                effects.extend(self.dispatch_event(
+                    effect_builder,
+                    rng,
+                    reactor_event_deploy_acceptor,
+                ));
                 effects
             }
             Event::LinearChainAnnouncement(LinearChainAnnouncement::NewFinalitySignature(fs)) => {
@@ -1066,6 +1223,9 @@ impl reactor::Reactor for Reactor {
             Event::BlocklistAnnouncement(ann) => {
                 self.dispatch_event(effect_builder, rng, Event::SmallNetwork(ann.into()))
             }
+            Event::BlockProposerAnnouncement(BlockProposerAnnouncement::DeploysEvicted(_)) => {
+                Effects::new()
+            }
         }
     }
 
@@ -1082,6 +1242,13 @@ impl reactor::Reactor for Reactor {
     }
 }
 
+#[cfg(test)]
+impl FinalizingReactor for Reactor {
+    fn finalized_blocks(&self) -> &BTreeMap<u64, BlockHash> {
+        self.storage.block_height_index()
+    }
+}
+
 #[cfg(test)]
 impl NetworkedReactor for Reactor {
     type NodeId = NodeId;
@@ -2,9 +2,10 @@ use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    logging::LoggingConfig, types::NodeConfig, BlockProposerConfig, ConsensusConfig,
-    ContractRuntimeConfig, DeployAcceptorConfig, EventStreamServerConfig, FetcherConfig,
-    GossipConfig, RestServerConfig, RpcServerConfig, SmallNetworkConfig, StorageConfig,
+    logging::LoggingConfig, reactor::SchedulerConfig, types::NodeConfig, BlockProposerConfig,
+    ChainSynchronizerConfig, ConsensusConfig, ContractRuntimeConfig, DeployAcceptorConfig,
+    EventStreamServerConfig, FetcherConfig, GossipConfig, RestServerConfig, RpcServerConfig,
+    SmallNetworkConfig, StorageConfig,
 };
 
 /// Root configuration.
@@ -32,6 +33,9 @@ pub struct Config {
     pub gossip: GossipConfig,
     /// Fetcher configuration.
     pub fetcher: FetcherConfig,
+    /// Chain synchronizer configuration.
+    #[serde(default)]
+    pub chain_synchronizer: ChainSynchronizerConfig,
     /// Contract runtime configuration.
     pub contract_runtime: ContractRuntimeConfig,
     /// Deploy acceptor configuration.
@@ -39,4 +43,7 @@ pub struct Config {
     /// Block proposer configuration.
     #[serde(default)]
     pub block_proposer: BlockProposerConfig,
+    /// Event scheduler configuration.
+    #[serde(default)]
+    pub scheduler: SchedulerConfig,
 }
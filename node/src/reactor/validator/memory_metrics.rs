@@ -1,7 +1,7 @@
 use std::env;
 
 use datasize::DataSize;
-use prometheus::{self, Histogram, HistogramOpts, IntGauge, Registry};
+use prometheus::{self, Histogram, HistogramOpts, IntGauge, IntGaugeVec, Opts, Registry};
 use tracing::debug;
 
 use super::Reactor;
@@ -33,6 +33,8 @@ pub(super) struct MemoryMetrics {
     mem_chainspec_loader: IntGauge,
     /// Estimated heap memory usage of consensus component.
     mem_consensus: IntGauge,
+    /// Estimated heap memory usage of each active era in the consensus component, labeled by era.
+    mem_consensus_era: IntGaugeVec,
     /// Estimated heap memory usage of deploy fetcher component.
     mem_deploy_fetcher: IntGauge,
     /// Estimated heap memory usage of deploy gossiper component.
@@ -78,6 +80,13 @@ impl MemoryMetrics {
             "chainspec_loader memory usage in bytes",
         )?;
         let mem_consensus = IntGauge::new("mem_consensus", "consensus memory usage in bytes")?;
+        let mem_consensus_era = IntGaugeVec::new(
+            Opts::new(
+                "mem_consensus_era",
+                "consensus memory usage per active era in bytes",
+            ),
+            &["era"],
+        )?;
         let mem_deploy_fetcher =
             IntGauge::new("mem_deploy_fetcher", "deploy_fetcher memory usage in bytes")?;
         let mem_deploy_gossiper = IntGauge::new(
@@ -113,6 +122,7 @@ impl MemoryMetrics {
         registry.register(Box::new(mem_event_stream_server.clone()))?;
         registry.register(Box::new(mem_chainspec_loader.clone()))?;
         registry.register(Box::new(mem_consensus.clone()))?;
+        registry.register(Box::new(mem_consensus_era.clone()))?;
         registry.register(Box::new(mem_deploy_fetcher.clone()))?;
         registry.register(Box::new(mem_deploy_gossiper.clone()))?;
         registry.register(Box::new(mem_block_proposer.clone()))?;
@@ -132,6 +142,7 @@ impl MemoryMetrics {
             mem_event_stream_server,
             mem_chainspec_loader,
             mem_consensus,
+            mem_consensus_era,
             mem_deploy_fetcher,
             mem_deploy_gossiper,
             mem_block_proposer,
@@ -194,6 +205,13 @@ impl MemoryMetrics {
         self.mem_event_stream_server.set(event_stream_server);
         self.mem_chainspec_loader.set(chainspec_loader);
         self.mem_consensus.set(consensus);
+        // Eras come and go, so drop the gauges of eras that are no longer active.
+        self.mem_consensus_era.reset();
+        for (era_id, era) in reactor.consensus.estimate_era_heap_sizes() {
+            self.mem_consensus_era
+                .with_label_values(&[&era_id.to_string()])
+                .set(era as i64);
+        }
         self.mem_deploy_fetcher.set(deploy_fetcher);
         self.mem_deploy_gossiper.set(deploy_gossiper);
         self.mem_block_proposer.set(block_proposer);
@@ -237,6 +255,7 @@ impl Drop for MemoryMetrics {
         unregister_metric!(self.registry, self.mem_event_stream_server);
         unregister_metric!(self.registry, self.mem_chainspec_loader);
         unregister_metric!(self.registry, self.mem_consensus);
+        unregister_metric!(self.registry, self.mem_consensus_era);
         unregister_metric!(self.registry, self.mem_deploy_fetcher);
         unregister_metric!(self.registry, self.mem_deploy_gossiper);
         unregister_metric!(self.registry, self.mem_block_proposer);
@@ -190,6 +190,8 @@ async fn run_validator_network() {
 
     net.settle_on(&mut rng, is_in_era(EraId::from(2)), Duration::from_secs(60))
         .await;
+
+    net.assert_finalized_blocks_agree();
 }
 
 // TODO: fix this test
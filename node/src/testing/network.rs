@@ -1,7 +1,7 @@
 //! A network of test reactors.
 
 use std::{
-    collections::{hash_map::Entry, HashMap},
+    collections::{hash_map::Entry, BTreeMap, HashMap},
     fmt::{Debug, Display},
     hash::Hash,
     time::Duration,
@@ -19,6 +19,7 @@ use crate::{
     effect::{EffectBuilder, Effects},
     reactor::{Finalize, Reactor, Runner},
     testing::TestRng,
+    types::BlockHash,
     NodeRng,
 };
 
@@ -36,6 +37,28 @@ pub trait NetworkedReactor: Sized {
     fn node_id(&self) -> Self::NodeId;
 }
 
+/// A networked reactor which finalizes a linear chain of blocks.
+pub trait FinalizingReactor: NetworkedReactor {
+    /// Returns the hashes of all blocks finalized and stored by this node, keyed by height.
+    fn finalized_blocks(&self) -> &BTreeMap<u64, BlockHash>;
+}
+
+/// Returns a condition for `settle_on` which holds once every node has finalized a block at
+/// `height`.
+pub fn all_finalized_height<R: Reactor + FinalizingReactor>(
+    height: u64,
+) -> impl Fn(&Nodes<R>) -> bool {
+    move |nodes: &Nodes<R>| {
+        nodes.values().all(|runner| {
+            runner
+                .reactor()
+                .inner()
+                .finalized_blocks()
+                .contains_key(&height)
+        })
+    }
+}
+
 /// Time interval for which to poll an observed testing network when no events have occurred.
 const POLL_INTERVAL: Duration = Duration::from_millis(10);
 
@@ -313,6 +336,57 @@ where
     }
 }
 
+impl<R> Network<R>
+where
+    R: Reactor + FinalizingReactor,
+{
+    /// Asserts that no two nodes have finalized different blocks at the same height.
+    ///
+    /// Nodes may lag behind each other, only heights finalized by more than one node are compared.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the conflicting node IDs and block hashes if the nodes disagree.
+    pub fn assert_finalized_blocks_agree(&self) {
+        let mut seen: BTreeMap<u64, (R::NodeId, BlockHash)> = BTreeMap::new();
+        for (node_id, runner) in &self.nodes {
+            for (height, block_hash) in runner.reactor().inner().finalized_blocks() {
+                match seen.get(height) {
+                    Some((other_id, other_hash)) => assert_eq!(
+                        block_hash, other_hash,
+                        "node {} finalized {} at height {}, but node {} finalized {}",
+                        node_id, block_hash, height, other_id, other_hash
+                    ),
+                    None => {
+                        let _ = seen.insert(*height, (node_id.clone(), *block_hash));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Asserts that every node has finalized a block at all heights up to and including `height`,
+    /// and that all nodes agree on these blocks.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any node is missing a block or the nodes disagree.
+    pub fn assert_all_finalized_up_to(&self, height: u64) {
+        for (node_id, runner) in &self.nodes {
+            let finalized = runner.reactor().inner().finalized_blocks();
+            for expected in 0..=height {
+                assert!(
+                    finalized.contains_key(&expected),
+                    "node {} has not finalized a block at height {}",
+                    node_id,
+                    expected
+                );
+            }
+        }
+        self.assert_finalized_blocks_agree();
+    }
+}
+
 impl<R> Finalize for Network<R>
 where
     R: Finalize + NetworkedReactor + Reactor + Send + 'static,
@@ -3,7 +3,9 @@
 pub(crate) mod appendable_block;
 mod block;
 pub mod chainspec;
+mod contract_event;
 mod deploy;
+mod era_rewards;
 mod exit_code;
 mod item;
 pub mod json_compatibility;
@@ -11,6 +13,8 @@ mod node_config;
 mod node_id;
 mod peers_map;
 mod status_feed;
+#[cfg(test)]
+pub(crate) mod test_chain;
 mod timestamp;
 
 use rand::{CryptoRng, RngCore};
@@ -18,16 +22,22 @@ use rand::{CryptoRng, RngCore};
 use rand_chacha::ChaCha20Rng;
 
 pub use block::{
-    json_compatibility::JsonBlock, Block, BlockBody, BlockHash, BlockHeader, BlockSignatures,
-    BlockValidationError, FinalitySignature,
+    json_compatibility::JsonBlock, Block, BlockBody, BlockHash, BlockHeader, BlockIdentifier,
+    BlockSignatures, BlockValidationError, FinalitySignature,
+};
+pub(crate) use block::{
+    BlockByHeight, BlockHeaderWithMetadata, BlockPayload, BlockWithMetadata,
+    ExecutionResultSummary, FinalizedBlock, LegacyBlockHeader,
 };
-pub(crate) use block::{BlockByHeight, BlockHeaderWithMetadata, BlockPayload, FinalizedBlock};
-pub(crate) use chainspec::ActivationPoint;
 pub use chainspec::Chainspec;
+pub(crate) use chainspec::{ActivationPoint, CHAINSPEC_KEY};
+pub use contract_event::ContractEventRecord;
+pub(crate) use deploy::{is_in_canonical_order, LegacyDeploy};
 pub use deploy::{
-    Approval, Deploy, DeployHash, DeployHeader, DeployMetadata, DeployValidationFailure,
-    Error as DeployError, ExcessiveSizeError as ExcessiveSizeDeployError,
+    Approval, Deploy, DeployHash, DeployHeader, DeployMetadata, DeployOrderKey,
+    DeployValidationFailure, Error as DeployError, ExcessiveSizeError as ExcessiveSizeDeployError,
 };
+pub use era_rewards::{DelegatorReward, EraRewards, ValidatorRewards};
 pub use exit_code::ExitCode;
 pub use item::{Item, Tag};
 pub use node_config::NodeConfig;
@@ -1,14 +1,17 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 
 use casper_execution_engine::shared::gas::Gas;
-use casper_types::PublicKey;
+use casper_types::{PublicKey, U512};
 use datasize::DataSize;
 use num_traits::Zero;
 use thiserror::Error;
 
 use crate::{
     components::block_proposer::DeployType,
-    types::{chainspec::DeployConfig, BlockPayload, DeployHash, Timestamp},
+    types::{
+        chainspec::{DeployConfig, SizeClass},
+        BlockPayload, DeployHash, DeployOrderKey, Timestamp,
+    },
 };
 
 #[derive(Debug, Error)]
@@ -17,6 +20,12 @@ pub(crate) enum AddError {
     TransferCount,
     #[error("would exceed maximum deploy count per block")]
     DeployCount,
+    #[error("would exceed maximum count of {0} deploys per block")]
+    SizeClassCount(SizeClass),
+    #[error("payment amount is below the minimum for {0} deploys")]
+    InsufficientPayment(SizeClass),
+    #[error("gas price is below the block's gas price floor")]
+    GasPriceBelowFloor,
     #[error("would exceed maximum gas per block")]
     GasLimit,
     #[error("would exceed maximum block size")]
@@ -30,32 +39,53 @@ pub(crate) enum AddError {
 }
 
 /// A block that is still being added to. It keeps track of and enforces block limits.
+///
+/// Deploys and transfers are kept in their canonical order, regardless of the order they are
+/// added in.
 #[derive(Clone, DataSize, Debug)]
 pub struct AppendableBlock {
     deploy_config: DeployConfig,
-    deploy_hashes: Vec<DeployHash>,
-    transfer_hashes: Vec<DeployHash>,
+    deploys: BTreeSet<DeployOrderKey>,
+    transfers: BTreeSet<DeployOrderKey>,
+    size_class_counts: BTreeMap<SizeClass, u32>,
     deploy_and_transfer_set: HashSet<DeployHash>,
     timestamp: Timestamp,
+    gas_price_floor: u64,
+    /// Whether the per-size-class deploy counts and minimum payments are enforced.
+    size_class_limits: bool,
     #[data_size(skip)]
     total_gas: Gas,
     total_size: usize,
 }
 
 impl AppendableBlock {
-    /// Creates an empty `AppendableBlock`.
-    pub(crate) fn new(deploy_config: DeployConfig, timestamp: Timestamp) -> Self {
+    /// Creates an empty `AppendableBlock`, only accepting deploys offering at least the given gas
+    /// price.
+    pub(crate) fn new(
+        deploy_config: DeployConfig,
+        timestamp: Timestamp,
+        gas_price_floor: u64,
+    ) -> Self {
         AppendableBlock {
             deploy_config,
-            deploy_hashes: Vec::new(),
-            transfer_hashes: Vec::new(),
+            deploys: BTreeSet::new(),
+            transfers: BTreeSet::new(),
+            size_class_counts: BTreeMap::new(),
             timestamp,
+            gas_price_floor,
+            size_class_limits: true,
             deploy_and_transfer_set: HashSet::new(),
             total_gas: Gas::zero(),
             total_size: 0,
         }
     }
 
+    /// Returns this block without the per-size-class deploy count and minimum payment limits.
+    pub(crate) fn without_size_class_limits(mut self) -> Self {
+        self.size_class_limits = false;
+        self
+    }
+
     /// Returns the total size of all deploys so far.
     pub(crate) fn total_size(&self) -> usize {
         self.total_size
@@ -77,15 +107,24 @@ impl AppendableBlock {
         {
             return Err(AddError::InvalidDeploy);
         }
+        if deploy_type.header().gas_price() < self.gas_price_floor {
+            return Err(AddError::GasPriceBelowFloor);
+        }
         if deploy_type.is_transfer() {
             if self.has_max_transfer_count() {
                 return Err(AddError::TransferCount);
             }
-            self.transfer_hashes.push(hash);
+            self.transfers
+                .insert(DeployOrderKey::new(hash, deploy_type.header()));
         } else {
             if self.has_max_deploy_count() {
                 return Err(AddError::DeployCount);
             }
+            let size_class = if self.size_class_limits {
+                Some(self.check_size_class_limits(deploy_type)?)
+            } else {
+                None
+            };
             // Only deploys count towards the size and gas limits.
             let new_total_size = self
                 .total_size
@@ -100,7 +139,11 @@ impl AppendableBlock {
             if new_total_gas > Gas::from(self.deploy_config.block_gas_limit) {
                 return Err(AddError::GasLimit);
             }
-            self.deploy_hashes.push(hash);
+            self.deploys
+                .insert(DeployOrderKey::new(hash, deploy_type.header()));
+            if let Some(size_class) = size_class {
+                *self.size_class_counts.entry(size_class).or_insert(0) += 1;
+            }
             self.total_gas = new_total_gas;
             self.total_size = new_total_size;
         }
@@ -108,30 +151,71 @@ impl AppendableBlock {
         Ok(())
     }
 
-    /// Creates a `BlockPayload` with the `AppendableBlock`s deploys and transfers, and the given
-    /// random bit and accusations.
+    /// Returns `true` if the given deploy and transfer hashes are exactly the ones added to this
+    /// block, each in canonical order.
+    pub(crate) fn is_in_canonical_order(
+        &self,
+        deploy_hashes: &[DeployHash],
+        transfer_hashes: &[DeployHash],
+    ) -> bool {
+        self.deploys
+            .iter()
+            .map(DeployOrderKey::deploy_hash)
+            .eq(deploy_hashes)
+            && self
+                .transfers
+                .iter()
+                .map(DeployOrderKey::deploy_hash)
+                .eq(transfer_hashes)
+    }
+
+    /// Creates a `BlockPayload` with the `AppendableBlock`s deploys and transfers in canonical
+    /// order, and the given random bit and accusations.
     pub(crate) fn into_block_payload(
         self,
         accusations: Vec<PublicKey>,
         random_bit: bool,
     ) -> BlockPayload {
         let AppendableBlock {
-            deploy_hashes,
-            transfer_hashes,
-            ..
+            deploys, transfers, ..
         } = self;
+        let deploy_hashes = deploys.iter().map(|key| *key.deploy_hash()).collect();
+        let transfer_hashes = transfers.iter().map(|key| *key.deploy_hash()).collect();
         BlockPayload::new(deploy_hashes, transfer_hashes, accusations, random_bit)
     }
 
+    /// Returns the deploy's size class, or an error if the block already has the maximum number of
+    /// deploys of that class or the deploy doesn't pay the class's minimum payment.
+    fn check_size_class_limits(&self, deploy_type: &DeployType) -> Result<SizeClass, AddError> {
+        let size_class = self
+            .deploy_config
+            .size_classes
+            .classify(deploy_type.size())
+            .ok_or(AddError::InvalidDeploy)?;
+        let limits = self.deploy_config.size_classes.get(size_class);
+        let size_class_count = self
+            .size_class_counts
+            .get(&size_class)
+            .copied()
+            .unwrap_or(0);
+        if size_class_count >= limits.block_max_count {
+            return Err(AddError::SizeClassCount(size_class));
+        }
+        if deploy_type.payment_amount().value() < U512::from(limits.minimum_payment_motes) {
+            return Err(AddError::InsufficientPayment(size_class));
+        }
+        Ok(size_class)
+    }
+
     /// Returns `true` if the number of transfers is already the maximum allowed count, i.e. no
     /// more transfers can be added to this block.
     fn has_max_transfer_count(&self) -> bool {
-        self.transfer_hashes.len() == self.deploy_config.block_max_transfer_count as usize
+        self.transfers.len() == self.deploy_config.block_max_transfer_count as usize
     }
 
     /// Returns `true` if the number of deploys is already the maximum allowed count, i.e. no more
     /// deploys can be added to this block.
     fn has_max_deploy_count(&self) -> bool {
-        self.deploy_hashes.len() == self.deploy_config.block_max_deploy_count as usize
+        self.deploys.len() == self.deploy_config.block_max_deploy_count as usize
     }
 }
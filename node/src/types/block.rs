@@ -30,7 +30,7 @@ use thiserror::Error;
 use casper_types::system::auction::BLOCK_REWARD;
 use casper_types::{
     bytesrepr::{self, FromBytes, ToBytes},
-    EraId, ProtocolVersion, PublicKey, SecretKey, Signature, U512,
+    EraId, ExecutionResult, ProtocolVersion, PublicKey, SecretKey, Signature, U512,
 };
 
 use super::{Item, Tag, Timestamp};
@@ -46,7 +46,7 @@ use crate::{
         AsymmetricKeyExt,
     },
     rpcs::docs::DocExample,
-    types::{Deploy, DeployHash, JsonBlock},
+    types::{chainspec::DeployConfig, Deploy, DeployHash, JsonBlock},
     utils::DisplayIter,
 };
 
@@ -148,6 +148,7 @@ static BLOCK: Lazy<Block> = Lazy::new(|| {
         finalized_block,
         next_era_validator_weights,
         protocol_version,
+        1,
     )
 });
 static JSON_BLOCK: Lazy<JsonBlock> = Lazy::new(|| {
@@ -308,7 +309,9 @@ impl DocExample for EraReport {
 /// and before execution happened yet.
 #[derive(Clone, DataSize, Debug, PartialOrd, Ord, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct FinalizedBlock {
+    /// The hashes of non-transfer deploys, in the order in which they are executed.
     deploy_hashes: Vec<DeployHash>,
+    /// The hashes of transfers, in the order in which they are executed after the deploys.
     transfer_hashes: Vec<DeployHash>,
     timestamp: Timestamp,
     random_bit: bool,
@@ -364,7 +367,17 @@ impl FinalizedBlock {
         self.proposer.clone()
     }
 
-    /// Returns an iterator over all deploy and transfer hashes.
+    /// Returns the hashes of the non-transfer deploys, in execution order.
+    pub(crate) fn deploy_hashes(&self) -> &[DeployHash] {
+        &self.deploy_hashes
+    }
+
+    /// Returns the hashes of the transfers, in execution order.
+    pub(crate) fn transfer_hashes(&self) -> &[DeployHash] {
+        &self.transfer_hashes
+    }
+
+    /// Returns an iterator over all deploy and transfer hashes, in execution order.
     pub(crate) fn deploys_and_transfers_iter(&self) -> impl Iterator<Item = &DeployHash> {
         self.deploy_hashes.iter().chain(&self.transfer_hashes)
     }
@@ -529,6 +542,34 @@ impl From<Digest> for BlockHash {
     }
 }
 
+/// Identifier for possible ways to retrieve a block.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub enum BlockIdentifier {
+    /// Identify and retrieve the block with its hash.
+    Hash(BlockHash),
+    /// Identify and retrieve the block with its height.
+    Height(u64),
+    /// Retrieve the highest block in the linear chain.
+    Latest,
+}
+
+impl From<BlockHash> for BlockIdentifier {
+    fn from(block_hash: BlockHash) -> Self {
+        BlockIdentifier::Hash(block_hash)
+    }
+}
+
+impl Display for BlockIdentifier {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            BlockIdentifier::Hash(block_hash) => write!(formatter, "{}", block_hash),
+            BlockIdentifier::Height(height) => write!(formatter, "block at height {}", height),
+            BlockIdentifier::Latest => write!(formatter, "latest block"),
+        }
+    }
+}
+
 impl AsRef<[u8]> for BlockHash {
     fn as_ref(&self) -> &[u8] {
         self.0.as_ref()
@@ -629,6 +670,7 @@ pub struct BlockHeader {
     era_id: EraId,
     height: u64,
     protocol_version: ProtocolVersion,
+    next_gas_price_floor: Option<u64>,
 }
 
 impl BlockHeader {
@@ -695,6 +737,13 @@ impl BlockHeader {
         self.protocol_version
     }
 
+    /// Returns the minimum gas price of the deploys included in this block's child.
+    ///
+    /// Blocks created before the gas price floor was introduced don't specify one.
+    pub fn next_gas_price_floor(&self) -> Option<u64> {
+        self.next_gas_price_floor
+    }
+
     /// Returns `true` if this block is the last one in the current era.
     pub fn is_switch_block(&self) -> bool {
         self.era_end.is_some()
@@ -724,9 +773,17 @@ impl BlockHeader {
         self.era_id().is_genesis() && self.height() == 0
     }
 
-    // Serialize the block header.
+    // Serialize the block header for hashing.
+    //
+    // Headers without a gas price floor are serialized exactly as before the floor was introduced,
+    // so that the hashes of existing blocks don't change.
     fn serialize(&self) -> Result<Vec<u8>, bytesrepr::Error> {
-        self.to_bytes()
+        let mut buffer = self.to_bytes()?;
+        if self.next_gas_price_floor.is_none() {
+            let none_length = self.next_gas_price_floor.serialized_length();
+            buffer.truncate(buffer.len() - none_length);
+        }
+        Ok(buffer)
     }
 }
 
@@ -743,6 +800,9 @@ impl Display for BlockHeader {
             self.accumulated_seed,
             self.timestamp,
         )?;
+        if let Some(next_gas_price_floor) = self.next_gas_price_floor {
+            write!(formatter, ", next gas price floor {}", next_gas_price_floor)?;
+        }
         if let Some(ee) = &self.era_end {
             write!(formatter, ", era_end: {}", ee)?;
         }
@@ -763,6 +823,7 @@ impl ToBytes for BlockHeader {
         buffer.extend(self.era_id.to_bytes()?);
         buffer.extend(self.height.to_bytes()?);
         buffer.extend(self.protocol_version.to_bytes()?);
+        buffer.extend(self.next_gas_price_floor.to_bytes()?);
         Ok(buffer)
     }
 
@@ -777,6 +838,7 @@ impl ToBytes for BlockHeader {
             + self.era_id.serialized_length()
             + self.height.serialized_length()
             + self.protocol_version.serialized_length()
+            + self.next_gas_price_floor.serialized_length()
     }
 }
 
@@ -792,6 +854,7 @@ impl FromBytes for BlockHeader {
         let (era_id, remainder) = EraId::from_bytes(remainder)?;
         let (height, remainder) = u64::from_bytes(remainder)?;
         let (protocol_version, remainder) = ProtocolVersion::from_bytes(remainder)?;
+        let (next_gas_price_floor, remainder) = Option::<u64>::from_bytes(remainder)?;
         let block_header = BlockHeader {
             parent_hash,
             state_root_hash,
@@ -803,11 +866,47 @@ impl FromBytes for BlockHeader {
             era_id,
             height,
             protocol_version,
+            next_gas_price_floor,
         };
         Ok((block_header, remainder))
     }
 }
 
+/// The encoding of a [`BlockHeader`] as stored before the gas price floor was introduced.
+///
+/// Only used to migrate headers persisted by earlier versions of the node.
+#[derive(Deserialize)]
+pub(crate) struct LegacyBlockHeader {
+    parent_hash: BlockHash,
+    state_root_hash: Digest,
+    body_hash: Digest,
+    random_bit: bool,
+    accumulated_seed: Digest,
+    era_end: Option<EraEnd>,
+    timestamp: Timestamp,
+    era_id: EraId,
+    height: u64,
+    protocol_version: ProtocolVersion,
+}
+
+impl From<LegacyBlockHeader> for BlockHeader {
+    fn from(legacy_header: LegacyBlockHeader) -> Self {
+        BlockHeader {
+            parent_hash: legacy_header.parent_hash,
+            state_root_hash: legacy_header.state_root_hash,
+            body_hash: legacy_header.body_hash,
+            random_bit: legacy_header.random_bit,
+            accumulated_seed: legacy_header.accumulated_seed,
+            era_end: legacy_header.era_end,
+            timestamp: legacy_header.timestamp,
+            era_id: legacy_header.era_id,
+            height: legacy_header.height,
+            protocol_version: legacy_header.protocol_version,
+            next_gas_price_floor: None,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct BlockHeaderWithMetadata {
     pub block_header: BlockHeader,
@@ -820,6 +919,54 @@ impl Display for BlockHeaderWithMetadata {
     }
 }
 
+/// A summary of the execution result of a single deploy or transfer included in a block.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct ExecutionResultSummary {
+    pub deploy_hash: DeployHash,
+    pub cost: U512,
+    /// The error message if execution failed, `None` if it succeeded.
+    pub error_message: Option<String>,
+}
+
+impl ExecutionResultSummary {
+    pub(crate) fn new(deploy_hash: DeployHash, execution_result: &ExecutionResult) -> Self {
+        let (cost, error_message) = match execution_result {
+            ExecutionResult::Success { cost, .. } => (*cost, None),
+            ExecutionResult::Failure {
+                cost,
+                error_message,
+                ..
+            } => (*cost, Some(error_message.clone())),
+        };
+        ExecutionResultSummary {
+            deploy_hash,
+            cost,
+            error_message,
+        }
+    }
+}
+
+/// A block together with its finality signatures and the execution result summaries of its
+/// deploys, all read from a single consistent storage snapshot.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct BlockWithMetadata {
+    pub block: Block,
+    pub block_signatures: BlockSignatures,
+    pub execution_results: Vec<ExecutionResultSummary>,
+}
+
+impl Display for BlockWithMetadata {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} and {} with {} execution results",
+            self.block,
+            self.block_signatures,
+            self.execution_results.len()
+        )
+    }
+}
+
 /// The body portion of a block.
 #[derive(Clone, DataSize, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize, Debug)]
 pub struct BlockBody {
@@ -1014,6 +1161,7 @@ impl Block {
         finalized_block: FinalizedBlock,
         next_era_validator_weights: Option<BTreeMap<PublicKey, U512>>,
         protocol_version: ProtocolVersion,
+        next_gas_price_floor: u64,
     ) -> Self {
         let body = BlockBody::new(
             finalized_block.proposer.clone(),
@@ -1046,6 +1194,7 @@ impl Block {
             era_id: finalized_block.era_id,
             height: finalized_block.height,
             protocol_version,
+            next_gas_price_floor: Some(next_gas_price_floor),
         };
 
         Self::new_from_header_and_body(header, body)
@@ -1098,6 +1247,16 @@ impl Block {
         self.header.protocol_version
     }
 
+    /// Returns the gas price floor applicable to this block's child.
+    ///
+    /// Blocks created before the gas price floor was introduced are followed by the minimum gas
+    /// price.
+    pub(crate) fn next_gas_price_floor(&self, deploy_config: &DeployConfig) -> u64 {
+        self.header
+            .next_gas_price_floor
+            .unwrap_or(deploy_config.min_gas_price)
+    }
+
     /// Returns the hash of the parent block.
     /// If the block is the first block in the linear chain returns `None`.
     pub fn parent(&self) -> Option<&BlockHash> {
@@ -1175,6 +1334,7 @@ impl Block {
             finalized_block,
             next_era_validator_weights,
             protocol_version,
+            1,
         )
     }
 }
@@ -1403,6 +1563,8 @@ pub(crate) mod json_compatibility {
         era_id: EraId,
         height: u64,
         protocol_version: ProtocolVersion,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        next_gas_price_floor: Option<u64>,
     }
 
     impl From<BlockHeader> for JsonBlockHeader {
@@ -1418,6 +1580,7 @@ pub(crate) mod json_compatibility {
                 era_id: block_header.era_id,
                 height: block_header.height,
                 protocol_version: block_header.protocol_version,
+                next_gas_price_floor: block_header.next_gas_price_floor,
             }
         }
     }
@@ -1435,6 +1598,7 @@ pub(crate) mod json_compatibility {
                 era_id: block_header.era_id,
                 height: block_header.height,
                 protocol_version: block_header.protocol_version,
+                next_gas_price_floor: block_header.next_gas_price_floor,
             }
         }
     }
@@ -1656,6 +1820,46 @@ mod tests {
         bytesrepr::test_serialization_roundtrip(&block_header);
     }
 
+    #[test]
+    fn legacy_block_header_should_keep_its_hash() {
+        let mut rng = TestRng::new();
+        let mut block_header: BlockHeader = Block::random(&mut rng).header;
+        block_header.next_gas_price_floor = None;
+
+        let legacy_fields = (
+            block_header.parent_hash,
+            block_header.state_root_hash,
+            block_header.body_hash,
+            block_header.random_bit,
+            block_header.accumulated_seed,
+            block_header.era_end.clone(),
+            block_header.timestamp,
+            block_header.era_id,
+            block_header.height,
+            block_header.protocol_version,
+        );
+        let legacy_header: LegacyBlockHeader =
+            bincode::deserialize(&bincode::serialize(&legacy_fields).unwrap()).unwrap();
+        assert_eq!(BlockHeader::from(legacy_header), block_header);
+
+        let mut legacy_bytes = vec![];
+        legacy_bytes.extend(block_header.parent_hash.to_bytes().unwrap());
+        legacy_bytes.extend(block_header.state_root_hash.to_bytes().unwrap());
+        legacy_bytes.extend(block_header.body_hash.to_bytes().unwrap());
+        legacy_bytes.extend(block_header.random_bit.to_bytes().unwrap());
+        legacy_bytes.extend(block_header.accumulated_seed.to_bytes().unwrap());
+        legacy_bytes.extend(block_header.era_end.to_bytes().unwrap());
+        legacy_bytes.extend(block_header.timestamp.to_bytes().unwrap());
+        legacy_bytes.extend(block_header.era_id.to_bytes().unwrap());
+        legacy_bytes.extend(block_header.height.to_bytes().unwrap());
+        legacy_bytes.extend(block_header.protocol_version.to_bytes().unwrap());
+        assert_eq!(
+            block_header.hash(),
+            BlockHash::new(hash::hash(&legacy_bytes))
+        );
+        bytesrepr::test_serialization_roundtrip(&block_header);
+    }
+
     #[test]
     fn bytesrepr_roundtrip_era_report() {
         let mut rng = TestRng::new();
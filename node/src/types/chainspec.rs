@@ -3,18 +3,22 @@
 
 mod accounts_config;
 mod activation_point;
+mod banned_validators;
 mod core_config;
 mod deploy_config;
 mod error;
+mod genesis_snapshot;
 mod global_state_update;
 mod highway_config;
 mod network_config;
 mod parse_toml;
 mod protocol_config;
+mod validators_override;
 
 use std::{fmt::Debug, path::Path};
 
 use datasize::DataSize;
+use once_cell::sync::Lazy;
 #[cfg(test)]
 use rand::Rng;
 use serde::Serialize;
@@ -22,31 +26,45 @@ use tracing::{error, warn};
 
 use casper_execution_engine::{
     core::engine_state::genesis::ExecConfig,
-    shared::{system_config::SystemConfig, wasm_config::WasmConfig},
+    shared::{stored_value::StoredValue, system_config::SystemConfig, wasm_config::WasmConfig},
 };
 use casper_types::{
     bytesrepr::{self, FromBytes, ToBytes},
-    ProtocolVersion,
+    CLValue, CLValueError, Key, ProtocolVersion,
 };
 
 #[cfg(test)]
 pub(crate) use self::accounts_config::{AccountConfig, ValidatorConfig};
 pub use self::error::Error;
 pub(crate) use self::{
-    accounts_config::AccountsConfig, activation_point::ActivationPoint, core_config::CoreConfig,
-    deploy_config::DeployConfig, global_state_update::GlobalStateUpdate,
-    highway_config::HighwayConfig, network_config::NetworkConfig, protocol_config::ProtocolConfig,
+    accounts_config::AccountsConfig,
+    activation_point::ActivationPoint,
+    banned_validators::BannedValidators,
+    core_config::CoreConfig,
+    deploy_config::{DeployConfig, SizeClass},
+    genesis_snapshot::{GenesisSnapshot, GENESIS_SNAPSHOT_FILENAME},
+    global_state_update::GlobalStateUpdate,
+    highway_config::HighwayConfig,
+    network_config::NetworkConfig,
+    protocol_config::ProtocolConfig,
+    validators_override::ValidatorsOverride,
 };
 #[cfg(test)]
 use crate::testing::TestRng;
 use crate::{
     crypto::hash::{self, Digest},
+    types::TimeDiff,
     utils::Loadable,
 };
 
 /// The name of the chainspec file on disk.
 pub const CHAINSPEC_NAME: &str = "chainspec.toml";
 
+/// The well-known key under which the active chainspec is stored in global state at genesis and on
+/// every upgrade.
+pub(crate) static CHAINSPEC_KEY: Lazy<Key> =
+    Lazy::new(|| Key::Hash(hash::hash(b"chainspec").to_array()));
+
 /// A collection of configuration settings describing the state of the system at genesis and after
 /// upgrades to basic system functionality occurring after genesis.
 #[derive(DataSize, PartialEq, Eq, Serialize, Debug)]
@@ -81,7 +99,30 @@ impl Chainspec {
             warn!("era duration is less than minimum era height * round length!");
         }
 
+        if self.max_deploy_ttl() < self.deploy_config.max_ttl {
+            warn!(
+                max_ttl = %self.deploy_config.max_ttl,
+                unbonding_period = %self.max_deploy_ttl(),
+                "deploy max_ttl exceeds the unbonding period, new deploys are limited to the latter"
+            );
+        }
+
         self.highway_config.validate_config();
+        self.deploy_config.validate_config();
+    }
+
+    /// Returns the maximum time-to-live of new deploys: the configured `max_ttl`, but no longer
+    /// than the unbonding period of `unbonding_delay` eras, so that a deploy can't be replayed
+    /// after its signers' stake has been unbonded.
+    ///
+    /// If the era duration is zero, eras are defined by height only and `max_ttl` applies as is.
+    pub(crate) fn max_deploy_ttl(&self) -> TimeDiff {
+        let era_duration = self.core_config.era_duration;
+        if era_duration.millis() == 0 {
+            return self.deploy_config.max_ttl;
+        }
+        let unbonding_period = era_duration.saturating_mul(self.core_config.unbonding_delay);
+        self.deploy_config.max_ttl.min(unbonding_period)
     }
 
     /// Serializes `self` and hashes the resulting bytes.
@@ -93,6 +134,25 @@ impl Chainspec {
         hash::hash(&serialized_chainspec)
     }
 
+    /// Returns the value stored under `CHAINSPEC_KEY` in global state: the serialized chainspec as
+    /// a `CLValue`.
+    pub(crate) fn to_stored_value(&self) -> Result<StoredValue, CLValueError> {
+        let serialized_chainspec = self.to_bytes().map_err(CLValueError::Serialization)?;
+        let cl_value = CLValue::from_t(serialized_chainspec)?;
+        Ok(StoredValue::CLValue(cl_value))
+    }
+
+    /// Parses a chainspec read from `CHAINSPEC_KEY` in global state.
+    pub(crate) fn from_stored_value(stored_value: StoredValue) -> Option<Self> {
+        match stored_value {
+            StoredValue::CLValue(cl_value) => {
+                let serialized_chainspec: Vec<u8> = cl_value.into_t().ok()?;
+                bytesrepr::deserialize(serialized_chainspec).ok()
+            }
+            _ => None,
+        }
+    }
+
     /// Returns true if this chainspec has an activation_point specifying era ID 0.
     pub(crate) fn is_genesis(&self) -> bool {
         self.protocol_config.activation_point.is_genesis()
@@ -252,6 +312,7 @@ mod tests {
             transfer_from_purse_to_account: HostFunction::new(136, [0, 1, 2, 3, 4, 5, 6, 7, 8]),
             transfer_from_purse_to_purse: HostFunction::new(137, [0, 1, 2, 3, 4, 5, 6, 7]),
             get_balance: HostFunction::new(110, [0, 1, 2]),
+            get_balance_with_proof: HostFunction::new(142, [0, 1, 2]),
             get_phase: HostFunction::new(117, [0]),
             get_system_contract: HostFunction::new(118, [0, 1, 2]),
             get_main_purse: HostFunction::new(114, [0]),
@@ -269,11 +330,13 @@ mod tests {
             remove_contract_user_group_urefs: HostFunction::new(131, [0, 1, 2, 3, 4, 5]),
             print: HostFunction::new(123, [0, 1]),
             blake2b: HostFunction::new(133, [0, 1, 2, 3]),
+            emit_event: HostFunction::new(143, [0, 1, 2, 3]),
         });
     static EXPECTED_GENESIS_WASM_COSTS: Lazy<WasmConfig> = Lazy::new(|| {
         WasmConfig::new(
             17, // initial_memory
             19, // max_stack_height
+            23, // max_table_size
             EXPECTED_GENESIS_COSTS,
             EXPECTED_GENESIS_STORAGE_COSTS,
             *EXPECTED_GENESIS_HOST_FUNCTION_COSTS,
@@ -330,6 +393,7 @@ mod tests {
                 );
             }
             assert!(spec.protocol_config.last_emergency_restart.is_none());
+            assert!(spec.protocol_config.banned_validators.is_empty());
         } else {
             assert_eq!(
                 spec.protocol_config.version,
@@ -348,6 +412,15 @@ mod tests {
                 spec.protocol_config.last_emergency_restart,
                 Some(EraId::new(99))
             );
+            assert_eq!(spec.protocol_config.banned_validators.len(), 1);
+            assert_eq!(
+                spec.protocol_config.banned_validators[0].era_id,
+                EraId::new(100)
+            );
+            assert_eq!(
+                spec.protocol_config.banned_validators[0].public_keys.len(),
+                2
+            );
         }
 
         assert_eq!(spec.network_config.name, "test-chain");
@@ -374,6 +447,22 @@ mod tests {
         assert_eq!(spec.deploy_config.max_block_size, 12);
         assert_eq!(spec.deploy_config.block_max_deploy_count, 125);
         assert_eq!(spec.deploy_config.block_gas_limit, 13);
+        assert_eq!(
+            spec.deploy_config.standard_payment_minimum_motes,
+            100_000_000
+        );
+        assert_eq!(spec.deploy_config.min_gas_price, 2);
+        assert_eq!(spec.deploy_config.max_gas_price, 20);
+        assert_eq!(spec.deploy_config.block_fullness_target_percent, 75);
+        assert_eq!(
+            spec.deploy_config.refund_handling.refund_ratio(),
+            Ratio::new(1, 4)
+        );
+        assert_eq!(spec.deploy_config.max_execution_steps(), Some(1_000_000));
+        let size_classes = &spec.deploy_config.size_classes;
+        assert_eq!(size_classes.small.max_size, 16_384);
+        assert_eq!(size_classes.medium.block_max_count, 20);
+        assert_eq!(size_classes.large.minimum_payment_motes, 10_000_000_000);
 
         assert_eq!(spec.wasm_config, *EXPECTED_GENESIS_WASM_COSTS);
     }
@@ -394,6 +483,32 @@ mod tests {
         bytesrepr::test_serialization_roundtrip(&chainspec);
     }
 
+    #[test]
+    fn stored_value_roundtrip() {
+        let mut rng = crate::new_rng();
+        let chainspec = Chainspec::random(&mut rng);
+        let stored_value = chainspec.to_stored_value().unwrap();
+        assert_eq!(Chainspec::from_stored_value(stored_value), Some(chainspec));
+    }
+
+    #[test]
+    fn should_limit_max_deploy_ttl_to_unbonding_period() {
+        let mut rng = crate::new_rng();
+        let mut chainspec = Chainspec::random(&mut rng);
+        chainspec.core_config.era_duration = TimeDiff::from(1_000);
+        chainspec.core_config.unbonding_delay = 7;
+
+        chainspec.deploy_config.max_ttl = TimeDiff::from(6_000);
+        assert_eq!(chainspec.max_deploy_ttl(), TimeDiff::from(6_000));
+
+        chainspec.deploy_config.max_ttl = TimeDiff::from(8_000);
+        assert_eq!(chainspec.max_deploy_ttl(), TimeDiff::from(7_000));
+
+        // Eras defined by height only don't bound the time-to-live.
+        chainspec.core_config.era_duration = TimeDiff::from(0);
+        assert_eq!(chainspec.max_deploy_ttl(), TimeDiff::from(8_000));
+    }
+
     #[ignore = "We probably need to reconsider our approach here"]
     #[test]
     fn should_have_deterministic_chainspec_hash() {
@@ -0,0 +1,76 @@
+use std::collections::BTreeSet;
+
+use datasize::DataSize;
+#[cfg(test)]
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use casper_types::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    EraId, PublicKey,
+};
+
+#[cfg(test)]
+use crate::{crypto::AsymmetricKeyExt, testing::TestRng};
+
+/// Validators that are banned in a particular era, regardless of any evidence against them.
+///
+/// Their units are treated like those of validators slashed in an earlier era: they are marked as
+/// faulty from the beginning of the era, and their weight doesn't count towards finality.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, DataSize, Debug)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct BannedValidators {
+    /// The era in which the validators are banned.
+    pub(crate) era_id: EraId,
+    /// The public keys of the banned validators.
+    pub(crate) public_keys: BTreeSet<PublicKey>,
+}
+
+#[cfg(test)]
+impl BannedValidators {
+    pub(crate) fn random(rng: &mut TestRng) -> Self {
+        let count = rng.gen_range(1..10);
+        BannedValidators {
+            era_id: EraId::new(rng.gen_range(0..u32::MAX as u64)),
+            public_keys: (0..count).map(|_| PublicKey::random(rng)).collect(),
+        }
+    }
+}
+
+impl ToBytes for BannedValidators {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        buffer.extend(self.era_id.to_bytes()?);
+        buffer.extend(self.public_keys.to_bytes()?);
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.era_id.serialized_length() + self.public_keys.serialized_length()
+    }
+}
+
+impl FromBytes for BannedValidators {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (era_id, remainder) = EraId::from_bytes(bytes)?;
+        let (public_keys, remainder) = BTreeSet::<PublicKey>::from_bytes(remainder)?;
+        let banned_validators = BannedValidators {
+            era_id,
+            public_keys,
+        };
+        Ok((banned_validators, remainder))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn banned_validators_bytesrepr_roundtrip() {
+        let mut rng = crate::new_rng();
+        let banned_validators = BannedValidators::random(&mut rng);
+        bytesrepr::test_serialization_roundtrip(&banned_validators);
+    }
+}
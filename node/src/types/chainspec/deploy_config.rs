@@ -1,7 +1,9 @@
+use std::fmt::{self, Display, Formatter};
 #[cfg(test)]
 use std::str::FromStr;
 
 use datasize::DataSize;
+use num::rational::Ratio;
 #[cfg(test)]
 use num_traits::Zero;
 #[cfg(test)]
@@ -20,6 +22,223 @@ use casper_types::{
 use crate::testing::TestRng;
 use crate::types::TimeDiff;
 
+const REFUND_HANDLING_REFUND_TAG: u8 = 0;
+
+/// Defines how the unspent part of a deploy's payment is handled once the deploy is executed.
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum RefundHandling {
+    /// `refund_ratio` of the unspent amount is refunded to the payer's purse, the rest is moved to
+    /// the rewards purse.  The refund is rounded down.
+    Refund { refund_ratio: Ratio<u64> },
+}
+
+impl RefundHandling {
+    /// Returns the fraction of the unspent payment amount refunded to the payer.
+    pub(crate) fn refund_ratio(&self) -> Ratio<u64> {
+        match self {
+            RefundHandling::Refund { refund_ratio } => *refund_ratio,
+        }
+    }
+}
+
+impl ToBytes for RefundHandling {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        match self {
+            RefundHandling::Refund { refund_ratio } => {
+                buffer.push(REFUND_HANDLING_REFUND_TAG);
+                buffer.extend(refund_ratio.to_bytes()?);
+            }
+        }
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        1 + match self {
+            RefundHandling::Refund { refund_ratio } => refund_ratio.serialized_length(),
+        }
+    }
+}
+
+impl FromBytes for RefundHandling {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (tag, remainder) = u8::from_bytes(bytes)?;
+        match tag {
+            REFUND_HANDLING_REFUND_TAG => {
+                let (refund_ratio, remainder) = Ratio::<u64>::from_bytes(remainder)?;
+                Ok((RefundHandling::Refund { refund_ratio }, remainder))
+            }
+            _ => Err(bytesrepr::Error::Formatting),
+        }
+    }
+}
+
+/// The size classes of non-transfer deploys.
+#[derive(Copy, Clone, DataSize, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum SizeClass {
+    Small,
+    Medium,
+    Large,
+}
+
+impl Display for SizeClass {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SizeClass::Small => write!(formatter, "small"),
+            SizeClass::Medium => write!(formatter, "medium"),
+            SizeClass::Large => write!(formatter, "large"),
+        }
+    }
+}
+
+/// The limits applying to the non-transfer deploys of one size class.
+#[derive(Copy, Clone, DataSize, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct DeploySizeClass {
+    /// The largest serialized size in bytes of deploys in this class.
+    pub(crate) max_size: u32,
+    /// The maximum number of deploys of this class permitted in a single block.
+    pub(crate) block_max_count: u32,
+    /// The minimum payment amount in motes of deploys in this class.
+    pub(crate) minimum_payment_motes: u64,
+}
+
+#[cfg(test)]
+impl DeploySizeClass {
+    /// Generates a random instance with the given maximum size using a `TestRng`.
+    fn random(rng: &mut TestRng, max_size: u32) -> Self {
+        DeploySizeClass {
+            max_size,
+            block_max_count: rng.gen(),
+            minimum_payment_motes: rng.gen_range(0..MAX_PAYMENT_AMOUNT),
+        }
+    }
+}
+
+impl ToBytes for DeploySizeClass {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        buffer.extend(self.max_size.to_bytes()?);
+        buffer.extend(self.block_max_count.to_bytes()?);
+        buffer.extend(self.minimum_payment_motes.to_bytes()?);
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.max_size.serialized_length()
+            + self.block_max_count.serialized_length()
+            + self.minimum_payment_motes.serialized_length()
+    }
+}
+
+impl FromBytes for DeploySizeClass {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (max_size, remainder) = u32::from_bytes(bytes)?;
+        let (block_max_count, remainder) = u32::from_bytes(remainder)?;
+        let (minimum_payment_motes, remainder) = u64::from_bytes(remainder)?;
+        let size_class = DeploySizeClass {
+            max_size,
+            block_max_count,
+            minimum_payment_motes,
+        };
+        Ok((size_class, remainder))
+    }
+}
+
+/// The limits of the small, medium and large non-transfer deploys.
+///
+/// Each deploy belongs to the smallest class whose `max_size` is not less than its serialized
+/// size.  Separate per-block counts keep a few large deploys, e.g. contract installations, from
+/// crowding out many small ones.
+#[derive(Copy, Clone, DataSize, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct DeploySizeClasses {
+    pub(crate) small: DeploySizeClass,
+    pub(crate) medium: DeploySizeClass,
+    pub(crate) large: DeploySizeClass,
+}
+
+impl DeploySizeClasses {
+    /// Returns the size class of deploys with the given serialized size, or `None` if it exceeds
+    /// the large class.
+    pub(crate) fn classify(&self, deploy_size: usize) -> Option<SizeClass> {
+        [SizeClass::Small, SizeClass::Medium, SizeClass::Large]
+            .iter()
+            .copied()
+            .find(|size_class| deploy_size <= self.get(*size_class).max_size as usize)
+    }
+
+    /// Returns the limits of the given size class.
+    pub(crate) fn get(&self, size_class: SizeClass) -> &DeploySizeClass {
+        match size_class {
+            SizeClass::Small => &self.small,
+            SizeClass::Medium => &self.medium,
+            SizeClass::Large => &self.large,
+        }
+    }
+}
+
+#[cfg(test)]
+impl DeploySizeClasses {
+    /// Generates a random instance covering deploys up to `max_deploy_size` using a `TestRng`.
+    fn random(rng: &mut TestRng, max_deploy_size: u32) -> Self {
+        let medium_max_size = rng.gen_range(0..=max_deploy_size);
+        let small_max_size = rng.gen_range(0..=medium_max_size);
+        DeploySizeClasses {
+            small: DeploySizeClass::random(rng, small_max_size),
+            medium: DeploySizeClass::random(rng, medium_max_size),
+            large: DeploySizeClass::random(rng, max_deploy_size),
+        }
+    }
+}
+
+#[cfg(test)]
+impl Default for DeploySizeClasses {
+    fn default() -> Self {
+        let unlimited = |max_size| DeploySizeClass {
+            max_size,
+            block_max_count: u32::MAX,
+            minimum_payment_motes: 0,
+        };
+        DeploySizeClasses {
+            small: unlimited(16_384),
+            medium: unlimited(131_072),
+            large: unlimited(1_048_576),
+        }
+    }
+}
+
+impl ToBytes for DeploySizeClasses {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        buffer.extend(self.small.to_bytes()?);
+        buffer.extend(self.medium.to_bytes()?);
+        buffer.extend(self.large.to_bytes()?);
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.small.serialized_length()
+            + self.medium.serialized_length()
+            + self.large.serialized_length()
+    }
+}
+
+impl FromBytes for DeploySizeClasses {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (small, remainder) = DeploySizeClass::from_bytes(bytes)?;
+        let (medium, remainder) = DeploySizeClass::from_bytes(remainder)?;
+        let (large, remainder) = DeploySizeClass::from_bytes(remainder)?;
+        let size_classes = DeploySizeClasses {
+            small,
+            medium,
+            large,
+        };
+        Ok((size_classes, remainder))
+    }
+}
+
 #[derive(Copy, Clone, DataSize, PartialEq, Eq, Serialize, Deserialize, Debug)]
 // Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
 #[serde(deny_unknown_fields)]
@@ -35,6 +254,108 @@ pub struct DeployConfig {
     pub(crate) payment_args_max_length: u32,
     pub(crate) session_args_max_length: u32,
     pub(crate) native_transfer_minimum_motes: u64,
+    pub(crate) standard_payment_minimum_motes: u64,
+    pub(crate) min_gas_price: u64,
+    pub(crate) max_gas_price: u64,
+    pub(crate) block_fullness_target_percent: u8,
+    #[data_size(skip)]
+    pub(crate) refund_handling: RefundHandling,
+    pub(crate) max_execution_steps: u64,
+    pub(crate) size_classes: DeploySizeClasses,
+}
+
+impl DeployConfig {
+    /// Checks whether the values set in the config make sense and panics if they don't.
+    pub fn validate_config(&self) {
+        if self.min_gas_price > self.max_gas_price {
+            panic!(
+                "Minimum gas price is greater than the maximum gas price.\n\
+                 Minimum gas price: {min},\n\
+                 Maximum gas price: {max}",
+                min = self.min_gas_price,
+                max = self.max_gas_price
+            );
+        }
+
+        if self.block_fullness_target_percent > 100 {
+            panic!(
+                "Block fullness target is not in the range [0, 100]! Target: {target}",
+                target = self.block_fullness_target_percent
+            );
+        }
+
+        let refund_ratio = self.refund_handling.refund_ratio();
+        if refund_ratio.numer() > refund_ratio.denom() {
+            panic!(
+                "Refund ratio is not in the range [0, 1]! Ratio: {ratio}",
+                ratio = refund_ratio
+            );
+        }
+
+        let DeploySizeClasses {
+            small,
+            medium,
+            large,
+        } = &self.size_classes;
+        if small.max_size > medium.max_size || medium.max_size > large.max_size {
+            panic!(
+                "Deploy size classes are not ordered by size! Small: {small}, medium: {medium}, \
+                 large: {large}",
+                small = small.max_size,
+                medium = medium.max_size,
+                large = large.max_size
+            );
+        }
+
+        if large.max_size < self.max_deploy_size {
+            panic!(
+                "The large deploy size class doesn't cover the maximum deploy size! Large: \
+                 {large}, maximum deploy size: {max}",
+                large = large.max_size,
+                max = self.max_deploy_size
+            );
+        }
+    }
+
+    /// Returns the maximum number of times gas may be charged while executing the payment or
+    /// session code of a deploy, or `None` if unlimited.
+    pub(crate) fn max_execution_steps(&self) -> Option<u64> {
+        (self.max_execution_steps != 0).then(|| self.max_execution_steps)
+    }
+
+    /// Returns the gas price floor applicable to the child of a block which was created with the
+    /// given `gas_price_floor` and contained the given numbers of deploys and transfers.
+    ///
+    /// If the block was fuller than `block_fullness_target_percent`, the floor is raised by an
+    /// eighth (at least by one), if it was emptier the floor is lowered by the same amount.  The
+    /// result is always kept within `[min_gas_price, max_gas_price]`.
+    pub(crate) fn next_gas_price_floor(
+        &self,
+        gas_price_floor: u64,
+        deploy_count: usize,
+        transfer_count: usize,
+    ) -> u64 {
+        let fullness_percent = |count: usize, max_count: u32| -> u64 {
+            if max_count == 0 {
+                0
+            } else {
+                (count as u64).saturating_mul(100) / u64::from(max_count)
+            }
+        };
+        let fullness = fullness_percent(deploy_count, self.block_max_deploy_count).max(
+            fullness_percent(transfer_count, self.block_max_transfer_count),
+        );
+        let target = u64::from(self.block_fullness_target_percent);
+        let step = (gas_price_floor / 8).max(1);
+        let next = if fullness > target {
+            gas_price_floor.saturating_add(step)
+        } else if fullness < target {
+            gas_price_floor.saturating_sub(step)
+        } else {
+            gas_price_floor
+        };
+        next.max(self.min_gas_price).min(self.max_gas_price)
+    }
 }
 
 #[cfg(test)]
@@ -53,6 +374,16 @@ impl DeployConfig {
         let session_args_max_length = rng.gen();
         let native_transfer_minimum_motes =
             rng.gen_range(MAX_PAYMENT_AMOUNT..1_000_000_000_000_000);
+        let standard_payment_minimum_motes = rng.gen_range(0..MAX_PAYMENT_AMOUNT);
+        let min_gas_price = rng.gen_range(1..100);
+        let max_gas_price = rng.gen_range(min_gas_price..1_000);
+        let block_fullness_target_percent = rng.gen_range(1..=100);
+        let refund_ratio_denom = rng.gen_range(1..1_000);
+        let refund_handling = RefundHandling::Refund {
+            refund_ratio: Ratio::new(rng.gen_range(0..=refund_ratio_denom), refund_ratio_denom),
+        };
+        let max_execution_steps = rng.gen();
+        let size_classes = DeploySizeClasses::random(rng, max_deploy_size);
 
         DeployConfig {
             max_payment_cost,
@@ -66,6 +397,13 @@ impl DeployConfig {
             payment_args_max_length,
             session_args_max_length,
             native_transfer_minimum_motes,
+            standard_payment_minimum_motes,
+            min_gas_price,
+            max_gas_price,
+            block_fullness_target_percent,
+            refund_handling,
+            max_execution_steps,
+            size_classes,
         }
     }
 }
@@ -85,6 +423,15 @@ impl Default for DeployConfig {
             payment_args_max_length: 1024,
             session_args_max_length: 1024,
             native_transfer_minimum_motes: MAX_PAYMENT_AMOUNT,
+            standard_payment_minimum_motes: MAX_PAYMENT_AMOUNT,
+            min_gas_price: 1,
+            max_gas_price: 1,
+            block_fullness_target_percent: 50,
+            refund_handling: RefundHandling::Refund {
+                refund_ratio: Ratio::new(0, 1),
+            },
+            max_execution_steps: 0,
+            size_classes: DeploySizeClasses::default(),
         }
     }
 }
@@ -103,6 +450,13 @@ impl ToBytes for DeployConfig {
         buffer.extend(self.payment_args_max_length.to_bytes()?);
         buffer.extend(self.session_args_max_length.to_bytes()?);
         buffer.extend(self.native_transfer_minimum_motes.to_bytes()?);
+        buffer.extend(self.standard_payment_minimum_motes.to_bytes()?);
+        buffer.extend(self.min_gas_price.to_bytes()?);
+        buffer.extend(self.max_gas_price.to_bytes()?);
+        buffer.extend(self.block_fullness_target_percent.to_bytes()?);
+        buffer.extend(self.refund_handling.to_bytes()?);
+        buffer.extend(self.max_execution_steps.to_bytes()?);
+        buffer.extend(self.size_classes.to_bytes()?);
         Ok(buffer)
     }
 
@@ -118,6 +472,13 @@ impl ToBytes for DeployConfig {
             + self.payment_args_max_length.serialized_length()
             + self.session_args_max_length.serialized_length()
             + self.native_transfer_minimum_motes.serialized_length()
+            + self.standard_payment_minimum_motes.serialized_length()
+            + self.min_gas_price.serialized_length()
+            + self.max_gas_price.serialized_length()
+            + self.block_fullness_target_percent.serialized_length()
+            + self.refund_handling.serialized_length()
+            + self.max_execution_steps.serialized_length()
+            + self.size_classes.serialized_length()
     }
 }
 
@@ -135,6 +496,13 @@ impl FromBytes for DeployConfig {
         let (payment_args_max_length, remainder) = u32::from_bytes(remainder)?;
         let (session_args_max_length, remainder) = u32::from_bytes(remainder)?;
         let (native_transfer_minimum_motes, remainder) = u64::from_bytes(remainder)?;
+        let (standard_payment_minimum_motes, remainder) = u64::from_bytes(remainder)?;
+        let (min_gas_price, remainder) = u64::from_bytes(remainder)?;
+        let (max_gas_price, remainder) = u64::from_bytes(remainder)?;
+        let (block_fullness_target_percent, remainder) = u8::from_bytes(remainder)?;
+        let (refund_handling, remainder) = RefundHandling::from_bytes(remainder)?;
+        let (max_execution_steps, remainder) = u64::from_bytes(remainder)?;
+        let (size_classes, remainder) = DeploySizeClasses::from_bytes(remainder)?;
         let config = DeployConfig {
             max_payment_cost,
             max_ttl,
@@ -147,6 +515,13 @@ impl FromBytes for DeployConfig {
             payment_args_max_length,
             session_args_max_length,
             native_transfer_minimum_motes,
+            standard_payment_minimum_motes,
+            min_gas_price,
+            max_gas_price,
+            block_fullness_target_percent,
+            refund_handling,
+            max_execution_steps,
+            size_classes,
         };
         Ok((config, remainder))
     }
@@ -171,4 +546,37 @@ mod tests {
         let decoded = toml::from_str(&encoded).unwrap();
         assert_eq!(config, decoded);
     }
+
+    #[test]
+    fn should_adjust_gas_price_floor_by_block_fullness() {
+        let config = DeployConfig {
+            block_max_deploy_count: 100,
+            block_max_transfer_count: 1000,
+            min_gas_price: 1,
+            max_gas_price: 100,
+            block_fullness_target_percent: 50,
+            ..Default::default()
+        };
+
+        // Full and empty blocks move the floor by an eighth, but at least by one.
+        assert_eq!(config.next_gas_price_floor(16, 100, 0), 18);
+        assert_eq!(config.next_gas_price_floor(16, 0, 0), 14);
+        assert_eq!(config.next_gas_price_floor(4, 0, 1000), 5);
+        // A block exactly at the target leaves the floor unchanged.
+        assert_eq!(config.next_gas_price_floor(16, 50, 0), 16);
+        // The floor never leaves the configured bounds.
+        assert_eq!(config.next_gas_price_floor(1, 0, 0), 1);
+        assert_eq!(config.next_gas_price_floor(100, 100, 1000), 100);
+    }
+
+    #[test]
+    fn should_classify_deploys_by_size() {
+        let size_classes = DeploySizeClasses::default();
+        assert_eq!(size_classes.classify(0), Some(SizeClass::Small));
+        assert_eq!(size_classes.classify(16_384), Some(SizeClass::Small));
+        assert_eq!(size_classes.classify(16_385), Some(SizeClass::Medium));
+        assert_eq!(size_classes.classify(131_072), Some(SizeClass::Medium));
+        assert_eq!(size_classes.classify(1_048_576), Some(SizeClass::Large));
+        assert_eq!(size_classes.classify(1_048_577), None);
+    }
 }
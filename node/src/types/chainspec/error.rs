@@ -3,7 +3,7 @@ use std::{io, path::PathBuf};
 use thiserror::Error;
 use uint::FromDecStrErr;
 
-use casper_types::account::ACCOUNT_HASH_LENGTH;
+use casper_types::{account::ACCOUNT_HASH_LENGTH, EraId};
 
 use crate::utils::ReadFileError;
 
@@ -34,6 +34,26 @@ pub enum Error {
     #[error("could not load the global state update: {0}")]
     LoadGlobalStateUpgrade(#[from] GlobalStateUpdateLoadError),
 
+    /// Error loading the validators override.
+    #[error("could not load the validators override: {0}")]
+    LoadValidatorsOverride(#[from] ValidatorsOverrideLoadError),
+
+    /// A validators override was given for an activation point that isn't an emergency restart.
+    #[error("validators can only be overridden in an emergency restart")]
+    ValidatorsOverrideWithoutEmergencyRestart,
+
+    /// Validators were banned in an era that ended before the activation point.
+    #[error("cannot ban validators in {0}, before the activation point")]
+    BannedValidatorsBeforeActivationPoint(EraId),
+
+    /// A genesis snapshot was given for an activation point that isn't genesis.
+    #[error("a genesis snapshot can only be used at genesis")]
+    GenesisSnapshotAfterGenesis,
+
+    /// A genesis snapshot was given, but the snapshot file is missing.
+    #[error("genesis snapshot file not found in the chainspec directory")]
+    MissingGenesisSnapshotFile,
+
     /// Failed to read the given directory.
     #[error("failed to read dir {}: {error}", dir.display())]
     ReadDir {
@@ -98,3 +118,35 @@ pub enum GlobalStateUpdateLoadError {
     #[error("decoding from formatted string error: {0}")]
     DecodingKeyFromStr(String),
 }
+
+/// Error loading validators override file.
+#[derive(Debug, Error)]
+pub enum ValidatorsOverrideLoadError {
+    /// Error loading the validators file.
+    #[error("could not load the file: {0}")]
+    LoadFile(#[from] ReadFileError),
+
+    /// Error while decoding the validators from TOML format.
+    #[error("decoding from TOML error: {0}")]
+    DecodingFromToml(#[from] toml::de::Error),
+
+    /// Error while decoding a validator's public key from hex format.
+    #[error("decoding public key from hex error: {0}")]
+    DecodingPublicKey(String),
+
+    /// Error while decoding a validator's weight from a decimal format.
+    #[error("decoding weight from base-10 error: {0}")]
+    DecodingWeight(#[from] FromDecStrErr),
+
+    /// A validator has a weight of zero.
+    #[error("validator {0} has zero weight")]
+    ZeroWeight(String),
+
+    /// A validator is listed more than once.
+    #[error("validator {0} is listed more than once")]
+    DuplicateValidator(String),
+
+    /// The validator set is empty.
+    #[error("no validators given")]
+    NoValidators,
+}
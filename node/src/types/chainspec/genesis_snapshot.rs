@@ -0,0 +1,96 @@
+use std::path::{Path, PathBuf};
+
+use datasize::DataSize;
+#[cfg(test)]
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use casper_types::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    EraId,
+};
+
+use crate::crypto::hash::Digest;
+#[cfg(test)]
+use crate::testing::TestRng;
+
+/// The name of the file containing the snapshot entries.  It must live in the same directory as
+/// chainspec.toml.
+pub(crate) const GENESIS_SNAPSHOT_FILENAME: &str = "genesis_snapshot.bin";
+
+/// A previously exported global state from which the network is initialized instead of running
+/// the genesis installer, e.g. to fork an existing network or to rehearse a migration.
+///
+/// The chain starts in the given era and at the given height, and the validators of that era are
+/// read from the auction state contained in the snapshot.
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, DataSize, Debug)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct GenesisSnapshot {
+    /// The expected root hash of the global state after importing the snapshot.
+    pub(crate) state_root_hash: Digest,
+    /// The era in which the chain starts.
+    pub(crate) era_id: EraId,
+    /// The height of the chain's first block.
+    pub(crate) height: u64,
+}
+
+impl GenesisSnapshot {
+    /// Returns the path of the snapshot file in the given chainspec directory.
+    pub(crate) fn path<P: AsRef<Path>>(chainspec_dir: P) -> PathBuf {
+        chainspec_dir.as_ref().join(GENESIS_SNAPSHOT_FILENAME)
+    }
+}
+
+#[cfg(test)]
+impl GenesisSnapshot {
+    pub(crate) fn random(rng: &mut TestRng) -> Self {
+        GenesisSnapshot {
+            state_root_hash: Digest::random(rng),
+            era_id: EraId::new(rng.gen_range(0..u32::MAX as u64)),
+            height: rng.gen(),
+        }
+    }
+}
+
+impl ToBytes for GenesisSnapshot {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        buffer.extend(self.state_root_hash.to_bytes()?);
+        buffer.extend(self.era_id.to_bytes()?);
+        buffer.extend(self.height.to_bytes()?);
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.state_root_hash.serialized_length()
+            + self.era_id.serialized_length()
+            + self.height.serialized_length()
+    }
+}
+
+impl FromBytes for GenesisSnapshot {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (state_root_hash, remainder) = Digest::from_bytes(bytes)?;
+        let (era_id, remainder) = EraId::from_bytes(remainder)?;
+        let (height, remainder) = u64::from_bytes(remainder)?;
+        let genesis_snapshot = GenesisSnapshot {
+            state_root_hash,
+            era_id,
+            height,
+        };
+        Ok((genesis_snapshot, remainder))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn genesis_snapshot_bytesrepr_roundtrip() {
+        let mut rng = crate::new_rng();
+        let genesis_snapshot = GenesisSnapshot::random(&mut rng);
+        bytesrepr::test_serialization_roundtrip(&genesis_snapshot);
+    }
+}
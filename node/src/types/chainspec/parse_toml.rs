@@ -14,9 +14,10 @@ use casper_execution_engine::shared::{system_config::SystemConfig, wasm_config::
 use casper_types::{EraId, ProtocolVersion};
 
 use super::{
-    accounts_config::AccountsConfig, global_state_update::GlobalStateUpdateConfig, ActivationPoint,
-    Chainspec, CoreConfig, DeployConfig, Error, GlobalStateUpdate, HighwayConfig, NetworkConfig,
-    ProtocolConfig,
+    accounts_config::AccountsConfig, global_state_update::GlobalStateUpdateConfig,
+    validators_override::ValidatorsOverrideConfig, ActivationPoint, BannedValidators, Chainspec,
+    CoreConfig, DeployConfig, Error, GenesisSnapshot, GlobalStateUpdate, HighwayConfig,
+    NetworkConfig, ProtocolConfig, ValidatorsOverride, GENESIS_SNAPSHOT_FILENAME,
 };
 use crate::utils::{self, Loadable};
 
@@ -36,6 +37,10 @@ struct TomlProtocol {
     hard_reset: bool,
     activation_point: ActivationPoint,
     last_emergency_restart: Option<EraId>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    banned_validators: Vec<BannedValidators>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    genesis_snapshot: Option<GenesisSnapshot>,
 }
 
 /// A chainspec configuration as laid out in the TOML-encoded configuration file.
@@ -59,6 +64,8 @@ impl From<&Chainspec> for TomlChainspec {
             hard_reset: chainspec.protocol_config.hard_reset,
             activation_point: chainspec.protocol_config.activation_point,
             last_emergency_restart: chainspec.protocol_config.last_emergency_restart,
+            banned_validators: chainspec.protocol_config.banned_validators.clone(),
+            genesis_snapshot: chainspec.protocol_config.genesis_snapshot,
         };
         let network = TomlNetwork {
             name: chainspec.network_config.name.clone(),
@@ -110,7 +117,31 @@ pub(super) fn parse_toml<P: AsRef<Path>>(chainspec_path: P) -> Result<Chainspec,
         activation_point: toml_chainspec.protocol.activation_point,
         global_state_update,
         last_emergency_restart: toml_chainspec.protocol.last_emergency_restart,
+        validators_override: Option::<ValidatorsOverrideConfig>::from_path(root)?
+            .map(ValidatorsOverride::try_from)
+            .transpose()?,
+        banned_validators: toml_chainspec.protocol.banned_validators,
+        genesis_snapshot: toml_chainspec.protocol.genesis_snapshot,
     };
+    if protocol_config.validators_override.is_some() && !protocol_config.is_emergency_restart() {
+        return Err(Error::ValidatorsOverrideWithoutEmergencyRestart);
+    }
+    if protocol_config.genesis_snapshot.is_some() {
+        if !protocol_config.activation_point.is_genesis() {
+            return Err(Error::GenesisSnapshotAfterGenesis);
+        }
+        if !root.join(GENESIS_SNAPSHOT_FILENAME).is_file() {
+            return Err(Error::MissingGenesisSnapshotFile);
+        }
+    }
+    let activation_era_id = protocol_config.activation_era_id();
+    if let Some(banned) = protocol_config
+        .banned_validators
+        .iter()
+        .find(|banned| banned.era_id < activation_era_id)
+    {
+        return Err(Error::BannedValidatorsBeforeActivationPoint(banned.era_id));
+    }
 
     Ok(Chainspec {
         protocol_config,
@@ -13,7 +13,9 @@ use casper_types::{
     EraId, ProtocolVersion,
 };
 
-use super::{ActivationPoint, GlobalStateUpdate};
+use super::{
+    ActivationPoint, BannedValidators, GenesisSnapshot, GlobalStateUpdate, ValidatorsOverride,
+};
 #[cfg(test)]
 use crate::testing::TestRng;
 
@@ -31,6 +33,46 @@ pub struct ProtocolConfig {
     pub(crate) global_state_update: Option<GlobalStateUpdate>,
     /// The era ID in which the last emergency restart happened.
     pub(crate) last_emergency_restart: Option<EraId>,
+    /// The validator set replacing the current one, if this activation point is an emergency
+    /// restart.
+    pub(crate) validators_override: Option<ValidatorsOverride>,
+    /// Validators that are banned in particular eras, in addition to the ones slashed for
+    /// equivocating.
+    #[serde(default)]
+    pub(crate) banned_validators: Vec<BannedValidators>,
+    /// The state snapshot the network is initialized from instead of running the genesis
+    /// installer, if any.
+    #[serde(default)]
+    pub(crate) genesis_snapshot: Option<GenesisSnapshot>,
+}
+
+impl ProtocolConfig {
+    /// Returns `true` if the activation point is an emergency restart.
+    pub(crate) fn is_emergency_restart(&self) -> bool {
+        self.last_emergency_restart == Some(self.activation_point.era_id())
+    }
+
+    /// Returns the first era to which this protocol config applies: the activation point's era,
+    /// or the genesis snapshot's era if the network is initialized from one.
+    pub(crate) fn activation_era_id(&self) -> EraId {
+        self.genesis_snapshot.map_or_else(
+            || self.activation_point.era_id(),
+            |snapshot| snapshot.era_id,
+        )
+    }
+
+    /// Returns the era in which the chain starts if the network is initialized with this protocol
+    /// config: the genesis snapshot's era, or era 0.
+    pub(crate) fn genesis_era_id(&self) -> EraId {
+        self.genesis_snapshot
+            .map_or_else(|| EraId::new(0), |snapshot| snapshot.era_id)
+    }
+
+    /// Returns the height of the first block if the network is initialized with this protocol
+    /// config: the genesis snapshot's height, or 0.
+    pub(crate) fn genesis_height(&self) -> u64 {
+        self.genesis_snapshot.map_or(0, |snapshot| snapshot.height)
+    }
 }
 
 #[cfg(test)]
@@ -44,6 +86,10 @@ impl ProtocolConfig {
         );
         let activation_point = ActivationPoint::random(rng);
         let last_emergency_restart = rng.gen::<bool>().then(|| rng.gen());
+        let banned_validators = (0..rng.gen_range(0..3))
+            .map(|_| BannedValidators::random(rng))
+            .collect();
+        let genesis_snapshot = rng.gen::<bool>().then(|| GenesisSnapshot::random(rng));
 
         ProtocolConfig {
             version: protocol_version,
@@ -51,6 +97,9 @@ impl ProtocolConfig {
             activation_point,
             global_state_update: None,
             last_emergency_restart,
+            validators_override: None,
+            banned_validators,
+            genesis_snapshot,
         }
     }
 }
@@ -63,6 +112,9 @@ impl ToBytes for ProtocolConfig {
         buffer.extend(self.activation_point.to_bytes()?);
         buffer.extend(self.global_state_update.to_bytes()?);
         buffer.extend(self.last_emergency_restart.to_bytes()?);
+        buffer.extend(self.validators_override.to_bytes()?);
+        buffer.extend(self.banned_validators.to_bytes()?);
+        buffer.extend(self.genesis_snapshot.to_bytes()?);
         Ok(buffer)
     }
 
@@ -72,6 +124,9 @@ impl ToBytes for ProtocolConfig {
             + self.activation_point.serialized_length()
             + self.global_state_update.serialized_length()
             + self.last_emergency_restart.serialized_length()
+            + self.validators_override.serialized_length()
+            + self.banned_validators.serialized_length()
+            + self.genesis_snapshot.serialized_length()
     }
 }
 
@@ -84,12 +139,18 @@ impl FromBytes for ProtocolConfig {
         let (activation_point, remainder) = ActivationPoint::from_bytes(remainder)?;
         let (global_state_update, remainder) = Option::<GlobalStateUpdate>::from_bytes(remainder)?;
         let (last_emergency_restart, remainder) = Option::<EraId>::from_bytes(remainder)?;
+        let (validators_override, remainder) = Option::<ValidatorsOverride>::from_bytes(remainder)?;
+        let (banned_validators, remainder) = Vec::<BannedValidators>::from_bytes(remainder)?;
+        let (genesis_snapshot, remainder) = Option::<GenesisSnapshot>::from_bytes(remainder)?;
         let protocol_config = ProtocolConfig {
             version,
             hard_reset,
             activation_point,
             global_state_update,
             last_emergency_restart,
+            validators_override,
+            banned_validators,
+            genesis_snapshot,
         };
         Ok((protocol_config, remainder))
     }
@@ -98,6 +159,7 @@ impl FromBytes for ProtocolConfig {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::Timestamp;
 
     #[test]
     fn activation_point_bytesrepr_roundtrip() {
@@ -113,6 +175,23 @@ mod tests {
         bytesrepr::test_serialization_roundtrip(&config);
     }
 
+    #[test]
+    fn genesis_snapshot_determines_start_of_chain() {
+        let mut rng = crate::new_rng();
+        let mut config = ProtocolConfig::random(&mut rng);
+        config.activation_point = ActivationPoint::Genesis(Timestamp::from(1000));
+        config.genesis_snapshot = None;
+        assert_eq!(config.activation_era_id(), EraId::new(0));
+        assert_eq!(config.genesis_era_id(), EraId::new(0));
+        assert_eq!(config.genesis_height(), 0);
+
+        let genesis_snapshot = GenesisSnapshot::random(&mut rng);
+        config.genesis_snapshot = Some(genesis_snapshot);
+        assert_eq!(config.activation_era_id(), genesis_snapshot.era_id);
+        assert_eq!(config.genesis_era_id(), genesis_snapshot.era_id);
+        assert_eq!(config.genesis_height(), genesis_snapshot.height);
+    }
+
     #[test]
     fn toml_roundtrip() {
         let mut rng = crate::new_rng();
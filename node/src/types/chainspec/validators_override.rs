@@ -0,0 +1,159 @@
+use std::{collections::BTreeMap, convert::TryFrom, path::Path};
+
+use datasize::DataSize;
+#[cfg(test)]
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use casper_types::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    AsymmetricType, PublicKey, U512,
+};
+
+use super::error::ValidatorsOverrideLoadError;
+
+use crate::utils::{self, Loadable};
+#[cfg(test)]
+use crate::{crypto::AsymmetricKeyExt, testing::TestRng};
+
+const VALIDATORS_OVERRIDE_FILENAME: &str = "validators.toml";
+
+#[derive(PartialEq, Eq, Serialize, Deserialize, DataSize, Debug, Clone)]
+pub struct ValidatorsOverrideEntry {
+    public_key: String,
+    weight: String,
+}
+
+#[derive(PartialEq, Eq, Serialize, Deserialize, DataSize, Debug, Clone)]
+pub struct ValidatorsOverrideConfig {
+    validators: Vec<ValidatorsOverrideEntry>,
+}
+
+impl Loadable for Option<ValidatorsOverrideConfig> {
+    type Error = ValidatorsOverrideLoadError;
+
+    fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, Self::Error> {
+        let override_path = path.as_ref().join(VALIDATORS_OVERRIDE_FILENAME);
+        if !override_path.is_file() {
+            return Ok(None);
+        }
+        let bytes = utils::read_file(override_path)?;
+        let toml_override: ValidatorsOverrideConfig = toml::from_slice(&bytes)?;
+        Ok(Some(toml_override))
+    }
+}
+
+/// The validator set replacing the current one in an emergency restart, with their weights.
+///
+/// It is used from the activation point until the auction delay has passed, after which the
+/// validators are selected by the auction again.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, DataSize, Debug)]
+pub struct ValidatorsOverride(pub(crate) BTreeMap<PublicKey, U512>);
+
+impl ToBytes for ValidatorsOverride {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        self.0.to_bytes()
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.0.serialized_length()
+    }
+}
+
+#[cfg(test)]
+impl ValidatorsOverride {
+    pub(crate) fn random(rng: &mut TestRng) -> Self {
+        let count = rng.gen_range(1..10);
+        let validators = (0..count)
+            .map(|_| {
+                (
+                    PublicKey::random(rng),
+                    U512::from(rng.gen_range(1..u64::MAX)),
+                )
+            })
+            .collect();
+        ValidatorsOverride(validators)
+    }
+}
+
+impl FromBytes for ValidatorsOverride {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (validators, remainder) = BTreeMap::<PublicKey, U512>::from_bytes(bytes)?;
+        Ok((ValidatorsOverride(validators), remainder))
+    }
+}
+
+impl TryFrom<ValidatorsOverrideConfig> for ValidatorsOverride {
+    type Error = ValidatorsOverrideLoadError;
+
+    fn try_from(config: ValidatorsOverrideConfig) -> Result<Self, Self::Error> {
+        let mut validators = BTreeMap::new();
+        for entry in config.validators.into_iter() {
+            let public_key = PublicKey::from_hex(&entry.public_key).map_err(|error| {
+                ValidatorsOverrideLoadError::DecodingPublicKey(format!("{}", error))
+            })?;
+            let weight = U512::from_dec_str(&entry.weight)?;
+            if weight.is_zero() {
+                return Err(ValidatorsOverrideLoadError::ZeroWeight(entry.public_key));
+            }
+            if validators.insert(public_key, weight).is_some() {
+                return Err(ValidatorsOverrideLoadError::DuplicateValidator(
+                    entry.public_key,
+                ));
+            }
+        }
+        if validators.is_empty() {
+            return Err(ValidatorsOverrideLoadError::NoValidators);
+        }
+        Ok(ValidatorsOverride(validators))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validators_override_bytesrepr_roundtrip() {
+        let mut rng = crate::new_rng();
+        let validators_override = ValidatorsOverride::random(&mut rng);
+        bytesrepr::test_serialization_roundtrip(&validators_override);
+    }
+
+    #[test]
+    fn should_reject_invalid_validators_override() {
+        let mut rng = crate::new_rng();
+        let public_key = PublicKey::random(&mut rng).to_hex();
+        let entry = |weight: &str| ValidatorsOverrideEntry {
+            public_key: public_key.clone(),
+            weight: weight.to_string(),
+        };
+
+        let valid = ValidatorsOverrideConfig {
+            validators: vec![entry("10")],
+        };
+        assert!(ValidatorsOverride::try_from(valid).is_ok());
+
+        let empty = ValidatorsOverrideConfig { validators: vec![] };
+        assert!(matches!(
+            ValidatorsOverride::try_from(empty),
+            Err(ValidatorsOverrideLoadError::NoValidators)
+        ));
+
+        let zero_weight = ValidatorsOverrideConfig {
+            validators: vec![entry("0")],
+        };
+        assert!(matches!(
+            ValidatorsOverride::try_from(zero_weight),
+            Err(ValidatorsOverrideLoadError::ZeroWeight(_))
+        ));
+
+        let duplicate = ValidatorsOverrideConfig {
+            validators: vec![entry("10"), entry("20")],
+        };
+        assert!(matches!(
+            ValidatorsOverride::try_from(duplicate),
+            Err(ValidatorsOverrideLoadError::DuplicateValidator(_))
+        ));
+    }
+}
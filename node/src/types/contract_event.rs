@@ -0,0 +1,45 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use casper_execution_engine::core::engine_state::ContractEvent;
+use casper_types::{bytesrepr::Bytes, ContractHash};
+
+use super::{BlockHash, DeployHash};
+
+/// An event emitted by a stored contract, together with the block and deploy it was emitted in.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ContractEventRecord {
+    /// The hash of the block containing the deploy which emitted the event.
+    pub block_hash: BlockHash,
+    /// The height of the block containing the deploy which emitted the event.
+    pub block_height: u64,
+    /// The hash of the deploy which emitted the event.
+    pub deploy_hash: DeployHash,
+    /// The hash of the contract which emitted the event.
+    pub contract_hash: ContractHash,
+    /// The topic of the event.
+    pub topic: String,
+    /// The hex-encoded payload of the event.
+    #[schemars(with = "String")]
+    pub data: Bytes,
+}
+
+impl ContractEventRecord {
+    /// Creates a record of `event`, emitted by the given deploy in the given block.
+    pub(crate) fn new(
+        block_hash: BlockHash,
+        block_height: u64,
+        deploy_hash: DeployHash,
+        event: ContractEvent,
+    ) -> Self {
+        ContractEventRecord {
+            block_hash,
+            block_height,
+            deploy_hash,
+            contract_hash: event.contract_hash,
+            topic: event.topic,
+            data: event.data,
+        }
+    }
+}
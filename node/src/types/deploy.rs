@@ -25,10 +25,11 @@ use casper_execution_engine::{
     shared::motes::Motes,
 };
 use casper_types::{
-    bytesrepr::{self, FromBytes, ToBytes},
+    account::AccountHash,
+    bytesrepr::{self, Bytes, FromBytes, ToBytes},
     runtime_args,
-    system::standard_payment::ARG_AMOUNT,
-    AsymmetricType, ExecutionResult, PublicKey, RuntimeArgs, SecretKey, Signature, U512,
+    system::{mint, standard_payment::ARG_AMOUNT},
+    AsymmetricType, EraId, ExecutionResult, PublicKey, RuntimeArgs, SecretKey, Signature, U512,
 };
 
 use super::{BlockHash, Item, Tag, TimeDiff, Timestamp};
@@ -42,7 +43,7 @@ use crate::{
         AsymmetricKeyExt,
     },
     rpcs::docs::DocExample,
-    types::chainspec::DeployConfig,
+    types::chainspec::{DeployConfig, SizeClass},
     utils::DisplayIter,
 };
 
@@ -71,6 +72,8 @@ static DEPLOY: Lazy<Deploy> = Lazy::new(|| {
         body_hash,
         dependencies: vec![DeployHash::new(Digest::from([1u8; Digest::LENGTH]))],
         chain_name: String::from("casper-example"),
+        valid_from_height: None,
+        valid_until_era: None,
     };
     let serialized_header = serialize_header(&header);
     let hash = DeployHash::new(hash::hash(&serialized_header));
@@ -130,6 +133,24 @@ pub enum DeployValidationFailure {
         got: TimeDiff,
     },
 
+    /// The deploy's validity window closed before the current era.
+    #[error("deploy was only valid until era {valid_until_era}, current era is {current_era}")]
+    ValidityWindowClosed {
+        /// The last era in which the deploy may be included in a block.
+        valid_until_era: EraId,
+        /// The current era.
+        current_era: EraId,
+    },
+
+    /// Gas price below the chain's minimum.
+    #[error("gas price of {got} is below the minimum of {min_gas_price}")]
+    GasPriceTooLow {
+        /// The minimum gas price.
+        min_gas_price: u64,
+        /// The received gas price.
+        got: u64,
+    },
+
     /// The provided body hash does not match the actual hash of the body.
     #[error("the provided body hash does not match the actual hash of the body")]
     InvalidBodyHash,
@@ -181,6 +202,37 @@ pub enum DeployValidationFailure {
         /// The attempted transfer amount.
         attempted: U512,
     },
+
+    /// Missing payment amount for the standard payment code.
+    #[error("missing standard payment amount")]
+    MissingPaymentAmount,
+
+    /// Invalid payment amount for the standard payment code.
+    #[error("invalid standard payment amount")]
+    InvalidPaymentAmount,
+
+    /// Insufficient payment amount for the standard payment code.
+    #[error("insufficient standard payment amount; minimum: {minimum} attempted: {attempted}")]
+    InsufficientPaymentAmount {
+        /// The minimum payment amount.
+        minimum: U512,
+        /// The attempted payment amount.
+        attempted: U512,
+    },
+
+    /// Insufficient payment amount for the size class of the deploy.
+    #[error(
+        "insufficient payment amount for {size_class} deploy; minimum: {minimum} attempted: \
+         {attempted}"
+    )]
+    InsufficientPaymentForSizeClass {
+        /// The size class of the deploy.
+        size_class: SizeClass,
+        /// The minimum payment amount for the size class.
+        minimum: U512,
+        /// The attempted payment amount.
+        attempted: U512,
+    },
 }
 
 /// Error returned when a Deploy is too large.
@@ -307,6 +359,10 @@ pub struct DeployHeader {
     body_hash: Digest,
     dependencies: Vec<DeployHash>,
     chain_name: String,
+    #[serde(default)]
+    valid_from_height: Option<u64>,
+    #[serde(default)]
+    valid_until_era: Option<EraId>,
 }
 
 impl DeployHeader {
@@ -351,6 +407,27 @@ impl DeployHeader {
         &self.chain_name
     }
 
+    /// The lowest block height at which the deploy may be included, if restricted.
+    pub fn valid_from_height(&self) -> Option<u64> {
+        self.valid_from_height
+    }
+
+    /// The last era in which the deploy may be included, if restricted.
+    pub fn valid_until_era(&self) -> Option<EraId> {
+        self.valid_until_era
+    }
+
+    /// Returns `true` if the deploy may be included in a block of the given height and era.
+    pub fn is_within_validity_window(&self, height: u64, era_id: EraId) -> bool {
+        let height_reached = self
+            .valid_from_height
+            .map_or(true, |valid_from_height| valid_from_height <= height);
+        let era_not_passed = self
+            .valid_until_era
+            .map_or(true, |valid_until_era| era_id <= valid_until_era);
+        height_reached && era_not_passed
+    }
+
     /// Determine if this deploy header has valid values based on a `DeployConfig` and timestamp.
     pub fn is_valid(&self, deploy_config: &DeployConfig, current_timestamp: Timestamp) -> bool {
         let ttl_valid = self.ttl() <= deploy_config.max_ttl;
@@ -378,6 +455,8 @@ impl ToBytes for DeployHeader {
         buffer.extend(self.body_hash.to_bytes()?);
         buffer.extend(self.dependencies.to_bytes()?);
         buffer.extend(self.chain_name.to_bytes()?);
+        buffer.extend(self.valid_from_height.to_bytes()?);
+        buffer.extend(self.valid_until_era.to_bytes()?);
         Ok(buffer)
     }
 
@@ -389,6 +468,8 @@ impl ToBytes for DeployHeader {
             + self.body_hash.serialized_length()
             + self.dependencies.serialized_length()
             + self.chain_name.serialized_length()
+            + self.valid_from_height.serialized_length()
+            + self.valid_until_era.serialized_length()
     }
 }
 
@@ -401,6 +482,8 @@ impl FromBytes for DeployHeader {
         let (body_hash, remainder) = Digest::from_bytes(remainder)?;
         let (dependencies, remainder) = Vec::<DeployHash>::from_bytes(remainder)?;
         let (chain_name, remainder) = String::from_bytes(remainder)?;
+        let (valid_from_height, remainder) = Option::<u64>::from_bytes(remainder)?;
+        let (valid_until_era, remainder) = Option::<EraId>::from_bytes(remainder)?;
         let deploy_header = DeployHeader {
             account,
             timestamp,
@@ -409,6 +492,8 @@ impl FromBytes for DeployHeader {
             body_hash,
             dependencies,
             chain_name,
+            valid_from_height,
+            valid_until_era,
         };
         Ok((deploy_header, remainder))
     }
@@ -418,7 +503,7 @@ impl Display for DeployHeader {
     fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
         write!(
             formatter,
-            "deploy-header[account: {}, timestamp: {}, ttl: {}, gas_price: {}, body_hash: {}, dependencies: [{}], chain_name: {}]",
+            "deploy-header[account: {}, timestamp: {}, ttl: {}, gas_price: {}, body_hash: {}, dependencies: [{}], chain_name: {}, valid_from_height: {}, valid_until_era: {}]",
             self.account,
             self.timestamp,
             self.ttl,
@@ -426,10 +511,54 @@ impl Display for DeployHeader {
             self.body_hash,
             DisplayIter::new(self.dependencies.iter()),
             self.chain_name,
+            self.valid_from_height
+                .map_or_else(|| "none".to_string(), |height| height.to_string()),
+            self.valid_until_era
+                .map_or_else(|| "none".to_string(), |era_id| era_id.to_string()),
         )
     }
 }
 
+/// The key by which deploys are sorted into their canonical order within a block.
+///
+/// Deploys are ordered by the hash of the account which sent them, then by timestamp, then by
+/// deploy hash. A block's deploy hashes and transfer hashes are each ordered separately, and the
+/// deploys are executed in this order, deploys first.
+#[derive(Clone, Copy, DataSize, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct DeployOrderKey {
+    account_hash: AccountHash,
+    timestamp: Timestamp,
+    deploy_hash: DeployHash,
+}
+
+impl DeployOrderKey {
+    /// Creates the key for the deploy with the given hash and header.
+    pub(crate) fn new(deploy_hash: DeployHash, header: &DeployHeader) -> Self {
+        DeployOrderKey {
+            account_hash: header.account().to_account_hash(),
+            timestamp: header.timestamp(),
+            deploy_hash,
+        }
+    }
+
+    /// Returns the hash of the deploy.
+    pub(crate) fn deploy_hash(&self) -> &DeployHash {
+        &self.deploy_hash
+    }
+}
+
+/// Returns `true` if the given deploys are in canonical order, as defined by [`DeployOrderKey`].
+pub(crate) fn is_in_canonical_order<'a, I>(deploys: I) -> bool
+where
+    I: IntoIterator<Item = &'a Deploy>,
+{
+    deploys
+        .into_iter()
+        .map(Deploy::order_key)
+        .tuple_windows()
+        .all(|(key, next_key)| key < next_key)
+}
+
 /// A struct containing a signature and the public key of the signer.
 #[derive(
     Clone, DataSize, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize, Debug, JsonSchema,
@@ -507,6 +636,35 @@ impl Deploy {
         payment: ExecutableDeployItem,
         session: ExecutableDeployItem,
         secret_key: &SecretKey,
+    ) -> Deploy {
+        Deploy::new_with_validity_window(
+            timestamp,
+            ttl,
+            gas_price,
+            dependencies,
+            chain_name,
+            None,
+            None,
+            payment,
+            session,
+            secret_key,
+        )
+    }
+
+    /// Constructs a new signed `Deploy` which may only be included in blocks at or above
+    /// `valid_from_height` and in eras up to and including `valid_until_era`, if given.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_with_validity_window(
+        timestamp: Timestamp,
+        ttl: TimeDiff,
+        gas_price: u64,
+        dependencies: Vec<DeployHash>,
+        chain_name: String,
+        valid_from_height: Option<u64>,
+        valid_until_era: Option<EraId>,
+        payment: ExecutableDeployItem,
+        session: ExecutableDeployItem,
+        secret_key: &SecretKey,
     ) -> Deploy {
         let serialized_body = serialize_body(&payment, &session);
         let body_hash = hash::hash(&serialized_body);
@@ -522,6 +680,8 @@ impl Deploy {
             body_hash,
             dependencies,
             chain_name,
+            valid_from_height,
+            valid_until_era,
         };
         let serialized_header = serialize_header(&header);
         let hash = DeployHash::new(hash::hash(&serialized_header));
@@ -539,6 +699,46 @@ impl Deploy {
         deploy
     }
 
+    /// Constructs a new signed wasmless transfer `Deploy`, moving `amount` motes from the main
+    /// purse of the account of `secret_key` to the account `target`.
+    ///
+    /// The payment is the standard payment of `payment_amount` motes.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_transfer(
+        amount: U512,
+        target: AccountHash,
+        id: Option<u64>,
+        payment_amount: U512,
+        timestamp: Timestamp,
+        ttl: TimeDiff,
+        gas_price: u64,
+        dependencies: Vec<DeployHash>,
+        chain_name: String,
+        secret_key: &SecretKey,
+    ) -> Deploy {
+        let payment = ExecutableDeployItem::ModuleBytes {
+            module_bytes: Bytes::new(),
+            args: runtime_args! { ARG_AMOUNT => payment_amount },
+        };
+        let session = ExecutableDeployItem::Transfer {
+            args: runtime_args! {
+                mint::ARG_AMOUNT => amount,
+                mint::ARG_TARGET => target,
+                mint::ARG_ID => id
+            },
+        };
+        Deploy::new(
+            timestamp,
+            ttl,
+            gas_price,
+            dependencies,
+            chain_name,
+            payment,
+            session,
+            secret_key,
+        )
+    }
+
     /// Adds a signature of this deploy's hash to its approvals.
     pub fn sign(&mut self, secret_key: &SecretKey) {
         let signer = PublicKey::from(secret_key);
@@ -547,11 +747,28 @@ impl Deploy {
         self.approvals.push(approval);
     }
 
+    /// Adds the given approvals, skipping any which are already present.
+    ///
+    /// The new approvals are not verified here, so the deploy should be validated again afterwards.
+    pub(crate) fn add_approvals<I: IntoIterator<Item = Approval>>(&mut self, approvals: I) {
+        for approval in approvals {
+            if !self.approvals.contains(&approval) {
+                self.approvals.push(approval);
+            }
+        }
+        self.is_valid = None;
+    }
+
     /// Returns the `DeployHash` identifying this `Deploy`.
     pub fn id(&self) -> &DeployHash {
         &self.hash
     }
 
+    /// Returns the key defining this deploy's position in the canonical order within a block.
+    pub fn order_key(&self) -> DeployOrderKey {
+        DeployOrderKey::new(self.hash, &self.header)
+    }
+
     /// Returns a reference to the `DeployHeader` of this `Deploy`.
     pub fn header(&self) -> &DeployHeader {
         &self.header
@@ -694,6 +911,19 @@ impl Deploy {
             });
         }
 
+        if header.gas_price() < config.min_gas_price {
+            info!(
+                deploy_hash = %self.id(),
+                deploy_header = %header,
+                min_gas_price = %config.min_gas_price,
+                "deploy gas price too low"
+            );
+            return Err(DeployValidationFailure::GasPriceTooLow {
+                min_gas_price: config.min_gas_price,
+                got: header.gas_price(),
+            });
+        }
+
         let payment_args_length = self.payment().args().serialized_length();
         if payment_args_length > config.payment_args_max_length as usize {
             info!(
@@ -720,6 +950,48 @@ impl Deploy {
             });
         }
 
+        if self.payment().is_standard_payment() {
+            let attempted = self
+                .payment()
+                .args()
+                .get(ARG_AMOUNT)
+                .ok_or(DeployValidationFailure::MissingPaymentAmount)?
+                .clone()
+                .into_t::<U512>()
+                .map_err(|_| DeployValidationFailure::InvalidPaymentAmount)?;
+            let minimum = U512::from(config.standard_payment_minimum_motes);
+            if attempted < minimum {
+                info!(%attempted, %minimum, "standard payment amount insufficient");
+                return Err(DeployValidationFailure::InsufficientPaymentAmount {
+                    minimum,
+                    attempted,
+                });
+            }
+        }
+
+        if !self.session().is_transfer() {
+            // Block proposers only include deploys paying at least the minimum of their size
+            // class.  The deploy size has been checked against `max_deploy_size` above, which the
+            // large size class covers.
+            let attempted = self
+                .payment()
+                .args()
+                .get(ARG_AMOUNT)
+                .and_then(|value| value.clone().into_t::<U512>().ok());
+            let size_class = config.size_classes.classify(self.serialized_length());
+            if let (Some(attempted), Some(size_class)) = (attempted, size_class) {
+                let minimum = U512::from(config.size_classes.get(size_class).minimum_payment_motes);
+                if attempted < minimum {
+                    info!(%attempted, %minimum, %size_class, "payment amount insufficient");
+                    return Err(DeployValidationFailure::InsufficientPaymentForSizeClass {
+                        size_class,
+                        minimum,
+                        attempted,
+                    });
+                }
+            }
+        }
+
         if self.session().is_transfer() {
             let item = self.session().clone();
             let attempted = item
@@ -779,10 +1051,20 @@ impl DocExample for Deploy {
     }
 }
 
+// Serialize the deploy header for hashing.
+//
+// Headers without a validity window are serialized exactly as before the window was introduced, so
+// that the hashes of existing deploys and of deploys created by older clients don't change.
 fn serialize_header(header: &DeployHeader) -> Vec<u8> {
-    header
+    let mut buffer = header
         .to_bytes()
-        .unwrap_or_else(|error| panic!("should serialize deploy header: {}", error))
+        .unwrap_or_else(|error| panic!("should serialize deploy header: {}", error));
+    if header.valid_from_height.is_none() && header.valid_until_era.is_none() {
+        let none_length = header.valid_from_height.serialized_length()
+            + header.valid_until_era.serialized_length();
+        buffer.truncate(buffer.len() - none_length);
+    }
+    buffer
 }
 
 fn serialize_body(payment: &ExecutableDeployItem, session: &ExecutableDeployItem) -> Vec<u8> {
@@ -871,6 +1153,7 @@ impl From<Deploy> for DeployItem {
             deploy.header().gas_price(),
             authorization_keys,
             casper_types::DeployHash::new(deploy.id().inner().to_array()),
+            deploy.header().chain_name().to_string(),
         )
     }
 }
@@ -925,12 +1208,91 @@ impl FromBytes for Deploy {
     }
 }
 
+/// The encoding of a [`Deploy`] as stored before deploys had a validity window.
+///
+/// Only used to migrate deploys persisted by earlier versions of the node.
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+pub(crate) struct LegacyDeploy {
+    hash: DeployHash,
+    header: LegacyDeployHeader,
+    payment: ExecutableDeployItem,
+    session: ExecutableDeployItem,
+    approvals: Vec<Approval>,
+}
+
+/// The encoding of a [`DeployHeader`] as stored before deploys had a validity window.
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct LegacyDeployHeader {
+    account: PublicKey,
+    timestamp: Timestamp,
+    ttl: TimeDiff,
+    gas_price: u64,
+    body_hash: Digest,
+    dependencies: Vec<DeployHash>,
+    chain_name: String,
+}
+
+impl From<LegacyDeploy> for Deploy {
+    fn from(legacy_deploy: LegacyDeploy) -> Self {
+        let LegacyDeploy {
+            hash,
+            header,
+            payment,
+            session,
+            approvals,
+        } = legacy_deploy;
+        let header = DeployHeader {
+            account: header.account,
+            timestamp: header.timestamp,
+            ttl: header.ttl,
+            gas_price: header.gas_price,
+            body_hash: header.body_hash,
+            dependencies: header.dependencies,
+            chain_name: header.chain_name,
+            valid_from_height: None,
+            valid_until_era: None,
+        };
+        Deploy {
+            hash,
+            header,
+            payment,
+            session,
+            approvals,
+            is_valid: None,
+        }
+    }
+}
+
+#[cfg(test)]
+impl From<&Deploy> for LegacyDeploy {
+    fn from(deploy: &Deploy) -> Self {
+        let header = LegacyDeployHeader {
+            account: deploy.header.account.clone(),
+            timestamp: deploy.header.timestamp,
+            ttl: deploy.header.ttl,
+            gas_price: deploy.header.gas_price,
+            body_hash: deploy.header.body_hash,
+            dependencies: deploy.header.dependencies.clone(),
+            chain_name: deploy.header.chain_name.clone(),
+        };
+        LegacyDeploy {
+            hash: deploy.hash,
+            header,
+            payment: deploy.payment.clone(),
+            session: deploy.session.clone(),
+            approvals: deploy.approvals.clone(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{iter, time::Duration};
 
     use casper_execution_engine::core::engine_state::MAX_PAYMENT_AMOUNT;
-    use casper_types::{bytesrepr::Bytes, CLValue};
+    use casper_types::CLValue;
 
     use super::*;
     use crate::crypto::AsymmetricKeyExt;
@@ -964,6 +1326,45 @@ mod tests {
         bytesrepr::test_serialization_roundtrip(&deploy);
     }
 
+    #[test]
+    fn legacy_deploy_should_keep_its_hash() {
+        let mut rng = crate::new_rng();
+        let mut deploy = Deploy::random(&mut rng);
+        let header = deploy.header().clone();
+        assert!(header.valid_from_height().is_none() && header.valid_until_era().is_none());
+
+        let legacy_header = (
+            header.account.clone(),
+            header.timestamp,
+            header.ttl,
+            header.gas_price,
+            header.body_hash,
+            header.dependencies.clone(),
+            header.chain_name.clone(),
+        );
+        let legacy_fields = (
+            deploy.hash,
+            legacy_header,
+            deploy.payment.clone(),
+            deploy.session.clone(),
+            deploy.approvals.clone(),
+        );
+        let legacy_deploy: LegacyDeploy =
+            bincode::deserialize(&bincode::serialize(&legacy_fields).unwrap()).unwrap();
+        assert_eq!(Deploy::from(legacy_deploy), deploy);
+
+        let mut legacy_bytes = vec![];
+        legacy_bytes.extend(header.account.to_bytes().unwrap());
+        legacy_bytes.extend(header.timestamp.to_bytes().unwrap());
+        legacy_bytes.extend(header.ttl.to_bytes().unwrap());
+        legacy_bytes.extend(header.gas_price.to_bytes().unwrap());
+        legacy_bytes.extend(header.body_hash.to_bytes().unwrap());
+        legacy_bytes.extend(header.dependencies.to_bytes().unwrap());
+        legacy_bytes.extend(header.chain_name.to_bytes().unwrap());
+        assert_eq!(*deploy.id(), DeployHash::new(hash::hash(&legacy_bytes)));
+        assert!(deploy.is_valid().is_ok());
+    }
+
     fn create_deploy(
         rng: &mut TestRng,
         ttl: TimeDiff,
@@ -974,12 +1375,12 @@ mod tests {
         let dependencies = iter::repeat_with(|| DeployHash::random(rng))
             .take(dependency_count)
             .collect();
-        let transfer_args = {
-            let mut transfer_args = RuntimeArgs::new();
+        let amount_args = {
+            let mut amount_args = RuntimeArgs::new();
             let value =
                 CLValue::from_t(U512::from(MAX_PAYMENT_AMOUNT)).expect("should create CLValue");
-            transfer_args.insert_cl_value(ARG_AMOUNT, value);
-            transfer_args
+            amount_args.insert_cl_value(ARG_AMOUNT, value);
+            amount_args
         };
         Deploy::new(
             Timestamp::now(),
@@ -989,15 +1390,54 @@ mod tests {
             chain_name.to_string(),
             ExecutableDeployItem::ModuleBytes {
                 module_bytes: Bytes::new(),
-                args: RuntimeArgs::new(),
-            },
-            ExecutableDeployItem::Transfer {
-                args: transfer_args,
+                args: amount_args.clone(),
             },
+            ExecutableDeployItem::Transfer { args: amount_args },
             &secret_key,
         )
     }
 
+    #[test]
+    fn new_transfer_has_transfer_args() {
+        let mut rng = crate::new_rng();
+        let target = AccountHash::new([7; 32]);
+        let mut deploy = Deploy::new_transfer(
+            U512::from(1_000),
+            target,
+            Some(42),
+            U512::from(10),
+            Timestamp::now(),
+            DeployConfig::default().max_ttl,
+            1,
+            vec![],
+            String::from("net-1"),
+            &SecretKey::random(&mut rng),
+        );
+        deploy.is_valid().expect("should be valid");
+        assert!(matches!(
+            deploy.deploy_type(),
+            Ok(DeployType::Transfer { .. })
+        ));
+
+        let args = match deploy.session() {
+            ExecutableDeployItem::Transfer { args } => args,
+            _ => panic!("expected a transfer"),
+        };
+        let get_arg = |name| args.get(name).cloned().expect("should have arg");
+        assert_eq!(
+            get_arg(mint::ARG_AMOUNT).into_t::<U512>().unwrap(),
+            U512::from(1_000)
+        );
+        assert_eq!(
+            get_arg(mint::ARG_TARGET).into_t::<AccountHash>().unwrap(),
+            target
+        );
+        assert_eq!(
+            get_arg(mint::ARG_ID).into_t::<Option<u64>>().unwrap(),
+            Some(42)
+        );
+    }
+
     #[test]
     fn is_valid() {
         let mut rng = crate::new_rng();
@@ -1082,6 +1522,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn should_add_approvals_once_and_revalidate() {
+        let mut rng = crate::new_rng();
+        let mut deploy = create_deploy(&mut rng, DeployConfig::default().max_ttl, 0, "net-1");
+        deploy.is_valid().expect("should be valid");
+
+        let mut cosigned = deploy.clone();
+        cosigned.sign(&SecretKey::random(&mut rng));
+        deploy.add_approvals(cosigned.approvals().to_vec());
+        assert_eq!(deploy.approvals().len(), 2);
+        assert_eq!(deploy.is_valid, None, "is valid should be reset");
+
+        let deploy2 = Deploy::random(&mut rng);
+        deploy.add_approvals(deploy2.approvals().to_vec());
+        check_is_not_valid(
+            deploy,
+            DeployValidationFailure::InvalidApproval {
+                index: 2,
+                error_msg: String::new(), // This field is ignored in the check.
+            },
+        );
+    }
+
     #[test]
     fn is_acceptable() {
         let mut rng = crate::new_rng();
@@ -1099,6 +1562,117 @@ mod tests {
             .expect("should be acceptable");
     }
 
+    #[test]
+    fn not_acceptable_due_to_missing_payment_amount() {
+        let mut rng = crate::new_rng();
+        let chain_name = "net-1";
+        let deploy_config = DeployConfig::default();
+
+        let mut deploy = Deploy::new(
+            Timestamp::now(),
+            deploy_config.max_ttl,
+            1,
+            vec![],
+            chain_name.to_string(),
+            ExecutableDeployItem::ModuleBytes {
+                module_bytes: Bytes::new(),
+                args: RuntimeArgs::new(),
+            },
+            ExecutableDeployItem::ModuleBytes {
+                module_bytes: Bytes::new(),
+                args: RuntimeArgs::new(),
+            },
+            &SecretKey::random(&mut rng),
+        );
+
+        assert_eq!(
+            deploy.is_acceptable(chain_name, &deploy_config),
+            Err(DeployValidationFailure::MissingPaymentAmount)
+        );
+        assert!(
+            deploy.is_valid.is_none(),
+            "deploy should not have run expensive `is_valid` call"
+        );
+    }
+
+    #[test]
+    fn not_acceptable_due_to_insufficient_payment_amount() {
+        let mut rng = crate::new_rng();
+        let chain_name = "net-1";
+        let deploy_config = DeployConfig {
+            standard_payment_minimum_motes: MAX_PAYMENT_AMOUNT + 1,
+            ..Default::default()
+        };
+
+        let mut deploy = create_deploy(
+            &mut rng,
+            deploy_config.max_ttl,
+            deploy_config.max_dependencies.into(),
+            chain_name,
+        );
+
+        let expected_error = DeployValidationFailure::InsufficientPaymentAmount {
+            minimum: U512::from(MAX_PAYMENT_AMOUNT + 1),
+            attempted: U512::from(MAX_PAYMENT_AMOUNT),
+        };
+
+        assert_eq!(
+            deploy.is_acceptable(chain_name, &deploy_config),
+            Err(expected_error)
+        );
+        assert!(
+            deploy.is_valid.is_none(),
+            "deploy should not have run expensive `is_valid` call"
+        );
+    }
+
+    #[test]
+    fn not_acceptable_due_to_insufficient_payment_for_size_class() {
+        let mut rng = crate::new_rng();
+        let chain_name = "net-1";
+        let mut deploy_config = DeployConfig::default();
+        deploy_config.size_classes.small.minimum_payment_motes = MAX_PAYMENT_AMOUNT + 1;
+
+        let amount_args = {
+            let mut amount_args = RuntimeArgs::new();
+            let value =
+                CLValue::from_t(U512::from(MAX_PAYMENT_AMOUNT)).expect("should create CLValue");
+            amount_args.insert_cl_value(ARG_AMOUNT, value);
+            amount_args
+        };
+        let mut deploy = Deploy::new(
+            Timestamp::now(),
+            deploy_config.max_ttl,
+            1,
+            vec![],
+            chain_name.to_string(),
+            ExecutableDeployItem::ModuleBytes {
+                module_bytes: Bytes::new(),
+                args: amount_args,
+            },
+            ExecutableDeployItem::ModuleBytes {
+                module_bytes: Bytes::new(),
+                args: RuntimeArgs::new(),
+            },
+            &SecretKey::random(&mut rng),
+        );
+
+        let expected_error = DeployValidationFailure::InsufficientPaymentForSizeClass {
+            size_class: SizeClass::Small,
+            minimum: U512::from(MAX_PAYMENT_AMOUNT + 1),
+            attempted: U512::from(MAX_PAYMENT_AMOUNT),
+        };
+
+        assert_eq!(
+            deploy.is_acceptable(chain_name, &deploy_config),
+            Err(expected_error)
+        );
+        assert!(
+            deploy.is_valid.is_none(),
+            "deploy should not have run expensive `is_valid` call"
+        );
+    }
+
     #[test]
     fn not_acceptable_due_to_invalid_chain_name() {
         let mut rng = crate::new_rng();
@@ -1187,4 +1761,69 @@ mod tests {
             "deploy should not have run expensive `is_valid` call"
         );
     }
+
+    #[test]
+    fn not_acceptable_due_to_low_gas_price() {
+        let mut rng = crate::new_rng();
+        let chain_name = "net-1";
+        let deploy_config = DeployConfig {
+            min_gas_price: 2,
+            max_gas_price: 2,
+            ..Default::default()
+        };
+
+        let mut deploy = create_deploy(
+            &mut rng,
+            deploy_config.max_ttl,
+            deploy_config.max_dependencies.into(),
+            chain_name,
+        );
+
+        let expected_error = DeployValidationFailure::GasPriceTooLow {
+            min_gas_price: 2,
+            got: 1,
+        };
+
+        assert_eq!(
+            deploy.is_acceptable(chain_name, &deploy_config),
+            Err(expected_error)
+        );
+        assert!(
+            deploy.is_valid.is_none(),
+            "deploy should not have run expensive `is_valid` call"
+        );
+    }
+
+    #[test]
+    fn should_check_validity_window() {
+        let mut rng = crate::new_rng();
+        let deploy = Deploy::new_with_validity_window(
+            Timestamp::now(),
+            TimeDiff::from(Duration::from_secs(60)),
+            1,
+            vec![],
+            "net-1".to_string(),
+            Some(10),
+            Some(EraId::from(3)),
+            ExecutableDeployItem::ModuleBytes {
+                module_bytes: Bytes::new(),
+                args: RuntimeArgs::new(),
+            },
+            ExecutableDeployItem::ModuleBytes {
+                module_bytes: Bytes::new(),
+                args: RuntimeArgs::new(),
+            },
+            &SecretKey::random(&mut rng),
+        );
+        let header = deploy.header();
+        assert!(!header.is_within_validity_window(9, EraId::from(3)));
+        assert!(header.is_within_validity_window(10, EraId::from(3)));
+        assert!(header.is_within_validity_window(100, EraId::from(0)));
+        assert!(!header.is_within_validity_window(100, EraId::from(4)));
+
+        let serialized = header.to_bytes().unwrap();
+        let (deserialized, remainder) = DeployHeader::from_bytes(&serialized).unwrap();
+        assert!(remainder.is_empty());
+        assert_eq!(*header, deserialized);
+    }
 }
@@ -0,0 +1,114 @@
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use casper_execution_engine::{
+    core::engine_state::execution_effect::ExecutionEffect,
+    shared::{stored_value::StoredValue, transform::Transform},
+};
+use casper_types::{
+    system::auction::{EraInfo, SeigniorageAllocation},
+    EraId, Key, PublicKey, U512,
+};
+
+/// The rewards distributed to a delegator for delegating to a single validator.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct DelegatorReward {
+    /// The delegator's public key.
+    pub delegator_public_key: PublicKey,
+    /// The amount in motes.
+    pub amount: U512,
+}
+
+/// The rewards distributed to a validator and its delegators.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ValidatorRewards {
+    /// The validator's public key.
+    pub validator_public_key: PublicKey,
+    /// The amount in motes distributed to the validator itself.
+    pub amount: U512,
+    /// The amounts distributed to the validator's delegators, sorted by public key.
+    pub delegators: Vec<DelegatorReward>,
+}
+
+/// The rewards distributed by the auction at the end of an era.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct EraRewards {
+    /// The era at the end of which the rewards were distributed.
+    pub era_id: EraId,
+    /// The total amount in motes distributed to all validators and delegators.
+    pub total: U512,
+    /// The rewards per validator, sorted by public key.
+    pub validators: Vec<ValidatorRewards>,
+}
+
+impl EraRewards {
+    /// Creates a summary of the seigniorage allocations in `era_info`.
+    pub(crate) fn new(era_id: EraId, era_info: &EraInfo) -> Self {
+        let mut validators: BTreeMap<PublicKey, (U512, BTreeMap<PublicKey, U512>)> =
+            BTreeMap::new();
+        let mut total = U512::zero();
+        for allocation in era_info.seigniorage_allocations() {
+            match allocation {
+                SeigniorageAllocation::Validator {
+                    validator_public_key,
+                    amount,
+                } => {
+                    let entry = validators.entry(validator_public_key.clone()).or_default();
+                    entry.0 += *amount;
+                    total += *amount;
+                }
+                SeigniorageAllocation::Delegator {
+                    delegator_public_key,
+                    validator_public_key,
+                    amount,
+                } => {
+                    let entry = validators.entry(validator_public_key.clone()).or_default();
+                    *entry.1.entry(delegator_public_key.clone()).or_default() += *amount;
+                    total += *amount;
+                }
+            }
+        }
+        let validators = validators
+            .into_iter()
+            .map(
+                |(validator_public_key, (amount, delegators))| ValidatorRewards {
+                    validator_public_key,
+                    amount,
+                    delegators: delegators
+                        .into_iter()
+                        .map(|(delegator_public_key, amount)| DelegatorReward {
+                            delegator_public_key,
+                            amount,
+                        })
+                        .collect(),
+                },
+            )
+            .collect();
+        EraRewards {
+            era_id,
+            total,
+            validators,
+        }
+    }
+
+    /// Creates a summary of the rewards distributed by the step at the end of `era_id`, from the
+    /// era info it wrote to global state.
+    ///
+    /// Returns `None` if the step didn't write any era info.
+    pub(crate) fn from_step_effect(era_id: EraId, effect: &ExecutionEffect) -> Option<Self> {
+        effect
+            .transforms
+            .iter()
+            .find_map(|(key, transform)| match (key, transform) {
+                (Key::EraInfo(_), Transform::Write(StoredValue::EraInfo(era_info))) => {
+                    Some(EraRewards::new(era_id, era_info))
+                }
+                _ => None,
+            })
+    }
+}
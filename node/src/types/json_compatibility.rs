@@ -2,13 +2,16 @@
 
 mod account;
 mod auction_state;
+pub mod cl_value;
 mod contracts;
+mod state_diff;
 mod stored_value;
 
 pub use account::Account;
 pub use auction_state::AuctionState;
 use casper_types::{contracts::NamedKeys, NamedKey};
 pub use contracts::{Contract, ContractPackage};
+pub use state_diff::{StateChange, StateDiff, StateEntry};
 pub use stored_value::StoredValue;
 
 /// A helper function to change NamedKeys into a Vec<NamedKey>
@@ -0,0 +1,329 @@
+//! Conversions between `CLValue`s and their JSON representation.
+//!
+//! A `CLValue` is represented in JSON by its `CLType` and the value itself, formatted as in the
+//! `parsed` field of a serialized `CLValue`.  This allows clients to provide runtime args without
+//! having to serialize them to bytesrepr first.
+
+use serde::{de::Error as SerdeError, Deserialize, Deserializer};
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+use casper_types::{
+    bytesrepr::{self, ToBytes, OPTION_NONE_TAG, OPTION_SOME_TAG, RESULT_ERR_TAG, RESULT_OK_TAG},
+    CLType, CLValue, Key, PublicKey, URef, U128, U256, U512,
+};
+
+use crate::types::Deploy;
+
+/// Error converting between a `CLValue` and its JSON representation.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// The JSON value doesn't match the given `CLType`.
+    #[error("JSON value {value} is not a valid {cl_type:?}")]
+    TypeMismatch {
+        /// The expected type.
+        cl_type: CLType,
+        /// The offending JSON value.
+        value: Value,
+    },
+    /// The `cl_type` field of a JSON `CLValue` isn't a valid `CLType`.
+    #[error("invalid CLType: {0}")]
+    InvalidCLType(Value),
+    /// `CLType::Any` has no JSON representation.
+    #[error("CLType Any has no JSON representation")]
+    AnyType,
+    /// The value couldn't be serialized to bytesrepr.
+    #[error("failed to serialize value: {0}")]
+    Bytesrepr(bytesrepr::Error),
+    /// A `CLValue`'s bytes couldn't be parsed as its `CLType`.
+    #[error("CLValue bytes do not match its CLType")]
+    Unparsable,
+}
+
+impl From<bytesrepr::Error> for Error {
+    fn from(error: bytesrepr::Error) -> Self {
+        Error::Bytesrepr(error)
+    }
+}
+
+/// Converts the JSON representation `value` of a value of type `cl_type` into a `CLValue`.
+pub fn cl_value_from_json(cl_type: &CLType, value: &Value) -> Result<CLValue, Error> {
+    let mut bytes = Vec::new();
+    write_json(cl_type, value, &mut bytes)?;
+    Ok(CLValue::from_components(cl_type.clone(), bytes))
+}
+
+/// Converts the `CLValue` into its JSON representation.
+///
+/// This is the inverse of [`cl_value_from_json`].
+pub fn cl_value_to_json(cl_value: &CLValue) -> Result<Value, Error> {
+    let mut serialized = serde_json::to_value(cl_value).map_err(|_| Error::Unparsable)?;
+    let parsed = serialized
+        .get_mut("parsed")
+        .map(Value::take)
+        .ok_or(Error::Unparsable)?;
+    // `null` is only a meaningful representation of a unit or of an option's `None`.
+    if parsed.is_null() && !matches!(cl_value.cl_type(), CLType::Unit | CLType::Option(_)) {
+        return Err(Error::Unparsable);
+    }
+    Ok(parsed)
+}
+
+/// Appends the bytesrepr serialization of the JSON `value` of type `cl_type` to `bytes`.
+fn write_json(cl_type: &CLType, value: &Value, bytes: &mut Vec<u8>) -> Result<(), Error> {
+    let mismatch = || Error::TypeMismatch {
+        cl_type: cl_type.clone(),
+        value: value.clone(),
+    };
+    match cl_type {
+        CLType::Bool => write_simple::<bool>(cl_type, value, bytes),
+        CLType::I32 => write_simple::<i32>(cl_type, value, bytes),
+        CLType::I64 => write_simple::<i64>(cl_type, value, bytes),
+        CLType::U8 => write_simple::<u8>(cl_type, value, bytes),
+        CLType::U32 => write_simple::<u32>(cl_type, value, bytes),
+        CLType::U64 => write_simple::<u64>(cl_type, value, bytes),
+        CLType::U128 => write_simple::<U128>(cl_type, value, bytes),
+        CLType::U256 => write_simple::<U256>(cl_type, value, bytes),
+        CLType::U512 => write_simple::<U512>(cl_type, value, bytes),
+        CLType::Unit => write_simple::<()>(cl_type, value, bytes),
+        CLType::String => write_simple::<String>(cl_type, value, bytes),
+        CLType::Key => write_simple::<Key>(cl_type, value, bytes),
+        CLType::URef => write_simple::<URef>(cl_type, value, bytes),
+        CLType::PublicKey => write_simple::<PublicKey>(cl_type, value, bytes),
+        CLType::Option(inner_cl_type) => match value {
+            Value::Null => {
+                bytes.push(OPTION_NONE_TAG);
+                Ok(())
+            }
+            _ => {
+                bytes.push(OPTION_SOME_TAG);
+                write_json(inner_cl_type, value, bytes)
+            }
+        },
+        CLType::List(inner_cl_type) => {
+            let items = value.as_array().ok_or_else(mismatch)?;
+            bytes.append(&mut (items.len() as u32).to_bytes()?);
+            items
+                .iter()
+                .try_for_each(|item| write_json(inner_cl_type, item, bytes))
+        }
+        CLType::ByteArray(length) => {
+            let decoded = value
+                .as_str()
+                .and_then(|hex_str| hex::decode(hex_str).ok())
+                .filter(|decoded| decoded.len() == *length as usize)
+                .ok_or_else(mismatch)?;
+            bytes.extend(decoded);
+            Ok(())
+        }
+        CLType::Result { ok, err } => {
+            let object = value
+                .as_object()
+                .filter(|object| object.len() == 1)
+                .ok_or_else(mismatch)?;
+            if let Some(ok_value) = object.get("Ok") {
+                bytes.push(RESULT_OK_TAG);
+                write_json(ok, ok_value, bytes)
+            } else if let Some(err_value) = object.get("Err") {
+                bytes.push(RESULT_ERR_TAG);
+                write_json(err, err_value, bytes)
+            } else {
+                Err(mismatch())
+            }
+        }
+        CLType::Map {
+            key,
+            value: value_cl_type,
+        } => {
+            // Entries are serialized in the given order, which for the bytes to be canonical must
+            // be the order of the keys.
+            let entries = value.as_array().ok_or_else(mismatch)?;
+            bytes.append(&mut (entries.len() as u32).to_bytes()?);
+            entries.iter().try_for_each(|entry| {
+                let entry_key = entry.get("key").ok_or_else(mismatch)?;
+                let entry_value = entry.get("value").ok_or_else(mismatch)?;
+                write_json(key, entry_key, bytes)?;
+                write_json(value_cl_type, entry_value, bytes)
+            })
+        }
+        CLType::Tuple1(cl_types) => write_tuple(cl_type, cl_types, value, bytes),
+        CLType::Tuple2(cl_types) => write_tuple(cl_type, cl_types, value, bytes),
+        CLType::Tuple3(cl_types) => write_tuple(cl_type, cl_types, value, bytes),
+        CLType::Any => Err(Error::AnyType),
+    }
+}
+
+fn write_simple<T>(cl_type: &CLType, value: &Value, bytes: &mut Vec<u8>) -> Result<(), Error>
+where
+    T: ToBytes + for<'de> Deserialize<'de>,
+{
+    let parsed: T = serde_json::from_value(value.clone()).map_err(|_| Error::TypeMismatch {
+        cl_type: cl_type.clone(),
+        value: value.clone(),
+    })?;
+    bytes.append(&mut parsed.to_bytes()?);
+    Ok(())
+}
+
+fn write_tuple(
+    cl_type: &CLType,
+    cl_types: &[Box<CLType>],
+    value: &Value,
+    bytes: &mut Vec<u8>,
+) -> Result<(), Error> {
+    let items = value
+        .as_array()
+        .filter(|items| items.len() == cl_types.len())
+        .ok_or_else(|| Error::TypeMismatch {
+            cl_type: cl_type.clone(),
+            value: value.clone(),
+        })?;
+    cl_types
+        .iter()
+        .zip(items)
+        .try_for_each(|(item_cl_type, item)| write_json(item_cl_type, item, bytes))
+}
+
+/// Fills in the `bytes` of every runtime arg in the JSON representation of a deploy which was
+/// provided only as a `cl_type` and `parsed` value.
+fn fill_in_arg_bytes(deploy: &mut Value) -> Result<(), Error> {
+    for item_name in &["payment", "session"] {
+        let variants = match deploy.get_mut(*item_name).and_then(Value::as_object_mut) {
+            Some(variants) => variants,
+            None => continue,
+        };
+        for item in variants.values_mut() {
+            let args = match item.get_mut("args").and_then(Value::as_array_mut) {
+                Some(args) => args,
+                None => continue,
+            };
+            for arg in args.iter_mut() {
+                if let Some(cl_value) = arg.get_mut(1).and_then(Value::as_object_mut) {
+                    fill_in_cl_value_bytes(cl_value)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn fill_in_cl_value_bytes(cl_value: &mut Map<String, Value>) -> Result<(), Error> {
+    if cl_value.contains_key("bytes") {
+        return Ok(());
+    }
+    let (cl_type, parsed) = match (cl_value.get("cl_type"), cl_value.get("parsed")) {
+        (Some(cl_type), Some(parsed)) => (cl_type, parsed),
+        _ => return Ok(()),
+    };
+    let cl_type: CLType = serde_json::from_value(cl_type.clone())
+        .map_err(|_| Error::InvalidCLType(cl_type.clone()))?;
+    let converted = cl_value_from_json(&cl_type, parsed)?;
+    cl_value.insert(
+        "bytes".to_string(),
+        Value::String(hex::encode(converted.inner_bytes())),
+    );
+    Ok(())
+}
+
+/// Deserializes a `Deploy`, accepting runtime args given as a `cl_type` and `parsed` value in place
+/// of their `bytes`.
+pub(crate) fn deserialize_deploy<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Deploy, D::Error> {
+    let mut deploy = Value::deserialize(deserializer)?;
+    fill_in_arg_bytes(&mut deploy).map_err(D::Error::custom)?;
+    serde_json::from_value(deploy).map_err(D::Error::custom)
+}
+
+/// Deserializes a list of `Deploy`s, accepting runtime args as in [`deserialize_deploy`].
+pub(crate) fn deserialize_deploys<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<Deploy>, D::Error> {
+    Vec::<Value>::deserialize(deserializer)?
+        .into_iter()
+        .map(|mut deploy| {
+            fill_in_arg_bytes(&mut deploy).map_err(D::Error::custom)?;
+            serde_json::from_value(deploy).map_err(D::Error::custom)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serde_json::json;
+
+    use casper_types::{CLTyped, SecretKey};
+
+    use super::*;
+
+    fn round_trip<T: CLTyped + ToBytes>(value: T) {
+        let cl_value = CLValue::from_t(value).unwrap();
+        let json = cl_value_to_json(&cl_value).unwrap();
+        let converted = cl_value_from_json(cl_value.cl_type(), &json).unwrap();
+        assert_eq!(cl_value, converted);
+    }
+
+    #[test]
+    fn should_round_trip_simple_types() {
+        round_trip(true);
+        round_trip(-7_i32);
+        round_trip(i64::min_value());
+        round_trip(u8::max_value());
+        round_trip(42_u32);
+        round_trip(u64::max_value());
+        round_trip(U128::max_value());
+        round_trip(U256::max_value());
+        round_trip(U512::from(12_345));
+        round_trip(());
+        round_trip(String::from("casper"));
+        round_trip(Key::Hash([7; 32]));
+        round_trip(URef::default());
+        round_trip(PublicKey::from(
+            SecretKey::ed25519_from_bytes([3; SecretKey::ED25519_LENGTH]).unwrap(),
+        ));
+        round_trip([1_u8, 2, 3]);
+    }
+
+    #[test]
+    fn should_round_trip_nested_types() {
+        round_trip(Some(vec![Some(1_u64), None]));
+        round_trip(Option::<String>::None);
+        round_trip(vec![vec![String::from("a")], vec![]]);
+        let mut map = BTreeMap::new();
+        map.insert(String::from("first"), vec![1_i32, 2]);
+        map.insert(String::from("second"), vec![]);
+        round_trip(map);
+        round_trip(Result::<u8, String>::Ok(1));
+        round_trip(Result::<u8, String>::Err(String::from("nope")));
+        round_trip((1_u8,));
+        round_trip((1_u8, String::from("two")));
+        round_trip((1_u8, String::from("two"), Some(3_u32)));
+    }
+
+    #[test]
+    fn should_reject_mismatched_json() {
+        assert!(cl_value_from_json(&CLType::U8, &json!(256)).is_err());
+        assert!(cl_value_from_json(&CLType::Bool, &json!("true")).is_err());
+        assert!(cl_value_from_json(&CLType::ByteArray(2), &json!("010203")).is_err());
+        assert!(cl_value_from_json(&<(u8, u8)>::cl_type(), &json!([1])).is_err());
+        assert!(cl_value_from_json(&CLType::Any, &json!(null)).is_err());
+    }
+
+    #[test]
+    fn should_fill_in_arg_bytes() {
+        let expected = CLValue::from_t(vec![1_u8, 2]).unwrap();
+        let mut deploy = json!({
+            "session": {
+                "ModuleBytes": {
+                    "module_bytes": "",
+                    "args": [["arg", { "cl_type": { "List": "U8" }, "parsed": [1, 2] }]]
+                }
+            }
+        });
+        fill_in_arg_bytes(&mut deploy).unwrap();
+        let arg = &deploy["session"]["ModuleBytes"]["args"][0][1];
+        assert_eq!(arg["bytes"], json!(hex::encode(expected.inner_bytes())));
+    }
+}
@@ -0,0 +1,86 @@
+//! This file provides types to allow conversion from an EE `StateDiff` into a similar type which
+//! can be serialized to a valid JSON representation.
+
+use std::{collections::BTreeMap, convert::TryFrom};
+
+use serde::{Deserialize, Serialize};
+
+use casper_execution_engine::{
+    core::engine_state::StateDiff as ExecutionEngineStateDiff,
+    shared::stored_value::StoredValue as ExecutionEngineStoredValue,
+};
+use casper_types::{bytesrepr, Key};
+
+use super::StoredValue;
+
+/// A value stored under a key in only one of the two compared global states.
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct StateEntry {
+    /// The formatted key.
+    pub key: String,
+    /// The stored value.
+    pub value: StoredValue,
+}
+
+/// A key whose stored value differs between the two compared global states.
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct StateChange {
+    /// The formatted key.
+    pub key: String,
+    /// The value in the pre state.
+    pub before: StoredValue,
+    /// The value in the post state.
+    pub after: StoredValue,
+}
+
+/// The differences between two global states, with all values decoded.
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct StateDiff {
+    /// Values only present in the post state.
+    pub added: Vec<StateEntry>,
+    /// Values only present in the pre state.
+    pub removed: Vec<StateEntry>,
+    /// Values present in both states, but differing.
+    pub changed: Vec<StateChange>,
+}
+
+impl TryFrom<&ExecutionEngineStateDiff> for StateDiff {
+    type Error = bytesrepr::Error;
+
+    fn try_from(ee_state_diff: &ExecutionEngineStateDiff) -> Result<Self, Self::Error> {
+        let changed = ee_state_diff
+            .changed
+            .iter()
+            .map(|(key, (before, after))| {
+                Ok(StateChange {
+                    key: key.to_formatted_string(),
+                    before: StoredValue::try_from(before)?,
+                    after: StoredValue::try_from(after)?,
+                })
+            })
+            .collect::<Result<Vec<_>, bytesrepr::Error>>()?;
+
+        Ok(StateDiff {
+            added: to_entries(&ee_state_diff.added)?,
+            removed: to_entries(&ee_state_diff.removed)?,
+            changed,
+        })
+    }
+}
+
+fn to_entries(
+    values: &BTreeMap<Key, ExecutionEngineStoredValue>,
+) -> Result<Vec<StateEntry>, bytesrepr::Error> {
+    values
+        .iter()
+        .map(|(key, value)| {
+            Ok(StateEntry {
+                key: key.to_formatted_string(),
+                value: StoredValue::try_from(value)?,
+            })
+        })
+        .collect()
+}
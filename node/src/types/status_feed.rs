@@ -15,6 +15,7 @@ use casper_types::{EraId, ProtocolVersion, PublicKey};
 
 use crate::{
     components::{
+        chain_synchronizer::TrieSyncProgress,
         chainspec_loader::NextUpgrade,
         rpc_server::rpcs::docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
     },
@@ -31,6 +32,7 @@ static CHAINSPEC_INFO: Lazy<ChainspecInfo> = Lazy::new(|| {
         name: String::from("casper-example"),
         starting_state_root_hash: Digest::from([2u8; Digest::LENGTH]),
         next_upgrade: Some(next_upgrade),
+        max_deploy_ttl: TimeDiff::from(86_400_000),
     }
 });
 
@@ -45,6 +47,7 @@ static GET_STATUS_RESULT: Lazy<GetStatusResult> = Lazy::new(|| {
         chainspec_info: ChainspecInfo::doc_example().clone(),
         our_public_signing_key: Some(PublicKey::doc_example().clone()),
         round_length: Some(TimeDiff::from(1 << 16)),
+        trie_sync_progress: None,
         version: crate::VERSION_STRING.as_str(),
     };
     GetStatusResult::new(status_feed, DOCS_EXAMPLE_PROTOCOL_VERSION)
@@ -60,6 +63,8 @@ pub struct ChainspecInfo {
     /// hash specified in the highest block on startup.
     starting_state_root_hash: Digest,
     next_upgrade: Option<NextUpgrade>,
+    /// The maximum time-to-live accepted for new deploys.
+    max_deploy_ttl: TimeDiff,
 }
 
 impl DocExample for ChainspecInfo {
@@ -73,11 +78,13 @@ impl ChainspecInfo {
         chainspec_network_name: String,
         starting_state_root_hash: Digest,
         next_upgrade: Option<NextUpgrade>,
+        max_deploy_ttl: TimeDiff,
     ) -> Self {
         ChainspecInfo {
             name: chainspec_network_name,
             starting_state_root_hash,
             next_upgrade,
+            max_deploy_ttl,
         }
     }
 }
@@ -96,6 +103,8 @@ pub struct StatusFeed<I> {
     pub our_public_signing_key: Option<PublicKey>,
     /// The next round length if this node is a validator.
     pub round_length: Option<TimeDiff>,
+    /// The progress of the global state trie sync, if one was started.
+    pub trie_sync_progress: Option<TrieSyncProgress>,
     /// The compiled node version.
     pub version: &'static str,
 }
@@ -106,6 +115,7 @@ impl<I> StatusFeed<I> {
         peers: BTreeMap<I, String>,
         chainspec_info: ChainspecInfo,
         consensus_status: Option<(PublicKey, Option<TimeDiff>)>,
+        trie_sync_progress: Option<TrieSyncProgress>,
     ) -> Self {
         let (our_public_signing_key, round_length) = match consensus_status {
             Some((public_key, round_length)) => (Some(public_key), round_length),
@@ -117,6 +127,7 @@ impl<I> StatusFeed<I> {
             chainspec_info,
             our_public_signing_key,
             round_length,
+            trie_sync_progress,
             version: crate::VERSION_STRING.as_str(),
         }
     }
@@ -168,6 +179,10 @@ pub struct GetStatusResult {
     pub round_length: Option<TimeDiff>,
     /// Information about the next scheduled upgrade.
     pub next_upgrade: Option<NextUpgrade>,
+    /// The maximum time-to-live accepted for new deploys.
+    pub max_deploy_ttl: TimeDiff,
+    /// The progress of the global state trie sync, if one was started.
+    pub trie_sync_progress: Option<TrieSyncProgress>,
     /// The compiled node version.
     pub build_version: String,
 }
@@ -186,6 +201,8 @@ impl GetStatusResult {
             our_public_signing_key: status_feed.our_public_signing_key,
             round_length: status_feed.round_length,
             next_upgrade: status_feed.chainspec_info.next_upgrade,
+            max_deploy_ttl: status_feed.chainspec_info.max_deploy_ttl,
+            trie_sync_progress: status_feed.trie_sync_progress,
             build_version: crate::VERSION_STRING.clone(),
         }
     }
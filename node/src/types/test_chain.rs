@@ -0,0 +1,277 @@
+//! Test support for constructing deterministic chains of linked blocks.
+//!
+//! Allows storage, linear chain and syncing components to be tested against realistic, multi-era
+//! chains without having to run consensus.
+
+use std::collections::{BTreeMap, HashMap};
+
+use casper_types::{EraId, ProtocolVersion, PublicKey, SecretKey, U512};
+
+use super::{
+    block::EraReport, Block, BlockHash, BlockPayload, FinalizedBlock, TimeDiff, Timestamp,
+};
+use crate::crypto::hash::{self, Digest};
+
+/// The default number of blocks in each era, including the switch block.
+const DEFAULT_ERA_LENGTH: u64 = 10;
+/// The default number of validators proposing the blocks.
+const DEFAULT_VALIDATOR_COUNT: u8 = 4;
+/// The default time between two consecutive blocks.
+const DEFAULT_BLOCK_TIME: TimeDiff = TimeDiff::from_seconds(8);
+
+/// A builder of a chain of linked blocks, starting with the first block after genesis.
+///
+/// All properties of the blocks are derived deterministically from the builder's settings, so
+/// building twice with the same settings yields identical chains.
+pub(crate) struct TestChainBuilder {
+    /// The heights of the switch blocks, i.e. the last blocks of their eras.
+    switch_block_heights: Option<Vec<u64>>,
+    /// The number of blocks in each era, used if no explicit switch block heights are given.
+    era_length: u64,
+    /// The validators of each era, keyed by the era they validate.  Eras without an entry use the
+    /// validators of the closest earlier era.
+    validators: BTreeMap<EraId, BTreeMap<PublicKey, U512>>,
+    /// Proposers overriding the default round-robin choice, by block height.
+    proposers: HashMap<u64, PublicKey>,
+    /// State root hashes overriding the default ones, by block height.
+    state_root_hashes: HashMap<u64, Digest>,
+    protocol_version: ProtocolVersion,
+    start_timestamp: Timestamp,
+    block_time: TimeDiff,
+}
+
+impl Default for TestChainBuilder {
+    fn default() -> Self {
+        let validators = (1..=DEFAULT_VALIDATOR_COUNT)
+            .map(|index| (validator_key(index), U512::from(u64::from(index) * 100)))
+            .collect();
+        TestChainBuilder {
+            switch_block_heights: None,
+            era_length: DEFAULT_ERA_LENGTH,
+            validators: vec![(EraId::from(0), validators)].into_iter().collect(),
+            proposers: HashMap::new(),
+            state_root_hashes: HashMap::new(),
+            protocol_version: ProtocolVersion::V1_0_0,
+            start_timestamp: Timestamp::from(1_600_000_000_000),
+            block_time: DEFAULT_BLOCK_TIME,
+        }
+    }
+}
+
+impl TestChainBuilder {
+    /// Creates a builder with the default settings: eras of ten blocks, proposed in turn by four
+    /// validators with distinct weights.
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of blocks in each era, including its switch block.
+    pub(crate) fn era_length(mut self, era_length: u64) -> Self {
+        assert!(era_length > 0, "eras must contain at least one block");
+        self.era_length = era_length;
+        self
+    }
+
+    /// Sets the heights of the switch blocks explicitly, overriding the era length.
+    ///
+    /// Blocks after the last given switch block all belong to one final, unfinished era.
+    pub(crate) fn switch_blocks_at(mut self, mut heights: Vec<u64>) -> Self {
+        heights.sort_unstable();
+        heights.dedup();
+        self.switch_block_heights = Some(heights);
+        self
+    }
+
+    /// Sets the validators of the given era and of all later eras not configured otherwise.
+    pub(crate) fn validators(
+        mut self,
+        era_id: EraId,
+        validators: BTreeMap<PublicKey, U512>,
+    ) -> Self {
+        assert!(
+            !validators.is_empty(),
+            "eras must have at least one validator"
+        );
+        let _ = self.validators.insert(era_id, validators);
+        self
+    }
+
+    /// Sets the proposer of the block at the given height.
+    pub(crate) fn proposer(mut self, height: u64, proposer: PublicKey) -> Self {
+        let _ = self.proposers.insert(height, proposer);
+        self
+    }
+
+    /// Sets the state root hash of the block at the given height.
+    pub(crate) fn state_root_hash(mut self, height: u64, state_root_hash: Digest) -> Self {
+        let _ = self.state_root_hashes.insert(height, state_root_hash);
+        self
+    }
+
+    /// Sets the protocol version of all blocks.
+    pub(crate) fn protocol_version(mut self, protocol_version: ProtocolVersion) -> Self {
+        self.protocol_version = protocol_version;
+        self
+    }
+
+    /// Returns the validators of the given era.
+    pub(crate) fn era_validators(&self, era_id: EraId) -> &BTreeMap<PublicKey, U512> {
+        self.validators
+            .range(..=era_id)
+            .next_back()
+            .map(|(_, validators)| validators)
+            .expect("validators of era 0 should be set")
+    }
+
+    /// Builds a chain of `length` blocks, the first of which is the first block after genesis.
+    pub(crate) fn build(&self, length: u64) -> Vec<Block> {
+        let mut blocks: Vec<Block> = Vec::new();
+        let mut era_id = EraId::from(0);
+        for height in 0..length {
+            let (parent_hash, parent_seed) = match blocks.last() {
+                Some(parent) => (*parent.hash(), parent.header().accumulated_seed()),
+                None => (BlockHash::new(Digest::default()), Digest::default()),
+            };
+            let is_switch_block = self.is_switch_block(height);
+            let era_report = if is_switch_block {
+                Some(EraReport {
+                    equivocators: vec![],
+                    rewards: BTreeMap::new(),
+                    inactive_validators: vec![],
+                })
+            } else {
+                None
+            };
+            let next_era_validator_weights = era_report
+                .as_ref()
+                .map(|_| self.era_validators(era_id.successor()).clone());
+            let finalized_block = FinalizedBlock::new(
+                BlockPayload::new(vec![], vec![], vec![], height % 2 == 0),
+                era_report,
+                self.start_timestamp + self.block_time * height,
+                era_id,
+                height,
+                self.proposer_at(era_id, height),
+            );
+            let state_root_hash = self
+                .state_root_hashes
+                .get(&height)
+                .copied()
+                .unwrap_or_else(|| hash::hash(height.to_le_bytes()));
+            blocks.push(Block::new(
+                parent_hash,
+                parent_seed,
+                state_root_hash,
+                finalized_block,
+                next_era_validator_weights,
+                self.protocol_version,
+                1,
+            ));
+            if is_switch_block {
+                era_id = era_id.successor();
+            }
+        }
+        blocks
+    }
+
+    /// Returns whether the block at the given height is the last block of its era.
+    fn is_switch_block(&self, height: u64) -> bool {
+        match &self.switch_block_heights {
+            Some(heights) => heights.binary_search(&height).is_ok(),
+            None => (height + 1) % self.era_length == 0,
+        }
+    }
+
+    /// Returns the proposer of the block at the given height, choosing the era's validators in
+    /// turn unless overridden.
+    fn proposer_at(&self, era_id: EraId, height: u64) -> PublicKey {
+        if let Some(proposer) = self.proposers.get(&height) {
+            return proposer.clone();
+        }
+        let validators = self.era_validators(era_id);
+        let index = (height % validators.len() as u64) as usize;
+        validators
+            .keys()
+            .nth(index)
+            .cloned()
+            .expect("index should be in range")
+    }
+}
+
+/// Returns the public key of the deterministic test validator with the given index.
+pub(crate) fn validator_key(index: u8) -> PublicKey {
+    let secret_key = SecretKey::ed25519_from_bytes([index; SecretKey::ED25519_LENGTH])
+        .expect("should create secret key");
+    PublicKey::from(&secret_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_build_linked_chain() {
+        let blocks = TestChainBuilder::new().build(25);
+        assert_eq!(blocks.len(), 25);
+        assert_eq!(blocks[0].parent(), None);
+        for (parent, child) in blocks.iter().zip(blocks.iter().skip(1)) {
+            assert_eq!(child.parent(), Some(parent.hash()));
+            assert_eq!(child.height(), parent.height() + 1);
+            assert!(child.timestamp() > parent.timestamp());
+            child.verify().expect("block hash should check");
+        }
+    }
+
+    #[test]
+    fn should_be_deterministic() {
+        let builder = TestChainBuilder::new().era_length(3);
+        assert_eq!(builder.build(10), builder.build(10));
+    }
+
+    #[test]
+    fn should_end_eras_with_switch_blocks() {
+        let blocks = TestChainBuilder::new()
+            .switch_blocks_at(vec![2, 7])
+            .build(10);
+        let switch_heights: Vec<u64> = blocks
+            .iter()
+            .filter(|block| block.header().is_switch_block())
+            .map(Block::height)
+            .collect();
+        assert_eq!(switch_heights, vec![2, 7]);
+        let era_ids: Vec<u64> = blocks
+            .iter()
+            .map(|block| block.header().era_id().value())
+            .collect();
+        assert_eq!(era_ids, vec![0, 0, 0, 1, 1, 1, 1, 1, 2, 2]);
+    }
+
+    #[test]
+    fn should_apply_overrides() {
+        let new_validator = validator_key(42);
+        let new_validators: BTreeMap<PublicKey, U512> = vec![(new_validator.clone(), 1.into())]
+            .into_iter()
+            .collect();
+        let state_root_hash = hash::hash(b"state");
+        let proposer = validator_key(7);
+        let blocks = TestChainBuilder::new()
+            .era_length(2)
+            .validators(EraId::from(1), new_validators.clone())
+            .state_root_hash(1, state_root_hash)
+            .proposer(0, proposer.clone())
+            .protocol_version(ProtocolVersion::from_parts(1, 1, 0))
+            .build(4);
+
+        assert_eq!(blocks[0].body().proposer(), &proposer);
+        assert_eq!(*blocks[1].state_root_hash(), state_root_hash);
+        assert_eq!(
+            blocks[1].header().next_era_validator_weights(),
+            Some(&new_validators)
+        );
+        assert_eq!(blocks[2].body().proposer(), &new_validator);
+        assert_eq!(blocks[3].body().proposer(), &new_validator);
+        assert!(blocks
+            .iter()
+            .all(|block| block.protocol_version() == ProtocolVersion::from_parts(1, 1, 0)));
+    }
+}
@@ -1,6 +1,7 @@
 //! Various functions that are not limited to a particular module, but are too small to warrant
 //! being factored out into standalone crates.
 
+mod correlation_id;
 mod counting_channel;
 pub mod ds;
 mod external;
@@ -34,11 +35,12 @@ use serde::Serialize;
 use thiserror::Error;
 use tracing::{error, warn};
 
+pub(crate) use correlation_id::CorrelationId;
 pub(crate) use counting_channel::{counting_unbounded_channel, CountingReceiver, CountingSender};
 #[cfg(test)]
 pub use external::RESOURCES_PATH;
 pub use external::{External, LoadError, Loadable};
-pub(crate) use round_robin::WeightedRoundRobin;
+pub(crate) use round_robin::{FairnessPolicy, WeightedRoundRobin};
 
 /// Sensible default for many if not all systems.
 const DEFAULT_PAGE_SIZE: usize = 4096;
@@ -116,15 +118,28 @@ pub enum ListeningError {
         /// The failure reason.
         error: Box<dyn std::error::Error + Send + Sync>,
     },
+
+    /// Failed to set up TLS.
+    #[error("failed to set up TLS: {0}")]
+    Tls(openssl::error::ErrorStack),
+
+    /// Invalid server configuration.
+    #[error("invalid configuration: {0}")]
+    InvalidConfig(String),
 }
 
 pub(crate) fn start_listening(address: &str) -> Result<Builder<AddrIncoming>, ListeningError> {
+    bind_incoming(address).map(Server::builder)
+}
+
+/// Binds to the given address, returning the listener's incoming connections.
+pub(crate) fn bind_incoming(address: &str) -> Result<AddrIncoming, ListeningError> {
     let address = resolve_address(address).map_err(|error| {
         warn!(%error, %address, "failed to start HTTP server, cannot parse address");
         ListeningError::ResolveAddress(error)
     })?;
 
-    Server::try_bind(&address).map_err(|error| {
+    AddrIncoming::bind(&address).map_err(|error| {
         warn!(%error, %address, "failed to start HTTP server");
         ListeningError::Listen {
             address,
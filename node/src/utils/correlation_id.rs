@@ -0,0 +1,64 @@
+//! Correlation IDs tying causally related work together.
+//!
+//! A correlation ID is set as a task-local value while a future runs inside
+//! [`CorrelationId::scope`]. Anything that wants to carry the ID across task boundaries, such as
+//! the event scheduler, can pick it up again using [`CorrelationId::current`].
+
+use std::{
+    fmt::{self, Display, Formatter},
+    future::Future,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use serde::Serialize;
+
+/// The next correlation ID to hand out.
+static NEXT_CORRELATION_ID: AtomicU64 = AtomicU64::new(1);
+
+tokio::task_local! {
+    static CURRENT_CORRELATION_ID: CorrelationId;
+}
+
+/// An identifier shared by all work derived from a single root cause.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize)]
+pub(crate) struct CorrelationId(u64);
+
+impl CorrelationId {
+    /// Creates a new, process-wide unique correlation ID.
+    pub(crate) fn fresh() -> Self {
+        CorrelationId(NEXT_CORRELATION_ID.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Returns the correlation ID of the current task, if any.
+    pub(crate) fn current() -> Option<Self> {
+        CURRENT_CORRELATION_ID.try_with(|id| *id).ok()
+    }
+
+    /// Runs `future` with `self` set as the current correlation ID.
+    pub(crate) async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT_CORRELATION_ID.scope(self, future).await
+    }
+}
+
+impl Display for CorrelationId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:x}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CorrelationId;
+
+    #[tokio::test]
+    async fn should_only_be_set_within_scope() {
+        assert_eq!(CorrelationId::current(), None);
+
+        let id = CorrelationId::fresh();
+        let seen = id.scope(async { CorrelationId::current() }).await;
+        assert_eq!(seen, Some(id));
+
+        assert_eq!(CorrelationId::current(), None);
+        assert_ne!(CorrelationId::fresh(), id);
+    }
+}
@@ -12,12 +12,37 @@ use std::{
     io::{self, BufWriter, Write},
     num::NonZeroUsize,
     sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
 };
 
 use enum_iterator::IntoEnumIterator;
 use serde::{ser::SerializeMap, Serialize, Serializer};
 use tokio::sync::{Mutex, MutexGuard, Semaphore};
 
+use super::CorrelationId;
+
+/// Fairness policy used when deciding which queue to pop the next item from.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum FairnessPolicy {
+    /// Plain weighted round-robin: each queue's weight is the number of items returned from it
+    /// before moving on to the next queue, regardless of how expensive these items are.
+    WeightedRoundRobin,
+    /// Deficit round-robin: each queue's weight multiplied by the quantum is the processing time
+    /// it is granted per round. The actual processing time of each item is charged against the
+    /// queue's deficit (see [`WeightedRoundRobin::charge`]), so a queue flooded with expensive
+    /// items cannot starve the remaining queues.
+    DeficitRoundRobin {
+        /// Processing time granted per unit of weight and round.
+        quantum: Duration,
+    },
+}
+
+impl Default for FairnessPolicy {
+    fn default() -> Self {
+        FairnessPolicy::WeightedRoundRobin
+    }
+}
+
 /// Weighted round-robin scheduler.
 ///
 /// The weighted round-robin scheduler keeps queues internally and returns an item from a queue
@@ -41,16 +66,22 @@ pub struct WeightedRoundRobin<I, K> {
 
     /// Number of items in all queues combined.
     total: Semaphore,
+
+    /// The fairness policy used when popping items.
+    policy: FairnessPolicy,
 }
 
 /// State that wraps queue and its event count.
+///
+/// Every item is stored alongside the instant it was pushed, allowing the age of the oldest item
+/// to be reported, and the correlation ID of the task that pushed it, if any.
 #[derive(Debug)]
 struct QueueState<I> {
     /// A queue's event counter.
     ///
     /// Do not modify this unless you are holding the `queue` lock.
     event_count: AtomicUsize,
-    queue: Mutex<VecDeque<I>>,
+    queue: Mutex<VecDeque<QueueEntry<I>>>,
 }
 
 impl<I> QueueState<I> {
@@ -64,14 +95,17 @@ impl<I> QueueState<I> {
     /// Remove all events from a queue.
     async fn drain(&self) -> Vec<I> {
         let mut guard = self.queue.lock().await;
-        let events: Vec<I> = guard.drain(..).collect();
+        let events: Vec<I> = guard.drain(..).map(|(event, _, _)| event).collect();
         self.event_count.fetch_sub(events.len(), Ordering::SeqCst);
         events
     }
 
     #[inline]
     async fn push_back(&self, element: I) {
-        self.queue.lock().await.push_back(element);
+        self.queue
+            .lock()
+            .await
+            .push_back((element, Instant::now(), CorrelationId::current()));
         self.event_count.fetch_add(1, Ordering::SeqCst);
     }
 
@@ -84,10 +118,33 @@ impl<I> QueueState<I> {
     fn event_count(&self) -> usize {
         self.event_count.load(Ordering::SeqCst)
     }
+
+    /// Returns the age of the oldest item in the queue.
+    ///
+    /// Returns `None` if the queue is empty or currently locked by another task.
+    fn oldest_event_age(&self) -> Option<Duration> {
+        let guard = self.queue.try_lock().ok()?;
+        guard
+            .front()
+            .map(|(_, enqueued_at, _)| enqueued_at.elapsed())
+    }
+}
+
+/// An item in a queue, along with the instant it was pushed and the correlation ID it was pushed
+/// under.
+type QueueEntry<I> = (I, Instant, Option<CorrelationId>);
+
+/// Serializes only the items of a queue, omitting their enqueuing timestamps and correlation IDs.
+struct QueueItems<'a, I>(&'a VecDeque<QueueEntry<I>>);
+
+impl<'a, I: Serialize> Serialize for QueueItems<'a, I> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.iter().map(|(item, _, _)| item))
+    }
 }
 
 /// The inner state of the queue iteration.
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug)]
 struct IterationState<K> {
     /// The currently active slot.
     ///
@@ -96,6 +153,12 @@ struct IterationState<K> {
 
     /// The position of the active slot. Used to calculate the next slot.
     active_slot_idx: usize,
+
+    /// Remaining processing time per slot when using deficit round-robin, in nanoseconds.
+    ///
+    /// May become negative after an expensive item was charged, in which case the debt is carried
+    /// into the next round.
+    deficits: Vec<i128>,
 }
 
 /// An internal slot in the round-robin scheduler.
@@ -146,9 +209,8 @@ where
 
         // By iterating over the guards, they are dropped in order.
         for (kind, guard) in locks {
-            let vd = &*guard;
             map.serialize_key(&kind)?;
-            map.serialize_value(vd)?;
+            map.serialize_value(&QueueItems(&*guard))?;
         }
         map.end()?;
 
@@ -169,7 +231,7 @@ where
         for (kind, guard) in locks {
             let queue = &*guard;
             writer.write_all(format!("Queue: {:?} ({}) [\n", kind, queue.len()).as_bytes())?;
-            for event in queue.iter() {
+            for (event, _, _) in queue.iter() {
                 writer.write_all(format!("\t{:?}\n", event).as_bytes())?;
             }
             writer.write_all(b"]\n")?;
@@ -178,7 +240,7 @@ where
     }
 
     /// Lock all queues in a well-defined order to avoid deadlocks conditions.
-    async fn lock_queues(&self) -> Vec<(K, MutexGuard<'_, VecDeque<I>>)> {
+    async fn lock_queues(&self) -> Vec<(K, MutexGuard<'_, VecDeque<QueueEntry<I>>>)> {
         let mut locks = Vec::new();
         for kind in K::into_enum_iter() {
             let queue_guard = self
@@ -205,6 +267,14 @@ where
     /// Creates a queue for each pair given in `weights`. The second component of each `weight` is
     /// the number of times to return items from one queue before moving on to the next one.
     pub(crate) fn new(weights: Vec<(K, NonZeroUsize)>) -> Self {
+        Self::with_policy(weights, FairnessPolicy::default())
+    }
+
+    /// Creates a new round-robin scheduler using the given fairness policy.
+    ///
+    /// With [`FairnessPolicy::DeficitRoundRobin`], the weights are interpreted as multiples of the
+    /// policy's quantum instead of item counts.
+    pub(crate) fn with_policy(weights: Vec<(K, NonZeroUsize)>, policy: FairnessPolicy) -> Self {
         assert!(!weights.is_empty(), "must provide at least one slot");
 
         let queues = weights
@@ -219,20 +289,38 @@ where
             })
             .collect();
         let active_slot = slots[0];
+        let mut deficits = vec![0; slots.len()];
+        deficits[0] = Self::slot_budget(&policy, &active_slot);
 
         WeightedRoundRobin {
             state: Mutex::new(IterationState {
                 active_slot,
                 active_slot_idx: 0,
+                deficits,
             }),
             slots,
             queues,
             total: Semaphore::new(0),
+            policy,
+        }
+    }
+
+    /// Returns the processing time budget in nanoseconds a slot is granted per round when using
+    /// deficit round-robin.
+    fn slot_budget(policy: &FairnessPolicy, slot: &Slot<K>) -> i128 {
+        match policy {
+            FairnessPolicy::WeightedRoundRobin => 0,
+            FairnessPolicy::DeficitRoundRobin { quantum } => {
+                // Ensure every round makes progress, even with a zero quantum.
+                (quantum.as_nanos() as i128).max(1) * slot.tickets as i128
+            }
         }
     }
 
     /// Pushes an item to a queue identified by key.
     ///
+    /// The item inherits the correlation ID of the calling task, if one is set.
+    ///
     /// ## Panics
     ///
     /// Panics if the queue identified by key `queue` does not exist.
@@ -251,6 +339,15 @@ where
     ///
     /// Asynchronously waits until a queue is non-empty or panics if an internal error occurred.
     pub(crate) async fn pop(&self) -> (I, K) {
+        let (item, key, _, _) = self.pop_traced().await;
+        (item, key)
+    }
+
+    /// Returns the next item from queue, along with the time it spent queued and the correlation
+    /// ID it was pushed under, if any.
+    ///
+    /// Asynchronously waits until a queue is non-empty or panics if an internal error occurred.
+    pub(crate) async fn pop_traced(&self) -> (I, K, Duration, Option<CorrelationId>) {
         // Safe to `expect` here as the only way for acquiring a permit to fail would be if the
         // `self.total` semaphore were closed.
         self.total.acquire().await.expect("should acquire").forget();
@@ -267,22 +364,61 @@ where
 
             let mut current_queue = queue_state.queue.lock().await;
 
-            if inner.active_slot.tickets == 0 || current_queue.is_empty() {
+            let exhausted = match self.policy {
+                FairnessPolicy::WeightedRoundRobin => inner.active_slot.tickets == 0,
+                FairnessPolicy::DeficitRoundRobin { .. } => {
+                    let idx = inner.active_slot_idx;
+                    inner.deficits[idx] <= 0
+                }
+            };
+
+            if exhausted || current_queue.is_empty() {
+                // A queue that ran empty forfeits its remaining deficit, as in classic DRR, while
+                // an overdrawn queue carries its debt into the next round.
+                if current_queue.is_empty() {
+                    let idx = inner.active_slot_idx;
+                    inner.deficits[idx] = 0;
+                }
+
                 // Go to next queue slot if we've exhausted the current queue.
                 inner.active_slot_idx = (inner.active_slot_idx + 1) % self.slots.len();
                 inner.active_slot = self.slots[inner.active_slot_idx];
+                let budget = Self::slot_budget(&self.policy, &inner.active_slot);
+                let idx = inner.active_slot_idx;
+                inner.deficits[idx] += budget;
                 continue;
             }
 
             // We have hit a queue that is not empty. Decrease tickets and pop.
-            inner.active_slot.tickets -= 1;
+            inner.active_slot.tickets = inner.active_slot.tickets.saturating_sub(1);
 
-            let item = current_queue
+            let (item, enqueued_at, correlation_id) = current_queue
                 .pop_front()
                 // We hold the queue's lock and checked `is_empty` earlier.
                 .expect("item disappeared. this should not happen");
             queue_state.dec_count();
-            break (item, inner.active_slot.key);
+            break (
+                item,
+                inner.active_slot.key,
+                enqueued_at.elapsed(),
+                correlation_id,
+            );
+        }
+    }
+
+    /// Charges the processing time of an item popped from `queue` against its deficit.
+    ///
+    /// Has no effect unless the scheduler uses [`FairnessPolicy::DeficitRoundRobin`] and `queue`
+    /// is still the active queue.
+    pub(crate) async fn charge(&self, queue: K, cost: Duration) {
+        if let FairnessPolicy::WeightedRoundRobin = self.policy {
+            return;
+        }
+
+        let mut inner = self.state.lock().await;
+        if inner.active_slot.key == queue {
+            let idx = inner.active_slot_idx;
+            inner.deficits[idx] -= cost.as_nanos() as i128;
         }
     }
 
@@ -318,6 +454,17 @@ where
             .map(|(key, queue)| (*key, queue.event_count()))
             .collect()
     }
+
+    /// Returns the age of the oldest event in each of the queues.
+    ///
+    /// Empty queues, as well as queues that are locked at the time of the call, are reported with
+    /// an age of zero.
+    pub(crate) fn oldest_event_ages(&self) -> HashMap<K, Duration> {
+        self.queues
+            .iter()
+            .map(|(key, queue)| (*key, queue.oldest_event_age().unwrap_or_default()))
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -366,4 +513,53 @@ mod tests {
         assert_eq!(('f', QueueKind::Two), scheduler.pop().await);
         assert_eq!(('c', QueueKind::One), scheduler.pop().await);
     }
+
+    #[tokio::test]
+    async fn should_carry_correlation_id_of_pushing_task() {
+        let scheduler = WeightedRoundRobin::<char, QueueKind>::new(weights());
+        let id = CorrelationId::fresh();
+        id.scope(scheduler.push('a', QueueKind::One)).await;
+        scheduler.push('b', QueueKind::One).await;
+
+        let (item, _, _, correlation_id) = scheduler.pop_traced().await;
+        assert_eq!(('a', Some(id)), (item, correlation_id));
+        let (item, _, _, correlation_id) = scheduler.pop_traced().await;
+        assert_eq!(('b', None), (item, correlation_id));
+    }
+
+    #[tokio::test]
+    async fn should_charge_processing_time_with_deficit_round_robin() {
+        let policy = FairnessPolicy::DeficitRoundRobin {
+            quantum: Duration::from_millis(10),
+        };
+        let weights = vec![
+            (QueueKind::One, NonZeroUsize::new(1).unwrap()),
+            (QueueKind::Two, NonZeroUsize::new(1).unwrap()),
+        ];
+        let scheduler = WeightedRoundRobin::<char, QueueKind>::with_policy(weights, policy);
+        for item in &['a', 'b', 'c'] {
+            scheduler.push(*item, QueueKind::One).await;
+        }
+        for item in &['d', 'e', 'f'] {
+            scheduler.push(*item, QueueKind::Two).await;
+        }
+
+        // An expensive item overdraws the first queue's deficit...
+        assert_eq!(('a', QueueKind::One), scheduler.pop().await);
+        scheduler
+            .charge(QueueKind::One, Duration::from_millis(30))
+            .await;
+
+        // ...so cheap items from the second queue are processed until it runs empty...
+        for expected in &['d', 'e', 'f'] {
+            assert_eq!((*expected, QueueKind::Two), scheduler.pop().await);
+            scheduler
+                .charge(QueueKind::Two, Duration::from_millis(1))
+                .await;
+        }
+
+        // ...after which the first queue pays off its debt and is served again.
+        assert_eq!(('b', QueueKind::One), scheduler.pop().await);
+        assert_eq!(('c', QueueKind::One), scheduler.pop().await);
+    }
 }
@@ -320,6 +320,23 @@ pub fn blake2b<T: AsRef<[u8]>>(input: T) -> [u8; BLAKE2B_DIGEST_LENGTH] {
     ret
 }
 
+/// Emits an event with the given `topic` and `data` on behalf of the currently executing stored
+/// contract.
+///
+/// Events are indexed by the hash of the emitting contract, and can be queried from a node's API
+/// once the block containing the deploy has been executed.
+pub fn emit_event<T: AsRef<[u8]>>(topic: &str, data: T) {
+    let result = unsafe {
+        ext_ffi::casper_emit_event(
+            topic.as_ptr(),
+            topic.len(),
+            data.as_ref().as_ptr(),
+            data.as_ref().len(),
+        )
+    };
+    api_error::result_from(result).unwrap_or_revert()
+}
+
 fn read_host_buffer_into(dest: &mut [u8]) -> Result<usize, ApiError> {
     let mut bytes_written = MaybeUninit::uninit();
     let ret = unsafe {
@@ -109,6 +109,31 @@ pub fn get_purse_balance(purse: URef) -> Option<U512> {
     Some(value)
 }
 
+/// Returns the balance in motes of the given purse along with the serialized Merkle proof of that
+/// balance.
+///
+/// The balance and proof are taken from global state as it was before the current deploy started
+/// executing.
+#[doc(hidden)]
+pub fn get_purse_balance_with_proof(purse: URef) -> Option<(U512, Vec<u8>)> {
+    let (purse_ptr, purse_size, _bytes) = contract_api::to_ptr(purse);
+
+    let value_size = {
+        let mut output_size = MaybeUninit::uninit();
+        let ret = unsafe {
+            ext_ffi::casper_get_balance_with_proof(purse_ptr, purse_size, output_size.as_mut_ptr())
+        };
+        match api_error::result_from(ret) {
+            Ok(_) => unsafe { output_size.assume_init() },
+            Err(ApiError::InvalidPurse) => return None,
+            Err(error) => runtime::revert(error),
+        }
+    };
+    let value_bytes = runtime::read_host_buffer(value_size).unwrap_or_revert();
+    let value: (U512, Vec<u8>) = bytesrepr::deserialize(value_bytes).unwrap_or_revert();
+    Some(value)
+}
+
 /// Returns the balance in motes of a purse.
 pub fn get_balance() -> Option<U512> {
     get_purse_balance(account::get_main_purse())
@@ -427,6 +427,25 @@ extern "C" {
         purse_size: usize,
         result_size: *mut usize,
     ) -> i32;
+    /// This function gets the balance of the specified purse together with a Merkle proof of
+    /// that balance against the pre-state root hash of the current execution. It causes a `Trap`
+    /// if the bytes in wasm memory from `purse_ptr` to `purse_ptr + purse_size` cannot be
+    /// de-serialized as a `URef`. The size of the result in bytes is written to `result_size`. The
+    /// result is copied to the host buffer and thus can be obtained by any function which copies
+    /// the buffer into wasm memory (e.g. `get_read`). The result bytes are serialized from type
+    /// `(U512, Vec<u8>)`, where the second element is the serialized `TrieMerkleProof`.
+    ///
+    /// # Arguments
+    ///
+    /// * `purse_ptr` - pointer in wasm memory to the bytes representing the `URef` of the purse to
+    ///   get the balance of
+    /// * `purse_size` - size of the `URef` (in bytes)
+    /// * `result_size` - pointer in wasm memory to write the size of the result to
+    pub fn casper_get_balance_with_proof(
+        purse_ptr: *const u8,
+        purse_size: usize,
+        result_size: *mut usize,
+    ) -> i32;
     /// This function writes bytes representing the current phase of the deploy
     /// execution to the specified pointer. The size of the result is always one
     /// byte, it is up to the caller to ensure one byte of memory is allocated at
@@ -440,7 +459,15 @@ extern "C" {
     ///
     /// * `dest_ptr` - pointer to position in wasm memory to write the result
     pub fn casper_get_phase(dest_ptr: *mut u8);
+    /// Writes the hash of the system contract with the given index to `dest_ptr`. The hash is
+    /// looked up in the system contract registry stored in global state under
+    /// `Key::SystemContractRegistry`.
+    ///
+    /// # Arguments
     ///
+    /// * `system_contract_index` - index of the system contract, as per `SystemContractType`
+    /// * `dest_ptr` - pointer to position in wasm memory to write the contract hash
+    /// * `dest_size` - size of the allocated memory at `dest_ptr`
     pub fn casper_get_system_contract(
         system_contract_index: u32,
         dest_ptr: *mut u8,
@@ -700,6 +727,22 @@ extern "C" {
         out_ptr: *mut u8,
         out_size: usize,
     ) -> i32;
+    /// Emits an event with the given topic and data on behalf of the currently executing stored
+    /// contract. The event is recorded in the execution results of the deploy, unless the deploy's
+    /// session fails. Causes a `Trap` if called from outside a stored contract.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic_ptr` - pointer to the UTF-8 bytes of the event's topic
+    /// * `topic_size` - length of the topic in bytes
+    /// * `data_ptr` - pointer to the event's data
+    /// * `data_size` - length of the data in bytes
+    pub fn casper_emit_event(
+        topic_ptr: *const u8,
+        topic_size: usize,
+        data_ptr: *const u8,
+        data_size: usize,
+    ) -> i32;
     /// Prints data directly to standard output on the host.
     ///
     /// # Arguments
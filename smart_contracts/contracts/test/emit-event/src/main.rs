@@ -0,0 +1,65 @@
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use casper_contract::contract_api::{runtime, storage};
+use casper_types::{
+    contracts::{EntryPoint, EntryPoints, Parameter},
+    CLType, CLTyped, EntryPointAccess, EntryPointType,
+};
+
+const ENTRY_POINT_EMIT: &str = "emit";
+const ENTRY_POINT_EMIT_IN_SESSION: &str = "emit_in_session";
+const HASH_KEY_NAME: &str = "emit_event_hash";
+const ARG_TOPIC: &str = "topic";
+const ARG_DATA: &str = "data";
+
+fn emit_event_from_args() {
+    let topic: String = runtime::get_named_arg(ARG_TOPIC);
+    let data: Vec<u8> = runtime::get_named_arg(ARG_DATA);
+    runtime::emit_event(&topic, data);
+}
+
+#[no_mangle]
+pub extern "C" fn emit() {
+    emit_event_from_args()
+}
+
+#[no_mangle]
+pub extern "C" fn emit_in_session() {
+    emit_event_from_args()
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let entry_points = {
+        let mut entry_points = EntryPoints::new();
+        for (name, entry_point_type) in &[
+            (ENTRY_POINT_EMIT, EntryPointType::Contract),
+            (ENTRY_POINT_EMIT_IN_SESSION, EntryPointType::Session),
+        ] {
+            let entry_point = EntryPoint::new(
+                name.to_string(),
+                vec![
+                    Parameter::new(ARG_TOPIC, CLType::String),
+                    Parameter::new(ARG_DATA, <Vec<u8>>::cl_type()),
+                ],
+                CLType::Unit,
+                EntryPointAccess::Public,
+                *entry_point_type,
+            );
+            entry_points.add_entry_point(entry_point);
+        }
+        entry_points
+    };
+
+    let (contract_hash, _contract_version) = storage::new_contract(entry_points, None, None, None);
+    runtime::put_key(HASH_KEY_NAME, contract_hash.into());
+}
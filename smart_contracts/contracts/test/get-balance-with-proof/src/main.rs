@@ -0,0 +1,29 @@
+#![no_std]
+#![no_main]
+
+use casper_contract::{
+    contract_api::{account, runtime, storage, system},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{AccessRights, ApiError, URef};
+
+const BALANCE_RESULT: &str = "balance_result";
+const PROOF_RESULT: &str = "proof_result";
+
+#[repr(u16)]
+enum Error {
+    UnknownPurseHasBalance = 0,
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let unknown_purse = URef::new([255; 32], AccessRights::READ_ADD_WRITE);
+    if system::get_purse_balance_with_proof(unknown_purse).is_some() {
+        runtime::revert(ApiError::User(Error::UnknownPurseHasBalance as u16));
+    }
+
+    let (balance, proof) =
+        system::get_purse_balance_with_proof(account::get_main_purse()).unwrap_or_revert();
+    runtime::put_key(BALANCE_RESULT, storage::new_uref(balance).into());
+    runtime::put_key(PROOF_RESULT, storage::new_uref(proof).into());
+}
@@ -257,6 +257,9 @@ pub type ContractVersions = BTreeMap<ContractVersionKey, ContractHash>;
 pub type DisabledVersions = BTreeSet<ContractVersionKey>;
 
 /// Collection of named groups.
+///
+/// Like all collections feeding into serialized and hashed global state values, this is ordered,
+/// so that its serialized form does not depend on insertion order.
 pub type Groups = BTreeMap<Group, BTreeSet<URef>>;
 
 /// A newtype wrapping a `HashAddr` which is the raw bytes of
@@ -828,6 +831,8 @@ impl FromBytes for ContractPackage {
 }
 
 /// Type alias for a container used inside [`EntryPoints`].
+///
+/// Ordered, so that the serialized form of [`EntryPoints`] does not depend on insertion order.
 pub type EntryPointsMap = BTreeMap<String, EntryPoint>;
 
 /// Collection of named entry points
@@ -901,7 +906,9 @@ impl From<Vec<EntryPoint>> for EntryPoints {
     }
 }
 
-/// Collection of named keys
+/// Collection of named keys.
+///
+/// Ordered, so that its serialized form does not depend on insertion order.
 pub type NamedKeys = BTreeMap<String, Key>;
 
 /// Methods and type signatures supported by a contract.
@@ -1604,3 +1611,116 @@ mod tests {
         assert_eq!(contract_hash, decoded)
     }
 }
+
+#[cfg(test)]
+mod prop_tests {
+    use alloc::vec::Vec;
+
+    use proptest::{prelude::*, sample::Index};
+
+    use crate::{
+        bytesrepr::ToBytes,
+        contracts::{Contract, ContractPackage, EntryPoints, Groups, NamedKeys},
+        gens,
+    };
+
+    /// Returns the elements of `items` reordered by the given indices, so that rebuilding a map
+    /// from them inserts its entries in an arbitrary order.
+    fn permute<T>(mut items: Vec<T>, indices: &[Index]) -> Vec<T> {
+        let mut permuted = Vec::with_capacity(items.len());
+        for index in indices {
+            if items.is_empty() {
+                break;
+            }
+            permuted.push(items.remove(index.index(items.len())));
+        }
+        permuted.extend(items);
+        permuted
+    }
+
+    fn indices() -> impl Strategy<Value = Vec<Index>> {
+        proptest::collection::vec(any::<Index>(), 0..32)
+    }
+
+    proptest! {
+        #[test]
+        fn named_keys_serialization_is_insertion_order_insensitive(
+            named_keys in gens::named_keys_arb(20),
+            indices in indices(),
+        ) {
+            let entries = permute(named_keys.clone().into_iter().collect(), &indices);
+            let reinserted: NamedKeys = entries.into_iter().collect();
+            prop_assert_eq!(named_keys.to_bytes().unwrap(), reinserted.to_bytes().unwrap());
+        }
+
+        #[test]
+        fn groups_serialization_is_insertion_order_insensitive(
+            groups in gens::groups_arb(),
+            indices in indices(),
+        ) {
+            let entries = permute(groups.clone().into_iter().collect(), &indices);
+            let reinserted: Groups = entries
+                .into_iter()
+                .map(|(group, urefs)| {
+                    let urefs = permute(urefs.into_iter().collect(), &indices);
+                    (group, urefs.into_iter().collect())
+                })
+                .collect();
+            prop_assert_eq!(groups.to_bytes().unwrap(), reinserted.to_bytes().unwrap());
+        }
+
+        #[test]
+        fn entry_points_serialization_is_insertion_order_insensitive(
+            entry_points in gens::entry_points_arb(),
+            indices in indices(),
+        ) {
+            let entries = permute(entry_points.clone().take_entry_points(), &indices);
+            let reinserted = EntryPoints::from(entries);
+            prop_assert_eq!(entry_points.to_bytes().unwrap(), reinserted.to_bytes().unwrap());
+        }
+
+        #[test]
+        fn contract_serialization_is_insertion_order_insensitive(
+            contract in gens::contract_arb(),
+            indices in indices(),
+        ) {
+            let named_keys = contract.named_keys().clone().into_iter().collect();
+            let named_keys = permute(named_keys, &indices);
+            let entry_points =
+                permute(contract.entry_points().clone().take_entry_points(), &indices);
+            let reinserted = Contract::new(
+                contract.contract_package_hash(),
+                contract.contract_wasm_hash(),
+                named_keys.into_iter().collect(),
+                EntryPoints::from(entry_points),
+                contract.protocol_version(),
+            );
+            prop_assert_eq!(contract.to_bytes().unwrap(), reinserted.to_bytes().unwrap());
+        }
+
+        #[test]
+        fn contract_package_serialization_is_insertion_order_insensitive(
+            contract_package in gens::contract_package_arb(),
+            indices in indices(),
+        ) {
+            let versions = contract_package.versions().clone().into_iter().collect();
+            let versions = permute(versions, &indices);
+            let disabled_versions =
+                contract_package.disabled_versions().clone().into_iter().collect();
+            let disabled_versions = permute(disabled_versions, &indices);
+            let groups = contract_package.groups().clone().into_iter().collect();
+            let groups = permute(groups, &indices);
+            let reinserted = ContractPackage::new(
+                contract_package.access_key(),
+                versions.into_iter().collect(),
+                disabled_versions.into_iter().collect(),
+                groups.into_iter().collect(),
+                contract_package.get_lock_status(),
+            );
+            prop_assert_eq!(
+                contract_package.to_bytes().unwrap(),
+                reinserted.to_bytes().unwrap()
+            );
+        }
+    }
+}
@@ -323,6 +323,49 @@ impl FromBytes for ExecutionEffect {
     }
 }
 
+/// A single operation performed while executing a deploy, in the journal of all its operations.
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "std", derive(JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct JournalEntry {
+    /// The formatted string of the `Key`.
+    pub key: String,
+    /// The type of operation.
+    pub kind: OpKind,
+    /// The type name of the value under the key after the operation.
+    pub value_type: String,
+}
+
+impl ToBytes for JournalEntry {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        buffer.extend(self.key.to_bytes()?);
+        buffer.extend(self.kind.to_bytes()?);
+        buffer.extend(self.value_type.to_bytes()?);
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.key.serialized_length()
+            + self.kind.serialized_length()
+            + self.value_type.serialized_length()
+    }
+}
+
+impl FromBytes for JournalEntry {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (key, remainder) = String::from_bytes(bytes)?;
+        let (kind, remainder) = OpKind::from_bytes(remainder)?;
+        let (value_type, remainder) = String::from_bytes(remainder)?;
+        let journal_entry = JournalEntry {
+            key,
+            kind,
+            value_type,
+        };
+        Ok((journal_entry, remainder))
+    }
+}
+
 /// An operation performed while executing a deploy.
 #[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
 #[cfg_attr(feature = "std", derive(JsonSchema))]
@@ -683,4 +726,15 @@ mod tests {
         let execution_result: ExecutionResult = rng.gen();
         bytesrepr::test_serialization_roundtrip(&execution_result);
     }
+
+    #[test]
+    fn bytesrepr_test_journal_entry() {
+        let journal_entry = JournalEntry {
+            key: "uref-2c4a11c062a8a337bfc97e27fd66291caeb2c65865dcb5d3ef3759c4c97efecb-007"
+                .to_string(),
+            kind: OpKind::Add,
+            value_type: "U512".to_string(),
+        };
+        bytesrepr::test_serialization_roundtrip(&journal_entry);
+    }
 }
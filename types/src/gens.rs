@@ -88,6 +88,7 @@ pub fn key_arb() -> impl Strategy<Value = Key> {
         uref_arb().prop_map(|uref| Key::Balance(uref.addr())),
         account_hash_arb().prop_map(Key::Bid),
         account_hash_arb().prop_map(Key::Withdraw),
+        Just(Key::SystemContractRegistry),
     ]
 }
 
@@ -33,6 +33,7 @@ const ERA_INFO_PREFIX: &str = "era-";
 const BALANCE_PREFIX: &str = "balance-";
 const BID_PREFIX: &str = "bid-";
 const WITHDRAW_PREFIX: &str = "withdraw-";
+const SYSTEM_CONTRACT_REGISTRY_STRING: &str = "system-contract-registry";
 
 /// The number of bytes in a Blake2b hash
 pub const BLAKE2B_DIGEST_LENGTH: usize = 32;
@@ -76,6 +77,7 @@ pub enum KeyTag {
     Balance = 6,
     Bid = 7,
     Withdraw = 8,
+    SystemContractRegistry = 9,
 }
 
 /// The type under which data (e.g. [`CLValue`](crate::CLValue)s, smart contracts, user accounts)
@@ -102,6 +104,8 @@ pub enum Key {
     Bid(AccountHash),
     /// A `Key` under which we store unbond information.
     Withdraw(AccountHash),
+    /// The `Key` under which we store the registry of system contract hashes, by name.
+    SystemContractRegistry,
 }
 
 #[derive(Debug)]
@@ -169,6 +173,7 @@ impl Key {
             Key::Balance(_) => String::from("Key::Balance"),
             Key::Bid(_) => String::from("Key::Bid"),
             Key::Withdraw(_) => String::from("Key::Unbond"),
+            Key::SystemContractRegistry => String::from("Key::SystemContractRegistry"),
         }
     }
 
@@ -213,6 +218,7 @@ impl Key {
             Key::Withdraw(account_hash) => {
                 format!("{}{}", WITHDRAW_PREFIX, base16::encode_lower(&account_hash))
             }
+            Key::SystemContractRegistry => String::from(SYSTEM_CONTRACT_REGISTRY_STRING),
         }
     }
 
@@ -281,6 +287,10 @@ impl Key {
             return Ok(Key::Withdraw(AccountHash::new(account_hash)));
         }
 
+        if input == SYSTEM_CONTRACT_REGISTRY_STRING {
+            return Ok(Key::SystemContractRegistry);
+        }
+
         Err(FromStrError::UnknownPrefix)
     }
 
@@ -339,6 +349,7 @@ impl Display for Key {
             Key::Balance(uref_addr) => write!(f, "Key::Balance({})", HexFmt(uref_addr)),
             Key::Bid(account_hash) => write!(f, "Key::Bid({})", account_hash),
             Key::Withdraw(account_hash) => write!(f, "Key::Withdraw({})", account_hash),
+            Key::SystemContractRegistry => write!(f, "Key::SystemContractRegistry"),
         }
     }
 }
@@ -361,6 +372,7 @@ impl Tagged<KeyTag> for Key {
             Key::Balance(_) => KeyTag::Balance,
             Key::Bid(_) => KeyTag::Bid,
             Key::Withdraw(_) => KeyTag::Withdraw,
+            Key::SystemContractRegistry => KeyTag::SystemContractRegistry,
         }
     }
 }
@@ -440,6 +452,7 @@ impl ToBytes for Key {
             Key::Withdraw(account_hash) => {
                 result.append(&mut account_hash.to_bytes()?);
             }
+            Key::SystemContractRegistry => {}
         }
         Ok(result)
     }
@@ -457,6 +470,7 @@ impl ToBytes for Key {
             Key::Balance(_) => KEY_BALANCE_SERIALIZED_LENGTH,
             Key::Bid(_) => KEY_BID_SERIALIZED_LENGTH,
             Key::Withdraw(_) => KEY_WITHDRAW_SERIALIZED_LENGTH,
+            Key::SystemContractRegistry => KEY_ID_SERIALIZED_LENGTH,
         }
     }
 }
@@ -501,6 +515,9 @@ impl FromBytes for Key {
                 let (account_hash, rem) = AccountHash::from_bytes(remainder)?;
                 Ok((Key::Withdraw(account_hash), rem))
             }
+            tag if tag == KeyTag::SystemContractRegistry as u8 => {
+                Ok((Key::SystemContractRegistry, remainder))
+            }
             _ => Err(Error::Formatting),
         }
     }
@@ -508,7 +525,7 @@ impl FromBytes for Key {
 
 impl Distribution<Key> for Standard {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Key {
-        match rng.gen_range(0..9) {
+        match rng.gen_range(0..10) {
             0 => Key::Account(rng.gen()),
             1 => Key::Hash(rng.gen()),
             2 => Key::URef(rng.gen()),
@@ -518,6 +535,7 @@ impl Distribution<Key> for Standard {
             6 => Key::Balance(rng.gen()),
             7 => Key::Bid(rng.gen()),
             8 => Key::Withdraw(rng.gen()),
+            9 => Key::SystemContractRegistry,
             _ => unreachable!(),
         }
     }
@@ -537,6 +555,7 @@ mod serde_helpers {
         Balance(String),
         Bid(String),
         Withdraw(String),
+        SystemContractRegistry(String),
     }
 
     impl From<&Key> for HumanReadable {
@@ -552,6 +571,9 @@ mod serde_helpers {
                 Key::Balance(_) => HumanReadable::Balance(formatted_string),
                 Key::Bid(_) => HumanReadable::Bid(formatted_string),
                 Key::Withdraw(_) => HumanReadable::Withdraw(formatted_string),
+                Key::SystemContractRegistry => {
+                    HumanReadable::SystemContractRegistry(formatted_string)
+                }
             }
         }
     }
@@ -569,7 +591,8 @@ mod serde_helpers {
                 | HumanReadable::EraInfo(formatted_string)
                 | HumanReadable::Balance(formatted_string)
                 | HumanReadable::Bid(formatted_string)
-                | HumanReadable::Withdraw(formatted_string) => {
+                | HumanReadable::Withdraw(formatted_string)
+                | HumanReadable::SystemContractRegistry(formatted_string) => {
                     Key::from_formatted_str(&formatted_string)
                 }
             }
@@ -587,6 +610,7 @@ mod serde_helpers {
         Balance(&'a URefAddr),
         Bid(&'a AccountHash),
         Withdraw(&'a AccountHash),
+        SystemContractRegistry,
     }
 
     impl<'a> From<&'a Key> for BinarySerHelper<'a> {
@@ -601,6 +625,7 @@ mod serde_helpers {
                 Key::Balance(uref_addr) => BinarySerHelper::Balance(uref_addr),
                 Key::Bid(account_hash) => BinarySerHelper::Bid(account_hash),
                 Key::Withdraw(account_hash) => BinarySerHelper::Withdraw(account_hash),
+                Key::SystemContractRegistry => BinarySerHelper::SystemContractRegistry,
             }
         }
     }
@@ -616,6 +641,7 @@ mod serde_helpers {
         Balance(URefAddr),
         Bid(AccountHash),
         Withdraw(AccountHash),
+        SystemContractRegistry,
     }
 
     impl From<BinaryDeserHelper> for Key {
@@ -630,6 +656,7 @@ mod serde_helpers {
                 BinaryDeserHelper::Balance(uref_addr) => Key::Balance(uref_addr),
                 BinaryDeserHelper::Bid(account_hash) => Key::Bid(account_hash),
                 BinaryDeserHelper::Withdraw(account_hash) => Key::Withdraw(account_hash),
+                BinaryDeserHelper::SystemContractRegistry => Key::SystemContractRegistry,
             }
         }
     }
@@ -679,7 +706,7 @@ mod tests {
     const BALANCE_KEY: Key = Key::Balance([42; 32]);
     const BID_KEY: Key = Key::Bid(AccountHash::new([42; 32]));
     const WITHDRAW_KEY: Key = Key::Withdraw(AccountHash::new([42; 32]));
-    const KEYS: [Key; 9] = [
+    const KEYS: [Key; 10] = [
         ACCOUNT_KEY,
         HASH_KEY,
         UREF_KEY,
@@ -689,6 +716,7 @@ mod tests {
         BALANCE_KEY,
         BID_KEY,
         WITHDRAW_KEY,
+        Key::SystemContractRegistry,
     ];
     const HEX_STRING: &str = "2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a";
 
@@ -772,6 +800,10 @@ mod tests {
             format!("{}", WITHDRAW_KEY),
             format!("Key::Withdraw({})", HEX_STRING)
         );
+        assert_eq!(
+            format!("{}", Key::SystemContractRegistry),
+            "Key::SystemContractRegistry".to_string()
+        );
     }
 
     #[test]
@@ -917,6 +949,7 @@ mod tests {
             format!(r#"{{"Balance":"balance-{}"}}"#, HEX_STRING),
             format!(r#"{{"Bid":"bid-{}"}}"#, HEX_STRING),
             format!(r#"{{"Withdraw":"withdraw-{}"}}"#, HEX_STRING),
+            r#"{"SystemContractRegistry":"system-contract-registry"}"#.to_string(),
         ];
 
         assert_eq!(
@@ -77,6 +77,8 @@ pub const METHOD_DISTRIBUTE: &str = "distribute";
 pub const METHOD_READ_ERA_ID: &str = "read_era_id";
 /// Named constant for method `activate_bid`.
 pub const METHOD_ACTIVATE_BID: &str = "activate_bid";
+/// Named constant for method `read_bid`.
+pub const METHOD_READ_BID: &str = "read_bid";
 
 /// Storage for `EraId`.
 pub const ERA_ID_KEY: &str = "era_id";
@@ -607,6 +607,18 @@ pub trait Auction:
         detail::get_era_id(self)
     }
 
+    /// Reads the bid of a single validator, including its delegators.
+    ///
+    /// Unlike reading the full bids map, the cost of this call doesn't grow with the number of
+    /// validators.
+    fn read_validator_bid(
+        &mut self,
+        validator_public_key: PublicKey,
+    ) -> Result<Option<Bid>, Error> {
+        let account_hash = AccountHash::from_public_key(&validator_public_key, |x| self.blake2b(x));
+        self.read_bid(&account_hash)
+    }
+
     /// Activates a given validator's bid.  To be used when a validator has been marked as inactive
     /// by consensus (aka "evicted").
     fn activate_bid(&mut self, validator_public_key: PublicKey) -> Result<(), Error> {
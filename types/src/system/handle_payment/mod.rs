@@ -6,15 +6,14 @@ mod runtime_provider;
 
 use core::marker::Sized;
 
+use num_rational::Ratio;
+
 use crate::{account::AccountHash, AccessRights, URef, U512};
 
 pub use crate::system::handle_payment::{
     constants::*, error::Error, mint_provider::MintProvider, runtime_provider::RuntimeProvider,
 };
 
-// A simplified representation of a refund percentage which is currently hardcoded to 0%.
-const REFUND_PERCENTAGE: U512 = U512::zero();
-
 /// Handle payment functionality implementation.
 pub trait HandlePayment: MintProvider + RuntimeProvider + Sized {
     /// Get payment purse.
@@ -49,6 +48,21 @@ pub trait HandlePayment: MintProvider + RuntimeProvider + Sized {
     }
 }
 
+/// Returns the part of the `unspent` payment amount which is refunded to the payer given the
+/// configured `refund_ratio`.
+///
+/// The result is rounded down, so any fractional mote is moved to the rewards purse rather than
+/// refunded.  Ratios greater than one are rejected.
+pub fn calculate_refund(unspent: U512, refund_ratio: Ratio<u64>) -> Result<U512, Error> {
+    if refund_ratio.numer() > refund_ratio.denom() {
+        return Err(Error::ArithmeticOverflow);
+    }
+    unspent
+        .checked_mul(U512::from(*refund_ratio.numer()))
+        .map(|product| product / U512::from(*refund_ratio.denom()))
+        .ok_or(Error::ArithmeticOverflow)
+}
+
 mod internal {
     use crate::{
         account::AccountHash,
@@ -56,7 +70,7 @@ mod internal {
         Key, Phase, PublicKey, URef, U512,
     };
 
-    use super::{PAYMENT_PURSE_KEY, REFUND_PERCENTAGE, REFUND_PURSE_KEY};
+    use super::{calculate_refund, PAYMENT_PURSE_KEY, REFUND_PURSE_KEY};
 
     /// Returns the purse for accepting payment for transactions.
     pub fn get_payment_purse<R: RuntimeProvider>(runtime_provider: &R) -> Result<URef, Error> {
@@ -96,6 +110,10 @@ mod internal {
     /// refund purse, depending on how much was spent on the computation. This function maintains
     /// the invariant that the balance of the payment purse is zero at the beginning and end of each
     /// deploy and that the refund purse is unset at the beginning and end of each deploy.
+    ///
+    /// Because of this invariant, the payment purse can be shared by all deploys: the net effect of
+    /// a deploy on its balance is nil, so the engine reduces the balance write to a read and
+    /// deploys executed against the same state don't conflict on it.
     pub fn finalize_payment<P: MintProvider + RuntimeProvider>(
         provider: &mut P,
         amount_spent: U512,
@@ -119,14 +137,10 @@ mod internal {
 
         // User's part
         let refund_amount = {
-            let refund_amount_raw = total
+            let unspent = total
                 .checked_sub(amount_spent)
                 .ok_or(Error::ArithmeticOverflow)?;
-            // Currently refund percentage is zero and we expect no overflows.
-            // However, we put this check should the constant change in the future.
-            refund_amount_raw
-                .checked_mul(REFUND_PERCENTAGE)
-                .ok_or(Error::ArithmeticOverflow)?
+            calculate_refund(unspent, provider.refund_ratio())?
         };
 
         // Validator reward
@@ -179,3 +193,57 @@ mod internal {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_calculate_refund_rounding_down() {
+        let unspent = U512::from(10);
+        assert_eq!(
+            calculate_refund(unspent, Ratio::new(0, 1)),
+            Ok(U512::zero())
+        );
+        assert_eq!(calculate_refund(unspent, Ratio::new(1, 1)), Ok(unspent));
+        assert_eq!(
+            calculate_refund(unspent, Ratio::new(1, 2)),
+            Ok(U512::from(5))
+        );
+        // 10 * 1/3 = 3.33.., the fractional mote stays with the rewards purse.
+        assert_eq!(
+            calculate_refund(unspent, Ratio::new(1, 3)),
+            Ok(U512::from(3))
+        );
+        // 10 * 2/3 = 6.66.., still rounded down.
+        assert_eq!(
+            calculate_refund(unspent, Ratio::new(2, 3)),
+            Ok(U512::from(6))
+        );
+        assert_eq!(
+            calculate_refund(U512::one(), Ratio::new(99, 100)),
+            Ok(U512::zero())
+        );
+    }
+
+    #[test]
+    fn should_reject_refund_ratio_above_one() {
+        assert_eq!(
+            calculate_refund(U512::from(10), Ratio::new(3, 2)),
+            Err(Error::ArithmeticOverflow)
+        );
+    }
+
+    #[test]
+    fn should_not_overflow_on_max_unspent() {
+        assert_eq!(
+            calculate_refund(U512::MAX, Ratio::new(2, 3)),
+            Err(Error::ArithmeticOverflow)
+        );
+        let large = U512::MAX / U512::from(u64::MAX);
+        assert_eq!(
+            calculate_refund(large, Ratio::new(u64::MAX - 1, u64::MAX)),
+            Ok(large * U512::from(u64::MAX - 1) / U512::from(u64::MAX))
+        );
+    }
+}
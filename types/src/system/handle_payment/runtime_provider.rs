@@ -1,3 +1,5 @@
+use num_rational::Ratio;
+
 use crate::{account::AccountHash, system::handle_payment::Error, BlockTime, Key, Phase};
 
 /// Provider of runtime host functionality.
@@ -19,4 +21,7 @@ pub trait RuntimeProvider {
 
     /// Get caller.
     fn get_caller(&self) -> AccountHash;
+
+    /// Get the fraction of the unspent payment amount which is refunded to the payer.
+    fn refund_ratio(&self) -> Ratio<u64>;
 }
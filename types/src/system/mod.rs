@@ -5,6 +5,7 @@ pub mod mint;
 pub mod standard_payment;
 
 pub use error::Error;
+pub use system_contract_registry::SystemContractRegistry;
 pub use system_contract_type::{
     SystemContractType, AUCTION, HANDLE_PAYMENT, MINT, STANDARD_PAYMENT,
 };
@@ -49,6 +50,18 @@ mod error {
     }
 }
 
+mod system_contract_registry {
+    //! Home of the system contract registry.
+
+    use alloc::{collections::BTreeMap, string::String};
+
+    use crate::ContractHash;
+
+    /// The hashes of the system contracts, by name, as stored in global state under
+    /// [`Key::SystemContractRegistry`](crate::Key::SystemContractRegistry).
+    pub type SystemContractRegistry = BTreeMap<String, ContractHash>;
+}
+
 mod system_contract_type {
     //! Home of system contract type enum.
 